@@ -0,0 +1,275 @@
+//! Criterion benchmarks for the scorers, multi-ISA windowing, extension
+//! detection and format detection.
+//!
+//! Inputs are generated at setup from a seeded PRNG and real instruction
+//! templates, so every run measures the same bytes and nothing large is
+//! committed.
+//!
+//! # Running
+//!
+//! ```text
+//! cargo bench --bench classifier                    # everything
+//! cargo bench --bench classifier -- score_all       # one group (regex filter)
+//! ```
+//!
+//! # Comparing against a baseline
+//!
+//! ```text
+//! git checkout main
+//! cargo bench --bench classifier -- --save-baseline main
+//! git checkout my-branch
+//! cargo bench --bench classifier -- --baseline main
+//! ```
+//!
+//! Criterion prints the change per benchmark and writes HTML reports under
+//! `target/criterion/`. The test suite carries a coarse wall-clock guard
+//! (`test_classification_time_budget`) for environments that never run
+//! these.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use isa_classifier::{detect_multi_isa, extensions, formats, heuristics};
+use isa_classifier::{ClassifierOptions, Endianness, Isa};
+
+/// SplitMix64: tiny, seedable and good enough for picking templates.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next() % u64::from(n)) as u32
+    }
+}
+
+/// ISAs the per-ISA benchmarks run on.
+const ISAS: [(&str, Isa); 3] = [
+    ("x86_64", Isa::X86_64),
+    ("aarch64", Isa::AArch64),
+    ("riscv64", Isa::RiscV64),
+];
+
+const KIB: usize = 1024;
+const MIB: usize = 1024 * KIB;
+
+/// Deterministic synthetic code for `isa`: functions with a prologue, a
+/// random body drawn from common instructions, and an epilogue.
+fn synthetic_code(isa: Isa, len: usize, seed: u64) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let mut out = Vec::with_capacity(len + 64);
+    while out.len() < len {
+        match isa {
+            Isa::X86_64 => x86_64_function(&mut rng, &mut out),
+            Isa::AArch64 => aarch64_function(&mut rng, &mut out),
+            Isa::RiscV64 => riscv64_function(&mut rng, &mut out),
+            _ => unreachable!("no templates for {isa}"),
+        }
+    }
+    out.truncate(len);
+    out
+}
+
+fn x86_64_function(rng: &mut Rng, out: &mut Vec<u8>) {
+    // push rbp; mov rbp, rsp; sub rsp, imm8
+    out.extend_from_slice(&[0x55, 0x48, 0x89, 0xE5, 0x48, 0x83, 0xEC]);
+    out.push((rng.below(8) as u8 + 1) * 8);
+    for _ in 0..4 + rng.below(12) {
+        let disp = 0x100 - (rng.below(15) as u16 + 1) * 8;
+        let disp = disp as u8;
+        match rng.below(8) {
+            // mov r64, imm32
+            0 => {
+                out.extend_from_slice(&[0x48, 0xC7, 0xC0 + rng.below(8) as u8]);
+                out.extend_from_slice(&rng.below(0x1000).to_le_bytes());
+            }
+            // mov [rbp-d], rax / mov rax, [rbp-d]
+            1 => out.extend_from_slice(&[0x48, 0x89, 0x45, disp]),
+            2 => out.extend_from_slice(&[0x48, 0x8B, 0x45, disp]),
+            // call rel32
+            3 => {
+                out.push(0xE8);
+                out.extend_from_slice(&(rng.below(0x10000) as i32 - 0x8000).to_le_bytes());
+            }
+            // cmp eax, imm8; jne rel8
+            4 => out.extend_from_slice(&[0x83, 0xF8, rng.below(0x40) as u8, 0x75, 0x04]),
+            // lea rax, [rip+disp32]
+            5 => {
+                out.extend_from_slice(&[0x48, 0x8D, 0x05]);
+                out.extend_from_slice(&rng.below(0x10000).to_le_bytes());
+            }
+            // xor eax, eax / test rax, rax
+            6 => out.extend_from_slice(&[0x31, 0xC0]),
+            _ => out.extend_from_slice(&[0x48, 0x85, 0xC0]),
+        }
+    }
+    // leave; ret; int3 padding
+    out.extend_from_slice(&[0xC9, 0xC3, 0xCC, 0xCC]);
+}
+
+fn aarch64_function(rng: &mut Rng, out: &mut Vec<u8>) {
+    let mut emit = |word: u32| out.extend_from_slice(&word.to_le_bytes());
+    emit(0xA9BF_7BFD); // stp x29, x30, [sp, #-16]!
+    emit(0x9100_03FD); // mov x29, sp
+    for _ in 0..4 + rng.below(12) {
+        let rd = rng.below(16);
+        let rn = rng.below(16);
+        let imm = rng.below(0x200);
+        emit(match rng.below(7) {
+            0 => 0x9100_0000 | imm << 10 | rn << 5 | rd, // add xd, xn, #imm
+            1 => 0xF940_0000 | imm << 10 | rn << 5 | rd, // ldr xd, [xn, #imm]
+            2 => 0xF900_0000 | imm << 10 | rn << 5 | rd, // str xd, [xn, #imm]
+            3 => 0xAA00_03E0 | rn << 16 | rd,            // mov xd, xn
+            4 => 0x9400_0000 | rng.below(0x10000),       // bl
+            5 => 0xF100_001F | imm << 10 | rn << 5,      // cmp xn, #imm
+            _ => 0x5400_0001 | rng.below(0x40) << 5,     // b.ne
+        });
+    }
+    emit(0xA8C1_7BFD); // ldp x29, x30, [sp], #16
+    emit(0xD65F_03C0); // ret
+}
+
+fn riscv64_function(rng: &mut Rng, out: &mut Vec<u8>) {
+    let mut emit = |word: u32| out.extend_from_slice(&word.to_le_bytes());
+    emit(0xFF01_0113); // addi sp, sp, -16
+    emit(0x0011_3423); // sd ra, 8(sp)
+    for _ in 0..4 + rng.below(12) {
+        // a0-a7
+        let rd = 10 + rng.below(8);
+        let rs = 10 + rng.below(8);
+        let imm = rng.below(0x100);
+        emit(match rng.below(6) {
+            0 => imm << 20 | rs << 15 | rd << 7 | 0x13, // addi rd, rs, imm
+            1 => imm << 20 | rs << 15 | rd << 7 | 0x1B, // addiw rd, rs, imm
+            2 => (imm & !7) << 20 | rs << 15 | 3 << 12 | rd << 7 | 0x03, // ld rd, imm(rs)
+            3 => rd << 20 | rs << 15 | 3 << 12 | (imm & 0x18) << 7 | 0x23, // sd rd, imm(rs)
+            4 => rs << 20 | rd << 15 | rd << 7 | 0x3B,  // addw rd, rd, rs
+            _ => rng.below(0x200) << 22 | 0xEF,         // jal ra, offset
+        });
+    }
+    emit(0x0081_3083); // ld ra, 8(sp)
+    emit(0x0101_0113); // addi sp, sp, 16
+    emit(0x0000_8067); // ret
+}
+
+/// Small, valid-looking headers for every family `detect_format` checks
+/// early, plus raw code as the fall-through case.
+fn format_samples() -> Vec<(&'static str, Vec<u8>)> {
+    let mut elf = vec![0u8; 64];
+    elf[..4].copy_from_slice(b"\x7FELF");
+    elf[4] = 2; // ELFCLASS64
+    elf[5] = 1; // ELFDATA2LSB
+    elf[6] = 1;
+    elf[18..20].copy_from_slice(&0x3Eu16.to_le_bytes());
+
+    let mut pe = vec![0u8; 256];
+    pe[..2].copy_from_slice(b"MZ");
+    pe[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+    pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+    pe[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+
+    let mut macho = vec![0u8; 32];
+    macho[..4].copy_from_slice(&0xFEED_FACFu32.to_le_bytes());
+    macho[4..8].copy_from_slice(&0x0100_000Cu32.to_le_bytes());
+
+    let mut fat = vec![0u8; 64];
+    fat[..4].copy_from_slice(&0xCAFE_BABEu32.to_be_bytes());
+    fat[4..8].copy_from_slice(&1u32.to_be_bytes());
+    fat[8..12].copy_from_slice(&0x0100_0007u32.to_be_bytes());
+
+    let java = [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34].to_vec();
+    let wasm = b"\0asm\x01\0\0\0".to_vec();
+    let mut dex = vec![0u8; 112];
+    dex[..8].copy_from_slice(b"dex\n039\0");
+    let hex = b":10000000000102030405060708090A0B0C0D0E0F78\n:00000001FF\n".to_vec();
+
+    vec![
+        ("elf", elf),
+        ("pe", pe),
+        ("macho", macho),
+        ("macho_fat", fat),
+        ("java", java),
+        ("wasm", wasm),
+        ("dex", dex),
+        ("intel_hex", hex),
+        ("raw", synthetic_code(Isa::AArch64, 4 * KIB, 7)),
+    ]
+}
+
+fn bench_score_all(c: &mut Criterion) {
+    let options = ClassifierOptions::new();
+    let mut group = c.benchmark_group("score_all_architectures");
+    for (name, isa) in ISAS {
+        for size in [4 * KIB, 64 * KIB, MIB] {
+            let data = synthetic_code(isa, size, 1);
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(name, format!("{}KiB", size / KIB)),
+                &data,
+                |b, data| b.iter(|| heuristics::score_all_architectures(black_box(data), &options)),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_multi_isa(c: &mut Criterion) {
+    // 64 KiB runs of each ISA in turn, like a firmware image with a
+    // boot loader, main application and coprocessor blob
+    let mut image = Vec::with_capacity(16 * MIB);
+    let mut seed = 0;
+    while image.len() < 16 * MIB {
+        for (_, isa) in ISAS {
+            seed += 1;
+            image.extend(synthetic_code(isa, 64 * KIB, seed));
+        }
+    }
+    image.truncate(16 * MIB);
+
+    let mut group = c.benchmark_group("detect_multi_isa");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(image.len() as u64));
+    group.bench_function("mixed_16MiB", |b| {
+        b.iter(|| detect_multi_isa(black_box(&image), &ClassifierOptions::new(), 1024))
+    });
+    group.finish();
+}
+
+fn bench_extensions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("detect_extensions");
+    for (name, isa) in &ISAS[..2] {
+        let data = synthetic_code(*isa, MIB, 2);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::new(*name, "1MiB"), &data, |b, data| {
+            b.iter(|| extensions::detect_from_code(black_box(data), *isa, Endianness::Little))
+        });
+    }
+    group.finish();
+}
+
+fn bench_detect_format(c: &mut Criterion) {
+    let mut group = c.benchmark_group("detect_format");
+    for (name, data) in format_samples() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+            b.iter(|| formats::detect_format(black_box(data)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_score_all,
+    bench_multi_isa,
+    bench_extensions,
+    bench_detect_format
+);
+criterion_main!(benches);
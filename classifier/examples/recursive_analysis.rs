@@ -332,6 +332,11 @@ fn enumerate_all_isas() {
             "TI MSP430 (16-bit ultra-low-power MCU)",
         ),
         (Isa::Pic, "pic", "Microchip PIC (8/16/32-bit MCU family)"),
+        (
+            Isa::Pic24,
+            "pic24",
+            "Microchip PIC24/dsPIC (16-bit MCU/DSC)",
+        ),
         (Isa::Stm8, "stm8", "STMicroelectronics STM8 (8-bit MCU)"),
         // GPU/Accelerators
         (Isa::AmdGpu, "amdgpu", "AMD GPU (GCN, RDNA architectures)"),
@@ -2059,7 +2064,9 @@ fn print_classification_result(result: &ClassificationResult) {
     println!("    endianness: {:?}", result.endianness);
     println!("    format: {:?}", result.format);
     println!("    variant: {:?}", result.variant);
-    print_variant_fields(&result.variant);
+    if let Some(ref variant) = result.variant {
+        print_variant_fields(variant);
+    }
     println!("    extensions: {:?}", result.extensions);
     print_extensions(&result.extensions);
     println!("    confidence: {}", result.confidence);
@@ -2480,6 +2487,9 @@ fn demonstrate_programmatic_iteration() {
                 MetadataValue::Hex(h) => {
                     println!("          -> Hex: 0x{:08X} (binary: {:032b})", h, h);
                 }
+                MetadataValue::Bool(b) => {
+                    println!("          -> Bool: {b}");
+                }
             }
 
             // Pattern match on MetadataKey
@@ -2549,6 +2559,7 @@ fn run_directory_analysis(target_dir: &Path) {
         max_scan_bytes: 2 * 1024 * 1024,
         detect_extensions: true,
         fast_mode: false,
+        ..ClassifierOptions::new()
     };
 
     print_options(&options);
@@ -2756,6 +2767,9 @@ fn print_detection_payload_compact(payload: &DetectionPayload) {
                 MetadataValue::Hex(h) => {
                     println!("        value=Hex(0x{:08X})", h);
                 }
+                MetadataValue::Bool(b) => {
+                    println!("        value=Bool({b})");
+                }
             }
         }
     }
@@ -3156,6 +3170,7 @@ fn validate_system_binaries() -> ValidationResults {
         max_scan_bytes: 2 * 1024 * 1024,
         detect_extensions: true,
         fast_mode: false,
+        ..ClassifierOptions::new()
     };
 
     // System directories to test
@@ -4214,6 +4229,7 @@ fn demonstrate_real_binary_analysis() {
                             MetadataValue::Integer(n) => println!("          value={}", n),
                             MetadataValue::Address(a) => println!("          value=0x{:016X}", a),
                             MetadataValue::Hex(h) => println!("          value=0x{:08X}", h),
+                            MetadataValue::Bool(b) => println!("          value={b}"),
                         }
                     }
                     println!();
@@ -4282,6 +4298,7 @@ fn demonstrate_heuristic_analysis() {
         max_scan_bytes: 1024 * 1024,
         detect_extensions: true,
         fast_mode: false,
+        ..ClassifierOptions::new()
     };
 
     // x86-64 code sequence - expanded with multiple functions
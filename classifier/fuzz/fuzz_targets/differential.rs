@@ -0,0 +1,10 @@
+#![no_main]
+
+use isa_classifier::{differential, ClassifierOptions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Err(divergence) = differential::check(data, &ClassifierOptions::new()) {
+        panic!("{divergence}");
+    }
+});
@@ -1,5 +1,7 @@
 //! AArch64 architecture analysis.
 
+use super::padding::{self, PaddingKind, PaddingRole};
+
 /// AArch64 system instructions.
 pub mod system {
     pub const NOP: u32 = 0xD503201F;
@@ -267,7 +269,22 @@ pub fn is_csel(instr: u32) -> bool {
 /// - Memory barriers (DSB, DMB, ISB)
 /// - Multi-instruction patterns (prologues, epilogues, exception handlers)
 pub fn score(data: &[u8]) -> i64 {
+    score_with_matches(data).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (returns,
+/// system-register accesses, prologues, barriers and other AArch64-only
+/// encodings) the structural check counted.
+pub fn score_with_matches(data: &[u8]) -> (i64, u32) {
+    let (score, matches, _) = score_with_padding(data);
+    (score, matches)
+}
+
+/// Like [`score_with_matches`], also returning the bytes of alignment
+/// padding runs the scorer treated as neutral.
+pub fn score_with_padding(data: &[u8]) -> (i64, u32, usize) {
     let mut score: i64 = 0;
+    let mut padding_bytes = 0usize;
     let mut zero_run: u32 = 0;
     let mut prev_was_zero = false;
     let mut prev_instr: u32 = 0;
@@ -402,9 +419,17 @@ pub fn score(data: &[u8]) -> i64 {
         prev_prev_instr = prev_instr;
         prev_instr = word;
 
-        // NOP
+        // NOP: padding before a function entry is very distinctive, a bare
+        // padding run is neutral
         if word == system::NOP {
-            score += 30;
+            match padding::role(PaddingKind::AArch64, data, i) {
+                Some(PaddingRole::BeforeEntry) => {
+                    score += 60;
+                    padding_bytes += 4;
+                }
+                Some(PaddingRole::Run) => padding_bytes += 4,
+                _ => score += 30,
+            }
         }
 
         // RET - very distinctive encoding
@@ -695,7 +720,11 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    score.max(0)
+    (
+        score.max(0),
+        ret_count + mrs_msr_count + prologue_count + stp_fp_lr_count + extra_distinctive,
+        padding_bytes,
+    )
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 //! ARM32 architecture analysis.
 
+use super::padding::{self, PaddingKind, PaddingRole};
+
 /// ARM condition codes.
 pub mod condition {
     pub const EQ: u8 = 0x0; // Equal
@@ -82,8 +84,9 @@ pub fn thumb_instruction_length(first_halfword: u16) -> usize {
 /// - Exact instruction matches (NOP, BX LR, PUSH, POP)
 /// - Well-formed opcode patterns with valid condition codes
 /// - Multi-instruction patterns (prologue/epilogue sequences)
-fn score_arm32(data: &[u8]) -> i64 {
+fn score_arm32(data: &[u8]) -> (i64, usize) {
     let mut score: i64 = 0;
+    let mut padding_bytes = 0usize;
     let mut consecutive_zeros = 0u32;
     let mut al_count = 0u32;
     let mut total_count = 0u32;
@@ -197,6 +200,20 @@ fn score_arm32(data: &[u8]) -> i64 {
 
         // === Exact match patterns (very high confidence) ===
 
+        // Alignment padding leading into a function entry; bare runs are neutral
+        match padding::role(PaddingKind::Arm, data, i) {
+            Some(PaddingRole::BeforeEntry) => {
+                score += 40;
+                padding_bytes += 4;
+                continue;
+            }
+            Some(PaddingRole::Run) => {
+                padding_bytes += 4;
+                continue;
+            }
+            _ => {}
+        }
+
         // NOP (MOV R0, R0)
         if word == patterns::NOP {
             score += 20;
@@ -389,7 +406,7 @@ fn score_arm32(data: &[u8]) -> i64 {
     // Apply Hexagon cross-architecture penalty
     score -= hexagon_penalty;
 
-    score.max(0)
+    (score.max(0), padding_bytes)
 }
 
 /// Score likelihood of Thumb/Thumb-2 code.
@@ -397,8 +414,10 @@ fn score_arm32(data: &[u8]) -> i64 {
 /// Analyzes raw bytes for patterns characteristic of Thumb/Thumb-2:
 /// - 16-bit Thumb instructions (PUSH, POP, BX LR, MOV, ADD, etc.)
 /// - 32-bit Thumb-2 instructions (BL, LDR.W, STR.W, etc.)
-fn score_thumb(data: &[u8]) -> i64 {
+fn score_thumb(data: &[u8]) -> (i64, usize, u32) {
     let mut score: i64 = 0;
+    let mut padding_bytes = 0usize;
+    let mut valid_count = 0u32;
     let mut consecutive_zeros = 0u32;
     let mut last_hw: u16 = 0;
     let mut repeat_count = 0u32;
@@ -568,6 +587,20 @@ fn score_thumb(data: &[u8]) -> i64 {
         }
         consecutive_zeros = 0;
 
+        // Alignment padding leading into a function entry; bare runs are neutral
+        if let Some(role) = padding::role(PaddingKind::Thumb, data, i) {
+            if role != PaddingRole::Isolated {
+                if role == PaddingRole::BeforeEntry {
+                    score += 35;
+                }
+                let unit = padding::unit_len(PaddingKind::Thumb, &data[i..]);
+                padding_bytes += unit;
+                i += unit;
+                last_hw = hw;
+                continue;
+            }
+        }
+
         // Track repeated halfwords (padding detection)
         // Exempt known valid repeated patterns like Thumb NOP (0xBF00)
         if hw == last_hw {
@@ -598,6 +631,7 @@ fn score_thumb(data: &[u8]) -> i64 {
             // 32-bit Thumb-2 instruction
             let hw2 = u16::from_le_bytes([data[i + 2], data[i + 3]]);
             let word = ((hw as u32) << 16) | (hw2 as u32);
+            let mut recognized = true;
 
             // BL/BLX - Branch with Link (VERY common in Thumb-2 firmware)
             if (hw & 0xF800) == 0xF000 && (hw2 & 0xD000) == 0xD000 {
@@ -687,6 +721,12 @@ fn score_thumb(data: &[u8]) -> i64 {
                 score += 15;
             }
             // Unrecognized 32-bit Thumb-2 - no points
+            else {
+                recognized = false;
+            }
+            if recognized {
+                valid_count += 1;
+            }
 
             i += 4;
         } else {
@@ -842,6 +882,8 @@ fn score_thumb(data: &[u8]) -> i64 {
             // Penalty for completely unrecognized halfwords
             if !matched {
                 score -= 1;
+            } else if hw != 0xFFFF {
+                valid_count += 1;
             }
 
             i += 2;
@@ -862,7 +904,13 @@ fn score_thumb(data: &[u8]) -> i64 {
     // Apply MIPS BE penalty
     score -= mips_be_penalty;
 
-    score.max(0)
+    (score.max(0), padding_bytes, valid_count)
+}
+
+/// Number of Thumb/Thumb-2 instructions in `data` the Thumb scorer
+/// decodes, padding and unrecognized encodings excluded.
+pub fn thumb_valid_count(data: &[u8]) -> u32 {
+    score_thumb(data).2
 }
 
 /// Score multi-instruction Thumb-2 patterns.
@@ -952,6 +1000,35 @@ fn score_thumb_patterns(data: &[u8]) -> i64 {
     score
 }
 
+/// Vector-table score above which an image is taken to start with a
+/// Cortex-M exception table.
+const CORTEX_M_VECTOR_TABLE_THRESHOLD: i64 = 150;
+
+/// Whether `data` carries a Cortex-M exception vector table (initial SP
+/// in RAM followed by Thumb handler addresses) at offset 0 or behind a
+/// common header size.
+pub fn has_cortex_m_vector_table(data: &[u8]) -> bool {
+    score_cortex_m_vector_table(data) >= CORTEX_M_VECTOR_TABLE_THRESHOLD
+}
+
+/// Byte length of a Cortex-M vector table starting at offset 0: the
+/// initial SP word plus every following word that is zero or a Thumb
+/// handler address. `None` when no table starts at offset 0.
+pub fn cortex_m_vector_table_len(data: &[u8]) -> Option<usize> {
+    if score_vector_table_at_offset(data, 0) < CORTEX_M_VECTOR_TABLE_THRESHOLD {
+        return None;
+    }
+    let handlers = data
+        .chunks_exact(4)
+        .skip(1)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+        .take_while(|&addr| {
+            addr == 0 || (addr & 1 == 1 && (0x100..0x2000_0000).contains(&(addr & !1)))
+        })
+        .count();
+    Some(4 * (1 + handlers))
+}
+
 /// Detect Cortex-M vector table pattern.
 /// Vector tables have addresses with odd LSB (Thumb mode indicator) pointing within firmware.
 fn score_cortex_m_vector_table(data: &[u8]) -> i64 {
@@ -1065,18 +1142,126 @@ fn score_vector_table_at_offset(data: &[u8], offset: usize) -> i64 {
     score
 }
 
+/// Variant name for code that uses Thumb-2 exclusively.
+pub const THUMB_ONLY_VARIANT: &str = "Thumb-only (ARMv8-M class)";
+
+/// Variant profile for application-class cores (Cortex-A).
+pub const A_PROFILE: &str = "A-profile";
+
+/// Variant profile for real-time cores (Cortex-R).
+pub const R_PROFILE: &str = "R-profile";
+
+/// Variant profile for microcontroller cores, which execute Thumb only.
+pub const M_PROFILE: &str = "M-profile (Cortex-M)";
+
+/// Fewest IT blocks and fewest CBZ/CBNZ each needed before Thumb code is
+/// taken for a v7-M/v8-M class core.
+const MIN_V7M_MARKERS: u32 = 2;
+
+/// Per-marker bonus for Thumb-only code, and the cap as a fraction
+/// (1/n) of the Thumb score.
+const V7M_MARKER_BONUS: i64 = 10;
+const V7M_BONUS_CAP_DIVISOR: i64 = 4;
+
+/// Thumb-2 encodings counted over a linear Thumb decode.
+///
+/// IT blocks and CBZ/CBNZ exist only in Thumb (ARMv6T2/ARMv7-M onward);
+/// ARMv8-M mainline cores (Cortex-M23/M33/M55) execute nothing else.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThumbProfile {
+    /// `IT` instructions (`0xBFxx` with a non-zero mask)
+    pub it_blocks: u32,
+    /// `CBZ`/`CBNZ` instructions
+    pub compare_branches: u32,
+    /// 32-bit Thumb-2 instructions
+    pub wide: u32,
+    /// 16-bit Thumb instructions
+    pub narrow: u32,
+}
+
+impl ThumbProfile {
+    /// Decode `data` linearly as Thumb and count the v7-M markers.
+    pub fn scan(data: &[u8]) -> Self {
+        let mut profile = Self::default();
+        let mut i = 0;
+        while i + 1 < data.len() {
+            let hw = u16::from_le_bytes([data[i], data[i + 1]]);
+            if thumb_instruction_length(hw) == 4 {
+                profile.wide += 1;
+                i += 4;
+                continue;
+            }
+            profile.narrow += 1;
+            // IT: 1011 1111 firstcond mask; a zero mask is a hint (NOP, WFI...)
+            if hw & 0xFF00 == 0xBF00 && hw & 0x000F != 0 && (hw >> 4) & 0xF != 0xF {
+                profile.it_blocks += 1;
+            }
+            // CBZ/CBNZ: 1011 o0i1 imm5 Rn
+            if hw & 0xF500 == 0xB100 {
+                profile.compare_branches += 1;
+            }
+            i += 2;
+        }
+        profile
+    }
+
+    /// Whether the IT-block and CBZ/CBNZ encodings of v7-M and later are
+    /// both present.
+    pub fn has_v7m_encodings(&self) -> bool {
+        self.it_blocks >= MIN_V7M_MARKERS && self.compare_branches >= MIN_V7M_MARKERS
+    }
+
+    fn v7m_markers(&self) -> i64 {
+        i64::from(self.it_blocks + self.compare_branches)
+    }
+}
+
+/// Thumb-only evaluation: the Thumb score plus a bonus for v7-M encodings,
+/// independent of any A32 evidence.
+fn score_thumb_only(thumb_score: i64, profile: &ThumbProfile) -> i64 {
+    if !profile.has_v7m_encodings() || thumb_score <= 0 {
+        return thumb_score;
+    }
+    let bonus = (profile.v7m_markers() * V7M_MARKER_BONUS).min(thumb_score / V7M_BONUS_CAP_DIVISOR);
+    thumb_score + bonus
+}
+
+/// Whether `data` reads as Thumb-only code of an ARMv8-M class core: the
+/// Thumb evaluation dominates A32 and the v7-M encodings are present.
+pub fn is_thumb_only(data: &[u8]) -> bool {
+    let profile = ThumbProfile::scan(data);
+    if !profile.has_v7m_encodings() {
+        return false;
+    }
+    let thumb = score_thumb_only(score_thumb(data).0 + score_thumb_patterns(data), &profile);
+    thumb > 0 && thumb >= 2 * score_arm32(data).0
+}
+
 /// Score likelihood of ARM32 code (ARM mode or Thumb mode).
 ///
 /// This scores both ARM32 and Thumb modes and returns the higher score.
 pub fn score(data: &[u8]) -> i64 {
-    let arm32_score = score_arm32(data);
-    let thumb_score = score_thumb(data);
+    score_with_padding(data).0
+}
+
+/// Like [`score`], also returning the bytes of alignment padding runs the
+/// winning mode's scorer treated as neutral.
+pub fn score_with_padding(data: &[u8]) -> (i64, usize) {
+    let (arm32_score, arm32_padding) = score_arm32(data);
+    let (thumb_score, thumb_padding, _) = score_thumb(data);
     let thumb_pattern_score = score_thumb_patterns(data);
     let vector_table_score = score_cortex_m_vector_table(data);
 
-    // Take the best score, adding pattern bonus to Thumb score
-    let effective_thumb_score = thumb_score + thumb_pattern_score;
+    // Take the best score, adding pattern bonus to Thumb score. The Thumb
+    // evaluation stands on its own: ARMv8-M code has no A32 to corroborate it
+    let effective_thumb_score =
+        score_thumb_only(thumb_score + thumb_pattern_score, &ThumbProfile::scan(data));
     let base_score = arm32_score.max(effective_thumb_score);
+    let padding_bytes = if effective_thumb_score > arm32_score {
+        thumb_padding
+    } else {
+        arm32_padding
+    };
 
     // Vector table is a strong indicator - scale the bonus based on confidence
     // A high vector table score (200+) indicates very likely Cortex-M firmware
@@ -1232,7 +1417,7 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    final_score.max(0)
+    (final_score.max(0), padding_bytes)
 }
 
 /// Detect SH7058 sparse vector table at the start of firmware.
@@ -1343,4 +1528,32 @@ mod tests {
         let ret = 0xE12FFF1Eu32.to_le_bytes();
         assert!(score(&ret) > 0);
     }
+
+    #[test]
+    fn test_thumb_profile() {
+        let mut code = Vec::new();
+        // cbz r0; cmp r0, #5; ite eq; moveq; movne; ldr.w r1, [r0, #4];
+        // nop (a hint, not an IT block); cbnz r1; bx lr
+        for hw in [
+            0xB120u16, 0x2805, 0xBF0C, 0x2001, 0x2000, 0xF8D0, 0x1004, 0xBF00, 0xB909, 0x4770,
+        ] {
+            code.extend(hw.to_le_bytes());
+        }
+        let profile = ThumbProfile::scan(&code);
+        assert_eq!(
+            profile,
+            ThumbProfile {
+                it_blocks: 1,
+                compare_branches: 2,
+                wide: 1,
+                narrow: 8,
+            }
+        );
+        assert!(!profile.has_v7m_encodings());
+
+        let twice = ThumbProfile::scan(&code.repeat(2));
+        assert!(twice.has_v7m_encodings());
+        assert!(score_thumb_only(1000, &twice) > 1000);
+        assert_eq!(score_thumb_only(1000, &profile), 1000);
+    }
 }
@@ -257,6 +257,29 @@ pub fn is_jmp(instr: u16) -> bool {
     (instr & format::JMP_MASK) == format::JMP_VAL
 }
 
+/// Minimum number of leading jump entries that make an interrupt vector table.
+const MIN_VECTOR_ENTRIES: usize = 8;
+
+/// Byte length of an interrupt vector table at offset 0: a run of `JMP`
+/// entries (devices with more than 8 KiB of flash) or of `RJMP` entries
+/// (smaller devices). `None` when fewer than 8 entries lead the image.
+pub fn vector_table_len(data: &[u8]) -> Option<usize> {
+    let jmps = data
+        .chunks_exact(4)
+        .take_while(|w| is_jmp(u16::from_le_bytes([w[0], w[1]])))
+        .count();
+    if jmps >= MIN_VECTOR_ENTRIES {
+        return Some(4 * jmps);
+    }
+    let rjmps = data
+        .chunks_exact(2)
+        .take_while(|w| is_rjmp(u16::from_le_bytes([w[0], w[1]])))
+        .count();
+    // A fill of 0xCC bytes also reads as RJMPs; real vectors differ
+    let distinct = data[..2 * rjmps].chunks_exact(2).any(|w| w != &data[..2]);
+    (rjmps >= MIN_VECTOR_ENTRIES && distinct).then_some(2 * rjmps)
+}
+
 /// Check if instruction is CALL (32-bit).
 pub fn is_call_32(instr: u16) -> bool {
     (instr & format::CALL_MASK) == format::CALL_VAL
@@ -378,6 +401,12 @@ pub fn is_likely_valid(instr: u16) -> bool {
 ///
 /// Analyzes raw bytes for patterns characteristic of AVR.
 pub fn score(data: &[u8]) -> i64 {
+    score_with_valid(data).0
+}
+
+/// Like [`score`], also returning how many instructions the scorer decoded
+/// as AVR.
+pub fn score_with_valid(data: &[u8]) -> (i64, u32) {
     let mut score: i64 = 0;
     let mut i = 0;
     let mut zero_run: u32 = 0;
@@ -1050,7 +1079,7 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    score.max(0)
+    (score.max(0), valid_count)
 }
 
 #[cfg(test)]
@@ -74,6 +74,8 @@ pub fn score(data: &[u8]) -> i64 {
                     i += 6;
                     continue;
                 }
+                // Truncated at the end of the data
+                i += 2;
             }
             0x9B00..=0x9B0F => {
                 // LDI:20 $i20, Ri
@@ -83,6 +85,8 @@ pub fn score(data: &[u8]) -> i64 {
                     i += 4;
                     continue;
                 }
+                // Truncated at the end of the data
+                i += 2;
             }
             0xC000..=0xCFFF => {
                 // LDI:8 $i8, Ri
@@ -125,4 +129,11 @@ mod tests {
         ];
         assert!(score(&code) > 0);
     }
+
+    #[test]
+    fn test_truncated_ldi_terminates() {
+        // LDI:32 and LDI:20 prefixes with no room for their immediates
+        assert_eq!(score(&[0xC0, 0x12, 0x9F, 0x80]), 4);
+        assert_eq!(score(&[0xC0, 0x12, 0x9B, 0x00]), 4);
+    }
 }
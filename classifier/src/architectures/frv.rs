@@ -0,0 +1,139 @@
+//! Heuristic scoring for Fujitsu FR-V architecture.
+//!
+//! Fixed 32-bit big-endian VLIW architecture. Bit 31 of every instruction
+//! is the pack bit: it is set on the last instruction of a VLIW packet, so
+//! real code never runs more than a packet's worth of words with it clear.
+//!
+//! Instruction layout: `P | GRk (6) | op (7) | GRi (6) | ...`. `sp` is
+//! `gr1` and `fp` is `gr2`. Key distinctive encodings:
+//! - `0x80880000`: NOP (`ori gr0,#0,gr0` with the pack bit)
+//! - `addi sp,#-N,sp`: function prologue
+//! - `ori sp,#0,fp` / `ori fp,#0,sp`: frame pointer setup and teardown
+//! - `sti grK,@(sp,#d12)`: callee-saved register spills
+
+use std::cmp;
+
+/// Pack bit: set on the last instruction of a VLIW packet.
+const PACK_BIT: u32 = 0x8000_0000;
+
+/// NOP (`ori gr0,#0,gr0`), pack bit ignored.
+const NOP: u32 = 0x0088_0000;
+
+/// `ori sp,#0,fp`
+const ORI_SP_FP: u32 = 0x0488_1000;
+/// `ori fp,#0,sp`
+const ORI_FP_SP: u32 = 0x0288_2000;
+
+/// Mask and value of `addi sp,#d12,sp` (immediate in bits 11..0).
+const ADDI_SP_MASK: u32 = 0x7FFF_F000;
+const ADDI_SP: u32 = 0x0240_1000;
+
+/// Mask and value of `sti grK,@(sp,#d12)`.
+const STI_SP_MASK: u32 = 0x01FF_F000;
+const STI_SP: u32 = 0x0148_1000;
+
+/// Longest packet any FR-V core issues (FR550: eight slots).
+const MAX_PACKET: u32 = 8;
+
+/// Score raw data as FR-V code.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < 4 {
+        return 0;
+    }
+
+    let mut score: i64 = 0;
+    let mut zero_run = 0;
+    let mut packet_len = 0u32;
+
+    let mut prologue_count = 0;
+    let mut frame_count = 0;
+    let mut nop_count = 0;
+
+    for chunk in data.chunks_exact(4) {
+        let word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+
+        if word == 0x0000_0000 || word == 0xFFFF_FFFF {
+            zero_run += 1;
+            if zero_run > 2 {
+                score -= 2;
+            }
+            packet_len = 0;
+            continue;
+        }
+        zero_run = 0;
+
+        let insn = word & !PACK_BIT;
+        if insn == NOP {
+            score += 10;
+            nop_count += 1;
+        } else if insn & ADDI_SP_MASK == ADDI_SP {
+            if insn & 0x800 != 0 {
+                // addi sp,#-N,sp
+                score += 25;
+                prologue_count += 1;
+            } else {
+                // addi sp,#N,sp
+                score += 15;
+            }
+        } else if insn == ORI_SP_FP || insn == ORI_FP_SP {
+            score += 20;
+            frame_count += 1;
+        } else if insn & STI_SP_MASK == STI_SP {
+            score += 5;
+        }
+
+        packet_len += 1;
+        if word & PACK_BIT != 0 {
+            packet_len = 0;
+        } else if packet_len > MAX_PACKET {
+            // Longer than any packet: not FR-V
+            score -= 3;
+        }
+    }
+
+    let idioms = [prologue_count, frame_count, nop_count]
+        .iter()
+        .filter(|&&count| count > 0)
+        .count();
+    if data.len() > 1024 && (prologue_count == 0 || idioms < 2) {
+        return 0;
+    }
+    cmp::max(0, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prologue, body and epilogue of a small FR-V function.
+    fn function() -> Vec<u32> {
+        vec![
+            0x8240_1FF0, // addi sp,#-16,sp
+            0x0548_1008, // sti fp,@(sp,#8)
+            0x8488_1000, // ori sp,#0,fp
+            0x9000_0003, // body
+            0x8288_2000, // ori fp,#0,sp
+            0x8240_1010, // addi sp,#16,sp
+            0x8088_0000, // nop
+        ]
+    }
+
+    fn to_bytes(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_frv_scoring() {
+        let code = to_bytes(&function().repeat(64));
+        assert!(score(&code) > 0);
+    }
+
+    #[test]
+    fn test_unpacked_runs_rejected() {
+        // Prologue words with the pack bit cleared, far beyond any packet
+        let words: Vec<u32> = function().iter().map(|w| w & !PACK_BIT).collect();
+        let code = to_bytes(&words.repeat(64));
+        assert!(score(&code) < score(&to_bytes(&function().repeat(64))));
+        assert_eq!(score(&[0x12; 2048]), 0);
+    }
+}
@@ -356,6 +356,12 @@ pub const VALID_OPCODE_PREFIXES: &[u32] = &[
 ///
 /// Analyzes raw bytes for patterns characteristic of LoongArch.
 pub fn score(data: &[u8]) -> i64 {
+    score_with_matches(data).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (returns, calls
+/// and prologues) the structural check counted.
+pub fn score_with_matches(data: &[u8]) -> (i64, u32) {
     let mut score: i64 = 0;
     let mut valid_count = 0u32;
     let mut ret_count = 0u32;
@@ -823,7 +829,7 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    score.max(0)
+    (score.max(0), ret_count + call_count + prologue_count)
 }
 
 #[cfg(test)]
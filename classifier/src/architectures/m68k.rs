@@ -374,6 +374,12 @@ pub const STRONG_INDICATORS: &[u16] = &[
 ///
 /// Analyzes raw bytes for patterns characteristic of m68k.
 pub fn score(data: &[u8]) -> i64 {
+    score_with_matches(data).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (returns and
+/// subroutine calls) the structural check counted.
+pub fn score_with_matches(data: &[u8]) -> (i64, u32) {
     let mut score: i64 = 0;
     let mut ret_count = 0u32;
     let mut call_count = 0u32;
@@ -712,7 +718,7 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    score.max(0)
+    (score.max(0), ret_count + call_count)
 }
 
 #[cfg(test)]
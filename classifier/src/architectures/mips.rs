@@ -358,6 +358,14 @@ fn score_word(word: u32, is_64: bool) -> i64 {
 ///
 /// Returns (big_endian_score, little_endian_score)
 pub fn score(data: &[u8], is_64: bool) -> (i64, i64) {
+    let ((be, _), (le, _)) = score_with_matches(data, is_64);
+    (be, le)
+}
+
+/// Like [`score`], pairing each endianness' score with the number of
+/// distinctive idioms (JR $ra, stack-frame setup, $ra saves and restores)
+/// counted for it.
+pub fn score_with_matches(data: &[u8], is_64: bool) -> ((i64, u32), (i64, u32)) {
     let mut score_be: i64 = 0;
     let mut score_le: i64 = 0;
     let mut zero_run = 0u32;
@@ -767,7 +775,10 @@ pub fn score(data: &[u8], is_64: bool) -> (i64, i64) {
         }
     }
 
-    (score_be.max(0), score_le.max(0))
+    (
+        (score_be.max(0), distinctive_be),
+        (score_le.max(0), distinctive_le),
+    )
 }
 
 #[cfg(test)]
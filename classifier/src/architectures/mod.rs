@@ -15,6 +15,7 @@ pub mod csky;
 pub mod cellspu;
 pub mod dalvik;
 pub mod fr30;
+pub mod frv;
 pub mod hc11;
 pub mod hcs12;
 pub mod hexagon;
@@ -26,10 +27,14 @@ pub mod loongarch;
 pub mod m68k;
 pub mod microblaze;
 pub mod mips;
+pub mod mos6502;
 pub mod msp430;
 pub mod nios2;
 pub mod openrisc;
+pub mod padding;
 pub mod parisc;
+pub mod pic;
+pub mod pic24;
 pub mod ppc;
 pub mod ppcvle;
 pub mod riscv;
@@ -39,12 +44,15 @@ pub mod s390x;
 pub mod sparc;
 pub mod superh;
 pub mod tic6000;
+pub mod tilegx;
 pub mod tricore;
 pub mod v850;
 pub mod vax;
+pub mod videocore;
 pub mod wasm;
 pub mod x86;
 pub mod xtensa;
+pub mod z80;
 
 use crate::types::{Endianness, Isa};
 
@@ -82,6 +90,7 @@ pub fn default_endianness(isa: Isa) -> Endianness {
         Isa::Lanai => Endianness::Big,
         Isa::McstElbrus => Endianness::Big,
         Isa::Jvm => Endianness::Big,         // JVM is big-endian
+        Isa::Dis => Endianness::Big,         // Dis operands are big-endian
         Isa::Dalvik => Endianness::Little,   // DEX is little-endian
         Isa::Blackfin => Endianness::Little, // Blackfin is little-endian
         Isa::Ia64 => Endianness::Little,     // Itanium is little-endian
@@ -101,6 +110,7 @@ pub fn default_endianness(isa: Isa) -> Endianness {
         Isa::Mcs6502 => Endianness::Little,
         Isa::W65816 => Endianness::Little,
         Isa::Pic => Endianness::Little,
+        Isa::Pic24 => Endianness::Little,
         Isa::Stm8 => Endianness::Big,
         Isa::S12z => Endianness::Big,
 
@@ -119,6 +129,7 @@ pub fn instruction_alignment(isa: Isa) -> usize {
         Isa::Z80 | Isa::Mcs6502 | Isa::W65816 => 1,
         Isa::Jvm => 1,      // JVM bytecode is byte-aligned
         Isa::Wasm => 1,     // WASM bytecode is byte-aligned
+        Isa::Dis => 1,      // Dis bytecode is byte-aligned
         Isa::Dalvik => 2,   // DEX bytecode is 2-byte aligned
         Isa::Vax => 1,      // VAX is byte-aligned (variable 1-37 bytes)
         Isa::Blackfin => 2, // Blackfin is 16-bit aligned (variable 16/32/64-bit)
@@ -135,6 +146,7 @@ pub fn instruction_alignment(isa: Isa) -> usize {
         Isa::Lanai => 4,
         Isa::CellSpu => 4, // Cell SPU is 32-bit fixed
         Isa::Ia64 => 16,   // IA-64 bundles are 128-bit (16-byte) aligned
+        Isa::TileGx => 8,  // TILE-Gx bundles are 64-bit (8-byte) aligned
         Isa::LoongArch32 | Isa::LoongArch64 => 4,
         Isa::Hexagon => 4,
         Isa::TiC6000 => 4,
@@ -155,6 +167,8 @@ pub fn instruction_alignment(isa: Isa) -> usize {
         Isa::Rh850 => 2,   // RH850 inherits V850-style 16/32-bit alignment
         Isa::PpcVle => 2,  // VLE supports 16-bit and 32-bit encodings
         Isa::Csky => 2,    // C-SKY has 16/32-bit encodings
+        Isa::Pic => 2,     // 12/14/16-bit words stored in 16-bit slots
+        Isa::Pic24 => 4,   // 24-bit word stored in 32 bits with a phantom byte
 
         _ => 4,
     }
@@ -181,6 +195,7 @@ pub fn supports_compressed(isa: Isa) -> bool {
             | Isa::Jvm     // JVM has variable-length bytecode
             | Isa::Wasm    // WASM has variable-length bytecode (LEB128)
             | Isa::Dalvik  // DEX has variable-length bytecode
+            | Isa::Dis     // Dis has variable-length operands
             | Isa::Vax     // VAX has variable-length CISC (1-37 bytes)
             | Isa::Blackfin // Blackfin has variable-length (16/32/64-bit)
             | Isa::Hcs12 // HC12/HCS12X has variable-length instructions (1-8 bytes)
@@ -201,66 +216,418 @@ pub fn register_width(isa: Isa) -> u8 {
 }
 
 /// Common instruction patterns across architectures.
+///
+/// [`catalog`] lists the canonical encodings of each ISA's NOP, return,
+/// call, prologue, epilogue and system call instructions, in the byte
+/// order the ISA uses by default. Entries are built from the constants of
+/// the per-architecture modules, so the scorers and the catalog agree on
+/// what the encodings are.
 pub mod patterns {
-    /// Generic NOP detection
-    pub fn is_likely_nop(bytes: &[u8]) -> bool {
-        if bytes.is_empty() {
-            return false;
+    use super::{
+        aarch64, alpha, arm, avr, hexagon, loongarch, m68k, mips, msp430, ppc, riscv, s390x, sparc,
+        superh, x86,
+    };
+    use crate::types::{Endianness, Isa};
+
+    /// What a canonical instruction pattern stands for.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum PatternKind {
+        /// No-operation, including alignment fillers.
+        Nop,
+        /// Return from a subroutine.
+        Ret,
+        /// Subroutine call.
+        Call,
+        /// First instruction of a typical function prologue.
+        Prologue,
+        /// Frame teardown before a return.
+        Epilogue,
+        /// System call or software trap into the kernel.
+        Syscall,
+    }
+
+    /// One canonical encoding of an instruction.
+    ///
+    /// A window matches when it holds at least `min_len` bytes and every
+    /// byte of it, masked with `mask`, equals the byte of `bytes` at the
+    /// same position. `bytes` and `mask` have the same length, which can
+    /// be shorter than `min_len` when only the opcode is fixed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct CanonicalPattern {
+        /// What the instruction is.
+        pub kind: PatternKind,
+        /// Fixed bits of the encoding, in memory order.
+        pub bytes: &'static [u8],
+        /// Which bits of each byte are fixed.
+        pub mask: &'static [u8],
+        /// Byte order `bytes` is laid out in.
+        pub endianness: Endianness,
+        /// Length of the whole instruction.
+        pub min_len: usize,
+    }
+
+    impl CanonicalPattern {
+        /// Whether the start of `window` holds this instruction.
+        pub fn matches(&self, window: &[u8]) -> bool {
+            window.len() >= self.min_len
+                && window.len() >= self.bytes.len()
+                && self
+                    .bytes
+                    .iter()
+                    .zip(self.mask)
+                    .zip(window)
+                    .all(|((&b, &m), &w)| w & m == b)
+        }
+
+        /// Whether the pattern is a single fixed encoding, with no
+        /// operand bits left open.
+        pub fn is_exact(&self) -> bool {
+            self.mask.iter().all(|&m| m == 0xFF) && self.min_len == self.bytes.len()
         }
+    }
 
-        // Common NOP patterns
-        match bytes.len() {
-            1 => bytes[0] == 0x90, // x86 NOP
-            2 => {
-                let w = u16::from_le_bytes([bytes[0], bytes[1]]);
-                w == 0x4E71    // m68k NOP
-                    || w == 0x0009 // SuperH NOP
-                    || w == 0xBF00 // Thumb NOP
-                    || w == 0x0001 // RISC-V C.NOP
+    /// Build a pattern from one instruction word: `pattern!(u32 le Nop VAL)`,
+    /// optionally followed by `, mask MASK` and `, min_len N`.
+    macro_rules! pattern {
+        ($ty:ident $order:ident $kind:ident $val:expr) => {
+            pattern!($ty $order $kind $val, mask $ty::MAX)
+        };
+        ($ty:ident $order:ident $kind:ident $val:expr, mask $mask:expr) => {
+            pattern!($ty $order $kind $val, mask $mask, min_len core::mem::size_of::<$ty>())
+        };
+        ($ty:ident le $kind:ident $val:expr, mask $mask:expr, min_len $min:expr) => {
+            CanonicalPattern {
+                kind: PatternKind::$kind,
+                bytes: &($val as $ty).to_le_bytes(),
+                mask: &($mask as $ty).to_le_bytes(),
+                endianness: Endianness::Little,
+                min_len: $min,
             }
-            4 => {
-                let le = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-                let be = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-
-                le == 0xD503201F    // AArch64 NOP
-                    || le == 0x00000013 // RISC-V NOP
-                    || le == 0xE1A00000 // ARM NOP
-                    || be == 0x00000000 // MIPS NOP
-                    || be == 0x60000000 // PowerPC NOP
-                    || be == 0x01000000 // SPARC NOP
+        };
+        ($ty:ident be $kind:ident $val:expr, mask $mask:expr, min_len $min:expr) => {
+            CanonicalPattern {
+                kind: PatternKind::$kind,
+                bytes: &($val as $ty).to_be_bytes(),
+                mask: &($mask as $ty).to_be_bytes(),
+                endianness: Endianness::Big,
+                min_len: $min,
             }
-            _ => false,
+        };
+    }
+
+    static X86: &[CanonicalPattern] = &[
+        CanonicalPattern {
+            kind: PatternKind::Nop,
+            bytes: &[x86::opcodes::NOP],
+            mask: &[0xFF],
+            endianness: Endianness::Little,
+            min_len: 1,
+        },
+        CanonicalPattern {
+            kind: PatternKind::Ret,
+            bytes: &[x86::opcodes::RET],
+            mask: &[0xFF],
+            endianness: Endianness::Little,
+            min_len: 1,
+        },
+        // call rel32
+        CanonicalPattern {
+            kind: PatternKind::Call,
+            bytes: &[x86::opcodes::CALL_REL32],
+            mask: &[0xFF],
+            endianness: Endianness::Little,
+            min_len: 5,
+        },
+        // push ebp; mov ebp, esp
+        CanonicalPattern {
+            kind: PatternKind::Prologue,
+            bytes: &[x86::opcodes::PUSH_EBP, 0x89, 0xE5],
+            mask: &[0xFF, 0xFF, 0xFF],
+            endianness: Endianness::Little,
+            min_len: 3,
+        },
+        CanonicalPattern {
+            kind: PatternKind::Epilogue,
+            bytes: &[x86::opcodes::LEAVE],
+            mask: &[0xFF],
+            endianness: Endianness::Little,
+            min_len: 1,
+        },
+        // int 0x80
+        CanonicalPattern {
+            kind: PatternKind::Syscall,
+            bytes: &[x86::opcodes::INT, 0x80],
+            mask: &[0xFF, 0xFF],
+            endianness: Endianness::Little,
+            min_len: 2,
+        },
+    ];
+
+    static X86_64: &[CanonicalPattern] = &[
+        X86[0],
+        X86[1],
+        X86[2],
+        // push rbp; mov rbp, rsp
+        CanonicalPattern {
+            kind: PatternKind::Prologue,
+            bytes: &[x86::opcodes::PUSH_EBP, 0x48, 0x89, 0xE5],
+            mask: &[0xFF, 0xFF, 0xFF, 0xFF],
+            endianness: Endianness::Little,
+            min_len: 4,
+        },
+        X86[4],
+        CanonicalPattern {
+            kind: PatternKind::Syscall,
+            bytes: &x86::opcodes::SYSCALL,
+            mask: &[0xFF, 0xFF],
+            endianness: Endianness::Little,
+            min_len: 2,
+        },
+    ];
+
+    static AARCH64: &[CanonicalPattern] = &[
+        pattern!(u32 le Nop aarch64::system::NOP),
+        pattern!(u32 le Ret aarch64::ret::RET),
+        // bl <label>
+        pattern!(u32 le Call 0x9400_0000, mask 0xFC00_0000),
+        // stp x29, x30, [sp, #-N]!
+        pattern!(u32 le Prologue 0xA980_7BFD, mask 0xFFC0_7FFF),
+        // ldp x29, x30, [sp], #N
+        pattern!(u32 le Epilogue 0xA8C0_7BFD, mask 0xFFC0_7FFF),
+        // svc #0
+        pattern!(u32 le Syscall 0xD400_0001),
+    ];
+
+    static ARM: &[CanonicalPattern] = &[
+        pattern!(u32 le Nop arm::patterns::NOP),
+        pattern!(u32 le Ret arm::patterns::BX_LR),
+        // bl <label>
+        pattern!(u32 le Call 0xEB00_0000, mask 0xFF00_0000),
+        // push {..., lr}
+        pattern!(u32 le Prologue 0xE92D_4000, mask 0xFFFF_4000),
+        // pop {..., pc}
+        pattern!(u32 le Epilogue 0xE8BD_8000, mask 0xFFFF_8000),
+        pattern!(u32 le Syscall arm::patterns::SVC_BASE, mask 0xFF00_0000),
+    ];
+
+    static RISCV: &[CanonicalPattern] = &[
+        pattern!(u32 le Nop riscv::patterns::NOP),
+        pattern!(u16 le Nop riscv::patterns::C_NOP),
+        pattern!(u32 le Ret riscv::patterns::RET),
+        pattern!(u16 le Ret riscv::patterns::C_RET),
+        // jal ra, <label>
+        pattern!(u32 le Call 0x0000_00EF, mask 0x0000_0FFF),
+        // addi sp, sp, -N
+        pattern!(u32 le Prologue 0x8001_0113, mask 0x800F_FFFF),
+        // addi sp, sp, N
+        pattern!(u32 le Epilogue 0x0001_0113, mask 0x800F_FFFF),
+        pattern!(u32 le Syscall riscv::patterns::ECALL),
+    ];
+
+    static MIPS: &[CanonicalPattern] = &[
+        pattern!(u32 be Nop mips::patterns::NOP),
+        pattern!(u32 be Ret mips::patterns::JR_RA),
+        // jal <target>
+        pattern!(u32 be Call 0x0C00_0000, mask 0xFC00_0000),
+        // addiu $sp, $sp, -N
+        pattern!(u32 be Prologue 0x27BD_8000, mask 0xFFFF_8000),
+        // addiu $sp, $sp, N
+        pattern!(u32 be Epilogue 0x27BD_0000, mask 0xFFFF_8000),
+        pattern!(u32 be Syscall mips::patterns::SYSCALL),
+    ];
+
+    static PPC: &[CanonicalPattern] = &[
+        pattern!(u32 be Nop ppc::patterns::NOP),
+        pattern!(u32 be Ret ppc::patterns::BLR),
+        // bl <target>
+        pattern!(u32 be Call 0x4800_0001, mask 0xFC00_0003),
+        pattern!(u32 be Prologue ppc::patterns::MFLR_R0),
+        pattern!(u32 be Epilogue ppc::patterns::MTLR_R0),
+        pattern!(u32 be Syscall ppc::patterns::SC),
+    ];
+
+    static SPARC: &[CanonicalPattern] = &[
+        pattern!(u32 be Nop sparc::patterns::NOP),
+        pattern!(u32 be Ret sparc::patterns::RETL),
+        // call <disp30>
+        pattern!(u32 be Call 0x4000_0000, mask 0xC000_0000),
+        // save %sp, -N, %sp
+        pattern!(u32 be Prologue 0x9DE3_B000, mask 0xFFFF_F000),
+        pattern!(u32 be Epilogue sparc::patterns::RESTORE),
+        // ta <imm7>
+        pattern!(u32 be Syscall sparc::patterns::TA_0, mask 0xFFFF_FF80),
+    ];
+
+    static M68K: &[CanonicalPattern] = &[
+        pattern!(u16 be Nop m68k::patterns::NOP),
+        pattern!(u16 be Ret m68k::patterns::RTS),
+        pattern!(u16 be Call m68k::patterns::JSR_VAL, mask m68k::patterns::JSR_MASK),
+        // link an, #disp
+        pattern!(u16 be Prologue m68k::patterns::LINK_VAL, mask m68k::patterns::LINK_MASK, min_len 4),
+        pattern!(u16 be Epilogue m68k::patterns::UNLK_VAL, mask m68k::patterns::UNLK_MASK),
+        // trap #n
+        pattern!(u16 be Syscall m68k::patterns::TRAP_0, mask 0xFFF0),
+    ];
+
+    static SUPERH: &[CanonicalPattern] = &[
+        pattern!(u16 le Nop superh::patterns::NOP),
+        pattern!(u16 le Ret superh::patterns::RTS),
+        pattern!(u16 le Call superh::patterns::JSR_VAL, mask superh::patterns::JSR_MASK),
+        // sts.l pr, @-r15
+        pattern!(u16 le Prologue 0x4F22),
+        // lds.l @r15+, pr
+        pattern!(u16 le Epilogue 0x4F26),
+        pattern!(u16 le Syscall superh::patterns::TRAPA_VAL, mask superh::patterns::TRAPA_MASK),
+    ];
+
+    static S390X: &[CanonicalPattern] = &[
+        pattern!(u16 be Nop s390x::patterns::NOP_2B),
+        pattern!(u32 be Nop s390x::patterns::NOP_4B),
+        pattern!(u16 be Ret s390x::patterns::BR_R14),
+        // brasl %r14, <label>
+        pattern!(u16 be Call 0xC0E5, mask 0xFFFF, min_len 6),
+        // stmg %rN, %r15, D(%r15)
+        CanonicalPattern {
+            kind: PatternKind::Prologue,
+            bytes: &[0xEB, 0x0F, 0xF0, 0x00, 0x00, 0x24],
+            mask: &[0xFF, 0x0F, 0xF0, 0x00, 0x00, 0xFF],
+            endianness: Endianness::Big,
+            min_len: 6,
+        },
+        // lmg %rN, %r15, D(%r15)
+        CanonicalPattern {
+            kind: PatternKind::Epilogue,
+            bytes: &[0xEB, 0x0F, 0xF0, 0x00, 0x00, 0x04],
+            mask: &[0xFF, 0x0F, 0xF0, 0x00, 0x00, 0xFF],
+            endianness: Endianness::Big,
+            min_len: 6,
+        },
+        pattern!(u16 be Syscall s390x::patterns::SVC_0, mask 0xFF00),
+    ];
+
+    static AVR: &[CanonicalPattern] = &[
+        pattern!(u16 le Nop avr::patterns::NOP),
+        pattern!(u16 le Ret avr::patterns::RET),
+        pattern!(u16 le Call avr::format::CALL_VAL, mask avr::format::CALL_MASK, min_len 4),
+        pattern!(u16 le Prologue avr::patterns::PUSH_VAL, mask avr::patterns::PUSH_MASK),
+        pattern!(u16 le Epilogue avr::patterns::POP_VAL, mask avr::patterns::POP_MASK),
+    ];
+
+    static MSP430: &[CanonicalPattern] = &[
+        pattern!(u16 le Nop msp430::patterns::NOP),
+        pattern!(u16 le Ret msp430::patterns::RET),
+        pattern!(u16 le Call msp430::patterns::CALL_VAL, mask msp430::patterns::CALL_MASK),
+        pattern!(u16 le Prologue msp430::patterns::PUSH_VAL, mask msp430::patterns::PUSH_MASK),
+    ];
+
+    static LOONGARCH64: &[CanonicalPattern] = &[
+        pattern!(u32 le Nop loongarch::patterns::NOP),
+        pattern!(u32 le Ret loongarch::patterns::RET),
+        pattern!(u32 le Call loongarch::opcode::BL, mask loongarch::patterns::OPCODE_MASK_26),
+        // addi.d $sp, $sp, -N
+        pattern!(u32 le Prologue 0x02E0_0063, mask 0xFFE0_03FF),
+        // addi.d $sp, $sp, N
+        pattern!(u32 le Epilogue 0x02C0_0063, mask 0xFFE0_03FF),
+        // syscall <code>
+        pattern!(u32 le Syscall loongarch::patterns::SYSCALL_0, mask loongarch::patterns::OPCODE_MASK_17),
+    ];
+
+    static HEXAGON: &[CanonicalPattern] = &[
+        // Any parse bits
+        pattern!(u32 le Nop hexagon::patterns::NOP, mask 0xFFFF_3FFF),
+        pattern!(u32 le Ret hexagon::patterns::JUMPR_LR_VAL, mask hexagon::patterns::JUMPR_LR_MASK),
+        pattern!(u32 le Prologue hexagon::patterns::ALLOCFRAME_VAL, mask hexagon::patterns::ALLOCFRAME_MASK),
+        pattern!(u32 le Epilogue hexagon::patterns::DEALLOC_RETURN),
+    ];
+
+    static ALPHA: &[CanonicalPattern] = &[
+        pattern!(u32 le Nop alpha::ALPHA_NOP),
+        pattern!(u32 le Nop alpha::ALPHA_UNOP),
+        pattern!(u32 le Ret alpha::ALPHA_RET),
+        // bsr ra, <disp>
+        pattern!(u32 le Call 0xD340_0000, mask 0xFFE0_0000),
+        // lda sp, -N(sp)
+        pattern!(u32 le Prologue 0x23DE_8000, mask 0xFFFF_8000),
+        // lda sp, N(sp)
+        pattern!(u32 le Epilogue 0x23DE_0000, mask 0xFFFF_8000),
+        // call_pal callsys
+        pattern!(u32 le Syscall 0x0000_0083),
+    ];
+
+    /// The canonical instruction patterns of `isa`, empty for ISAs
+    /// without a catalog.
+    pub fn catalog(isa: Isa) -> &'static [CanonicalPattern] {
+        match isa {
+            Isa::X86 => X86,
+            Isa::X86_64 => X86_64,
+            Isa::AArch64 => AARCH64,
+            Isa::Arm => ARM,
+            Isa::RiscV32 | Isa::RiscV64 | Isa::RiscV128 => RISCV,
+            Isa::Mips | Isa::Mips64 => MIPS,
+            Isa::Ppc | Isa::Ppc64 => PPC,
+            Isa::Sparc | Isa::Sparc64 => SPARC,
+            Isa::M68k | Isa::ColdFire => M68K,
+            Isa::Sh | Isa::Sh4 => SUPERH,
+            Isa::S390x => S390X,
+            Isa::Avr => AVR,
+            Isa::Msp430 => MSP430,
+            Isa::LoongArch64 => LOONGARCH64,
+            Isa::Hexagon => HEXAGON,
+            Isa::Alpha => ALPHA,
+            _ => &[],
         }
     }
 
-    /// Generic RET detection
-    pub fn is_likely_ret(bytes: &[u8]) -> bool {
+    /// What the instruction at the start of `window` is, if it matches
+    /// one of `isa`'s canonical patterns.
+    pub fn classify(isa: Isa, window: &[u8]) -> Option<PatternKind> {
+        catalog(isa)
+            .iter()
+            .find(|p| p.matches(window))
+            .map(|p| p.kind)
+    }
+
+    /// ISAs whose exact NOP and return encodings the generic checks below
+    /// recognize.
+    const GENERIC_ISAS: &[Isa] = &[
+        Isa::X86,
+        Isa::AArch64,
+        Isa::Arm,
+        Isa::RiscV64,
+        Isa::Mips,
+        Isa::Ppc,
+        Isa::Sparc,
+        Isa::M68k,
+        Isa::Sh,
+    ];
+
+    /// Thumb NOP and `bx lr`; Thumb shares [`Isa::Arm`] with the A32
+    /// state, whose catalog it would make ambiguous.
+    const THUMB_NOP: u16 = 0xBF00;
+    const THUMB_BX_LR: u16 = 0x4770;
+
+    /// Whether `bytes` is exactly one of the fixed encodings of `kind`.
+    fn is_exact_generic(kind: PatternKind, bytes: &[u8]) -> bool {
+        GENERIC_ISAS
+            .iter()
+            .flat_map(|&isa| catalog(isa))
+            .any(|p| p.kind == kind && p.is_exact() && p.bytes == bytes)
+    }
+
+    /// Generic NOP detection
+    pub fn is_likely_nop(bytes: &[u8]) -> bool {
         if bytes.is_empty() {
             return false;
         }
+        bytes == THUMB_NOP.to_le_bytes() || is_exact_generic(PatternKind::Nop, bytes)
+    }
 
-        match bytes.len() {
-            1 => bytes[0] == 0xC3, // x86 RET
-            2 => {
-                let w = u16::from_le_bytes([bytes[0], bytes[1]]);
-                w == 0x4E75    // m68k RTS
-                    || w == 0x000B // SuperH RTS
-                    || w == 0x4770 // Thumb BX LR
-                    || w == 0x8082 // RISC-V C.RET
-            }
-            4 => {
-                let le = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-                let be = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-
-                le == 0xD65F03C0    // AArch64 RET
-                    || le == 0x00008067 // RISC-V RET
-                    || le == 0xE12FFF1E // ARM BX LR
-                    || be == 0x03E00008 // MIPS JR $ra
-                    || be == 0x4E800020 // PowerPC BLR
-                    || be == 0x81C3E008 // SPARC RETL
-            }
-            _ => false,
+    /// Generic RET detection
+    pub fn is_likely_ret(bytes: &[u8]) -> bool {
+        if bytes.is_empty() {
+            return false;
         }
+        bytes == THUMB_BX_LR.to_le_bytes() || is_exact_generic(PatternKind::Ret, bytes)
     }
 }
 
@@ -286,11 +653,104 @@ mod tests {
     fn test_nop_detection() {
         assert!(patterns::is_likely_nop(&[0x90]));
         assert!(patterns::is_likely_nop(&[0x1F, 0x20, 0x03, 0xD5]));
+        assert!(patterns::is_likely_nop(&[0x4E, 0x71])); // m68k NOP
+        assert!(patterns::is_likely_nop(&[0x09, 0x00])); // SuperH NOP
+        assert!(patterns::is_likely_nop(&[0x00, 0xBF])); // Thumb NOP
+        assert!(patterns::is_likely_nop(&[0x60, 0x00, 0x00, 0x00])); // PowerPC NOP
+        assert!(!patterns::is_likely_nop(&[0x90, 0x90]));
+        assert!(!patterns::is_likely_nop(&[]));
     }
 
     #[test]
     fn test_ret_detection() {
         assert!(patterns::is_likely_ret(&[0xC3]));
         assert!(patterns::is_likely_ret(&[0xC0, 0x03, 0x5F, 0xD6]));
+        assert!(patterns::is_likely_ret(&[0x4E, 0x75])); // m68k RTS
+        assert!(patterns::is_likely_ret(&[0x70, 0x47])); // Thumb BX LR
+        assert!(patterns::is_likely_ret(&[0x81, 0xC3, 0xE0, 0x08])); // SPARC RETL
+        assert!(!patterns::is_likely_ret(&[0xC3, 0x00]));
+    }
+
+    /// ISAs with a pattern catalog.
+    const CATALOGED: &[Isa] = &[
+        Isa::X86,
+        Isa::X86_64,
+        Isa::AArch64,
+        Isa::Arm,
+        Isa::RiscV32,
+        Isa::RiscV64,
+        Isa::Mips,
+        Isa::Mips64,
+        Isa::Ppc,
+        Isa::Ppc64,
+        Isa::Sparc,
+        Isa::Sparc64,
+        Isa::M68k,
+        Isa::Sh,
+        Isa::S390x,
+        Isa::Avr,
+        Isa::Msp430,
+        Isa::LoongArch64,
+        Isa::Hexagon,
+        Isa::Alpha,
+    ];
+
+    #[test]
+    fn test_pattern_catalog_entries() {
+        for &isa in CATALOGED {
+            let catalog = patterns::catalog(isa);
+            assert!(!catalog.is_empty(), "{isa:?}");
+            for p in catalog {
+                assert_eq!(p.endianness, default_endianness(isa), "{isa:?} {p:?}");
+                assert_eq!(p.bytes.len(), p.mask.len(), "{isa:?} {p:?}");
+                assert!(p.min_len >= p.bytes.len(), "{isa:?} {p:?}");
+                // Bits outside the mask are left clear
+                assert!(
+                    p.bytes.iter().zip(p.mask).all(|(&b, &m)| b & !m == 0),
+                    "{isa:?} {p:?}"
+                );
+            }
+        }
+        assert!(patterns::catalog(Isa::Wasm).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_catalog_round_trip() {
+        for &isa in CATALOGED {
+            for p in patterns::catalog(isa) {
+                let mut window = p.bytes.to_vec();
+                window.resize(p.min_len, 0);
+                assert!(p.matches(&window), "{isa:?} {p:?}");
+                assert_eq!(
+                    patterns::classify(isa, &window),
+                    Some(p.kind),
+                    "{isa:?} {p:?}"
+                );
+                // Too short to hold the whole instruction
+                assert!(!p.matches(&window[..p.min_len - 1]), "{isa:?} {p:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pattern_kinds_disjoint() {
+        for &isa in CATALOGED {
+            let catalog = patterns::catalog(isa);
+            for (i, a) in catalog.iter().enumerate() {
+                for b in &catalog[i + 1..] {
+                    if a.kind == b.kind {
+                        continue;
+                    }
+                    // Some byte both patterns fix must differ
+                    let disjoint = a
+                        .bytes
+                        .iter()
+                        .zip(a.mask)
+                        .zip(b.bytes.iter().zip(b.mask))
+                        .any(|((&ab, &am), (&bb, &bm))| (ab ^ bb) & am & bm != 0);
+                    assert!(disjoint, "{isa:?}: {a:?} overlaps {b:?}");
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,421 @@
+//! Heuristic scoring for the MOS 6502 and WDC 65816.
+//!
+//! The 6502 is an 8-bit little-endian CPU with 16-bit addresses, found in
+//! the NES, Commodore 64, Apple II, Atari 8-bit machines and countless
+//! embedded boards. Instructions are one to three bytes, byte-aligned: an
+//! opcode followed by an 8-bit immediate, zero-page address or branch
+//! displacement, or a 16-bit little-endian absolute address. Only 151 of
+//! the 256 opcodes are documented; the rest are undocumented or jam the
+//! CPU and compilers and assemblers do not emit them.
+//!
+//! The 65816 (SNES, Apple IIGS) fills every remaining slot: 24-bit long
+//! addressing (`JSL`, `JML`, `LDA long`), stack-relative modes, block
+//! moves, and `REP`/`SEP`, which switch the accumulator and index
+//! registers between 8 and 16 bits. Immediate operands follow that width,
+//! so the decoder tracks it through `REP`/`SEP` the way the CPU does.
+//!
+//! The scorers lean on what 6502 code is full of: `JSR`/`RTS` pairs,
+//! immediate, zero-page and absolute loads and stores, relative branches
+//! and the reset/NMI/IRQ vector table at the top of the address space.
+//! The 65816 scorer additionally requires native-mode idioms (`REP`,
+//! `SEP`, `XCE`, `JSL`/`RTL`) so plain 6502 code is not claimed by its
+//! superset.
+
+use std::cmp;
+
+/// Common 6502 and 65816 opcodes.
+pub mod opcodes {
+    /// `BRK`
+    pub const BRK: u8 = 0x00;
+    /// `JSR abs`
+    pub const JSR: u8 = 0x20;
+    /// `RTI`
+    pub const RTI: u8 = 0x40;
+    /// `JMP abs`
+    pub const JMP: u8 = 0x4C;
+    /// `RTS`
+    pub const RTS: u8 = 0x60;
+    /// `JMP (abs)`
+    pub const JMP_IND: u8 = 0x6C;
+    /// `LDA #imm`
+    pub const LDA_IMM: u8 = 0xA9;
+    /// `LDA zp`
+    pub const LDA_ZP: u8 = 0xA5;
+    /// `LDA abs`
+    pub const LDA_ABS: u8 = 0xAD;
+    /// `NOP`
+    pub const NOP: u8 = 0xEA;
+    /// `JSL long` (65816)
+    pub const JSL: u8 = 0x22;
+    /// `JML long` (65816)
+    pub const JML: u8 = 0x5C;
+    /// `RTL` (65816)
+    pub const RTL: u8 = 0x6B;
+    /// `REP #imm` (65816)
+    pub const REP: u8 = 0xC2;
+    /// `SEP #imm` (65816)
+    pub const SEP: u8 = 0xE2;
+    /// `XCE` (65816)
+    pub const XCE: u8 = 0xFB;
+}
+
+use opcodes::{
+    BRK, JML, JMP, JMP_IND, JSL, JSR, LDA_ABS, LDA_IMM, LDA_ZP, NOP, REP, RTI, RTL, RTS, SEP, XCE,
+};
+
+/// Instruction lengths of the documented NMOS 6502 opcodes; 0 marks an
+/// undocumented opcode.
+#[rustfmt::skip]
+const NMOS_LEN: [u8; 256] = [
+    1, 2, 0, 0, 0, 2, 2, 0, 1, 2, 1, 0, 0, 3, 3, 0, // 0x
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // 1x
+    3, 2, 0, 0, 2, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // 2x
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // 3x
+    1, 2, 0, 0, 0, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // 4x
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // 5x
+    1, 2, 0, 0, 0, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // 6x
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // 7x
+    0, 2, 0, 0, 2, 2, 2, 0, 1, 0, 1, 0, 3, 3, 3, 0, // 8x
+    2, 2, 0, 0, 2, 2, 2, 0, 1, 3, 1, 0, 0, 3, 0, 0, // 9x
+    2, 2, 2, 0, 2, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // Ax
+    2, 2, 0, 0, 2, 2, 2, 0, 1, 3, 1, 0, 3, 3, 3, 0, // Bx
+    2, 2, 0, 0, 2, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // Cx
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // Dx
+    2, 2, 0, 0, 2, 2, 2, 0, 1, 2, 1, 0, 3, 3, 3, 0, // Ex
+    2, 2, 0, 0, 0, 2, 2, 0, 1, 3, 0, 0, 0, 3, 3, 0, // Fx
+];
+
+/// Instruction lengths of the 65816 with 8-bit accumulator and index
+/// registers.
+#[rustfmt::skip]
+const W65816_LEN: [u8; 256] = [
+    1, 2, 2, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // 0x
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // 1x
+    3, 2, 4, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // 2x
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // 3x
+    1, 2, 2, 2, 3, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // 4x
+    2, 2, 2, 2, 3, 2, 2, 2, 1, 3, 1, 1, 4, 3, 3, 4, // 5x
+    1, 2, 3, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // 6x
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // 7x
+    2, 2, 3, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // 8x
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // 9x
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // Ax
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // Bx
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // Cx
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // Dx
+    2, 2, 2, 2, 2, 2, 2, 2, 1, 2, 1, 1, 3, 3, 3, 4, // Ex
+    2, 2, 2, 2, 3, 2, 2, 2, 1, 3, 1, 1, 3, 3, 3, 4, // Fx
+];
+
+/// Status register bit selecting an 8-bit accumulator.
+const FLAG_M: u8 = 0x20;
+/// Status register bit selecting 8-bit index registers.
+const FLAG_X: u8 = 0x10;
+
+/// Length of an instruction, or `None` for an undocumented 6502 opcode.
+///
+/// `wide_a`/`wide_xy` select 16-bit immediates on the 65816.
+fn insn_len(op: u8, native: bool, wide_a: bool, wide_xy: bool) -> Option<usize> {
+    if !native {
+        return match NMOS_LEN[usize::from(op)] {
+            0 => None,
+            len => Some(usize::from(len)),
+        };
+    }
+    let len = usize::from(W65816_LEN[usize::from(op)]);
+    Some(match op {
+        // ORA/AND/EOR/ADC/BIT/LDA/CMP/SBC #imm
+        0x09 | 0x29 | 0x49 | 0x69 | 0x89 | 0xA9 | 0xC9 | 0xE9 if wide_a => 3,
+        // LDY/LDX/CPY/CPX #imm
+        0xA0 | 0xA2 | 0xC0 | 0xE0 if wide_xy => 3,
+        _ => len,
+    })
+}
+
+/// Whether the three 16-bit vectors (NMI, RESET, IRQ/BRK) at the end of
+/// the input point into the upper half of the address space, where
+/// cartridge and system ROMs are mapped.
+fn has_vector_table(data: &[u8]) -> bool {
+    let Some(table) = data.len().checked_sub(6).map(|start| &data[start..]) else {
+        return false;
+    };
+    let vectors: Vec<u16> = table
+        .chunks_exact(2)
+        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+        .collect();
+    vectors.iter().all(|&v| (0x8000..0xFFFF).contains(&v))
+}
+
+/// Shared decoder for both CPUs.
+fn score_impl(data: &[u8], native: bool) -> i64 {
+    if data.len() < 8 {
+        return 0;
+    }
+
+    // Text decodes as a mix of undocumented opcodes and ALU instructions;
+    // real images are far from all printable
+    let printable = data
+        .iter()
+        .filter(|&&b| (0x20..0x7F).contains(&b) || matches!(b, b'\t' | b'\n' | b'\r'))
+        .count();
+    if printable * 10 > data.len() * 9 {
+        return 0;
+    }
+
+    let mut score: i64 = 0;
+    let mut i = 0;
+    let mut pad_run = 0u32;
+    let mut rets = 0u32;
+    let mut calls = 0u32;
+    let mut stack_ops = 0u32;
+    let mut insns = 0u32;
+    let mut native_ops = 0u32;
+    // Reset state: emulation mode, 8-bit registers
+    let mut wide_a = false;
+    let mut wide_xy = false;
+
+    while i < data.len() {
+        let op = data[i];
+
+        if matches!(op, BRK | NOP | 0xFF) && data.get(i + 1) == Some(&op) {
+            pad_run += 1;
+            if pad_run > 2 {
+                score -= 2;
+            }
+            i += 1;
+            continue;
+        }
+        pad_run = 0;
+
+        let Some(len) = insn_len(op, native, wide_a, wide_xy) else {
+            // Undocumented opcode: a jam or an unstable combination
+            score -= 4;
+            i += 1;
+            continue;
+        };
+        if i + len > data.len() {
+            break;
+        }
+        insns += 1;
+
+        let points = match op {
+            RTS => {
+                rets += 1;
+                6
+            }
+            JSR => {
+                calls += 1;
+                6
+            }
+            RTI => 4,
+            JMP => 3,
+            JMP_IND => 2,
+            // BPL/BMI/BVC/BVS/BCC/BCS/BNE/BEQ
+            0x10 | 0x30 | 0x50 | 0x70 | 0x90 | 0xB0 | 0xD0 | 0xF0 => 2,
+            // LDA/LDX/LDY immediate, zero page and absolute
+            LDA_IMM | LDA_ZP | LDA_ABS | 0xA0 | 0xA2 | 0xA4 | 0xA6 | 0xAC | 0xAE => 2,
+            // STA/STX/STY zero page and absolute
+            0x85 | 0x8D | 0x84 | 0x8C | 0x86 | 0x8E => 2,
+            // LDA/STA abs,X / abs,Y / (zp),Y
+            0xBD | 0xB9 | 0xB1 | 0x9D | 0x99 | 0x91 => 2,
+            // PHA/PLA/PHP/PLP
+            0x48 | 0x68 | 0x08 | 0x28 => {
+                stack_ops += 1;
+                2
+            }
+            // CLC/SEC/CLI/SEI/CLD, register transfers, INX/INY/DEX/DEY
+            0x18 | 0x38 | 0x58 | 0x78 | 0xD8 => 1,
+            0xAA | 0xA8 | 0x8A | 0x98 | 0x9A | 0xBA | 0xE8 | 0xC8 | 0xCA | 0x88 => 1,
+            // CMP/CPX/CPY immediate, INC/DEC zero page and absolute
+            0xC9 | 0xE0 | 0xC0 | 0xE6 | 0xC6 | 0xEE | 0xCE => 1,
+            _ if !native => 0,
+            REP | SEP => {
+                let flags = data[i + 1];
+                if flags & !(FLAG_M | FLAG_X | 0x01 | 0x08) != 0 || flags == 0 {
+                    // Only width, carry and decimal are switched in practice
+                    0
+                } else {
+                    native_ops += 1;
+                    let wide = op == REP;
+                    if flags & FLAG_M != 0 {
+                        wide_a = wide;
+                    }
+                    if flags & FLAG_X != 0 {
+                        wide_xy = wide;
+                    }
+                    5
+                }
+            }
+            XCE => {
+                native_ops += 1;
+                4
+            }
+            JSL => {
+                calls += 1;
+                native_ops += 1;
+                6
+            }
+            RTL => {
+                rets += 1;
+                native_ops += 1;
+                6
+            }
+            JML => 3,
+            // PHB/PLB/PHD/PLD/PHK/PHX/PHY/PLX/PLY
+            0x8B | 0xAB | 0x0B | 0x2B | 0x4B | 0xDA | 0x5A | 0xFA | 0x7A => {
+                stack_ops += 1;
+                2
+            }
+            // BRA, STZ, LDA/STA long, TCD/TCS
+            0x80 | 0x64 | 0x74 | 0x9C | 0x9E | 0xAF | 0x8F | 0x5B | 0x1B => 2,
+            // COP, WDM, STP: never in ordinary code
+            0x02 | 0x42 | 0xDB => -3,
+            _ => 0,
+        };
+        score += points;
+        i += len;
+    }
+
+    // Subroutines are the backbone of 6502 code; input without calls and
+    // returns is something else
+    if rets == 0 || calls == 0 {
+        return 0;
+    }
+    if rets > 4 && calls > 4 && stack_ops > 4 {
+        score += i64::from(rets.min(calls) + stack_ops) * 2;
+    }
+    if has_vector_table(data) {
+        score += 20;
+    }
+    // Without native-mode idioms the input is plain 6502 code, which
+    // the 65816 runs unchanged
+    if native && native_ops * 100 < insns {
+        score /= 2;
+    }
+
+    cmp::max(0, score)
+}
+
+/// Score raw data as MOS 6502 code.
+pub fn score(data: &[u8]) -> i64 {
+    score_impl(data, false)
+}
+
+/// Score raw data as WDC 65816 code.
+pub fn score_65816(data: &[u8]) -> i64 {
+    score_impl(data, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NES style reset handler, NMI handler and a controller read
+    /// subroutine.
+    const NES_ROUTINES: &[u8] = &[
+        0x78, 0xD8, 0xA2, 0x40, 0x8E, 0x17, 0x40, // SEI; CLD; LDX #$40; STX $4017
+        0xA2, 0xFF, 0x9A, 0xE8, 0x8E, 0x00, 0x20, // LDX #$FF; TXS; INX; STX $2000
+        0x8E, 0x01, 0x20, 0x8E, 0x10, 0x40, // STX $2001; STX $4010
+        0x2C, 0x02, 0x20, 0x10, 0xFB, // BIT $2002; BPL -5
+        0xA9, 0x00, 0x9D, 0x00, 0x02, 0xE8, 0xD0, 0xFA, // LDA #0; STA $0200,X; INX; BNE
+        0x20, 0x40, 0x80, 0x4C, 0x20, 0x80, // JSR read_pad; JMP main
+        0x48, 0x8A, 0x48, 0x98, 0x48, // NMI: PHA; TXA; PHA; TYA; PHA
+        0xA9, 0x02, 0x8D, 0x14, 0x40, // LDA #$02; STA $4014
+        0x20, 0x40, 0x80, 0xE6, 0x10, // JSR read_pad; INC $10
+        0x68, 0xA8, 0x68, 0xAA, 0x68, 0x40, // PLA; TAY; PLA; TAX; PLA; RTI
+        0xA9, 0x01, 0x8D, 0x16, 0x40, 0xA9, 0x00, // read_pad: LDA #1; STA $4016; LDA #0
+        0x8D, 0x16, 0x40, 0xA2, 0x08, // STA $4016; LDX #8
+        0xAD, 0x16, 0x40, 0x4A, 0x26, 0x11, // LDA $4016; LSR A; ROL $11
+        0xCA, 0xD0, 0xF7, 0x60, // DEX; BNE; RTS
+    ];
+
+    /// NMI, RESET and IRQ vectors at $FFFA.
+    const NES_VECTORS: &[u8] = &[0x2D, 0x80, 0x00, 0x80, 0x00, 0x80];
+
+    /// SNES style native-mode start-up and a long subroutine.
+    const SNES_ROUTINES: &[u8] = &[
+        0x78, 0x18, 0xFB, // SEI; CLC; XCE
+        0xC2, 0x30, 0xA2, 0xFF, 0x1F, 0x9A, // REP #$30; LDX #$1FFF; TXS
+        0xA9, 0x00, 0x00, 0x5B, // LDA #$0000; TCD
+        0xE2, 0x20, 0xA9, 0x80, 0x8D, 0x00, 0x21, // SEP #$20; LDA #$80; STA $2100
+        0x22, 0x20, 0x80, 0x00, // JSL $008020
+        0x20, 0x30, 0x80, 0x5C, 0x00, 0x80, 0x00, // JSR sub; JML $008000
+        0x8B, 0x4B, 0xAB, 0xC2, 0x20, // PHB; PHK; PLB; REP #$20
+        0xA5, 0x10, 0x18, 0x69, 0x01, 0x00, 0x85, 0x10, // LDA $10; CLC; ADC #$0001; STA $10
+        0xE2, 0x20, 0xAB, 0x6B, // SEP #$20; PLB; RTL
+        0xDA, 0x5A, 0xA9, 0x0F, 0x8D, 0x00, 0x21, // PHX; PHY; LDA #$0F; STA $2100
+        0x9C, 0x0B, 0x42, 0x7A, 0xFA, 0x60, // STZ $420B; PLY; PLX; RTS
+    ];
+
+    #[test]
+    fn test_instruction_lengths() {
+        assert_eq!(insn_len(JSR, false, false, false), Some(3));
+        assert_eq!(insn_len(RTS, false, false, false), Some(1));
+        assert_eq!(insn_len(LDA_IMM, false, false, false), Some(2));
+        assert_eq!(insn_len(REP, false, false, false), None);
+        assert_eq!(insn_len(JSL, true, false, false), Some(4));
+        assert_eq!(insn_len(LDA_IMM, true, true, false), Some(3));
+        assert_eq!(insn_len(0xA2, true, true, false), Some(2));
+        assert_eq!(insn_len(0xA2, true, false, true), Some(3));
+        let documented = NMOS_LEN.iter().filter(|&&len| len != 0).count();
+        assert_eq!(documented, 151);
+        assert!(W65816_LEN.iter().all(|&len| len != 0));
+    }
+
+    #[test]
+    fn test_vector_table() {
+        let mut code = NES_ROUTINES.to_vec();
+        assert!(!has_vector_table(&code));
+        code.extend_from_slice(NES_VECTORS);
+        assert!(has_vector_table(&code));
+        assert!(score(&code) > score(NES_ROUTINES));
+    }
+
+    #[test]
+    fn test_nes_routines() {
+        let mut code = NES_ROUTINES.repeat(64);
+        code.extend_from_slice(NES_VECTORS);
+        assert!(score(NES_ROUTINES) > 50, "scored {}", score(NES_ROUTINES));
+        assert!(score(&code) > score_65816(&code) * 3 / 2);
+        let x86 = crate::architectures::x86::score(&code, 32);
+        assert!(score(&code) > x86, "{} vs x86 {}", score(&code), x86);
+    }
+
+    #[test]
+    fn test_snes_routines() {
+        let code = SNES_ROUTINES.repeat(64);
+        assert!(
+            score_65816(&code) > score(&code) * 3 / 2,
+            "{} vs 6502 {}",
+            score_65816(&code),
+            score(&code)
+        );
+    }
+
+    #[test]
+    fn test_rejects_text_and_padding() {
+        let text = b"The quick brown fox jumps over the lazy dog. \
+            Pack my box with five dozen liquor jugs.\n"
+            .repeat(40);
+        assert_eq!(score(&text), 0);
+        assert_eq!(score_65816(&text), 0);
+        for fill in [0x00, 0xEA, 0xFF] {
+            assert_eq!(score(&[fill; 1024]), 0);
+            assert_eq!(score_65816(&[fill; 1024]), 0);
+        }
+    }
+
+    #[test]
+    fn test_code_classified_as_6502() {
+        let options = crate::types::ClassifierOptions::new();
+        let mut nes = NES_ROUTINES.repeat(64);
+        nes.extend_from_slice(NES_VECTORS);
+        let result = crate::heuristics::analyze(&nes, &options).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::Mcs6502);
+        assert_eq!(result.endianness, crate::types::Endianness::Little);
+
+        let snes = SNES_ROUTINES.repeat(64);
+        let result = crate::heuristics::analyze(&snes, &options).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::W65816);
+    }
+}
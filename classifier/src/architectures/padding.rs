@@ -0,0 +1,478 @@
+//! Inter-function padding idioms.
+//!
+//! Compilers align function entries (usually to 16 or 32 bytes) and fill the
+//! gap after the previous function with an ISA-specific filler: `INT3` or the
+//! recommended multi-byte `NOP` forms on x86, `NOP` words on AArch64, ARM and
+//! RISC-V. A padding run that ends right where a function-entry idiom begins
+//! is strong evidence for the ISA. A padding run on its own is not: an
+//! all-`0xCC` buffer is not x86 code, so scorers treat bare padding as
+//! neutral rather than rewarding or penalizing it.
+//!
+//! The padding-aware scorers report the padding bytes they skip, which are
+//! excluded from the normalization denominator. [`scan`] measures padding
+//! on its own and estimates an input's function count from prologue
+//! sightings.
+
+use super::{aarch64, arm, riscv};
+use crate::types::Isa;
+
+/// Instruction encoding whose padding idioms to look for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingKind {
+    /// x86 and x86-64 (`INT3`, `NOP` and multi-byte `NOP` forms).
+    X86,
+    /// AArch64 (`NOP` words).
+    AArch64,
+    /// ARM A32 (`NOP` hint and `MOV r0, r0`).
+    Arm,
+    /// Thumb/Thumb-2 (`NOP`, `MOV r8, r8`, `NOP.W`).
+    Thumb,
+    /// RISC-V (`NOP` and `C.NOP`).
+    RiscV,
+}
+
+impl PaddingKind {
+    /// Kinds to scan for `isa`; ARM code may be either A32 or Thumb.
+    pub fn for_isa(isa: Isa) -> &'static [PaddingKind] {
+        match isa {
+            Isa::X86 | Isa::X86_64 => &[PaddingKind::X86],
+            Isa::AArch64 => &[PaddingKind::AArch64],
+            Isa::Arm => &[PaddingKind::Arm, PaddingKind::Thumb],
+            Isa::RiscV32 | Isa::RiscV64 => &[PaddingKind::RiscV],
+            _ => &[],
+        }
+    }
+
+    /// Widths a padding unit can have, used to look one unit back.
+    fn unit_widths(self) -> &'static [usize] {
+        match self {
+            PaddingKind::X86 => &[1],
+            PaddingKind::AArch64 | PaddingKind::Arm => &[4],
+            PaddingKind::Thumb | PaddingKind::RiscV => &[2, 4],
+        }
+    }
+}
+
+/// Position of a padding unit relative to the surrounding code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaddingRole {
+    /// A lone padding unit between ordinary instructions.
+    Isolated,
+    /// Part of a padding run that does not lead into a function entry.
+    Run,
+    /// Last unit of a padding run followed by a function-entry idiom.
+    BeforeEntry,
+}
+
+/// Padding and function-boundary statistics for one input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaddingStats {
+    /// Bytes inside padding runs.
+    pub padding_bytes: usize,
+    /// Estimated number of functions (padded entries plus stand-alone
+    /// prologues).
+    pub functions: u32,
+    /// Functions whose entry directly follows a padding run.
+    pub padded_functions: u32,
+}
+
+/// Longest run of `0x66`/`0x2E` prefixes GCC and LLVM put on a `NOPW`.
+const X86_MAX_NOP_PREFIXES: usize = 6;
+
+/// Bytes after an entry idiom in which another prologue is the same function.
+const ENTRY_WINDOW: usize = 8;
+
+fn u16_at(data: &[u8], off: usize) -> Option<u16> {
+    data.get(off..off + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    data.get(off..off + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Length of the x86 padding instruction at the start of `data`, or 0.
+fn x86_unit_len(data: &[u8]) -> usize {
+    let prefixes = data
+        .iter()
+        .take(X86_MAX_NOP_PREFIXES)
+        .take_while(|&&b| b == 0x66 || b == 0x2E)
+        .count();
+    let body_len = match &data[prefixes..] {
+        [0xCC, ..] if prefixes == 0 => 1,
+        // NOP / XCHG AX, AX
+        [0x90, ..] => 1,
+        // NOP r/m with a zero displacement (Intel SDM recommended forms)
+        [0x0F, 0x1F, 0x00, ..] => 3,
+        [0x0F, 0x1F, 0x40, 0x00, ..] => 4,
+        [0x0F, 0x1F, 0x44, 0x00, 0x00, ..] => 5,
+        [0x0F, 0x1F, 0x80, 0x00, 0x00, 0x00, 0x00, ..] => 7,
+        [0x0F, 0x1F, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00, ..] => 8,
+        _ if prefixes > 0 => return 0,
+        // 32-bit no-op fillers from older assemblers (MOV/LEA of a register to itself)
+        [0x89, 0xF6, ..] => 2,
+        [0x8D, 0x76, 0x00, ..] => 3,
+        [0x8D, 0x74, 0x26, 0x00, ..] => 4,
+        [0x8D, 0xB6 | 0xBF, 0x00, 0x00, 0x00, 0x00, ..] => 6,
+        [0x8D, 0xB4, 0x26, 0x00, 0x00, 0x00, 0x00, ..]
+        | [0x8D, 0xBC, 0x27, 0x00, 0x00, 0x00, 0x00, ..] => 7,
+        _ => return 0,
+    };
+    prefixes + body_len
+}
+
+/// Length of the padding unit at the start of `data` for `kind`, or 0.
+pub fn unit_len(kind: PaddingKind, data: &[u8]) -> usize {
+    match kind {
+        PaddingKind::X86 => x86_unit_len(data),
+        PaddingKind::AArch64 => match u32_at(data, 0) {
+            Some(aarch64::system::NOP) => 4,
+            _ => 0,
+        },
+        PaddingKind::Arm => match u32_at(data, 0) {
+            Some(arm::patterns::NOP_HINT | arm::patterns::NOP) => 4,
+            _ => 0,
+        },
+        PaddingKind::Thumb => match (u16_at(data, 0), u16_at(data, 2)) {
+            (Some(0xF3AF), Some(0x8000)) => 4,
+            // NOP / MOV r8, r8
+            (Some(0xBF00 | 0x46C0), _) => 2,
+            _ => 0,
+        },
+        PaddingKind::RiscV => {
+            if u32_at(data, 0) == Some(riscv::patterns::NOP) {
+                4
+            } else if u16_at(data, 0) == Some(riscv::patterns::C_NOP) {
+                2
+            } else {
+                0
+            }
+        }
+    }
+}
+
+/// Total length of the consecutive padding units at the start of `data`.
+pub fn run_len(kind: PaddingKind, data: &[u8]) -> usize {
+    let mut len = 0;
+    loop {
+        match unit_len(kind, &data[len..]) {
+            0 => return len,
+            n => len += n,
+        }
+    }
+}
+
+/// Whether `data` starts with an instruction that typically opens a
+/// function after alignment padding.
+pub fn is_entry(kind: PaddingKind, data: &[u8]) -> bool {
+    match kind {
+        PaddingKind::X86 => matches!(
+            data,
+            // ENDBR64 / ENDBR32
+            [0xF3, 0x0F, 0x1E, 0xFA | 0xFB, ..]
+                // PUSH rBP / rBX / rSI / rDI, PUSH r12-r15
+                | [0x55 | 0x53 | 0x56 | 0x57, ..]
+                | [0x41, 0x54..=0x57, ..]
+                // SUB rSP, imm
+                | [0x48, 0x81 | 0x83, 0xEC, ..]
+                | [0x81 | 0x83, 0xEC, ..]
+        ),
+        PaddingKind::AArch64 => u32_at(data, 0).is_some_and(|w| {
+            is_strong_prologue(kind, data)
+                || w == aarch64::system::BTI_C
+                || w == aarch64::system::BTI_JC
+                // SUB sp, sp, #imm
+                || (w & 0xFF80_03FF) == 0xD100_03FF
+        }),
+        PaddingKind::Arm | PaddingKind::Thumb | PaddingKind::RiscV => {
+            is_strong_prologue(kind, data)
+        }
+    }
+}
+
+/// Operand size implied by the x86 entry idiom at the start of `data`:
+/// `ENDBR64` and REX-prefixed forms are 64-bit only, `ENDBR32` and
+/// `SUB esp` 32-bit only, and a plain `PUSH` either.
+pub fn x86_entry_bits(data: &[u8]) -> Option<u8> {
+    match data {
+        [0xF3, 0x0F, 0x1E, 0xFA, ..] | [0x41 | 0x48, ..] => Some(64),
+        [0xF3, 0x0F, 0x1E, 0xFB, ..] | [0x81 | 0x83, 0xEC, ..] => Some(32),
+        _ => None,
+    }
+}
+
+/// Whether `data` starts with a prologue distinctive enough to count as a
+/// function without surrounding padding.
+fn is_strong_prologue(kind: PaddingKind, data: &[u8]) -> bool {
+    match kind {
+        PaddingKind::X86 => super::x86::is_prologue(data),
+        PaddingKind::AArch64 => u32_at(data, 0).is_some_and(|w| {
+            // STP x29, x30, [sp, #-N]!
+            (w & 0xFFC0_7FFF) == 0xA980_7BFD || w == aarch64::system::PACIASP
+        }),
+        PaddingKind::Arm => u32_at(data, 0).is_some_and(|w| {
+            // PUSH {.., lr} / STR lr, [sp, #-4]!
+            (w & 0xFFFF_4000) == 0xE92D_4000 || w == 0xE52D_E004
+        }),
+        PaddingKind::Thumb => match (u16_at(data, 0), u16_at(data, 2)) {
+            // PUSH.W {.., lr}
+            (Some(0xE92D), Some(hw2)) if hw2 & 0x4000 != 0 => true,
+            // PUSH {.., lr}
+            (Some(hw), _) => (hw & 0xFF00) == 0xB500,
+            _ => false,
+        },
+        PaddingKind::RiscV => {
+            // ADDI sp, sp, -N
+            if u32_at(data, 0).is_some_and(|w| (w & 0x800F_FFFF) == 0x8001_0113) {
+                return true;
+            }
+            // C.ADDI sp, -N / C.ADDI16SP -N
+            u16_at(data, 0)
+                .is_some_and(|h| h & 0x1000 != 0 && matches!(h & 0xEF83, 0x0101 | 0x6101))
+        }
+    }
+}
+
+/// Whether the unit ending at `off` is padding.
+fn follows_padding(kind: PaddingKind, data: &[u8], off: usize) -> bool {
+    kind.unit_widths()
+        .iter()
+        .any(|&w| off >= w && unit_len(kind, &data[off - w..]) == w)
+}
+
+/// Role of the padding unit at `off`, or `None` if `off` is not padding.
+///
+/// Intended for scorers of fixed-width encodings that visit one unit at a
+/// time: the last unit of a run leading into a function entry is reported
+/// as [`PaddingRole::BeforeEntry`], other units of a run as
+/// [`PaddingRole::Run`].
+pub fn role(kind: PaddingKind, data: &[u8], off: usize) -> Option<PaddingRole> {
+    let len = unit_len(kind, data.get(off..)?);
+    if len == 0 {
+        return None;
+    }
+    let next = &data[off + len..];
+    if unit_len(kind, next) > 0 {
+        Some(PaddingRole::Run)
+    } else if is_entry(kind, next) {
+        Some(PaddingRole::BeforeEntry)
+    } else if follows_padding(kind, data, off) {
+        Some(PaddingRole::Run)
+    } else {
+        Some(PaddingRole::Isolated)
+    }
+}
+
+/// Length of the x86 padding run at `off` if it leads into a function entry.
+///
+/// A single filler byte only counts after a `RET`, since `0x90` and `0xCC`
+/// also occur inside other instructions.
+pub fn x86_padded_entry(data: &[u8], off: usize) -> Option<usize> {
+    let run = run_len(PaddingKind::X86, &data[off..]);
+    let after_ret = off > 0 && data[off - 1] == 0xC3;
+    (run > 0 && (run >= 2 || after_ret) && is_entry(PaddingKind::X86, &data[off + run..]))
+        .then_some(run)
+}
+
+/// Measure padding and estimate the function count of `data` as `kind`.
+pub fn scan(kind: PaddingKind, data: &[u8]) -> PaddingStats {
+    let mut stats = PaddingStats::default();
+    let step = kind.unit_widths()[0];
+    let mut entry_end = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        let run = run_len(kind, &data[i..]);
+        if run == 0 {
+            if i >= entry_end && is_strong_prologue(kind, &data[i..]) {
+                stats.functions += 1;
+                entry_end = i + ENTRY_WINDOW;
+            }
+            i += step;
+            continue;
+        }
+
+        let padded = match kind {
+            PaddingKind::X86 => x86_padded_entry(data, i).is_some(),
+            _ => is_entry(kind, &data[i + run..]),
+        };
+        if padded {
+            stats.functions += 1;
+            stats.padded_functions += 1;
+            entry_end = i + run + ENTRY_WINDOW;
+        }
+        // A lone filler unit is ordinary code; longer runs are alignment
+        let bare_run = match kind {
+            PaddingKind::X86 => run >= 2,
+            _ => run > unit_len(kind, &data[i..]),
+        };
+        if padded || bare_run {
+            stats.padding_bytes += run;
+        }
+        i += run;
+    }
+
+    stats
+}
+
+/// Estimated function count of `data` as `isa`, or `None` if `isa` has no
+/// padding idioms or no prologue was seen.
+pub fn estimated_functions(isa: Isa, data: &[u8]) -> Option<u32> {
+    PaddingKind::for_isa(isa)
+        .iter()
+        .map(|&kind| scan(kind, data).functions)
+        .max()
+        .filter(|&n| n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ClassifierOptions, MetadataKey};
+
+    /// First 2 KiB of `.text` from 20 small C functions built with
+    /// `gcc -O2 -falign-functions=32 -fcf-protection` (x86-64).
+    const X86_64_O2_TEXT: &[&str] = &[
+        "f30f1efa4885f67417488d14f731c0904803074883c7084839d775f4c30f1f0031c0c366662e0f1f8400000000006666",
+        "2e0f1f8400000000000f1f8000000000f30f1efaeb12662e0f1f840000000000397708740b488b3f4885ff75f331c0c3",
+        "b801000000c366662e0f1f84000000000066662e0f1f8400000000000f1f4000f30f1efaeb12662e0f1f840000000000",
+        "3977087413488b3f4885ff75f348c7c0ffffffffc30f1f00488b4710c366662e0f1f8400000000000f1f840000000000",
+        "f30f1efa4885f6742a4883ee01742431c00f1f80000000000fb614070fb60c37880c074883c0018814374883ee014839",
+        "f072e5c366662e0f1f84000000000090f30f1efa0fb617b80515000084d2741889c14883c701c1e10501c801d00fb617",
+        "84d275ecc30f1f00c366662e0f1f84000000000066662e0f1f84000000000090f30f1efa4885ff741f534889fb0f1f00",
+        "488b7b10e800000000488b1b4885db75ef5bc30f1f440000c366662e0f1f84000000000066662e0f1f84000000000090",
+        "f30f1efa488b064839070f9fc00f9cc20fb6d20fb6c029d0c30f1f8000000000f30f1efa41574156415541544189fc55",
+        "5331db4883ec7883ff010f8e6d0300008d47ff4189fd31db89fd89c24189c783e2fe4129d54589ec4139ec0f84410300",
+        "0083ed024489f944896424244489fa89e84531ed4989df83e0fe29c1894c243c8d42ff3954243c0f8436030000448d62",
+        "fe89c64c896c242831db4489e144896424384989de4189ec83e1fe29ce897424408b7424408d78ff39f00f8497020000",
+        "8d48fe89fe4c897c243031db89c883e0fe29c64489e04989dc8974244489c389ce8b442444448d4fff39c70f844b0200",
+        "0083ef024589cd4d89f04189f389f94c89e64531ff4589ce83e1fe4189fa4129cd4589ec418d46ff4539e60f84ff0100",
+        "004183ee0289c24d89c14531ed4489f74989f083e7fe29fa895424188b7424188d68ff39f00f84770100008d78fd83e8",
+        "0289ee31c989c2893c2483e2fe29d6897424208b7c24208d55ff39fd0f84660100008b3c2489d68944241c4c894c2408",
+        "4189d983e7fe48894c241029fe8974240431f64889f18b4424048d5aff39c20f845b01000031f68d7bff44894c246c83",
+        "eb02448954246844895c2464488974245848894c2450895424604c89442448e800000000488b7424584c8b4424488b54",
+        "2460488b4c24504801c683fb01448b5c2464448b542468448b4c246c7fa98d7afd83ea0283e7014801f74801f983fa01",
+        "7f844889ce4489cb488b4c24104c8b4c24088b44241c4863d283ed02832c24024801f24801d183fd010f8f24ffffff48",
+        "63d54801ca4901d583f8010f8febfeffff4c89c64d89c848984c01e84901c74183fe010f8fabfeffff4989f44589f144",
+        "89de4d89c64489d74963c94c01f94901cc83ff010f8f57feffff89d84c8b7c24304c89e389f14189c489c84863cf4801",
+        "d94901ce83f8010f8f04feffff4489e54c8b6c2428448b6424384c89f3e9ac0000004c89c683e8024d89c84983c501eb",
+        "860f1f800000000083ed024883c1014863d54801ca4901d583f8010f8f4bfeffffe95bffffff662e0f1f840000000000",
+        "4889ce4489cb488b4c24104c8b4c24088b44241c83ea024883c601e906ffffff4589f14989f44d89c64489de4489d741",
+        "83e9024983c701e93cffffff89d84c89e34c8b7c243089f14189c483ef024883c301e942ffffff4c89f34489e54c8b6c",
+        "2428448b64243883e8024883c30148984489e24801d84901c54183fc010f8ffdfcffff4c89fb4589e7448b6424244d63",
+        "ff4b8d042f4801c383fd017f484189eceb0b4189ec4883c3014183ec024883c4784d63e4498d041c5b5d415c415d415e",
+        "415fc34c89fb4189d74983c501448b6424244183ef024d63ff4b8d042f4801c383fd017eb8448d7dffe96afcffff6666",
+        "2e0f1f8400000000000f1f8000000000f30f1efa0fb60731d284c0741a0f1f004038c60f94c04883c7010fb6c04801c2",
+        "0fb60784c075e94889d0c366662e0f1f840000000000662e0f1f840000000000f30f1efa4885f6743f41554989fd4154",
+        "4989f4554889d55331db4883ec08669049896cdd004889ef4883c301e8000000004939dc75ea4883c4085b5d415c415d",
+        "c30f1f8000000000c366662e0f1f84000000000066662e0f1f84000000000090f30f1efa488b174883fe01761e488d47",
+        "08488d34f70f1f00488b084839ca480f4cd14883c0084839f075ed4889d0c366662e0f1f840000000000660f1f440000",
+        "f30f1efa0fb6073c2d74458d50d031f680fa09775b31d2660f1f84000000000083e8308d14924883c7010fbec08d1450",
+        "0fb6078d48d080f90976e589d0f7d885f60f45d089d0c3660f1f8400000000000fb64701488d4f018d50d080fa097710",
+        "4889cfbe01000000ebab660f1f44000031d289d0c366662e0f1f840000000000f30f1efa4889f84889f7488b10488916",
+        "488930e9000000000f1f840000000000f30f1efa534885d2742e31c031db6690488b0cc7480faf0cc64883c0014801cb",
+        "4839c275eb4889dfe8000000004889d85bc3660f1f44000031db4889dfe8000000004889d85bc366662e0f1f84000000",
+        "000066662e0f1f8400000000000f1f00f30f1efa4883fe017626488b17b801000000eb0d0f1f40004883c0014839c674",
+        "0f4889d1488b14c74839ca7deb31c0c3b801000000c3662e0f1f840000000000f30f1efa4885ff741f534889fb0f1f00",
+        "4889df488b1be8000000004885db75f05bc3660f1f440000c366662e0f1f84000000000066662e0f1f84000000000090",
+        "f30f1efa4889f84889f24885f67419904889d1489948f7f94889c84885d275f0",
+    ];
+
+    fn x86_64_o2_text() -> Vec<u8> {
+        hex::decode(X86_64_O2_TEXT.concat()).unwrap()
+    }
+
+    #[test]
+    fn test_x86_units() {
+        assert_eq!(unit_len(PaddingKind::X86, &[0xCC, 0x55]), 1);
+        assert_eq!(
+            unit_len(PaddingKind::X86, &[0x0F, 0x1F, 0x44, 0x00, 0x00]),
+            5
+        );
+        let nopw = [
+            0x66, 0x66, 0x2E, 0x0F, 0x1F, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(unit_len(PaddingKind::X86, &nopw), nopw.len());
+        // A prefixed INT3 or a non-zero displacement is not filler
+        assert_eq!(unit_len(PaddingKind::X86, &[0x66, 0xCC]), 0);
+        assert_eq!(unit_len(PaddingKind::X86, &[0x0F, 0x1F, 0x40, 0x08]), 0);
+    }
+
+    #[test]
+    fn test_roles() {
+        let nop = aarch64::system::NOP.to_le_bytes();
+        let stp = 0xA9BF_7BFDu32.to_le_bytes(); // stp x29, x30, [sp, #-16]!
+        let ret = 0xD65F_03C0u32.to_le_bytes();
+        let data = [ret, nop, nop, stp, nop, ret, nop, nop].concat();
+
+        let kind = PaddingKind::AArch64;
+        assert_eq!(role(kind, &data, 0), None);
+        assert_eq!(role(kind, &data, 4), Some(PaddingRole::Run));
+        assert_eq!(role(kind, &data, 8), Some(PaddingRole::BeforeEntry));
+        assert_eq!(role(kind, &data, 16), Some(PaddingRole::Isolated));
+        assert_eq!(role(kind, &data, 28), Some(PaddingRole::Run));
+
+        let stats = scan(kind, &data);
+        assert_eq!(stats.functions, 1);
+        assert_eq!(stats.padded_functions, 1);
+        assert_eq!(stats.padding_bytes, 16);
+    }
+
+    #[test]
+    fn test_thumb_and_riscv_entries() {
+        // nop; nop.w; push {r4, lr}
+        let thumb = [0x00, 0xBF, 0xAF, 0xF3, 0x00, 0x80, 0x10, 0xB5];
+        assert_eq!(
+            role(PaddingKind::Thumb, &thumb, 2),
+            Some(PaddingRole::BeforeEntry)
+        );
+        assert_eq!(scan(PaddingKind::Thumb, &thumb).padded_functions, 1);
+
+        // c.nop; c.nop; addi sp, sp, -32
+        let riscv = [0x01, 0x00, 0x01, 0x00, 0x13, 0x01, 0x01, 0xFE];
+        assert_eq!(
+            role(PaddingKind::RiscV, &riscv, 2),
+            Some(PaddingRole::BeforeEntry)
+        );
+        assert_eq!(estimated_functions(Isa::RiscV64, &riscv), Some(1));
+    }
+
+    #[test]
+    fn test_x86_64_o2_extract() {
+        let data = x86_64_o2_text();
+        assert_eq!(data.len(), 2048);
+
+        let stats = scan(PaddingKind::X86, &data);
+        assert_eq!(stats.functions, 16);
+        assert_eq!(stats.padded_functions, 16);
+        assert!(stats.padding_bytes > 256);
+        // The scorer skips the same runs in its own pass
+        let (_, _, skipped) = crate::architectures::x86::score_with_padding(&data, 64);
+        assert_eq!(skipped, stats.padding_bytes);
+
+        // Scored without padding idioms this extract came out at ~0.44
+        let result = crate::classify_bytes_with_options(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert!(result.confidence > 0.55, "confidence {}", result.confidence);
+        let functions = result
+            .metadata
+            .custom
+            .iter()
+            .find(|e| e.key == MetadataKey::Custom("estimated_functions".into()))
+            .expect("estimated_functions");
+        assert_eq!(functions.value.to_string(), "16");
+    }
+
+    #[test]
+    fn test_bare_padding_is_neutral() {
+        let data = [0xCCu8; 1024];
+        let stats = scan(PaddingKind::X86, &data);
+        assert_eq!(stats.padding_bytes, 1024);
+        assert_eq!(stats.functions, 0);
+
+        let result = crate::classify_bytes_with_options(&data, &ClassifierOptions::new());
+        assert!(!result
+            .is_ok_and(|r| { matches!(r.isa, Isa::X86 | Isa::X86_64) && r.confidence >= 0.5 }));
+    }
+}
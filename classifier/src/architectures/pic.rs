@@ -0,0 +1,444 @@
+//! Heuristic scoring for 8-bit Microchip PIC (PIC10/12/16/18) code.
+//!
+//! The 8-bit PIC families use fixed-width instruction words that are not
+//! byte sized. Toolchains export program memory (Intel HEX, raw `objcopy`
+//! dumps) as one 16-bit little-endian slot per word, with the unused high
+//! bits clear:
+//! - baseline (PIC10F, PIC12F5xx, PIC16F5x): 12-bit words, `0x000`-`0xFFF`.
+//!   Subroutines return with `RETLW`; `CALL` is `0x9xx`, `GOTO`
+//!   `0xA00`-`0xBFF`, `MOVLW` `0xCxx`, `CLRF` `0x060 | f`
+//! - mid-range (PIC12F6xx, PIC16F): 14-bit words, `0x0000`-`0x3FFF`.
+//!   `RETURN` is `0x0008`, `CALL` `0x2000`-`0x27FF`, `GOTO`
+//!   `0x2800`-`0x2FFF`, `MOVLW` `0x30xx`, `RETLW` `0x34xx`, `MOVWF`
+//!   `0x0080 | f`
+//! - PIC18: 16-bit words. `CALL`, `GOTO`, `MOVFF` and `LFSR` are two words
+//!   whose second word always starts with `0xF`; `RETURN` is `0x0012`,
+//!   `MOVLW` `0x0Exx`, `MOVWF` `0x6Exx`, `RETLW` `0x0Cxx`
+//!
+//! Each family is scored separately over the 16-bit words and the best
+//! match is returned. Erased flash (all ones within the word width) and
+//! `NOP` (`0x0000`) are treated as padding and do not contribute.
+
+use std::cmp;
+
+/// Mid-range `RETURN`.
+const MIDRANGE_RETURN: u16 = 0x0008;
+/// Mid-range `RETFIE`.
+const MIDRANGE_RETFIE: u16 = 0x0009;
+/// PIC18 `RETURN` (bit 0 is the fast-return flag).
+const PIC18_RETURN: u16 = 0x0012;
+/// PIC18 `RETFIE` (bit 0 is the fast-return flag).
+const PIC18_RETFIE: u16 = 0x0010;
+
+/// Iterate over the 16-bit little-endian words of `data`.
+fn words(data: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    data.chunks_exact(2)
+        .map(|w| u16::from_le_bytes([w[0], w[1]]))
+}
+
+/// Subroutine evidence shared by the per-family scorers.
+#[derive(Default)]
+struct Counts {
+    rets: u32,
+    calls: u32,
+    valid: u32,
+    invalid: u32,
+}
+
+impl Counts {
+    /// Apply the common gates and bonuses to a raw score.
+    fn finish(&self, score: i64) -> i64 {
+        // Firmware is made of subroutines; without calls and returns the
+        // words are something else
+        if self.rets == 0 || self.calls == 0 {
+            return 0;
+        }
+        // Out-of-width words mean the slots do not hold this encoding
+        if self.invalid * 10 > self.valid {
+            return 0;
+        }
+        let mut score = score + i64::from(self.rets + self.calls) * 5;
+        if self.valid > 10 {
+            score += i64::from(self.valid);
+        }
+        cmp::max(0, score)
+    }
+}
+
+/// Score 12-bit baseline words.
+fn score_baseline(data: &[u8]) -> i64 {
+    let mut score: i64 = 0;
+    let mut counts = Counts::default();
+
+    for w in words(data) {
+        if w == 0x0000 || w == 0x0FFF {
+            continue;
+        }
+        if w > 0x0FFF {
+            counts.invalid += 1;
+            score -= 5;
+            continue;
+        }
+        counts.valid += 1;
+        score += match w >> 8 {
+            // RETLW k
+            0x8 => {
+                counts.rets += 1;
+                6
+            }
+            // CALL k
+            0x9 => {
+                counts.calls += 1;
+                8
+            }
+            // GOTO k
+            0xA | 0xB => 4,
+            // MOVLW k
+            0xC => 3,
+            // IORLW/ANDLW/XORLW k
+            0xD..=0xF => 2,
+            // BCF/BSF/BTFSC/BTFSS f,b
+            0x4..=0x7 => 2,
+            // OPTION, SLEEP, CLRWDT, TRIS
+            0x0 if (0x002..=0x007).contains(&w) => 4,
+            // MOVWF f
+            0x0 if w & 0xFE0 == 0x020 => 3,
+            // CLRW
+            0x0 if w == 0x040 => 2,
+            // CLRF f
+            0x0 if w & 0xFE0 == 0x060 => 3,
+            // SUBWF f,d / DECF f,d
+            0x0 if w >= 0x080 => 1,
+            0x0 => {
+                counts.valid -= 1;
+                counts.invalid += 1;
+                -2
+            }
+            // Byte-oriented file register operations
+            _ => 1,
+        };
+    }
+
+    counts.finish(score)
+}
+
+/// Score 14-bit mid-range words.
+fn score_midrange(data: &[u8]) -> i64 {
+    let mut score: i64 = 0;
+    let mut counts = Counts::default();
+
+    for w in words(data) {
+        if w == 0x0000 || w == 0x3FFF {
+            continue;
+        }
+        if w > 0x3FFF {
+            counts.invalid += 1;
+            score -= 5;
+            continue;
+        }
+        counts.valid += 1;
+        score += match w {
+            MIDRANGE_RETURN => {
+                counts.rets += 1;
+                15
+            }
+            MIDRANGE_RETFIE => {
+                counts.rets += 1;
+                10
+            }
+            // SLEEP, CLRWDT
+            0x0063 | 0x0064 => 4,
+            // RETLW k
+            _ if w & 0x3C00 == 0x3400 => {
+                counts.rets += 1;
+                3
+            }
+            // CALL k
+            _ if w & 0x3800 == 0x2000 => {
+                counts.calls += 1;
+                8
+            }
+            // GOTO k
+            _ if w & 0x3800 == 0x2800 => 4,
+            // MOVLW k
+            _ if w & 0x3C00 == 0x3000 => 3,
+            // MOVWF f
+            _ if w & 0x3F80 == 0x0080 => 3,
+            // BSF/BCF STATUS,RP0/RP1: bank selection
+            0x1683 | 0x1283 | 0x1703 | 0x1303 => 4,
+            // BCF/BSF/BTFSC/BTFSS f,b
+            _ if w & 0x3000 == 0x1000 => 2,
+            // CLRF f, MOVF f,d, DECFSZ/INCFSZ f,d
+            _ if w & 0x3F80 == 0x0180 => 2,
+            _ if w & 0x3F00 == 0x0800 => 2,
+            _ if w & 0x3B00 == 0x0B00 => 2,
+            // Literal arithmetic and logic
+            _ if w >= 0x3800 => 2,
+            _ => 1,
+        };
+    }
+
+    counts.finish(score)
+}
+
+/// Score 16-bit PIC18 words.
+fn score_pic18(data: &[u8]) -> i64 {
+    let words: Vec<u16> = words(data).collect();
+    let mut score: i64 = 0;
+    let mut counts = Counts::default();
+    let mut i = 0;
+
+    while i < words.len() {
+        let w = words[i];
+        i += 1;
+        if w == 0x0000 || w == 0xFFFF {
+            continue;
+        }
+
+        // Two-word instructions: the second word is 0xFxxx
+        let two_word = match w >> 8 {
+            0xEC | 0xED => Some(15),
+            0xEF => Some(10),
+            0xEE if w & 0x00C0 == 0 => Some(8),
+            0xC0..=0xCF => Some(6),
+            _ => None,
+        };
+        if let Some(points) = two_word {
+            match words.get(i) {
+                Some(&second) if second >> 12 == 0xF => {
+                    if w >> 9 == 0x76 {
+                        counts.calls += 1;
+                    }
+                    counts.valid += 1;
+                    score += points;
+                    i += 1;
+                }
+                Some(_) => {
+                    counts.invalid += 1;
+                    score -= 3;
+                }
+                None => {}
+            }
+            continue;
+        }
+
+        counts.valid += 1;
+        score += match w {
+            PIC18_RETURN | 0x0013 => {
+                counts.rets += 1;
+                15
+            }
+            PIC18_RETFIE | 0x0011 => {
+                counts.rets += 1;
+                10
+            }
+            // SLEEP, CLRWDT, PUSH, POP, DAW, TBLRD/TBLWT, RESET
+            0x0003..=0x000F | 0x00FF => 2,
+            0x0001 | 0x0002 | 0x0014..=0x00FE => {
+                counts.valid -= 1;
+                counts.invalid += 1;
+                -4
+            }
+            // RETLW k
+            _ if w >> 8 == 0x0C => {
+                counts.rets += 1;
+                3
+            }
+            // RCALL n
+            _ if w >> 11 == 0x1B => {
+                counts.calls += 1;
+                6
+            }
+            // MOVLB k
+            _ if w & 0xFFF0 == 0x0100 => 4,
+            // MOVLW k, MOVWF f
+            _ if w >> 8 == 0x0E => 3,
+            _ if w >> 9 == 0x37 => 3,
+            // BRA n, conditional branches
+            _ if w >> 11 == 0x1A => 3,
+            _ if w >> 11 == 0x1C => 2,
+            // BSF/BCF/BTFSS/BTFSC f,b
+            _ if w >> 14 == 0x2 => 2,
+            // CLRF/SETF, MOVF, other literal operations
+            0x6800..=0x6BFF | 0x5000..=0x53FF | 0x0800..=0x0BFF | 0x0D00..=0x0DFF => 2,
+            // A second word without a first
+            _ if w >> 12 == 0xF => {
+                counts.valid -= 1;
+                counts.invalid += 1;
+                -4
+            }
+            // Byte-oriented file register operations
+            _ => 1,
+        };
+    }
+
+    counts.finish(score)
+}
+
+/// Score raw data as 8-bit PIC code: the best of the baseline, mid-range
+/// and PIC18 encodings.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < 8 {
+        return 0;
+    }
+    score_baseline(data)
+        .max(score_midrange(data))
+        .max(score_pic18(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(ws: &[u16]) -> Vec<u8> {
+        ws.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    /// PIC18 port set-up, a main loop and a polled subroutine.
+    const PIC18_ROUTINE: &[u16] = &[
+        0x0E00, // MOVLW 0x00
+        0x6E93, // MOVWF TRISB
+        0x0E55, // MOVLW 0x55
+        0x6E81, // MOVWF PORTB
+        0xEE01, 0xF000, // LFSR 0, 0x100
+        0xEC10, 0xF000, // CALL 0x20
+        0xD7FE, // BRA $-2
+        0x0105, // MOVLB 5
+        0x5001, // MOVF 0x01,W
+        0x0A0F, // XORLW 0x0F
+        0xE102, // BNZ $+4
+        0x6A02, // CLRF 0x02
+        0x2A03, // INCF 0x03,F
+        0x8081, // BSF PORTB,0
+        0x9081, // BCF PORTB,0
+        0xA4D8, // BTFSS STATUS,Z
+        0xD801, // RCALL $+2
+        0x0012, // RETURN
+        0xC001, 0xF002, // MOVFF 0x001, 0x002
+        0x0C41, // RETLW 'A'
+        0x0C42, // RETLW 'B'
+        0x0012, // RETURN
+    ];
+
+    /// PIC16F84 style bank switch, port set-up and a delay loop.
+    const MIDRANGE_ROUTINE: &[u16] = &[
+        0x1683, // BSF STATUS,RP0
+        0x3000, // MOVLW 0
+        0x0086, // MOVWF TRISB
+        0x1283, // BCF STATUS,RP0
+        0x2010, // CALL 0x10
+        0x2805, // GOTO 5
+        0x0186, // CLRF PORTB
+        0x0806, // MOVF PORTB,W
+        0x3A0F, // XORLW 0x0F
+        0x1D03, // BTFSS STATUS,Z
+        0x0B8C, // DECFSZ 0x0C,F
+        0x0008, // RETURN
+        0x3441, // RETLW 'A'
+    ];
+
+    /// PIC12F508 start-up: oscillator calibration, OPTION/TRIS set-up, a
+    /// RAM-clearing loop through FSR, then a main loop with a countdown
+    /// compare and a delay subroutine.
+    const PIC12F508_INIT: &[u16] = &[
+        0x0025, // MOVWF OSCCAL
+        0x0CDF, // MOVLW 0xDF
+        0x0002, // OPTION
+        0x0066, // CLRF GPIO
+        0x0C08, // MOVLW 0x08
+        0x0006, // TRIS GPIO
+        0x0C07, // MOVLW 0x07
+        0x0024, // MOVWF FSR
+        0x0060, // CLRF INDF
+        0x02A4, // INCF FSR,F
+        0x07A4, // BTFSS FSR,5
+        0x0A08, // GOTO 0x008
+        0x0040, // CLRW
+        0x0C0A, // MOVLW 10
+        0x0030, // MOVWF 0x10
+        0x0406, // BCF GPIO,0
+        0x0C05, // MOVLW 5
+        0x0090, // SUBWF 0x10,W
+        0x0603, // BTFSC STATUS,C
+        0x0506, // BSF GPIO,0
+        0x0C40, // MOVLW 0x40
+        0x0918, // CALL 0x018
+        0x00F0, // DECF 0x10,F
+        0x0743, // BTFSS STATUS,Z
+        0x0A0F, // GOTO 0x00F
+        0x0A0D, // GOTO 0x00D
+        0x0031, // MOVWF 0x11
+        0x0072, // CLRF 0x12
+        0x02F2, // DECFSZ 0x12,F
+        0x0A1B, // GOTO 0x01B
+        0x00F1, // DECF 0x11,F
+        0x0643, // BTFSC STATUS,Z
+        0x0800, // RETLW 0
+        0x0A1B, // GOTO 0x01B
+    ];
+
+    #[test]
+    fn test_pic18_routine() {
+        let code = bytes(PIC18_ROUTINE).repeat(32);
+        assert!(score_pic18(&code) > 0);
+        assert!(score_pic18(&code) > score_midrange(&code));
+        assert_eq!(score(&code), score_pic18(&code));
+        let msp430 = crate::architectures::msp430::score(&code);
+        assert!(
+            score(&code) > msp430,
+            "{} vs MSP430 {}",
+            score(&code),
+            msp430
+        );
+    }
+
+    #[test]
+    fn test_midrange_routine() {
+        let code = bytes(MIDRANGE_ROUTINE).repeat(32);
+        assert!(score_midrange(&code) > 0);
+        assert_eq!(score(&code), score_midrange(&code));
+    }
+
+    #[test]
+    fn test_baseline_routine() {
+        let code = bytes(&[
+            0x0C07, // MOVLW 7
+            0x0002, // OPTION
+            0x0925, // CALL 0x25
+            0x0A03, // GOTO 3
+            0x0406, // BCF GPIO,0
+            0x0506, // BSF GPIO,0
+            0x0800, // RETLW 0
+        ])
+        .repeat(32);
+        assert!(score_baseline(&code) > 0);
+    }
+
+    #[test]
+    fn test_baseline_clear_and_arithmetic_ops() {
+        // CLRW, CLRF, SUBWF and DECF live in 0x040-0x0FF
+        let code = bytes(PIC12F508_INIT).repeat(8);
+        assert!(score_baseline(&code) > 0);
+        assert_eq!(score(&code), score_baseline(&code));
+        let result =
+            crate::heuristics::analyze(&code, &crate::types::ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::Pic);
+    }
+
+    #[test]
+    fn test_rejects_text_and_padding() {
+        let text = b"The quick brown fox jumps over the lazy dog. 0123456789".repeat(8);
+        assert_eq!(score(&text), 0);
+        assert_eq!(score(&[0xFF; 512]), 0);
+        assert_eq!(score(&[0x00; 512]), 0);
+    }
+
+    #[test]
+    fn test_code_classified_as_pic() {
+        let code = bytes(PIC18_ROUTINE).repeat(32);
+        let result =
+            crate::heuristics::analyze(&code, &crate::types::ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::Pic);
+        assert_eq!(result.endianness, crate::types::Endianness::Little);
+    }
+}
@@ -0,0 +1,254 @@
+//! Heuristic scoring for Microchip PIC24/dsPIC (16-bit MCU/DSC) code.
+//!
+//! PIC24 and dsPIC30F/33F/33E share a 24-bit instruction word. Program
+//! memory is addressed in 16-bit units, so every instruction occupies two
+//! program-counter addresses but only three bytes of real storage. When the
+//! image is exported (ELF `.text`, raw `objcopy` dumps, Intel HEX from MPLAB)
+//! each word is padded out to 32 bits with an unimplemented "phantom" byte:
+//!
+//! ```text
+//!   byte 0   byte 1   byte 2   byte 3
+//!   low      mid      opcode   phantom (always 0x00)
+//! ```
+//!
+//! The scorer walks 4-byte aligned little-endian words and first checks that
+//! the phantom byte is zero; a file where that byte is frequently non-zero
+//! is not PIC24 program memory and scores 0. Within the remaining words it
+//! looks at the opcode byte (bits 23..16):
+//! - `0x060000`: RETURN, `0x064000`: RETFIE, `0x05xxxx`: RETLW
+//! - `0x04xxxx`: GOTO, `0x02xxxx`: CALL (two-word; second word has only
+//!   7 address bits, so `second & 0xFFFF80 == 0`)
+//! - `0x07xxxx`: RCALL, `0x37xxxx`: BRA, `0x30`..`0x3F`: conditional BRA
+//! - `0x78`..`0x7F`: MOV Ws, Wd; `0x20`..`0x2F`: MOV #lit16, Wn;
+//!   `0x80`..`0x8F`: MOV f <-> Wn
+//! - `0xF8`/`0xF9`: PUSH/POP f, `0xFA0000`: LNK, `0xFA8000`: ULNK
+//!
+//! NOP (`0x000000`) and erased flash (`0xFFFFFF`) are treated as padding and
+//! do not contribute to the score.
+
+use std::cmp;
+
+/// Minimum fraction of non-padding words that must carry a zero phantom byte.
+const MIN_PHANTOM_RATIO: f64 = 0.9;
+
+/// Returns true for words that carry no instruction signal.
+#[inline]
+fn is_padding(w: u32) -> bool {
+    matches!(w, 0x0000_0000 | 0xFFFF_FFFF | 0x00FF_FFFF)
+}
+
+/// Score raw data as PIC24/dsPIC code.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < 8 {
+        return 0;
+    }
+
+    let word_at = |off: usize| -> Option<u32> {
+        data.get(off..off + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    let mut score: i64 = 0;
+    let mut i = 0usize;
+
+    let mut non_padding_words = 0u32;
+    let mut phantom_ok = 0u32;
+    let mut ret_count = 0u32;
+    let mut call_pairs = 0u32;
+    let mut valid_insn = 0u32;
+
+    while let Some(w) = word_at(i) {
+        if is_padding(w) {
+            i += 4;
+            continue;
+        }
+        non_padding_words += 1;
+
+        if w >> 24 != 0 {
+            // Phantom byte set: not a packed 24-bit instruction word.
+            score -= 3;
+            i += 4;
+            continue;
+        }
+        phantom_ok += 1;
+
+        let insn = w & 0x00FF_FFFF;
+        let opcode = (insn >> 16) as u8;
+
+        match opcode {
+            0x06 if insn == 0x06_0000 => {
+                // RETURN
+                score += 20;
+                ret_count += 1;
+            }
+            0x06 if insn == 0x06_4000 => {
+                // RETFIE
+                score += 15;
+                ret_count += 1;
+            }
+            0x05 => {
+                // RETLW #lit10, Wn
+                score += 4;
+                ret_count += 1;
+            }
+            0x02 | 0x04 if insn & 1 == 0 => {
+                // CALL / GOTO: second word holds the upper address bits
+                match word_at(i + 4) {
+                    Some(second) if second & 0xFFFF_FF80 == 0 => {
+                        score += 15;
+                        call_pairs += 1;
+                        valid_insn += 1;
+                        i += 8;
+                        continue;
+                    }
+                    _ => score -= 2,
+                }
+            }
+            0x07 | 0x37 => {
+                // RCALL / BRA
+                score += 6;
+                valid_insn += 1;
+            }
+            0x30..=0x3F => {
+                // Conditional BRA
+                score += 3;
+                valid_insn += 1;
+            }
+            0xFA if insn == 0xFA_8000 => {
+                // ULNK
+                score += 12;
+                valid_insn += 1;
+            }
+            0xFA if insn & 0x00_C001 == 0 => {
+                // LNK #lit14
+                score += 10;
+                valid_insn += 1;
+            }
+            0xF8 | 0xF9 if insn & 1 == 0 => {
+                // PUSH f / POP f
+                score += 5;
+                valid_insn += 1;
+            }
+            0x78..=0x7F => {
+                // MOV Ws, Wd (and byte forms)
+                score += 4;
+                valid_insn += 1;
+            }
+            0x20..=0x2F => {
+                // MOV #lit16, Wn
+                score += 3;
+                valid_insn += 1;
+            }
+            0x80..=0x8F => {
+                // MOV f, Wn / MOV Wn, f
+                score += 3;
+                valid_insn += 1;
+            }
+            _ => {}
+        }
+
+        i += 4;
+    }
+
+    if non_padding_words < 2 {
+        return 0;
+    }
+    let phantom_ratio = f64::from(phantom_ok) / f64::from(non_padding_words);
+    if phantom_ratio < MIN_PHANTOM_RATIO {
+        return 0;
+    }
+
+    if ret_count > 0 && call_pairs > 0 {
+        score += i64::from(ret_count + call_pairs) * 5;
+    }
+    if valid_insn > 10 {
+        score += i64::from(valid_insn) * 2;
+    }
+
+    if data.len() > 4096 && (ret_count == 0 || call_pairs == 0) {
+        return 0;
+    }
+    cmp::max(0, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(ws: &[u32]) -> Vec<u8> {
+        ws.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    const RESET_SEQUENCE: &[u32] = &[
+        0x0004_0200, // GOTO __reset (0x000200)
+        0x0000_0000, //   second word
+        0x0020_800F, // __reset: MOV #0x0800, W15
+        0x0022_7F00, // MOV #0x27F0, W0
+        0x0088_0100, // MOV W0, SPLIM
+        0x0000_0000, // NOP
+        0x0002_0300, // CALL _main (0x000300)
+        0x0000_0000, //   second word
+        0x0037_FFFF, // BRA $
+        0x00FA_0000, // _main: LNK #0
+        0x0078_0080, // MOV W0, W1
+        0x00FA_8000, // ULNK
+        0x0006_0000, // RETURN
+    ];
+
+    #[test]
+    fn test_pic24_reset_sequence() {
+        let code = words(RESET_SEQUENCE);
+        let pic24 = score(&code);
+        assert!(pic24 > 0);
+        for (name, other) in [
+            ("8-bit PIC", crate::architectures::pic::score(&code)),
+            ("MSP430", crate::architectures::msp430::score(&code)),
+            ("AVR", crate::architectures::avr::score(&code)),
+        ] {
+            assert!(pic24 > other, "{pic24} vs {name} {other}");
+        }
+    }
+
+    #[test]
+    fn test_pic24_rejects_other_mcu_code() {
+        // MSP430: PUSH R4; MOV SP, R4; MOV #0x1234, R15; CALL #0x8000; POP R4; RET
+        let msp430: Vec<u8> = [
+            0x1204u16, 0x4104, 0x403F, 0x1234, 0x12B0, 0x8000, 0x4134, 0x4130,
+        ]
+        .repeat(8)
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .collect();
+        assert_eq!(score(&msp430), 0);
+
+        // AVR: PUSH r28; PUSH r29; IN r28, SPL; RCALL .+0; POP r29; POP r28; RET
+        let avr: Vec<u8> = [
+            0x93CFu16, 0x93DF, 0xB7CD, 0xD000, 0x91DF, 0x91CF, 0x9508, 0x0000,
+        ]
+        .repeat(8)
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .collect();
+        assert_eq!(score(&avr), 0);
+    }
+
+    #[test]
+    fn test_pic24_rejects_nonzero_phantom() {
+        // Same opcodes, but the phantom byte is populated.
+        let code = words(&[
+            0x1206_0000,
+            0x3402_0300,
+            0x5600_0000,
+            0x7878_0080,
+            0x9AFA_0000,
+            0xBC06_0000,
+        ]);
+        assert_eq!(score(&code), 0);
+    }
+
+    #[test]
+    fn test_pic24_rejects_text() {
+        let text = b"The quick brown fox jumps over the lazy dog. 0123456789";
+        assert_eq!(score(text), 0);
+    }
+}
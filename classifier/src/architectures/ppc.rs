@@ -250,7 +250,9 @@ fn score_word(word: u32) -> i64 {
 }
 
 /// Internal: Score PPC code from pre-decoded words, applying structural requirements.
-fn score_with_structural(data: &[u8], be: bool) -> i64 {
+///
+/// Also returns the number of distinctive idioms (BLR, MFLR and NOP) seen.
+fn score_with_structural(data: &[u8], be: bool) -> (i64, u32) {
     let mut score: i64 = 0;
     let mut blr_count = 0u32;
     let mut bl_count = 0u32;
@@ -370,20 +372,31 @@ fn score_with_structural(data: &[u8], be: bool) -> i64 {
         }
     }
 
-    score.max(0)
+    (score.max(0), blr_count + mflr_count + nop_count)
 }
 
 /// Score likelihood of PowerPC code (big-endian).
 ///
 /// Analyzes raw bytes for patterns characteristic of PowerPC.
 pub fn score(data: &[u8]) -> i64 {
-    score_with_structural(data, true)
+    score_with_structural(data, true).0
 }
 
 /// Score likelihood of PowerPC code (little-endian).
 ///
 /// PPC64 LE (Power8+) uses little-endian instruction encoding.
 pub fn score_le(data: &[u8]) -> i64 {
+    score_with_structural(data, false).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (BLR, MFLR
+/// and NOP) were counted.
+pub fn score_with_matches(data: &[u8]) -> (i64, u32) {
+    score_with_structural(data, true)
+}
+
+/// Like [`score_le`], also returning how many distinctive idioms were counted.
+pub fn score_le_with_matches(data: &[u8]) -> (i64, u32) {
     score_with_structural(data, false)
 }
 
@@ -79,6 +79,8 @@ pub fn score(data: &[u8]) -> i64 {
                         continue;
                     }
                 }
+                // Truncated at the end of the data
+                i += 2;
             }
             _ => {
                 i += 2;
@@ -379,4 +381,10 @@ mod tests {
         ];
         assert!(score(&code) > 0);
     }
+
+    #[test]
+    fn test_truncated_e_b_terminates() {
+        // An e_b/e_bl halfword with no room for the second half
+        assert_eq!(score(&[0x44, 0x00, 0x44, 0x00, 0x78, 0x56]), 20);
+    }
 }
@@ -1,5 +1,7 @@
 //! RISC-V architecture analysis.
 
+use super::padding::{self, PaddingKind, PaddingRole};
+
 /// Standard RISC-V opcodes (bits [6:0]).
 pub mod opcode {
     pub const LOAD: u8 = 0x03;
@@ -152,7 +154,21 @@ pub fn uses_v_extension(instr: u32) -> bool {
 /// - Standard opcodes with funct3/funct7 validation
 /// - Compressed instructions with specific pattern checks
 pub fn score(data: &[u8], bits: u8) -> i64 {
+    score_with_matches(data, bits).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (returns and
+/// JAL/JALR calls) the structural check counted.
+pub fn score_with_matches(data: &[u8], bits: u8) -> (i64, u32) {
+    let (score, matches, _) = score_with_padding(data, bits);
+    (score, matches)
+}
+
+/// Like [`score_with_matches`], also returning the bytes of alignment
+/// padding runs the scorer skipped as neutral.
+pub fn score_with_padding(data: &[u8], bits: u8) -> (i64, u32, usize) {
     let mut score: i64 = 0;
+    let mut padding_bytes = 0usize;
     let is_64 = bits == 64;
     let mut i = 0;
     let mut ret_count = 0u32;
@@ -297,6 +313,20 @@ pub fn score(data: &[u8], bits: u8) -> i64 {
     }
 
     while i < data.len() {
+        // Alignment padding leading into a function entry; bare runs are neutral
+        if let Some(role) = padding::role(PaddingKind::RiscV, data, i) {
+            if role != PaddingRole::Isolated {
+                if role == PaddingRole::BeforeEntry {
+                    score += 40;
+                    anchor_count += 1;
+                }
+                let unit = padding::unit_len(PaddingKind::RiscV, &data[i..]);
+                padding_bytes += unit;
+                i += unit;
+                continue;
+            }
+        }
+
         let instr_len = instruction_length(&data[i..]);
 
         if instr_len == 2 {
@@ -612,7 +642,7 @@ pub fn score(data: &[u8], bits: u8) -> i64 {
         }
     }
 
-    score.max(0)
+    (score.max(0), ret_count + jal_jalr_count, padding_bytes)
 }
 
 #[cfg(test)]
@@ -122,8 +122,10 @@ pub mod op3_mem {
     pub const STDFQ: u8 = 0x26; // Store double FP queue
     pub const STDF: u8 = 0x27; // Store double FP
                                // V9 64-bit loads/stores
+    pub const LDSW: u8 = 0x08; // Load signed word
     pub const LDX: u8 = 0x0B; // Load extended word
     pub const STX: u8 = 0x0E; // Store extended word
+    pub const LDSWA: u8 = 0x18; // Load signed word from alternate space
     pub const LDXA: u8 = 0x1B; // Load extended from alternate space
     pub const STXA: u8 = 0x1E; // Store extended to alternate space
     pub const PREFETCH: u8 = 0x2D; // Prefetch (V9)
@@ -364,6 +366,79 @@ pub fn is_sethi(instr: u32) -> bool {
     get_format(instr) == format::BRANCH_SETHI && get_op2(instr) == op2::SETHI
 }
 
+/// Bytes per trap table entry (four instructions).
+const TRAP_ENTRY_SIZE: usize = 16;
+
+/// Minimum number of leading entries that make a trap table.
+const MIN_TRAP_ENTRIES: usize = 16;
+
+/// Byte length of a SPARC V8 trap table at offset 0, as LEON boot images
+/// start with: a run of 16-byte entries that each leave through a `ba`, a
+/// `jmp` (`jmpl` discarding the link) or a `ta`. `None` when fewer than 16
+/// entries lead the image.
+pub fn trap_table_len(data: &[u8]) -> Option<usize> {
+    let entries = data
+        .chunks_exact(TRAP_ENTRY_SIZE)
+        .take_while(|entry| {
+            entry
+                .chunks_exact(4)
+                .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+                .any(|w| is_ba(w) || (is_jmpl(w) && get_rd(w) == 0) || is_trap(w))
+        })
+        .count();
+    let len = entries * TRAP_ENTRY_SIZE;
+    // One entry repeated throughout is a fill pattern, not a table
+    let distinct = data[..len]
+        .chunks_exact(TRAP_ENTRY_SIZE)
+        .any(|entry| entry != &data[..TRAP_ENTRY_SIZE]);
+    (entries >= MIN_TRAP_ENTRIES && distinct).then_some(len)
+}
+
+/// Check if instruction exists only in SPARC V9 (64-bit).
+///
+/// Covers the predicted and register-conditional branches, conditional
+/// moves, 64-bit multiply/divide, `POPC`, extended shifts (`SLLX`/`SRLX`/
+/// `SRAX`), extended and signed-word loads/stores, `CASA`/`CASXA`,
+/// `PREFETCH`, `FLUSHW`, `SAVED`/`RESTORED` and `DONE`/`RETRY`. All of these
+/// are unimplemented encodings on V8.
+pub fn is_v9_only(instr: u32) -> bool {
+    match get_format(instr) {
+        format::BRANCH_SETHI => matches!(get_op2(instr), op2::BPCC | op2::BPR | op2::FBPFCC),
+        format::ARITHMETIC => {
+            let op3 = get_op3(instr);
+            match op3 {
+                op3_arith::MULX
+                | op3_arith::UDIVX
+                | op3_arith::MOVCC
+                | op3_arith::SDIVX
+                | op3_arith::POPC
+                | op3_arith::MOVR
+                | op3_arith::DONE => true,
+                // The X bit selects a 64-bit shift
+                op3_arith::SLL | op3_arith::SRL | op3_arith::SRA => instr & 0x1000 != 0,
+                // FLUSHW (RDTBR on V8 has a non-zero rd)
+                op3_arith::RDTBR => get_rd(instr) == 0 && !get_i_bit(instr),
+                // SAVED / RESTORED (WRPSR on V8 writes rd = 0 only)
+                op3_arith::WRPSR => get_rd(instr) == 1,
+                _ => false,
+            }
+        }
+        format::LOAD_STORE => matches!(
+            get_op3(instr),
+            op3_mem::LDSW
+                | op3_mem::LDX
+                | op3_mem::STX
+                | op3_mem::LDSWA
+                | op3_mem::LDXA
+                | op3_mem::STXA
+                | op3_mem::PREFETCH
+                | op3_mem::CAS
+                | op3_mem::CASX
+        ),
+        _ => false,
+    }
+}
+
 /// Valid op3 values for arithmetic format (for heuristics).
 pub const VALID_ARITH_OP3: &[u8] = &[
     op3_arith::ADD,
@@ -407,8 +482,16 @@ pub const VALID_MEM_OP3: &[u8] = &[
 
 /// Score likelihood of SPARC code.
 ///
-/// Analyzes raw bytes for patterns characteristic of SPARC.
-pub fn score(data: &[u8]) -> i64 {
+/// Analyzes raw bytes for patterns characteristic of SPARC. With `is_64`
+/// V9-only encodings (see [`is_v9_only`]) are scored as strong 64-bit
+/// evidence; without it they are invalid V8 encodings.
+pub fn score(data: &[u8], is_64: bool) -> i64 {
+    score_with_valid(data, is_64).0
+}
+
+/// Like [`score`], also returning how many instruction words the scorer
+/// decoded as SPARC.
+pub fn score_with_valid(data: &[u8], is_64: bool) -> (i64, u32) {
     let mut total_score: i64 = 0;
     let mut valid_count = 0u32;
     let mut save_count = 0u32;
@@ -417,6 +500,7 @@ pub fn score(data: &[u8]) -> i64 {
     let mut restore_sp_count = 0u32;
     let mut ret_count = 0u32;
     let mut call_count = 0u32;
+    let mut v9_count = 0u32;
 
     // SPARC is big-endian, 4-byte aligned
     let num_words = data.len() / 4;
@@ -594,6 +678,18 @@ pub fn score(data: &[u8]) -> i64 {
             continue;
         }
 
+        // V9-only encodings separate SPARC64 from 32-bit SPARC
+        if is_v9_only(word) {
+            if is_64 {
+                total_score += 6;
+                v9_count += 1;
+                valid_count += 1;
+            } else {
+                total_score -= 2;
+            }
+            continue;
+        }
+
         // CALL (format 01 - covers 25% of space!)
         // Since 25% of random words match CALL format, score very conservatively
         if fmt == format::CALL {
@@ -629,20 +725,10 @@ pub fn score(data: &[u8]) -> i64 {
                     }
                     valid_count += 1;
                 }
-                op2::BPCC => {
-                    // V9 branch with prediction - strong indicator
-                    total_score += 6;
-                    valid_count += 1;
-                }
-                op2::FBFCC | op2::FBPFCC => {
+                op2::FBFCC => {
                     total_score += 5;
                     valid_count += 1;
                 }
-                op2::BPR => {
-                    // V9 branch on register
-                    total_score += 6;
-                    valid_count += 1;
-                }
                 op2::CBCCC => {
                     total_score += 4;
                     valid_count += 1;
@@ -734,17 +820,11 @@ pub fn score(data: &[u8]) -> i64 {
                 0x25 | 0x26 | 0x27 | // SLL, SRL, SRA
                 0x28 | 0x29 | 0x2A | 0x2B | // RDY, RDPSR, RDWIM, RDTBR
                 0x30 | 0x31 | 0x32 | 0x33 | // WRY, WRPSR, WRWIM, WRTBR
-                0x09 | 0x0D | // MULX, UDIVX (V9)
-                0x2C | 0x2D | 0x2E | 0x2F | // MOVCC, SDIVX, POPC, MOVR (V9)
                 0x20 | 0x21 | 0x22 | 0x23 | 0x24 | // TADDCC, TSUBCC, etc.
                 0x3B // FLUSH
             ) {
                 total_score += 3;
                 valid_count += 1;
-            } else if op3 == 0x3E || op3 == 0x3F {
-                // DONE/RETRY (V9) - distinctive
-                total_score += 8;
-                valid_count += 1;
             } else {
                 // Unknown op3 in arithmetic format - mild penalty
                 total_score -= 1;
@@ -759,16 +839,14 @@ pub fn score(data: &[u8]) -> i64 {
                 op3,
                 0x00 | 0x01 | 0x02 | 0x03 | // LD, LDUB, LDUH, LDD
                 0x04 | 0x05 | 0x06 | 0x07 | // ST, STB, STH, STD
-                0x09 | 0x0A | 0x0B |         // LDSB, LDSH, LDX (V9)
-                0x0D | 0x0E | 0x0F |         // LDSTUB, STX (V9), SWAP
+                0x09 | 0x0A |                // LDSB, LDSH
+                0x0D | 0x0F |                // LDSTUB, SWAP
                 0x10 | 0x11 | 0x12 | 0x13 |  // LDA, LDUBA, LDUHA, LDDA
                 0x14 | 0x15 | 0x16 | 0x17 |  // STA, STBA, STHA, STDA
-                0x19 | 0x1A | 0x1B |          // LDSBA, LDSHA, LDXA (V9)
-                0x1D | 0x1E | 0x1F |          // LDSTUBA, STXA (V9), SWAPA
+                0x19 | 0x1A |                 // LDSBA, LDSHA
+                0x1D | 0x1F |                 // LDSTUBA, SWAPA
                 0x20 | 0x21 | 0x23 |          // LDF, LDFSR, LDDF
-                0x24 | 0x25 | 0x26 | 0x27 |  // STF, STFSR, STDFQ, STDF
-                0x2D |                         // PREFETCH (V9)
-                0x3C | 0x3E // CAS, CASX (V9)
+                0x24 | 0x25 | 0x26 | 0x27 // STF, STFSR, STDFQ, STDF
             ) {
                 total_score += 4;
                 valid_count += 1;
@@ -807,7 +885,13 @@ pub fn score(data: &[u8]) -> i64 {
         }
     }
 
-    total_score.max(0)
+    // 64-bit code without a single V9-only instruction is implausible, so
+    // plain V8-compatible code ranks as 32-bit SPARC
+    if is_64 && v9_count == 0 {
+        total_score = total_score * 4 / 5;
+    }
+
+    (total_score.max(0), valid_count)
 }
 
 #[cfg(test)]
@@ -883,6 +967,137 @@ mod tests {
     fn test_score() {
         // SPARC NOP (big-endian)
         let nop = patterns::NOP.to_be_bytes();
-        assert!(score(&nop) > 0);
+        assert!(score(&nop, false) > 0);
+        assert!(score(&nop, true) > 0);
+    }
+
+    /// Format 2/3 instruction with a 13-bit immediate.
+    fn imm(op: u32, rd: u32, op3: u32, rs1: u32, simm13: i32) -> u32 {
+        (op << 30)
+            | (rd << 25)
+            | (op3 << 19)
+            | (rs1 << 14)
+            | 0x2000
+            | (u32::from_ne_bytes(simm13.to_ne_bytes()) & 0x1FFF)
+    }
+
+    /// Format 2/3 instruction with a register operand.
+    fn reg(op: u32, rd: u32, op3: u32, rs1: u32, rs2: u32) -> u32 {
+        (op << 30) | (rd << 25) | (op3 << 19) | (rs1 << 14) | rs2
+    }
+
+    fn blob(words: &[u32], copies: usize) -> Vec<u8> {
+        words
+            .iter()
+            .cycle()
+            .take(words.len() * copies)
+            .flat_map(|w| w.to_be_bytes())
+            .collect()
+    }
+
+    const SP: u32 = 14;
+    const FP: u32 = 30;
+
+    /// `gcc -m64`-style function: 176-byte frame, biased stack, ldx/stx,
+    /// sllx, brz and be,pt %xcc.
+    fn v9_code() -> Vec<u8> {
+        blob(
+            &[
+                imm(2, SP, 0x3C, SP, -176),            // save %sp, -176, %sp
+                imm(3, 1, 0x0B, FP, 2039),             // ldx [%fp+2039], %g1
+                imm(2, 2, 0x25, 24, 0x1003),           // sllx %i0, 3, %g2
+                reg(2, 1, 0x00, 1, 2),                 // add %g1, %g2, %g1
+                imm(3, 1, 0x0E, FP, 2031),             // stx %g1, [%fp+2031]
+                (1 << 25) | (3 << 22) | (1 << 14) | 4, // brz %g1, .+16
+                patterns::NOP,
+                (1 << 25) | (1 << 22) | (2 << 20) | (1 << 19) | 4, // be,pt %xcc, .+16
+                patterns::NOP,
+                0x4000_0010, // call .+64
+                patterns::NOP,
+                imm(2, 0, 0x39, 31, 8), // return %i7+8
+                patterns::NOP,
+            ],
+            4,
+        )
+    }
+
+    /// `gcc -m32`-style function: 96-byte frame, ld/st, sll, cmp and be.
+    fn v8_code() -> Vec<u8> {
+        blob(
+            &[
+                imm(2, SP, 0x3C, SP, -96), // save %sp, -96, %sp
+                imm(3, 1, 0x00, FP, 68),   // ld [%fp+68], %g1
+                imm(2, 2, 0x25, 24, 2),    // sll %i0, 2, %g2
+                reg(2, 8, 0x00, 1, 2),     // add %g1, %g2, %o0
+                imm(3, 8, 0x04, FP, -4),   // st %o0, [%fp-4]
+                imm(2, 0, 0x14, 8, 0),     // cmp %o0, 0
+                (1 << 25) | (2 << 22) | 4, // be .+16
+                patterns::NOP,
+                0x4000_0010, // call .+64
+                patterns::NOP,
+                patterns::RET,
+                patterns::RESTORE,
+            ],
+            4,
+        )
+    }
+
+    #[test]
+    fn test_v9_only() {
+        assert!(is_v9_only(imm(3, 1, 0x0B, FP, 0))); // ldx
+        assert!(is_v9_only(imm(2, 2, 0x25, 24, 0x1003))); // sllx
+        assert!(!is_v9_only(imm(2, 2, 0x25, 24, 3))); // sll
+        assert!(!is_v9_only(imm(3, 1, 0x00, FP, 0))); // ld
+        assert!(!is_v9_only(patterns::NOP));
+        assert!(!is_v9_only(patterns::RET));
+    }
+
+    #[test]
+    fn test_score_bitwidth() {
+        let v9 = v9_code();
+        assert!(score(&v9, true) > score(&v9, false));
+        let v8 = v8_code();
+        assert!(score(&v8, false) > score(&v8, true));
+    }
+
+    #[test]
+    fn test_classify_sparc_bitwidth() {
+        use crate::types::{ClassifierOptions, Isa};
+
+        let opts = ClassifierOptions::new();
+        let v9 = crate::heuristics::analyze(&v9_code(), &opts).unwrap();
+        assert_eq!((v9.isa, v9.bitwidth), (Isa::Sparc64, 64));
+        let v8 = crate::heuristics::analyze(&v8_code(), &opts).unwrap();
+        assert_eq!((v8.isa, v8.bitwidth), (Isa::Sparc, 32));
+    }
+
+    /// LEON-style trap table: `mov %psr, %l0; sethi %hi(h), %l4;
+    /// jmp %l4 + %lo(h); mov %tbr, %l3` per entry.
+    fn trap_table(entries: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        for k in 0..entries {
+            let handler = 0x4000_1000 + k * 0x40;
+            for word in [
+                0xA148_0000,
+                0x2900_0000 | handler >> 10,
+                0x81C5_2000 | (handler & 0x3FF),
+                0xA750_0000,
+            ] {
+                data.extend_from_slice(&u32::to_be_bytes(word));
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_trap_table_len() {
+        let mut data = trap_table(256);
+        data.extend_from_slice(&patterns::RETL.to_be_bytes());
+        assert_eq!(trap_table_len(&data), Some(4096));
+        assert_eq!(trap_table_len(&trap_table(8)), None);
+
+        // One entry repeated is a fill pattern
+        let fill = data[..16].repeat(64);
+        assert_eq!(trap_table_len(&fill), None);
     }
 }
@@ -0,0 +1,225 @@
+//! Heuristic scoring for Tilera (later EZchip/Mellanox) TILE-Gx.
+//!
+//! TILE-Gx issues 64-bit little-endian bundles, 8-byte aligned. The two
+//! top bits select the bundle mode:
+//! - `00`: X mode, two 31-bit slots: X0 (bits 0-30) and X1 (bits 31-61)
+//! - `01`..`11`: Y mode, three slots: Y0, Y1 and the memory slot Y2
+//!
+//! Every slot of a bundle must hold an instruction, so the assembler fills
+//! the slots a compiler leaves empty with `fnop` or `nop`. Those fillers
+//! have fixed encodings and show up in most bundles of real code:
+//! - X0 `fnop`: `0x51483000`, X0 `nop`: `0x51485000`
+//! - X1 `nop`: `0x286B1800` in the upper word (`{ nop ; nop }` is
+//!   `0x286B1800_51485000`)
+//!
+//! All of them are unary operations: opcode `RRR_0` (5) in the slot's
+//! three-bit opcode field, the `UNARY` extension in the RRR extension
+//! field and the operation in the unary extension field.
+
+use std::cmp;
+
+/// Bundle size in bytes.
+pub const BUNDLE_SIZE: usize = 8;
+
+/// Mask of one 31-bit X-mode slot.
+const SLOT_MASK: u64 = 0x7FFF_FFFF;
+
+/// X0 `fnop`.
+pub const X0_FNOP: u64 = 0x5148_3000;
+
+/// X0 `nop`.
+pub const X0_NOP: u64 = 0x5148_5000;
+
+/// X1 `nop`, as a slot value (bundle bits 31-61).
+pub const X1_NOP: u64 = 0x286B_1800_0000_0000 >> 31;
+
+/// `RRR_0` opcode, the same in the X0 and X1 three-bit opcode fields.
+const OPCODE_RRR_0: u64 = 5;
+
+/// `UNARY` extension of `RRR_0` in X0.
+const UNARY_RRR_0_X0: u64 = 82;
+
+/// `UNARY` extension of `RRR_0` in X1.
+const UNARY_RRR_0_X1: u64 = 53;
+
+/// Bundle mode (bits 62-63); 0 is X mode.
+pub fn mode(bundle: u64) -> u8 {
+    (bundle >> 62) as u8
+}
+
+/// The X0 and X1 slots of an X-mode bundle.
+pub fn x_slots(bundle: u64) -> (u64, u64) {
+    (bundle & SLOT_MASK, (bundle >> 31) & SLOT_MASK)
+}
+
+/// Whether an X0 slot is `fnop` or `nop`.
+pub fn is_x0_filler(slot: u64) -> bool {
+    slot == X0_FNOP || slot == X0_NOP
+}
+
+/// Whether an X1 slot is `nop`.
+pub fn is_x1_filler(slot: u64) -> bool {
+    slot == X1_NOP
+}
+
+/// Whether an X0 slot is a unary operation other than the fillers:
+/// `cntlz` through `tblidxb3`, with extensions 1-12.
+fn is_x0_unary(slot: u64) -> bool {
+    slot >> 28 == OPCODE_RRR_0
+        && (slot >> 18) & 0x3FF == UNARY_RRR_0_X0
+        && (1..=12).contains(&((slot >> 12) & 0x3F))
+}
+
+/// Whether an X1 slot is an `RRR_0` unary operation (jumps through
+/// registers, loads without offset, fences).
+fn is_x1_unary(slot: u64) -> bool {
+    slot >> 28 == OPCODE_RRR_0 && (slot >> 18) & 0x3FF == UNARY_RRR_0_X1
+}
+
+/// Score raw data as TILE-Gx code.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < BUNDLE_SIZE {
+        return 0;
+    }
+
+    let mut score: i64 = 0;
+    let mut zero_run = 0u32;
+    let mut run = 0u32;
+    let mut bundles = 0u32;
+    let mut filled = 0u32;
+
+    for chunk in data.chunks_exact(BUNDLE_SIZE) {
+        let mut bytes = [0u8; BUNDLE_SIZE];
+        bytes.copy_from_slice(chunk);
+        let bundle = u64::from_le_bytes(bytes);
+        bundles += 1;
+
+        if bundle == 0 || bundle == u64::MAX {
+            zero_run += 1;
+            if zero_run > 2 {
+                score -= 2;
+            }
+            run = 0;
+            continue;
+        }
+        zero_run = 0;
+
+        if mode(bundle) != 0 {
+            // Y-mode slots have no fixed fillers to anchor on
+            run = 0;
+            continue;
+        }
+
+        let (x0, x1) = x_slots(bundle);
+        let mut anchored = false;
+        // A filler is an exact 31-bit match: strong evidence on its own
+        if is_x0_filler(x0) {
+            score += 12;
+            anchored = true;
+        } else if is_x0_unary(x0) {
+            score += 2;
+        }
+        if is_x1_filler(x1) {
+            score += 12;
+            anchored = true;
+        } else if is_x1_unary(x1) {
+            score += 2;
+        }
+
+        if anchored {
+            filled += 1;
+            run += 1;
+            // Runs of well-formed bundles are code, not a stray match
+            if run >= 3 {
+                score += 4;
+            }
+        } else {
+            run = 0;
+        }
+    }
+
+    // Fillers are everywhere in compiled code; a large input with almost
+    // none is not TILE-Gx
+    if data.len() > 4096 && filled * 32 < bundles {
+        return 0;
+    }
+    cmp::max(0, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bundle shapes of compiled code: one instruction paired with a
+    /// filler, a `{ fnop ; nop }` bundle and a Y-mode bundle.
+    const FUNCTION: &[u64] = &[
+        0x286B_1800_4000_5D42, // { <X0 op> ; nop }
+        0x1FCB_E800_5148_3000, // { fnop ; <X1 op> }
+        0x286B_1800_2D9C_0040, // { <X0 op> ; nop }
+        0x286B_1800_5148_3000, // { fnop ; nop }
+        0x8A46_0F13_C5D8_2001, // Y mode
+        0x286A_7000_5148_3000, // { fnop ; <X1 op> }
+    ];
+
+    fn to_bytes(bundles: &[u64]) -> Vec<u8> {
+        bundles.iter().flat_map(|b| b.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_fillers() {
+        assert_eq!(x_slots(0x286B_1800_5148_5000), (X0_NOP, X1_NOP));
+        assert_eq!(mode(0x286B_1800_5148_5000), 0);
+        assert!(is_x0_filler(X0_FNOP));
+        assert!(!is_x0_filler(X0_FNOP + 1));
+        assert!(is_x1_unary(X1_NOP));
+        assert_eq!(mode(0x8A46_0F13_C5D8_2001), 2);
+    }
+
+    #[test]
+    fn test_tilegx_scoring() {
+        let code = to_bytes(&FUNCTION.repeat(100));
+
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let noise: Vec<u8> = (0..code.len())
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect();
+
+        let code_score = score(&code);
+        assert!(code_score > 1000, "code scored {code_score}");
+        assert_eq!(score(&noise), 0);
+
+        // Misaligned by half a bundle, no filler lines up
+        assert_eq!(score(&code[4..code.len() - 4]), 0);
+    }
+
+    #[test]
+    fn test_code_classified_as_tilegx() {
+        let code = to_bytes(&FUNCTION.repeat(100));
+        let result =
+            crate::heuristics::analyze(&code, &crate::types::ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::TileGx);
+        assert_eq!(result.bitwidth, 64);
+    }
+
+    #[test]
+    fn test_tilegx_elf() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(b"\x7FELF");
+        data[4] = 2; // ELFCLASS64
+        data[5] = 1; // ELFDATA2LSB
+        data[6] = 1;
+        data[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        data[18..20].copy_from_slice(&191u16.to_le_bytes()); // EM_TILEGX
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+
+        let result = crate::classify_bytes(&data).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::TileGx);
+        assert_eq!(result.bitwidth, 64);
+        assert_eq!(result.endianness, crate::types::Endianness::Little);
+    }
+}
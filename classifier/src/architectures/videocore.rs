@@ -0,0 +1,172 @@
+//! Heuristic scoring for Broadcom VideoCore IV/VI architecture.
+//!
+//! Variable-length little-endian encoding built from 16-bit halfwords; the
+//! top bits of the first halfword give the length:
+//! - `0xxx`: 16-bit scalar
+//! - `10xx`, `110x`: 32-bit scalar (branches, ALU with immediates)
+//! - `1110`: 48-bit scalar (32-bit immediates)
+//! - `11110`: 48-bit vector
+//! - `11111`: 80-bit vector
+//!
+//! Key distinctive encodings:
+//! - `0x0001`: NOP
+//! - `0x005A`: RTS (`b lr`)
+//! - `0x0380`..`0x03FF`: PUSH rb-rb+m, LR
+//! - `0x0300`..`0x037F`: POP rb-rb+m, PC
+//! - `0xE800 | rd`: MOV rd, #imm32
+
+use std::cmp;
+
+/// Instruction length in bytes for the first halfword `hw`.
+fn insn_len(hw: u16) -> usize {
+    match hw >> 11 {
+        0x00..=0x0F => 2,
+        0x10..=0x1B => 4,
+        0x1C..=0x1E => 6,
+        _ => 10,
+    }
+}
+
+/// Whether a PUSH/POP halfword names registers that exist: the range
+/// starts at r0, r6, r16 or r24 and must end by r31.
+fn valid_reg_range(hw: u16) -> bool {
+    let base = [0, 6, 16, 24][usize::from((hw >> 5) & 3)];
+    base + (hw & 0x1F) <= 31
+}
+
+/// Score raw data as VideoCore code.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < 4 {
+        return 0;
+    }
+
+    let mut score: i64 = 0;
+    let mut i = 0;
+    let mut zero_run = 0;
+
+    let mut push_count = 0;
+    let mut return_count = 0;
+    let mut valid_insn = 0;
+
+    while i + 1 < data.len() {
+        let hw = u16::from_le_bytes([data[i], data[i + 1]]);
+
+        if hw == 0x0000 || hw == 0xFFFF {
+            zero_run += 1;
+            if zero_run > 2 {
+                score -= 2;
+            }
+            i += 2;
+            continue;
+        }
+        zero_run = 0;
+
+        let len = insn_len(hw);
+        if i + len > data.len() {
+            // Truncated at the end of the data
+            break;
+        }
+
+        match hw {
+            0x0001 => {
+                // NOP
+                score += 3;
+                valid_insn += 1;
+            }
+            0x005A => {
+                // RTS
+                score += 20;
+                return_count += 1;
+                valid_insn += 1;
+            }
+            0x0380..=0x03FF if valid_reg_range(hw) => {
+                // PUSH rb-rb+m, LR
+                score += 20;
+                push_count += 1;
+                valid_insn += 1;
+            }
+            0x0300..=0x037F if valid_reg_range(hw) => {
+                // POP rb-rb+m, PC
+                score += 20;
+                return_count += 1;
+                valid_insn += 1;
+            }
+            0x0040..=0x007F => {
+                // B rd / BL rd
+                score += 5;
+                valid_insn += 1;
+            }
+            0xE800..=0xE81F => {
+                // MOV rd, #imm32
+                score += 4;
+                valid_insn += 1;
+            }
+            0x9000..=0x9FFF => {
+                // Bcc / BL with 23- or 27-bit displacement
+                score += 2;
+                valid_insn += 1;
+            }
+            _ => {}
+        }
+        i += len;
+    }
+
+    if push_count > 0 && return_count > 0 {
+        score += i64::from(cmp::min(push_count, return_count)) * 10;
+    }
+
+    if valid_insn > 10 {
+        score += i64::from(valid_insn) * 2;
+    }
+
+    if data.len() > 4096 && (push_count == 0 || return_count == 0) {
+        return 0;
+    }
+    cmp::max(0, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A leaf-calling function: push r6-r7, lr; mov r0, #0x12345678;
+    /// bl r1; nop; pop r6-r7, pc; rts.
+    const FUNCTION: &[u16] = &[
+        0x03A1, 0xE800, 0x5678, 0x1234, 0x0061, 0x0001, 0x0321, 0x005A,
+    ];
+
+    fn to_bytes(halfwords: &[u16]) -> Vec<u8> {
+        halfwords.iter().flat_map(|h| h.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_insn_len() {
+        assert_eq!(insn_len(0x005A), 2);
+        assert_eq!(insn_len(0x9F00), 4);
+        assert_eq!(insn_len(0xC000), 4);
+        assert_eq!(insn_len(0xE800), 6);
+        assert_eq!(insn_len(0xF000), 6);
+        assert_eq!(insn_len(0xF800), 10);
+    }
+
+    #[test]
+    fn test_videocore_scoring() {
+        let code = to_bytes(&FUNCTION.repeat(200));
+        assert!(score(&code) > 0);
+        // The MOV immediate is skipped, not decoded as a push
+        assert_eq!(score(&to_bytes(&FUNCTION[1..4])), 4);
+        // r16-r37 does not exist
+        assert!(!valid_reg_range(0x03D5));
+        assert!(valid_reg_range(0x03A1));
+        // Large input without a prologue/epilogue pair
+        assert_eq!(score(&to_bytes(&[0x0001; 4096])), 0);
+    }
+
+    #[test]
+    fn test_firmware_classified_as_videocore() {
+        let code = to_bytes(&FUNCTION.repeat(200));
+        let result =
+            crate::heuristics::analyze(&code, &crate::types::ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::VideoCore5);
+    }
+}
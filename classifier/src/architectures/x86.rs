@@ -1,5 +1,7 @@
 //! x86/x86-64 architecture analysis.
 
+use super::padding::{self, PaddingKind};
+
 /// Common x86 opcodes for identification.
 pub mod opcodes {
     pub const NOP: u8 = 0x90;
@@ -100,6 +102,19 @@ pub fn is_epilogue(data: &[u8]) -> bool {
     false
 }
 
+/// Size of a PC boot sector.
+pub const BOOT_SECTOR_SIZE: usize = 512;
+
+/// Byte length of a PC boot sector (MBR or volume boot record) at offset 0:
+/// the `55 AA` signature at offset 510 behind a leading jump over the BIOS
+/// parameter block or real-mode setup (`cli`, `cld`, `xor ax, ax`). `None`
+/// otherwise.
+pub fn boot_sector_len(data: &[u8]) -> Option<usize> {
+    let signature = data.get(510..BOOT_SECTOR_SIZE)? == [0x55, 0xAA];
+    let entry = matches!(data[0], 0xEB | 0xE9 | 0xFA | 0xFC | 0x31 | 0x33);
+    (signature && entry).then_some(BOOT_SECTOR_SIZE)
+}
+
 /// Score likelihood of x86/x86-64 code.
 ///
 /// Analyzes raw bytes for patterns characteristic of x86/x86-64:
@@ -108,16 +123,50 @@ pub fn is_epilogue(data: &[u8]) -> bool {
 /// - Prologue patterns
 /// - System calls
 pub fn score(data: &[u8], bits: u8) -> i64 {
+    score_with_matches(data, bits).0
+}
+
+/// Like [`score`], also returning how many distinctive idioms (returns, calls
+/// and frame-setup prologues) the structural check counted.
+pub fn score_with_matches(data: &[u8], bits: u8) -> (i64, u32) {
+    let (score, matches, _) = score_with_padding(data, bits);
+    (score, matches)
+}
+
+/// Like [`score_with_matches`], also returning the bytes of alignment
+/// padding runs the scorer skipped as neutral.
+pub fn score_with_padding(data: &[u8], bits: u8) -> (i64, u32, usize) {
     let mut score: i64 = 0;
     let is_64 = bits == 64;
     let mut ret_count = 0u32;
     let mut call_count = 0u32;
     let mut prologue_count = 0u32;
+    let mut padding_bytes = 0usize;
 
     let mut i = 0;
     while i < data.len() {
         let b = data[i];
 
+        // Alignment padding counts only where it leads into a function
+        // entry; longer runs on their own are skipped without points
+        if let Some(run) = padding::x86_padded_entry(data, i) {
+            score += match padding::x86_entry_bits(&data[i + run..]) {
+                Some(entry_bits) if entry_bits == bits => 60,
+                Some(_) => 20,
+                None => 40,
+            };
+            prologue_count += 1;
+            padding_bytes += run;
+            i += run;
+            continue;
+        }
+        let run = padding::run_len(PaddingKind::X86, &data[i..]);
+        if run >= 2 {
+            padding_bytes += run;
+            i += run;
+            continue;
+        }
+
         // Check for prologue patterns (high confidence)
         if i + 3 < data.len() && is_prologue(&data[i..]) {
             score += 25;
@@ -685,7 +734,11 @@ pub fn score(data: &[u8], bits: u8) -> i64 {
         }
     }
 
-    score.max(0)
+    (
+        score.max(0),
+        ret_count + call_count + prologue_count,
+        padding_bytes,
+    )
 }
 
 #[cfg(test)]
@@ -711,7 +764,34 @@ mod tests {
         assert!(score(&[0x55, 0x89, 0xE5], 32) > 0);
         // x86-64 with REX prefix
         assert!(score(&[0x48, 0x89, 0xE5], 64) > 0);
-        // NOP sled
-        assert!(score(&[0x90, 0x90, 0x90, 0x90], 32) > 0);
+        // NOP sled: bare padding is neutral
+        assert_eq!(score(&[0x90, 0x90, 0x90, 0x90], 32), 0);
+    }
+
+    #[test]
+    fn test_score_padding() {
+        // INT3 fill alone carries no evidence
+        assert_eq!(score(&[0xCC; 1024], 64), 0);
+
+        // ret; int3 x11; endbr64; push rbp; mov rbp, rsp
+        let mut padded = vec![0xC3];
+        padded.extend_from_slice(&[0xCC; 11]);
+        padded.extend_from_slice(&[0xF3, 0x0F, 0x1E, 0xFA, 0x55, 0x48, 0x89, 0xE5]);
+        let mut unpadded = padded.clone();
+        unpadded[1..12].fill(0x00);
+        assert!(score(&padded, 64) > score(&unpadded, 64) + 30);
+    }
+
+    #[test]
+    fn test_boot_sector_len() {
+        // jmp short over the BPB; nop
+        let mut data = vec![0u8; 1024];
+        data[..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+        data[510..512].copy_from_slice(&[0x55, 0xAA]);
+        assert_eq!(boot_sector_len(&data), Some(BOOT_SECTOR_SIZE));
+        assert_eq!(boot_sector_len(&data[..511]), None);
+
+        data[0] = 0x7F;
+        assert_eq!(boot_sector_len(&data), None);
     }
 }
@@ -0,0 +1,610 @@
+//! Heuristic scoring for the Zilog Z80.
+//!
+//! The Z80 is an 8-bit little-endian CPU with 16-bit addresses, found in
+//! CP/M machines, the ZX Spectrum, MSX, arcade boards and a long tail of
+//! embedded controllers. Instructions are one to four bytes,
+//! byte-aligned:
+//! - unprefixed opcodes take an 8-bit immediate or displacement (`LD r,n`,
+//!   `JR e`) or a 16-bit little-endian address (`CALL nn`, `LD HL,nn`)
+//! - `0xCB` selects the bit, rotate and shift page; every second byte is
+//!   valid
+//! - `0xED` selects the extended page: block moves (`LDIR`), 16-bit
+//!   loads from memory, `IM`, `RETI`/`RETN`. Half of it is unassigned
+//! - `0xDD`/`0xFD` redirect `HL` to `IX`/`IY`; memory operands gain a
+//!   signed displacement byte
+//!
+//! Almost every byte decodes to something, so single opcodes say little.
+//! The scorer leans on what compiled and hand-written Z80 code is full
+//! of: `CALL nn`/`RET` pairs, `PUSH`/`POP` of register pairs, 16-bit
+//! immediate loads, `DJNZ` loops and well-formed prefixed instructions.
+//! Text decodes as long runs of `LD r,r'` (`0x40`-`0x7F`), which real code
+//! rarely strings together, and is rejected outright.
+//!
+//! Other ISAs' code is dense in the same opcode ranges, so points alone
+//! are not trusted. Input is only scored as Z80 when it is also
+//! structurally Z80 ([`Structure`]): absolute `CALL`/`JP` targets land
+//! inside the image for some load address, prefixed instructions are
+//! well formed, and unconditional `RET`s are not swamped by conditional
+//! ones.
+
+use std::cmp;
+
+/// Common Z80 opcodes.
+pub mod opcodes {
+    /// `NOP`
+    pub const NOP: u8 = 0x00;
+    /// `DJNZ e`
+    pub const DJNZ: u8 = 0x10;
+    /// `JR e`
+    pub const JR: u8 = 0x18;
+    /// `HALT`
+    pub const HALT: u8 = 0x76;
+    /// `JP nn`
+    pub const JP: u8 = 0xC3;
+    /// `RET`
+    pub const RET: u8 = 0xC9;
+    /// `CALL nn`
+    pub const CALL: u8 = 0xCD;
+    /// `DI`
+    pub const DI: u8 = 0xF3;
+    /// `EI`
+    pub const EI: u8 = 0xFB;
+    /// Bit, rotate and shift page.
+    pub const PREFIX_CB: u8 = 0xCB;
+    /// `IX` page.
+    pub const PREFIX_DD: u8 = 0xDD;
+    /// Extended page.
+    pub const PREFIX_ED: u8 = 0xED;
+    /// `IY` page.
+    pub const PREFIX_FD: u8 = 0xFD;
+    /// `LDIR` (after `0xED`).
+    pub const LDIR: u8 = 0xB0;
+    /// `LDDR` (after `0xED`).
+    pub const LDDR: u8 = 0xB8;
+}
+
+use opcodes::{
+    CALL, DI, DJNZ, EI, HALT, JP, LDDR, LDIR, NOP, PREFIX_CB, PREFIX_DD, PREFIX_ED, PREFIX_FD, RET,
+};
+
+/// Longest run of `LD r,r'` that still looks like code.
+const MAX_LD_RUN: u32 = 4;
+
+/// Bank size of banked Z80 ROMs; longer input is judged one bank at a
+/// time.
+const BANK_SIZE: usize = 0x4000;
+
+/// Structural evidence gathered while scoring.
+#[derive(Debug, Default)]
+struct Structure {
+    /// `CALL`/`JP` targets, with the offset of the instruction.
+    targets: Vec<(usize, u16)>,
+    /// Prefixed (`CB`/`ED`/`DD`/`FD`) instructions.
+    prefixed: u32,
+    /// Prefixed instructions that decode to nothing a compiler emits.
+    bad_prefixed: u32,
+    /// Unconditional `RET`s.
+    rets: u32,
+    /// Conditional `RET cc`s.
+    cond_rets: u32,
+}
+
+impl Structure {
+    /// Absolute targets that land inside their bank for the load address
+    /// (a multiple of 256) that places the most of them there.
+    fn targets_in_image(&self, len: usize) -> usize {
+        let mut in_image = 0;
+        for bank_start in (0..len).step_by(BANK_SIZE) {
+            let bank_len = (len - bank_start).min(BANK_SIZE);
+            let mut votes = [0u32; 256];
+            for &(_, target) in self
+                .targets
+                .iter()
+                .filter(|&&(offset, _)| offset / BANK_SIZE == bank_start / BANK_SIZE)
+            {
+                // Pages p with p * 256 <= target < p * 256 + bank_len
+                let target = usize::from(target);
+                let first = (target + 1).saturating_sub(bank_len).div_ceil(256);
+                for page in first..=target / 256 {
+                    votes[page] += 1;
+                }
+            }
+            in_image += votes.iter().max().copied().unwrap_or(0) as usize;
+        }
+        in_image
+    }
+
+    /// Whether the evidence looks like Z80 code rather than another ISA's
+    /// bytes, given [`targets_in_image`](Self::targets_in_image).
+    fn is_plausible(&self, in_image: usize) -> bool {
+        // Over half of the absolute targets stay inside the image; random
+        // operands of a 16 KiB bank land there about a quarter of the time
+        if in_image * 2 < self.targets.len() {
+            return false;
+        }
+        // Real code uses the prefix pages as documented; other bytes hit
+        // their unassigned halves
+        if self.bad_prefixed * 4 > self.prefixed {
+            return false;
+        }
+        // Every routine ends in a RET; RET cc opcodes outnumber RET eight
+        // to one in random bytes
+        self.rets * 2 >= self.cond_rets
+    }
+}
+
+/// Length of an unprefixed instruction.
+fn base_len(op: u8) -> usize {
+    match op {
+        // LD r,n; DJNZ; JR; ALU A,n; OUT (n),A; IN A,(n)
+        0x06 | 0x0E | 0x16 | 0x1E | 0x26 | 0x2E | 0x36 | 0x3E => 2,
+        0x10 | 0x18 | 0x20 | 0x28 | 0x30 | 0x38 => 2,
+        0xC6 | 0xCE | 0xD6 | 0xDE | 0xE6 | 0xEE | 0xF6 | 0xFE => 2,
+        0xD3 | 0xDB => 2,
+        // LD rr,nn; LD (nn),HL/A; LD HL/A,(nn); JP; CALL
+        0x01 | 0x11 | 0x21 | 0x31 | 0x22 | 0x2A | 0x32 | 0x3A => 3,
+        0xC2 | 0xC3 | 0xCA | 0xD2 | 0xDA | 0xE2 | 0xEA | 0xF2 | 0xFA => 3,
+        0xC4 | 0xCC | 0xCD | 0xD4 | 0xDC | 0xE4 | 0xEC | 0xF4 | 0xFC => 3,
+        _ => 1,
+    }
+}
+
+/// Score and length of an `0xED` page instruction; `op` is the second
+/// byte.
+fn score_ed(op: u8) -> (i64, usize) {
+    match op {
+        // LD (nn),rr / LD rr,(nn)
+        0x43 | 0x4B | 0x53 | 0x5B | 0x73 | 0x7B => (5, 4),
+        // Undocumented duplicates of the HL forms and NOPs
+        0x63 | 0x6B => (0, 4),
+        0x77 | 0x7F => (-2, 2),
+        // IN/OUT (C), SBC/ADC HL, NEG, RETN/RETI, IM, LD I/R, RRD/RLD
+        0x40..=0x7F => (4, 2),
+        LDIR | LDDR => (6, 2),
+        // Other block transfers, compares and I/O
+        0xA0..=0xA3 | 0xA8..=0xAB | 0xB1..=0xB3 | 0xB9..=0xBB => (4, 2),
+        _ => (-4, 2),
+    }
+}
+
+/// Score and length of an `IX`/`IY` page instruction; `op` is the byte
+/// after the prefix.
+fn score_index(op: u8) -> (i64, usize) {
+    match op {
+        // ADD IX,rr; INC/DEC IX
+        0x09 | 0x19 | 0x29 | 0x39 | 0x23 | 0x2B => (4, 2),
+        // LD IX,nn; LD (nn),IX; LD IX,(nn)
+        0x21 | 0x22 | 0x2A => (5, 4),
+        // INC/DEC (IX+d)
+        0x34 | 0x35 => (4, 3),
+        // LD (IX+d),n
+        0x36 => (5, 4),
+        // LD r,(IX+d)
+        0x46 | 0x4E | 0x56 | 0x5E | 0x66 | 0x6E | 0x7E => (5, 3),
+        // LD (IX+d),r
+        0x70..=0x75 | 0x77 => (5, 3),
+        // ALU A,(IX+d)
+        0x86 | 0x8E | 0x96 | 0x9E | 0xA6 | 0xAE | 0xB6 | 0xBE => (4, 3),
+        // Bit operations on (IX+d)
+        PREFIX_CB => (4, 4),
+        // POP/PUSH IX, EX (SP),IX, JP (IX), LD SP,IX
+        0xE1 | 0xE3 | 0xE5 | 0xE9 | 0xF9 => (4, 2),
+        // Prefix with nothing to redirect: a wasted byte compilers never emit
+        _ => (-3, 1),
+    }
+}
+
+/// Score raw data as Z80 code.
+pub fn score(data: &[u8]) -> i64 {
+    if data.len() < 8 {
+        return 0;
+    }
+
+    // Text decodes as a wall of LD r,r'; real images are far from all
+    // printable
+    let printable = data
+        .iter()
+        .filter(|&&b| (0x20..0x7F).contains(&b) || matches!(b, b'\t' | b'\n' | b'\r'))
+        .count();
+    if printable * 10 > data.len() * 9 {
+        return 0;
+    }
+
+    let mut score: i64 = 0;
+    let mut i = 0;
+    let mut pad_run = 0u32;
+    let mut ld_run = 0u32;
+    let mut calls = 0u32;
+    let mut stack_ops = 0u32;
+    let mut structure = Structure::default();
+
+    while i < data.len() {
+        let op = data[i];
+
+        if op == NOP || op == 0xFF {
+            pad_run += 1;
+            if pad_run > 2 {
+                score -= 2;
+            }
+            ld_run = 0;
+            i += 1;
+            continue;
+        }
+        pad_run = 0;
+
+        if (0x40..=0x7F).contains(&op) && op != HALT {
+            ld_run += 1;
+            score += if ld_run > MAX_LD_RUN { -2 } else { 1 };
+            i += 1;
+            continue;
+        }
+        ld_run = 0;
+
+        // CALL nn, CALL cc,nn, JP nn and JP cc,nn
+        if matches!(op & 0xC7, 0xC2 | 0xC4) || op == CALL || op == JP {
+            if let Some(&[lo, hi]) = data.get(i + 1..i + 3) {
+                // Fill bytes decode as calls to $FFFF, $CCCC and the like
+                if lo != hi {
+                    structure.targets.push((i, u16::from_le_bytes([lo, hi])));
+                }
+            }
+        }
+
+        let (points, len) = match op {
+            RET => {
+                structure.rets += 1;
+                (6, 1)
+            }
+            CALL => {
+                calls += 1;
+                (6, 3)
+            }
+            // RET cc
+            0xC0 | 0xC8 | 0xD0 | 0xD8 | 0xE0 | 0xE8 | 0xF0 | 0xF8 => {
+                structure.cond_rets += 1;
+                (3, 1)
+            }
+            // CALL cc
+            0xC4 | 0xCC | 0xD4 | 0xDC | 0xE4 | 0xEC | 0xF4 | 0xFC => {
+                calls += 1;
+                (3, 3)
+            }
+            JP => (3, 3),
+            DJNZ => (3, 2),
+            // PUSH/POP rr
+            0xC1 | 0xC5 | 0xD1 | 0xD5 | 0xE1 | 0xE5 | 0xF1 | 0xF5 => {
+                stack_ops += 1;
+                (3, 1)
+            }
+            // LD rr,nn; LD (nn),HL; LD HL,(nn); LD (nn),A; LD A,(nn)
+            0x01 | 0x11 | 0x21 | 0x31 | 0x22 | 0x2A | 0x32 | 0x3A => (2, 3),
+            // EXX, EX AF,AF', EX DE,HL, DI, EI
+            0xD9 | 0x08 | 0xEB | DI | EI => (2, 1),
+            // INC/DEC rr, RST, HALT
+            0x03 | 0x13 | 0x23 | 0x33 | 0x0B | 0x1B | 0x2B | 0x3B => (1, 1),
+            0xC7 | 0xCF | 0xD7 | 0xDF | 0xE7 | 0xEF | 0xF7 | HALT => (1, 1),
+            PREFIX_CB => {
+                structure.prefixed += 1;
+                (1, 2)
+            }
+            PREFIX_ED | PREFIX_DD | PREFIX_FD => {
+                let Some(&next) = data.get(i + 1) else {
+                    break;
+                };
+                let (points, len) = if op == PREFIX_ED {
+                    score_ed(next)
+                } else {
+                    score_index(next)
+                };
+                structure.prefixed += 1;
+                if points < 0 {
+                    structure.bad_prefixed += 1;
+                }
+                (points, len)
+            }
+            // ALU A,r
+            0x80..=0xBF => (1, 1),
+            _ => (0, base_len(op)),
+        };
+        if i + len > data.len() {
+            break;
+        }
+        score += points;
+        i += len;
+    }
+
+    // Subroutines are the backbone of Z80 code; input without calls and
+    // returns is something else
+    let rets = structure.rets + structure.cond_rets;
+    let in_image = structure.targets_in_image(data.len());
+    if rets == 0 || calls == 0 || !structure.is_plausible(in_image) {
+        return 0;
+    }
+    // Each resolved control-flow edge is evidence in its own right
+    score += in_image as i64 * 6;
+    if rets > 4 && calls > 4 && stack_ops > 4 {
+        score += i64::from(rets.min(calls) + stack_ops) * 2;
+    }
+
+    cmp::max(0, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small ROM laid out from address 0 as on a ZX Spectrum: restart
+    /// and interrupt vectors at their fixed addresses, screen and keyboard
+    /// drivers, arithmetic helpers, a RAM test and the messages and tables
+    /// they use.
+    const ROM: &[u8] = &[
+        // reset ($0000)
+        0xF3, 0xAF, 0x31, 0x00, 0xFF, 0xC3, 0x68, 0x00, // di; xor a; ld sp,$ff00; jp start
+        // rst8 ($0008)
+        0xC3, 0xF3, 0x01, // jp error
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // rst10 ($0010)
+        0xC3, 0x8B, 0x00, // jp print_a
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // im1 ($0038)
+        0xF5, 0xE5, 0xC5, 0xD5, // push af; push hl; push bc; push de
+        0xCD, 0x40, 0x01, 0x32, 0x05, 0x5C, 0xD1, // call scan_keys; ld (KEY),a; pop de
+        0xC1, 0xE1, 0xF1, 0xFB, 0xC9, // pop bc; pop hl; pop af; ei; ret
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        // nmi ($0066)
+        0xED, 0x45, // retn
+        // start ($0068)
+        0xED, 0x56, 0xCD, 0x00, 0x01, 0xCD, 0x21, 0x01, // im 1; call cls; call init_vars
+        0x21, 0x3D, 0x02, 0xCD, 0x84, 0x00, 0xFB, // ld hl,msg_ready; call print_str; ei
+        // main_loop ($0077)
+        0x76, 0x3A, 0x05, 0x5C, 0xFE, 0xFF, 0x28,
+        0xF8, // halt; ld a,(KEY); cp $ff; jr z,main_loop
+        0xCD, 0x65, 0x01, 0x18, 0xF3, // call handle_key; jr main_loop
+        // print_str ($0084)
+        0x7E, 0xB7, 0xC8, 0xD7, 0x23, // ld a,(hl); or a; ret z; rst $10; inc hl
+        0x18, 0xF9, // jr print_str
+        // print_a ($008B)
+        0xE5, 0xD5, 0xC5, 0xFE, 0x0D, // push hl; push de; push bc; cp 13
+        0x28, 0x24, 0x4F, 0x2A, 0x00, 0x5C, // jr z,print_newline; ld c,a; ld hl,(CURSOR)
+        0xCD, 0xCE, 0x00, 0x79, 0xCD, 0xDE, 0x00, // call char_addr; ld a,c; call glyph_addr
+        0x06, 0x08, // ld b,8
+        // print_row ($009F)
+        0x1A, 0x77, 0x13, 0x24, 0x10,
+        0xFA, // ld a,(de); ld (hl),a; inc de; inc h; djnz print_row
+        0x2A, 0x00, 0x5C, 0x2C, 0x7D, 0xFE, 0x20, // ld hl,(CURSOR); inc l; ld a,l; cp 32
+        0xCC, 0xC1, 0x00, 0x22, 0x00, 0x5C, // call z,next_line; ld (CURSOR),hl
+        // print_done ($00B2)
+        0xC1, 0xD1, 0xE1, 0xC9, // pop bc; pop de; pop hl; ret
+        // print_newline ($00B6)
+        0x2A, 0x00, 0x5C, 0xCD, 0xC1, 0x00, // ld hl,(CURSOR); call next_line
+        0x22, 0x00, 0x5C, 0x18, 0xF1, // ld (CURSOR),hl; jr print_done
+        // next_line ($00C1)
+        0x2E, 0x00, 0x24, 0x7C, 0xFE, 0x18, 0xD8, // ld l,0; inc h; ld a,h; cp 24; ret c
+        0x26, 0x17, 0xCD, 0xEC, 0x00, 0xC9, // ld h,23; call scroll; ret
+        // char_addr ($00CE)
+        0x7C, 0xE6, 0x18, 0xF6, 0x40, 0x57, 0x7C, // ld a,h; and $18; or $40; ld d,a; ld a,h
+        0xE6, 0x07, 0x0F, 0x0F, 0x0F, 0x85, 0x5F, // and 7; rrca; rrca; rrca; add a,l; ld e,a
+        0xEB, 0xC9, // ex de,hl; ret
+        // glyph_addr ($00DE)
+        0xD6, 0x20, 0x6F, 0x26, 0x00, 0x29,
+        0x29, // sub 32; ld l,a; ld h,0; add hl,hl; add hl,hl
+        0x29, 0x11, 0x71, 0x02, 0x19, 0xEB,
+        0xC9, // add hl,hl; ld de,font; add hl,de; ex de,hl; ret
+        // scroll ($00EC)
+        0x21, 0x20, 0x40, 0x11, 0x00, 0x40, // ld hl,$4020; ld de,$4000
+        0x01, 0xE0, 0x17, 0xED, 0xB0, 0xEB, 0x06,
+        0x20, // ld bc,$17e0; ldir; ex de,hl; ld b,32
+        // scroll_clear ($00FA)
+        0x36, 0x00, 0x23, 0x10, 0xFB, 0xC9, // ld (hl),0; inc hl; djnz scroll_clear; ret
+        // cls ($0100)
+        0x21, 0x00, 0x40, 0x11, 0x01, 0x40, // ld hl,$4000; ld de,$4001
+        0x01, 0xFF, 0x17, 0x36, 0x00, 0xED, 0xB0, // ld bc,$17ff; ld (hl),0; ldir
+        0x21, 0x00, 0x58, 0x11, 0x01, 0x58, // ld hl,$5800; ld de,$5801
+        0x01, 0xFF, 0x02, 0x36, 0x38, 0xED, 0xB0, // ld bc,$2ff; ld (hl),$38; ldir
+        0x21, 0x00, 0x00, 0x22, 0x00, 0x5C, 0xC9, // ld hl,0; ld (CURSOR),hl; ret
+        // init_vars ($0121)
+        0xDD, 0x21, 0x00, 0x5C, 0xDD, 0x36, 0x00, 0x00, // ld ix,VARS; ld (ix+0),0
+        0xDD, 0x36, 0x01, 0x00, 0xDD, 0x36, 0x02, 0xFF, // ld (ix+1),0; ld (ix+2),$ff
+        0xDD, 0x36, 0x05, 0xFF, 0xED, 0x73, 0x03, 0x5C, // ld (ix+5),$ff; ld (ERR_SP),sp
+        0x21, 0x34, 0x12, 0x22, 0x06, 0x5C, 0xC9, // ld hl,$1234; ld (SEED),hl; ret
+        // scan_keys ($0140)
+        0x01, 0xFE, 0xFE, 0x16, 0x00, // ld bc,$fefe; ld d,0
+        // scan_row ($0145)
+        0xED, 0x78, 0x2F, 0xE6, 0x1F, 0x20, 0x0B, // in a,(c); cpl; and $1f; jr nz,scan_found
+        0x7A, 0xC6, 0x05, 0x57, 0xCB, 0x00, // ld a,d; add a,5; ld d,a; rlc b
+        0x38, 0xF1, 0x3E, 0xFF, 0xC9, // jr c,scan_row; ld a,$ff; ret
+        // scan_found ($0157)
+        0x1F, 0x38, 0x03, 0x14, 0x18, 0xFA, // rra; jr c,scan_bit; inc d; jr scan_found
+        // scan_bit ($015D)
+        0x7A, 0x21, 0x4D, 0x02, 0x85, 0x6F, // ld a,d; ld hl,keymap; add a,l; ld l,a
+        0x7E, 0xC9, // ld a,(hl); ret
+        // handle_key ($0165)
+        0xFE, 0x0D, 0x28, 0x08, 0xFE, 0x20, 0xD8, // cp 13; jr z,key_enter; cp 32; ret c
+        0xD7, 0xCD, 0x83, 0x01, 0xC9, // rst $10; call beep; ret
+        // key_enter ($0171)
+        0x3E, 0x0D, 0xD7, 0xCD, 0xA8, 0x01, 0xE6, 0x07, // ld a,13; rst $10; call rand; and 7
+        0xCD, 0x9B, 0x01, 0x21, 0x43, 0x02, // call set_border; ld hl,msg_ok
+        0xCD, 0x84, 0x00, 0xC9, // call print_str; ret
+        // beep ($0183)
+        0xF3, 0x06, 0x40, // di; ld b,64
+        // beep_loop ($0186)
+        0x3E, 0x10, 0xD3, 0xFE, 0x0E, 0x20, // ld a,$10; out ($fe),a; ld c,32
+        // beep_wait1 ($018C)
+        0x0D, 0x20, 0xFD, 0xAF, 0xD3, 0xFE, // dec c; jr nz,beep_wait1; xor a; out ($fe),a
+        0x0E, 0x20, // ld c,32
+        // beep_wait2 ($0194)
+        0x0D, 0x20, 0xFD, 0x10, 0xED, 0xFB, // dec c; jr nz,beep_wait2; djnz beep_loop; ei
+        0xC9, // ret
+        // set_border ($019B)
+        0xE6, 0x07, 0xD3, 0xFE, 0x07, 0x07, 0x07, // and 7; out ($fe),a; rlca; rlca; rlca
+        0xF6, 0x07, 0x32, 0x08, 0x5C, 0xC9, // or 7; ld (BORDCR),a; ret
+        // rand ($01A8)
+        0x2A, 0x06, 0x5C, 0x54, 0x5D, 0x29, // ld hl,(SEED); ld d,h; ld e,l; add hl,hl
+        0x29, 0x19, 0x11, 0x29, 0x00, 0x19, // add hl,hl; add hl,de; ld de,41; add hl,de
+        0x22, 0x06, 0x5C, 0x7C, 0xC9, // ld (SEED),hl; ld a,h; ret
+        // mul8 ($01B9)
+        0x16, 0x00, 0x6A, 0x06, 0x08, // ld d,0; ld l,d; ld b,8
+        // mul8_loop ($01BE)
+        0x29, 0x30, 0x01, 0x19, // add hl,hl; jr nc,mul8_skip; add hl,de
+        // mul8_skip ($01C2)
+        0x10, 0xFA, 0xC9, // djnz mul8_loop; ret
+        // div16 ($01C5)
+        0x01, 0x00, 0x00, 0x3E, 0x10, // ld bc,0; ld a,16
+        // div16_loop ($01CA)
+        0x29, 0xCB, 0x11, 0xCB, 0x10, 0xC5, 0xE3, // add hl,hl; rl c; rl b; push bc; ex (sp),hl
+        0xB7, 0xED, 0x52, 0x38, 0x05, 0xE3, // or a; sbc hl,de; jr c,div16_restore; ex (sp),hl
+        0x2C, 0xC1, 0xC5, 0xE3, // inc l; pop bc; push bc; ex (sp),hl
+        // div16_restore ($01DB)
+        0xE3, 0xC1, 0x3D, 0x20, 0xEA, 0xC9, // ex (sp),hl; pop bc; dec a; jr nz,div16_loop; ret
+        // print_num ($01E1)
+        0x11, 0x0A, 0x00, 0xCD, 0xC5, 0x01, 0x79,
+        0xF5, // ld de,10; call div16; ld a,c; push af
+        0x7C, 0xB5, 0xC4, 0xE1, 0x01, 0xF1, // ld a,h; or l; call nz,print_num; pop af
+        0xC6, 0x30, 0xD7, 0xC9, // add a,'0'; rst $10; ret
+        // error ($01F3)
+        0xE1, 0x7E, 0x32, 0x02, 0x5C, // pop hl; ld a,(hl); ld (ERR_NR),a
+        0x21, 0x46, 0x02, 0xCD, 0x84, 0x00, // ld hl,msg_error; call print_str
+        0x2A, 0x02, 0x5C, 0x26, 0x00, 0xCD, 0xE1,
+        0x01, // ld hl,(ERR_NR); ld h,0; call print_num
+        0xED, 0x7B, 0x03, 0x5C, 0xC3, 0x77, 0x00, // ld sp,(ERR_SP); jp main_loop
+        // checksum ($020D)
+        0xAF, // xor a
+        // checksum_loop ($020E)
+        0x86, 0x23, 0x0B, 0x57, 0x78, 0xB1, // add a,(hl); inc hl; dec bc; ld d,a; ld a,b; or c
+        0x7A, 0x20, 0xF7, 0xC9, // ld a,d; jr nz,checksum_loop; ret
+        // copy_block ($0218)
+        0x78, 0xB1, 0xC8, 0xED, 0xB0, 0xC9, // ld a,b; or c; ret z; ldir; ret
+        // test_ram ($021E)
+        0x21, 0x00, 0x80, 0x01, 0x00, 0x80, // ld hl,$8000; ld bc,$8000
+        // test_ram_loop ($0224)
+        0x36, 0x55, 0x7E, 0xFE, 0x55, // ld (hl),$55; ld a,(hl); cp $55
+        0xC2, 0x3B, 0x02, 0x36, 0xAA, 0x7E, // jp nz,ram_fail; ld (hl),$aa; ld a,(hl)
+        0xFE, 0xAA, 0xC2, 0x3B, 0x02, 0x23, 0x0B, // cp $aa; jp nz,ram_fail; inc hl; dec bc
+        0x78, 0xB1, 0x20, 0xEA, 0xC9, // ld a,b; or c; jr nz,test_ram_loop; ret
+        // ram_fail ($023B)
+        0xCF, 0x03, // rst 8; defb 3
+        // msg_ready ($023D)
+        0x52, 0x45, 0x41, 0x44, 0x59, 0x00, // defm "READY",0
+        // msg_ok ($0243)
+        0x4F, 0x4B, 0x00, // defm "OK",0
+        // msg_error ($0246)
+        0x45, 0x52, 0x52, 0x4F, 0x52, 0x20, 0x00, // defm "ERROR ",0
+        // keymap ($024D)
+        0x00, 0x5A, 0x58, 0x43, 0x56, 0x41, 0x53, 0x44, // defm keymap
+        0x46, 0x47, 0x51, 0x57, 0x45, 0x52, 0x54, 0x31, 0x32, 0x33, 0x34, 0x35, 0x30, 0x39, 0x38,
+        0x37, 0x36, 0x50, 0x4F, 0x49, 0x55, 0x59, 0x0D, 0x4C, 0x4B, 0x4A, 0x48, 0x20,
+        // font ($0271)
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // ' '
+        0x00, 0x10, 0x10, 0x10, 0x10, 0x00, 0x10, 0x00, // '!'
+    ];
+
+    #[test]
+    fn test_instruction_lengths() {
+        assert_eq!(base_len(CALL), 3);
+        assert_eq!(base_len(0x3E), 2);
+        assert_eq!(base_len(RET), 1);
+        assert_eq!(score_ed(LDIR), (6, 2));
+        assert_eq!(score_ed(0x5B), (5, 4));
+        assert!(score_ed(0x00).0 < 0);
+        assert_eq!(score_index(0x36), (5, 4));
+        assert_eq!(score_index(PREFIX_CB), (4, 4));
+        assert!(score_index(0x00).0 < 0);
+    }
+
+    #[test]
+    fn test_z80_rom() {
+        let z80 = score(ROM);
+        let x86 = crate::architectures::x86::score(ROM, 32);
+        assert!(z80 > 100, "scored {z80}");
+        assert!(z80 > x86, "{z80} vs x86 {x86}");
+    }
+
+    #[test]
+    fn test_rom_targets_resolve() {
+        let mut structure = Structure::default();
+        for (i, pair) in ROM.windows(3).enumerate() {
+            if pair[0] == CALL {
+                structure
+                    .targets
+                    .push((i, u16::from_le_bytes([pair[1], pair[2]])));
+            }
+        }
+        assert!(structure.targets.len() > 10);
+        assert_eq!(
+            structure.targets_in_image(ROM.len()),
+            structure.targets.len()
+        );
+
+        // The same calls as seen by a ROM at $8000
+        for (_, target) in &mut structure.targets {
+            *target += 0x8000;
+        }
+        assert_eq!(
+            structure.targets_in_image(ROM.len()),
+            structure.targets.len()
+        );
+    }
+
+    #[test]
+    fn test_z80_rejects_noise() {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let noise: Vec<u8> = (0..64 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect();
+        assert_eq!(score(&noise), 0);
+    }
+
+    /// Compiled x86-64 code is dense in `LD r,r'`, ALU and `CALL`/`RET`
+    /// opcode bytes; the test binary's own `.text` is a ready sample.
+    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+    #[test]
+    fn test_z80_rejects_x86_64_code() {
+        let exe = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let options = crate::types::ClassifierOptions {
+            collect_sections: true,
+            ..crate::types::ClassifierOptions::new()
+        };
+        let payload = crate::detect_payload(&exe, &options).unwrap();
+        let text = payload
+            .sections
+            .iter()
+            .find(|s| s.name == ".text")
+            .expect(".text");
+        let text = &exe[text.offset as usize..][..text.size as usize];
+
+        for chunk in text.chunks_exact(64 * 1024).take(8) {
+            assert_eq!(score(chunk), 0);
+        }
+        for chunk in text.chunks_exact(4096).step_by(97).take(8) {
+            assert_eq!(score(chunk), 0);
+        }
+    }
+
+    #[test]
+    fn test_z80_rejects_text() {
+        let text = b"The quick brown fox jumps over the lazy dog. \
+            Pack my box with five dozen liquor jugs.\n"
+            .repeat(40);
+        assert_eq!(score(&text), 0);
+    }
+
+    #[test]
+    fn test_z80_padding_penalized() {
+        assert_eq!(score(&[0xFF; 1024]), 0);
+        assert_eq!(score(&[0x00; 1024]), 0);
+    }
+
+    #[test]
+    fn test_code_classified_as_z80() {
+        let result =
+            crate::heuristics::analyze(ROM, &crate::types::ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, crate::types::Isa::Z80);
+        assert_eq!(result.endianness, crate::types::Endianness::Little);
+    }
+}
@@ -0,0 +1,38 @@
+//! Timestamps for batch outputs.
+//!
+//! Every timestamp the batch store writes (run manifest timing, stats
+//! `last_updated`, sidecar `ingested_at`, ledger `ts`, index shard and ref
+//! file times, the default run ID) comes from [`now`], never from
+//! `Utc::now()` directly. When a source date is pinned — by
+//! [`PipelineConfig::source_date`](super::PipelineConfig::source_date) in the
+//! library, or by the `SOURCE_DATE_EPOCH` environment variable in the CLI —
+//! all of them are that instant, and durations and rates come out as zero.
+//! See <https://reproducible-builds.org/specs/source-date-epoch/>.
+
+use chrono::{DateTime, Utc};
+
+/// Environment variable naming the pinned time, in seconds since the Unix epoch.
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Parse a `SOURCE_DATE_EPOCH` value: a non-negative decimal count of
+/// seconds since the Unix epoch. Anything else is rejected.
+pub fn parse_source_date_epoch(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    DateTime::from_timestamp(value.parse().ok()?, 0)
+}
+
+/// The pinned time from the `SOURCE_DATE_EPOCH` environment variable, if
+/// set to a valid value.
+pub fn source_date_epoch() -> Option<DateTime<Utc>> {
+    std::env::var(SOURCE_DATE_EPOCH)
+        .ok()
+        .and_then(|value| parse_source_date_epoch(&value))
+}
+
+/// The current time, or `source_date` when one is pinned.
+pub fn now(source_date: Option<DateTime<Utc>>) -> DateTime<Utc> {
+    source_date.unwrap_or_else(Utc::now)
+}
@@ -17,6 +17,7 @@
 //! isa-classify batch -i /data/firmware -o /staging/run-001 -j 16
 //! ```
 
+pub mod clock;
 pub mod keys;
 pub mod pipeline;
 pub mod routing;
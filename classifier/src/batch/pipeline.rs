@@ -8,6 +8,7 @@
 //!
 //! All stages communicate via bounded crossbeam channels.
 
+use super::clock;
 use super::keys::KeyConfig;
 use super::routing::{route, RoutingConfig, RoutingDecision, RoutingInput};
 use super::stats::PipelineStats;
@@ -15,9 +16,10 @@ use super::types::*;
 use super::writer::{ClassifiedFile, LedgerRotationConfig, StagingWriter};
 use crate::detect_payload;
 use crate::types::{ClassificationSource, ClassifierOptions, Endianness, FileFormat, Isa};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use sha2::{Digest, Sha256};
+use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -77,12 +79,16 @@ pub struct PipelineConfig {
     pub deep_scan: bool,
     /// Enable extension detection.
     pub detect_extensions: bool,
+    /// Pinned time for every timestamp the run writes, for reproducible
+    /// output (see [`clock`]). Durations and rates are then zero.
+    pub source_date: Option<DateTime<Utc>>,
 }
 
 impl PipelineConfig {
-    /// Generate a default run ID based on current time.
+    /// Generate a default run ID based on current time, or on
+    /// `SOURCE_DATE_EPOCH` when it is set.
     pub fn generate_run_id() -> String {
-        let now = Utc::now();
+        let now = clock::now(clock::source_date_epoch());
         format!("{}-batch-001", now.format("%Y%m%dT%H%M%SZ"))
     }
 }
@@ -105,7 +111,7 @@ pub fn run_pipeline(
     stats: Arc<PipelineStats>,
     shutdown: Arc<AtomicBool>,
 ) -> std::io::Result<PipelineResult> {
-    let started_at = Utc::now();
+    let started_at = clock::now(config.source_date);
 
     let key_config = KeyConfig::new(&config.prefix);
     let classifier_version = crate::version().to_string();
@@ -139,7 +145,8 @@ pub fn run_pipeline(
         .spawn(move || {
             let walker = WalkDir::new(&walker_config.input_dir)
                 .follow_links(walker_config.follow_symlinks)
-                .min_depth(1);
+                .min_depth(1)
+                .sort_by_file_name();
 
             for entry in walker {
                 if walker_shutdown.load(Ordering::Relaxed) {
@@ -280,13 +287,16 @@ pub fn run_pipeline(
                     let original_name = item
                         .path
                         .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unnamed")
-                        .to_string();
-                    let source_path = item.path.display().to_string();
+                        .map_or_else(|| OsString::from("unnamed"), OsStr::to_os_string);
+                    let source_path = item.path.clone().into_os_string();
+                    let display_name = original_name.to_string_lossy().into_owned();
 
                     // Run classification
-                    let payload = match detect_payload(&item.data, &opts) {
+                    let file_opts = ClassifierOptions {
+                        input_len: Some(item.data.len() as u64),
+                        ..opts.clone()
+                    };
+                    let payload = match detect_payload(&item.data, &file_opts) {
                         Ok(p) => p,
                         Err(e) => {
                             st.errors.fetch_add(1, Ordering::Relaxed);
@@ -300,7 +310,7 @@ pub fn run_pipeline(
                                 &item.sha256_hex,
                                 AmbiguousReason::Error,
                                 &FileFormat::Raw,
-                                &original_name,
+                                &display_name,
                             );
                             let result = ClassifiedFile {
                                 sha256_hex: item.sha256_hex,
@@ -365,7 +375,7 @@ pub fn run_pipeline(
                             &isa,
                             bitwidth,
                             &endianness,
-                            &original_name,
+                            &display_name,
                         ),
                         RoutingStatus::Ambiguous => {
                             let reason =
@@ -374,7 +384,7 @@ pub fn run_pipeline(
                                 &item.sha256_hex,
                                 reason,
                                 &format,
-                                &original_name,
+                                &display_name,
                             )
                         }
                         _ => key_cfg.classified_view_key(
@@ -383,7 +393,7 @@ pub fn run_pipeline(
                             &isa,
                             bitwidth,
                             &endianness,
-                            &original_name,
+                            &display_name,
                         ),
                     };
 
@@ -430,7 +440,8 @@ pub fn run_pipeline(
         config.run_id.clone(),
         classifier_version.clone(),
         config.ledger_config.clone(),
-    );
+    )
+    .with_source_date(config.source_date);
 
     while let Ok(result) = class_rx.recv() {
         if shutdown.load(Ordering::Relaxed) {
@@ -469,7 +480,7 @@ pub fn run_pipeline(
     // Flush remaining buffers
     writer.flush_all()?;
 
-    let completed_at = Utc::now();
+    let completed_at = clock::now(config.source_date);
     let duration = (completed_at - started_at).num_milliseconds() as f64 / 1000.0;
     let files_per_second = if duration > 0.0 {
         writer.counts.processed as f64 / duration
@@ -547,3 +558,132 @@ pub fn run_pipeline(
         stats: Arc::try_unwrap(stats).unwrap_or_else(|arc| (*arc).clone()),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small mixed corpus: x86-64 ELF, big-endian PowerPC code, text.
+    fn corpus() -> Vec<(&'static str, Vec<u8>)> {
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(b"\x7FELF");
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[16] = 2;
+        elf[18] = 0x3E;
+        elf[20] = 1;
+        elf[52] = 64;
+        elf.extend(
+            std::iter::repeat([0x55, 0x48, 0x89, 0xE5, 0x5D, 0xC3])
+                .take(64)
+                .flatten(),
+        );
+
+        let ppc: Vec<u8> = std::iter::repeat([0x7C08_02A6u32, 0x9421_FFF0, 0x4E80_0020])
+            .take(128)
+            .flatten()
+            .flat_map(u32::to_be_bytes)
+            .collect();
+
+        vec![
+            ("a.elf", elf),
+            ("b.ppc", ppc),
+            ("c.txt", b"plain text, not a binary\n".repeat(20)),
+            ("d.elf", [b"\x7FELF".as_slice(), &[0; 12]].concat()),
+        ]
+    }
+
+    fn config(root: &std::path::Path, jobs: usize) -> PipelineConfig {
+        PipelineConfig {
+            input_dir: root.join("in"),
+            staging_dir: root.join("out"),
+            prefix: "isa-harvester/v1".to_string(),
+            jobs,
+            min_file_size: 4,
+            max_file_size: 1 << 20,
+            follow_symlinks: false,
+            run_id: "repro".to_string(),
+            classifier_options: ClassifierOptions::new(),
+            routing_config: RoutingConfig::default(),
+            ledger_config: LedgerRotationConfig::default(),
+            skip_existing: false,
+            deep_scan: false,
+            detect_extensions: true,
+            source_date: clock::parse_source_date_epoch("1700000000"),
+        }
+    }
+
+    /// Every staged file except the ledgers, which are append logs in
+    /// processing order.
+    fn staged_files(dir: &std::path::Path) -> Vec<(String, Vec<u8>)> {
+        let mut files: Vec<(String, Vec<u8>)> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let rel = e.path().strip_prefix(dir).unwrap().display().to_string();
+                (rel, std::fs::read(e.path()).unwrap())
+            })
+            .filter(|(rel, _)| !rel.contains("/ledgers/"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Run the pipeline over a fresh copy of the corpus, written in `order`.
+    fn run(root: &std::path::Path, order: &[usize], jobs: usize) -> Vec<(String, Vec<u8>)> {
+        let _ = std::fs::remove_dir_all(root.join("in"));
+        let _ = std::fs::remove_dir_all(root.join("out"));
+        std::fs::create_dir_all(root.join("in")).unwrap();
+        let corpus = corpus();
+        for &i in order {
+            std::fs::write(root.join("in").join(corpus[i].0), &corpus[i].1).unwrap();
+        }
+        run_pipeline(
+            config(root, jobs),
+            Arc::new(PipelineStats::new()),
+            Arc::new(AtomicBool::new(false)),
+        )
+        .unwrap();
+        staged_files(&root.join("out"))
+    }
+
+    #[test]
+    fn test_source_date_epoch_parsing() {
+        let pinned = clock::parse_source_date_epoch("1700000000").unwrap();
+        assert_eq!(pinned.to_rfc3339(), "2023-11-14T22:13:20+00:00");
+        assert_eq!(clock::now(Some(pinned)), pinned);
+        for bad in ["", "-1", "1.5", "soon", "99999999999999999999"] {
+            assert_eq!(clock::parse_source_date_epoch(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_reproducible_run_output() {
+        let root = tempfile::tempdir().unwrap();
+        // Fresh HashMap seeds and a different arrival order on each run
+        let first = run(root.path(), &[0, 1, 2, 3], 4);
+        let second = run(root.path(), &[3, 1, 0, 2], 4);
+
+        let names: Vec<&str> = first.iter().map(|(name, _)| name.as_str()).collect();
+        assert!(names.iter().any(|n| n.ends_with("runs/repro.json")));
+        assert!(names.iter().any(|n| n.ends_with("stats/current.json")));
+        assert_eq!(
+            names,
+            second.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>()
+        );
+        for ((name, a), (_, b)) in first.iter().zip(&second) {
+            assert!(a == b, "{name} differs between runs");
+        }
+
+        let manifest = &first
+            .iter()
+            .find(|(n, _)| n.ends_with("runs/repro.json"))
+            .unwrap()
+            .1;
+        let manifest = String::from_utf8_lossy(manifest);
+        assert!(manifest.contains("\"started_at\": \"2023-11-14T22:13:20Z\""));
+        assert!(manifest.contains(&format!("\"classifier_version\": \"{}\"", crate::version())));
+    }
+}
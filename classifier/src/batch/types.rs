@@ -5,7 +5,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 // ---------------------------------------------------------------------------
 // Routing
@@ -122,6 +122,9 @@ pub struct MetaIdentity {
 pub struct MetaNames {
     pub original_names: Vec<String>,
     pub original_paths: Vec<String>,
+    /// Hex-encoded raw bytes of `original_paths` entries that are not UTF-8.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub original_path_bytes: Vec<String>,
     pub sanitized_name: String,
 }
 
@@ -287,7 +290,7 @@ impl IndexEntry {
             RoutingStatus::Duplicate => "duplicate",
         };
         format!(
-            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{}\t{}\t{}",
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.4}\t{}\t{}\t{}",
             self.sha256,
             self.object_key,
             self.meta_key,
@@ -349,9 +352,9 @@ pub struct RunManifest {
     pub timing: RunTiming,
     pub counts: RunCounts,
     pub storage: RunStorage,
-    pub breakdown_by_status: HashMap<String, u64>,
-    pub breakdown_by_format: HashMap<String, u64>,
-    pub breakdown_by_isa: HashMap<String, u64>,
+    pub breakdown_by_status: BTreeMap<String, u64>,
+    pub breakdown_by_format: BTreeMap<String, u64>,
+    pub breakdown_by_isa: BTreeMap<String, u64>,
     pub errors_summary: Vec<ErrorSummary>,
 }
 
@@ -430,10 +433,10 @@ pub struct StatsFile {
     pub last_updated: DateTime<Utc>,
     pub last_run_id: String,
     pub totals: StatsTotals,
-    pub by_format: HashMap<String, StatsCount>,
-    pub by_isa: HashMap<String, StatsCount>,
-    pub by_status: HashMap<String, u64>,
-    pub by_confidence_band: HashMap<String, u64>,
+    pub by_format: BTreeMap<String, StatsCount>,
+    pub by_isa: BTreeMap<String, StatsCount>,
+    pub by_status: BTreeMap<String, u64>,
+    pub by_confidence_band: BTreeMap<String, u64>,
     pub runs_completed: u64,
     pub last_10_runs: Vec<StatsRunSummary>,
 }
@@ -470,21 +473,28 @@ impl StatsFile {
     pub fn new() -> Self {
         Self {
             schema_version: 1,
-            last_updated: Utc::now(),
+            // Set from the first merged run; never the wall clock
+            last_updated: DateTime::<Utc>::default(),
             last_run_id: String::new(),
             totals: StatsTotals::default(),
-            by_format: HashMap::new(),
-            by_isa: HashMap::new(),
-            by_status: HashMap::new(),
-            by_confidence_band: HashMap::new(),
+            by_format: BTreeMap::new(),
+            by_isa: BTreeMap::new(),
+            by_status: BTreeMap::new(),
+            by_confidence_band: BTreeMap::new(),
             runs_completed: 0,
             last_10_runs: Vec::new(),
         }
     }
 
     /// Merge counts from a completed run manifest into this stats file.
+    ///
+    /// `last_updated` becomes the run's completion time, so the file only
+    /// carries timestamps the manifest already has.
     pub fn merge_run(&mut self, manifest: &RunManifest) {
-        self.last_updated = Utc::now();
+        self.last_updated = manifest
+            .timing
+            .completed_at
+            .unwrap_or(manifest.timing.started_at);
         self.last_run_id = manifest.run_id.clone();
         self.runs_completed += 1;
 
@@ -10,14 +10,17 @@
 //! See `docs/batch-store/04-batch-ingestion.md` Section 2.2 (Stage 4)
 //! and `docs/batch-store/10-local-staging.md` for file creation semantics.
 
+use super::clock;
 use super::keys::{staging_path, KeyConfig};
 use super::slugs::{
     endianness_display, endianness_slug, format_slug, hash_fanout, isa_slug, sanitize_filename,
 };
 use super::types::*;
+use crate::formatter::path_bytes_hex;
 use crate::types::{ClassificationSource, Endianness, FileFormat, Isa, IsaCandidate};
-use chrono::Utc;
-use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -121,31 +124,33 @@ pub struct StagingWriter {
     run_id: String,
     /// Classifier version string.
     classifier_version: String,
+    /// Pinned time for every written timestamp (see [`clock`]).
+    source_date: Option<DateTime<Utc>>,
 
     /// Ledger rotation config.
     ledger_config: LedgerRotationConfig,
     /// Global ledger buffer.
     global_ledger: LedgerBuffer,
     /// Per-ISA ledger buffers (keyed by ISA slug).
-    isa_ledgers: HashMap<String, LedgerBuffer>,
+    isa_ledgers: BTreeMap<String, LedgerBuffer>,
 
     /// Index shard buffers: `(ab_hex, cd_hex) → BTreeMap<sha256, IndexEntry>`.
-    index_shards: HashMap<(String, String), BTreeMap<String, IndexEntry>>,
+    index_shards: BTreeMap<(String, String), BTreeMap<String, IndexEntry>>,
 
     /// Running counts for the run manifest.
     pub counts: RunCounts,
     /// Per-format counts.
-    pub format_counts: HashMap<String, u64>,
+    pub format_counts: BTreeMap<String, u64>,
     /// Per-ISA counts.
-    pub isa_counts: HashMap<String, u64>,
+    pub isa_counts: BTreeMap<String, u64>,
     /// Per-status counts.
-    pub status_counts: HashMap<String, u64>,
+    pub status_counts: BTreeMap<String, u64>,
     /// Per-confidence-band counts.
-    pub band_counts: HashMap<String, u64>,
+    pub band_counts: BTreeMap<String, u64>,
     /// Total bytes stored.
     pub total_bytes_stored: u64,
     /// Error summaries.
-    pub error_summaries: HashMap<String, (u64, String)>,
+    pub error_summaries: BTreeMap<String, (u64, String)>,
     /// Number of ledger segments flushed (global).
     pub ledger_segments_global: u64,
     /// Number of ledger segments flushed (ISA).
@@ -167,22 +172,35 @@ impl StagingWriter {
             key_config,
             run_id,
             classifier_version,
+            source_date: None,
             ledger_config,
             global_ledger: LedgerBuffer::new(global_key_prefix),
-            isa_ledgers: HashMap::new(),
-            index_shards: HashMap::new(),
+            isa_ledgers: BTreeMap::new(),
+            index_shards: BTreeMap::new(),
             counts: RunCounts::default(),
-            format_counts: HashMap::new(),
-            isa_counts: HashMap::new(),
-            status_counts: HashMap::new(),
-            band_counts: HashMap::new(),
+            format_counts: BTreeMap::new(),
+            isa_counts: BTreeMap::new(),
+            status_counts: BTreeMap::new(),
+            band_counts: BTreeMap::new(),
             total_bytes_stored: 0,
-            error_summaries: HashMap::new(),
+            error_summaries: BTreeMap::new(),
             ledger_segments_global: 0,
             ledger_segments_isa: 0,
         }
     }
 
+    /// Stamp every written timestamp with `source_date` instead of the
+    /// current time.
+    pub fn with_source_date(mut self, source_date: Option<DateTime<Utc>>) -> Self {
+        self.source_date = source_date;
+        self
+    }
+
+    /// The timestamp for an artifact written now.
+    fn timestamp(&self) -> DateTime<Utc> {
+        clock::now(self.source_date)
+    }
+
     /// Write a classified file result to staging.
     ///
     /// This is the main entry point called by the pipeline for each processed file.
@@ -303,9 +321,9 @@ impl StagingWriter {
             sha256: result.sha256_hex.clone(),
             object_key: self.key_config.object_key(&result.sha256_hex),
             meta_key: self.key_config.meta_key(&result.sha256_hex),
-            original_name: result.original_name.clone(),
+            original_name: result.original_name.to_string_lossy().into_owned(),
             file_size: result.file_size,
-            ingested_at: Utc::now(),
+            ingested_at: self.timestamp(),
         };
 
         let json = serde_json::to_string_pretty(&ref_file)
@@ -367,7 +385,7 @@ impl StagingWriter {
             confidence: result.confidence,
             status: result.routing.status,
             file_size: result.file_size,
-            ingested_at: Utc::now(),
+            ingested_at: self.timestamp(),
         };
 
         self.index_shards
@@ -483,7 +501,7 @@ impl StagingWriter {
 
     fn build_metadata_sidecar(&self, result: &ClassifiedFile) -> MetadataSidecar {
         let obj_key = self.key_config.object_key(&result.sha256_hex);
-        let sanitized = sanitize_filename(&result.original_name);
+        let sanitized = sanitize_filename(&result.original_name.to_string_lossy());
 
         let classification_source = match result.source {
             ClassificationSource::FileFormat => "file_format",
@@ -553,8 +571,11 @@ impl StagingWriter {
                 object_key: obj_key,
             },
             names: MetaNames {
-                original_names: vec![result.original_name.clone()],
-                original_paths: vec![result.source_path.clone()],
+                original_names: vec![result.original_name.to_string_lossy().into_owned()],
+                original_paths: vec![result.source_path.to_string_lossy().into_owned()],
+                original_path_bytes: path_bytes_hex(Path::new(&result.source_path))
+                    .into_iter()
+                    .collect(),
                 sanitized_name: sanitized,
             },
             classification: MetaClassification {
@@ -582,8 +603,8 @@ impl StagingWriter {
             provenance: MetaProvenance {
                 run_id: self.run_id.clone(),
                 classifier_version: self.classifier_version.clone(),
-                ingested_at: Utc::now(),
-                source_path: result.source_path.clone(),
+                ingested_at: self.timestamp(),
+                source_path: result.source_path.to_string_lossy().into_owned(),
                 reclassified_count: 0,
                 previous_classifications: vec![],
             },
@@ -611,7 +632,7 @@ impl StagingWriter {
         };
 
         LedgerEntry {
-            ts: Utc::now(),
+            ts: self.timestamp(),
             run: self.run_id.clone(),
             sha256: result.sha256_hex.clone(),
             size: result.file_size,
@@ -630,8 +651,8 @@ impl StagingWriter {
             amb_reason: result.routing.ambiguous_reason,
             margin,
             runner_up,
-            orig_name: result.original_name.clone(),
-            orig_path: result.source_path.clone(),
+            orig_name: result.original_name.to_string_lossy().into_owned(),
+            orig_path: result.source_path.to_string_lossy().into_owned(),
             obj_key: self.key_config.object_key(&result.sha256_hex),
             meta_key: self.key_config.meta_key(&result.sha256_hex),
             view_key: result.view_key.clone(),
@@ -656,10 +677,10 @@ pub struct ClassifiedFile {
     pub data: Vec<u8>,
     /// File size in bytes.
     pub file_size: u64,
-    /// Original filename (basename).
-    pub original_name: String,
-    /// Full original source path.
-    pub source_path: String,
+    /// Original filename (basename), kept as raw OS bytes.
+    pub original_name: OsString,
+    /// Full original source path, kept as raw OS bytes.
+    pub source_path: OsString,
 
     // Classification results
     pub format: FileFormat,
@@ -0,0 +1,605 @@
+//! Boot protocol header detection.
+//!
+//! Hobby-OS kernels and hypervisor payloads are ordinary ELF files or flat
+//! images to the format parsers; what marks them as kernels is the boot
+//! protocol header the loader looks for. Four protocols are recognised:
+//!
+//! - **Multiboot**: magic `0x1BADB002` on a 4-byte boundary within the
+//!   first [`MULTIBOOT_SEARCH`] bytes. The checksum must make the first
+//!   three words sum to zero.
+//! - **Multiboot2**: magic `0xE85250D6` on an 8-byte boundary within the
+//!   first [`MULTIBOOT2_SEARCH`] bytes, followed by a tag list. The
+//!   checksum is verified and every tag must fit inside the header, which
+//!   itself must fit inside the file.
+//! - **Xen**: `XEN_ELFNOTE_*` entries (note name `"Xen"`) in an ELF
+//!   `PT_NOTE` segment. `XEN_ELFNOTE_PHYS32_ENTRY` marks a PVH kernel.
+//! - **Limine**: the request markers of the Limine boot protocol, two
+//!   64-bit magic words on an 8-byte boundary.
+//!
+//! Protocols are tried in that order of precedence (Xen first, since its
+//! notes can only be there on purpose) and the first match is reported.
+
+use memchr::memmem;
+
+use crate::formats::elf::{self, class, data as elf_data, pt};
+use crate::formats::{read_u16, read_u32, read_u64};
+use crate::types::{MetadataEntry, MetadataValue, Variant};
+
+/// Bytes searched for a Multiboot header, as the specification requires.
+pub const MULTIBOOT_SEARCH: usize = 8 * 1024;
+
+/// Bytes searched for a Multiboot2 header, as the specification requires.
+pub const MULTIBOOT2_SEARCH: usize = 32 * 1024;
+
+/// Maximum number of bytes searched for Limine request markers.
+pub const LIMINE_SEARCH: usize = 16 * 1024 * 1024;
+
+/// Multiboot header magic.
+pub const MULTIBOOT_MAGIC: u32 = 0x1BAD_B002;
+
+/// Multiboot2 header magic.
+pub const MULTIBOOT2_MAGIC: u32 = 0xE852_50D6;
+
+/// Multiboot flag bit 16: the header carries load and entry addresses.
+const MULTIBOOT_AOUT_KLUDGE: u32 = 1 << 16;
+
+/// Multiboot2 tag types with an entry address.
+const MB2_TAG_END: u16 = 0;
+const MB2_TAG_ENTRY: u16 = 3;
+const MB2_TAG_ENTRY_EFI_AMD64: u16 = 9;
+
+/// Xen note types carrying an entry point.
+const XEN_ELFNOTE_ENTRY: u32 = 1;
+const XEN_ELFNOTE_PHYS32_ENTRY: u32 = 18;
+
+/// First two words of every Limine request identifier.
+pub const LIMINE_COMMON_MAGIC: [u64; 2] = [0xc7b1_dd30_df4c_8b88, 0x0a82_e883_a194_f07b];
+
+/// Limine base revision tag; the third word is the revision.
+const LIMINE_BASE_REVISION: [u64; 2] = [0xf956_2b2d_5c95_a6c8, 0x6a7b_3849_4453_6bdc];
+
+/// A recognised boot protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootProtocol {
+    /// Multiboot (version 1)
+    Multiboot,
+    /// Multiboot2
+    Multiboot2,
+    /// Xen paravirtualised guest kernel
+    XenPv,
+    /// Xen PVH guest kernel (32-bit physical entry point)
+    XenPvh,
+    /// Limine boot protocol
+    Limine,
+}
+
+impl BootProtocol {
+    /// Protocol name used in metadata.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BootProtocol::Multiboot => "Multiboot",
+            BootProtocol::Multiboot2 => "Multiboot2",
+            BootProtocol::XenPv => "Xen PV",
+            BootProtocol::XenPvh => "Xen PVH",
+            BootProtocol::Limine => "Limine",
+        }
+    }
+
+    /// Variant name for a kernel using this protocol (e.g. "Multiboot2 kernel").
+    pub fn variant_name(self) -> String {
+        format!("{} kernel", self.as_str())
+    }
+}
+
+/// A boot protocol header found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootHeader {
+    /// Which protocol
+    pub protocol: BootProtocol,
+    /// File offset of the header, when the protocol has a single one
+    pub offset: Option<usize>,
+    /// Entry address, when the header specifies one
+    pub entry: Option<u64>,
+    /// Requests made of the loader: Multiboot flag bits, Multiboot2 tags,
+    /// Xen notes or Limine requests
+    pub requests: usize,
+    /// Limine base revision, when tagged
+    pub revision: Option<u64>,
+}
+
+impl BootHeader {
+    /// Build the metadata entries describing this header.
+    pub fn to_metadata(&self) -> Vec<MetadataEntry> {
+        let mut entries = vec![
+            MetadataEntry::custom(
+                "boot_protocol",
+                MetadataValue::String(self.protocol.as_str().to_string()),
+                "Boot Protocol",
+            ),
+            MetadataEntry::custom(
+                "boot_requests",
+                MetadataValue::Integer(self.requests as u64),
+                "Boot Requests",
+            ),
+        ];
+        if let Some(offset) = self.offset {
+            entries.push(MetadataEntry::custom(
+                "boot_header_offset",
+                MetadataValue::Integer(offset as u64),
+                "Boot Header Offset",
+            ));
+        }
+        if let Some(entry) = self.entry {
+            entries.push(MetadataEntry::custom(
+                "boot_entry",
+                MetadataValue::Address(entry),
+                "Boot Entry",
+            ));
+        }
+        if let Some(revision) = self.revision {
+            entries.push(MetadataEntry::custom(
+                "limine_base_revision",
+                MetadataValue::Integer(revision),
+                "Limine Base Revision",
+            ));
+        }
+        entries
+            .into_iter()
+            .map(|entry| entry.with_origin("boot"))
+            .collect()
+    }
+
+    /// Record the protocol in `variant`.
+    ///
+    /// An unnamed variant takes the protocol's kernel name; a named one
+    /// (an ISA revision from the format header) keeps it and gains the
+    /// protocol as its profile.
+    pub fn apply_variant(&self, variant: &mut Option<Variant>) {
+        let name = self.protocol.variant_name();
+        match variant {
+            Some(v) if !v.name.is_empty() => {
+                v.profile.get_or_insert(name);
+            }
+            Some(v) => v.name = name,
+            None => *variant = Some(Variant::new(name)),
+        }
+    }
+}
+
+/// Find the boot protocol header in `data`.
+///
+/// Returns `None` when no protocol matched or every candidate failed
+/// validation.
+pub fn identify(data: &[u8]) -> Option<BootHeader> {
+    xen_notes(data)
+        .or_else(|| multiboot2(data))
+        .or_else(|| multiboot(data))
+        .or_else(|| limine(data))
+}
+
+/// Scan for a Multiboot header with a valid checksum.
+fn multiboot(data: &[u8]) -> Option<BootHeader> {
+    let hay = &data[..data.len().min(MULTIBOOT_SEARCH)];
+    (0..hay.len().saturating_sub(11))
+        .step_by(4)
+        .find_map(|offset| parse_multiboot(hay, data, offset))
+}
+
+fn parse_multiboot(hay: &[u8], data: &[u8], offset: usize) -> Option<BootHeader> {
+    if read_u32(hay, offset, true).ok()? != MULTIBOOT_MAGIC {
+        return None;
+    }
+    let flags = read_u32(hay, offset + 4, true).ok()?;
+    let checksum = read_u32(hay, offset + 8, true).ok()?;
+    if MULTIBOOT_MAGIC.wrapping_add(flags).wrapping_add(checksum) != 0 {
+        return None;
+    }
+    // header_addr, load_addr, load_end_addr, bss_end_addr, entry_addr
+    let entry = if flags & MULTIBOOT_AOUT_KLUDGE != 0 {
+        Some(u64::from(read_u32(data, offset + 28, true).ok()?))
+    } else {
+        None
+    };
+    Some(BootHeader {
+        protocol: BootProtocol::Multiboot,
+        offset: Some(offset),
+        entry,
+        requests: flags.count_ones() as usize,
+        revision: None,
+    })
+}
+
+/// Scan for a Multiboot2 header with a valid checksum and tag list.
+fn multiboot2(data: &[u8]) -> Option<BootHeader> {
+    let end = data.len().min(MULTIBOOT2_SEARCH);
+    (0..end.saturating_sub(15))
+        .step_by(8)
+        .find_map(|offset| parse_multiboot2(data, offset))
+}
+
+fn parse_multiboot2(data: &[u8], offset: usize) -> Option<BootHeader> {
+    if read_u32(data, offset, true).ok()? != MULTIBOOT2_MAGIC {
+        return None;
+    }
+    let arch = read_u32(data, offset + 4, true).ok()?;
+    let length = read_u32(data, offset + 8, true).ok()?;
+    let checksum = read_u32(data, offset + 12, true).ok()?;
+    if MULTIBOOT2_MAGIC
+        .wrapping_add(arch)
+        .wrapping_add(length)
+        .wrapping_add(checksum)
+        != 0
+    {
+        return None;
+    }
+    let header = data.get(offset..offset.checked_add(length as usize)?)?;
+
+    let mut entry = None;
+    let mut tags = 0;
+    let mut pos = 16;
+    loop {
+        let tag_type = read_u16(header, pos, true).ok()?;
+        let size = read_u32(header, pos + 4, true).ok()? as usize;
+        if size < 8 || pos.checked_add(size)? > header.len() {
+            return None;
+        }
+        match tag_type {
+            MB2_TAG_END => break,
+            MB2_TAG_ENTRY | MB2_TAG_ENTRY_EFI_AMD64 if size >= 12 => {
+                entry.get_or_insert(u64::from(read_u32(header, pos + 8, true).ok()?));
+            }
+            _ => {}
+        }
+        tags += 1;
+        // Tags are padded to 8-byte boundaries
+        pos = pos.checked_add(size.checked_add(7)? & !7)?;
+    }
+
+    Some(BootHeader {
+        protocol: BootProtocol::Multiboot2,
+        offset: Some(offset),
+        entry,
+        requests: tags,
+        revision: None,
+    })
+}
+
+/// Collect the Xen notes from the `PT_NOTE` segments of an ELF image.
+fn xen_notes(data: &[u8]) -> Option<BootHeader> {
+    if data.len() < 0x34 || !data.starts_with(&crate::formats::magic::ELF) {
+        return None;
+    }
+    let is_64 = data[4] == class::ELFCLASS64;
+    let le = data[5] == elf_data::ELFDATA2LSB;
+
+    let mut notes = 0;
+    let mut entry = None;
+    let mut phys32_entry = None;
+    for ph in elf::program_headers(data, is_64, le) {
+        if ph.p_type != pt::PT_NOTE {
+            continue;
+        }
+        let Some(area) = usize::try_from(ph.p_offset)
+            .ok()
+            .zip(usize::try_from(ph.p_filesz).ok())
+            .and_then(|(offset, size)| crate::formats::read_bytes(data, offset, size).ok())
+        else {
+            continue;
+        };
+        walk_notes(area, le, |name, note_type, desc| {
+            if name != b"Xen\0" {
+                return;
+            }
+            notes += 1;
+            let value = match desc.len() {
+                4 => read_u32(desc, 0, le).ok().map(u64::from),
+                8 => read_u64(desc, 0, le).ok(),
+                _ => None,
+            };
+            match note_type {
+                XEN_ELFNOTE_ENTRY => entry = entry.or(value),
+                XEN_ELFNOTE_PHYS32_ENTRY => phys32_entry = phys32_entry.or(value),
+                _ => {}
+            }
+        });
+    }
+    if notes == 0 {
+        return None;
+    }
+
+    Some(BootHeader {
+        protocol: if phys32_entry.is_some() {
+            BootProtocol::XenPvh
+        } else {
+            BootProtocol::XenPv
+        },
+        offset: None,
+        entry: phys32_entry.or(entry),
+        requests: notes,
+        revision: None,
+    })
+}
+
+/// Call `visit(name, type, desc)` for each note in a 4-byte aligned area.
+fn walk_notes(area: &[u8], le: bool, mut visit: impl FnMut(&[u8], u32, &[u8])) {
+    let align = |n: usize| n.checked_add(3).map(|n| n & !3);
+    let mut offset = 0;
+    while offset + 12 <= area.len() {
+        let (Ok(namesz), Ok(descsz), Ok(note_type)) = (
+            read_u32(area, offset, le),
+            read_u32(area, offset + 4, le),
+            read_u32(area, offset + 8, le),
+        ) else {
+            return;
+        };
+        let name_start = offset + 12;
+        let Some(desc_start) = align(namesz as usize).and_then(|n| name_start.checked_add(n))
+        else {
+            return;
+        };
+        let (Some(name), Some(desc)) = (
+            area.get(name_start..name_start + namesz as usize),
+            area.get(desc_start..desc_start.saturating_add(descsz as usize)),
+        ) else {
+            return;
+        };
+        visit(name, note_type, desc);
+        match align(descsz as usize).and_then(|n| desc_start.checked_add(n)) {
+            Some(next) => offset = next,
+            None => return,
+        }
+    }
+}
+
+/// Count the Limine requests and find the base revision tag.
+fn limine(data: &[u8]) -> Option<BootHeader> {
+    let hay = &data[..data.len().min(LIMINE_SEARCH)];
+    let word = |offset: usize| read_u64(hay, offset, true).ok();
+
+    let first = LIMINE_COMMON_MAGIC[0].to_le_bytes();
+    let requests = memmem::find_iter(hay, &first)
+        .filter(|&pos| pos % 8 == 0 && word(pos + 8) == Some(LIMINE_COMMON_MAGIC[1]))
+        .count();
+
+    let first = LIMINE_BASE_REVISION[0].to_le_bytes();
+    let revision = memmem::find_iter(hay, &first)
+        .filter(|&pos| pos % 8 == 0 && word(pos + 8) == Some(LIMINE_BASE_REVISION[1]))
+        .find_map(|pos| word(pos + 16));
+
+    if requests == 0 && revision.is_none() {
+        return None;
+    }
+    Some(BootHeader {
+        protocol: BootProtocol::Limine,
+        offset: None,
+        entry: None,
+        requests,
+        revision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{classify_bytes_with_options, detect_payload, ClassifierOptions};
+
+    fn words(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    fn multiboot_image(flags: u32, checksum_delta: u32) -> Vec<u8> {
+        let mut data = vec![0x90u8; 64];
+        let checksum = 0u32
+            .wrapping_sub(MULTIBOOT_MAGIC)
+            .wrapping_sub(flags)
+            .wrapping_add(checksum_delta);
+        data.extend(words(&[
+            MULTIBOOT_MAGIC,
+            flags,
+            checksum,
+            0x0010_0040, // header_addr
+            0x0010_0000, // load_addr
+            0,
+            0,
+            0x0010_0100, // entry_addr
+        ]));
+        data.resize(4096, 0x90);
+        data
+    }
+
+    /// Multiboot2 header with an information request tag, an entry address
+    /// tag and the end tag.
+    fn multiboot2_header(tag_size_override: Option<u32>) -> Vec<u8> {
+        let mut tags = Vec::new();
+        // Information request: type 1, two requested tag types
+        tags.extend(words(&[1, 16, 4, 6]));
+        // Entry address: type 3, size 12, padded to 16
+        tags.extend(words(&[3, tag_size_override.unwrap_or(12), 0x0020_0000, 0]));
+        // End tag
+        tags.extend(words(&[0, 8]));
+        let length = 16 + tags.len() as u32;
+        let checksum = 0u32.wrapping_sub(MULTIBOOT2_MAGIC).wrapping_sub(length);
+        let mut header = words(&[MULTIBOOT2_MAGIC, 0, length, checksum]);
+        header.extend(tags);
+        header
+    }
+
+    /// A minimal x86-64 ELF with one `PT_NOTE` segment holding `notes`.
+    fn elf_with_notes(notes: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(b"\x7FELF");
+        data[4] = 2; // ELFCLASS64
+        data[5] = 1; // ELFDATA2LSB
+        data[6] = 1;
+        data[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        data[18..20].copy_from_slice(&62u16.to_le_bytes()); // EM_X86_64
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+        data[24..32].copy_from_slice(&0x0100_0000u64.to_le_bytes());
+        data[32..40].copy_from_slice(&64u64.to_le_bytes()); // e_phoff
+        data[52..54].copy_from_slice(&64u16.to_le_bytes());
+        data[54..56].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        data[56..58].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        let mut ph = [0u8; 56];
+        ph[0..4].copy_from_slice(&pt::PT_NOTE.to_le_bytes());
+        ph[8..16].copy_from_slice(&120u64.to_le_bytes());
+        ph[32..40].copy_from_slice(&(notes.len() as u64).to_le_bytes());
+        data.extend_from_slice(&ph);
+        data.extend_from_slice(notes);
+        data
+    }
+
+    fn xen_note(note_type: u32, desc: &[u8]) -> Vec<u8> {
+        let mut note = words(&[4, desc.len() as u32, note_type]);
+        note.extend_from_slice(b"Xen\0");
+        note.extend_from_slice(desc);
+        note.resize((note.len() + 3) & !3, 0);
+        note
+    }
+
+    fn custom<'a>(entries: &'a [MetadataEntry], key: &str) -> Option<&'a MetadataValue> {
+        let key = crate::types::MetadataKey::Custom(key.to_string());
+        entries.iter().find(|e| e.key == key).map(|e| &e.value)
+    }
+
+    #[test]
+    fn test_multiboot_checksum() {
+        let header = identify(&multiboot_image(0x0001_0003, 0)).unwrap();
+        assert_eq!(header.protocol, BootProtocol::Multiboot);
+        assert_eq!(header.offset, Some(64));
+        assert_eq!(header.entry, Some(0x0010_0100));
+        assert_eq!(header.requests, 3);
+
+        // Without the a.out kludge the address fields are not meaningful
+        assert_eq!(identify(&multiboot_image(0x3, 0)).unwrap().entry, None);
+        assert_eq!(identify(&multiboot_image(0x0001_0003, 1)), None);
+
+        // Beyond the first 8 KiB the header is not looked for
+        let mut late = vec![0x90u8; MULTIBOOT_SEARCH];
+        late.extend(multiboot_image(0x3, 0));
+        assert_eq!(identify(&late), None);
+    }
+
+    #[test]
+    fn test_multiboot2_tags() {
+        let mut data = vec![0u8; 0x1000];
+        data.extend(multiboot2_header(None));
+        data.resize(0x2000, 0);
+
+        let header = identify(&data).unwrap();
+        assert_eq!(header.protocol, BootProtocol::Multiboot2);
+        assert_eq!(header.offset, Some(0x1000));
+        assert_eq!(header.requests, 2);
+        assert_eq!(header.entry, Some(0x0020_0000));
+
+        // A tag running past header_length fails the bounds check
+        let mut bad = vec![0u8; 0x1000];
+        bad.extend(multiboot2_header(Some(0x100)));
+        bad.resize(0x2000, 0);
+        assert_eq!(identify(&bad), None);
+
+        // So does a header truncated by the end of the file
+        let truncated = &data[..0x1000 + 24];
+        assert_eq!(identify(truncated), None);
+    }
+
+    #[test]
+    fn test_xen_notes() {
+        let mut notes = xen_note(6, b"linux\0\0\0");
+        notes.extend(xen_note(
+            XEN_ELFNOTE_ENTRY,
+            &0xFFFF_FFFF_8100_0000u64.to_le_bytes(),
+        ));
+        let header = identify(&elf_with_notes(&notes)).unwrap();
+        assert_eq!(header.protocol, BootProtocol::XenPv);
+        assert_eq!(header.entry, Some(0xFFFF_FFFF_8100_0000));
+        assert_eq!(header.requests, 2);
+
+        notes.extend(xen_note(
+            XEN_ELFNOTE_PHYS32_ENTRY,
+            &0x0100_0000u32.to_le_bytes(),
+        ));
+        let header = identify(&elf_with_notes(&notes)).unwrap();
+        assert_eq!(header.protocol, BootProtocol::XenPvh);
+        assert_eq!(header.entry, Some(0x0100_0000));
+        assert_eq!(header.requests, 3);
+
+        // GNU notes alone are not a boot protocol
+        let mut gnu = words(&[4, 16, 1]);
+        gnu.extend_from_slice(b"GNU\0");
+        gnu.extend(words(&[0, 3, 2, 0]));
+        assert_eq!(identify(&elf_with_notes(&gnu)), None);
+    }
+
+    #[test]
+    fn test_limine_requests() {
+        let mut data = vec![0u8; 0x100];
+        // Base revision 2
+        for w in [LIMINE_BASE_REVISION[0], LIMINE_BASE_REVISION[1], 2] {
+            data.extend_from_slice(&w.to_le_bytes());
+        }
+        // Framebuffer and HHDM requests: common magic plus two id words
+        for id in [
+            [0x9d58_27dc_d881_dd75, 0xa314_8604_f6fa_b11b],
+            [0x48dc_f1cb_8ad2_b852, 0x6398_4e95_9a98_244b],
+        ] {
+            for w in [
+                LIMINE_COMMON_MAGIC[0],
+                LIMINE_COMMON_MAGIC[1],
+                id[0],
+                id[1],
+                0,
+                0,
+            ] {
+                data.extend_from_slice(&w.to_le_bytes());
+            }
+        }
+        let header = identify(&data).unwrap();
+        assert_eq!(header.protocol, BootProtocol::Limine);
+        assert_eq!(header.requests, 2);
+        assert_eq!(header.revision, Some(2));
+
+        // Unaligned markers are coincidence
+        let mut shifted = vec![0u8; 4];
+        shifted.extend_from_slice(&data[0x118..]);
+        assert_eq!(identify(&shifted), None);
+    }
+
+    #[test]
+    fn test_boot_protocol_reported() {
+        let mut notes = xen_note(XEN_ELFNOTE_PHYS32_ENTRY, &0x0100_0000u32.to_le_bytes());
+        notes.extend(xen_note(6, b"linux\0\0\0"));
+        let data = elf_with_notes(&notes);
+
+        let result = classify_bytes_with_options(&data, &ClassifierOptions::new()).unwrap();
+        assert!(result.variant.as_ref().is_some_and(
+            |v| v.name == "Xen PVH kernel" || v.profile.as_deref() == Some("Xen PVH kernel")
+        ));
+        assert!(matches!(
+            custom(&result.metadata.custom, "boot_protocol"),
+            Some(MetadataValue::String(p)) if p == "Xen PVH"
+        ));
+
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        assert!(matches!(
+            custom(&payload.metadata, "boot_entry"),
+            Some(MetadataValue::Address(0x0100_0000))
+        ));
+        assert_eq!(payload.primary.variant, result.variant);
+
+        // Raw x86 kernel image with a Multiboot2 header
+        let mut raw = multiboot2_header(None);
+        while raw.len() < 4096 {
+            raw.extend_from_slice(&[0x55, 0x48, 0x89, 0xE5, 0x31, 0xC0, 0x5D, 0xC3]);
+        }
+        let payload = detect_payload(&raw, &ClassifierOptions::new()).unwrap();
+        assert_eq!(
+            payload.primary.variant.as_ref().map(|v| v.name.as_str()),
+            Some("Multiboot2 kernel")
+        );
+        assert!(matches!(
+            custom(&payload.metadata, "boot_requests"),
+            Some(MetadataValue::Integer(2))
+        ));
+    }
+}
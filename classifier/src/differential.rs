@@ -0,0 +1,138 @@
+//! Differential check between the two classification entry points.
+//!
+//! [`classify_bytes_with_options`](crate::classify_bytes_with_options) and
+//! [`detect_payload`](crate::detect_payload) dispatch on the same format
+//! detection and must agree on the core answer for any input. [`check`]
+//! runs both on the same bytes and reports the first disagreement; the
+//! `differential` cargo-fuzz target and the corpus replay test below both
+//! drive it.
+//!
+//! # Guarantees
+//!
+//! Neither entry point panics on any input. Both fail, or both succeed with
+//! the same ISA, bitwidth, endianness and file format.
+//!
+//! # Documented differences
+//!
+//! - Format refinement: the payload reports the container family chosen by
+//!   format detection and carries the refinement in its variant, so an NE
+//!   image is `FileFormat::Ne` in the legacy result but `FileFormat::Mz` in
+//!   the payload. [`formats_comparable`] lists the accepted pairs.
+//! - Errors: the error kinds may differ; only success versus failure is
+//!   compared.
+
+use crate::types::FileFormat;
+use crate::{classify_bytes_with_options, detect_payload, ClassifierOptions};
+
+/// Longest prefix length tried by [`mutations`].
+pub const MAX_TRUNCATION: usize = 512;
+
+/// Leading bytes flipped one at a time by [`mutations`].
+pub const HEADER_FLIP_BYTES: usize = 64;
+
+/// Whether the legacy and payload formats describe the same container.
+///
+/// The payload keeps the family chosen by `detect_format` and carries the
+/// refinement as its variant string, while the legacy result names the
+/// refined format directly: NE and LE images are `Mz`, and Plan 9 a.out is
+/// `Aout`, in the payload.
+pub fn formats_comparable(legacy: FileFormat, payload: FileFormat) -> bool {
+    matches!(
+        (legacy, payload),
+        (FileFormat::Ne | FileFormat::Le, FileFormat::Mz)
+            | (FileFormat::Plan9Aout, FileFormat::Aout)
+    ) || legacy == payload
+}
+
+/// Run both entry points on `data` and describe any disagreement.
+pub fn check(data: &[u8], options: &ClassifierOptions) -> Result<(), String> {
+    let legacy = classify_bytes_with_options(data, options);
+    let payload = detect_payload(data, options);
+    match (legacy, payload) {
+        (Err(_), Err(_)) => Ok(()),
+        (Ok(legacy), Err(err)) => Err(format!(
+            "classify_bytes succeeded ({}) but detect_payload failed: {err}",
+            legacy.isa
+        )),
+        (Err(err), Ok(payload)) => Err(format!(
+            "detect_payload succeeded ({}) but classify_bytes failed: {err}",
+            payload.primary.isa
+        )),
+        (Ok(legacy), Ok(payload)) => {
+            let lhs = (legacy.isa, legacy.bitwidth, legacy.endianness);
+            let rhs = (
+                payload.primary.isa,
+                payload.primary.bitwidth,
+                payload.primary.endianness,
+            );
+            if lhs != rhs {
+                return Err(format!(
+                    "ISA mismatch: classify_bytes {lhs:?}, detect_payload {rhs:?}"
+                ));
+            }
+            let (lf, pf) = (legacy.format, payload.format.format);
+            if !formats_comparable(lf, pf) {
+                return Err(format!(
+                    "format mismatch: classify_bytes {lf:?}, detect_payload {pf:?}"
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Structured mutations of a seed input.
+///
+/// Yields the seed itself, every truncation up to [`MAX_TRUNCATION`] bytes,
+/// and the seed with each of its first [`HEADER_FLIP_BYTES`] bytes
+/// inverted, in that order.
+pub fn mutations(seed: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    let truncations = (0..seed.len().min(MAX_TRUNCATION)).map(|len| seed[..len].to_vec());
+    let flips = (0..seed.len().min(HEADER_FLIP_BYTES)).map(|i| {
+        let mut mutated = seed.to_vec();
+        mutated[i] ^= 0xFF;
+        mutated
+    });
+    std::iter::once(seed.to_vec())
+        .chain(truncations)
+        .chain(flips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_differential_corpus() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/differential");
+        let mut seeds: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        seeds.sort();
+        assert!(!seeds.is_empty(), "empty corpus at {}", dir.display());
+
+        let options = ClassifierOptions::new();
+        let mut failures = Vec::new();
+        for path in &seeds {
+            let seed = std::fs::read(path).unwrap();
+            for (i, input) in mutations(&seed).enumerate() {
+                if let Err(divergence) = check(&input, &options) {
+                    failures.push(format!("{} #{i}: {divergence}", path.display()));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_mutations() {
+        let seed = [1u8, 2, 3];
+        let all: Vec<_> = mutations(&seed).collect();
+        assert_eq!(all.len(), 1 + 3 + 3);
+        assert_eq!(all[0], seed);
+        assert_eq!(all[1], Vec::<u8>::new());
+        assert_eq!(all[4], [0xFE, 2, 3]);
+    }
+}
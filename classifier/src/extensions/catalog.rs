@@ -0,0 +1,1305 @@
+//! Reference data for the extensions each ISA can report.
+//!
+//! [`known_extensions_detailed`] lists, per ISA, every extension name the
+//! detectors and format parsers emit, with a one-line description, the
+//! ISA version or core that introduced it where that is meaningful, and the
+//! other spellings in use (`ASIMD` for `NEON`, `AES` for `AES-NI`). The
+//! names in the tables are canonical: detection output uses them, and
+//! [`canonical_name`] maps any alias back to them.
+
+use crate::types::{Extension, ExtensionCategory as C, Isa};
+
+/// An extension an ISA can report, with reference data for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownExtension {
+    /// Canonical name, as detection output spells it
+    pub name: &'static str,
+    /// Extension category
+    pub category: C,
+    /// Short description
+    pub description: &'static str,
+    /// ISA version, profile or first core that introduced the extension
+    pub introduced_in: Option<&'static str>,
+    /// Other names for the same extension, matched case-insensitively
+    pub aliases: &'static [&'static str],
+}
+
+impl KnownExtension {
+    /// Whether `name` is this extension's name or one of its aliases,
+    /// ignoring ASCII case.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+const fn ext(
+    name: &'static str,
+    category: C,
+    description: &'static str,
+    introduced_in: Option<&'static str>,
+    aliases: &'static [&'static str],
+) -> KnownExtension {
+    KnownExtension {
+        name,
+        category,
+        description,
+        introduced_in,
+        aliases,
+    }
+}
+
+const X86: &[KnownExtension] = &[
+    // SIMD
+    ext(
+        "MMX",
+        C::Simd,
+        "64-bit integer SIMD",
+        Some("Pentium MMX"),
+        &[],
+    ),
+    ext(
+        "SSE",
+        C::Simd,
+        "128-bit single-precision SIMD",
+        Some("Pentium III"),
+        &["SSE1"],
+    ),
+    ext(
+        "SSE2",
+        C::Simd,
+        "128-bit double-precision and integer SIMD",
+        Some("Pentium 4; x86-64 baseline"),
+        &[],
+    ),
+    ext(
+        "SSE3",
+        C::Simd,
+        "Horizontal and complex arithmetic",
+        Some("Prescott; x86-64-v2"),
+        &["PNI"],
+    ),
+    ext(
+        "SSSE3",
+        C::Simd,
+        "Supplemental byte shuffles and absolute values",
+        Some("Core 2; x86-64-v2"),
+        &[],
+    ),
+    ext(
+        "SSE4.1",
+        C::Simd,
+        "Blends, dot products and rounding",
+        Some("Penryn; x86-64-v2"),
+        &["SSE4_1"],
+    ),
+    ext(
+        "SSE4.2",
+        C::Simd,
+        "String compares and CRC32",
+        Some("Nehalem; x86-64-v2"),
+        &["SSE4_2"],
+    ),
+    ext(
+        "AVX",
+        C::Simd,
+        "256-bit VEX-encoded floating-point SIMD",
+        Some("Sandy Bridge; x86-64-v3"),
+        &[],
+    ),
+    ext(
+        "AVX2",
+        C::Simd,
+        "256-bit integer SIMD and gathers",
+        Some("Haswell; x86-64-v3"),
+        &[],
+    ),
+    ext(
+        "FMA",
+        C::Simd,
+        "Fused multiply-add",
+        Some("Haswell; x86-64-v3"),
+        &["FMA3"],
+    ),
+    ext(
+        "AVX-512",
+        C::Simd,
+        "EVEX-encoded 512-bit SIMD, subset undetermined",
+        Some("Knights Landing; x86-64-v4"),
+        &["AVX512"],
+    ),
+    ext(
+        "AVX-512F",
+        C::Simd,
+        "AVX-512 foundation",
+        Some("Knights Landing; x86-64-v4"),
+        &["AVX512F"],
+    ),
+    ext(
+        "AVX-512BW",
+        C::Simd,
+        "AVX-512 byte and word operations",
+        Some("Skylake-SP; x86-64-v4"),
+        &["AVX512BW"],
+    ),
+    ext(
+        "AVX-512VL",
+        C::Simd,
+        "AVX-512 at 128 and 256-bit vector lengths",
+        Some("Skylake-SP; x86-64-v4"),
+        &["AVX512VL"],
+    ),
+    ext(
+        "AVX-512DQ",
+        C::Simd,
+        "AVX-512 doubleword and quadword operations",
+        Some("Skylake-SP; x86-64-v4"),
+        &["AVX512DQ"],
+    ),
+    ext(
+        "AVX-512CD",
+        C::Simd,
+        "AVX-512 conflict detection",
+        Some("Knights Landing; x86-64-v4"),
+        &["AVX512CD"],
+    ),
+    ext(
+        "AVX-512_VNNI",
+        C::Simd,
+        "AVX-512 neural-network dot products",
+        Some("Cascade Lake"),
+        &["AVX512_VNNI", "AVX512VNNI"],
+    ),
+    ext(
+        "AVX-512_VBMI",
+        C::Simd,
+        "AVX-512 byte permutes",
+        Some("Cannon Lake"),
+        &["AVX512_VBMI", "AVX512VBMI"],
+    ),
+    ext(
+        "AVX-512_VBMI2",
+        C::Simd,
+        "AVX-512 byte and word compress, expand and shifts",
+        Some("Ice Lake"),
+        &["AVX512_VBMI2", "AVX512VBMI2"],
+    ),
+    ext(
+        "AVX-512_FP16",
+        C::Simd,
+        "AVX-512 half-precision arithmetic",
+        Some("Sapphire Rapids"),
+        &["AVX512_FP16", "AVX512FP16"],
+    ),
+    ext(
+        "AVX10",
+        C::Simd,
+        "Converged AVX-512 successor",
+        Some("Granite Rapids"),
+        &[],
+    ),
+    // Crypto
+    ext(
+        "AES-NI",
+        C::Crypto,
+        "AES round instructions",
+        Some("Westmere"),
+        &["AES", "AESNI"],
+    ),
+    ext(
+        "SHA",
+        C::Crypto,
+        "SHA-1 and SHA-256 acceleration",
+        Some("Goldmont"),
+        &["SHA-NI", "SHANI"],
+    ),
+    ext(
+        "PCLMULQDQ",
+        C::Crypto,
+        "Carry-less multiplication",
+        Some("Westmere"),
+        &["CLMUL", "PCLMUL"],
+    ),
+    ext("VAES", C::Crypto, "Vector AES", Some("Ice Lake"), &[]),
+    ext(
+        "VPCLMULQDQ",
+        C::Crypto,
+        "Vector carry-less multiplication",
+        Some("Ice Lake"),
+        &[],
+    ),
+    ext(
+        "GFNI",
+        C::Crypto,
+        "Galois field arithmetic",
+        Some("Ice Lake"),
+        &[],
+    ),
+    // Bit manipulation
+    ext(
+        "BMI1",
+        C::BitManip,
+        "Bit manipulation set 1",
+        Some("Haswell; x86-64-v3"),
+        &["BMI"],
+    ),
+    ext(
+        "BMI2",
+        C::BitManip,
+        "Bit manipulation set 2",
+        Some("Haswell; x86-64-v3"),
+        &[],
+    ),
+    ext(
+        "POPCNT",
+        C::BitManip,
+        "Population count",
+        Some("Nehalem; x86-64-v2"),
+        &[],
+    ),
+    ext(
+        "LZCNT",
+        C::BitManip,
+        "Leading zero count",
+        Some("Haswell; x86-64-v3"),
+        &["ABM"],
+    ),
+    // ML
+    ext(
+        "AMX-TILE",
+        C::MachineLearning,
+        "Advanced Matrix Extensions tile registers",
+        Some("Sapphire Rapids"),
+        &["AMX_TILE"],
+    ),
+    ext(
+        "AMX-INT8",
+        C::MachineLearning,
+        "AMX 8-bit integer tile multiply",
+        Some("Sapphire Rapids"),
+        &["AMX_INT8"],
+    ),
+    ext(
+        "AMX-BF16",
+        C::MachineLearning,
+        "AMX bfloat16 tile multiply",
+        Some("Sapphire Rapids"),
+        &["AMX_BF16"],
+    ),
+    ext(
+        "AMX-FP16",
+        C::MachineLearning,
+        "AMX half-precision tile multiply",
+        Some("Granite Rapids"),
+        &["AMX_FP16"],
+    ),
+    ext(
+        "AVX-VNNI",
+        C::MachineLearning,
+        "VEX-encoded neural-network dot products",
+        Some("Alder Lake"),
+        &["AVX_VNNI", "AVXVNNI"],
+    ),
+    // Security
+    ext(
+        "CET",
+        C::Security,
+        "Control-flow Enforcement Technology (shadow stack, IBT)",
+        Some("Tiger Lake"),
+        &["CET-IBT", "IBT"],
+    ),
+    ext(
+        "SGX",
+        C::Security,
+        "Software Guard Extensions enclaves",
+        Some("Skylake"),
+        &[],
+    ),
+    // System
+    ext(
+        "APX",
+        C::System,
+        "Advanced Performance Extensions (32 GPRs, REX2)",
+        None,
+        &[],
+    ),
+    ext(
+        "TSX",
+        C::Transactional,
+        "Transactional Synchronization Extensions",
+        Some("Haswell"),
+        &["RTM"],
+    ),
+];
+
+const AARCH64: &[KnownExtension] = &[
+    // Floating point
+    ext(
+        "FP",
+        C::FloatingPoint,
+        "Scalar floating point",
+        Some("ARMv8.0 baseline"),
+        &[],
+    ),
+    // SIMD / Vector
+    ext(
+        "NEON",
+        C::Simd,
+        "Advanced SIMD, 128-bit vectors",
+        Some("ARMv8.0 baseline"),
+        &["ASIMD", "AdvSIMD"],
+    ),
+    ext(
+        "SVE",
+        C::Simd,
+        "Scalable Vector Extension",
+        Some("ARMv8.2 (optional)"),
+        &[],
+    ),
+    ext(
+        "SVE2",
+        C::Simd,
+        "Scalable Vector Extension 2",
+        Some("ARMv9.0"),
+        &[],
+    ),
+    ext(
+        "SME",
+        C::Simd,
+        "Scalable Matrix Extension",
+        Some("ARMv9.2 (optional)"),
+        &[],
+    ),
+    ext(
+        "SME2",
+        C::Simd,
+        "Scalable Matrix Extension 2",
+        Some("ARMv9.4 (optional)"),
+        &[],
+    ),
+    ext(
+        "DOTPROD",
+        C::Simd,
+        "Int8 dot product",
+        Some("ARMv8.2 (optional), ARMv8.4"),
+        &["DotProd", "ASIMDDP"],
+    ),
+    ext(
+        "FP16",
+        C::Simd,
+        "Half-precision arithmetic",
+        Some("ARMv8.2 (optional)"),
+        &["FPHP", "ASIMDHP", "FullFP16"],
+    ),
+    ext("BF16", C::Simd, "BFloat16 arithmetic", Some("ARMv8.6"), &[]),
+    ext(
+        "I8MM",
+        C::Simd,
+        "Int8 matrix multiply",
+        Some("ARMv8.6"),
+        &[],
+    ),
+    ext(
+        "RDM",
+        C::Simd,
+        "Rounding double multiply accumulate",
+        Some("ARMv8.1"),
+        &["RDMA", "ASIMDRDM"],
+    ),
+    ext(
+        "FHM",
+        C::Simd,
+        "Half-precision multiply-add to single precision",
+        Some("ARMv8.2 (optional), ARMv8.4"),
+        &["FP16FML", "ASIMDFHM"],
+    ),
+    ext(
+        "FCMA",
+        C::Simd,
+        "Complex number arithmetic",
+        Some("ARMv8.3"),
+        &[],
+    ),
+    ext(
+        "FRINTTS",
+        C::Simd,
+        "Round to integral in integer range",
+        Some("ARMv8.5"),
+        &["FRINT"],
+    ),
+    // Crypto
+    ext(
+        "AES",
+        C::Crypto,
+        "AES round instructions",
+        Some("ARMv8.0 (optional)"),
+        &[],
+    ),
+    ext(
+        "PMULL",
+        C::Crypto,
+        "64-bit polynomial multiply",
+        Some("ARMv8.0 (optional)"),
+        &[],
+    ),
+    ext(
+        "SHA1",
+        C::Crypto,
+        "SHA-1 acceleration",
+        Some("ARMv8.0 (optional)"),
+        &[],
+    ),
+    ext(
+        "SHA256",
+        C::Crypto,
+        "SHA-256 acceleration",
+        Some("ARMv8.0 (optional)"),
+        &["SHA2"],
+    ),
+    ext(
+        "SHA512",
+        C::Crypto,
+        "SHA-512 acceleration",
+        Some("ARMv8.2 (optional)"),
+        &[],
+    ),
+    ext(
+        "SHA3",
+        C::Crypto,
+        "SHA-3 helper instructions",
+        Some("ARMv8.2 (optional)"),
+        &[],
+    ),
+    ext(
+        "SM3",
+        C::Crypto,
+        "SM3 hash acceleration",
+        Some("ARMv8.2 (optional)"),
+        &[],
+    ),
+    ext(
+        "SM4",
+        C::Crypto,
+        "SM4 cipher acceleration",
+        Some("ARMv8.2 (optional)"),
+        &[],
+    ),
+    // Atomics
+    ext(
+        "LSE",
+        C::Atomic,
+        "Large System Extensions atomics",
+        Some("ARMv8.1"),
+        &["Atomics"],
+    ),
+    ext(
+        "LSE2",
+        C::Atomic,
+        "Relaxed alignment for single-copy atomicity",
+        Some("ARMv8.4"),
+        &[],
+    ),
+    ext(
+        "LRCPC",
+        C::Atomic,
+        "Load-acquire RCpc",
+        Some("ARMv8.3"),
+        &["RCPC"],
+    ),
+    ext(
+        "LRCPC2",
+        C::Atomic,
+        "Load-acquire RCpc with immediate offsets",
+        Some("ARMv8.4"),
+        &["RCPC2", "ILRCPC"],
+    ),
+    // Security
+    ext(
+        "PAC",
+        C::Security,
+        "Pointer authentication",
+        Some("ARMv8.3"),
+        &["PAuth"],
+    ),
+    ext(
+        "BTI",
+        C::Security,
+        "Branch target identification",
+        Some("ARMv8.5"),
+        &[],
+    ),
+    ext(
+        "MTE",
+        C::Security,
+        "Memory tagging",
+        Some("ARMv8.5 (optional)"),
+        &["MemTag"],
+    ),
+    ext(
+        "GCS",
+        C::Security,
+        "Guarded control stack",
+        Some("ARMv9.4 (optional)"),
+        &[],
+    ),
+    ext(
+        "RNG",
+        C::Security,
+        "Random number instructions",
+        Some("ARMv8.5 (optional)"),
+        &["RNDR"],
+    ),
+    ext(
+        "SB",
+        C::Security,
+        "Speculation barrier",
+        Some("ARMv8.5"),
+        &[],
+    ),
+    ext(
+        "SSBS",
+        C::Security,
+        "Speculative store bypass safe",
+        Some("ARMv8.5"),
+        &[],
+    ),
+    ext(
+        "DIT",
+        C::Security,
+        "Data-independent timing",
+        Some("ARMv8.4"),
+        &[],
+    ),
+    // System
+    ext(
+        "DPB",
+        C::System,
+        "Clean data cache to point of persistence",
+        Some("ARMv8.2"),
+        &["DCPOP"],
+    ),
+    ext(
+        "DPB2",
+        C::System,
+        "Clean data cache to point of deep persistence",
+        Some("ARMv8.5"),
+        &["DCPODP"],
+    ),
+    ext(
+        "WFxT",
+        C::System,
+        "WFE and WFI with timeout",
+        Some("ARMv8.7"),
+        &["WFXT"],
+    ),
+    // Other
+    ext(
+        "CRC32",
+        C::Other,
+        "CRC32 and CRC32C",
+        Some("ARMv8.0 (optional), ARMv8.1"),
+        &["CRC"],
+    ),
+    ext(
+        "JSCVT",
+        C::Other,
+        "JavaScript double-to-int32 conversion",
+        Some("ARMv8.3"),
+        &[],
+    ),
+    ext(
+        "FlagM",
+        C::Other,
+        "Condition flag manipulation",
+        Some("ARMv8.4"),
+        &[],
+    ),
+    ext(
+        "FlagM2",
+        C::Other,
+        "Condition flag format conversion",
+        Some("ARMv8.5"),
+        &[],
+    ),
+    ext(
+        "MOPS",
+        C::Other,
+        "Memory copy and set instructions",
+        Some("ARMv8.8"),
+        &[],
+    ),
+    ext(
+        "HBC",
+        C::Other,
+        "Hinted conditional branches",
+        Some("ARMv8.8"),
+        &[],
+    ),
+    ext(
+        "CSSC",
+        C::Other,
+        "Common short sequence compression",
+        Some("ARMv8.9"),
+        &[],
+    ),
+    ext(
+        "LS64",
+        C::Other,
+        "Single-copy atomic 64-byte loads and stores",
+        Some("ARMv8.7 (optional)"),
+        &[],
+    ),
+];
+
+const RISCV: &[KnownExtension] = &[
+    // Standard
+    ext(
+        "M",
+        C::Other,
+        "Integer multiply and divide",
+        Some("RVA20U64"),
+        &[],
+    ),
+    ext("A", C::Atomic, "Atomic instructions", Some("RVA20U64"), &[]),
+    ext(
+        "F",
+        C::FloatingPoint,
+        "Single-precision floating point",
+        Some("RVA20U64"),
+        &[],
+    ),
+    ext(
+        "D",
+        C::FloatingPoint,
+        "Double-precision floating point",
+        Some("RVA20U64"),
+        &[],
+    ),
+    ext(
+        "Q",
+        C::FloatingPoint,
+        "Quad-precision floating point",
+        None,
+        &[],
+    ),
+    ext(
+        "C",
+        C::Compressed,
+        "Compressed 16-bit instructions",
+        Some("RVA20U64"),
+        &[],
+    ),
+    ext(
+        "E",
+        C::Other,
+        "Embedded base with 16 integer registers",
+        None,
+        &[],
+    ),
+    ext("V", C::Simd, "Vector extension", Some("RVA23U64"), &[]),
+    ext("H", C::Virtualization, "Hypervisor extension", None, &[]),
+    ext(
+        "Zicsr",
+        C::System,
+        "Control and status register access",
+        None,
+        &[],
+    ),
+    ext("Zifencei", C::System, "Instruction-fetch fence", None, &[]),
+    // Bit manipulation
+    ext(
+        "Zba",
+        C::BitManip,
+        "Address generation",
+        Some("RVA22U64"),
+        &[],
+    ),
+    ext(
+        "Zbb",
+        C::BitManip,
+        "Basic bit manipulation",
+        Some("RVA22U64"),
+        &[],
+    ),
+    ext("Zbc", C::BitManip, "Carry-less multiplication", None, &[]),
+    ext(
+        "Zbs",
+        C::BitManip,
+        "Single-bit instructions",
+        Some("RVA22U64"),
+        &[],
+    ),
+    // Crypto
+    ext(
+        "Zbkb",
+        C::Crypto,
+        "Bit manipulation for cryptography",
+        None,
+        &[],
+    ),
+    ext(
+        "Zbkc",
+        C::Crypto,
+        "Carry-less multiplication for cryptography",
+        None,
+        &[],
+    ),
+    ext("Zbkx", C::Crypto, "Crossbar permutations", None, &[]),
+    ext("Zknd", C::Crypto, "NIST AES decryption", None, &[]),
+    ext("Zkne", C::Crypto, "NIST AES encryption", None, &[]),
+    ext("Zknh", C::Crypto, "NIST SHA-2 hashes", None, &[]),
+    ext("Zksed", C::Crypto, "ShangMi SM4 cipher", None, &[]),
+    ext("Zksh", C::Crypto, "ShangMi SM3 hash", None, &[]),
+    // Other
+    ext(
+        "Ztso",
+        C::Other,
+        "Total store ordering memory model",
+        None,
+        &[],
+    ),
+];
+
+const PPC: &[KnownExtension] = &[
+    ext(
+        "VLE",
+        C::Compressed,
+        "Variable-length encoding",
+        Some("Power ISA 2.03"),
+        &[],
+    ),
+    ext(
+        "VMX",
+        C::Simd,
+        "AltiVec 128-bit SIMD",
+        Some("PowerPC G4 (7400)"),
+        &["AltiVec"],
+    ),
+    ext(
+        "VSX",
+        C::Simd,
+        "Vector-scalar extension",
+        Some("Power ISA 2.06 (POWER7)"),
+        &[],
+    ),
+    ext(
+        "DFP",
+        C::FloatingPoint,
+        "Decimal floating point",
+        Some("Power ISA 2.05 (POWER6)"),
+        &[],
+    ),
+    ext(
+        "MMA",
+        C::MachineLearning,
+        "Matrix-multiply assist",
+        Some("Power ISA 3.1 (POWER10)"),
+        &[],
+    ),
+    ext(
+        "HTM",
+        C::Transactional,
+        "Hardware transactional memory",
+        Some("Power ISA 2.07 (POWER8)"),
+        &[],
+    ),
+    ext(
+        "Crypto",
+        C::Crypto,
+        "Vector AES, SHA-2 and polynomial multiply",
+        Some("Power ISA 2.07 (POWER8)"),
+        &[],
+    ),
+];
+
+const MIPS: &[KnownExtension] = &[
+    ext(
+        "MSA",
+        C::Simd,
+        "MIPS SIMD Architecture",
+        Some("MIPS Release 5"),
+        &[],
+    ),
+    ext(
+        "DSP",
+        C::Simd,
+        "DSP application-specific extension",
+        Some("MIPS32 Release 2"),
+        &["DSPr1"],
+    ),
+    ext(
+        "DSP2",
+        C::Simd,
+        "DSP ASE revision 2",
+        Some("MIPS32 Release 2"),
+        &["DSPr2"],
+    ),
+    ext(
+        "MDMX",
+        C::Simd,
+        "MIPS Digital Media Extension",
+        Some("MIPS V"),
+        &[],
+    ),
+    ext(
+        "microMIPS",
+        C::Compressed,
+        "Mixed 16/32-bit encoding",
+        Some("MIPS Release 3"),
+        &[],
+    ),
+    ext(
+        "MIPS16e",
+        C::Compressed,
+        "16-bit compressed encoding",
+        Some("MIPS32 Release 1"),
+        &["MIPS16"],
+    ),
+];
+
+const S390: &[KnownExtension] = &[
+    ext("VX", C::Simd, "Vector facility", Some("z13"), &["Vector"]),
+    ext(
+        "VXE",
+        C::Simd,
+        "Vector enhancements facility 1",
+        Some("z14"),
+        &[],
+    ),
+    ext(
+        "VXE2",
+        C::Simd,
+        "Vector enhancements facility 2",
+        Some("z15"),
+        &[],
+    ),
+    ext(
+        "MSA",
+        C::Crypto,
+        "Message-security assist (CPACF)",
+        Some("z990"),
+        &["CPACF"],
+    ),
+    ext(
+        "NNPA",
+        C::MachineLearning,
+        "Neural-network processing assist",
+        Some("z16"),
+        &[],
+    ),
+    ext(
+        "TX",
+        C::Transactional,
+        "Transactional execution facility",
+        Some("zEC12"),
+        &[],
+    ),
+];
+
+const ALPHA: &[KnownExtension] = &[
+    ext(
+        "BWX",
+        C::Other,
+        "Byte and word loads and stores",
+        Some("21164A (EV56)"),
+        &[],
+    ),
+    ext(
+        "FIX",
+        C::FloatingPoint,
+        "Square root and FP/integer register moves",
+        Some("21264 (EV6)"),
+        &[],
+    ),
+    ext(
+        "CIX",
+        C::BitManip,
+        "Count instructions",
+        Some("21264A (EV67)"),
+        &[],
+    ),
+    ext(
+        "MVI",
+        C::Simd,
+        "Motion video instructions",
+        Some("21164PC (PCA56)"),
+        &[],
+    ),
+    ext(
+        "PALcode",
+        C::System,
+        "Privileged architecture library calls",
+        None,
+        &["PAL"],
+    ),
+];
+
+const LOONGARCH: &[KnownExtension] = &[
+    ext("LSX", C::Simd, "128-bit SIMD", Some("Loongson 3A5000"), &[]),
+    ext(
+        "LASX",
+        C::Simd,
+        "256-bit SIMD",
+        Some("Loongson 3A5000"),
+        &[],
+    ),
+    ext(
+        "LVZ",
+        C::Virtualization,
+        "Virtualization extension",
+        Some("Loongson 3A5000"),
+        &[],
+    ),
+    ext(
+        "LBT",
+        C::Other,
+        "Binary translation assist",
+        Some("Loongson 3A5000"),
+        &[],
+    ),
+    ext("LAM", C::Atomic, "Atomic memory access", None, &[]),
+    ext("FP", C::FloatingPoint, "Floating point", None, &[]),
+    ext("Crypto", C::Crypto, "CRC checks", None, &[]),
+];
+
+const ARM: &[KnownExtension] = &[
+    // Compressed instruction sets
+    ext(
+        "Thumb",
+        C::Compressed,
+        "16-bit Thumb instruction set",
+        Some("ARMv4T"),
+        &[],
+    ),
+    ext(
+        "Thumb-2",
+        C::Compressed,
+        "Mixed 16/32-bit Thumb instruction set",
+        Some("ARMv6T2"),
+        &["Thumb2"],
+    ),
+    // Floating-point
+    ext(
+        "VFP",
+        C::FloatingPoint,
+        "Vector floating point, version undetermined",
+        Some("ARMv5TE"),
+        &[],
+    ),
+    ext(
+        "VFPv2",
+        C::FloatingPoint,
+        "VFP version 2",
+        Some("ARMv5TE"),
+        &[],
+    ),
+    ext(
+        "VFPv3",
+        C::FloatingPoint,
+        "VFP version 3",
+        Some("ARMv7"),
+        &[],
+    ),
+    ext(
+        "VFP-D32",
+        C::FloatingPoint,
+        "VFP with 32 double-precision registers",
+        Some("ARMv7 (VFPv3)"),
+        &["VFPv3-D32", "D32"],
+    ),
+    ext(
+        "VFPv4",
+        C::FloatingPoint,
+        "VFP version 4 with fused multiply-add",
+        Some("ARMv7 (Cortex-A5, A7, A15)"),
+        &[],
+    ),
+    ext(
+        "FPv4-SP",
+        C::FloatingPoint,
+        "Single-precision FP for microcontrollers",
+        Some("ARMv7E-M (Cortex-M4)"),
+        &[],
+    ),
+    // SIMD
+    ext(
+        "NEON",
+        C::Simd,
+        "Advanced SIMD",
+        Some("ARMv7-A (optional)"),
+        &["ASIMD", "AdvSIMD"],
+    ),
+    ext(
+        "DSP",
+        C::Simd,
+        "Saturating and DSP multiply instructions",
+        Some("ARMv5TE"),
+        &["ARMv5TE DSP"],
+    ),
+    ext(
+        "SIMDv1",
+        C::Simd,
+        "ARMv6 32-bit packed SIMD",
+        Some("ARMv6"),
+        &["ARMv6 SIMD"],
+    ),
+    // Security
+    ext(
+        "TrustZone",
+        C::Security,
+        "Security Extensions (secure monitor)",
+        Some("ARMv6Z"),
+        &["Security"],
+    ),
+    // Virtualization
+    ext(
+        "Virtualization",
+        C::Virtualization,
+        "Hypervisor mode",
+        Some("ARMv7-A (optional)"),
+        &["VE"],
+    ),
+    // Crypto
+    ext(
+        "AES",
+        C::Crypto,
+        "AES round instructions",
+        Some("ARMv8 AArch32 (optional)"),
+        &[],
+    ),
+    ext(
+        "SHA1",
+        C::Crypto,
+        "SHA-1 acceleration",
+        Some("ARMv8 AArch32 (optional)"),
+        &[],
+    ),
+    ext(
+        "SHA256",
+        C::Crypto,
+        "SHA-256 acceleration",
+        Some("ARMv8 AArch32 (optional)"),
+        &["SHA2"],
+    ),
+    ext(
+        "PMULL",
+        C::Crypto,
+        "64-bit polynomial multiply",
+        Some("ARMv8 AArch32 (optional)"),
+        &[],
+    ),
+    ext(
+        "Crypto",
+        C::Crypto,
+        "AES, SHA-1, SHA-256 and PMULL together",
+        Some("ARMv8 AArch32 (optional)"),
+        &[],
+    ),
+    // Other
+    ext(
+        "IDIV",
+        C::Other,
+        "Hardware integer divide",
+        Some("ARMv7-R, ARMv7VE"),
+        &["HWDIV"],
+    ),
+    ext(
+        "CRC32",
+        C::Other,
+        "CRC32 and CRC32C",
+        Some("ARMv8 AArch32"),
+        &["CRC"],
+    ),
+    ext(
+        "MOVW",
+        C::Other,
+        "16-bit immediate moves (MOVW, MOVT)",
+        Some("ARMv6T2"),
+        &["MOVT"],
+    ),
+    ext(
+        "BitField",
+        C::Other,
+        "Bit-field insert and extract",
+        Some("ARMv6T2"),
+        &[],
+    ),
+    ext("RBIT", C::Other, "Bit reverse", Some("ARMv6T2"), &[]),
+    ext(
+        "BE8",
+        C::Other,
+        "Byte-invariant big-endian",
+        Some("ARMv6"),
+        &[],
+    ),
+    ext(
+        "Jazelle",
+        C::Other,
+        "Java bytecode execution",
+        Some("ARMv5TEJ"),
+        &[],
+    ),
+    ext(
+        "XScale",
+        C::Other,
+        "Intel XScale extensions",
+        Some("XScale (ARMv5TE)"),
+        &[],
+    ),
+];
+
+/// Every extension `isa` can report, in a stable order; empty for ISAs
+/// without extension detection.
+pub fn known_extensions_detailed(isa: Isa) -> &'static [KnownExtension] {
+    match isa {
+        Isa::X86 | Isa::X86_64 => X86,
+        Isa::AArch64 => AARCH64,
+        Isa::RiscV32 | Isa::RiscV64 | Isa::RiscV128 => RISCV,
+        Isa::Ppc | Isa::Ppc64 | Isa::PpcVle => PPC,
+        Isa::Mips | Isa::Mips64 => MIPS,
+        Isa::S390 | Isa::S390x => S390,
+        Isa::Alpha => ALPHA,
+        Isa::LoongArch32 | Isa::LoongArch64 => LOONGARCH,
+        Isa::Arm => ARM,
+        _ => &[],
+    }
+}
+
+/// The entry for `name` (canonical or alias, any ASCII case) on `isa`.
+pub fn lookup(isa: Isa, name: &str) -> Option<&'static KnownExtension> {
+    let table = known_extensions_detailed(isa);
+    // A canonical name wins over another entry's alias
+    table
+        .iter()
+        .find(|known| known.name.eq_ignore_ascii_case(name))
+        .or_else(|| table.iter().find(|known| known.matches(name)))
+}
+
+/// Canonical spelling of `name` on `isa`, if it is a known extension.
+pub fn canonical_name(isa: Isa, name: &str) -> Option<&'static str> {
+    lookup(isa, name).map(|known| known.name)
+}
+
+/// Rename the extensions in `extensions` to their canonical spelling on
+/// `isa`, dropping entries that become duplicates (the first is kept, with
+/// the highest confidence of the merged entries). Unknown names are left
+/// as they are.
+pub fn canonicalize(isa: Isa, extensions: &mut Vec<Extension>) {
+    let mut merged: Vec<Extension> = Vec::with_capacity(extensions.len());
+    for mut ext in extensions.drain(..) {
+        if let Some(name) = canonical_name(isa, &ext.name) {
+            if ext.name != name {
+                ext.name = name.to_string();
+            }
+        }
+        match merged.iter_mut().find(|e| e.name == ext.name) {
+            Some(existing) => existing.confidence = existing.confidence.max(ext.confidence),
+            None => merged.push(ext),
+        }
+    }
+    *extensions = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE_ISAS: [Isa; 9] = [
+        Isa::X86_64,
+        Isa::AArch64,
+        Isa::RiscV64,
+        Isa::Ppc64,
+        Isa::Mips,
+        Isa::S390x,
+        Isa::Alpha,
+        Isa::LoongArch64,
+        Isa::Arm,
+    ];
+
+    #[test]
+    fn test_detector_names_are_canonical() {
+        use crate::extensions::detector;
+
+        // Every extension a detector can insert, so new ones can't be added
+        // without a table entry
+        let detectors = [
+            (Isa::X86_64, detector::x86::ALL),
+            (Isa::AArch64, detector::aarch64::ALL),
+            (Isa::RiscV64, detector::riscv::ALL),
+            (Isa::Mips, detector::mips::ALL),
+            (Isa::Ppc64, detector::ppc::ALL),
+            (Isa::S390x, detector::s390x::ALL),
+            (Isa::Alpha, detector::alpha::ALL),
+            (Isa::LoongArch64, detector::loongarch::ALL),
+            (Isa::Arm, detector::arm32::ALL),
+        ];
+        let mut checked = 0;
+        for (isa, found) in detectors {
+            for &(name, category) in found {
+                let known = known_extensions_detailed(isa)
+                    .iter()
+                    .find(|k| k.name == name)
+                    .unwrap_or_else(|| panic!("{name} missing from the {isa} table"));
+                assert_eq!(known.category, category, "{name}");
+                checked += 1;
+            }
+        }
+        assert!(checked > 100);
+
+        // Baselines are reported under table names too
+        for (isa, variant) in [(Isa::X86_64, "x86-64-v4"), (Isa::AArch64, "")] {
+            let variant = crate::types::Variant::new(variant);
+            for (name, _) in super::super::baseline_extensions(isa, Some(&variant)) {
+                assert_eq!(canonical_name(isa, name), Some(name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_aliases_resolve_to_canonical_entries() {
+        assert_eq!(canonical_name(Isa::AArch64, "ASIMD"), Some("NEON"));
+        assert_eq!(canonical_name(Isa::AArch64, "advsimd"), Some("NEON"));
+        assert_eq!(canonical_name(Isa::Arm, "ASIMD"), Some("NEON"));
+        assert_eq!(canonical_name(Isa::X86_64, "AES"), Some("AES-NI"));
+        assert_eq!(canonical_name(Isa::X86, "aes-ni"), Some("AES-NI"));
+        // AES is its own name on Arm
+        assert_eq!(canonical_name(Isa::AArch64, "AES"), Some("AES"));
+        assert_eq!(canonical_name(Isa::AArch64, "PAuth"), Some("PAC"));
+        assert_eq!(canonical_name(Isa::Arm, "Security"), Some("TrustZone"));
+        assert_eq!(canonical_name(Isa::AArch64, "AVX2"), None);
+
+        let sve = lookup(Isa::AArch64, "sve").unwrap();
+        assert_eq!(sve.introduced_in, Some("ARMv8.2 (optional)"));
+        assert!(!sve.description.is_empty());
+
+        let mut exts = vec![
+            Extension::with_confidence("AES", C::Crypto, 0.6),
+            Extension::with_confidence("AES-NI", C::Crypto, 0.9),
+            Extension::new("AVX2", C::Simd),
+        ];
+        canonicalize(Isa::X86_64, &mut exts);
+        let names: Vec<_> = exts
+            .iter()
+            .map(|e| (e.name.as_str(), e.confidence))
+            .collect();
+        assert_eq!(names, [("AES-NI", 0.9), ("AVX2", 1.0)]);
+    }
+
+    #[test]
+    fn test_table_names_unambiguous() {
+        for isa in TABLE_ISAS {
+            let table = known_extensions_detailed(isa);
+            for known in table {
+                assert!(!known.description.is_empty(), "{}", known.name);
+                for name in std::iter::once(&known.name).chain(known.aliases) {
+                    let owners: Vec<_> = table
+                        .iter()
+                        .filter(|k| k.matches(name))
+                        .map(|k| k.name)
+                        .collect();
+                    assert_eq!(owners, [known.name], "{isa} {name}");
+                }
+            }
+        }
+    }
+}
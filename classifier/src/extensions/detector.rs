@@ -6,6 +6,250 @@
 use crate::types::{Endianness, Extension, ExtensionCategory};
 use std::collections::HashSet;
 
+/// An extension a detector reports: its catalog name and category.
+pub(crate) type Found = (&'static str, ExtensionCategory);
+
+/// Extensions [`detect_x86_extensions`] reports.
+pub(crate) mod x86 {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const AVX: Found = ("AVX", Simd);
+    pub(crate) const AVX2: Found = ("AVX2", Simd);
+    pub(crate) const FMA: Found = ("FMA", Simd);
+    pub(crate) const AVX_512: Found = ("AVX-512", Simd);
+    pub(crate) const AVX_512F: Found = ("AVX-512F", Simd);
+    pub(crate) const APX: Found = ("APX", System);
+    pub(crate) const SSE: Found = ("SSE", Simd);
+    pub(crate) const SSE2: Found = ("SSE2", Simd);
+    pub(crate) const POPCNT: Found = ("POPCNT", BitManip);
+    pub(crate) const LZCNT: Found = ("LZCNT", BitManip);
+    pub(crate) const AES_NI: Found = ("AES-NI", Crypto);
+    pub(crate) const SHA: Found = ("SHA", Crypto);
+    pub(crate) const CET: Found = ("CET", Security);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[
+        AVX, AVX2, FMA, AVX_512, AVX_512F, APX, SSE, SSE2, POPCNT, LZCNT, AES_NI, SHA, CET,
+    ];
+}
+
+/// Extensions [`detect_aarch64_extensions`] reports.
+pub(crate) mod aarch64 {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const SVE: Found = ("SVE", Simd);
+    pub(crate) const SVE2: Found = ("SVE2", Simd);
+    pub(crate) const SME: Found = ("SME", Simd);
+    pub(crate) const SME2: Found = ("SME2", Simd);
+    pub(crate) const DOTPROD: Found = ("DOTPROD", Simd);
+    pub(crate) const FP16: Found = ("FP16", Simd);
+    pub(crate) const BF16: Found = ("BF16", Simd);
+    pub(crate) const I8MM: Found = ("I8MM", Simd);
+    pub(crate) const RDM: Found = ("RDM", Simd);
+    pub(crate) const FHM: Found = ("FHM", Simd);
+    pub(crate) const AES: Found = ("AES", Crypto);
+    pub(crate) const PMULL: Found = ("PMULL", Crypto);
+    pub(crate) const SHA1: Found = ("SHA1", Crypto);
+    pub(crate) const SHA256: Found = ("SHA256", Crypto);
+    pub(crate) const SHA512: Found = ("SHA512", Crypto);
+    pub(crate) const SHA3: Found = ("SHA3", Crypto);
+    pub(crate) const SM3: Found = ("SM3", Crypto);
+    pub(crate) const SM4: Found = ("SM4", Crypto);
+    pub(crate) const LSE: Found = ("LSE", Atomic);
+    pub(crate) const LSE2: Found = ("LSE2", Atomic);
+    pub(crate) const LRCPC: Found = ("LRCPC", Atomic);
+    pub(crate) const LRCPC2: Found = ("LRCPC2", Atomic);
+    pub(crate) const PAC: Found = ("PAC", Security);
+    pub(crate) const BTI: Found = ("BTI", Security);
+    pub(crate) const MTE: Found = ("MTE", Security);
+    pub(crate) const RNG: Found = ("RNG", Security);
+    pub(crate) const SB: Found = ("SB", Security);
+    pub(crate) const SSBS: Found = ("SSBS", Security);
+    pub(crate) const DIT: Found = ("DIT", Security);
+    pub(crate) const DPB: Found = ("DPB", System);
+    pub(crate) const DPB2: Found = ("DPB2", System);
+    pub(crate) const WFXT: Found = ("WFxT", System);
+    pub(crate) const CRC32: Found = ("CRC32", Other);
+    pub(crate) const JSCVT: Found = ("JSCVT", Other);
+    pub(crate) const FCMA: Found = ("FCMA", Simd);
+    pub(crate) const FRINTTS: Found = ("FRINTTS", Simd);
+    pub(crate) const FLAGM: Found = ("FlagM", Other);
+    pub(crate) const FLAGM2: Found = ("FlagM2", Other);
+    pub(crate) const MOPS: Found = ("MOPS", Other);
+    pub(crate) const HBC: Found = ("HBC", Other);
+    pub(crate) const CSSC: Found = ("CSSC", Other);
+    pub(crate) const LS64: Found = ("LS64", Other);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[
+        SVE, SVE2, SME, SME2, DOTPROD, FP16, BF16, I8MM, RDM, FHM, AES, PMULL, SHA1, SHA256,
+        SHA512, SHA3, SM3, SM4, LSE, LSE2, LRCPC, LRCPC2, PAC, BTI, MTE, RNG, SB, SSBS, DIT, DPB,
+        DPB2, WFXT, CRC32, JSCVT, FCMA, FRINTTS, FLAGM, FLAGM2, MOPS, HBC, CSSC, LS64,
+    ];
+}
+
+/// Extensions [`detect_riscv_extensions`] reports.
+pub(crate) mod riscv {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const M: Found = ("M", Other);
+    pub(crate) const A: Found = ("A", Atomic);
+    pub(crate) const F: Found = ("F", FloatingPoint);
+    pub(crate) const D: Found = ("D", FloatingPoint);
+    pub(crate) const V: Found = ("V", Simd);
+    pub(crate) const ZBA: Found = ("Zba", BitManip);
+    pub(crate) const ZBB: Found = ("Zbb", BitManip);
+    pub(crate) const ZBC: Found = ("Zbc", BitManip);
+    pub(crate) const ZBS: Found = ("Zbs", BitManip);
+    pub(crate) const ZKND: Found = ("Zknd", Crypto);
+    pub(crate) const ZKNE: Found = ("Zkne", Crypto);
+    pub(crate) const ZKNH: Found = ("Zknh", Crypto);
+    pub(crate) const C: Found = ("C", Compressed);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[M, A, F, D, V, ZBA, ZBB, ZBC, ZBS, ZKND, ZKNE, ZKNH, C];
+}
+
+/// Extensions [`detect_mips_extensions`] reports.
+pub(crate) mod mips {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const MSA: Found = ("MSA", Simd);
+    pub(crate) const MDMX: Found = ("MDMX", Simd);
+    pub(crate) const DSP: Found = ("DSP", Simd);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[MSA, MDMX, DSP];
+}
+
+/// Extensions [`detect_ppc_extensions`] reports.
+pub(crate) mod ppc {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const VMX: Found = ("VMX", Simd);
+    pub(crate) const VSX: Found = ("VSX", Simd);
+    pub(crate) const DFP: Found = ("DFP", FloatingPoint);
+    pub(crate) const CRYPTO: Found = ("Crypto", Crypto);
+    pub(crate) const MMA: Found = ("MMA", MachineLearning);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[VMX, VSX, DFP, CRYPTO, MMA];
+}
+
+/// Extensions [`detect_s390x_extensions`] reports.
+pub(crate) mod s390x {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const VX: Found = ("VX", Simd);
+    pub(crate) const VXE: Found = ("VXE", Simd);
+    pub(crate) const MSA: Found = ("MSA", Crypto);
+    pub(crate) const NNPA: Found = ("NNPA", MachineLearning);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[VX, VXE, MSA, NNPA];
+}
+
+/// Extensions [`detect_alpha_extensions`] reports.
+pub(crate) mod alpha {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const BWX: Found = ("BWX", Other);
+    pub(crate) const FIX: Found = ("FIX", FloatingPoint);
+    pub(crate) const CIX: Found = ("CIX", BitManip);
+    pub(crate) const MVI: Found = ("MVI", Simd);
+    pub(crate) const PALCODE: Found = ("PALcode", System);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[BWX, FIX, CIX, MVI, PALCODE];
+}
+
+/// Extensions [`detect_loongarch_extensions`] reports.
+pub(crate) mod loongarch {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const LSX: Found = ("LSX", Simd);
+    pub(crate) const LASX: Found = ("LASX", Simd);
+    pub(crate) const LVZ: Found = ("LVZ", Virtualization);
+    pub(crate) const CRYPTO: Found = ("Crypto", Crypto);
+    pub(crate) const LBT: Found = ("LBT", Other);
+    pub(crate) const LAM: Found = ("LAM", Atomic);
+    pub(crate) const FP: Found = ("FP", FloatingPoint);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[LSX, LASX, LVZ, CRYPTO, LBT, LAM, FP];
+}
+
+/// Extensions [`detect_arm32_extensions`] reports.
+pub(crate) mod arm32 {
+    use super::Found;
+    use crate::types::ExtensionCategory::*;
+
+    pub(crate) const THUMB: Found = ("Thumb", Compressed);
+    pub(crate) const THUMB_2: Found = ("Thumb-2", Compressed);
+    pub(crate) const VFPV4: Found = ("VFPv4", FloatingPoint);
+    pub(crate) const VFP: Found = ("VFP", FloatingPoint);
+    pub(crate) const VFP_D32: Found = ("VFP-D32", FloatingPoint);
+    pub(crate) const VFPV3: Found = ("VFPv3", FloatingPoint);
+    pub(crate) const NEON: Found = ("NEON", Simd);
+    pub(crate) const DSP: Found = ("DSP", Simd);
+    pub(crate) const SIMDV1: Found = ("SIMDv1", Simd);
+    pub(crate) const TRUSTZONE: Found = ("TrustZone", Security);
+    pub(crate) const VIRTUALIZATION: Found = ("Virtualization", Virtualization);
+    pub(crate) const IDIV: Found = ("IDIV", Other);
+    pub(crate) const CRC32: Found = ("CRC32", Other);
+    pub(crate) const AES: Found = ("AES", Crypto);
+    pub(crate) const SHA1: Found = ("SHA1", Crypto);
+    pub(crate) const SHA256: Found = ("SHA256", Crypto);
+    pub(crate) const PMULL: Found = ("PMULL", Crypto);
+    pub(crate) const MOVW: Found = ("MOVW", Other);
+    pub(crate) const BITFIELD: Found = ("BitField", Other);
+    pub(crate) const RBIT: Found = ("RBIT", Other);
+    pub(crate) const JAZELLE: Found = ("Jazelle", Other);
+
+    /// Every extension above
+    pub(crate) const ALL: &[Found] = &[
+        THUMB,
+        THUMB_2,
+        VFPV4,
+        VFP,
+        VFP_D32,
+        VFPV3,
+        NEON,
+        DSP,
+        SIMDV1,
+        TRUSTZONE,
+        VIRTUALIZATION,
+        IDIV,
+        CRC32,
+        AES,
+        SHA1,
+        SHA256,
+        PMULL,
+        MOVW,
+        BITFIELD,
+        RBIT,
+        JAZELLE,
+    ];
+}
+
+/// The `found` extensions in `table` order, so the result does not depend
+/// on hash order.
+fn in_table_order(table: &[Found], found: &HashSet<Found>) -> Vec<Extension> {
+    table
+        .iter()
+        .filter(|ext| found.contains(ext))
+        .map(|&(name, category)| Extension::new(name, category))
+        .collect()
+}
+
 /// Detect x86/x86-64 extensions from instruction prefixes and patterns.
 pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
     let mut extensions = HashSet::new();
@@ -30,21 +274,21 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
 
         // VEX 2-byte prefix (C5)
         if b == 0xC5 && i + 2 < data.len() {
-            extensions.insert(("AVX", ExtensionCategory::Simd));
+            extensions.insert(x86::AVX);
             i += 2;
             continue;
         }
 
         // VEX 3-byte prefix (C4)
         if b == 0xC4 && i + 3 < data.len() {
-            extensions.insert(("AVX", ExtensionCategory::Simd));
+            extensions.insert(x86::AVX);
 
             let p1 = data[i + 1];
             let map_sel = p1 & 0x1F;
 
             // Map select can indicate AVX2
             if map_sel == 2 || map_sel == 3 {
-                extensions.insert(("AVX2", ExtensionCategory::Simd));
+                extensions.insert(x86::AVX2);
             }
 
             // Check for FMA (map 2 with specific opcodes)
@@ -55,7 +299,7 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
                         || (0xA6..=0xAF).contains(&opcode)
                         || (0xB6..=0xBF).contains(&opcode)
                     {
-                        extensions.insert(("FMA", ExtensionCategory::Simd));
+                        extensions.insert(x86::FMA);
                     }
                 }
             }
@@ -70,7 +314,7 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
 
             // Verify EVEX: bits [3:2] should be 0
             if (p1 & 0x0C) == 0x00 {
-                extensions.insert(("AVX-512", ExtensionCategory::Simd));
+                extensions.insert(x86::AVX_512);
 
                 // Check for specific AVX-512 features
                 let _p2 = data[i + 2];
@@ -80,12 +324,12 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
                 let ll = (p3 >> 5) & 0x3;
                 if ll == 2 {
                     // 512-bit operations
-                    extensions.insert(("AVX-512F", ExtensionCategory::Simd));
+                    extensions.insert(x86::AVX_512F);
                 }
 
                 // Check for masking (aaa field)
                 if (p3 & 0x07) != 0 {
-                    extensions.insert(("AVX-512F", ExtensionCategory::Simd));
+                    extensions.insert(x86::AVX_512F);
                 }
             }
 
@@ -95,7 +339,7 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
 
         // REX2 prefix (D5) - APX
         if b == 0xD5 && i + 2 < data.len() {
-            extensions.insert(("APX", ExtensionCategory::System));
+            extensions.insert(x86::APX);
             i += 2;
             continue;
         }
@@ -106,29 +350,29 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
 
             // SSE instructions
             if (0x10..=0x17).contains(&next) {
-                extensions.insert(("SSE", ExtensionCategory::Simd));
+                extensions.insert(x86::SSE);
             }
 
             // SSE2 conversion instructions
             if next == 0x5A || next == 0x5B {
-                extensions.insert(("SSE2", ExtensionCategory::Simd));
+                extensions.insert(x86::SSE2);
             }
 
             // POPCNT (F3 0F B8)
             if i > 0 && data[i - 1] == 0xF3 && next == 0xB8 {
-                extensions.insert(("POPCNT", ExtensionCategory::BitManip));
+                extensions.insert(x86::POPCNT);
             }
 
             // LZCNT (F3 0F BD)
             if i > 0 && data[i - 1] == 0xF3 && next == 0xBD {
-                extensions.insert(("LZCNT", ExtensionCategory::BitManip));
+                extensions.insert(x86::LZCNT);
             }
 
             // AES-NI (0F 38 with specific opcodes)
             if next == 0x38 && i + 3 < data.len() {
                 let aes_op = data[i + 2];
                 if matches!(aes_op, 0xDB | 0xDC | 0xDD | 0xDE | 0xDF) {
-                    extensions.insert(("AES-NI", ExtensionCategory::Crypto));
+                    extensions.insert(x86::AES_NI);
                 }
             }
 
@@ -136,7 +380,7 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
             if next == 0x38 && i + 3 < data.len() {
                 let sha_op = data[i + 2];
                 if matches!(sha_op, 0xC8 | 0xC9 | 0xCA | 0xCB | 0xCC | 0xCD) {
-                    extensions.insert(("SHA", ExtensionCategory::Crypto));
+                    extensions.insert(x86::SHA);
                 }
             }
         }
@@ -145,17 +389,14 @@ pub fn detect_x86_extensions(data: &[u8]) -> Vec<Extension> {
         if i + 4 <= data.len() {
             let window = &data[i..i + 4];
             if window == [0xF3, 0x0F, 0x1E, 0xFA] || window == [0xF3, 0x0F, 0x1E, 0xFB] {
-                extensions.insert(("CET", ExtensionCategory::Security));
+                extensions.insert(x86::CET);
             }
         }
 
         i += 1;
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(x86::ALL, &extensions)
 }
 
 /// Detect AArch64 extensions from instruction patterns.
@@ -171,127 +412,127 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         // SVE instructions (bits [31:25] patterns)
         let top7 = (word >> 25) & 0x7F;
         if matches!(top7, 0x04 | 0x05 | 0x25 | 0x45 | 0x65 | 0x85) {
-            extensions.insert(("SVE", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SVE);
         }
 
         // SVE2 specific (extended SVE encodings)
         if top7 == 0x45 {
-            extensions.insert(("SVE2", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SVE2);
         }
 
         // SME (Scalable Matrix Extension)
         if (word >> 24) == 0xC0 {
-            extensions.insert(("SME", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SME);
         }
         // SME start/stop (SMSTART, SMSTOP)
         if word == 0xD503417F || word == 0xD503427F {
-            extensions.insert(("SME", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SME);
         }
         // SMSTART/SMSTOP variants
         if (word & 0xFFFFFE3F) == 0xD503401F {
-            extensions.insert(("SME", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SME);
         }
 
         // SME2 (enhanced SME)
         // ZERO { ZT0 } and other ZT0 operations
         if (word & 0xFFFFFF00) == 0xC0480000 {
-            extensions.insert(("SME2", ExtensionCategory::Simd));
+            extensions.insert(aarch64::SME2);
         }
 
         // Dot product (SDOT, UDOT) - FEAT_DotProd
         if (word & 0xBF20FC00) == 0x0E809400 {
-            extensions.insert(("DOTPROD", ExtensionCategory::Simd));
+            extensions.insert(aarch64::DOTPROD);
         }
 
         // FP16 (half-precision) - FEAT_FP16
         if (word & 0xFF200C00) == 0x1E200000 {
             let ftype = (word >> 22) & 0x3;
             if ftype == 3 {
-                extensions.insert(("FP16", ExtensionCategory::Simd));
+                extensions.insert(aarch64::FP16);
             }
         }
         // SIMD FP16 operations
         if (word & 0xBF80F400) == 0x0E400400 {
-            extensions.insert(("FP16", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FP16);
         }
 
         // BF16 - FEAT_BF16
         if (word & 0xFFE0FC00) == 0x2E40EC00 {
-            extensions.insert(("BF16", ExtensionCategory::Simd));
+            extensions.insert(aarch64::BF16);
         }
         // BFCVT, BFCVTN, BFCVTN2
         if (word & 0xFFFFFC00) == 0x1E634000 || (word & 0xBFBFFC00) == 0x0EA16800 {
-            extensions.insert(("BF16", ExtensionCategory::Simd));
+            extensions.insert(aarch64::BF16);
         }
 
         // I8MM (matrix multiply) - FEAT_I8MM
         if (word & 0xBFE0FC00) == 0x0E80A400 {
-            extensions.insert(("I8MM", ExtensionCategory::Simd));
+            extensions.insert(aarch64::I8MM);
         }
 
         // RDMA (SQRDMLAH, SQRDMLSH) - FEAT_RDM
         // Vector form: 0x2E008400 / 0x2E008C00
         if (word & 0xBF20FC00) == 0x2E008400 || (word & 0xBF20FC00) == 0x2E008C00 {
-            extensions.insert(("RDM", ExtensionCategory::Simd));
+            extensions.insert(aarch64::RDM);
         }
         // Scalar form
         if (word & 0xFF20FC00) == 0x7E008400 || (word & 0xFF20FC00) == 0x7E008C00 {
-            extensions.insert(("RDM", ExtensionCategory::Simd));
+            extensions.insert(aarch64::RDM);
         }
 
         // FHM (FMLAL, FMLSL) - FEAT_FHM
         if (word & 0xBFE0FC00) == 0x0E20EC00 || (word & 0xBFE0FC00) == 0x0EA0EC00 {
-            extensions.insert(("FHM", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FHM);
         }
         // FMLAL2, FMLSL2
         if (word & 0xBFE0FC00) == 0x2E20CC00 || (word & 0xBFE0FC00) == 0x2EA0CC00 {
-            extensions.insert(("FHM", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FHM);
         }
 
         // ==================== Crypto Extensions ====================
 
         // AES - FEAT_AES
         if (word & 0xFFFFFC00) == 0x4E284800 {
-            extensions.insert(("AES", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::AES);
         }
         // AESE, AESD, AESMC, AESIMC
         if (word & 0xFFFF0C00) == 0x4E280800 {
-            extensions.insert(("AES", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::AES);
         }
 
         // PMULL, PMULL2 (also part of AES feature)
         if (word & 0xBFE0FC00) == 0x0E20E000 {
-            extensions.insert(("PMULL", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::PMULL);
         }
 
         // SHA1 - FEAT_SHA1
         if (word & 0xFFFFFC00) == 0x5E280800 {
-            extensions.insert(("SHA1", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SHA1);
         }
         // SHA1C, SHA1P, SHA1M, SHA1H, SHA1SU0, SHA1SU1
         if (word & 0xFFE0FC00) == 0x5E000000 {
             let op = (word >> 12) & 0x7;
             if matches!(op, 0..=4) {
-                extensions.insert(("SHA1", ExtensionCategory::Crypto));
+                extensions.insert(aarch64::SHA1);
             }
         }
 
         // SHA256 - FEAT_SHA256
         if (word & 0xFFFFFC00) == 0x5E282800 {
-            extensions.insert(("SHA256", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SHA256);
         }
         // SHA256H, SHA256H2, SHA256SU0, SHA256SU1
         if (word & 0xFFE0FC00) == 0x5E004000 {
-            extensions.insert(("SHA256", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SHA256);
         }
 
         // SHA512 - FEAT_SHA512
         if (word & 0xFFE0FC00) == 0xCE608000 {
-            extensions.insert(("SHA512", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SHA512);
         }
         // SHA512H, SHA512H2, SHA512SU0, SHA512SU1
         if (word & 0xFFE0FC00) == 0xCE608800 || (word & 0xFFFFFC00) == 0xCEC08000 {
-            extensions.insert(("SHA512", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SHA512);
         }
 
         // SHA3 - FEAT_SHA3
@@ -299,20 +540,20 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         if (word & 0xFFE08000) == 0xCE000000 {
             let op = (word >> 21) & 0x7;
             if matches!(op, 0 | 1 | 2 | 3) {
-                extensions.insert(("SHA3", ExtensionCategory::Crypto));
+                extensions.insert(aarch64::SHA3);
             }
         }
 
         // SM3 - FEAT_SM3
         // SM3SS1, SM3TT1A, SM3TT1B, SM3TT2A, SM3TT2B, SM3PARTW1, SM3PARTW2
         if (word & 0xFFE08000) == 0xCE400000 {
-            extensions.insert(("SM3", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SM3);
         }
 
         // SM4 - FEAT_SM4
         // SM4E, SM4EKEY
         if (word & 0xFFFFFC00) == 0xCEC08400 || (word & 0xFFE0FC00) == 0xCE60C800 {
-            extensions.insert(("SM4", ExtensionCategory::Crypto));
+            extensions.insert(aarch64::SM4);
         }
 
         // ==================== Atomic Extensions ====================
@@ -323,47 +564,47 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
             let l = (word >> 22) & 0x1;
             let o0 = (word >> 15) & 0x1;
             if o2 == 1 && l == 0 && o0 == 1 {
-                extensions.insert(("LSE", ExtensionCategory::Atomic));
+                extensions.insert(aarch64::LSE);
             }
         }
         // CASP, CASPA, CASPAL, CASPL
         if (word & 0xBFE0FC00) == 0x08207C00 {
-            extensions.insert(("LSE", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LSE);
         }
         // LDADD, LDCLR, LDEOR, LDSET, LDSMAX, LDSMIN, LDUMAX, LDUMIN
         if (word & 0x3F208C00) == 0x38200000 {
-            extensions.insert(("LSE", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LSE);
         }
         // SWP variants
         if (word & 0x3F20FC00) == 0x38208000 {
-            extensions.insert(("LSE", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LSE);
         }
 
         // LSE2 (unaligned atomics) - FEAT_LSE2
         // LDAPUR, STLUR, LDAPURSW, etc.
         if (word & 0x3FE00C00) == 0x19000000 {
-            extensions.insert(("LSE2", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LSE2);
         }
         // 128-bit atomics (LDXP/STXP with release/acquire)
         if (word & 0xBFE08000) == 0x88208000 {
             let rt2 = (word >> 10) & 0x1F;
             if rt2 != 0x1F {
-                extensions.insert(("LSE2", ExtensionCategory::Atomic));
+                extensions.insert(aarch64::LSE2);
             }
         }
 
         // LRCPC (LDAPR) - FEAT_LRCPC
         if (word & 0x3FE00C00) == 0x38800C00 {
-            extensions.insert(("LRCPC", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LRCPC);
         }
         // LDAPR register variants
         if (word & 0xBFFFFC00) == 0xB8BFC000 {
-            extensions.insert(("LRCPC", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LRCPC);
         }
 
         // LRCPC2 (LDAPUR/STLUR with immediate offset) - FEAT_LRCPC2
         if (word & 0x3FE00400) == 0x19400000 {
-            extensions.insert(("LRCPC2", ExtensionCategory::Atomic));
+            extensions.insert(aarch64::LRCPC2);
         }
 
         // ==================== Security Extensions ====================
@@ -371,69 +612,69 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         // PAC (Pointer Authentication) - FEAT_PAuth
         if word == 0xD503233F {
             // PACIASP
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         if word == 0xD50323BF {
             // AUTIASP
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // PACIA, PACIB, PACDA, PACDB, AUTIA, AUTIB, AUTDA, AUTDB
         if (word & 0xFFFFF800) == 0xDAC10000 {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // PACIZA, PACIZB, PACDZA, PACDZB, AUTIZA, AUTIZB, AUTDZA, AUTDZB
         if (word & 0xFFFFF800) == 0xDAC10800 {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // PACIA1716, PACIB1716, AUTIA1716, AUTIB1716
         if (word & 0xFFFFFFF0) == 0xD503211F {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // XPACI, XPACD
         if (word & 0xFFFFFC00) == 0xDAC143E0 {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // BLRAA, BLRAB, BRAA, BRAB, BLRAAZ, BLRABZ, BRAAZ, BRABZ
         if (word & 0xFEFFF800) == 0xD61F0800 || (word & 0xFEFFFFE0) == 0xD63F081F {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // RETAA, RETAB
         if word == 0xD65F0BFF || word == 0xD65F0FFF {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // ERETAA, ERETAB
         if word == 0xD69F0BFF || word == 0xD69F0FFF {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
         // LDRAA, LDRAB (authenticated load)
         if (word & 0xFF200400) == 0xF8200400 {
-            extensions.insert(("PAC", ExtensionCategory::Security));
+            extensions.insert(aarch64::PAC);
         }
 
         // BTI (Branch Target Identification) - FEAT_BTI
         if (word & 0xFFFFFF3F) == 0xD503241F {
-            extensions.insert(("BTI", ExtensionCategory::Security));
+            extensions.insert(aarch64::BTI);
         }
 
         // MTE (Memory Tagging Extension) - FEAT_MTE
         if (word & 0xFF000000) == 0xD9000000 {
-            extensions.insert(("MTE", ExtensionCategory::Security));
+            extensions.insert(aarch64::MTE);
         }
         // IRG, GMI, SUBP, SUBPS
         if (word & 0xFFE00C00) == 0x9AC00000 {
-            extensions.insert(("MTE", ExtensionCategory::Security));
+            extensions.insert(aarch64::MTE);
         }
         // ADDG, SUBG
         if (word & 0xFFC0C000) == 0x91800000 {
-            extensions.insert(("MTE", ExtensionCategory::Security));
+            extensions.insert(aarch64::MTE);
         }
         // LDG, STG, STZG, ST2G, STZ2G
         if (word & 0xFFE00C00) == 0xD9200000 {
-            extensions.insert(("MTE", ExtensionCategory::Security));
+            extensions.insert(aarch64::MTE);
         }
         // LDGM, STGM, STZGM
         if (word & 0xFFE0FC00) == 0xD9E00000 {
-            extensions.insert(("MTE", ExtensionCategory::Security));
+            extensions.insert(aarch64::MTE);
         }
 
         // RNG (Random Number) - FEAT_RNG
@@ -441,24 +682,24 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         // MRS Xt, RNDR: 0xD53B2400 | Rt
         // MRS Xt, RNDRRS: 0xD53B2420 | Rt
         if (word & 0xFFFFFFE0) == 0xD53B2400 || (word & 0xFFFFFFE0) == 0xD53B2420 {
-            extensions.insert(("RNG", ExtensionCategory::Security));
+            extensions.insert(aarch64::RNG);
         }
 
         // SB (Speculation Barrier) - FEAT_SB
         if word == 0xD50330FF {
-            extensions.insert(("SB", ExtensionCategory::Security));
+            extensions.insert(aarch64::SB);
         }
 
         // SSBS (Speculative Store Bypass Safe) - FEAT_SSBS
         // MSR SSBS, #imm or MRS/MSR with SSBS register
         if (word & 0xFFFFF0FF) == 0xD500411F {
-            extensions.insert(("SSBS", ExtensionCategory::Security));
+            extensions.insert(aarch64::SSBS);
         }
 
         // DIT (Data Independent Timing) - FEAT_DIT
         // MSR DIT, #imm
         if (word & 0xFFFFF0FF) == 0xD500415F {
-            extensions.insert(("DIT", ExtensionCategory::Security));
+            extensions.insert(aarch64::DIT);
         }
 
         // ==================== System Extensions ====================
@@ -466,46 +707,46 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         // DPB (DC CVAP - Data Cache Clean to Point of Persistence) - FEAT_DPB
         // DC CVAP, Xt: 0xD50B7C20 | Rt
         if (word & 0xFFFFFFE0) == 0xD50B7C20 {
-            extensions.insert(("DPB", ExtensionCategory::System));
+            extensions.insert(aarch64::DPB);
         }
 
         // DPB2 (DC CVADP - Data Cache Clean to Point of Deep Persistence) - FEAT_DPB2
         // DC CVADP, Xt: 0xD50B7D20 | Rt
         if (word & 0xFFFFFFE0) == 0xD50B7D20 {
-            extensions.insert(("DPB2", ExtensionCategory::System));
+            extensions.insert(aarch64::DPB2);
         }
 
         // WFxT (WFET, WFIT) - FEAT_WFxT
         // WFET: 0xD5031000 | Rd
         // WFIT: 0xD5031020 | Rd
         if (word & 0xFFFFFFE0) == 0xD5031000 || (word & 0xFFFFFFE0) == 0xD5031020 {
-            extensions.insert(("WFxT", ExtensionCategory::System));
+            extensions.insert(aarch64::WFXT);
         }
 
         // ==================== Other Extensions ====================
 
         // CRC32 - FEAT_CRC32
         if (word & 0xFFF0FC00) == 0x1AC04000 {
-            extensions.insert(("CRC32", ExtensionCategory::Other));
+            extensions.insert(aarch64::CRC32);
         }
 
         // JSCVT (FJCVTZS - JavaScript conversion) - FEAT_JSCVT
         if (word & 0xFFFFFC00) == 0x1E7E0000 {
-            extensions.insert(("JSCVT", ExtensionCategory::Other));
+            extensions.insert(aarch64::JSCVT);
         }
 
         // FCMA (FCMLA, FCADD - complex number arithmetic) - FEAT_FCMA
         // Vector FCMLA
         if (word & 0xBF80E400) == 0x2E00C400 {
-            extensions.insert(("FCMA", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FCMA);
         }
         // Vector FCADD
         if (word & 0xBF80EC00) == 0x2E00E400 {
-            extensions.insert(("FCMA", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FCMA);
         }
         // FCMLA by element
         if (word & 0xBF001000) == 0x2F001000 {
-            extensions.insert(("FCMA", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FCMA);
         }
 
         // FRINTTS (FRINT32Z, FRINT32X, FRINT64Z, FRINT64X) - FEAT_FRINTTS
@@ -513,69 +754,66 @@ pub fn detect_aarch64_extensions(data: &[u8], _endianness: Endianness) -> Vec<Ex
         if (word & 0xFFBFFC00) == 0x1E284000 {
             let op = (word >> 15) & 0x3;
             if matches!(op, 0 | 1) {
-                extensions.insert(("FRINTTS", ExtensionCategory::Simd));
+                extensions.insert(aarch64::FRINTTS);
             }
         }
         // Vector versions
         if (word & 0xBFBFF800) == 0x0E21E800 {
-            extensions.insert(("FRINTTS", ExtensionCategory::Simd));
+            extensions.insert(aarch64::FRINTTS);
         }
 
         // FlagM (CFINV, RMIF, SETF8, SETF16) - FEAT_FlagM
         // CFINV: 0xD500401F
         if word == 0xD500401F {
-            extensions.insert(("FlagM", ExtensionCategory::Other));
+            extensions.insert(aarch64::FLAGM);
         }
         // RMIF: 0xBA000400 | ...
         if (word & 0xFFE07C10) == 0xBA000400 {
-            extensions.insert(("FlagM", ExtensionCategory::Other));
+            extensions.insert(aarch64::FLAGM);
         }
         // SETF8, SETF16: 0x3A00080D / 0x3A00480D
         if (word & 0xFFFFBC1F) == 0x3A00080D {
-            extensions.insert(("FlagM", ExtensionCategory::Other));
+            extensions.insert(aarch64::FLAGM);
         }
 
         // FlagM2 (AXFLAG, XAFLAG) - FEAT_FlagM2
         if word == 0xD500405F || word == 0xD500403F {
-            extensions.insert(("FlagM2", ExtensionCategory::Other));
+            extensions.insert(aarch64::FLAGM2);
         }
 
         // MOPS (Memory Operations - CPYFP/CPYM/CPYE, SETP/SETM/SETE) - FEAT_MOPS
         // Memory copy/set instructions have specific patterns
         if (word & 0x3FE0FC00) == 0x19000400 || (word & 0x3FE0FC00) == 0x19400400 {
-            extensions.insert(("MOPS", ExtensionCategory::Other));
+            extensions.insert(aarch64::MOPS);
         }
         // CPY* instructions
         if (word & 0xFFE00C00) == 0x1D000400 {
-            extensions.insert(("MOPS", ExtensionCategory::Other));
+            extensions.insert(aarch64::MOPS);
         }
         // SET* instructions
         if (word & 0xFFE00C00) == 0x1D800400 {
-            extensions.insert(("MOPS", ExtensionCategory::Other));
+            extensions.insert(aarch64::MOPS);
         }
 
         // HBC (Hinted Conditional Branches - BC.cond) - FEAT_HBC
         if (word & 0xFF000010) == 0x54000010 {
-            extensions.insert(("HBC", ExtensionCategory::Other));
+            extensions.insert(aarch64::HBC);
         }
 
         // CSSC (Common Short Sequence Compression) - FEAT_CSSC
         // ABS, CNT (scalar), CTZ, SMAX, SMIN, UMAX, UMIN (scalar)
         if (word & 0xFFE0FC00) == 0x5AC01000 {
-            extensions.insert(("CSSC", ExtensionCategory::Other));
+            extensions.insert(aarch64::CSSC);
         }
 
         // LS64 (64-byte loads/stores) - FEAT_LS64
         // LD64B, ST64B, ST64BV, ST64BV0
         if (word & 0xFFFFFC00) == 0xF8200C00 || (word & 0xFFE0FC00) == 0xF8200800 {
-            extensions.insert(("LS64", ExtensionCategory::Other));
+            extensions.insert(aarch64::LS64);
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(aarch64::ALL, &extensions)
 }
 
 /// Detect RISC-V extensions from instruction patterns.
@@ -607,13 +845,13 @@ pub fn detect_riscv_extensions(data: &[u8], _endianness: Endianness) -> Vec<Exte
             if opcode == 0x33 {
                 let funct7 = (word >> 25) & 0x7F;
                 if funct7 == 0x01 {
-                    extensions.insert(("M", ExtensionCategory::Other));
+                    extensions.insert(riscv::M);
                 }
             }
 
             // A extension (atomic)
             if opcode == 0x2F {
-                extensions.insert(("A", ExtensionCategory::Atomic));
+                extensions.insert(riscv::A);
             }
 
             // F/D extension (floating-point)
@@ -621,17 +859,17 @@ pub fn detect_riscv_extensions(data: &[u8], _endianness: Endianness) -> Vec<Exte
                 if opcode == 0x53 {
                     let funct7 = (word >> 25) & 0x7F;
                     if (funct7 & 0x60) == 0x00 {
-                        extensions.insert(("F", ExtensionCategory::FloatingPoint));
+                        extensions.insert(riscv::F);
                     }
                     if (funct7 & 0x60) == 0x20 {
-                        extensions.insert(("D", ExtensionCategory::FloatingPoint));
+                        extensions.insert(riscv::D);
                     }
                 }
             }
 
             // V extension (vector)
             if opcode == 0x57 {
-                extensions.insert(("V", ExtensionCategory::Simd));
+                extensions.insert(riscv::V);
             }
 
             // Zba/Zbb/Zbc/Zbs (bit manipulation)
@@ -641,22 +879,22 @@ pub fn detect_riscv_extensions(data: &[u8], _endianness: Endianness) -> Vec<Exte
 
                 // SH1ADD, SH2ADD, SH3ADD (Zba)
                 if funct7 == 0x10 && matches!(funct3, 2 | 4 | 6) {
-                    extensions.insert(("Zba", ExtensionCategory::BitManip));
+                    extensions.insert(riscv::ZBA);
                 }
 
                 // Various Zbb instructions
                 if funct7 == 0x05 || funct7 == 0x20 {
-                    extensions.insert(("Zbb", ExtensionCategory::BitManip));
+                    extensions.insert(riscv::ZBB);
                 }
 
                 // CLMUL, CLMULH, CLMULR (Zbc)
                 if funct7 == 0x05 && matches!(funct3, 1 | 3 | 2) {
-                    extensions.insert(("Zbc", ExtensionCategory::BitManip));
+                    extensions.insert(riscv::ZBC);
                 }
 
                 // BCLR, BEXT, BINV, BSET (Zbs)
                 if funct7 == 0x24 || funct7 == 0x34 {
-                    extensions.insert(("Zbs", ExtensionCategory::BitManip));
+                    extensions.insert(riscv::ZBS);
                 }
             }
 
@@ -665,12 +903,12 @@ pub fn detect_riscv_extensions(data: &[u8], _endianness: Endianness) -> Vec<Exte
                 let funct7 = (word >> 25) & 0x7F;
                 // AES32 instructions
                 if funct7 == 0x18 || funct7 == 0x1A {
-                    extensions.insert(("Zknd", ExtensionCategory::Crypto));
-                    extensions.insert(("Zkne", ExtensionCategory::Crypto));
+                    extensions.insert(riscv::ZKND);
+                    extensions.insert(riscv::ZKNE);
                 }
                 // SHA256
                 if funct7 == 0x08 || funct7 == 0x0C {
-                    extensions.insert(("Zknh", ExtensionCategory::Crypto));
+                    extensions.insert(riscv::ZKNH);
                 }
             }
 
@@ -679,13 +917,10 @@ pub fn detect_riscv_extensions(data: &[u8], _endianness: Endianness) -> Vec<Exte
     }
 
     if has_compressed {
-        extensions.insert(("C", ExtensionCategory::Compressed));
+        extensions.insert(riscv::C);
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(riscv::ALL, &extensions)
 }
 
 /// Detect MIPS extensions from instruction patterns.
@@ -704,14 +939,14 @@ pub fn detect_mips_extensions(data: &[u8], endianness: Endianness) -> Vec<Extens
 
         // MSA (MIPS SIMD Architecture)
         if opcode == 0x1E {
-            extensions.insert(("MSA", ExtensionCategory::Simd));
+            extensions.insert(mips::MSA);
         }
 
         // COP2 for MDMX
         if opcode == 0x12 {
             let rs = (word >> 21) & 0x1F;
             if rs >= 0x10 {
-                extensions.insert(("MDMX", ExtensionCategory::Simd));
+                extensions.insert(mips::MDMX);
             }
         }
 
@@ -720,15 +955,12 @@ pub fn detect_mips_extensions(data: &[u8], endianness: Endianness) -> Vec<Extens
             let funct = word & 0x3F;
             // Various DSP function codes
             if matches!(funct, 0x10..=0x17 | 0x18..=0x1F | 0x30..=0x37) {
-                extensions.insert(("DSP", ExtensionCategory::Simd));
+                extensions.insert(mips::DSP);
             }
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(mips::ALL, &extensions)
 }
 
 /// Detect PowerPC extensions from instruction patterns.
@@ -742,12 +974,12 @@ pub fn detect_ppc_extensions(data: &[u8], _endianness: Endianness) -> Vec<Extens
 
         // VMX/AltiVec (opcode 4)
         if opcode == 4 {
-            extensions.insert(("VMX", ExtensionCategory::Simd));
+            extensions.insert(ppc::VMX);
         }
 
         // VSX (opcode 60)
         if opcode == 60 {
-            extensions.insert(("VSX", ExtensionCategory::Simd));
+            extensions.insert(ppc::VSX);
         }
 
         // DFP (opcode 59, 63 with DFP sub-opcodes)
@@ -755,7 +987,7 @@ pub fn detect_ppc_extensions(data: &[u8], _endianness: Endianness) -> Vec<Extens
             let xo = (word >> 1) & 0x3FF;
             // DFP specific XO values
             if matches!(xo, 2 | 34 | 66 | 98 | 130 | 162 | 194 | 226 | 258) {
-                extensions.insert(("DFP", ExtensionCategory::FloatingPoint));
+                extensions.insert(ppc::DFP);
             }
         }
 
@@ -763,7 +995,7 @@ pub fn detect_ppc_extensions(data: &[u8], _endianness: Endianness) -> Vec<Extens
         if opcode == 4 {
             let va = word & 0x3F;
             if matches!(va, 0x28 | 0x29 | 0x2A | 0x2B) {
-                extensions.insert(("Crypto", ExtensionCategory::Crypto));
+                extensions.insert(ppc::CRYPTO);
             }
         }
 
@@ -771,15 +1003,12 @@ pub fn detect_ppc_extensions(data: &[u8], _endianness: Endianness) -> Vec<Extens
         if opcode == 59 {
             let xo = (word >> 1) & 0x3FF;
             if xo >= 0x10 && xo <= 0x1F {
-                extensions.insert(("MMA", ExtensionCategory::MachineLearning));
+                extensions.insert(ppc::MMA);
             }
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(ppc::ALL, &extensions)
 }
 
 /// Detect s390x extensions from instruction patterns.
@@ -804,13 +1033,13 @@ pub fn detect_s390x_extensions(data: &[u8]) -> Vec<Extension> {
 
         // Vector instructions (E7 prefix)
         if first == 0xE7 && len >= 6 {
-            extensions.insert(("VX", ExtensionCategory::Simd));
+            extensions.insert(s390x::VX);
 
             // Check for VXE specific opcodes
             if i + 6 <= data.len() {
                 let op2 = data[i + 5];
                 if matches!(op2, 0x85 | 0x86 | 0x87) {
-                    extensions.insert(("VXE", ExtensionCategory::Simd));
+                    extensions.insert(s390x::VXE);
                 }
             }
         }
@@ -821,23 +1050,20 @@ pub fn detect_s390x_extensions(data: &[u8]) -> Vec<Extension> {
                 let op2 = data[i + 3];
                 // KMAC, KM, KMC, etc.
                 if matches!(op2, 0x2A | 0x2B | 0x2C | 0x2D | 0x2E | 0x2F) {
-                    extensions.insert(("MSA", ExtensionCategory::Crypto));
+                    extensions.insert(s390x::MSA);
                 }
             }
         }
 
         // NNPA (E6 prefix with specific opcodes)
         if first == 0xE6 && len >= 6 {
-            extensions.insert(("NNPA", ExtensionCategory::MachineLearning));
+            extensions.insert(s390x::NNPA);
         }
 
         i += len;
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(s390x::ALL, &extensions)
 }
 
 /// Detect Alpha extensions from instruction patterns.
@@ -859,7 +1085,7 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
         // BWX - Byte/Word extensions (LDBU, LDWU, STB, STW, SEXTB, SEXTW)
         // LDBU: 0x0A, LDWU: 0x0C, STB: 0x0E, STW: 0x0D
         if matches!(opcode, 0x0A | 0x0C | 0x0D | 0x0E) {
-            extensions.insert(("BWX", ExtensionCategory::Other));
+            extensions.insert(alpha::BWX);
         }
 
         // BWX via operate format (opcode 0x1C)
@@ -867,7 +1093,7 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
             let function = (word >> 5) & 0x7F;
             // SEXTB: 0x00, SEXTW: 0x01
             if function == 0x00 || function == 0x01 {
-                extensions.insert(("BWX", ExtensionCategory::Other));
+                extensions.insert(alpha::BWX);
             }
         }
 
@@ -877,11 +1103,11 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
             let function = (word >> 5) & 0x7FF;
             // SQRT functions: 0x02B, 0x00B, 0x02A, 0x00A
             if matches!(function & 0x3F, 0x0A | 0x0B | 0x2A | 0x2B) {
-                extensions.insert(("FIX", ExtensionCategory::FloatingPoint));
+                extensions.insert(alpha::FIX);
             }
             // FTOIS, FTOIT, ITOFS, ITOFT
             if matches!(function & 0x3F, 0x04 | 0x24 | 0x14 | 0x34) {
-                extensions.insert(("FIX", ExtensionCategory::FloatingPoint));
+                extensions.insert(alpha::FIX);
             }
         }
 
@@ -891,7 +1117,7 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
             let function = (word >> 5) & 0x7F;
             // CTPOP: 0x30, CTLZ: 0x32, CTTZ: 0x33
             if matches!(function, 0x30 | 0x32 | 0x33) {
-                extensions.insert(("CIX", ExtensionCategory::BitManip));
+                extensions.insert(alpha::CIX);
             }
         }
 
@@ -916,7 +1142,7 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
                     | 0x78
                     | 0x79
             ) {
-                extensions.insert(("MVI", ExtensionCategory::Simd));
+                extensions.insert(alpha::MVI);
             }
         }
 
@@ -927,15 +1153,12 @@ pub fn detect_alpha_extensions(data: &[u8]) -> Vec<Extension> {
             // GENTRAP, BPT, etc. are standard
             if palcode > 0x80 {
                 // Higher PALcodes often indicate newer features
-                extensions.insert(("PALcode", ExtensionCategory::System));
+                extensions.insert(alpha::PALCODE);
             }
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(alpha::ALL, &extensions)
 }
 
 /// Detect LoongArch extensions from instruction patterns.
@@ -963,14 +1186,14 @@ pub fn detect_loongarch_extensions(data: &[u8]) -> Vec<Extension> {
             let sub = (word >> 22) & 0xF;
             // LSX vector operations
             if sub >= 0x0 && sub <= 0xF {
-                extensions.insert(("LSX", ExtensionCategory::Simd));
+                extensions.insert(loongarch::LSX);
             }
         }
 
         // LSX loads/stores: VLD, VST patterns
         // VLD: 0x0A8xxxxx, VST: 0x0ACxxxxx
         if (word & 0xFFC00000) == 0x2C000000 || (word & 0xFFC00000) == 0x2C400000 {
-            extensions.insert(("LSX", ExtensionCategory::Simd));
+            extensions.insert(loongarch::LSX);
         }
 
         // LASX instructions (256-bit SIMD)
@@ -979,20 +1202,20 @@ pub fn detect_loongarch_extensions(data: &[u8]) -> Vec<Extension> {
         if major == 0x0F {
             let sub = (word >> 22) & 0xF;
             if sub >= 0x0 && sub <= 0xF {
-                extensions.insert(("LASX", ExtensionCategory::Simd));
+                extensions.insert(loongarch::LASX);
             }
         }
 
         // LASX loads/stores: XVLD, XVST
         if (word & 0xFFC00000) == 0x2C800000 || (word & 0xFFC00000) == 0x2CC00000 {
-            extensions.insert(("LASX", ExtensionCategory::Simd));
+            extensions.insert(loongarch::LASX);
         }
 
         // LVZ - Virtualization instructions
         // HVCL, ERTN, etc. in privileged opcodes
         if (word & 0xFFFF8000) == 0x002B0000 {
             // HVCL (hypervisor call)
-            extensions.insert(("LVZ", ExtensionCategory::Virtualization));
+            extensions.insert(loongarch::LVZ);
         }
 
         // Crypto instructions
@@ -1002,11 +1225,11 @@ pub fn detect_loongarch_extensions(data: &[u8]) -> Vec<Extension> {
             let function = (word >> 15) & 0x7F;
             // AES functions
             if matches!(function, 0x74 | 0x75 | 0x76 | 0x77) {
-                extensions.insert(("Crypto", ExtensionCategory::Crypto));
+                extensions.insert(loongarch::CRYPTO);
             }
             // SM3/SM4 functions
             if matches!(function, 0x78 | 0x79 | 0x7A | 0x7B) {
-                extensions.insert(("Crypto", ExtensionCategory::Crypto));
+                extensions.insert(loongarch::CRYPTO);
             }
         }
 
@@ -1015,26 +1238,23 @@ pub fn detect_loongarch_extensions(data: &[u8]) -> Vec<Extension> {
         // STLE, LDLE for x86 segment handling
         if (word & 0xFFFF8000) == 0x00380000 {
             // LBT-specific instructions
-            extensions.insert(("LBT", ExtensionCategory::Other));
+            extensions.insert(loongarch::LBT);
         }
 
         // LAM - LoongArch AMO (atomics)
         // AM*.W, AM*.D instructions
         if (word & 0xFFF00000) == 0x38600000 {
-            extensions.insert(("LAM", ExtensionCategory::Atomic));
+            extensions.insert(loongarch::LAM);
         }
 
         // FP - Floating-point (standard but worth noting)
         // FADD.S, FADD.D, etc.
         if (word & 0xFFF00000) == 0x01008000 || (word & 0xFFF00000) == 0x01010000 {
-            extensions.insert(("FP", ExtensionCategory::FloatingPoint));
+            extensions.insert(loongarch::FP);
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(loongarch::ALL, &extensions)
 }
 
 /// Detect ARM32 extensions from instruction patterns.
@@ -1119,9 +1339,9 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
 
     // Record detected mode
     if thumb_score > arm_score && thumb_score > 10 {
-        extensions.insert(("Thumb", ExtensionCategory::Compressed));
+        extensions.insert(arm32::THUMB);
         if thumb2_seen {
-            extensions.insert(("Thumb-2", ExtensionCategory::Compressed));
+            extensions.insert(arm32::THUMB_2);
         }
     }
 
@@ -1152,31 +1372,31 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
 
                     // VFMA, VFMS, VFNMA, VFNMS - VFPv4
                     if (opc1 & 0xB) == 0xA {
-                        extensions.insert(("VFPv4", ExtensionCategory::FloatingPoint));
+                        extensions.insert(arm32::VFPV4);
                     } else {
                         // Could be VFPv2 or VFPv3
-                        extensions.insert(("VFP", ExtensionCategory::FloatingPoint));
+                        extensions.insert(arm32::VFP);
                     }
 
                     if cp_num == 11 {
-                        extensions.insert(("VFP-D32", ExtensionCategory::FloatingPoint));
+                        extensions.insert(arm32::VFP_D32);
                     }
                 }
             }
 
             // VFP load/store (VLDR, VSTR)
             if (word & 0x0E100F00) == 0x0C100A00 || (word & 0x0E100F00) == 0x0C100B00 {
-                extensions.insert(("VFP", ExtensionCategory::FloatingPoint));
+                extensions.insert(arm32::VFP);
             }
 
             // VMOV immediate (VFPv3+)
             if (word & 0x0FB00EF0) == 0x0EB00A00 {
-                extensions.insert(("VFPv3", ExtensionCategory::FloatingPoint));
+                extensions.insert(arm32::VFPV3);
             }
 
             // VCVT with fixed-point (VFPv3+)
             if (word & 0x0FBE0E50) == 0x0EBE0A40 {
-                extensions.insert(("VFPv3", ExtensionCategory::FloatingPoint));
+                extensions.insert(arm32::VFPV3);
             }
 
             // ==================== NEON Extensions ====================
@@ -1184,17 +1404,17 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
             // NEON data processing
             // Pattern: 1111001x_xxxxxxxx_xxxxxxxx_xxxxxxxx
             if (word & 0xFE000000) == 0xF2000000 {
-                extensions.insert(("NEON", ExtensionCategory::Simd));
+                extensions.insert(arm32::NEON);
             }
 
             // NEON load/store
             if (word & 0xFF100000) == 0xF4000000 {
-                extensions.insert(("NEON", ExtensionCategory::Simd));
+                extensions.insert(arm32::NEON);
             }
 
             // NEON register transfer
             if (word & 0xFF000F10) == 0xF2000010 {
-                extensions.insert(("NEON", ExtensionCategory::Simd));
+                extensions.insert(arm32::NEON);
             }
         }
 
@@ -1202,22 +1422,22 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
 
         // Saturating arithmetic (QADD, QSUB, QDADD, QDSUB)
         if (word & 0x0F900FF0) == 0x01000050 {
-            extensions.insert(("DSP", ExtensionCategory::Simd));
+            extensions.insert(arm32::DSP);
         }
 
         // Signed multiply-accumulate (SMLA<x><y>, SMLAW<y>, SMULW<y>)
         if (word & 0x0F900090) == 0x01000080 {
-            extensions.insert(("DSP", ExtensionCategory::Simd));
+            extensions.insert(arm32::DSP);
         }
 
         // SMLAL<x><y>
         if (word & 0x0F900090) == 0x01400080 {
-            extensions.insert(("DSP", ExtensionCategory::Simd));
+            extensions.insert(arm32::DSP);
         }
 
         // CLZ (Count Leading Zeros)
         if (word & 0x0FFF0FF0) == 0x016F0F10 {
-            extensions.insert(("DSP", ExtensionCategory::Simd));
+            extensions.insert(arm32::DSP);
         }
 
         // ==================== SIMD (ARMv6) Extensions ====================
@@ -1225,27 +1445,27 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
 
         // Parallel add/sub (SADD8, SSUB8, UADD8, USUB8, etc.)
         if (word & 0x0F800FF0) == 0x06100F10 {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // USAD8, USADA8
         if (word & 0x0FF00FF0) == 0x07800010 || (word & 0x0FF000F0) == 0x07800010 {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // PKHBT, PKHTB (pack halfword)
         if (word & 0x0FF00030) == 0x06800010 {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // SEL (select bytes based on GE flags)
         if (word & 0x0FF00FF0) == 0x06800FB0 {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // SSAT, USAT (saturate)
         if (word & 0x0FE00030) == 0x06A00010 || (word & 0x0FE00030) == 0x06E00010 {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // REV, REV16, REVSH (byte reverse)
@@ -1253,54 +1473,54 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
             || (word & 0x0FFF0FF0) == 0x06BF0FB0
             || (word & 0x0FFF0FF0) == 0x06FF0FB0
         {
-            extensions.insert(("SIMDv1", ExtensionCategory::Simd));
+            extensions.insert(arm32::SIMDV1);
         }
 
         // ==================== Security Extensions ====================
 
         // SMC (Secure Monitor Call) - Security Extensions
         if (word & 0x0FF000F0) == 0x01600070 {
-            extensions.insert(("Security", ExtensionCategory::Security));
+            extensions.insert(arm32::TRUSTZONE);
         }
 
         // TrustZone - ERET
         if (word & 0x0FFFFFFF) == 0x0160006E {
-            extensions.insert(("TrustZone", ExtensionCategory::Security));
+            extensions.insert(arm32::TRUSTZONE);
         }
 
         // MRS/MSR to banked registers (Security Extensions)
         if (word & 0x0FE00FFF) == 0x01000200 || (word & 0x0FE00FFF) == 0x01200200 {
-            extensions.insert(("TrustZone", ExtensionCategory::Security));
+            extensions.insert(arm32::TRUSTZONE);
         }
 
         // ==================== Virtualization Extensions ====================
 
         // HVC (Hypervisor Call)
         if (word & 0x0FF000F0) == 0x01400070 {
-            extensions.insert(("Virtualization", ExtensionCategory::Virtualization));
+            extensions.insert(arm32::VIRTUALIZATION);
         }
 
         // ERET (also used in virtualization context)
         if (word & 0x0FFFFFFF) == 0x0160006E {
-            extensions.insert(("Virtualization", ExtensionCategory::Virtualization));
+            extensions.insert(arm32::VIRTUALIZATION);
         }
 
         // ==================== Divide Instructions ====================
 
         // SDIV
         if (word & 0x0FF000F0) == 0x07100010 {
-            extensions.insert(("IDIV", ExtensionCategory::Other));
+            extensions.insert(arm32::IDIV);
         }
 
         // UDIV
         if (word & 0x0FF000F0) == 0x07300010 {
-            extensions.insert(("IDIV", ExtensionCategory::Other));
+            extensions.insert(arm32::IDIV);
         }
 
         // ==================== CRC32 (ARMv8) ====================
 
         if (word & 0x0F900FF0) == 0x01000040 {
-            extensions.insert(("CRC32", ExtensionCategory::Other));
+            extensions.insert(arm32::CRC32);
         }
 
         // ==================== Crypto Extensions (ARMv8) ====================
@@ -1308,67 +1528,67 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
         // AES instructions
         // AESE, AESD
         if (word & 0xFFFF0FF0) == 0xF3B00300 {
-            extensions.insert(("AES", ExtensionCategory::Crypto));
+            extensions.insert(arm32::AES);
         }
         // AESMC, AESIMC
         if (word & 0xFFFF0FF0) == 0xF3B00380 {
-            extensions.insert(("AES", ExtensionCategory::Crypto));
+            extensions.insert(arm32::AES);
         }
 
         // SHA1 instructions
         // SHA1C, SHA1P, SHA1M
         if (word & 0xFFF00F90) == 0xF2000C00 {
-            extensions.insert(("SHA1", ExtensionCategory::Crypto));
+            extensions.insert(arm32::SHA1);
         }
         // SHA1H
         if (word & 0xFFFF0FF0) == 0xF3B902C0 {
-            extensions.insert(("SHA1", ExtensionCategory::Crypto));
+            extensions.insert(arm32::SHA1);
         }
         // SHA1SU0, SHA1SU1
         if (word & 0xFFF00F90) == 0xF2200C00 || (word & 0xFFFF0FF0) == 0xF3BA0380 {
-            extensions.insert(("SHA1", ExtensionCategory::Crypto));
+            extensions.insert(arm32::SHA1);
         }
 
         // SHA256 instructions
         // SHA256H, SHA256H2
         if (word & 0xFFF00F90) == 0xF3000C00 {
-            extensions.insert(("SHA256", ExtensionCategory::Crypto));
+            extensions.insert(arm32::SHA256);
         }
         // SHA256SU0, SHA256SU1
         if (word & 0xFFFF0FF0) == 0xF3BA03C0 || (word & 0xFFF00F90) == 0xF3200C00 {
-            extensions.insert(("SHA256", ExtensionCategory::Crypto));
+            extensions.insert(arm32::SHA256);
         }
 
         // VMULL.P64 (polynomial multiply - part of crypto)
         if (word & 0xFFB00F90) == 0xF2A00E00 {
-            extensions.insert(("PMULL", ExtensionCategory::Crypto));
+            extensions.insert(arm32::PMULL);
         }
 
         // ==================== Miscellaneous ====================
 
         // MOVW, MOVT (ARMv6T2+)
         if (word & 0x0FF00000) == 0x03000000 || (word & 0x0FF00000) == 0x03400000 {
-            extensions.insert(("MOVW", ExtensionCategory::Other));
+            extensions.insert(arm32::MOVW);
         }
 
         // BFC, BFI (Bit Field Clear/Insert - ARMv6T2+)
         if (word & 0x0FE0007F) == 0x07C0001F || (word & 0x0FE00070) == 0x07C00010 {
-            extensions.insert(("BitField", ExtensionCategory::Other));
+            extensions.insert(arm32::BITFIELD);
         }
 
         // SBFX, UBFX (Bit Field Extract - ARMv6T2+)
         if (word & 0x0FE00070) == 0x07A00050 || (word & 0x0FE00070) == 0x07E00050 {
-            extensions.insert(("BitField", ExtensionCategory::Other));
+            extensions.insert(arm32::BITFIELD);
         }
 
         // RBIT (Reverse Bits - ARMv6T2+)
         if (word & 0x0FFF0FF0) == 0x06FF0F30 {
-            extensions.insert(("RBIT", ExtensionCategory::Other));
+            extensions.insert(arm32::RBIT);
         }
 
         // Jazelle (BXJ)
         if (word & 0x0FFFFFF0) == 0x012FFF20 {
-            extensions.insert(("Jazelle", ExtensionCategory::Other));
+            extensions.insert(arm32::JAZELLE);
         }
     }
 
@@ -1395,17 +1615,17 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
 
                 // SDIV/UDIV in Thumb-2
                 if (word & 0xFFF0F0F0) == 0xFB90F0F0 || (word & 0xFFF0F0F0) == 0xFBB0F0F0 {
-                    extensions.insert(("IDIV", ExtensionCategory::Other));
+                    extensions.insert(arm32::IDIV);
                 }
 
                 // VFP/NEON in Thumb-2
                 if (word & 0xEF000000) == 0xEF000000 || (word & 0xFF000000) == 0xFC000000 {
-                    extensions.insert(("VFP", ExtensionCategory::FloatingPoint));
+                    extensions.insert(arm32::VFP);
                 }
 
                 // MOVW/MOVT in Thumb-2
                 if (word & 0xFBF08000) == 0xF2400000 || (word & 0xFBF08000) == 0xF2C00000 {
-                    extensions.insert(("MOVW", ExtensionCategory::Other));
+                    extensions.insert(arm32::MOVW);
                 }
 
                 i += 4;
@@ -1415,12 +1635,8 @@ pub fn detect_arm32_extensions(data: &[u8], endianness: Endianness) -> Vec<Exten
         }
     }
 
-    extensions
-        .into_iter()
-        .map(|(name, cat)| Extension::new(name, cat))
-        .collect()
+    in_table_order(arm32::ALL, &extensions)
 }
-
 #[cfg(test)]
 mod tests {
     use super::*;
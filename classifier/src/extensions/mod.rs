@@ -5,15 +5,45 @@
 //! - Specific instruction patterns
 //! - ELF attributes and flags
 
+pub mod catalog;
 pub mod detector;
+pub mod stubs;
 
-use crate::types::{Endianness, Extension, ExtensionCategory, Isa};
+use crate::types::{Endianness, Extension, ExtensionCategory, ExtensionDetection, Isa, Variant};
 
+pub use catalog::{canonical_name, canonicalize, known_extensions_detailed, KnownExtension};
 pub use detector::*;
 
+/// Whether [`detect_from_code`] has a detector for `isa`; for any other
+/// ISA it always returns an empty list.
+pub fn has_code_detector(isa: Isa) -> bool {
+    matches!(
+        isa,
+        Isa::X86
+            | Isa::X86_64
+            | Isa::Arm
+            | Isa::AArch64
+            | Isa::RiscV32
+            | Isa::RiscV64
+            | Isa::RiscV128
+            | Isa::Mips
+            | Isa::Mips64
+            | Isa::Ppc
+            | Isa::Ppc64
+            | Isa::PpcVle
+            | Isa::S390
+            | Isa::S390x
+            | Isa::Alpha
+            | Isa::LoongArch32
+            | Isa::LoongArch64
+    )
+}
+
 /// Detect extensions from code analysis.
+///
+/// Names are canonical (see [`canonical_name`]).
 pub fn detect_from_code(data: &[u8], isa: Isa, endianness: Endianness) -> Vec<Extension> {
-    match isa {
+    let mut extensions = match isa {
         Isa::X86 | Isa::X86_64 => detector::detect_x86_extensions(data),
         Isa::Arm => detector::detect_arm32_extensions(data, endianness),
         Isa::AArch64 => detector::detect_aarch64_extensions(data, endianness),
@@ -26,223 +56,360 @@ pub fn detect_from_code(data: &[u8], isa: Isa, endianness: Endianness) -> Vec<Ex
         Isa::Alpha => detector::detect_alpha_extensions(data),
         Isa::LoongArch32 | Isa::LoongArch64 => detector::detect_loongarch_extensions(data),
         _ => Vec::new(),
+    };
+    canonicalize(isa, &mut extensions);
+    extensions
+}
+
+/// Detect extensions from code analysis, skipping opaque file ranges.
+///
+/// `opaque` holds `(offset, length)` ranges (see
+/// [`ClassificationMetadata::opaque_ranges`](crate::types::ClassificationMetadata::opaque_ranges));
+/// the bytes around them are scanned as separate spans and the highest
+/// confidence per extension is kept.
+pub fn detect_from_code_excluding(
+    data: &[u8],
+    isa: Isa,
+    endianness: Endianness,
+    opaque: &[(u64, u64)],
+) -> Vec<Extension> {
+    if opaque.is_empty() {
+        return detect_from_code(data, isa, endianness);
+    }
+
+    let mut ranges: Vec<(usize, usize)> = opaque
+        .iter()
+        .map(|&(offset, len)| {
+            let start = usize::try_from(offset).map_or(data.len(), |o| o.min(data.len()));
+            let len = usize::try_from(len).unwrap_or(usize::MAX);
+            (start, start.saturating_add(len).min(data.len()))
+        })
+        .collect();
+    ranges.sort_unstable();
+    ranges.push((data.len(), data.len()));
+
+    let mut found: Vec<Extension> = Vec::new();
+    let mut cursor = 0;
+    for (start, end) in ranges {
+        if start > cursor {
+            for ext in detect_from_code(&data[cursor..start], isa, endianness) {
+                match found.iter_mut().find(|e| e.name == ext.name) {
+                    Some(existing) => existing.confidence = existing.confidence.max(ext.confidence),
+                    None => found.push(ext),
+                }
+            }
+        }
+        cursor = cursor.max(end);
     }
+    found
 }
 
 /// Get all known extensions for an ISA.
+///
+/// A projection of [`known_extensions_detailed`], which also carries
+/// descriptions, introducing versions and aliases.
 pub fn known_extensions(isa: Isa) -> Vec<(&'static str, ExtensionCategory)> {
-    match isa {
-        Isa::X86 | Isa::X86_64 => vec![
-            // SIMD
+    known_extensions_detailed(isa)
+        .iter()
+        .map(|known| (known.name, known.category))
+        .collect()
+}
+
+/// Every known extension of every supported ISA, as `(isa, name,
+/// category)`, for searching across architectures ("which ISAs have AES?").
+///
+/// ISAs that share a table (x86 and x86-64, RV32 and RV64, ...) each list
+/// its entries. Names are canonical; match aliases with [`canonical_name`].
+pub fn all_known_extensions() -> impl Iterator<Item = (Isa, &'static str, ExtensionCategory)> {
+    crate::supported_isas().into_iter().flat_map(|isa| {
+        known_extensions_detailed(isa)
+            .iter()
+            .map(move |known| (isa, known.name, known.category))
+    })
+}
+
+/// A-profile features no M-profile (Cortex-M) core has. Scanning Thumb
+/// code for A32 encodings can make them look present.
+const NOT_ON_M_PROFILE: &[&str] = &[
+    "NEON",
+    "VFP-D32",
+    "Virtualization",
+    "Jazelle",
+    "XScale",
+    "AES",
+    "SHA1",
+    "SHA256",
+    "PMULL",
+    "Crypto",
+];
+
+/// Drop the code-detected extensions `variant`'s profile rules out: on ARM
+/// M-profile cores, the A-profile-only features.
+pub fn retain_profile_extensions(
+    isa: Isa,
+    variant: Option<&Variant>,
+    extensions: &mut Vec<Extension>,
+) {
+    let m_profile = isa == Isa::Arm
+        && variant.and_then(|v| v.profile.as_deref()) == Some(crate::architectures::arm::M_PROFILE);
+    if m_profile {
+        extensions.retain(|e| !NOT_ON_M_PROFILE.contains(&e.name.as_str()));
+    }
+}
+
+/// ISA, variant name (empty for any) and the extensions it guarantees.
+type BaselineRow = (
+    Isa,
+    &'static str,
+    &'static [(&'static str, ExtensionCategory)],
+);
+
+/// Extensions every binary for an ISA (or ISA variant) may assume.
+///
+/// Rows with an empty variant apply to the whole ISA. The x86-64 psABI
+/// microarchitecture levels are cumulative, so a `x86-64-v3` variant also
+/// takes the `x86-64-v2` row.
+const BASELINE_TABLE: &[BaselineRow] = &[
+    (
+        Isa::X86_64,
+        "",
+        &[
             ("MMX", ExtensionCategory::Simd),
             ("SSE", ExtensionCategory::Simd),
             ("SSE2", ExtensionCategory::Simd),
+        ],
+    ),
+    (
+        Isa::X86_64,
+        "x86-64-v2",
+        &[
             ("SSE3", ExtensionCategory::Simd),
             ("SSSE3", ExtensionCategory::Simd),
             ("SSE4.1", ExtensionCategory::Simd),
             ("SSE4.2", ExtensionCategory::Simd),
+            ("POPCNT", ExtensionCategory::BitManip),
+        ],
+    ),
+    (
+        Isa::X86_64,
+        "x86-64-v3",
+        &[
             ("AVX", ExtensionCategory::Simd),
             ("AVX2", ExtensionCategory::Simd),
-            ("AVX-512F", ExtensionCategory::Simd),
-            ("AVX-512BW", ExtensionCategory::Simd),
-            ("AVX-512VL", ExtensionCategory::Simd),
-            ("AVX-512DQ", ExtensionCategory::Simd),
-            ("AVX-512CD", ExtensionCategory::Simd),
-            ("AVX-512_VNNI", ExtensionCategory::Simd),
-            ("AVX-512_VBMI", ExtensionCategory::Simd),
-            ("AVX-512_VBMI2", ExtensionCategory::Simd),
-            ("AVX-512_FP16", ExtensionCategory::Simd),
-            ("AVX10", ExtensionCategory::Simd),
-            // Crypto
-            ("AES-NI", ExtensionCategory::Crypto),
-            ("SHA", ExtensionCategory::Crypto),
-            ("PCLMULQDQ", ExtensionCategory::Crypto),
-            ("VAES", ExtensionCategory::Crypto),
-            ("VPCLMULQDQ", ExtensionCategory::Crypto),
-            ("GFNI", ExtensionCategory::Crypto),
-            // Bit manipulation
+            ("FMA", ExtensionCategory::Simd),
             ("BMI1", ExtensionCategory::BitManip),
             ("BMI2", ExtensionCategory::BitManip),
-            ("POPCNT", ExtensionCategory::BitManip),
             ("LZCNT", ExtensionCategory::BitManip),
-            // ML
-            ("AMX-TILE", ExtensionCategory::MachineLearning),
-            ("AMX-INT8", ExtensionCategory::MachineLearning),
-            ("AMX-BF16", ExtensionCategory::MachineLearning),
-            ("AMX-FP16", ExtensionCategory::MachineLearning),
-            ("AVX-VNNI", ExtensionCategory::MachineLearning),
-            // Security
-            ("CET", ExtensionCategory::Security),
-            ("SGX", ExtensionCategory::Security),
-            // System
-            ("APX", ExtensionCategory::System),
-            ("TSX", ExtensionCategory::Transactional),
-        ],
-        Isa::AArch64 => vec![
-            // SIMD / Vector
-            ("NEON", ExtensionCategory::Simd),
-            ("SVE", ExtensionCategory::Simd),
-            ("SVE2", ExtensionCategory::Simd),
-            ("SME", ExtensionCategory::Simd),
-            ("SME2", ExtensionCategory::Simd),
-            ("DOTPROD", ExtensionCategory::Simd),
-            ("FP16", ExtensionCategory::Simd),
-            ("BF16", ExtensionCategory::Simd),
-            ("I8MM", ExtensionCategory::Simd),
-            ("RDM", ExtensionCategory::Simd),
-            ("FHM", ExtensionCategory::Simd),
-            ("FCMA", ExtensionCategory::Simd),
-            ("FRINTTS", ExtensionCategory::Simd),
-            // Crypto
-            ("AES", ExtensionCategory::Crypto),
-            ("PMULL", ExtensionCategory::Crypto),
-            ("SHA1", ExtensionCategory::Crypto),
-            ("SHA256", ExtensionCategory::Crypto),
-            ("SHA512", ExtensionCategory::Crypto),
-            ("SHA3", ExtensionCategory::Crypto),
-            ("SM3", ExtensionCategory::Crypto),
-            ("SM4", ExtensionCategory::Crypto),
-            // Atomics
-            ("LSE", ExtensionCategory::Atomic),
-            ("LSE2", ExtensionCategory::Atomic),
-            ("LRCPC", ExtensionCategory::Atomic),
-            ("LRCPC2", ExtensionCategory::Atomic),
-            // Security
-            ("PAC", ExtensionCategory::Security),
-            ("BTI", ExtensionCategory::Security),
-            ("MTE", ExtensionCategory::Security),
-            ("RNG", ExtensionCategory::Security),
-            ("SB", ExtensionCategory::Security),
-            ("SSBS", ExtensionCategory::Security),
-            ("DIT", ExtensionCategory::Security),
-            // System
-            ("DPB", ExtensionCategory::System),
-            ("DPB2", ExtensionCategory::System),
-            ("WFxT", ExtensionCategory::System),
-            // Other
-            ("CRC32", ExtensionCategory::Other),
-            ("JSCVT", ExtensionCategory::Other),
-            ("FlagM", ExtensionCategory::Other),
-            ("FlagM2", ExtensionCategory::Other),
-            ("MOPS", ExtensionCategory::Other),
-            ("HBC", ExtensionCategory::Other),
-            ("CSSC", ExtensionCategory::Other),
-            ("LS64", ExtensionCategory::Other),
-        ],
-        Isa::RiscV32 | Isa::RiscV64 | Isa::RiscV128 => vec![
-            // Standard
-            ("M", ExtensionCategory::Other),  // Multiply/Divide
-            ("A", ExtensionCategory::Atomic), // Atomic
-            ("F", ExtensionCategory::FloatingPoint), // Single-precision FP
-            ("D", ExtensionCategory::FloatingPoint), // Double-precision FP
-            ("Q", ExtensionCategory::FloatingPoint), // Quad-precision FP
-            ("C", ExtensionCategory::Compressed), // Compressed
-            ("V", ExtensionCategory::Simd),   // Vector
-            ("H", ExtensionCategory::Virtualization), // Hypervisor
-            // Zicsr, Zifencei
-            ("Zicsr", ExtensionCategory::System),
-            ("Zifencei", ExtensionCategory::System),
-            // Bit manipulation
-            ("Zba", ExtensionCategory::BitManip),
-            ("Zbb", ExtensionCategory::BitManip),
-            ("Zbc", ExtensionCategory::BitManip),
-            ("Zbs", ExtensionCategory::BitManip),
-            // Crypto
-            ("Zbkb", ExtensionCategory::Crypto),
-            ("Zbkc", ExtensionCategory::Crypto),
-            ("Zbkx", ExtensionCategory::Crypto),
-            ("Zknd", ExtensionCategory::Crypto),
-            ("Zkne", ExtensionCategory::Crypto),
-            ("Zknh", ExtensionCategory::Crypto),
-            ("Zksed", ExtensionCategory::Crypto),
-            ("Zksh", ExtensionCategory::Crypto),
-            // Other
-            ("Ztso", ExtensionCategory::Other),
-        ],
-        Isa::Ppc | Isa::Ppc64 | Isa::PpcVle => vec![
-            ("VLE", ExtensionCategory::Compressed),
-            ("VMX", ExtensionCategory::Simd), // AltiVec
-            ("VSX", ExtensionCategory::Simd),
-            ("DFP", ExtensionCategory::FloatingPoint),
-            ("MMA", ExtensionCategory::MachineLearning),
-            ("HTM", ExtensionCategory::Transactional),
-            ("Crypto", ExtensionCategory::Crypto),
         ],
-        Isa::Mips | Isa::Mips64 => vec![
-            ("MSA", ExtensionCategory::Simd),
-            ("DSP", ExtensionCategory::Simd),
-            ("DSP2", ExtensionCategory::Simd),
-            ("MDMX", ExtensionCategory::Simd),
-            ("microMIPS", ExtensionCategory::Compressed),
-            ("MIPS16e", ExtensionCategory::Compressed),
-        ],
-        Isa::S390 | Isa::S390x => vec![
-            ("VX", ExtensionCategory::Simd),
-            ("VXE", ExtensionCategory::Simd),
-            ("VXE2", ExtensionCategory::Simd),
-            ("MSA", ExtensionCategory::Crypto),
-            ("NNPA", ExtensionCategory::MachineLearning),
-            ("TX", ExtensionCategory::Transactional),
-        ],
-        Isa::Alpha => vec![
-            ("BWX", ExtensionCategory::Other),
-            ("FIX", ExtensionCategory::FloatingPoint),
-            ("CIX", ExtensionCategory::BitManip),
-            ("MVI", ExtensionCategory::Simd),
-            ("PALcode", ExtensionCategory::System),
+    ),
+    (
+        Isa::X86_64,
+        "x86-64-v4",
+        &[
+            ("AVX-512F", ExtensionCategory::Simd),
+            ("AVX-512BW", ExtensionCategory::Simd),
+            ("AVX-512CD", ExtensionCategory::Simd),
+            ("AVX-512DQ", ExtensionCategory::Simd),
+            ("AVX-512VL", ExtensionCategory::Simd),
         ],
-        Isa::LoongArch32 | Isa::LoongArch64 => vec![
-            ("LSX", ExtensionCategory::Simd),
-            ("LASX", ExtensionCategory::Simd),
-            ("LVZ", ExtensionCategory::Virtualization),
-            ("LBT", ExtensionCategory::Other),
-            ("LAM", ExtensionCategory::Atomic),
+    ),
+    (
+        Isa::AArch64,
+        "",
+        &[
             ("FP", ExtensionCategory::FloatingPoint),
-            ("Crypto", ExtensionCategory::Crypto),
-        ],
-        Isa::Arm => vec![
-            // Compressed instruction sets
-            ("Thumb", ExtensionCategory::Compressed),
-            ("Thumb-2", ExtensionCategory::Compressed),
-            // Floating-point
-            ("VFP", ExtensionCategory::FloatingPoint),
-            ("VFPv2", ExtensionCategory::FloatingPoint),
-            ("VFPv3", ExtensionCategory::FloatingPoint),
-            ("VFPv3-D32", ExtensionCategory::FloatingPoint),
-            ("VFP-D32", ExtensionCategory::FloatingPoint),
-            ("VFPv4", ExtensionCategory::FloatingPoint),
-            ("FPv4-SP", ExtensionCategory::FloatingPoint),
-            // SIMD
             ("NEON", ExtensionCategory::Simd),
-            ("DSP", ExtensionCategory::Simd),
-            ("SIMDv1", ExtensionCategory::Simd),
-            // Security
-            ("Security", ExtensionCategory::Security),
-            ("TrustZone", ExtensionCategory::Security),
-            // Virtualization
-            ("Virtualization", ExtensionCategory::Virtualization),
-            // Crypto
-            ("AES", ExtensionCategory::Crypto),
-            ("SHA1", ExtensionCategory::Crypto),
-            ("SHA256", ExtensionCategory::Crypto),
-            ("PMULL", ExtensionCategory::Crypto),
-            // Other
-            ("IDIV", ExtensionCategory::Other),
-            ("CRC32", ExtensionCategory::Other),
-            ("MOVW", ExtensionCategory::Other),
-            ("BitField", ExtensionCategory::Other),
-            ("RBIT", ExtensionCategory::Other),
-            ("Jazelle", ExtensionCategory::Other),
-            ("XScale", ExtensionCategory::Other),
         ],
-        _ => Vec::new(),
+    ),
+];
+
+/// Level number of an x86-64 psABI variant name ("x86-64-v3" -> 3).
+fn psabi_level(name: &str) -> Option<u8> {
+    let level = name
+        .get(..8)?
+        .eq_ignore_ascii_case("x86-64-v")
+        .then(|| &name[8..])?;
+    level.parse().ok()
+}
+
+/// Rows of [`BASELINE_TABLE`] that apply to `isa` under variant `name`.
+fn baseline_rows(isa: Isa, name: &str) -> impl Iterator<Item = &'static BaselineRow> + '_ {
+    let level = psabi_level(name);
+    BASELINE_TABLE
+        .iter()
+        .filter(move |(row_isa, row_variant, _)| {
+            *row_isa == isa
+                && (row_variant.is_empty()
+                    || row_variant.eq_ignore_ascii_case(name)
+                    || psabi_level(row_variant)
+                        .zip(level)
+                        .is_some_and(|(row, level)| row <= level))
+        })
+}
+
+/// Baseline extensions for `isa` under `variant`, per [`BASELINE_TABLE`].
+pub fn baseline_extensions(
+    isa: Isa,
+    variant: Option<&Variant>,
+) -> Vec<(&'static str, ExtensionCategory)> {
+    let name = variant.map_or("", |v| v.name.as_str());
+    baseline_rows(isa, name)
+        .flat_map(|(_, _, exts)| exts.iter().copied())
+        .collect()
+}
+
+/// Flag the baseline entries of `extensions` and add the ones a specific
+/// variant implies but no detector reported.
+///
+/// Whole-ISA baselines are only flagged, never added: listing SSE2 on every
+/// x86-64 binary is exactly the noise the flag exists to suppress.
+pub fn mark_baseline(
+    extensions: &mut Vec<ExtensionDetection>,
+    isa: Isa,
+    variant: Option<&Variant>,
+) {
+    let name = variant.map_or("", |v| v.name.as_str());
+    for (_, row_variant, exts) in baseline_rows(isa, name) {
+        for &(ext_name, category) in *exts {
+            match extensions.iter_mut().find(|e| e.name == ext_name) {
+                Some(existing) => existing.baseline = true,
+                None if !row_variant.is_empty() => {
+                    let mut implied = ExtensionDetection::from_variant(ext_name, category);
+                    implied.baseline = true;
+                    extensions.push(implied);
+                }
+                None => {}
+            }
+        }
     }
 }
 
+/// RVA application profiles for RV64, oldest first, with the extensions each
+/// adds to its predecessor.
+///
+/// Only instruction-set extensions are listed: the cache-block,
+/// memory-attribute, and timing guarantees a profile also mandates (Ziccif,
+/// Za64rs, Zkt, ...) leave no trace in code or header flags.
+const RISCV_PROFILE_TABLE: &[(&str, &[&str])] = &[
+    ("RVA20U64", &["M", "A", "F", "D", "C"]),
+    (
+        "RVA22U64",
+        &[
+            "Zba",
+            "Zbb",
+            "Zbs",
+            "Zicbom",
+            "Zicbop",
+            "Zicboz",
+            "Zfhmin",
+            "Zihintpause",
+        ],
+    ),
+    (
+        "RVA23U64",
+        &[
+            "V",
+            "Zvfhmin",
+            "Zvbb",
+            "Zihintntl",
+            "Zicond",
+            "Zimop",
+            "Zcmop",
+            "Zcb",
+            "Zfa",
+            "Zawrs",
+        ],
+    ),
+];
+
+/// Highest RVA profile whose mandatory extensions all appear in `extensions`.
+///
+/// `G` stands in for `IMAFD`. Profiles are defined for RV64 only, and an
+/// RV64E base rules them out entirely.
+pub fn riscv_profile(isa: Isa, extensions: &[ExtensionDetection]) -> Option<&'static str> {
+    let has = |name: &str| extensions.iter().any(|e| e.name.eq_ignore_ascii_case(name));
+    if isa != Isa::RiscV64 || has("E") {
+        return None;
+    }
+    let present = |name: &&str| has(name) || (matches!(*name, "M" | "A" | "F" | "D") && has("G"));
+
+    RISCV_PROFILE_TABLE
+        .iter()
+        .take_while(|(_, required)| required.iter().all(present))
+        .last()
+        .map(|(profile, _)| *profile)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_detect_from_code_excluding_skips_opaque_range() {
+        // vpaddd ymm0, ymm0, ymm0 (VEX-encoded) followed by plain NOPs
+        let mut data = vec![0xC5, 0xFD, 0xFE, 0xC0];
+        data.extend_from_slice(&[0x90; 60]);
+        let has_avx = |exts: &[Extension]| exts.iter().any(|e| e.name == "AVX");
+
+        assert!(has_avx(&detect_from_code(
+            &data,
+            Isa::X86_64,
+            Endianness::Little
+        )));
+        assert!(has_avx(&detect_from_code_excluding(
+            &data,
+            Isa::X86_64,
+            Endianness::Little,
+            &[(16, 16)]
+        )));
+        assert!(!has_avx(&detect_from_code_excluding(
+            &data,
+            Isa::X86_64,
+            Endianness::Little,
+            &[(0, 16)]
+        )));
+    }
+
+    #[test]
+    fn test_mark_baseline_x86_64() {
+        let mut exts = vec![
+            ExtensionDetection::from_code("AVX2", ExtensionCategory::Simd, 0.9),
+            ExtensionDetection::from_code("SSE2", ExtensionCategory::Simd, 0.9),
+        ];
+        mark_baseline(&mut exts, Isa::X86_64, None);
+        assert_eq!(exts.len(), 2);
+        assert!(!exts[0].baseline);
+        assert!(exts[1].baseline);
+
+        // Plain x86 has no SSE2 guarantee
+        let mut exts = vec![ExtensionDetection::from_code(
+            "SSE2",
+            ExtensionCategory::Simd,
+            0.9,
+        )];
+        mark_baseline(&mut exts, Isa::X86, None);
+        assert!(!exts[0].baseline);
+    }
+
+    #[test]
+    fn test_psabi_level_implies_extensions() {
+        let v3 = Variant::new("x86-64-v3");
+        let baseline = baseline_extensions(Isa::X86_64, Some(&v3));
+        for name in ["SSE2", "SSE4.2", "AVX2"] {
+            assert!(baseline.iter().any(|(n, _)| *n == name), "{name}");
+        }
+        assert!(!baseline.iter().any(|(n, _)| *n == "AVX-512F"));
+
+        // The level adds its implied set; the whole-ISA row is only flagged
+        let mut exts = Vec::new();
+        mark_baseline(&mut exts, Isa::X86_64, Some(&v3));
+        let avx2 = exts.iter().find(|e| e.name == "AVX2").unwrap();
+        assert!(avx2.baseline);
+        assert_eq!(avx2.source, crate::types::ExtensionSource::VariantImplied);
+        assert!(exts.iter().any(|e| e.name == "POPCNT"));
+        assert!(!exts.iter().any(|e| e.name == "SSE2"));
+    }
+
     #[test]
     fn test_known_extensions_x86() {
         let exts = known_extensions(Isa::X86_64);
@@ -257,6 +424,61 @@ mod tests {
         assert!(exts.iter().any(|(name, _)| *name == "PAC"));
     }
 
+    #[test]
+    fn test_riscv_profile() {
+        let exts = |names: &[&str]| -> Vec<ExtensionDetection> {
+            names
+                .iter()
+                .map(|&n| ExtensionDetection::from_code(n, ExtensionCategory::Other, 0.9))
+                .collect()
+        };
+
+        // RV64GC with the RVA22 bit-manipulation, cache-block and hint set
+        let rva22 = exts(&[
+            "G",
+            "C",
+            "Zba",
+            "Zbb",
+            "Zbs",
+            "Zicbom",
+            "Zicbop",
+            "Zicboz",
+            "Zfhmin",
+            "Zihintpause",
+        ]);
+        assert_eq!(riscv_profile(Isa::RiscV64, &rva22), Some("RVA22U64"));
+        // Profiles are RV64-only
+        assert_eq!(riscv_profile(Isa::RiscV32, &rva22), None);
+
+        // Vector alone does not skip RVA22's requirements
+        let rvv = exts(&["M", "A", "F", "D", "C", "V"]);
+        assert_eq!(riscv_profile(Isa::RiscV64, &rvv), Some("RVA20U64"));
+
+        // Missing compressed falls short of every profile
+        let no_c = exts(&["M", "A", "F", "D"]);
+        assert_eq!(riscv_profile(Isa::RiscV64, &no_c), None);
+    }
+
+    #[test]
+    fn test_all_known_extensions() {
+        let aes: Vec<Isa> = all_known_extensions()
+            .filter(|&(_, name, category)| {
+                category == ExtensionCategory::Crypto && name.starts_with("AES")
+            })
+            .map(|(isa, _, _)| isa)
+            .collect();
+        assert!(aes.contains(&Isa::X86_64));
+        assert!(aes.contains(&Isa::AArch64));
+        assert!(!aes.contains(&Isa::Avr));
+
+        // Agrees with the per-ISA lists
+        let arm: Vec<_> = all_known_extensions()
+            .filter(|&(isa, _, _)| isa == Isa::AArch64)
+            .map(|(_, name, category)| (name, category))
+            .collect();
+        assert_eq!(arm, known_extensions(Isa::AArch64));
+    }
+
     #[test]
     fn test_known_extensions_riscv() {
         let exts = known_extensions(Isa::RiscV64);
@@ -0,0 +1,387 @@
+//! Linker-generated stubs in ARM and AArch64 code.
+//!
+//! PLT entries and branch veneers are short, identical sequences the linker
+//! emits once per imported function or out-of-range call. In a small
+//! `.text` they can outnumber the code the application wrote and skew what
+//! the code scans report (a Thumb binary read as A32 because of its ARM PLT,
+//! BTI reported because every PLT entry starts with `bti c`). [`find`]
+//! locates them so scans can skip them, and counts them: a dynamically
+//! linked binary has PLT stubs, a statically linked one only veneers.
+//!
+//! Recognised sequences:
+//!
+//! - **AArch64 PLT**: `[bti c] adrp x16; ldr x17, [x16, #n]; add x16, x16, #n; br x17`
+//! - **AArch64 veneers**: `adrp x16; add x16, x16, #n; br x16` and
+//!   `ldr x16, #8; br x16; .quad`
+//! - **A32 PLT**: `add ip, pc, #n; add ip, ip, #n; ldr pc, [ip, #n]!`
+//! - **A32 veneer**: `ldr pc, [pc, #-4]; .word`
+//! - **Thumb interworking veneer**: `bx pc; nop` followed by an A32 `b` or
+//!   `ldr pc, [pc, #-4]; .word`
+//! - **Thumb-2 veneers**: `ldr.w pc, [pc, #-0]; .word` and
+//!   `movw ip, #n; movt ip, #n; bx ip`
+//!
+//! For ELF inputs the `.plt` and `.iplt` sections are skipped wholesale,
+//! PLT header included; the patterns find stubs everywhere else.
+
+use std::borrow::Cow;
+
+use crate::formats::elf::{self, class, data as elf_data};
+use crate::types::{Endianness, Isa, MetadataEntry, MetadataValue};
+
+/// Linker stubs found in an ARM or AArch64 input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkerStubs {
+    /// PLT entries
+    pub plt_stubs: usize,
+    /// Branch and interworking veneers
+    pub veneers: usize,
+    /// File ranges `(offset, length)` holding stubs, in ascending order
+    pub ranges: Vec<(u64, u64)>,
+}
+
+impl LinkerStubs {
+    /// `plt_stubs` and `veneers` metadata entries.
+    pub fn to_metadata(&self) -> Vec<MetadataEntry> {
+        vec![
+            MetadataEntry::custom(
+                "plt_stubs",
+                MetadataValue::Integer(self.plt_stubs as u64),
+                "PLT Stubs",
+            ),
+            MetadataEntry::custom(
+                "veneers",
+                MetadataValue::Integer(self.veneers as u64),
+                "Veneers",
+            ),
+        ]
+    }
+
+    /// `data` with the stub ranges cut out, borrowed when there are none.
+    pub fn strip<'d>(&self, data: &'d [u8]) -> Cow<'d, [u8]> {
+        if self.ranges.is_empty() {
+            return Cow::Borrowed(data);
+        }
+        let mut kept = Vec::with_capacity(data.len());
+        let mut cursor = 0;
+        for &(offset, len) in &self.ranges {
+            let start = usize::try_from(offset).map_or(data.len(), |o| o.min(data.len()));
+            if start > cursor {
+                kept.extend_from_slice(&data[cursor..start]);
+            }
+            let end = usize::try_from(offset.saturating_add(len)).unwrap_or(usize::MAX);
+            cursor = cursor.max(end.min(data.len()));
+        }
+        kept.extend_from_slice(&data[cursor..]);
+        Cow::Owned(kept)
+    }
+}
+
+/// What a stub pattern matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stub {
+    Plt,
+    Veneer,
+}
+
+/// Stub pattern matcher: `(data, offset, little_endian)` to the stub
+/// starting at `offset` and its length.
+type Matcher = fn(&[u8], usize, bool) -> Option<(Stub, usize)>;
+
+/// Find the linker stubs in `data`; `None` for ISAs other than ARM and
+/// AArch64.
+pub fn find(data: &[u8], isa: Isa, endianness: Endianness) -> Option<LinkerStubs> {
+    let matcher: Matcher = match isa {
+        Isa::Arm => match_arm,
+        Isa::AArch64 => match_aarch64,
+        _ => return None,
+    };
+    let le = endianness != Endianness::Big;
+    // A64 instructions are words; Thumb ones may start on any halfword
+    let step = if isa == Isa::AArch64 { 4 } else { 2 };
+
+    let mut stubs = LinkerStubs::default();
+    let mut plt = elf_plt_sections(data);
+    plt.sort_unstable();
+    for &(offset, len) in &plt {
+        // In range: plt_sections clamps to the input
+        let section = &data[offset as usize..][..len as usize];
+        let mut at = 0;
+        while at < section.len() {
+            match matcher(section, at, le) {
+                Some((_, size)) => {
+                    stubs.plt_stubs += 1;
+                    at += size;
+                }
+                None => at += step,
+            }
+        }
+    }
+
+    let mut cursor = 0;
+    for end in plt
+        .iter()
+        .map(|&(offset, _)| offset as usize)
+        .chain([data.len()])
+    {
+        let mut at = cursor;
+        while at < end {
+            match matcher(&data[..end], at, le) {
+                Some((kind, size)) => {
+                    match kind {
+                        Stub::Plt => stubs.plt_stubs += 1,
+                        Stub::Veneer => stubs.veneers += 1,
+                    }
+                    stubs.ranges.push((at as u64, size as u64));
+                    at += size;
+                }
+                None => at += step,
+            }
+        }
+        if let Some(&(offset, len)) = plt.iter().find(|&&(offset, _)| offset as usize == end) {
+            stubs.ranges.push((offset, len));
+            cursor = cursor.max((offset + len) as usize);
+        }
+    }
+    stubs.ranges.sort_unstable();
+    Some(stubs)
+}
+
+/// `.plt` and `.iplt` of an ELF input.
+fn elf_plt_sections(data: &[u8]) -> Vec<(u64, u64)> {
+    if !data.starts_with(b"\x7FELF") || data.len() < 6 {
+        return Vec::new();
+    }
+    elf::plt_sections(
+        data,
+        data[4] == class::ELFCLASS64,
+        data[5] != elf_data::ELFDATA2MSB,
+    )
+}
+
+fn word_at(data: &[u8], offset: usize, le: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(offset..offset.checked_add(4)?)?.try_into().ok()?;
+    Some(if le {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn half_at(data: &[u8], offset: usize, le: bool) -> Option<u16> {
+    let bytes: [u8; 2] = data.get(offset..offset.checked_add(2)?)?.try_into().ok()?;
+    Some(if le {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+/// Whether the words at `offset` match `patterns` as `(mask, value)` pairs.
+fn words_match(data: &[u8], offset: usize, le: bool, patterns: &[(u32, u32)]) -> bool {
+    patterns.iter().enumerate().all(|(i, &(mask, value))| {
+        word_at(data, offset + 4 * i, le).is_some_and(|word| word & mask == value)
+    })
+}
+
+/// AArch64 stub starting at `offset`, with its length.
+fn match_aarch64(data: &[u8], offset: usize, le: bool) -> Option<(Stub, usize)> {
+    const BTI_C: u32 = 0xD503_245F;
+    const ADRP_X16: (u32, u32) = (0x9F00_001F, 0x9000_0010);
+    const LDR_X17_X16: (u32, u32) = (0xFFC0_03FF, 0xF940_0211);
+    const ADD_X16_X16: (u32, u32) = (0xFFC0_03FF, 0x9100_0210);
+    const BR_X17: (u32, u32) = (u32::MAX, 0xD61F_0220);
+    const BR_X16: (u32, u32) = (u32::MAX, 0xD61F_0200);
+    const LDR_X16_LIT8: (u32, u32) = (u32::MAX, 0x5800_0050);
+
+    let bti = usize::from(word_at(data, offset, le) == Some(BTI_C)) * 4;
+    let plt = [ADRP_X16, LDR_X17_X16, ADD_X16_X16, BR_X17];
+    if words_match(data, offset + bti, le, &plt) {
+        return Some((Stub::Plt, bti + 16));
+    }
+    if words_match(data, offset, le, &[ADRP_X16, ADD_X16_X16, BR_X16]) {
+        return Some((Stub::Veneer, 12));
+    }
+    if words_match(data, offset, le, &[LDR_X16_LIT8, BR_X16]) && offset + 16 <= data.len() {
+        return Some((Stub::Veneer, 16));
+    }
+    None
+}
+
+/// ARM (A32 or Thumb) stub starting at `offset`, with its length.
+fn match_arm(data: &[u8], offset: usize, le: bool) -> Option<(Stub, usize)> {
+    const ADD_IP_PC: (u32, u32) = (0xFFFF_FF00, 0xE28F_C600);
+    const ADD_IP_IP: (u32, u32) = (0xFFFF_FF00, 0xE28C_CA00);
+    const LDR_PC_IP: (u32, u32) = (0xFFFF_F000, 0xE5BC_F000);
+    const LDR_PC_PC4: (u32, u32) = (u32::MAX, 0xE51F_F004);
+    const B: (u32, u32) = (0xFF00_0000, 0xEA00_0000);
+
+    // The 32-bit forms are word aligned
+    if offset % 4 == 0 {
+        if words_match(data, offset, le, &[ADD_IP_PC, ADD_IP_IP, LDR_PC_IP]) {
+            return Some((Stub::Plt, 12));
+        }
+        if words_match(data, offset, le, &[LDR_PC_PC4]) && offset + 8 <= data.len() {
+            return Some((Stub::Veneer, 8));
+        }
+        // bx pc; nop, then A32 from the next word
+        let bx_pc = half_at(data, offset, le) == Some(0x4778)
+            && matches!(half_at(data, offset + 2, le), Some(0x46C0 | 0xBF00));
+        if bx_pc {
+            if words_match(data, offset + 4, le, &[B]) {
+                return Some((Stub::Veneer, 8));
+            }
+            if words_match(data, offset + 4, le, &[LDR_PC_PC4]) && offset + 12 <= data.len() {
+                return Some((Stub::Veneer, 12));
+            }
+        }
+    }
+
+    let half = |i: usize| half_at(data, offset + 2 * i, le);
+    // ldr.w pc, [pc, #-0]; .word
+    if half(0) == Some(0xF85F) && half(1) == Some(0xF000) && offset + 8 <= data.len() {
+        return Some((Stub::Veneer, 8));
+    }
+    // movw ip, #lo; movt ip, #hi; bx ip
+    let mov_ip = |i: usize, op: u16| {
+        half(i).is_some_and(|h| h & 0xFBF0 == op)
+            && half(i + 1).is_some_and(|h| h & 0x8F00 == 0x0C00)
+    };
+    if mov_ip(0, 0xF240) && mov_ip(2, 0xF2C0) && half(4) == Some(0x4760) {
+        return Some((Stub::Veneer, 10));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architectures::arm;
+
+    /// A32 PLT entry for slot `n`.
+    fn arm_plt_stub(n: u32) -> [u32; 3] {
+        [0xE28F_C600, 0xE28C_CA08, 0xE5BC_F000 | ((n * 4) & 0xFFF)]
+    }
+
+    /// Ten Thumb-2 functions of a Cortex-M class core.
+    fn thumb_functions() -> Vec<u8> {
+        let mut code = Vec::new();
+        for n in 0..10u16 {
+            for hw in [
+                0xB5F0,           // push {r4-r7, lr}
+                0xB120 | (n & 3), // cbz rN
+                0x2805,           // cmp r0, #5
+                0xBF0C,           // ite eq
+                0x2001,           // moveq r0, #1
+                0x2000,           // movne r0, #0
+                0xF8D0,
+                0x1004, // ldr.w r1, [r0, #4]
+                0xB909, // cbnz r1
+                0xBDF0, // pop {r4-r7, pc}
+            ] {
+                code.extend(hw.to_le_bytes());
+            }
+        }
+        code
+    }
+
+    /// Sorted extension names found in `data` outside `skipped`.
+    fn extension_names(data: &[u8], isa: Isa, skipped: &[(u64, u64)]) -> Vec<String> {
+        let mut names: Vec<String> =
+            super::super::detect_from_code_excluding(data, isa, Endianness::Little, skipped)
+                .into_iter()
+                .map(|e| e.name)
+                .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_arm_plt_stubs_excluded_from_thumb_stats() {
+        let mut text: Vec<u8> = (0..40)
+            .flat_map(arm_plt_stub)
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        text.extend(thumb_functions());
+
+        let stubs = find(&text, Isa::Arm, Endianness::Little).unwrap();
+        assert_eq!((stubs.plt_stubs, stubs.veneers), (40, 0));
+        assert_eq!(stubs.ranges.len(), 40);
+        assert_eq!(stubs.strip(&text), thumb_functions());
+        assert!(arm::is_thumb_only(&stubs.strip(&text)));
+        assert_eq!(
+            extension_names(&text, Isa::Arm, &stubs.ranges),
+            extension_names(&thumb_functions(), Isa::Arm, &[])
+        );
+    }
+
+    #[test]
+    fn test_aarch64_bti_plt_stubs_excluded_from_extensions() {
+        // bti c; adrp x16; ldr x17, [x16, #n]; add x16, x16, #n; br x17
+        let mut text: Vec<u8> = (0..40u32)
+            .flat_map(|n| {
+                [
+                    0xD503_245F,
+                    0x9000_0110,
+                    0xF940_0211 | (n << 10),
+                    0x9100_0210 | (n << 10),
+                    0xD61F_0220,
+                ]
+            })
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        let functions: Vec<u8> = (0..10)
+            .flat_map(|_| {
+                [
+                    0xA9BF_7BFDu32, // stp x29, x30, [sp, #-16]!
+                    0x9100_03FD,    // mov x29, sp
+                    0x9400_0010,    // bl
+                    0xA8C1_7BFD,    // ldp x29, x30, [sp], #16
+                    0xD65F_03C0,    // ret
+                ]
+            })
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        text.extend(&functions);
+
+        let stubs = find(&text, Isa::AArch64, Endianness::Little).unwrap();
+        assert_eq!((stubs.plt_stubs, stubs.veneers), (40, 0));
+        assert!(extension_names(&text, Isa::AArch64, &[]).contains(&"BTI".to_string()));
+        assert_eq!(
+            extension_names(&text, Isa::AArch64, &stubs.ranges),
+            extension_names(&functions, Isa::AArch64, &[])
+        );
+    }
+
+    #[test]
+    fn test_veneers() {
+        let mut code = Vec::new();
+        // bx pc; nop; b target
+        code.extend([0x78, 0x47, 0xC0, 0x46]);
+        code.extend(0xEA00_0010u32.to_le_bytes());
+        // ldr.w pc, [pc, #-0]; .word target
+        code.extend([0x5F, 0xF8, 0x00, 0xF0, 0x01, 0x10, 0x00, 0x08]);
+        // movw ip, #0x1001; movt ip, #0x0800; bx ip
+        code.extend([0x41, 0xF2, 0x01, 0x0C, 0xC0, 0xF6, 0x00, 0x0C, 0x60, 0x47]);
+        let stubs = find(&code, Isa::Arm, Endianness::Little).unwrap();
+        assert_eq!((stubs.plt_stubs, stubs.veneers), (0, 3));
+        assert_eq!(stubs.ranges, [(0, 8), (8, 8), (16, 10)]);
+
+        let aarch64: Vec<u8> = [
+            0xD503_245Fu32, // bti c
+            0x9000_0110,    // adrp x16, ...
+            0xF940_0A11,    // ldr x17, [x16, #16]
+            0x9100_4210,    // add x16, x16, #16
+            0xD61F_0220,    // br x17
+            0xB000_0010,    // adrp x16, ...
+            0x9100_0210,    // add x16, x16, #0
+            0xD61F_0200,    // br x16
+        ]
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .collect();
+        let stubs = find(&aarch64, Isa::AArch64, Endianness::Little).unwrap();
+        assert_eq!((stubs.plt_stubs, stubs.veneers), (1, 1));
+        assert_eq!(stubs.ranges, [(0, 20), (20, 12)]);
+        assert!(find(&aarch64, Isa::X86_64, Endianness::Little).is_none());
+    }
+}
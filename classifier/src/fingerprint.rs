@@ -0,0 +1,545 @@
+//! Structural "same-binary" fingerprints.
+//!
+//! Exact content hashes tell byte-identical files apart; they say nothing
+//! about whether two files are the same program rebuilt. A structural
+//! fingerprint is built from what the classifier already extracted, plus
+//! one bounded pass over the code at the entry point:
+//!
+//! - ISA, register width and container format
+//! - section names with power-of-two size buckets, and the section count
+//! - the detected extension set
+//! - a bottom-k sketch of byte 4-grams from the first [`PROLOGUE_WINDOW`]
+//!   bytes of code at the entry point
+//! - a histogram of printable string lengths in the first [`STRING_SCAN`]
+//!   bytes
+//!
+//! The features are folded into a 128-bit SimHash. Rebuilding a program
+//! with the same toolchain usually yields the same value, or one a few
+//! bits away ([`Fingerprint::distance`]); different programs rarely land
+//! close. [`Fingerprint::similarity`] compares the feature vectors
+//! themselves and is the better measure for ranking.
+//!
+//! This is a triage aid for corpus deduplication and drift tracking, **not
+//! a security mechanism**: the features are trivially steerable and the
+//! hash is not collision resistant. Never use it to decide that a file is
+//! trustworthy.
+//!
+//! Section features need [`ClassifierOptions::collect_sections`]
+//! (also used to locate the entry point); without it they are empty and the
+//! sketch starts at the first byte of the file.
+//!
+//! [`ClassifierOptions::collect_sections`]: crate::types::ClassifierOptions::collect_sections
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{DetectionPayload, MetadataKey, MetadataValue};
+
+/// Bytes of code after the entry point sampled for n-grams.
+pub const PROLOGUE_WINDOW: usize = 4096;
+
+/// N-gram length, in bytes, for the prologue sketch.
+pub const NGRAM: usize = 4;
+
+/// Number of n-gram hashes kept in the prologue sketch.
+pub const SKETCH_SIZE: usize = 64;
+
+/// Bytes scanned for printable strings.
+pub const STRING_SCAN: usize = 1024 * 1024;
+
+/// Shortest printable run counted as a string.
+const MIN_STRING: usize = 4;
+
+/// String length buckets: 4-7, 8-15, 16-31, 32-63, 64-127, 128 and longer.
+pub const STRING_BUCKETS: usize = 6;
+
+/// Weights of the feature groups in [`Fingerprint::similarity`].
+const WEIGHT_FORMAT: f64 = 0.05;
+const WEIGHT_SECTIONS: f64 = 0.15;
+const WEIGHT_EXTENSIONS: f64 = 0.1;
+const WEIGHT_PROLOGUE: f64 = 0.5;
+const WEIGHT_STRINGS: f64 = 0.2;
+
+/// The features a [`Fingerprint`] is built from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FingerprintFeatures {
+    /// ISA name
+    pub isa: String,
+    /// Register width in bits
+    pub bitwidth: u8,
+    /// Container format name
+    pub format: String,
+    /// Section count, as a power-of-two bucket
+    pub section_count_bucket: u8,
+    /// Sorted "name:size bucket" tokens, one per section
+    pub sections: Vec<String>,
+    /// Sorted extension names
+    pub extensions: Vec<String>,
+    /// The smallest [`SKETCH_SIZE`] distinct n-gram hashes, ascending
+    pub prologue_sketch: Vec<u64>,
+    /// Printable string counts per length bucket
+    pub string_lengths: Vec<u32>,
+}
+
+/// A structural fingerprint: a 128-bit SimHash and the features behind it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// SimHash of the features, serialized as 32 hex digits
+    #[serde(with = "hex_u128")]
+    pub value: u128,
+    /// The feature vector the value was computed from
+    pub features: FingerprintFeatures,
+}
+
+impl Fingerprint {
+    /// Compute the fingerprint of `data`, already analyzed into `payload`.
+    pub fn compute(payload: &DetectionPayload, data: &[u8]) -> Self {
+        let features = FingerprintFeatures::extract(payload, data);
+        Self {
+            value: features.simhash(),
+            features,
+        }
+    }
+
+    /// The value as 32 lowercase hex digits.
+    pub fn to_hex(&self) -> String {
+        format!("{:032x}", self.value)
+    }
+
+    /// Number of differing bits between the two values.
+    pub fn distance(&self, other: &Self) -> u32 {
+        (self.value ^ other.value).count_ones()
+    }
+
+    /// Similarity of the feature vectors, from 0.0 (unrelated) to 1.0.
+    ///
+    /// Code for a different ISA or register width is never the same
+    /// program and scores 0.0. Otherwise each feature group is compared on
+    /// its own (Jaccard for sets, histogram overlap for string lengths) and
+    /// the groups are weighted, skipping those empty on both sides.
+    pub fn similarity(&self, other: &Self) -> f64 {
+        let (a, b) = (&self.features, &other.features);
+        if a.isa != b.isa || a.bitwidth != b.bitwidth {
+            return 0.0;
+        }
+
+        let mut parts = vec![(WEIGHT_FORMAT, f64::from(u8::from(a.format == b.format)))];
+        if !a.sections.is_empty() || !b.sections.is_empty() {
+            parts.push((WEIGHT_SECTIONS, jaccard(&a.sections, &b.sections)));
+        }
+        if !a.extensions.is_empty() || !b.extensions.is_empty() {
+            parts.push((WEIGHT_EXTENSIONS, jaccard(&a.extensions, &b.extensions)));
+        }
+        if !a.prologue_sketch.is_empty() || !b.prologue_sketch.is_empty() {
+            parts.push((
+                WEIGHT_PROLOGUE,
+                sketch_similarity(&a.prologue_sketch, &b.prologue_sketch),
+            ));
+        }
+        if let Some(overlap) = histogram_overlap(&a.string_lengths, &b.string_lengths) {
+            parts.push((WEIGHT_STRINGS, overlap));
+        }
+
+        let total: f64 = parts.iter().map(|(w, _)| w).sum();
+        parts.iter().map(|(w, s)| w * s).sum::<f64>() / total
+    }
+}
+
+impl FingerprintFeatures {
+    /// Collect the features of `data`, already analyzed into `payload`.
+    pub fn extract(payload: &DetectionPayload, data: &[u8]) -> Self {
+        let section_count = if payload.sections.is_empty() {
+            payload
+                .metadata
+                .iter()
+                .find_map(|e| match (&e.key, &e.value) {
+                    (MetadataKey::SectionCount, MetadataValue::Integer(n)) => Some(*n),
+                    _ => None,
+                })
+        } else {
+            Some(payload.sections.len() as u64)
+        };
+
+        let mut sections: Vec<String> = payload
+            .sections
+            .iter()
+            .map(|s| format!("{}:{}", s.name, bucket(s.size)))
+            .collect();
+        sections.sort();
+        sections.dedup();
+
+        let mut extensions: Vec<String> =
+            payload.extensions.iter().map(|e| e.name.clone()).collect();
+        extensions.sort();
+        extensions.dedup();
+
+        Self {
+            isa: payload.primary.isa.name().to_string(),
+            bitwidth: payload.primary.bitwidth,
+            format: payload.format.format.to_string(),
+            section_count_bucket: section_count.map_or(0, bucket),
+            sections,
+            extensions,
+            prologue_sketch: prologue_sketch(code_window(payload, data)),
+            string_lengths: string_lengths(&data[..data.len().min(STRING_SCAN)]),
+        }
+    }
+
+    /// Fold the features into a 128-bit SimHash.
+    fn simhash(&self) -> u128 {
+        let mut votes = [0i64; 128];
+        let mut add = |token: &str, weight: i64| {
+            let hash = hash128(token.as_bytes());
+            for (bit, vote) in votes.iter_mut().enumerate() {
+                *vote += if (hash >> bit) & 1 == 1 {
+                    weight
+                } else {
+                    -weight
+                };
+            }
+        };
+
+        add(&format!("isa:{}:{}", self.isa, self.bitwidth), 16);
+        add(&format!("format:{}", self.format), 8);
+        add(&format!("sections:{}", self.section_count_bucket), 4);
+        for section in &self.sections {
+            add(&format!("section:{section}"), 2);
+        }
+        for extension in &self.extensions {
+            add(&format!("ext:{extension}"), 2);
+        }
+        for hash in &self.prologue_sketch {
+            add(&format!("ngram:{hash:016x}"), 1);
+        }
+        for (i, &count) in self.string_lengths.iter().enumerate() {
+            add(&format!("strings:{i}:{}", bucket(u64::from(count))), 4);
+        }
+
+        votes
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v > 0)
+            .fold(0u128, |value, (bit, _)| value | 1 << bit)
+    }
+}
+
+/// Power-of-two bucket of `n`: 0 for 0, otherwise `floor(log2(n)) + 1`.
+fn bucket(n: u64) -> u8 {
+    (u64::BITS - n.leading_zeros()) as u8
+}
+
+/// The code to sample: from the entry point to the end of its executable
+/// segment or section, capped at [`PROLOGUE_WINDOW`] bytes.
+fn code_window<'a>(payload: &DetectionPayload, data: &'a [u8]) -> &'a [u8] {
+    let entry = payload
+        .metadata
+        .iter()
+        .find_map(|e| match (&e.key, &e.value) {
+            (MetadataKey::EntryPoint, MetadataValue::Address(a)) => Some(*a),
+            _ => None,
+        });
+    let from_segment = entry.and_then(|entry| {
+        payload
+            .segments
+            .iter()
+            .filter(|s| s.flags_execute && s.file_size > 0)
+            .filter(|s| entry >= s.vaddr && entry - s.vaddr < s.file_size)
+            // A segment whose file range overflows is malformed; skip it
+            .find_map(|s| {
+                Some((
+                    s.offset.checked_add(entry - s.vaddr)?,
+                    s.offset.checked_add(s.file_size)?,
+                ))
+            })
+    });
+    let from_section = || {
+        payload
+            .sections
+            .iter()
+            .filter(|s| s.flags_execinstr && s.size > 0)
+            .find_map(|s| Some((s.offset, s.offset.checked_add(s.size)?)))
+    };
+
+    let (start, end) = from_segment
+        .or_else(from_section)
+        .and_then(|(start, end)| Some((usize::try_from(start).ok()?, usize::try_from(end).ok()?)))
+        .unwrap_or((0, data.len()));
+    let end = end
+        .min(data.len())
+        .min(start.saturating_add(PROLOGUE_WINDOW));
+    data.get(start..end).unwrap_or(&[])
+}
+
+/// The [`SKETCH_SIZE`] smallest distinct hashes of the [`NGRAM`]-byte
+/// windows of `code`.
+fn prologue_sketch(code: &[u8]) -> Vec<u64> {
+    let mut hashes: Vec<u64> = code.windows(NGRAM).map(fnv1a).collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes.truncate(SKETCH_SIZE);
+    hashes
+}
+
+/// Printable ASCII runs of at least [`MIN_STRING`] bytes, counted per
+/// [`STRING_BUCKETS`] length bucket.
+fn string_lengths(data: &[u8]) -> Vec<u32> {
+    let mut counts = vec![0u32; STRING_BUCKETS];
+    let mut count = |len: usize| {
+        if len >= MIN_STRING {
+            let slot = (usize::BITS - 1 - len.leading_zeros()) as usize - 2;
+            counts[slot.min(STRING_BUCKETS - 1)] += 1;
+        }
+    };
+    let mut run = 0;
+    for &b in data {
+        if (0x20..0x7F).contains(&b) {
+            run += 1;
+        } else {
+            count(run);
+            run = 0;
+        }
+    }
+    count(run);
+    counts
+}
+
+/// Jaccard index of two sorted, deduplicated lists.
+fn jaccard(a: &[String], b: &[String]) -> f64 {
+    let shared = a.iter().filter(|x| b.binary_search(x).is_ok()).count();
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        1.0
+    } else {
+        shared as f64 / union as f64
+    }
+}
+
+/// Bottom-k estimate of the Jaccard index of the two n-gram sets: the share
+/// of the union's smallest hashes that both sketches hold.
+fn sketch_similarity(a: &[u64], b: &[u64]) -> f64 {
+    let mut union: Vec<u64> = a.iter().chain(b).copied().collect();
+    union.sort_unstable();
+    union.dedup();
+    union.truncate(SKETCH_SIZE.min(a.len().max(b.len())));
+    if union.is_empty() {
+        return 1.0;
+    }
+    let shared = union
+        .iter()
+        .filter(|h| a.binary_search(h).is_ok() && b.binary_search(h).is_ok())
+        .count();
+    shared as f64 / union.len() as f64
+}
+
+/// Overlap of two histograms after normalizing each to unit mass, or
+/// `None` when both are empty.
+fn histogram_overlap(a: &[u32], b: &[u32]) -> Option<f64> {
+    let (ta, tb) = (
+        a.iter().map(|&n| u64::from(n)).sum::<u64>(),
+        b.iter().map(|&n| u64::from(n)).sum::<u64>(),
+    );
+    match (ta, tb) {
+        (0, 0) => None,
+        (0, _) | (_, 0) => Some(0.0),
+        _ => Some(
+            a.iter()
+                .zip(b)
+                .map(|(&x, &y)| (f64::from(x) / ta as f64).min(f64::from(y) / tb as f64))
+                .sum(),
+        ),
+    }
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 128 well-mixed bits for a SimHash token.
+fn hash128(bytes: &[u8]) -> u128 {
+    let h = fnv1a(bytes);
+    (u128::from(mix(h)) << 64) | u128::from(mix(h ^ 0x9e37_79b9_7f4a_7c15))
+}
+
+/// The SplitMix64 finalizer.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Serialize the 128-bit value as a hex string, which JSON readers without
+/// 128-bit integers can still carry.
+mod hex_u128 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:032x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        u128::from_str_radix(&text, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{detect_payload, ClassifierOptions};
+
+    /// Deterministic x86-64 "program": functions with varied bodies, each
+    /// `push rbp; mov rbp, rsp; ...; pop rbp; ret`, and a string table.
+    /// `build` perturbs a few immediates, as a rebuild shifting addresses
+    /// would.
+    fn x86_64_program(build: u32) -> Vec<u8> {
+        let mut seed = 0x1234_5678u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            seed >> 16
+        };
+        let mut code = Vec::new();
+        for function in 0..96u32 {
+            code.extend_from_slice(&[0x55, 0x48, 0x89, 0xE5]);
+            for _ in 0..(next() % 4 + 2) {
+                match next() % 4 {
+                    // mov eax, imm32
+                    0 => {
+                        code.push(0xB8);
+                        code.extend_from_slice(&(next() & 0xFFFF).to_le_bytes());
+                    }
+                    // add rax, rbx / mov rdi, rax
+                    1 => code.extend_from_slice(&[0x48, 0x01, 0xD8]),
+                    2 => code.extend_from_slice(&[0x48, 0x89, 0xC7]),
+                    // call rel32, displacement moves between builds
+                    _ => {
+                        code.push(0xE8);
+                        let disp = if function % 16 == 0 {
+                            next() + build * 0x40
+                        } else {
+                            next()
+                        };
+                        code.extend_from_slice(&disp.to_le_bytes());
+                    }
+                }
+            }
+            code.extend_from_slice(&[0x5D, 0xC3]);
+        }
+        for i in 0..40 {
+            code.extend_from_slice(format!("message number {i}\0").as_bytes());
+        }
+        code
+    }
+
+    /// A big-endian PowerPC sequence: mflr, stwu, lwz, blr with varying
+    /// offsets.
+    fn ppc_program() -> Vec<u8> {
+        let mut code = Vec::new();
+        for i in 0..512u32 {
+            for word in [
+                0x7C08_02A6,
+                0x9421_FFF0 | (i & 0xF),
+                0x8062_0000 | (i * 4),
+                0x4E80_0020,
+            ] {
+                code.extend_from_slice(&u32::to_be_bytes(word));
+            }
+        }
+        code
+    }
+
+    fn fingerprint(data: &[u8]) -> Fingerprint {
+        let payload = detect_payload(data, &ClassifierOptions::new()).unwrap();
+        payload.structural_fingerprint(data)
+    }
+
+    #[test]
+    fn test_rebuilds_are_similar() {
+        let a = fingerprint(&x86_64_program(0));
+        let b = fingerprint(&x86_64_program(1));
+        assert_eq!(a.features.isa, b.features.isa);
+        assert_ne!(a.features.prologue_sketch, Vec::<u64>::new());
+
+        let similarity = a.similarity(&b);
+        assert!(similarity > 0.8, "similarity {similarity}");
+        assert!(a.distance(&b) < 16, "distance {}", a.distance(&b));
+        assert!((a.similarity(&a) - 1.0).abs() < f64::EPSILON);
+        assert_eq!(a.distance(&a), 0);
+    }
+
+    #[test]
+    fn test_unrelated_isas_score_near_zero() {
+        let x86 = fingerprint(&x86_64_program(0));
+        let ppc = fingerprint(&ppc_program());
+        assert_ne!(x86.features.isa, ppc.features.isa);
+        assert!(x86.similarity(&ppc) < 0.05);
+
+        // Even ignoring the ISA gate, the code and strings have nothing in common
+        let mut relabelled = ppc.clone();
+        relabelled.features.isa.clone_from(&x86.features.isa);
+        relabelled.features.bitwidth = x86.features.bitwidth;
+        assert!(x86.similarity(&relabelled) < 0.3);
+        assert!(x86.distance(&ppc) > 16);
+    }
+
+    #[test]
+    fn test_fingerprint_serde_roundtrip() {
+        let fp = fingerprint(&x86_64_program(0));
+        assert_eq!(fp.to_hex().len(), 32);
+        let json = serde_json::to_string(&fp).unwrap();
+        assert!(json.contains(&format!("\"value\":\"{}\"", fp.to_hex())));
+        let back: Fingerprint = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, fp);
+    }
+
+    #[test]
+    fn test_overflowing_segment_is_skipped() {
+        // ELF64 x86-64 whose only PT_LOAD claims p_offset 0xFFFF_FFFF_FFFF_F000:
+        // offset + size wraps, so the entry segment must be ignored rather
+        // than panicking (debug) or selecting a wrapped window (release)
+        let code = x86_64_program(0);
+        let mut elf = vec![0u8; 0x78];
+        elf[..7].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1]);
+        elf[0x10..0x12].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        elf[0x12..0x14].copy_from_slice(&62u16.to_le_bytes()); // EM_X86_64
+        elf[0x14..0x18].copy_from_slice(&1u32.to_le_bytes());
+        elf[0x18..0x20].copy_from_slice(&0x40_0000u64.to_le_bytes()); // e_entry
+        elf[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes()); // e_phoff
+        elf[0x34..0x36].copy_from_slice(&64u16.to_le_bytes());
+        elf[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+        elf[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+        let ph = 0x40;
+        elf[ph..ph + 4].copy_from_slice(&1u32.to_le_bytes()); // PT_LOAD
+        elf[ph + 4..ph + 8].copy_from_slice(&5u32.to_le_bytes()); // R+X
+        elf[ph + 8..ph + 16].copy_from_slice(&0xFFFF_FFFF_FFFF_F000u64.to_le_bytes());
+        elf[ph + 16..ph + 24].copy_from_slice(&0x40_0000u64.to_le_bytes());
+        elf[ph + 24..ph + 32].copy_from_slice(&0x40_0000u64.to_le_bytes());
+        elf[ph + 32..ph + 40].copy_from_slice(&0x2000u64.to_le_bytes());
+        elf[ph + 40..ph + 48].copy_from_slice(&0x2000u64.to_le_bytes());
+        elf.extend_from_slice(&code);
+
+        let options = ClassifierOptions {
+            collect_sections: true,
+            ..ClassifierOptions::new()
+        };
+        let payload = detect_payload(&elf, &options).unwrap();
+        assert!(payload
+            .segments
+            .iter()
+            .any(|s| s.offset == 0xFFFF_FFFF_FFFF_F000));
+        // No usable range: the window falls back to the start of the file
+        let window = code_window(&payload, &elf);
+        assert_eq!(window, &elf[..PROLOGUE_WINDOW.min(elf.len())]);
+        let _ = payload.structural_fingerprint(&elf);
+    }
+
+    #[test]
+    fn test_string_length_buckets() {
+        let counts = string_lengths(b"abc\0abcd\0abcdefgh\0\x01abcdefghijklmnopqrstuvwxyz012345");
+        assert_eq!(counts, vec![1, 1, 0, 1, 0, 0]);
+        assert_eq!(bucket(0), 0);
+        assert_eq!(bucket(1), 1);
+        assert_eq!(bucket(4096), 13);
+    }
+}
@@ -69,7 +69,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::M68k, 32, Endianness::Big, FileFormat::AmigaHunk);
-    result.variant = Variant::new("Amiga Hunk");
+    result.variant = Some(Variant::new("Amiga Hunk"));
     result.metadata = metadata;
     Ok(result)
 }
@@ -51,7 +51,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Arm, 32, Endianness::Little, FileFormat::Aof);
-    result.variant = Variant::new("AOF");
+    result.variant = Some(Variant::new("AOF"));
     result.metadata = metadata;
     Ok(result)
 }
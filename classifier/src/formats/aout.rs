@@ -233,12 +233,13 @@ fn parse_bsd(data: &[u8], mid: u8, aout_magic: u16) -> Result<ClassificationResu
     let metadata = ClassificationMetadata {
         entry_point: Some(entry as u64),
         code_size: Some(text_size as u64),
+        raw_machine: Some(u32::from(mid)),
         notes,
         ..Default::default()
     };
 
     let mut result = ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::Aout);
-    result.variant = Variant::new(magic_name);
+    result.variant = Some(Variant::new(magic_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -312,13 +313,14 @@ fn parse_plan9(data: &[u8], magic: u32) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         entry_point: Some(entry as u64),
         code_size: Some(text_size as u64),
+        raw_machine: Some(magic),
         notes,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::Plan9Aout);
-    result.variant = Variant::new(arch_name);
+    result.variant = Some(Variant::new(arch_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -363,13 +365,15 @@ fn parse_minix(data: &[u8], combined_id: bool) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         entry_point: Some(entry as u64),
         code_size: Some(text_size as u64),
+        // a_cpu byte of the Minix exec header
+        raw_machine: Some(u32::from(data[3])),
         notes,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::MinixAout);
-    result.variant = Variant::new(id_type);
+    result.variant = Some(Variant::new(id_type));
     result.metadata = metadata;
 
     Ok(result)
@@ -4,6 +4,7 @@
 //! The format is ISA-independent but contains object files for a specific architecture.
 
 use crate::error::{ClassifierError, Result};
+use crate::formats::{read_cstr, Absent};
 use crate::types::{
     ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
 };
@@ -85,6 +86,16 @@ pub fn detect(data: &[u8]) -> Option<ArVariant> {
 
 /// Parse ar archive.
 pub fn parse(data: &[u8], variant: ArVariant) -> Result<ClassificationResult> {
+    parse_with_input_len(data, variant, None)
+}
+
+/// Like [`parse`], for `data` read from an input of `input_len` bytes
+/// (see [`Absent`]).
+pub fn parse_with_input_len(
+    data: &[u8],
+    variant: ArVariant,
+    input_len: Option<u64>,
+) -> Result<ClassificationResult> {
     if data.len() < 8 {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
@@ -97,8 +108,22 @@ pub fn parse(data: &[u8], variant: ArVariant) -> Result<ClassificationResult> {
     let mut member_count = 0;
     let mut offset = 8; // Skip magic
     let mut member_names: Vec<String> = Vec::new();
+    let mut absent = None;
+
+    while offset < data.len() {
+        // A prefix of a larger archive ends partway through; the members
+        // counted so far are all that can be reported
+        absent = Absent::check(
+            data,
+            input_len,
+            "ar members",
+            offset as u64,
+            AR_MEMBER_HEADER_SIZE as u64,
+        );
+        if absent.is_some() {
+            break;
+        }
 
-    while offset + AR_MEMBER_HEADER_SIZE <= data.len() {
         // Verify magic at end of header
         if data[offset + ar_header::FMAG_OFFSET..offset + ar_header::FMAG_OFFSET + 2]
             != ar_header::FMAG
@@ -107,8 +132,7 @@ pub fn parse(data: &[u8], variant: ArVariant) -> Result<ClassificationResult> {
         }
 
         // Read name
-        let name_bytes = &data[offset..offset + 16];
-        let name = String::from_utf8_lossy(name_bytes)
+        let name = String::from_utf8_lossy(read_cstr(data, offset, 16)?)
             .trim_end()
             .trim_end_matches('/')
             .to_string();
@@ -138,6 +162,15 @@ pub fn parse(data: &[u8], variant: ArVariant) -> Result<ClassificationResult> {
             offset += 1;
         }
     }
+    if offset > data.len() && absent.is_none() {
+        absent = Absent::check(
+            data,
+            input_len,
+            "ar members",
+            offset as u64,
+            AR_MEMBER_HEADER_SIZE as u64,
+        );
+    }
 
     let variant_name = match variant {
         ArVariant::Gnu => "GNU/SysV ar",
@@ -165,13 +198,14 @@ pub fn parse(data: &[u8], variant: ArVariant) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         section_count: Some(member_count),
         notes,
+        warnings: absent.iter().map(Absent::note).collect(),
         ..Default::default()
     };
 
     // ar archives are ISA-independent containers
     let mut result =
         ClassificationResult::from_format(Isa::Unknown(0), 0, Endianness::Little, format);
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(variant_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -223,5 +257,34 @@ mod tests {
         let variant = detect(&data).unwrap();
         let result = parse(&data, variant).unwrap();
         assert_eq!(result.format, FileFormat::Archive);
+        assert!(result.metadata.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ar_prefix() {
+        // The buffer stops inside test.o, so whatever follows it is unseen
+        let data = make_ar_archive();
+        let prefix = &data[..8 + 2 * AR_MEMBER_HEADER_SIZE + 10];
+        let result = parse(prefix, ArVariant::Gnu).unwrap();
+        assert_eq!(result.metadata.section_count, Some(1));
+        assert_eq!(
+            result.metadata.warnings,
+            ["ar members at offset 0xe4 not examined (input supplied ends at 0x8a)"]
+        );
+
+        // A header cut in half is the same situation
+        let prefix = &data[..8 + 30];
+        let result = parse(prefix, ArVariant::Gnu).unwrap();
+        assert_eq!(result.metadata.section_count, Some(0));
+        assert_eq!(result.metadata.warnings.len(), 1);
+        assert!(result.metadata.warnings[0].starts_with("ar members at offset 0x8 "));
+
+        // The whole file ends inside a member header
+        let result =
+            parse_with_input_len(prefix, ArVariant::Gnu, Some(prefix.len() as u64)).unwrap();
+        assert_eq!(
+            result.metadata.warnings,
+            ["ar members at offset 0x8 extends past the end of the file (truncated)"]
+        );
     }
 }
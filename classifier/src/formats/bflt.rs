@@ -118,7 +118,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     };
 
     let mut result = ClassificationResult::from_format(isa, 32, Endianness::Big, FileFormat::Bflt);
-    result.variant = Variant::new(format!("v{}", rev));
+    result.variant = Some(Variant::new(format!("v{rev}")));
     result.metadata = metadata;
 
     Ok(result)
@@ -344,10 +344,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let (isa, bitwidth, endianness, variant_note) = machine_to_isa(machine);
 
     // Build variant
-    let variant = match variant_note {
-        Some(note) => Variant::new(note),
-        None => Variant::default(),
-    };
+    let variant = variant_note.map(Variant::new);
 
     // Collect notes
     let mut notes = if is_bigobj {
@@ -0,0 +1,369 @@
+//! CP/M and DOS `.COM` flat binary assessment.
+//!
+//! A `.COM` file has no header: the whole file is loaded at offset `0x0100`
+//! of a single segment (DOS) or of the Transient Program Area (CP/M) and
+//! execution starts at the first byte. Nothing in the file says which system
+//! it targets, so this module looks for the system-call idioms each platform
+//! relies on and checks that absolute branch targets land inside the image
+//! once it is placed at `0x0100`:
+//!
+//! - DOS (8086): `MOV AH, fn` / `MOV AX, imm` followed by `INT 21h`, and
+//!   termination via `INT 20h` or `MOV AH, 4Ch; INT 21h`. Near `CALL`/`JMP`
+//!   (`E8`/`E9` rel16) targets must fall within the loaded image.
+//! - CP/M (8080/Z80): `MVI C, fn` (`0E xx`) followed by `CALL 0005h`
+//!   (`CD 05 00`), the BDOS entry point. `CALL`/`JP` (`CD`/`C3` abs16)
+//!   targets must fall within the image or the BDOS/warm-boot vectors.
+//!
+//! Headerless files are otherwise indistinguishable from any other raw
+//! blob, so assessment only runs on inputs that fit in a 64 KiB segment and
+//! requires at least one system-call idiom. Callers opt in through
+//! [`ClassifierOptions::detect_com`](crate::types::ClassifierOptions::detect_com)
+//! and should only accept an assessment the instruction scorers back (see
+//! [`ComAssessment::scorers_agree`]).
+
+use crate::heuristics::scorer;
+use crate::types::{
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
+};
+
+/// Load address of a `.COM` image (after the 256-byte PSP / zero page).
+pub const COM_ORIGIN: u16 = 0x0100;
+
+/// Largest `.COM` image that fits in one 64 KiB segment above the PSP.
+pub const MAX_COM_SIZE: usize = 0xFF00;
+
+/// Smallest input worth assessing (setup + call + terminate).
+const MIN_COM_SIZE: usize = 4;
+
+/// How far back from a call instruction the function-number setup may be.
+const SETUP_WINDOW: usize = 6;
+
+/// Target system of a `.COM` image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComFlavor {
+    /// MS-DOS / PC DOS, 16-bit x86 real mode.
+    Dos,
+    /// CP/M-80, 8080 or Z80.
+    Cpm,
+}
+
+impl ComFlavor {
+    /// Human-readable format variant.
+    pub fn name(self) -> &'static str {
+        match self {
+            ComFlavor::Dos => "DOS .COM (8086)",
+            ComFlavor::Cpm => "CP/M .COM (Z80)",
+        }
+    }
+
+    /// ISA and bitwidth executed by this flavor.
+    pub fn isa(self) -> (Isa, u8) {
+        match self {
+            ComFlavor::Dos => (Isa::X86, 16),
+            ComFlavor::Cpm => (Isa::Z80, 16),
+        }
+    }
+}
+
+/// Evidence gathered for a `.COM` classification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComAssessment {
+    /// Inferred target system.
+    pub flavor: ComFlavor,
+    /// Number of system-call idioms found for that system.
+    pub system_calls: u32,
+    /// Whether the first instruction is a plausible entry point.
+    pub entry_plausible: bool,
+    /// Branches whose absolute target lands inside the loaded image.
+    pub branches_in_image: u32,
+    /// Confidence in the classification.
+    pub confidence: f64,
+}
+
+impl ComAssessment {
+    /// Whether the instruction scorers back the inferred flavor.
+    ///
+    /// The x86 (16-bit) scorer must beat the Z80 scorer for a DOS image and
+    /// the other way around for CP/M, with a positive score either way.
+    /// System-call idioms are a few bytes long and turn up by chance in
+    /// unrelated data; the scorers look at the whole image.
+    pub fn scorers_agree(&self, data: &[u8]) -> bool {
+        let x86 = scorer::score_x86(data, 16);
+        let z80 = scorer::score_z80(data);
+        match self.flavor {
+            ComFlavor::Dos => x86 > 0 && x86 > z80,
+            ComFlavor::Cpm => z80 > 0 && z80 > x86,
+        }
+    }
+
+    /// Convert the assessment into a classification result.
+    pub fn to_result(&self) -> ClassificationResult {
+        let (isa, bits) = self.flavor.isa();
+        let mut result =
+            ClassificationResult::from_heuristics(isa, bits, Endianness::Little, self.confidence);
+        result.format = FileFormat::Com;
+        result.variant = Some(Variant::new(self.flavor.name()));
+        result.metadata = ClassificationMetadata {
+            entry_point: Some(u64::from(COM_ORIGIN)),
+            notes: vec![format!(
+                "{}: assumed load address 0x{:04X}, {} system call(s)",
+                self.flavor.name(),
+                COM_ORIGIN,
+                self.system_calls
+            )],
+            custom: vec![MetadataEntry::custom(
+                "system_calls",
+                MetadataValue::Integer(u64::from(self.system_calls)),
+                "System Calls",
+            )],
+            ..Default::default()
+        };
+        result
+    }
+}
+
+/// Assess `data` as a headerless `.COM` image.
+///
+/// Returns `None` for inputs outside the `.COM` size range, inputs without
+/// system-call idioms, and inputs where DOS and CP/M evidence is tied.
+pub fn assess(data: &[u8]) -> Option<ComAssessment> {
+    if data.len() < MIN_COM_SIZE || data.len() > MAX_COM_SIZE {
+        return None;
+    }
+
+    let dos = assess_dos(data);
+    let cpm = assess_cpm(data);
+
+    let best = match dos.system_calls.cmp(&cpm.system_calls) {
+        std::cmp::Ordering::Greater => dos,
+        std::cmp::Ordering::Less => cpm,
+        std::cmp::Ordering::Equal => return None,
+    };
+
+    if best.system_calls == 0 || !(best.entry_plausible || best.system_calls >= 2) {
+        return None;
+    }
+    Some(best)
+}
+
+/// Whether a 16-bit absolute address falls within the loaded image.
+fn in_image(addr: u16, len: usize) -> bool {
+    let origin = usize::from(COM_ORIGIN);
+    let addr = usize::from(addr);
+    addr >= origin && addr < origin + len
+}
+
+fn read_u16(data: &[u8], off: usize) -> Option<u16> {
+    data.get(off..off + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn finish(
+    flavor: ComFlavor,
+    system_calls: u32,
+    entry_plausible: bool,
+    branches: u32,
+) -> ComAssessment {
+    let mut confidence = 0.5 + 0.08 * f64::from(system_calls.min(4));
+    if entry_plausible {
+        confidence += 0.1;
+    }
+    if branches > 0 {
+        confidence += 0.05;
+    }
+    ComAssessment {
+        flavor,
+        system_calls,
+        entry_plausible,
+        branches_in_image: branches,
+        confidence: confidence.min(0.9),
+    }
+}
+
+fn assess_dos(data: &[u8]) -> ComAssessment {
+    let len = data.len();
+    let mut calls = 0u32;
+    let mut terminations = 0u32;
+
+    for i in 0..len.saturating_sub(1) {
+        if data[i] != 0xCD {
+            continue;
+        }
+        match data[i + 1] {
+            // INT 20h: terminate program, no setup needed
+            0x20 => terminations += 1,
+            // INT 21h: AH selects the function
+            0x21 => {
+                let start = i.saturating_sub(SETUP_WINDOW);
+                let window = &data[start..i];
+                let has_setup = window
+                    .iter()
+                    .enumerate()
+                    .any(|(k, &b)| matches!(b, 0xB4 | 0xB8) && start + k + 2 <= i);
+                if has_setup {
+                    calls += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // INT 20h is only a two-byte pattern; count it once DOS calls are present
+    if calls > 0 && terminations > 0 {
+        calls += 1;
+    }
+
+    // Near CALL/JMP rel16, relative to the next instruction at origin + i + 3
+    let mut branches = 0u32;
+    for i in 0..len.saturating_sub(2) {
+        if matches!(data[i], 0xE8 | 0xE9) {
+            if let Some(rel) = read_u16(data, i + 1) {
+                // 16-bit IP arithmetic wraps within the segment
+                let next = (usize::from(COM_ORIGIN) + i + 3) & 0xFFFF;
+                let target = (next + usize::from(rel)) & 0xFFFF;
+                if u16::try_from(target).is_ok_and(|t| in_image(t, len)) {
+                    branches += 1;
+                }
+            }
+        }
+    }
+
+    let entry_plausible = match data[0] {
+        0xE9 => read_u16(data, 1)
+            .is_some_and(|rel| in_image(COM_ORIGIN.wrapping_add(3).wrapping_add(rel), len)),
+        0xEB => {
+            let target = i32::from(COM_ORIGIN) + 2 + i32::from(i8::from_le_bytes([data[1]]));
+            u16::try_from(target).is_ok_and(|t| in_image(t, len))
+        }
+        // MOV DX, imm16 (string pointer) or MOV AH, imm8 (function number)
+        0xBA => read_u16(data, 1).is_some_and(|a| in_image(a, len)),
+        0xB4 | 0xB8 | 0xFC | 0xFA => true,
+        _ => false,
+    };
+
+    finish(ComFlavor::Dos, calls, entry_plausible, branches)
+}
+
+fn assess_cpm(data: &[u8]) -> ComAssessment {
+    let len = data.len();
+    let mut calls = 0u32;
+    let mut branches = 0u32;
+
+    for i in 0..len.saturating_sub(2) {
+        match data[i] {
+            0xCD if data[i + 1] == 0x05 && data[i + 2] == 0x00 => {
+                // CALL BDOS: C selects the function
+                let start = i.saturating_sub(SETUP_WINDOW);
+                let has_setup = data[start..i]
+                    .iter()
+                    .enumerate()
+                    .any(|(k, &b)| b == 0x0E && start + k + 2 <= i);
+                if has_setup {
+                    calls += 1;
+                }
+            }
+            0xCD | 0xC3 => {
+                if let Some(addr) = read_u16(data, i + 1) {
+                    if in_image(addr, len) {
+                        branches += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let entry_plausible = match data[0] {
+        // JP addr / LD DE, string / LD HL, nn
+        0xC3 | 0x11 | 0x21 => read_u16(data, 1).is_some_and(|a| in_image(a, len)),
+        // LD SP, nn / LD C, fn
+        0x31 | 0x0E => true,
+        _ => false,
+    };
+
+    finish(ComFlavor::Cpm, calls, entry_plausible, branches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `mov ah,9 / mov dx,msg / int 21h / int 20h / msg db "Hello$"`
+    fn dos_hello() -> Vec<u8> {
+        let mut code = vec![
+            0xB4, 0x09, // mov ah, 09h
+            0xBA, 0x09, 0x01, // mov dx, 0109h
+            0xCD, 0x21, // int 21h
+            0xCD, 0x20, // int 20h
+        ];
+        code.extend_from_slice(b"Hello, world!$");
+        code
+    }
+
+    /// `ld c,9 / ld de,msg / call 5 / ret / msg db "Hello$"`
+    fn cpm_hello() -> Vec<u8> {
+        let mut code = vec![
+            0x0E, 0x09, // ld c, 9
+            0x11, 0x09, 0x01, // ld de, 0109h
+            0xCD, 0x05, 0x00, // call 0005h
+            0xC9, // ret (back to the CCP)
+        ];
+        code.extend_from_slice(b"Hello, world!$");
+        code
+    }
+
+    #[test]
+    fn test_dos_hello() {
+        let com = assess(&dos_hello()).expect("DOS .COM");
+        assert_eq!(com.flavor, ComFlavor::Dos);
+        assert_eq!(com.system_calls, 2);
+        assert!(com.entry_plausible);
+
+        let result = com.to_result();
+        assert_eq!(result.isa, Isa::X86);
+        assert_eq!(result.bitwidth, 16);
+        assert_eq!(result.format, FileFormat::Com);
+    }
+
+    #[test]
+    fn test_cpm_hello() {
+        let com = assess(&cpm_hello()).expect("CP/M .COM");
+        assert_eq!(com.flavor, ComFlavor::Cpm);
+        assert_eq!(com.system_calls, 1);
+        assert!(com.entry_plausible);
+        assert_eq!(com.to_result().isa, Isa::Z80);
+    }
+
+    #[test]
+    fn test_scorers_agree() {
+        let dos = dos_hello();
+        assert!(assess(&dos).unwrap().scorers_agree(&dos));
+        let cpm = cpm_hello();
+        assert!(assess(&cpm).unwrap().scorers_agree(&cpm));
+
+        // A BDOS call idiom at the start of x86 SSE code (movdqa xmm0, xmm1)
+        let mut data = vec![0x0E, 0x09, 0xCD, 0x05, 0x00];
+        data.extend([0x66, 0x0F, 0x6F, 0xC1].repeat(16));
+        let com = assess(&data).expect("CP/M idiom present");
+        assert_eq!(com.flavor, ComFlavor::Cpm);
+        assert!(!com.scorers_agree(&data));
+    }
+
+    #[test]
+    fn test_rejects_without_call_idioms() {
+        // ARM Thumb-ish halfwords and plain data
+        let data: Vec<u8> = (0..512u32)
+            .map(|i| i.wrapping_mul(37).to_le_bytes()[0])
+            .collect();
+        assert!(assess(&data).is_none());
+        assert!(assess(&[0x70, 0x47, 0x00, 0xBF, 0x10, 0xB5, 0x10, 0xBD]).is_none());
+    }
+
+    #[test]
+    fn test_rejects_oversized() {
+        let mut data = dos_hello();
+        data.resize(MAX_COM_SIZE + 1, 0);
+        assert!(assess(&data).is_none());
+    }
+}
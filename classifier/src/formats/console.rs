@@ -6,11 +6,13 @@
 //! - SELF/SPRX (PlayStation 3/4/5)
 //! - NSO/NRO (Nintendo Switch)
 //! - DOL/REL (GameCube/Wii)
+//! - IP.BIN (Dreamcast boot header)
 
 use crate::error::{ClassifierError, Result};
 use crate::formats::{read_u16, read_u32, read_u64};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
 // === XBE (Original Xbox) ===
@@ -66,6 +68,42 @@ pub const NRO_HEADER_SIZE: usize = 0x80;
 /// DOL header size.
 pub const DOL_HEADER_SIZE: usize = 0x100;
 
+// === Dreamcast IP.BIN ===
+
+/// Dreamcast hardware ID at the start of IP.BIN.
+pub const DREAMCAST_HARDWARE_ID: &[u8; 16] = b"SEGA SEGAKATANA ";
+
+/// Size of the IP.BIN system ID block.
+pub const IP_BIN_META_SIZE: usize = 0x100;
+
+/// IP.BIN system ID fields: offset and length of fixed-width ASCII text,
+/// padded with spaces.
+pub mod ip_bin {
+    /// Maker ID, `SEGA ENTERPRISES` or `SEGA LC-T-nnn`.
+    pub const MAKER_ID: (usize, usize) = (0x10, 16);
+    /// Disc CRC and `GD-ROMn/m` disc number.
+    pub const DEVICE_INFO: (usize, usize) = (0x20, 16);
+    /// Area symbols: `J`, `U` and `E` for the regions the disc boots in.
+    pub const AREA_SYMBOLS: (usize, usize) = (0x30, 8);
+    /// Supported peripherals, as hexadecimal flags.
+    pub const PERIPHERALS: (usize, usize) = (0x38, 8);
+    /// Product number, e.g. `HDR-0001` or `T-8101N`.
+    pub const PRODUCT_NUMBER: (usize, usize) = (0x40, 10);
+    /// Product version, e.g. `V1.000`.
+    pub const PRODUCT_VERSION: (usize, usize) = (0x4A, 6);
+    /// Release date as `YYYYMMDD`.
+    pub const RELEASE_DATE: (usize, usize) = (0x50, 16);
+    /// Name of the boot executable on the disc.
+    pub const BOOT_FILENAME: (usize, usize) = (0x60, 16);
+    /// Software maker name.
+    pub const SOFTWARE_MAKER: (usize, usize) = (0x70, 16);
+    /// Game title.
+    pub const TITLE: (usize, usize) = (0x80, 128);
+
+    /// Boot executable named by almost every disc.
+    pub const DEFAULT_BOOT_FILENAME: &str = "1ST_READ.BIN";
+}
+
 /// Console format variant.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConsoleFormat {
@@ -77,6 +115,7 @@ pub enum ConsoleFormat {
     Nso,
     Nro,
     Dol,
+    DreamcastIp,
 }
 
 /// Detect console format.
@@ -119,6 +158,14 @@ pub fn detect(data: &[u8]) -> Option<ConsoleFormat> {
         return Some(ConsoleFormat::Nro);
     }
 
+    // Dreamcast IP.BIN
+    if data.len() >= IP_BIN_META_SIZE
+        && data.starts_with(DREAMCAST_HARDWARE_ID)
+        && is_plausible_ip_bin(data)
+    {
+        return Some(ConsoleFormat::DreamcastIp);
+    }
+
     // DOL - no magic, but starts with section offsets
     // Heuristic: first 7 u32s are text offsets, should be reasonable values
     if data.len() >= DOL_HEADER_SIZE {
@@ -193,7 +240,7 @@ fn parse_xbe(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, 32, Endianness::Little, FileFormat::Xbe);
-    result.variant = Variant::new(build_type);
+    result.variant = Some(Variant::new(build_type));
     result.metadata = metadata;
 
     Ok(result)
@@ -239,7 +286,7 @@ fn parse_xex(data: &[u8], version: u8) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Ppc, 32, Endianness::Big, FileFormat::Xex);
-    result.variant = Variant::new(format!("XEX{} {}", version, module_type));
+    result.variant = Some(Variant::new(format!("XEX{version} {module_type}")));
     result.metadata = metadata;
 
     Ok(result)
@@ -283,7 +330,7 @@ fn parse_self_ps3(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Ppc64, 64, Endianness::Big, FileFormat::SelfPs3);
-    result.variant = Variant::new("Cell BE");
+    result.variant = Some(Variant::new("Cell BE"));
     result.metadata = metadata;
 
     Ok(result)
@@ -316,7 +363,7 @@ fn parse_self_ps4(data: &[u8], is_ps5: bool) -> Result<ClassificationResult> {
     };
 
     let mut result = ClassificationResult::from_format(Isa::X86_64, 64, Endianness::Little, format);
-    result.variant = Variant::new(platform);
+    result.variant = Some(Variant::new(platform));
     result.metadata = metadata;
 
     Ok(result)
@@ -380,7 +427,7 @@ fn parse_nso(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::AArch64, 64, Endianness::Little, FileFormat::Nso);
-    result.variant = Variant::new("Nintendo Switch");
+    result.variant = Some(Variant::new("Nintendo Switch"));
     result.metadata = metadata;
 
     Ok(result)
@@ -428,7 +475,7 @@ fn parse_nro(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::AArch64, 64, Endianness::Little, FileFormat::Nro);
-    result.variant = Variant::new("Nintendo Switch Homebrew");
+    result.variant = Some(Variant::new("Nintendo Switch Homebrew"));
     result.metadata = metadata;
 
     Ok(result)
@@ -478,7 +525,126 @@ fn parse_dol(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Ppc, 32, Endianness::Big, FileFormat::Dol);
-    result.variant = Variant::new("GameCube/Wii");
+    result.variant = Some(Variant::new("GameCube/Wii"));
+    result.metadata = metadata;
+
+    Ok(result)
+}
+
+/// A fixed-width IP.BIN text field, or `None` if it holds anything but
+/// printable ASCII.
+fn ip_bin_field(data: &[u8], (offset, len): (usize, usize)) -> Option<&str> {
+    let bytes = data.get(offset..offset + len)?;
+    if !bytes.iter().all(|b| (0x20..0x7F).contains(b)) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok().map(str::trim_end)
+}
+
+/// Whether the fields after the hardware ID look like a real IP.BIN:
+/// a SEGA maker ID, printable text fields, area symbols from `JUE` and an
+/// eight-digit release date. Erased (0xFF) or zeroed blocks that happen
+/// to keep the ID string fail.
+fn is_plausible_ip_bin(data: &[u8]) -> bool {
+    let text = |field| ip_bin_field(data, field);
+    let Some(maker) = text(ip_bin::MAKER_ID) else {
+        return false;
+    };
+    let Some(area) = text(ip_bin::AREA_SYMBOLS) else {
+        return false;
+    };
+    let Some(date) = text(ip_bin::RELEASE_DATE) else {
+        return false;
+    };
+    let fields = [
+        ip_bin::DEVICE_INFO,
+        ip_bin::PERIPHERALS,
+        ip_bin::PRODUCT_NUMBER,
+        ip_bin::PRODUCT_VERSION,
+        ip_bin::BOOT_FILENAME,
+    ];
+    maker.starts_with("SEGA")
+        && !area.is_empty()
+        && area.chars().all(|c| matches!(c, 'J' | 'U' | 'E' | ' '))
+        && date.len() == 8
+        && date.bytes().all(|b| b.is_ascii_digit())
+        && fields
+            .iter()
+            .all(|&f| text(f).is_some_and(|s| !s.is_empty()))
+}
+
+/// Region names for IP.BIN area symbols.
+fn ip_bin_regions(area: &str) -> String {
+    let regions: Vec<&str> = area
+        .chars()
+        .filter_map(|c| match c {
+            'J' => Some("Japan"),
+            'U' => Some("USA"),
+            'E' => Some("Europe"),
+            _ => None,
+        })
+        .collect();
+    regions.join(", ")
+}
+
+/// Parse a Dreamcast IP.BIN boot header.
+fn parse_dreamcast_ip(data: &[u8]) -> Result<ClassificationResult> {
+    if data.len() < IP_BIN_META_SIZE {
+        return Err(ClassifierError::TruncatedData {
+            offset: 0,
+            expected: IP_BIN_META_SIZE,
+            actual: data.len(),
+        });
+    }
+
+    let text = |field| ip_bin_field(data, field).unwrap_or_default().to_string();
+    let product = text(ip_bin::PRODUCT_NUMBER);
+    let version = text(ip_bin::PRODUCT_VERSION);
+    let boot = text(ip_bin::BOOT_FILENAME);
+    let regions = ip_bin_regions(&text(ip_bin::AREA_SYMBOLS));
+
+    let mut notes = vec!["Dreamcast IP.BIN".to_string()];
+    notes.push(format!("Product: {product} {version}"));
+    notes.push(format!("Regions: {regions}"));
+    notes.push(format!("Boot file: {boot}"));
+    if boot != ip_bin::DEFAULT_BOOT_FILENAME {
+        notes.push(format!(
+            "Boot file is not {}",
+            ip_bin::DEFAULT_BOOT_FILENAME
+        ));
+    }
+
+    let fields = [
+        ("device_info", text(ip_bin::DEVICE_INFO), "Device Info"),
+        ("regions", regions, "Regions"),
+        ("peripherals", text(ip_bin::PERIPHERALS), "Peripherals"),
+        ("product_number", product, "Product Number"),
+        ("product_version", version, "Product Version"),
+        ("release_date", text(ip_bin::RELEASE_DATE), "Release Date"),
+        ("boot_filename", boot, "Boot Filename"),
+        (
+            "software_maker",
+            text(ip_bin::SOFTWARE_MAKER),
+            "Software Maker",
+        ),
+        ("title", text(ip_bin::TITLE), "Title"),
+    ];
+    let custom = fields
+        .into_iter()
+        .filter(|(_, value, _)| !value.is_empty())
+        .map(|(key, value, label)| MetadataEntry::custom(key, MetadataValue::String(value), label))
+        .collect();
+
+    let metadata = ClassificationMetadata {
+        header_size: Some(IP_BIN_META_SIZE as u64),
+        notes,
+        custom,
+        ..Default::default()
+    };
+
+    let mut result =
+        ClassificationResult::from_format(Isa::Sh4, 32, Endianness::Little, FileFormat::IpBin);
+    result.variant = Some(Variant::new("Dreamcast IP.BIN"));
     result.metadata = metadata;
 
     Ok(result)
@@ -495,6 +661,7 @@ pub fn parse(data: &[u8], format: ConsoleFormat) -> Result<ClassificationResult>
         ConsoleFormat::Nso => parse_nso(data),
         ConsoleFormat::Nro => parse_nro(data),
         ConsoleFormat::Dol => parse_dol(data),
+        ConsoleFormat::DreamcastIp => parse_dreamcast_ip(data),
     }
 }
 
@@ -525,6 +692,75 @@ mod tests {
         data
     }
 
+    /// The system ID block of a retail IP.BIN.
+    fn make_ip_bin() -> Vec<u8> {
+        let mut data = Vec::with_capacity(IP_BIN_META_SIZE);
+        data.extend_from_slice(b"SEGA SEGAKATANA SEGA ENTERPRISES");
+        data.extend_from_slice(b"5BB4 GD-ROM1/1  ");
+        data.extend_from_slice(b"JUE     ");
+        data.extend_from_slice(b"E000F10 ");
+        data.extend_from_slice(b"HDR-0001  V1.000");
+        data.extend_from_slice(b"19990909        ");
+        data.extend_from_slice(b"1ST_READ.BIN    ");
+        data.extend_from_slice(b"SEGA ENTERPRISES");
+        let mut title = b"SONIC ADVENTURE".to_vec();
+        title.resize(128, b' ');
+        data.extend_from_slice(&title);
+        assert_eq!(data.len(), IP_BIN_META_SIZE);
+        data
+    }
+
+    #[test]
+    fn test_parse_dreamcast_ip_bin() {
+        let data = make_ip_bin();
+        let format = detect(&data).unwrap();
+        assert_eq!(format, ConsoleFormat::DreamcastIp);
+        let result = parse(&data, format).unwrap();
+        assert_eq!(result.isa, Isa::Sh4);
+        assert_eq!(result.bitwidth, 32);
+        assert_eq!(result.endianness, Endianness::Little);
+        assert_eq!(result.format, FileFormat::IpBin);
+        assert_eq!(result.variant, Some(Variant::new("Dreamcast IP.BIN")));
+
+        let field = |key: &str| {
+            result
+                .metadata
+                .custom
+                .iter()
+                .find(|e| e.key.to_string() == key)
+                .map(|e| match &e.value {
+                    MetadataValue::String(s) => s.clone(),
+                    other => panic!("{key}: {other:?}"),
+                })
+        };
+        assert_eq!(field("product_number").as_deref(), Some("HDR-0001"));
+        assert_eq!(field("product_version").as_deref(), Some("V1.000"));
+        assert_eq!(field("regions").as_deref(), Some("Japan, USA, Europe"));
+        assert_eq!(field("device_info").as_deref(), Some("5BB4 GD-ROM1/1"));
+        assert_eq!(field("boot_filename").as_deref(), Some("1ST_READ.BIN"));
+        assert_eq!(field("title").as_deref(), Some("SONIC ADVENTURE"));
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n == "Boot file: 1ST_READ.BIN"));
+    }
+
+    #[test]
+    fn test_reject_implausible_ip_bin() {
+        // Hardware ID intact, fields erased
+        let mut data = make_ip_bin();
+        data[0x20..].fill(0xFF);
+        assert_eq!(detect(&data), None);
+
+        let mut data = make_ip_bin();
+        data[0x30..0x38].copy_from_slice(b"XYZ     ");
+        assert_eq!(detect(&data), None);
+
+        // Too short for the system ID block
+        assert_eq!(detect(&make_ip_bin()[..0x80]), None);
+    }
+
     #[test]
     fn test_detect_xbe() {
         let data = make_xbe_header();
@@ -4,9 +4,10 @@
 //! Related formats include ODEX (Optimized DEX), VDEX, and ART image files.
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::read_u32;
+use crate::formats::{read_cstr, read_u32};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
 /// DEX magic prefix: "dex\n"
@@ -93,16 +94,25 @@ fn parse_dex(data: &[u8], version: [u8; 3], is_odex: bool) -> Result<Classificat
         });
     }
 
-    // Parse header fields
-    let _checksum = read_u32(data, 8, true)?;
-    // SHA-1 signature at 12-31
-    let file_size = read_u32(data, 32, true)?;
-    let header_size = read_u32(data, 36, true)?;
+    // The endian tag decides how every other header field is read
     let endian_tag = read_u32(data, 40, true)?;
-
-    let little_endian = endian_tag == ENDIAN_CONSTANT;
+    let mut warnings = Vec::new();
+    let little_endian = match endian_tag {
+        ENDIAN_CONSTANT => true,
+        REVERSE_ENDIAN_CONSTANT => false,
+        tag => {
+            warnings.push(format!(
+                "Unrecognized DEX endian tag 0x{tag:08X}; assuming little-endian"
+            ));
+            true
+        }
+    };
     let le = little_endian;
 
+    let checksum = read_u32(data, 8, le)?;
+    // SHA-1 signature at 12-31
+    let file_size = read_u32(data, 32, le)?;
+    let header_size = read_u32(data, 36, le)?;
     let _link_size = read_u32(data, 44, le)?;
     let _link_off = read_u32(data, 48, le)?;
     let _map_off = read_u32(data, 52, le)?;
@@ -122,8 +132,11 @@ fn parse_dex(data: &[u8], version: [u8; 3], is_odex: bool) -> Result<Classificat
     let _data_off = read_u32(data, 108, le)?;
 
     // Parse version string
-    let version_str = String::from_utf8_lossy(&version);
-    let version_num: u32 = version_str.trim_end_matches('\0').parse().unwrap_or(0);
+    let version_str = String::from_utf8_lossy(read_cstr(&version, 0, version.len())?);
+    let version_num: u32 = version_str.parse().unwrap_or(0);
+    if !version.iter().all(u8::is_ascii_digit) {
+        warnings.push(format!("Malformed DEX version {:?}", version_str.trim()));
+    }
 
     // Map DEX version to Android version
     let android_version = match version_num {
@@ -136,6 +149,20 @@ fn parse_dex(data: &[u8], version: [u8; 3], is_odex: bool) -> Result<Classificat
         _ => "Unknown Android version",
     };
 
+    // DEX 041 containers append container_size and header_offset
+    let expected_header = if version_num >= 41 { 0x78 } else { 0x70 };
+    if header_size != expected_header {
+        warnings.push(format!(
+            "DEX header size {header_size} (expected {expected_header})"
+        ));
+    }
+    if file_size as usize > data.len() {
+        warnings.push(format!(
+            "DEX file size {file_size} exceeds the {} bytes present",
+            data.len()
+        ));
+    }
+
     let format_name = if is_odex { "ODEX" } else { "DEX" };
     let format = if is_odex {
         FileFormat::Odex
@@ -143,28 +170,45 @@ fn parse_dex(data: &[u8], version: [u8; 3], is_odex: bool) -> Result<Classificat
         FileFormat::Dex
     };
 
-    let mut notes = vec![format!(
-        "{} file version {}",
-        format_name,
-        version_str.trim()
-    )];
-    notes.push(format!("Target: {}", android_version));
-    notes.push(format!("File size: {} bytes", file_size));
-    notes.push(format!("Header size: {} bytes", header_size));
-    notes.push(format!(
-        "Endianness: {}",
-        if little_endian { "little" } else { "big" }
-    ));
-    notes.push(format!("Strings: {}", string_ids_size));
-    notes.push(format!("Types: {}", type_ids_size));
-    notes.push(format!("Prototypes: {}", proto_ids_size));
-    notes.push(format!("Fields: {}", field_ids_size));
-    notes.push(format!("Methods: {}", method_ids_size));
-    notes.push(format!("Classes: {}", class_defs_size));
+    let notes = vec![
+        format!("{} file version {}", format_name, version_str.trim()),
+        format!("Target: {}", android_version),
+        format!(
+            "Endianness: {}",
+            if little_endian { "little" } else { "big" }
+        ),
+    ];
+
+    let count = |key: &str, value: u32, label: &str| {
+        MetadataEntry::custom(key, MetadataValue::Integer(u64::from(value)), label)
+    };
+    let custom = vec![
+        MetadataEntry::custom(
+            "dex_version",
+            MetadataValue::String(version_str.trim().to_string()),
+            "DEX Version",
+        ),
+        MetadataEntry::custom("endian_tag", MetadataValue::Hex(endian_tag), "Endian Tag"),
+        MetadataEntry::custom(
+            "checksum",
+            MetadataValue::Hex(checksum),
+            "Adler-32 Checksum",
+        ),
+        count("file_size", file_size, "File Size"),
+        count("string_ids", string_ids_size, "Strings"),
+        count("type_ids", type_ids_size, "Types"),
+        count("proto_ids", proto_ids_size, "Prototypes"),
+        count("field_ids", field_ids_size, "Fields"),
+        count("method_ids", method_ids_size, "Methods"),
+        count("class_defs", class_defs_size, "Classes"),
+    ];
 
     let metadata = ClassificationMetadata {
         code_size: Some(file_size as u64),
+        header_size: Some(u64::from(header_size)),
         notes,
+        warnings,
+        custom,
         ..Default::default()
     };
 
@@ -175,7 +219,7 @@ fn parse_dex(data: &[u8], version: [u8; 3], is_odex: bool) -> Result<Classificat
     };
 
     let mut result = ClassificationResult::from_format(Isa::Dalvik, 32, endianness, format);
-    result.variant = Variant::new(format!("DEX {}", version_str.trim()));
+    result.variant = Some(Variant::new(format!("DEX {}", version_str.trim())));
     result.metadata = metadata;
 
     Ok(result)
@@ -203,27 +247,180 @@ fn parse_vdex(data: &[u8], version: [u8; 4]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Dalvik, 32, Endianness::Little, FileFormat::Vdex);
-    result.variant = Variant::new(format!("VDEX {}", version_str.trim()));
+    result.variant = Some(Variant::new(format!("VDEX {}", version_str.trim())));
     result.metadata = metadata;
 
     Ok(result)
 }
 
+/// Field offsets of one generation of the ART `ImageHeader`
+/// (`art/runtime/image.h`).
+struct ArtLayout {
+    image_begin: usize,
+    image_size: usize,
+    oat_checksum: usize,
+    oat_file_begin: usize,
+    oat_data_begin: usize,
+    oat_data_end: usize,
+    oat_file_end: usize,
+    /// Relocation applied by `patchoat` (dropped in Android 10)
+    patch_delta: Option<usize>,
+    /// Native pointer size (added in Android 6)
+    pointer_size: Option<usize>,
+    /// Bytes needed to read every field above
+    header_size: usize,
+}
+
+impl ArtLayout {
+    /// Layout for image `version`.
+    fn for_version(version: u32) -> Self {
+        if version >= 74 {
+            // Android 10+: reservation size and component count first
+            Self {
+                image_begin: 16,
+                image_size: 20,
+                oat_checksum: 28,
+                oat_file_begin: 32,
+                oat_data_begin: 36,
+                oat_data_end: 40,
+                oat_file_end: 44,
+                patch_delta: None,
+                pointer_size: Some(68),
+                header_size: 72,
+            }
+        } else if version >= 29 {
+            // Android 7-9: boot image range before the patch delta
+            Self {
+                patch_delta: Some(52),
+                pointer_size: Some(60),
+                header_size: 64,
+                ..Self::LEGACY
+            }
+        } else if version >= 17 {
+            Self {
+                pointer_size: Some(44),
+                header_size: 48,
+                ..Self::LEGACY
+            }
+        } else {
+            Self::LEGACY
+        }
+    }
+
+    /// Android 5 layout.
+    const LEGACY: Self = Self {
+        image_begin: 8,
+        image_size: 12,
+        oat_checksum: 16,
+        oat_file_begin: 20,
+        oat_data_begin: 24,
+        oat_data_end: 28,
+        oat_file_end: 32,
+        patch_delta: Some(36),
+        pointer_size: None,
+        header_size: 40,
+    };
+}
+
 /// Parse ART image file.
+///
+/// The image header gives the address the image maps at and the layout of
+/// the OAT file holding its compiled code. It records the native pointer
+/// size but not the instruction set, which lives in the companion OAT
+/// file's header.
 fn parse_art(data: &[u8], version: [u8; 3]) -> Result<ClassificationResult> {
     let version_str = String::from_utf8_lossy(&version);
+    let version_num: u32 = version_str.trim().parse().unwrap_or(0);
+    let layout = ArtLayout::for_version(version_num);
+    if data.len() < layout.header_size {
+        return Err(ClassifierError::TruncatedData {
+            offset: 0,
+            expected: layout.header_size,
+            actual: data.len(),
+        });
+    }
+
+    let image_begin = read_u32(data, layout.image_begin, true)?;
+    let image_size = read_u32(data, layout.image_size, true)?;
+    let oat_checksum = read_u32(data, layout.oat_checksum, true)?;
+    let oat_file_begin = read_u32(data, layout.oat_file_begin, true)?;
+    let oat_data_begin = read_u32(data, layout.oat_data_begin, true)?;
+    let oat_data_end = read_u32(data, layout.oat_data_end, true)?;
+    let oat_file_end = read_u32(data, layout.oat_file_end, true)?;
 
     let mut notes = vec!["ART image file".to_string()];
     notes.push(format!("Version: {}", version_str.trim()));
+    let mut warnings = Vec::new();
+
+    let address = |key: &str, value: u32, label: &str| {
+        MetadataEntry::custom(key, MetadataValue::Address(u64::from(value)), label)
+    };
+    let mut custom = vec![
+        address("image_begin", image_begin, "Image Begin"),
+        MetadataEntry::custom(
+            "image_size",
+            MetadataValue::Integer(u64::from(image_size)),
+            "Image Size",
+        ),
+        MetadataEntry::custom(
+            "oat_checksum",
+            MetadataValue::Hex(oat_checksum),
+            "OAT Checksum",
+        ),
+        address("oat_file_begin", oat_file_begin, "OAT File Begin"),
+        address("oat_data_begin", oat_data_begin, "OAT Data Begin"),
+        address("oat_data_end", oat_data_end, "OAT Data End"),
+        address("oat_file_end", oat_file_end, "OAT File End"),
+    ];
+
+    if let Some(offset) = layout.patch_delta {
+        let patch_delta = read_u32(data, offset, true)? as i32;
+        custom.push(MetadataEntry::custom(
+            "patch_delta",
+            MetadataValue::String(format!(
+                "{}0x{:X}",
+                if patch_delta < 0 { "-" } else { "" },
+                patch_delta.unsigned_abs()
+            )),
+            "Patch Delta",
+        ));
+    }
+
+    if let Some(offset) = layout.pointer_size {
+        let pointer_size = read_u32(data, offset, true)?;
+        if let 4 | 8 = pointer_size {
+            notes.push(format!(
+                "Native code: {}-bit (instruction set recorded in the companion OAT file)",
+                pointer_size * 8
+            ));
+        } else {
+            warnings.push(format!("Unexpected ART pointer size {pointer_size}"));
+        }
+        custom.push(MetadataEntry::custom(
+            "pointer_size",
+            MetadataValue::Integer(u64::from(pointer_size)),
+            "Pointer Size",
+        ));
+    }
+
+    if !(oat_file_begin <= oat_data_begin
+        && oat_data_begin <= oat_data_end
+        && oat_data_end <= oat_file_end)
+    {
+        warnings.push("ART image header has an inconsistent OAT layout".to_string());
+    }
 
     let metadata = ClassificationMetadata {
         notes,
+        warnings,
+        custom,
+        header_size: Some(layout.header_size as u64),
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(Isa::Dalvik, 32, Endianness::Little, FileFormat::Art);
-    result.variant = Variant::new(format!("ART {}", version_str.trim()));
+    result.variant = Some(Variant::new(format!("ART {}", version_str.trim())));
     result.metadata = metadata;
 
     Ok(result)
@@ -296,12 +493,147 @@ mod tests {
         assert_eq!(result.format, FileFormat::Dex);
     }
 
+    #[test]
+    fn test_parse_dex_header_fields() {
+        let mut data = make_dex_header(b"038");
+        // Prototypes and fields
+        data[72..76].copy_from_slice(&7u32.to_le_bytes());
+        data[80..84].copy_from_slice(&9u32.to_le_bytes());
+        let result = parse(&data, detect(&data).unwrap()).unwrap();
+        assert_eq!(result.variant_name(), Some("DEX 038"));
+        assert_eq!(result.endianness, Endianness::Little);
+        assert_eq!(result.metadata.header_size, Some(112));
+        assert!(result.metadata.warnings.is_empty());
+
+        let value = |key: &str| {
+            result
+                .metadata
+                .custom
+                .iter()
+                .find(|e| e.key == crate::types::MetadataKey::Custom(key.to_string()))
+                .map(|e| e.value.clone())
+        };
+        assert!(matches!(value("dex_version"), Some(MetadataValue::String(v)) if v == "038"));
+        assert!(matches!(
+            value("endian_tag"),
+            Some(MetadataValue::Hex(ENDIAN_CONSTANT))
+        ));
+        assert!(matches!(
+            value("checksum"),
+            Some(MetadataValue::Hex(0x1234_5678))
+        ));
+        for (key, expected) in [
+            ("string_ids", 10),
+            ("type_ids", 5),
+            ("proto_ids", 7),
+            ("field_ids", 9),
+            ("method_ids", 20),
+            ("class_defs", 3),
+        ] {
+            assert!(
+                matches!(value(key), Some(MetadataValue::Integer(n)) if n == expected),
+                "{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dex_endian_tag() {
+        // Byte-swapped DEX: every header word is big-endian
+        let mut data = make_dex_header(b"035");
+        for offset in (8..DEX_HEADER_SIZE).step_by(4) {
+            data[offset..offset + 4].reverse();
+        }
+        let result = parse(&data, detect(&data).unwrap()).unwrap();
+        assert_eq!(result.endianness, Endianness::Big);
+        assert!(result.metadata.warnings.is_empty());
+        assert!(result.metadata.custom.iter().any(|e| {
+            e.key == crate::types::MetadataKey::Custom("method_ids".to_string())
+                && matches!(e.value, MetadataValue::Integer(20))
+        }));
+
+        let mut data = make_dex_header(b"035");
+        data[40..44].copy_from_slice(&0xDEAD_BEEFu32.to_le_bytes());
+        let result = parse(&data, detect(&data).unwrap()).unwrap();
+        assert_eq!(result.endianness, Endianness::Little);
+        assert!(result.metadata.warnings[0].contains("0xDEADBEEF"));
+    }
+
     #[test]
     fn test_parse_dex039() {
         let data = make_dex_header(b"039");
         let variant = detect(&data).unwrap();
         let result = parse(&data, variant).unwrap();
         assert_eq!(result.isa, Isa::Dalvik);
-        assert!(result.variant.name.contains("039"));
+        assert!(result.variant_name().unwrap().contains("039"));
+    }
+
+    /// Android 9 (version 056) boot image header.
+    fn make_art_header() -> Vec<u8> {
+        let mut data = vec![0u8; 512];
+        data[0..4].copy_from_slice(&ART_MAGIC);
+        data[4..8].copy_from_slice(b"056\0");
+        let fields: [(usize, u32); 8] = [
+            (8, 0x7000_0000),  // image_begin
+            (12, 0x0123_4000), // image_size
+            (16, 0xDEAD_BEEF), // oat_checksum
+            (20, 0x7123_4000), // oat_file_begin
+            (24, 0x7123_5000), // oat_data_begin
+            (28, 0x7345_6000), // oat_data_end
+            (32, 0x7346_0000), // oat_file_end
+            (60, 8),           // pointer_size
+        ];
+        for (offset, value) in fields {
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        data[52..56].copy_from_slice(&(-0x2000i32).to_le_bytes()); // patch_delta
+        data
+    }
+
+    #[test]
+    fn test_parse_art_header() {
+        let data = make_art_header();
+        let variant = detect(&data).unwrap();
+        assert!(matches!(variant, DexVariant::Art { .. }));
+        let result = parse(&data, variant).unwrap();
+        assert_eq!(result.isa, Isa::Dalvik);
+        assert_eq!(result.format, FileFormat::Art);
+        assert!(result.metadata.warnings.is_empty());
+        assert!(result.metadata.notes.iter().any(|n| n.contains("64-bit")));
+
+        let entry = |key: &str| {
+            result
+                .metadata
+                .custom
+                .iter()
+                .find(|e| e.key == crate::types::MetadataKey::Custom(key.to_string()))
+                .map(|e| e.value.clone())
+                .unwrap()
+        };
+        assert!(matches!(
+            entry("image_begin"),
+            MetadataValue::Address(0x7000_0000)
+        ));
+        assert!(matches!(
+            entry("oat_data_begin"),
+            MetadataValue::Address(0x7123_5000)
+        ));
+        assert!(matches!(
+            entry("oat_checksum"),
+            MetadataValue::Hex(0xDEAD_BEEF)
+        ));
+        assert!(matches!(entry("patch_delta"), MetadataValue::String(s) if s == "-0x2000"));
+        assert!(matches!(entry("pointer_size"), MetadataValue::Integer(8)));
+    }
+
+    #[test]
+    fn test_art_header_validation() {
+        let mut data = make_art_header();
+        // oat_data_end before oat_data_begin
+        data[28..32].copy_from_slice(&0x7000_0000u32.to_le_bytes());
+        let result = parse(&data, detect(&data).unwrap()).unwrap();
+        assert_eq!(result.metadata.warnings.len(), 1);
+
+        assert!(parse(&data[..32], detect(&data).unwrap()).is_err());
     }
 }
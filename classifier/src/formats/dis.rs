@@ -0,0 +1,460 @@
+//! Inferno Dis module parser.
+//!
+//! Dis is the virtual machine of the Inferno operating system (and of
+//! Plan 9's Inferno hosting). A compiled Limbo module is a `.dis` file:
+//! a header of variable-length operands, then the code, type and data
+//! sections, the NUL-terminated module name and the link section.
+//!
+//! Operands are big-endian and self-sizing; the top two bits of the first
+//! byte select the length:
+//! - `00`/`01`: one byte, a 7-bit signed value
+//! - `10`: two bytes, a 14-bit signed value
+//! - `11`: four bytes, a 30-bit signed value
+//!
+//! The module name sits after the data section, so reading it means
+//! walking every instruction, type descriptor and data item before it.
+
+use crate::error::{ClassifierError, Result};
+use crate::types::{
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
+};
+
+/// Magic of an unsigned module (`XMAGIC`).
+pub const XMAGIC: i32 = 819248;
+
+/// Magic of a signed module (`SMAGIC`), followed by the signature.
+pub const SMAGIC: i32 = 923426;
+
+/// Longest module name we accept.
+const MAX_NAME_LEN: usize = 256;
+
+/// Runtime flags in the header.
+pub mod runtime_flag {
+    /// Compile the module to native code on load.
+    pub const MUSTCOMPILE: i32 = 1 << 0;
+    /// Never compile the module to native code.
+    pub const DONTCOMPILE: i32 = 1 << 1;
+    /// Share the module data between instances.
+    pub const SHAREMP: i32 = 1 << 2;
+    /// Loaded as a dynamic module.
+    pub const DYNMOD: i32 = 1 << 3;
+    /// Has exception handlers.
+    pub const HASEXCEPT: i32 = 1 << 5;
+    /// Has an import table (`ldt`).
+    pub const HASLDT: i32 = 1 << 6;
+}
+
+/// Operand addressing modes of an instruction's source and destination.
+mod address {
+    /// Offset from the module pointer.
+    pub const AMP: u8 = 0x00;
+    /// Offset from the frame pointer.
+    pub const AFP: u8 = 0x01;
+    /// Immediate.
+    pub const AIMM: u8 = 0x02;
+    /// No operand.
+    pub const AXXX: u8 = 0x03;
+    /// Double indirect, combined with `AMP` or `AFP`.
+    pub const AIND: u8 = 0x04;
+    /// Mask of the middle operand mode; non-zero when one is present.
+    pub const ARM: u8 = 0xC0;
+}
+
+/// Data item types in the data section.
+mod data_type {
+    pub const DEFB: u8 = 1;
+    pub const DEFW: u8 = 2;
+    pub const DEFS: u8 = 3;
+    pub const DEFF: u8 = 4;
+    pub const DEFA: u8 = 5;
+    pub const DIND: u8 = 6;
+    pub const DAPOP: u8 = 7;
+    pub const DEFL: u8 = 8;
+}
+
+/// Fixed fields of a Dis module header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisHeader {
+    /// Whether the module carries a signature (`SMAGIC`).
+    pub signed: bool,
+    /// Runtime flags.
+    pub runtime_flag: i32,
+    /// Stack extent.
+    pub stack_extent: i32,
+    /// Number of instructions.
+    pub code_size: i32,
+    /// Size of the module data in bytes.
+    pub data_size: i32,
+    /// Number of type descriptors.
+    pub type_size: i32,
+    /// Number of exported functions.
+    pub link_size: i32,
+    /// Entry point, as an instruction index.
+    pub entry_pc: i32,
+    /// Type descriptor of the entry frame.
+    pub entry_type: i32,
+    /// Offset of the code section.
+    pub code_offset: usize,
+}
+
+/// Cursor over a Dis byte stream.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        let end = self.pos.checked_add(n)?;
+        (end <= self.data.len()).then(|| self.pos = end)
+    }
+
+    /// One variable-length operand.
+    fn operand(&mut self) -> Option<i32> {
+        let c = self.byte()?;
+        let high = |c: u8| {
+            let bits = i32::from(c & 0x3F);
+            if c & 0x20 != 0 {
+                bits - 0x40
+            } else {
+                bits
+            }
+        };
+        Some(match c & 0xC0 {
+            0x00 => i32::from(c),
+            0x40 => i32::from(c) - 0x80,
+            0x80 => (high(c) << 8) | i32::from(self.byte()?),
+            _ => {
+                let mut v = high(c);
+                for _ in 0..3 {
+                    v = (v << 8) | i32::from(self.byte()?);
+                }
+                v
+            }
+        })
+    }
+
+    /// A count operand, rejecting negative values.
+    fn count(&mut self) -> Option<usize> {
+        usize::try_from(self.operand()?).ok()
+    }
+
+    /// The operands of a source or destination in addressing mode `mode`.
+    fn address(&mut self, mode: u8) -> Option<()> {
+        match mode {
+            address::AMP | address::AFP | address::AIMM => {
+                self.operand()?;
+            }
+            address::AXXX => {}
+            m if m == address::AIND | address::AMP || m == address::AIND | address::AFP => {
+                self.operand()?;
+                self.operand()?;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Read the module header.
+pub fn header(data: &[u8]) -> Option<DisHeader> {
+    let mut r = Reader { data, pos: 0 };
+    let signed = match r.operand()? {
+        XMAGIC => false,
+        SMAGIC => {
+            let siglen = r.count()?;
+            r.skip(siglen)?;
+            true
+        }
+        _ => return None,
+    };
+    let header = DisHeader {
+        signed,
+        runtime_flag: r.operand()?,
+        stack_extent: r.operand()?,
+        code_size: r.operand()?,
+        data_size: r.operand()?,
+        type_size: r.operand()?,
+        link_size: r.operand()?,
+        entry_pc: r.operand()?,
+        entry_type: r.operand()?,
+        code_offset: r.pos,
+    };
+    let sizes = [
+        header.stack_extent,
+        header.code_size,
+        header.data_size,
+        header.type_size,
+        header.link_size,
+    ];
+    if sizes.iter().any(|&s| s < 0) {
+        return None;
+    }
+    // The loader accepts a module without an entry point (-1)
+    if header.entry_pc < -1 || header.entry_pc >= header.code_size.max(1) {
+        return None;
+    }
+    Some(header)
+}
+
+/// Detect a Dis module.
+pub fn detect(data: &[u8]) -> bool {
+    header(data).is_some()
+}
+
+/// Walk the code, type and data sections; returns the length of the code
+/// section and the module name.
+fn walk(data: &[u8], header: &DisHeader) -> Option<(usize, String)> {
+    let mut r = Reader {
+        data,
+        pos: header.code_offset,
+    };
+
+    for _ in 0..header.code_size {
+        r.byte()?; // opcode
+        let mode = r.byte()?;
+        if mode & address::ARM != 0 {
+            r.operand()?;
+        }
+        r.address((mode >> 3) & 0x07)?;
+        r.address(mode & 0x07)?;
+    }
+    let code_len = r.pos - header.code_offset;
+
+    for _ in 0..header.type_size {
+        r.operand()?; // descriptor number
+        r.operand()?; // frame size
+        let map_len = r.count()?;
+        r.skip(map_len)?;
+    }
+
+    loop {
+        let item = r.byte()?;
+        if item == 0 {
+            break;
+        }
+        let count = match item & 0x0F {
+            0 => r.count()?,
+            n => usize::from(n),
+        };
+        r.operand()?; // offset
+        let len = match item >> 4 {
+            data_type::DEFB | data_type::DEFS => count,
+            data_type::DEFW => count.checked_mul(4)?,
+            data_type::DEFF | data_type::DEFL => count.checked_mul(8)?,
+            data_type::DEFA => 8,
+            data_type::DIND => 4,
+            data_type::DAPOP => 0,
+            _ => return None,
+        };
+        r.skip(len)?;
+    }
+
+    let rest = &data[r.pos..];
+    let len = rest.iter().take(MAX_NAME_LEN).position(|&b| b == 0)?;
+    let name = std::str::from_utf8(&rest[..len]).ok()?;
+    Some((code_len, name.to_string()))
+}
+
+/// Parse a Dis module.
+pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
+    let header = header(data).ok_or_else(|| ClassifierError::InvalidMagic {
+        expected: "Dis module header".to_string(),
+        actual: format!("{:02X?}", &data[..data.len().min(4)]),
+    })?;
+
+    let mut notes = vec!["Inferno Dis module".to_string()];
+    if header.signed {
+        notes.push("Signed module".to_string());
+    }
+    notes.push(format!(
+        "Instructions: {}, types: {}, exports: {}",
+        header.code_size, header.type_size, header.link_size
+    ));
+    notes.push(format!("Data size: {} bytes", header.data_size));
+    let flags = [
+        (runtime_flag::MUSTCOMPILE, "must compile"),
+        (runtime_flag::DONTCOMPILE, "don't compile"),
+        (runtime_flag::SHAREMP, "shared data"),
+        (runtime_flag::DYNMOD, "dynamic module"),
+        (runtime_flag::HASEXCEPT, "exception handlers"),
+        (runtime_flag::HASLDT, "imports"),
+    ];
+    let set: Vec<&str> = flags
+        .iter()
+        .filter(|(bit, _)| header.runtime_flag & bit != 0)
+        .map(|&(_, name)| name)
+        .collect();
+    if !set.is_empty() {
+        notes.push(format!("Runtime flags: {}", set.join(", ")));
+    }
+
+    let mut metadata = ClassificationMetadata {
+        entry_point: u64::try_from(header.entry_pc).ok(),
+        header_size: Some(header.code_offset as u64),
+        flags: Some(header.runtime_flag as u32),
+        ..Default::default()
+    };
+    match walk(data, &header) {
+        Some((code_len, name)) => {
+            metadata.code_size = Some(code_len as u64);
+            notes.push(format!("Module: {name}"));
+            metadata.custom.push(MetadataEntry::custom(
+                "dis_module",
+                MetadataValue::String(name),
+                "Dis Module",
+            ));
+        }
+        None => metadata
+            .warnings
+            .push("Dis module sections are truncated or malformed".to_string()),
+    }
+    metadata.notes = notes;
+
+    let mut result =
+        ClassificationResult::from_format(Isa::Dis, 32, Endianness::Big, FileFormat::Dis);
+    if header.signed {
+        result.variant = Some(Variant::new("Signed"));
+    }
+    result.metadata = metadata;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MetadataKey;
+
+    /// Encode an operand in its shortest form.
+    fn op(v: i32) -> Vec<u8> {
+        if (-64..64).contains(&v) {
+            vec![(v & 0x7F) as u8]
+        } else if (-8192..8192).contains(&v) {
+            vec![0x80 | ((v >> 8) & 0x3F) as u8, v as u8]
+        } else {
+            let b = (v & 0x3FFF_FFFF).to_be_bytes();
+            vec![0xC0 | b[0], b[1], b[2], b[3]]
+        }
+    }
+
+    /// A module `Hello` with two instructions, one type and one string.
+    fn make_module(magic: i32) -> Vec<u8> {
+        let mut data = op(magic);
+        if magic == SMAGIC {
+            data.extend(op(3));
+            data.extend([0xAA, 0xBB, 0xCC]);
+        }
+        // runtime flag, stack, code, data, types, links, entry pc, entry type
+        for v in [runtime_flag::HASLDT, 300, 2, 16, 1, 1, 0, 0] {
+            data.extend(op(v));
+        }
+        // movw $1000, 16(fp): immediate source, frame pointer destination
+        data.extend([0x2D, (address::AIMM << 3) | address::AFP]);
+        data.extend(op(1000));
+        data.extend(op(16));
+        // ret
+        data.extend([0x0E, (address::AXXX << 3) | address::AXXX]);
+        // type 0: 16 bytes, one map byte
+        data.extend(op(0));
+        data.extend(op(16));
+        data.extend(op(1));
+        data.push(0x80);
+        // DEFS "hi" at offset 0, then the terminator
+        data.push((data_type::DEFS << 4) | 2);
+        data.extend(op(0));
+        data.extend(b"hi");
+        data.push(0);
+        data.extend(b"Hello\0");
+        // link: pc 0, type 0, signature, "init"
+        data.extend(op(0));
+        data.extend(op(0));
+        data.extend(0x1234_5678u32.to_be_bytes());
+        data.extend(b"init\0");
+        data
+    }
+
+    #[test]
+    fn test_operand_encoding() {
+        for v in [
+            0, 63, -1, -64, 64, -65, 8191, -8192, 8192, XMAGIC, SMAGIC, -1_000_000,
+        ] {
+            let bytes = op(v);
+            let mut r = Reader {
+                data: &bytes,
+                pos: 0,
+            };
+            assert_eq!(r.operand(), Some(v), "{v}");
+            assert_eq!(r.pos, bytes.len());
+        }
+        assert_eq!(op(XMAGIC), [0xC0, 0x0C, 0x80, 0x30]);
+    }
+
+    #[test]
+    fn test_detect_dis() {
+        assert!(detect(&make_module(XMAGIC)));
+        assert!(detect(&make_module(SMAGIC)));
+        assert!(!detect(&[0xC0, 0x0C, 0x80, 0x30]));
+        assert!(!detect(b"\x7FELF\x02\x01\x01\x00"));
+    }
+
+    #[test]
+    fn test_parse_dis() {
+        let result = parse(&make_module(XMAGIC)).unwrap();
+        assert_eq!(result.isa, Isa::Dis);
+        assert_eq!(result.format, FileFormat::Dis);
+        assert_eq!(result.endianness, Endianness::Big);
+        assert_eq!(result.metadata.entry_point, Some(0));
+        assert_eq!(result.metadata.code_size, Some(7));
+        assert!(result.variant.is_none());
+        let module = result
+            .metadata
+            .custom
+            .iter()
+            .find(|e| e.key == MetadataKey::Custom("dis_module".to_string()))
+            .unwrap();
+        assert!(matches!(&module.value, MetadataValue::String(name) if name == "Hello"));
+        assert!(result.metadata.notes.iter().any(|n| n == "Module: Hello"));
+
+        let signed = parse(&make_module(SMAGIC)).unwrap();
+        assert_eq!(signed.variant, Some(Variant::new("Signed")));
+        assert_eq!(signed.metadata.custom.len(), 1);
+    }
+
+    #[test]
+    fn test_runtime_flags_from_limbo_header() {
+        // Header words as limbo writes them for a module with imports and
+        // exception handlers: XMAGIC, then runtime flag 0x60 (two-byte form)
+        let mut data = vec![0xC0, 0x0C, 0x80, 0x30, 0x80, 0x60];
+        data.extend_from_slice(&make_module(XMAGIC)[6..]);
+        let result = parse(&data).unwrap();
+        assert_eq!(result.metadata.flags, Some(0x60));
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n == "Runtime flags: exception handlers, imports"));
+    }
+
+    #[test]
+    fn test_parse_truncated_dis() {
+        let data = make_module(XMAGIC);
+        let end = header(&data).unwrap().code_offset + 3;
+        let result = parse(&data[..end]).unwrap();
+        assert!(result.metadata.custom.is_empty());
+        assert_eq!(result.metadata.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_dis_classified() {
+        let result = crate::classify_bytes(&make_module(XMAGIC)).unwrap();
+        assert_eq!(result.isa, Isa::Dis);
+        assert_eq!(result.format, FileFormat::Dis);
+    }
+}
@@ -0,0 +1,498 @@
+//! ECMA-335 (.NET CLR) metadata parser.
+//!
+//! Managed PE images point their CLR runtime header at a metadata root
+//! (`BSJB`) holding the `#~` table stream and the `#Strings`/`#Blob` heaps.
+//! Only enough of the table stream is decoded to reach the Assembly row:
+//! the row sizes of every preceding table, which depend on the row counts
+//! and heap index widths, and then the version, public key and name.
+//!
+//! Every read is bounded by the metadata slice; malformed input yields
+//! `None` rather than an error.
+
+use crate::formats::{read_u16, read_u32, read_u64};
+
+/// Metadata root signature ("BSJB").
+pub const METADATA_MAGIC: u32 = 0x424A_5342;
+
+/// Assembly table number.
+const ASSEMBLY_TABLE: usize = 0x20;
+
+/// Identity of the assembly defined by a metadata root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssemblyInfo {
+    /// Simple assembly name
+    pub name: Option<String>,
+    /// Major, minor, build and revision numbers
+    pub version: [u16; 4],
+    /// Whether the assembly carries a public key (is strong-named)
+    pub strong_named: bool,
+}
+
+impl AssemblyInfo {
+    /// Dotted version string ("1.2.3.4").
+    pub fn version_string(&self) -> String {
+        let [major, minor, build, revision] = self.version;
+        format!("{major}.{minor}.{build}.{revision}")
+    }
+}
+
+/// Decoded metadata root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClrMetadata {
+    /// Runtime version string (e.g. "v4.0.30319")
+    pub runtime_version: String,
+    /// Assembly identity; `None` for netmodules, which have no Assembly row
+    pub assembly: Option<AssemblyInfo>,
+}
+
+/// Coded index kinds used by tables 0x00-0x1F.
+#[derive(Debug, Clone, Copy)]
+enum Coded {
+    TypeDefOrRef,
+    HasConstant,
+    HasCustomAttribute,
+    HasFieldMarshal,
+    HasDeclSecurity,
+    MemberRefParent,
+    HasSemantics,
+    MethodDefOrRef,
+    MemberForwarded,
+    CustomAttributeType,
+    ResolutionScope,
+}
+
+impl Coded {
+    /// Tag width and the tables the index can refer to.
+    fn layout(self) -> (u32, &'static [usize]) {
+        match self {
+            Coded::TypeDefOrRef => (2, &[0x02, 0x01, 0x1B]),
+            Coded::HasConstant => (2, &[0x04, 0x08, 0x17]),
+            Coded::HasCustomAttribute => (
+                5,
+                &[
+                    0x06, 0x04, 0x01, 0x02, 0x08, 0x09, 0x0A, 0x00, 0x0E, 0x17, 0x14, 0x11, 0x1A,
+                    0x1B, 0x20, 0x23, 0x26, 0x27, 0x28, 0x2A, 0x2C, 0x2B,
+                ],
+            ),
+            Coded::HasFieldMarshal => (1, &[0x04, 0x08]),
+            Coded::HasDeclSecurity => (2, &[0x02, 0x06, 0x20]),
+            Coded::MemberRefParent => (3, &[0x02, 0x01, 0x1A, 0x06, 0x1B]),
+            Coded::HasSemantics => (1, &[0x14, 0x17]),
+            Coded::MethodDefOrRef => (1, &[0x06, 0x0A]),
+            Coded::MemberForwarded => (1, &[0x04, 0x06]),
+            Coded::CustomAttributeType => (3, &[0x06, 0x0A]),
+            Coded::ResolutionScope => (2, &[0x00, 0x1A, 0x23, 0x01]),
+        }
+    }
+}
+
+/// A table column.
+#[derive(Debug, Clone, Copy)]
+enum Col {
+    /// Fixed-width field of this many bytes
+    Fixed(usize),
+    /// `#Strings` heap index
+    Str,
+    /// `#GUID` heap index
+    Guid,
+    /// `#Blob` heap index
+    Blob,
+    /// Simple index into a table
+    Table(usize),
+    /// Coded index
+    Coded(Coded),
+}
+
+use Col::{Blob, Fixed, Guid, Str, Table};
+
+/// Column layouts of tables 0x00-0x1F, the ones preceding Assembly.
+const SCHEMA: [&[Col]; ASSEMBLY_TABLE] = [
+    // Module
+    &[Fixed(2), Str, Guid, Guid, Guid],
+    // TypeRef
+    &[Col::Coded(Coded::ResolutionScope), Str, Str],
+    // TypeDef
+    &[
+        Fixed(4),
+        Str,
+        Str,
+        Col::Coded(Coded::TypeDefOrRef),
+        Table(0x04),
+        Table(0x06),
+    ],
+    // FieldPtr
+    &[Table(0x04)],
+    // Field
+    &[Fixed(2), Str, Blob],
+    // MethodPtr
+    &[Table(0x06)],
+    // MethodDef
+    &[Fixed(4), Fixed(2), Fixed(2), Str, Blob, Table(0x08)],
+    // ParamPtr
+    &[Table(0x08)],
+    // Param
+    &[Fixed(2), Fixed(2), Str],
+    // InterfaceImpl
+    &[Table(0x02), Col::Coded(Coded::TypeDefOrRef)],
+    // MemberRef
+    &[Col::Coded(Coded::MemberRefParent), Str, Blob],
+    // Constant
+    &[Fixed(2), Col::Coded(Coded::HasConstant), Blob],
+    // CustomAttribute
+    &[
+        Col::Coded(Coded::HasCustomAttribute),
+        Col::Coded(Coded::CustomAttributeType),
+        Blob,
+    ],
+    // FieldMarshal
+    &[Col::Coded(Coded::HasFieldMarshal), Blob],
+    // DeclSecurity
+    &[Fixed(2), Col::Coded(Coded::HasDeclSecurity), Blob],
+    // ClassLayout
+    &[Fixed(2), Fixed(4), Table(0x02)],
+    // FieldLayout
+    &[Fixed(4), Table(0x04)],
+    // StandAloneSig
+    &[Blob],
+    // EventMap
+    &[Table(0x02), Table(0x14)],
+    // EventPtr
+    &[Table(0x14)],
+    // Event
+    &[Fixed(2), Str, Col::Coded(Coded::TypeDefOrRef)],
+    // PropertyMap
+    &[Table(0x02), Table(0x17)],
+    // PropertyPtr
+    &[Table(0x17)],
+    // Property
+    &[Fixed(2), Str, Blob],
+    // MethodSemantics
+    &[Fixed(2), Table(0x06), Col::Coded(Coded::HasSemantics)],
+    // MethodImpl
+    &[
+        Table(0x02),
+        Col::Coded(Coded::MethodDefOrRef),
+        Col::Coded(Coded::MethodDefOrRef),
+    ],
+    // ModuleRef
+    &[Str],
+    // TypeSpec
+    &[Blob],
+    // ImplMap
+    &[
+        Fixed(2),
+        Col::Coded(Coded::MemberForwarded),
+        Str,
+        Table(0x1A),
+    ],
+    // FieldRVA
+    &[Fixed(4), Table(0x04)],
+    // EncLog
+    &[Fixed(4), Fixed(4)],
+    // EncMap
+    &[Fixed(4)],
+];
+
+/// Row counts and heap widths that determine column sizes.
+struct Layout {
+    heap_sizes: u8,
+    rows: [u32; 64],
+}
+
+impl Layout {
+    fn col_size(&self, col: Col) -> usize {
+        let wide_heap = |bit: u8| if self.heap_sizes & bit != 0 { 4 } else { 2 };
+        match col {
+            Fixed(n) => n,
+            Str => wide_heap(0x01),
+            Guid => wide_heap(0x02),
+            Blob => wide_heap(0x04),
+            Table(t) => {
+                if self.rows[t] < 1 << 16 {
+                    2
+                } else {
+                    4
+                }
+            }
+            Col::Coded(kind) => {
+                let (tag_bits, tables) = kind.layout();
+                let max = tables.iter().map(|&t| self.rows[t]).max().unwrap_or(0);
+                if max < 1 << (16 - tag_bits) {
+                    2
+                } else {
+                    4
+                }
+            }
+        }
+    }
+
+    fn row_size(&self, table: usize) -> usize {
+        SCHEMA[table].iter().map(|&c| self.col_size(c)).sum()
+    }
+}
+
+/// Heap index of the given width.
+fn index_at(data: &[u8], offset: usize, width: usize) -> Option<usize> {
+    if width == 4 {
+        read_u32(data, offset, true).ok().map(|v| v as usize)
+    } else {
+        read_u16(data, offset, true).ok().map(usize::from)
+    }
+}
+
+/// NUL-terminated UTF-8 string from the `#Strings` heap.
+fn heap_string(heap: &[u8], index: usize) -> Option<String> {
+    let tail = heap.get(index..)?;
+    let end = tail.iter().position(|&b| b == 0)?;
+    std::str::from_utf8(&tail[..end]).ok().map(str::to_string)
+}
+
+/// Length of the `#Blob` heap entry at `index` (ECMA-335 II.24.2.4).
+fn blob_len(heap: &[u8], index: usize) -> Option<usize> {
+    let first = *heap.get(index)?;
+    let len = if first & 0x80 == 0 {
+        usize::from(first)
+    } else if first & 0xC0 == 0x80 {
+        usize::from(first & 0x3F) << 8 | usize::from(*heap.get(index + 1)?)
+    } else if first & 0xE0 == 0xC0 {
+        let rest = heap.get(index + 1..index + 4)?;
+        (usize::from(first & 0x1F) << 24)
+            | (usize::from(rest[0]) << 16)
+            | (usize::from(rest[1]) << 8)
+            | usize::from(rest[2])
+    } else {
+        return None;
+    };
+    Some(len)
+}
+
+/// Parse the metadata root at the start of `root`.
+pub fn parse_metadata(root: &[u8]) -> Option<ClrMetadata> {
+    if read_u32(root, 0, true).ok()? != METADATA_MAGIC {
+        return None;
+    }
+    let version_len = read_u32(root, 12, true).ok()? as usize;
+    let version = root.get(16..16usize.checked_add(version_len)?)?;
+    let runtime_version =
+        heap_string(version, 0).unwrap_or_else(|| String::from_utf8_lossy(version).into_owned());
+
+    // Stream headers follow the 4-byte-padded version string
+    let mut offset = 16 + ((version_len + 3) & !3);
+    let stream_count = read_u16(root, offset + 2, true).ok()?;
+    offset += 4;
+
+    let (mut tables, mut strings, mut blobs) = (None, None, None);
+    for _ in 0..stream_count {
+        let stream_offset = read_u32(root, offset, true).ok()? as usize;
+        let stream_size = read_u32(root, offset + 4, true).ok()? as usize;
+        let name_field = root.get(offset + 8..)?;
+        let name_len = name_field.iter().take(32).position(|&b| b == 0)?;
+        let stream = root.get(stream_offset..stream_offset.checked_add(stream_size)?)?;
+        match &name_field[..name_len] {
+            b"#~" | b"#-" => tables = Some(stream),
+            b"#Strings" => strings = Some(stream),
+            b"#Blob" => blobs = Some(stream),
+            _ => {}
+        }
+        offset += 8 + ((name_len + 4) & !3);
+    }
+
+    let assembly = parse_assembly(tables?, strings.unwrap_or(&[]), blobs.unwrap_or(&[]));
+    Some(ClrMetadata {
+        runtime_version,
+        assembly,
+    })
+}
+
+/// Decode the Assembly row of a `#~` stream.
+fn parse_assembly(tables: &[u8], strings: &[u8], blobs: &[u8]) -> Option<AssemblyInfo> {
+    let heap_sizes = *tables.get(6)?;
+    let valid = read_u64(tables, 8, true).ok()?;
+    if valid & (1 << ASSEMBLY_TABLE) == 0 {
+        return None;
+    }
+
+    let mut layout = Layout {
+        heap_sizes,
+        rows: [0; 64],
+    };
+    let mut offset = 24;
+    for table in (0..64).filter(|t| valid & (1 << t) != 0) {
+        layout.rows[table] = read_u32(tables, offset, true).ok()?;
+        offset += 4;
+    }
+    // Uncompressed (#-) streams may carry an extra dword here
+    if heap_sizes & 0x40 != 0 {
+        offset += 4;
+    }
+
+    for table in 0..ASSEMBLY_TABLE {
+        let size = layout
+            .row_size(table)
+            .checked_mul(layout.rows[table] as usize)?;
+        offset = offset.checked_add(size)?;
+    }
+
+    // HashAlgId, four version numbers, Flags, PublicKey, Name, Culture
+    let version = [
+        read_u16(tables, offset + 4, true).ok()?,
+        read_u16(tables, offset + 6, true).ok()?,
+        read_u16(tables, offset + 8, true).ok()?,
+        read_u16(tables, offset + 10, true).ok()?,
+    ];
+    let blob_width = layout.col_size(Blob);
+    let public_key = index_at(tables, offset + 16, blob_width)?;
+    let name = index_at(tables, offset + 16 + blob_width, layout.col_size(Str))?;
+
+    Some(AssemblyInfo {
+        name: heap_string(strings, name).filter(|n| !n.is_empty()),
+        version,
+        strong_named: public_key != 0 && blob_len(blobs, public_key).is_some_and(|len| len > 0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metadata root with a Module row, one TypeDef row and an Assembly
+    /// row named "Demo" at version 1.2.3.4, optionally with a public key.
+    fn metadata_root(public_key: bool) -> Vec<u8> {
+        let strings = b"\0Demo\0".to_vec();
+        let blobs = vec![0u8, 4, 0xDE, 0xAD, 0xBE, 0xEF];
+
+        let mut tables = vec![0u8; 24];
+        tables[4] = 2; // major version
+        let valid: u64 = 1 | 1 << 0x02 | 1 << ASSEMBLY_TABLE;
+        tables[8..16].copy_from_slice(&valid.to_le_bytes());
+        for rows in [1u32, 1, 1] {
+            tables.extend_from_slice(&rows.to_le_bytes());
+        }
+        // Module: Generation, Name, Mvid, EncId, EncBaseId
+        tables.extend_from_slice(&[0; 10]);
+        // TypeDef: Flags, Name, Namespace, Extends, FieldList, MethodList
+        tables.extend_from_slice(&[0; 14]);
+        // Assembly
+        tables.extend_from_slice(&0x8004u32.to_le_bytes());
+        for v in [1u16, 2, 3, 4] {
+            tables.extend_from_slice(&v.to_le_bytes());
+        }
+        tables.extend_from_slice(&1u32.to_le_bytes());
+        tables.extend_from_slice(&u16::from(public_key).to_le_bytes());
+        tables.extend_from_slice(&1u16.to_le_bytes());
+        tables.extend_from_slice(&0u16.to_le_bytes());
+
+        let version = b"v4.0.30319\0\0";
+        let streams: [(&[u8], &[u8]); 3] = [
+            (b"#~\0\0", &tables),
+            (b"#Strings\0\0\0\0", &strings),
+            (b"#Blob\0\0\0", &blobs),
+        ];
+        let headers_len: usize = streams.iter().map(|(name, _)| 8 + name.len()).sum();
+
+        let mut root = Vec::new();
+        root.extend_from_slice(&METADATA_MAGIC.to_le_bytes());
+        root.extend_from_slice(&[1, 0, 1, 0, 0, 0, 0, 0]);
+        root.extend_from_slice(&(version.len() as u32).to_le_bytes());
+        root.extend_from_slice(version);
+        root.extend_from_slice(&[0, 0]);
+        root.extend_from_slice(&(streams.len() as u16).to_le_bytes());
+
+        let mut data_offset = root.len() + headers_len;
+        for (name, body) in &streams {
+            root.extend_from_slice(&(data_offset as u32).to_le_bytes());
+            root.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            root.extend_from_slice(name);
+            data_offset += body.len();
+        }
+        for (_, body) in &streams {
+            root.extend_from_slice(body);
+        }
+        root
+    }
+
+    #[test]
+    fn test_parse_assembly_identity() {
+        let metadata = parse_metadata(&metadata_root(true)).unwrap();
+        assert_eq!(metadata.runtime_version, "v4.0.30319");
+        let assembly = metadata.assembly.unwrap();
+        assert_eq!(assembly.name.as_deref(), Some("Demo"));
+        assert_eq!(assembly.version_string(), "1.2.3.4");
+        assert!(assembly.strong_named);
+
+        let unsigned = parse_metadata(&metadata_root(false)).unwrap();
+        assert!(!unsigned.assembly.unwrap().strong_named);
+    }
+
+    #[test]
+    fn test_truncated_metadata_is_rejected() {
+        let root = metadata_root(true);
+        assert!(parse_metadata(&root[..40]).is_none());
+        // Cutting into the tables stream drops the assembly, never panics
+        for len in 0..root.len() {
+            let _ = parse_metadata(&root[..len]);
+        }
+        assert!(parse_metadata(b"BSJA\0\0\0\0").is_none());
+    }
+
+    #[test]
+    fn test_pe_reports_assembly_metadata() {
+        use crate::types::MetadataValue;
+
+        // PE32 image: one section at RVA 0x1000 / file 0x200 holding the
+        // CLR runtime header followed by the metadata root
+        let root = metadata_root(true);
+        let mut data = vec![0u8; 0x200];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3C] = 0x40;
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+        data[0x44..0x46].copy_from_slice(&0x014Cu16.to_le_bytes());
+        data[0x46] = 1; // one section
+        data[0x54..0x56].copy_from_slice(&0xE0u16.to_le_bytes());
+        let opt = 0x58;
+        data[opt..opt + 2].copy_from_slice(&0x10Bu16.to_le_bytes());
+        data[opt + 92..opt + 96].copy_from_slice(&16u32.to_le_bytes());
+        data[opt + 96 + 14 * 8..opt + 100 + 14 * 8].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[opt + 100 + 14 * 8] = 0x48;
+
+        let section = opt + 0xE0;
+        let raw_len = (0x48 + root.len()) as u32;
+        data[section..section + 8].copy_from_slice(b".text\0\0\0");
+        data[section + 8..section + 12].copy_from_slice(&raw_len.to_le_bytes());
+        data[section + 12..section + 16].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[section + 16..section + 20].copy_from_slice(&raw_len.to_le_bytes());
+        data[section + 20..section + 24].copy_from_slice(&0x200u32.to_le_bytes());
+
+        let mut cor20 = vec![0u8; 0x48];
+        cor20[..4].copy_from_slice(&0x48u32.to_le_bytes());
+        cor20[8..12].copy_from_slice(&0x1048u32.to_le_bytes());
+        cor20[12..16].copy_from_slice(&(root.len() as u32).to_le_bytes());
+        data.extend_from_slice(&cor20);
+        data.extend_from_slice(&root);
+
+        let result = crate::formats::pe::parse(&data, 0x40).unwrap();
+        let value = |key: &str| {
+            result
+                .metadata
+                .custom
+                .iter()
+                .find(|e| e.label == key)
+                .map(|e| e.value.to_string())
+        };
+        assert_eq!(value("Assembly Version").as_deref(), Some("1.2.3.4"));
+        assert_eq!(value("Assembly Name").as_deref(), Some("Demo"));
+        assert!(result
+            .metadata
+            .custom
+            .iter()
+            .any(|e| e.label == "Strong-Named" && matches!(e.value, MetadataValue::Bool(true))));
+        assert!(result.metadata.notes.iter().any(|n| n.contains(".NET")));
+
+        // A metadata RVA outside every section is reported, not fatal
+        data[0x200 + 8] = 0x00;
+        data[0x200 + 9] = 0x80;
+        let result = crate::formats::pe::parse(&data, 0x40).unwrap();
+        assert!(result.metadata.custom.is_empty());
+        assert!(!result.metadata.warnings.is_empty());
+    }
+}
@@ -8,9 +8,10 @@
 //! but legacy binaries still exist.
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::{read_u16, read_u32, read_u64};
+use crate::formats::{read_cstr, read_u16, read_u32, read_u64};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
 /// ECOFF magic numbers.
@@ -84,6 +85,118 @@ pub const ECOFF_MIPS_AOUT_SIZE: usize = 56;
 /// ECOFF Alpha optional header size.
 pub const ECOFF_ALPHA_AOUT_SIZE: usize = 80;
 
+/// Symbolic header (HDRR) magic values.
+pub mod hdrr_magic {
+    /// MIPS symbolic header (magicSym)
+    pub const MIPS: u16 = 0x7009;
+    /// Alpha symbolic header (magicSym2)
+    pub const ALPHA: u16 = 0x1992;
+}
+
+/// Most file descriptor names reported in metadata.
+pub const MAX_SOURCE_FILES: usize = 4;
+
+/// Most compiler version strings reported in metadata.
+const MAX_COMPILER_STRINGS: usize = 2;
+
+/// Longest string read from the local string space.
+const MAX_STRING_LEN: usize = 256;
+
+/// Compiler names that open the version strings DEC-era toolchains leave in
+/// the string space.
+const COMPILER_MARKERS: &[&str] = &[
+    "DEC C",
+    "DEC Fortran",
+    "DEC Pascal",
+    "DEC Ada",
+    "DEC COBOL",
+    "DIGITAL C",
+    "DIGITAL Fortran",
+    "Compaq C",
+    "Compaq Fortran",
+    "MIPSpro",
+    "GNU C",
+];
+
+/// Field offsets of the symbolic header and file descriptor for one variant.
+struct HdrrLayout {
+    magic: u16,
+    ipd_max: usize,
+    isym_max: usize,
+    iss_max: usize,
+    ifd_max: usize,
+    iext_max: usize,
+    cb_line_offset: usize,
+    cb_dn_offset: usize,
+    cb_ss_offset: usize,
+    cb_fd_offset: usize,
+    /// File offsets are 64-bit on Alpha
+    wide_offsets: bool,
+    fdr_size: usize,
+    fdr_rss: usize,
+    fdr_iss_base: usize,
+}
+
+const MIPS_HDRR: HdrrLayout = HdrrLayout {
+    magic: hdrr_magic::MIPS,
+    ipd_max: 24,
+    isym_max: 32,
+    iss_max: 56,
+    ifd_max: 72,
+    iext_max: 88,
+    cb_line_offset: 12,
+    cb_dn_offset: 20,
+    cb_ss_offset: 60,
+    cb_fd_offset: 76,
+    wide_offsets: false,
+    fdr_size: 72,
+    fdr_rss: 4,
+    fdr_iss_base: 8,
+};
+
+const ALPHA_HDRR: HdrrLayout = HdrrLayout {
+    magic: hdrr_magic::ALPHA,
+    ipd_max: 12,
+    isym_max: 16,
+    iss_max: 28,
+    ifd_max: 36,
+    iext_max: 44,
+    cb_line_offset: 56,
+    cb_dn_offset: 64,
+    cb_ss_offset: 104,
+    cb_fd_offset: 120,
+    wide_offsets: true,
+    fdr_size: 96,
+    fdr_rss: 32,
+    fdr_iss_base: 36,
+};
+
+/// Counts and table locations from the ECOFF symbolic header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolicHeader {
+    /// Procedure descriptors (ipdMax)
+    pub procedures: u32,
+    /// Local symbols (isymMax)
+    pub local_symbols: u32,
+    /// External symbols (iextMax)
+    pub external_symbols: u32,
+    /// File descriptors, one per source file (ifdMax)
+    pub file_descriptors: u32,
+    /// Size of the local string space in bytes (issMax)
+    pub strings_size: u32,
+    /// File offset of the local string space (cbSsOffset)
+    pub strings_offset: u64,
+    /// File offset of the file descriptor table (cbFdOffset)
+    pub fd_offset: u64,
+}
+
+impl SymbolicHeader {
+    /// Local and external symbols together.
+    pub fn symbol_count(&self) -> u64 {
+        u64::from(self.local_symbols) + u64::from(self.external_symbols)
+    }
+}
+
 /// ECOFF variant types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EcoffVariant {
@@ -150,6 +263,14 @@ impl EcoffVariant {
             EcoffVariant::Alpha => "Alpha",
         }
     }
+
+    /// Symbolic header layout for this variant.
+    fn hdrr_layout(&self) -> &'static HdrrLayout {
+        match self {
+            EcoffVariant::MipsLe | EcoffVariant::MipsBe => &MIPS_HDRR,
+            EcoffVariant::Alpha => &ALPHA_HDRR,
+        }
+    }
 }
 
 /// Detect ECOFF variant from raw bytes.
@@ -177,6 +298,174 @@ pub fn detect(data: &[u8]) -> Option<EcoffVariant> {
     None
 }
 
+/// Read the symbolic header at `offset` (the file header's `f_symptr`).
+///
+/// Returns `None` when the magic does not match, a count is negative, or
+/// the line/dense-number table offsets point past the end of the file.
+pub fn symbolic_header(
+    data: &[u8],
+    variant: EcoffVariant,
+    offset: usize,
+) -> Option<SymbolicHeader> {
+    let layout = variant.hdrr_layout();
+    let le = variant.is_little_endian();
+    if offset == 0 || read_u16(data, offset, le).ok()? != layout.magic {
+        return None;
+    }
+
+    let count = |field: usize| {
+        read_u32(data, offset + field, le)
+            .ok()
+            .filter(|&n| i32::try_from(n).is_ok())
+    };
+    let file_offset = |field: usize| {
+        if layout.wide_offsets {
+            read_u64(data, offset + field, le).ok()
+        } else {
+            read_u32(data, offset + field, le).ok().map(u64::from)
+        }
+    };
+
+    let len = data.len() as u64;
+    if file_offset(layout.cb_line_offset)? > len || file_offset(layout.cb_dn_offset)? > len {
+        return None;
+    }
+
+    Some(SymbolicHeader {
+        procedures: count(layout.ipd_max)?,
+        local_symbols: count(layout.isym_max)?,
+        external_symbols: count(layout.iext_max)?,
+        file_descriptors: count(layout.ifd_max)?,
+        strings_size: count(layout.iss_max)?,
+        strings_offset: file_offset(layout.cb_ss_offset)?,
+        fd_offset: file_offset(layout.cb_fd_offset)?,
+    })
+}
+
+/// The part of the local string space present in `data`.
+fn string_space<'a>(data: &'a [u8], header: &SymbolicHeader) -> &'a [u8] {
+    let start = usize::try_from(header.strings_offset).map_or(data.len(), |o| o.min(data.len()));
+    let end = start
+        .saturating_add(header.strings_size as usize)
+        .min(data.len());
+    &data[start..end]
+}
+
+/// Names of the first `max` source files in the file descriptor table.
+///
+/// Descriptors or names cut off by a truncated file are skipped.
+pub fn source_files(
+    data: &[u8],
+    variant: EcoffVariant,
+    header: &SymbolicHeader,
+    max: usize,
+) -> Vec<String> {
+    let layout = variant.hdrr_layout();
+    let le = variant.is_little_endian();
+    let strings = string_space(data, header);
+    let Ok(table) = usize::try_from(header.fd_offset) else {
+        return Vec::new();
+    };
+
+    (0..header.file_descriptors as usize)
+        .take(max)
+        .filter_map(|i| {
+            let entry = table.checked_add(i.checked_mul(layout.fdr_size)?)?;
+            let rss = read_u32(data, entry + layout.fdr_rss, le).ok()?;
+            let iss_base = read_u32(data, entry + layout.fdr_iss_base, le).ok()?;
+            let index = (iss_base as usize).checked_add(rss as usize)?;
+            let name = read_cstr(strings, index, MAX_STRING_LEN).ok()?;
+            (!name.is_empty()).then(|| String::from_utf8_lossy(name).into_owned())
+        })
+        .collect()
+}
+
+/// Compiler version strings (e.g. `DEC C V5.9-005`) in the local string space.
+pub fn compiler_versions(data: &[u8], header: &SymbolicHeader) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for raw in string_space(data, header).split(|&b| b == 0) {
+        let Ok(text) = std::str::from_utf8(&raw[..raw.len().min(MAX_STRING_LEN)]) else {
+            continue;
+        };
+        let text = text.trim_start_matches("@(#)").trim();
+        let versioned = text.bytes().any(|b| b.is_ascii_digit());
+        if versioned
+            && COMPILER_MARKERS.iter().any(|m| text.starts_with(m))
+            && !found.iter().any(|f| f == text)
+        {
+            found.push(text.to_string());
+            if found.len() == MAX_COMPILER_STRINGS {
+                break;
+            }
+        }
+    }
+    found
+}
+
+/// Source language implied by a file name's extension.
+pub fn source_language(name: &str) -> Option<&'static str> {
+    let (_, ext) = name.rsplit_once('.')?;
+    // Upper-case .C is C++ on the UNIX systems that used ECOFF
+    if ext == "C" {
+        return Some("C++");
+    }
+    match ext.to_ascii_lowercase().as_str() {
+        "c" | "h" => Some("C"),
+        "cc" | "cxx" | "cpp" | "hxx" => Some("C++"),
+        "f" | "for" | "f77" | "f90" => Some("Fortran"),
+        "p" | "pas" => Some("Pascal"),
+        "ada" | "adb" | "ads" => Some("Ada"),
+        "cob" | "cbl" => Some("COBOL"),
+        "s" => Some("Assembly"),
+        _ => None,
+    }
+}
+
+/// Metadata entries describing the symbolic header's contents.
+fn symbolic_metadata(
+    data: &[u8],
+    variant: EcoffVariant,
+    header: &SymbolicHeader,
+) -> Vec<MetadataEntry> {
+    let mut custom = vec![MetadataEntry::custom(
+        "procedure_count",
+        MetadataValue::Integer(u64::from(header.procedures)),
+        "Procedures",
+    )];
+
+    let files = source_files(data, variant, header, MAX_SOURCE_FILES);
+    let mut languages: Vec<&str> = Vec::new();
+    for language in files.iter().filter_map(|f| source_language(f)) {
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    if !files.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "source_files",
+            MetadataValue::String(files.join(", ")),
+            "Source Files",
+        ));
+    }
+    if !languages.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "source_languages",
+            MetadataValue::String(languages.join(", ")),
+            "Source Languages",
+        ));
+    }
+
+    let compilers = compiler_versions(data, header);
+    if !compilers.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "compiler",
+            MetadataValue::String(compilers.join("; ")),
+            "Compiler",
+        ));
+    }
+    custom
+}
+
 /// Parse an ECOFF file.
 pub fn parse(data: &[u8], variant: EcoffVariant) -> Result<ClassificationResult> {
     if data.len() < ECOFF_HEADER_SIZE {
@@ -190,11 +479,12 @@ pub fn parse(data: &[u8], variant: EcoffVariant) -> Result<ClassificationResult>
     let le = variant.is_little_endian();
 
     // Parse file header
-    let _magic = read_u16(data, 0, le)?;
+    let magic = read_u16(data, 0, le)?;
     let num_sections = read_u16(data, 2, le)?;
     let _timestamp = read_u32(data, 4, le)?;
-    let _sym_offset = read_u32(data, 8, le)?;
-    let num_symbols = read_u32(data, 12, le)?;
+    let sym_offset = read_u32(data, 8, le)?;
+    // f_nsyms holds the symbolic header's size, not a symbol count
+    let _hdrr_size = read_u32(data, 12, le)?;
     let opt_header_size = read_u16(data, 16, le)?;
     let flags = read_u16(data, 18, le)?;
 
@@ -257,20 +547,27 @@ pub fn parse(data: &[u8], variant: EcoffVariant) -> Result<ClassificationResult>
 
     // Build variant info
     let var = match variant {
-        EcoffVariant::MipsLe => Variant::new("MIPS-LE"),
-        EcoffVariant::MipsBe => Variant::new("MIPS-BE"),
-        EcoffVariant::Alpha => Variant::default(),
+        EcoffVariant::MipsLe => Some(Variant::new("MIPS-LE")),
+        EcoffVariant::MipsBe => Some(Variant::new("MIPS-BE")),
+        EcoffVariant::Alpha => None,
     };
 
+    let symbolic = symbolic_header(data, variant, sym_offset as usize);
+    let custom = symbolic
+        .as_ref()
+        .map(|header| symbolic_metadata(data, variant, header))
+        .unwrap_or_default();
+
     let metadata = ClassificationMetadata {
         entry_point,
         section_count: Some(num_sections as usize),
-        symbol_count: if num_symbols > 0 {
-            Some(num_symbols as usize)
-        } else {
-            None
-        },
+        raw_machine: Some(u32::from(magic)),
+        symbol_count: symbolic
+            .map(|h| h.symbol_count())
+            .filter(|&n| n > 0)
+            .and_then(|n| usize::try_from(n).ok()),
         notes,
+        custom,
         ..Default::default()
     };
 
@@ -441,6 +738,118 @@ mod tests {
         assert_eq!(result.metadata.section_count, Some(6));
     }
 
+    /// Append a symbolic header describing `main.c` and `util.f`, with a
+    /// DEC C version string in the local string space.
+    fn with_symbolic_header(mut data: Vec<u8>, variant: EcoffVariant) -> Vec<u8> {
+        let layout = variant.hdrr_layout();
+        let le = variant.is_little_endian();
+        let put32 = |data: &mut Vec<u8>, off: usize, v: u32| {
+            let bytes = if le { v.to_le_bytes() } else { v.to_be_bytes() };
+            data[off..off + 4].copy_from_slice(&bytes);
+        };
+        let put_offset = |data: &mut Vec<u8>, off: usize, v: u32| {
+            if layout.wide_offsets {
+                data[off..off + 8].copy_from_slice(&u64::from(v).to_le_bytes());
+            } else {
+                put32(data, off, v);
+            }
+        };
+
+        let hdrr = data.len();
+        let hdrr_size = if layout.wide_offsets { 144 } else { 96 };
+        let fd_off = hdrr + hdrr_size;
+        let ss_off = fd_off + 2 * layout.fdr_size;
+        let strings = b"\0main.c\0util.f\0@(#)DEC C V5.9-005 on Digital UNIX V4.0\0";
+        data.resize(ss_off + strings.len(), 0);
+        data[ss_off..].copy_from_slice(strings);
+
+        put32(&mut data, 8, hdrr as u32); // f_symptr
+        let magic = if le {
+            layout.magic.to_le_bytes()
+        } else {
+            layout.magic.to_be_bytes()
+        };
+        data[hdrr..hdrr + 2].copy_from_slice(&magic);
+        put32(&mut data, hdrr + layout.ipd_max, 3);
+        put32(&mut data, hdrr + layout.isym_max, 10);
+        put32(&mut data, hdrr + layout.iext_max, 5);
+        put32(&mut data, hdrr + layout.ifd_max, 2);
+        put32(&mut data, hdrr + layout.iss_max, strings.len() as u32);
+        put_offset(&mut data, hdrr + layout.cb_ss_offset, ss_off as u32);
+        put_offset(&mut data, hdrr + layout.cb_fd_offset, fd_off as u32);
+
+        // Each file's strings start at issBase; rss indexes within them
+        for (i, (iss_base, rss)) in [(0u32, 1u32), (7, 1)].into_iter().enumerate() {
+            let entry = fd_off + i * layout.fdr_size;
+            put32(&mut data, entry + layout.fdr_iss_base, iss_base);
+            put32(&mut data, entry + layout.fdr_rss, rss);
+        }
+        data
+    }
+
+    fn custom_value(result: &ClassificationResult, key: &str) -> Option<String> {
+        result
+            .metadata
+            .custom
+            .iter()
+            .find(|e| e.key == crate::types::MetadataKey::Custom(key.to_string()))
+            .map(|e| e.value.to_string())
+    }
+
+    #[test]
+    fn test_symbolic_header() {
+        for variant in [EcoffVariant::MipsBe, EcoffVariant::Alpha] {
+            let data = with_symbolic_header(make_ecoff_header(variant, 3, 0), variant);
+            let result = parse(&data, variant).unwrap();
+            assert_eq!(result.metadata.symbol_count, Some(15));
+            assert_eq!(
+                custom_value(&result, "procedure_count").as_deref(),
+                Some("3")
+            );
+            assert_eq!(
+                custom_value(&result, "source_files").as_deref(),
+                Some("main.c, util.f")
+            );
+            assert_eq!(
+                custom_value(&result, "source_languages").as_deref(),
+                Some("C, Fortran")
+            );
+            assert_eq!(
+                custom_value(&result, "compiler").as_deref(),
+                Some("DEC C V5.9-005 on Digital UNIX V4.0")
+            );
+        }
+    }
+
+    #[test]
+    fn test_symbolic_header_truncated() {
+        let variant = EcoffVariant::Alpha;
+        let data = with_symbolic_header(make_ecoff_header(variant, 3, 0), variant);
+
+        // Cut inside the second file name: the first is still reported
+        let cut = data.windows(4).position(|w| w == b"util").unwrap() + 3;
+        let result = parse(&data[..cut], variant).unwrap();
+        assert_eq!(
+            custom_value(&result, "source_files").as_deref(),
+            Some("main.c, uti")
+        );
+        assert_eq!(custom_value(&result, "compiler"), None);
+
+        // Without the symbolic header there are no counts to report
+        let result = parse(&data[..256], variant).unwrap();
+        assert_eq!(result.metadata.symbol_count, None);
+        assert!(result.metadata.custom.is_empty());
+    }
+
+    #[test]
+    fn test_source_language() {
+        assert_eq!(source_language("main.c"), Some("C"));
+        assert_eq!(source_language("Shape.C"), Some("C++"));
+        assert_eq!(source_language("solver.F"), Some("Fortran"));
+        assert_eq!(source_language("crt0.s"), Some("Assembly"));
+        assert_eq!(source_language("Makefile"), None);
+    }
+
     #[test]
     fn test_variant_properties() {
         assert_eq!(EcoffVariant::MipsLe.isa(), Isa::Mips);
@@ -3,11 +3,13 @@
 //! Comprehensive ELF parser supporting all e_machine values and
 //! architecture-specific e_flags parsing.
 
+use crate::architectures::arm;
 use crate::error::{ClassifierError, Result};
-use crate::formats::{read_u16, read_u32, read_u64};
+use crate::formats::{read_cstr, read_u16, read_u32, read_u64, Absent};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, Extension, ExtensionCategory,
-    FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, ClassificationSource, ClassifierOptions,
+    Endianness, Extension, ExtensionCategory, FileFormat, HardeningSummary, Isa, MetadataEntry,
+    MetadataValue, SectionInfo, SegmentInfo, Variant,
 };
 
 /// ELF class values (32-bit vs 64-bit)
@@ -22,6 +24,1017 @@ pub mod data {
     pub const ELFDATA2MSB: u8 = 2; // Big-endian
 }
 
+/// `e_ident` offsets beyond class and data encoding
+pub mod ident {
+    /// Target OS ABI (`EI_OSABI`)
+    pub const EI_OSABI: usize = 7;
+    /// ABI version (`EI_ABIVERSION`)
+    pub const EI_ABIVERSION: usize = 8;
+}
+
+/// OS ABI values (`EI_OSABI`)
+pub mod osabi {
+    /// UNIX System V; also what most Linux toolchains emit
+    pub const ELFOSABI_NONE: u8 = 0;
+    /// Linux with GNU extensions (e.g. `STT_GNU_IFUNC`)
+    pub const ELFOSABI_GNU: u8 = 3;
+    /// FreeBSD
+    pub const ELFOSABI_FREEBSD: u8 = 9;
+    /// ARM EABI (machine-specific, `EM_ARM` only)
+    pub const ELFOSABI_ARM_AEABI: u8 = 64;
+    /// TI C6000 ELFABI (machine-specific, `EM_TI_C6000` only)
+    pub const ELFOSABI_C6000_ELFABI: u8 = 64;
+    /// Legacy ARM ABI (machine-specific)
+    pub const ELFOSABI_ARM: u8 = 97;
+    /// Standalone (embedded) application
+    pub const ELFOSABI_STANDALONE: u8 = 255;
+}
+
+/// Note types (`n_type`) in the "GNU" namespace
+pub mod nt {
+    /// `NT_GNU_ABI_TAG`: target OS and minimum kernel version
+    pub const NT_GNU_ABI_TAG: u32 = 1;
+}
+
+/// Object file types (`e_type`)
+pub mod et {
+    /// Relocatable object
+    pub const ET_REL: u16 = 1;
+    /// Executable at a fixed address
+    pub const ET_EXEC: u16 = 2;
+    /// Shared object or position-independent executable
+    pub const ET_DYN: u16 = 3;
+}
+
+/// Program header types (`p_type`)
+pub mod pt {
+    /// Unused entry
+    pub const PT_NULL: u32 = 0;
+    /// Loadable segment
+    pub const PT_LOAD: u32 = 1;
+    /// Dynamic linking information
+    pub const PT_DYNAMIC: u32 = 2;
+    /// Program interpreter path
+    pub const PT_INTERP: u32 = 3;
+    /// Auxiliary notes
+    pub const PT_NOTE: u32 = 4;
+    /// Reserved
+    pub const PT_SHLIB: u32 = 5;
+    /// Program header table itself
+    pub const PT_PHDR: u32 = 6;
+    /// Thread-local storage template
+    pub const PT_TLS: u32 = 7;
+    /// GNU exception-handling frame header
+    pub const PT_GNU_EH_FRAME: u32 = 0x6474_E550;
+    /// GNU stack executability
+    pub const PT_GNU_STACK: u32 = 0x6474_E551;
+    /// GNU read-only-after-relocation region
+    pub const PT_GNU_RELRO: u32 = 0x6474_E552;
+    /// GNU property notes
+    pub const PT_GNU_PROPERTY: u32 = 0x6474_E553;
+}
+
+/// Program header flags (`p_flags`)
+pub mod pf {
+    /// Executable
+    pub const PF_X: u32 = 0x1;
+    /// Writable
+    pub const PF_W: u32 = 0x2;
+    /// Readable
+    pub const PF_R: u32 = 0x4;
+}
+
+/// Dynamic section tags (`d_tag`)
+pub mod dt {
+    /// End of the dynamic array
+    pub const DT_NULL: u64 = 0;
+    /// Address of the dynamic string table
+    pub const DT_STRTAB: u64 = 5;
+    /// Address of the `Elf*_Rela` relocation table
+    pub const DT_RELA: u64 = 7;
+    /// Size of the dynamic string table
+    pub const DT_STRSZ: u64 = 10;
+    /// Address of the `Elf*_Rel` relocation table
+    pub const DT_REL: u64 = 17;
+    /// Type of the PLT relocations (`DT_REL` or `DT_RELA`)
+    pub const DT_PLTREL: u64 = 20;
+    /// Resolve all relocations at load time
+    pub const DT_BIND_NOW: u64 = 24;
+    /// `DF_*` flags
+    pub const DT_FLAGS: u64 = 30;
+    /// Address of the compact relative relocation table
+    pub const DT_RELR: u64 = 36;
+    /// `DF_1_*` flags
+    pub const DT_FLAGS_1: u64 = 0x6FFF_FFFB;
+}
+
+/// `DT_FLAGS` and `DT_FLAGS_1` bits
+pub mod df {
+    /// `DT_FLAGS`: resolve all relocations at load time
+    pub const DF_BIND_NOW: u64 = 0x8;
+    /// `DT_FLAGS_1`: resolve all relocations at load time
+    pub const DF_1_NOW: u64 = 0x1;
+    /// `DT_FLAGS_1`: position-independent executable
+    pub const DF_1_PIE: u64 = 0x0800_0000;
+}
+
+/// Section header types (`sh_type`)
+pub mod sht {
+    /// Program data (`.text`, `.data`, ...)
+    pub const SHT_PROGBITS: u32 = 1;
+    /// Occupies no file space (e.g. `.bss`)
+    pub const SHT_NOBITS: u32 = 8;
+    /// ARM EABI build attributes (`.ARM.attributes`)
+    pub const SHT_ARM_ATTRIBUTES: u32 = 0x7000_0003;
+    /// RISC-V build attributes (`.riscv.attributes`); shares the
+    /// processor-specific value with [`SHT_ARM_ATTRIBUTES`]
+    pub const SHT_RISCV_ATTRIBUTES: u32 = 0x7000_0003;
+}
+
+/// Section header flags (`sh_flags`)
+pub mod shf {
+    /// Writable at runtime
+    pub const SHF_WRITE: u64 = 0x1;
+    /// Occupies memory at runtime
+    pub const SHF_ALLOC: u64 = 0x2;
+    /// Contains executable instructions
+    pub const SHF_EXECINSTR: u64 = 0x4;
+}
+
+/// Section header table layout from the ELF file header.
+#[derive(Debug, Clone, Copy)]
+struct HeaderTables {
+    is_64: bool,
+    little_endian: bool,
+    phoff: u64,
+    phentsize: usize,
+    phnum: usize,
+    shoff: u64,
+    shentsize: usize,
+    shnum: usize,
+    shstrndx: usize,
+}
+
+/// A section header entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionHeader {
+    /// Offset of the name in the section header string table
+    pub sh_name: u32,
+    /// Section type
+    pub sh_type: u32,
+    /// Section flags (SHF_*)
+    pub sh_flags: u64,
+    /// Virtual address
+    pub sh_addr: u64,
+    /// File offset
+    pub sh_offset: u64,
+    /// Size in bytes
+    pub sh_size: u64,
+    /// Section index link
+    pub sh_link: u32,
+    /// Extra information
+    pub sh_info: u32,
+    /// Entry size for table sections
+    pub sh_entsize: u64,
+}
+
+/// A program header entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramHeader {
+    /// Segment type (PT_*)
+    pub p_type: u32,
+    /// Segment flags (PF_*)
+    pub p_flags: u32,
+    /// File offset
+    pub p_offset: u64,
+    /// Virtual address
+    pub p_vaddr: u64,
+    /// Size in the file
+    pub p_filesz: u64,
+    /// Size in memory
+    pub p_memsz: u64,
+}
+
+/// Read the header table layout, resolving extended section numbering.
+fn header_tables(data: &[u8], is_64: bool, little_endian: bool) -> Option<HeaderTables> {
+    let le = little_endian;
+    let mut tables = if is_64 {
+        HeaderTables {
+            is_64,
+            little_endian,
+            phoff: read_u64(data, 0x20, le).ok()?,
+            shoff: read_u64(data, 0x28, le).ok()?,
+            phentsize: read_u16(data, 0x36, le).ok()? as usize,
+            phnum: read_u16(data, 0x38, le).ok()? as usize,
+            shentsize: read_u16(data, 0x3A, le).ok()? as usize,
+            shnum: read_u16(data, 0x3C, le).ok()? as usize,
+            shstrndx: read_u16(data, 0x3E, le).ok()? as usize,
+        }
+    } else {
+        HeaderTables {
+            is_64,
+            little_endian,
+            phoff: u64::from(read_u32(data, 0x1C, le).ok()?),
+            shoff: u64::from(read_u32(data, 0x20, le).ok()?),
+            phentsize: read_u16(data, 0x2A, le).ok()? as usize,
+            phnum: read_u16(data, 0x2C, le).ok()? as usize,
+            shentsize: read_u16(data, 0x2E, le).ok()? as usize,
+            shnum: read_u16(data, 0x30, le).ok()? as usize,
+            shstrndx: read_u16(data, 0x32, le).ok()? as usize,
+        }
+    };
+
+    // Extended numbering: e_shnum == 0 and e_shstrndx == SHN_XINDEX defer
+    // to fields of section header 0.
+    if tables.shoff != 0 && (tables.shnum == 0 || tables.shstrndx == 0xFFFF) {
+        if let Some(first) = read_section_header(data, &tables, 0) {
+            if tables.shnum == 0 {
+                tables.shnum = usize::try_from(first.sh_size).unwrap_or(usize::MAX);
+            }
+            if tables.shstrndx == 0xFFFF {
+                tables.shstrndx = first.sh_link as usize;
+            }
+        }
+    }
+
+    Some(tables)
+}
+
+fn read_section_header(data: &[u8], t: &HeaderTables, index: usize) -> Option<SectionHeader> {
+    let min_size = if t.is_64 { 64 } else { 40 };
+    if t.shentsize < min_size {
+        return None;
+    }
+    let base = usize::try_from(t.shoff)
+        .ok()?
+        .checked_add(index.checked_mul(t.shentsize)?)?;
+    let le = t.little_endian;
+    if t.is_64 {
+        Some(SectionHeader {
+            sh_name: read_u32(data, base, le).ok()?,
+            sh_type: read_u32(data, base.checked_add(4)?, le).ok()?,
+            sh_flags: read_u64(data, base.checked_add(8)?, le).ok()?,
+            sh_addr: read_u64(data, base.checked_add(0x10)?, le).ok()?,
+            sh_offset: read_u64(data, base.checked_add(0x18)?, le).ok()?,
+            sh_size: read_u64(data, base.checked_add(0x20)?, le).ok()?,
+            sh_link: read_u32(data, base.checked_add(0x28)?, le).ok()?,
+            sh_info: read_u32(data, base.checked_add(0x2C)?, le).ok()?,
+            sh_entsize: read_u64(data, base.checked_add(0x38)?, le).ok()?,
+        })
+    } else {
+        Some(SectionHeader {
+            sh_name: read_u32(data, base, le).ok()?,
+            sh_type: read_u32(data, base.checked_add(4)?, le).ok()?,
+            sh_flags: u64::from(read_u32(data, base.checked_add(8)?, le).ok()?),
+            sh_addr: u64::from(read_u32(data, base.checked_add(0x0C)?, le).ok()?),
+            sh_offset: u64::from(read_u32(data, base.checked_add(0x10)?, le).ok()?),
+            sh_size: u64::from(read_u32(data, base.checked_add(0x14)?, le).ok()?),
+            sh_link: read_u32(data, base.checked_add(0x18)?, le).ok()?,
+            sh_info: read_u32(data, base.checked_add(0x1C)?, le).ok()?,
+            sh_entsize: u64::from(read_u32(data, base.checked_add(0x24)?, le).ok()?),
+        })
+    }
+}
+
+fn read_program_header(data: &[u8], t: &HeaderTables, index: usize) -> Option<ProgramHeader> {
+    let min_size = if t.is_64 { 56 } else { 32 };
+    if t.phentsize < min_size {
+        return None;
+    }
+    let base = usize::try_from(t.phoff)
+        .ok()?
+        .checked_add(index.checked_mul(t.phentsize)?)?;
+    let le = t.little_endian;
+    if t.is_64 {
+        Some(ProgramHeader {
+            p_type: read_u32(data, base, le).ok()?,
+            p_flags: read_u32(data, base.checked_add(4)?, le).ok()?,
+            p_offset: read_u64(data, base.checked_add(8)?, le).ok()?,
+            p_vaddr: read_u64(data, base.checked_add(0x10)?, le).ok()?,
+            p_filesz: read_u64(data, base.checked_add(0x20)?, le).ok()?,
+            p_memsz: read_u64(data, base.checked_add(0x28)?, le).ok()?,
+        })
+    } else {
+        Some(ProgramHeader {
+            p_type: read_u32(data, base, le).ok()?,
+            p_offset: u64::from(read_u32(data, base.checked_add(4)?, le).ok()?),
+            p_vaddr: u64::from(read_u32(data, base.checked_add(8)?, le).ok()?),
+            p_filesz: u64::from(read_u32(data, base.checked_add(0x10)?, le).ok()?),
+            p_memsz: u64::from(read_u32(data, base.checked_add(0x14)?, le).ok()?),
+            p_flags: read_u32(data, base.checked_add(0x18)?, le).ok()?,
+        })
+    }
+}
+
+/// Read all section headers that lie within `data`.
+///
+/// Stops at the first entry that would extend past the end of the input,
+/// so truncated files yield the headers that are actually present.
+pub fn section_headers(data: &[u8], is_64: bool, little_endian: bool) -> Vec<SectionHeader> {
+    let Some(tables) = header_tables(data, is_64, little_endian) else {
+        return Vec::new();
+    };
+    if tables.shoff == 0 {
+        return Vec::new();
+    }
+    (0..tables.shnum)
+        .map_while(|i| read_section_header(data, &tables, i))
+        .collect()
+}
+
+/// Read all program headers that lie within `data`.
+pub fn program_headers(data: &[u8], is_64: bool, little_endian: bool) -> Vec<ProgramHeader> {
+    let Some(tables) = header_tables(data, is_64, little_endian) else {
+        return Vec::new();
+    };
+    if tables.phoff == 0 {
+        return Vec::new();
+    }
+    (0..tables.phnum)
+        .map_while(|i| read_program_header(data, &tables, i))
+        .collect()
+}
+
+/// Resolve section names through the section header string table.
+///
+/// Returns one entry per header; names that cannot be resolved are empty.
+pub fn section_names(
+    data: &[u8],
+    is_64: bool,
+    little_endian: bool,
+    headers: &[SectionHeader],
+) -> Vec<String> {
+    let strtab = header_tables(data, is_64, little_endian)
+        .and_then(|t| headers.get(t.shstrndx))
+        .and_then(|sh| {
+            let start = usize::try_from(sh.sh_offset).ok()?;
+            let size = usize::try_from(sh.sh_size).ok()?;
+            crate::formats::read_bytes(data, start, size).ok()
+        });
+
+    headers
+        .iter()
+        .map(|sh| {
+            strtab
+                .and_then(|table| read_cstr(table, sh.sh_name as usize, 256).ok())
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Human-readable name for a program header type.
+pub fn segment_type_name(p_type: u32) -> String {
+    match p_type {
+        pt::PT_NULL => "NULL".to_string(),
+        pt::PT_LOAD => "LOAD".to_string(),
+        pt::PT_DYNAMIC => "DYNAMIC".to_string(),
+        pt::PT_INTERP => "INTERP".to_string(),
+        pt::PT_NOTE => "NOTE".to_string(),
+        pt::PT_SHLIB => "SHLIB".to_string(),
+        pt::PT_PHDR => "PHDR".to_string(),
+        pt::PT_TLS => "TLS".to_string(),
+        pt::PT_GNU_EH_FRAME => "GNU_EH_FRAME".to_string(),
+        pt::PT_GNU_STACK => "GNU_STACK".to_string(),
+        pt::PT_GNU_RELRO => "GNU_RELRO".to_string(),
+        pt::PT_GNU_PROPERTY => "GNU_PROPERTY".to_string(),
+        other => format!("0x{other:08X}"),
+    }
+}
+
+/// Section and segment inventory collected from an ELF image.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    /// Sections, capped at the requested maximum
+    pub sections: Vec<SectionInfo>,
+    /// Segments, capped at the requested maximum
+    pub segments: Vec<SegmentInfo>,
+    /// Total section headers present before capping
+    pub total_sections: usize,
+    /// Total program headers present before capping
+    pub total_segments: usize,
+}
+
+impl Inventory {
+    /// Whether either list was cut short by the cap.
+    pub fn is_truncated(&self) -> bool {
+        self.sections.len() < self.total_sections || self.segments.len() < self.total_segments
+    }
+}
+
+/// Collect the section and segment inventory, keeping at most `max` of each.
+pub fn inventory(data: &[u8], ei_class: u8, ei_data: u8, max: usize) -> Inventory {
+    let is_64 = ei_class == class::ELFCLASS64;
+    let little_endian = ei_data == data::ELFDATA2LSB;
+
+    let headers = section_headers(data, is_64, little_endian);
+    let names = section_names(data, is_64, little_endian, &headers);
+    let sections = headers
+        .iter()
+        .zip(names)
+        .take(max)
+        .map(|(sh, name)| SectionInfo {
+            name,
+            offset: sh.sh_offset,
+            size: sh.sh_size,
+            flags_execinstr: sh.sh_flags & shf::SHF_EXECINSTR != 0,
+            flags_write: sh.sh_flags & shf::SHF_WRITE != 0,
+        })
+        .collect();
+
+    let phdrs = program_headers(data, is_64, little_endian);
+    let segments = phdrs
+        .iter()
+        .take(max)
+        .map(|ph| SegmentInfo {
+            kind: segment_type_name(ph.p_type),
+            offset: ph.p_offset,
+            vaddr: ph.p_vaddr,
+            file_size: ph.p_filesz,
+            mem_size: ph.p_memsz,
+            flags_read: ph.p_flags & pf::PF_R != 0,
+            flags_write: ph.p_flags & pf::PF_W != 0,
+            flags_execute: ph.p_flags & pf::PF_X != 0,
+        })
+        .collect();
+
+    Inventory {
+        sections,
+        segments,
+        total_sections: headers.len(),
+        total_segments: phdrs.len(),
+    }
+}
+
+/// File offset one past the ELF header and both header tables.
+pub fn header_extent(data: &[u8], ei_class: u8, ei_data: u8) -> u64 {
+    let is_64 = ei_class == class::ELFCLASS64;
+    let mut end: u64 = if is_64 { 64 } else { 52 };
+    let Some(tables) = header_tables(data, is_64, ei_data == data::ELFDATA2LSB) else {
+        return end;
+    };
+    let table_end = |off: u64, num: usize, entsize: usize| {
+        off.saturating_add((num as u64).saturating_mul(entsize as u64))
+    };
+    if tables.phoff != 0 {
+        end = end.max(table_end(tables.phoff, tables.phnum, tables.phentsize));
+    }
+    if tables.shoff != 0 {
+        end = end.max(table_end(tables.shoff, tables.shnum, tables.shentsize));
+    }
+    end
+}
+
+/// File offset one past the last byte the ELF image at `data[0]` claims.
+///
+/// Covers the file header, both header tables, every section except
+/// `SHT_NOBITS`, and every segment's file image. The value is not clamped to
+/// `data.len()`, so a truncated image reports where it should have ended.
+pub fn image_extent(data: &[u8], ei_class: u8, ei_data: u8) -> u64 {
+    let is_64 = ei_class == class::ELFCLASS64;
+    let little_endian = ei_data == data::ELFDATA2LSB;
+    let mut end = header_extent(data, ei_class, ei_data);
+    for sh in section_headers(data, is_64, little_endian) {
+        if sh.sh_type != sht::SHT_NOBITS {
+            end = end.max(sh.sh_offset.saturating_add(sh.sh_size));
+        }
+    }
+    for ph in program_headers(data, is_64, little_endian) {
+        end = end.max(ph.p_offset.saturating_add(ph.p_filesz));
+    }
+    end
+}
+
+/// An ELF image packed after the end of the preceding one.
+#[derive(Debug, Clone)]
+pub struct AppendedImage {
+    /// File offset of the image's ELF header
+    pub offset: u64,
+    /// Bytes the image occupies (its [`image_extent`], clamped to the input)
+    pub size: u64,
+    /// Classification of the image on its own
+    pub result: ClassificationResult,
+}
+
+/// Cap on the images [`appended_images`] enumerates.
+const MAX_APPENDED_IMAGES: usize = 64;
+
+/// Enumerate ELF images concatenated after the one at offset 0.
+///
+/// The search for the next ELF magic starts where the previous image ends
+/// (see [`image_extent`]), so padding between images is skipped. A magic
+/// whose identification bytes or header do not parse is passed over.
+pub fn appended_images(data: &[u8], ei_class: u8, ei_data: u8) -> Vec<AppendedImage> {
+    let mut images = Vec::new();
+    let mut pos = image_extent(data, ei_class, ei_data);
+
+    while images.len() < MAX_APPENDED_IMAGES {
+        let Some(start) = usize::try_from(pos).ok().filter(|&p| p < data.len()) else {
+            break;
+        };
+        let Some(found) = memchr::memmem::find(&data[start..], b"\x7FELF") else {
+            break;
+        };
+        let offset = start + found;
+        let image = &data[offset..];
+        let parsed = match (image.get(4), image.get(5), image.get(6)) {
+            (
+                Some(&ei_class @ (class::ELFCLASS32 | class::ELFCLASS64)),
+                Some(&ei_data @ (data::ELFDATA2LSB | data::ELFDATA2MSB)),
+                Some(1),
+            ) => parse(image, ei_class, ei_data)
+                .ok()
+                .map(|result| (image_extent(image, ei_class, ei_data), result)),
+            _ => None,
+        };
+        match parsed {
+            Some((extent, result)) => {
+                let size = extent.min(image.len() as u64);
+                images.push(AppendedImage {
+                    offset: offset as u64,
+                    size,
+                    result,
+                });
+                pos = offset as u64 + size;
+            }
+            None => pos = offset as u64 + 4,
+        }
+    }
+    images
+}
+
+/// Cap on the dynamic entries [`dynamic_entries`] reads.
+const MAX_DYNAMIC_ENTRIES: usize = 4096;
+
+/// Read the `(d_tag, d_val)` pairs of the `PT_DYNAMIC` segment, up to `DT_NULL`.
+pub fn dynamic_entries(data: &[u8], is_64: bool, little_endian: bool) -> Vec<(u64, u64)> {
+    let Some(dynamic) = program_headers(data, is_64, little_endian)
+        .into_iter()
+        .find(|ph| ph.p_type == pt::PT_DYNAMIC)
+    else {
+        return Vec::new();
+    };
+    let (Ok(start), Ok(size)) = (
+        usize::try_from(dynamic.p_offset),
+        usize::try_from(dynamic.p_filesz),
+    ) else {
+        return Vec::new();
+    };
+    let entsize = if is_64 { 16 } else { 8 };
+    let word = |off: usize| {
+        if is_64 {
+            read_u64(data, off, little_endian).ok()
+        } else {
+            read_u32(data, off, little_endian).ok().map(u64::from)
+        }
+    };
+
+    (0..(size / entsize).min(MAX_DYNAMIC_ENTRIES))
+        .map_while(|i| {
+            let off = start.checked_add(i * entsize)?;
+            Some((word(off)?, word(off + entsize / 2)?))
+        })
+        .take_while(|&(tag, _)| tag != dt::DT_NULL)
+        .collect()
+}
+
+/// Relocation format of a dynamically linked image: "relr" when it uses
+/// compact relative relocations (`DT_RELR`, recent linkers with
+/// `-z pack-relative-relocs`), otherwise "rela" or "rel".
+///
+/// `None` without a dynamic section or relocation tags.
+pub fn reloc_format(data: &[u8], is_64: bool, little_endian: bool) -> Option<&'static str> {
+    let dynamic = dynamic_entries(data, is_64, little_endian);
+    let has = |wanted: u64| dynamic.iter().any(|&(tag, _)| tag == wanted);
+    let plt_rel = dynamic
+        .iter()
+        .find(|&&(tag, _)| tag == dt::DT_PLTREL)
+        .map(|&(_, value)| value);
+
+    if has(dt::DT_RELR) {
+        Some("relr")
+    } else if has(dt::DT_RELA) || plt_rel == Some(dt::DT_RELA) {
+        Some("rela")
+    } else if has(dt::DT_REL) || plt_rel == Some(dt::DT_REL) {
+        Some("rel")
+    } else {
+        None
+    }
+}
+
+/// Map a virtual address to a file offset through the `PT_LOAD` segments.
+fn vaddr_to_offset(phdrs: &[ProgramHeader], vaddr: u64) -> Option<u64> {
+    phdrs
+        .iter()
+        .filter(|ph| ph.p_type == pt::PT_LOAD)
+        .find_map(|ph| {
+            let delta = vaddr.checked_sub(ph.p_vaddr)?;
+            (delta < ph.p_filesz).then(|| ph.p_offset + delta)
+        })
+}
+
+/// Summarize exploit mitigations: PIE, non-executable stack, RELRO,
+/// immediate binding and a stack-protector hint.
+///
+/// The canary hint looks for `__stack_chk_fail` in the dynamic string
+/// table, so it is `None` for static binaries.
+pub fn hardening(data: &[u8], ei_class: u8, ei_data: u8) -> HardeningSummary {
+    let is_64 = ei_class == class::ELFCLASS64;
+    let little_endian = ei_data == data::ELFDATA2LSB;
+    let e_type = read_u16(data, 0x10, little_endian).unwrap_or(0);
+    let phdrs = program_headers(data, is_64, little_endian);
+    let dynamic = dynamic_entries(data, is_64, little_endian);
+    let tag = |wanted: u64| {
+        dynamic
+            .iter()
+            .find(|&&(t, _)| t == wanted)
+            .map(|&(_, value)| value)
+    };
+
+    let has_segment = |p_type: u32| phdrs.iter().any(|ph| ph.p_type == p_type);
+    let flags_1 = tag(dt::DT_FLAGS_1).unwrap_or(0);
+    let pie = e_type == et::ET_DYN && (has_segment(pt::PT_INTERP) || flags_1 & df::DF_1_PIE != 0);
+    // Without PT_GNU_STACK the GNU loader makes the stack executable
+    let nx = phdrs
+        .iter()
+        .find(|ph| ph.p_type == pt::PT_GNU_STACK)
+        .is_some_and(|ph| ph.p_flags & pf::PF_X == 0);
+    let bind_now = tag(dt::DT_BIND_NOW).is_some()
+        || tag(dt::DT_FLAGS).unwrap_or(0) & df::DF_BIND_NOW != 0
+        || flags_1 & df::DF_1_NOW != 0;
+
+    let dynstr = tag(dt::DT_STRTAB)
+        .and_then(|addr| vaddr_to_offset(&phdrs, addr))
+        .zip(tag(dt::DT_STRSZ))
+        .and_then(|(off, size)| {
+            let off = usize::try_from(off).ok()?;
+            let size = usize::try_from(size).ok()?;
+            data.get(off..off.checked_add(size)?)
+        });
+    let stack_canary =
+        dynstr.map(|table| memchr::memmem::find(table, b"__stack_chk_fail\0").is_some());
+
+    HardeningSummary {
+        pie,
+        nx,
+        relro: Some(has_segment(pt::PT_GNU_RELRO)),
+        bind_now: Some(bind_now),
+        stack_canary,
+        ..Default::default()
+    }
+    .with_note()
+}
+
+/// File range `(offset, size)` of the code in a relocatable object: the
+/// `.text` section, or the first executable section when there is none.
+///
+/// The range is clamped to `data`; `None` when no such section has bytes.
+pub fn text_section(data: &[u8], is_64: bool, little_endian: bool) -> Option<(u64, u64)> {
+    let headers = section_headers(data, is_64, little_endian);
+    let names = section_names(data, is_64, little_endian, &headers);
+    let has_code = |sh: &SectionHeader| sh.sh_type != sht::SHT_NOBITS && sh.sh_size != 0;
+    let section = headers
+        .iter()
+        .zip(&names)
+        .find(|(sh, name)| name.as_str() == ".text" && has_code(sh))
+        .or_else(|| {
+            headers
+                .iter()
+                .zip(&names)
+                .find(|(sh, _)| sh.sh_flags & shf::SHF_EXECINSTR != 0 && has_code(sh))
+        })
+        .map(|(sh, _)| sh)?;
+
+    let len = data.len() as u64;
+    let start = section.sh_offset.min(len);
+    let size = section.sh_size.min(len - start);
+    (size != 0).then_some((start, size))
+}
+
+/// File ranges `(offset, size)` of the executable code: every
+/// `SHF_EXECINSTR` section, or every `PT_LOAD` segment with `PF_X` when the
+/// section headers are stripped.
+///
+/// Ranges are clamped to `data`; empty ones are dropped.
+pub fn executable_ranges(data: &[u8], is_64: bool, little_endian: bool) -> Vec<(u64, u64)> {
+    let len = data.len() as u64;
+    let clamp = |offset: u64, size: u64| {
+        let start = offset.min(len);
+        (start, size.min(len - start))
+    };
+    let sections: Vec<_> = section_headers(data, is_64, little_endian)
+        .iter()
+        .filter(|sh| sh.sh_flags & shf::SHF_EXECINSTR != 0 && sh.sh_type != sht::SHT_NOBITS)
+        .map(|sh| clamp(sh.sh_offset, sh.sh_size))
+        .filter(|&(_, size)| size != 0)
+        .collect();
+    if !sections.is_empty() {
+        return sections;
+    }
+    program_headers(data, is_64, little_endian)
+        .iter()
+        .filter(|ph| ph.p_type == pt::PT_LOAD && ph.p_flags & pf::PF_X != 0)
+        .map(|ph| clamp(ph.p_offset, ph.p_filesz))
+        .filter(|&(_, size)| size != 0)
+        .collect()
+}
+
+/// File ranges `(offset, size)` of the procedure linkage tables: the
+/// `.plt` and `.iplt` sections, clamped to `data`.
+pub fn plt_sections(data: &[u8], is_64: bool, little_endian: bool) -> Vec<(u64, u64)> {
+    let headers = section_headers(data, is_64, little_endian);
+    let names = section_names(data, is_64, little_endian, &headers);
+    let len = data.len() as u64;
+    headers
+        .iter()
+        .zip(&names)
+        .filter(|(sh, name)| {
+            matches!(name.as_str(), ".plt" | ".iplt") && sh.sh_type != sht::SHT_NOBITS
+        })
+        .map(|(sh, _)| {
+            let start = sh.sh_offset.min(len);
+            (start, sh.sh_size.min(len - start))
+        })
+        .filter(|&(_, size)| size != 0)
+        .collect()
+}
+
+/// File-wide ARM EABI build attributes the classifier uses, from the
+/// public `aeabi` subsection of `.ARM.attributes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArmAttributes {
+    /// `Tag_CPU_arch_profile`: `b'A'`, `b'R'`, `b'M'`, or `b'S'` for
+    /// classic code that runs on either A or R
+    pub profile: Option<u8>,
+    /// `Tag_ARM_ISA_use`: 0 when the code contains no A32 instructions
+    pub arm_isa_use: Option<u64>,
+    /// `Tag_THUMB_ISA_use`: 1 for 16-bit Thumb, 2 for Thumb-2, 3 for the
+    /// Thumb the architecture implies
+    pub thumb_isa_use: Option<u64>,
+}
+
+impl ArmAttributes {
+    /// Variant profile named by `Tag_CPU_arch_profile`.
+    pub fn profile_name(&self) -> Option<&'static str> {
+        match self.profile? {
+            b'A' => Some(arm::A_PROFILE),
+            b'R' => Some(arm::R_PROFILE),
+            b'M' => Some(arm::M_PROFILE),
+            _ => None,
+        }
+    }
+
+    /// Set the variant profile and, for M-profile code, add the Thumb
+    /// instruction set it runs: Thumb-2, or 16-bit Thumb when the
+    /// attributes say so (ARMv6-M).
+    pub fn apply(&self, variant: &mut Variant, extensions: &mut Vec<Extension>) {
+        let Some(profile) = self.profile_name() else {
+            return;
+        };
+        variant.profile = Some(profile.to_string());
+        if profile == arm::M_PROFILE {
+            let thumb = if self.thumb_isa_use == Some(1) {
+                "Thumb"
+            } else {
+                "Thumb-2"
+            };
+            if !extensions.iter().any(|e| e.name == thumb) {
+                extensions.push(Extension::new(thumb, ExtensionCategory::Compressed));
+            }
+        }
+    }
+}
+
+/// Build attributes from the `SHT_ARM_ATTRIBUTES` section, if there is one.
+pub fn arm_attributes(data: &[u8], is_64: bool, little_endian: bool) -> Option<ArmAttributes> {
+    let section = section_headers(data, is_64, little_endian)
+        .into_iter()
+        .find(|sh| sh.sh_type == sht::SHT_ARM_ATTRIBUTES)?;
+    let start = usize::try_from(section.sh_offset).ok()?;
+    let size = usize::try_from(section.sh_size).ok()?;
+    let bytes = crate::formats::read_bytes(data, start, size).ok()?;
+    parse_arm_attributes(bytes, little_endian)
+}
+
+/// Parse an `.ARM.attributes` section. Only the file-scope (`Tag_File`)
+/// attributes of the `aeabi` subsection are read.
+pub fn parse_arm_attributes(section: &[u8], little_endian: bool) -> Option<ArmAttributes> {
+    let mut attributes = ArmAttributes::default();
+    for tags in file_attributes(section, b"aeabi", little_endian)? {
+        read_arm_attribute_tags(tags, &mut attributes);
+    }
+    Some(attributes)
+}
+
+/// File-scope (`Tag_File`) attribute blocks of the `vendor` subsection of
+/// a build-attributes section: format version `'A'`, then length-prefixed
+/// vendor subsections. ARM (`aeabi`) and RISC-V (`riscv`) share the layout.
+fn file_attributes<'a>(
+    section: &'a [u8],
+    vendor: &[u8],
+    little_endian: bool,
+) -> Option<Vec<&'a [u8]>> {
+    const TAG_FILE: u8 = 1;
+
+    let (&version, mut rest) = section.split_first()?;
+    if version != b'A' {
+        return None;
+    }
+    let read_len = |bytes: &[u8]| -> Option<usize> {
+        let word = [
+            *bytes.first()?,
+            *bytes.get(1)?,
+            *bytes.get(2)?,
+            *bytes.get(3)?,
+        ];
+        let value = if little_endian {
+            u32::from_le_bytes(word)
+        } else {
+            u32::from_be_bytes(word)
+        };
+        usize::try_from(value).ok()
+    };
+
+    let mut file_blocks = Vec::new();
+    while let Some(len) = read_len(rest).filter(|&len| len >= 4 && len <= rest.len()) {
+        let (subsection, tail) = rest.split_at(len);
+        rest = tail;
+        let body = &subsection[4..];
+        let Some(nul) = body.iter().position(|&b| b == 0) else {
+            continue;
+        };
+        if &body[..nul] != vendor {
+            continue;
+        }
+
+        let mut blocks = &body[nul + 1..];
+        while let Some(size) = read_len(blocks.get(1..).unwrap_or_default())
+            .filter(|&size| size >= 5 && size <= blocks.len())
+        {
+            let (block, tail) = blocks.split_at(size);
+            blocks = tail;
+            if block[0] == TAG_FILE {
+                file_blocks.push(&block[5..]);
+            }
+        }
+    }
+    Some(file_blocks)
+}
+
+/// Read `(tag, value)` pairs until the data ends or is malformed.
+///
+/// Tags 4, 5, 65 and 67 and odd tags above 32 take a NUL-terminated
+/// string; `Tag_compatibility` (32) a number and a string; the rest a
+/// ULEB128 number.
+fn read_arm_attribute_tags(data: &[u8], attributes: &mut ArmAttributes) {
+    let mut pos = 0;
+    let skip_string = |pos: &mut usize| -> Option<()> {
+        let len = data.get(*pos..)?.iter().position(|&b| b == 0)?;
+        *pos += len + 1;
+        Some(())
+    };
+    while pos < data.len() {
+        let Some(tag) = read_uleb128(data, &mut pos) else {
+            return;
+        };
+        let parsed = match tag {
+            4 | 5 | 65 | 67 => skip_string(&mut pos),
+            32 => read_uleb128(data, &mut pos).and_then(|_| skip_string(&mut pos)),
+            tag if tag > 32 && tag % 2 == 1 => skip_string(&mut pos),
+            tag => read_uleb128(data, &mut pos).map(|value| match tag {
+                // Tag_CPU_arch_profile
+                7 => attributes.profile = u8::try_from(value).ok(),
+                // Tag_ARM_ISA_use
+                8 => attributes.arm_isa_use = Some(value),
+                // Tag_THUMB_ISA_use
+                9 => attributes.thumb_isa_use = Some(value),
+                _ => {}
+            }),
+        };
+        if parsed.is_none() {
+            return;
+        }
+    }
+}
+
+/// `Tag_RISCV_arch` (e.g. `rv64i2p1_m2p0_a2p1_c2p0`) from the
+/// `SHT_RISCV_ATTRIBUTES` section, if there is one.
+pub fn riscv_arch_attribute(data: &[u8], is_64: bool, little_endian: bool) -> Option<String> {
+    let section = section_headers(data, is_64, little_endian)
+        .into_iter()
+        .find(|sh| sh.sh_type == sht::SHT_RISCV_ATTRIBUTES)?;
+    let start = usize::try_from(section.sh_offset).ok()?;
+    let size = usize::try_from(section.sh_size).ok()?;
+    let bytes = crate::formats::read_bytes(data, start, size).ok()?;
+    file_attributes(bytes, b"riscv", little_endian)?
+        .into_iter()
+        .find_map(read_riscv_arch_tag)
+}
+
+/// Read `(tag, value)` pairs up to `Tag_RISCV_arch` (5). Odd tags take a
+/// NUL-terminated string, even tags a ULEB128 number.
+fn read_riscv_arch_tag(data: &[u8]) -> Option<String> {
+    const TAG_RISCV_ARCH: u64 = 5;
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let tag = read_uleb128(data, &mut pos)?;
+        if tag % 2 == 0 {
+            read_uleb128(data, &mut pos)?;
+            continue;
+        }
+        let len = data.get(pos..)?.iter().position(|&b| b == 0)?;
+        let value = &data[pos..pos + len];
+        pos += len + 1;
+        if tag == TAG_RISCV_ARCH {
+            return Some(String::from_utf8_lossy(value).into_owned());
+        }
+    }
+    None
+}
+
+/// Read an unsigned LEB128 number at `pos`, advancing past it.
+fn read_uleb128(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    let mut shift = 0u32;
+    loop {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        if shift < 64 {
+            value |= u64::from(byte & 0x7F) << shift;
+        }
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Program interpreter path from `PT_INTERP`, if the image has one.
+pub fn interpreter(data: &[u8], is_64: bool, little_endian: bool) -> Option<String> {
+    let interp = program_headers(data, is_64, little_endian)
+        .into_iter()
+        .find(|p| p.p_type == pt::PT_INTERP)?;
+    let offset = usize::try_from(interp.p_offset).ok()?;
+    let size = usize::try_from(interp.p_filesz).ok()?;
+    let path = read_cstr(data, offset, size).ok()?;
+    (!path.is_empty()).then(|| String::from_utf8_lossy(path).into_owned())
+}
+
+/// Dynamic loaders whose file name gives away their word size, per
+/// e_machine family: `(machines, name prefix, bits)`, first match wins.
+///
+/// glibc and musl loaders usually carry the ABI in the name
+/// (`ld-linux-x86-64.so.2`, `ld-musl-riscv64.so.1`), but the same name can
+/// mean different things per architecture: `ld-linux.so.2` is the i386
+/// loader and also the 64-bit Alpha and SPARC64 one, and `ld.so.1` or
+/// `ld-linux-mipsn8.so.1` serve every MIPS ABI. Families without an entry
+/// (MIPS, SPARC, Alpha, ...) are not checked.
+const LOADER_BITS: &[(&[u16], &str, u8)] = &[
+    // i386 / x86-64 / x32
+    (&[0x03, 0x3E], "ld-linux.so.", 32),
+    (&[0x03, 0x3E], "ld-linux-x86-64.", 64),
+    (&[0x03, 0x3E], "ld-linux-x32.", 32),
+    (&[0x03, 0x3E], "ld-musl-i386.", 32),
+    (&[0x03, 0x3E], "ld-musl-x86_64.", 64),
+    (&[0x03, 0x3E], "ld-musl-x32.", 32),
+    // ARM / AArch64 (ILP32 before LP64)
+    (&[0x28, 0xB7], "ld-linux.so.", 32),
+    (&[0x28, 0xB7], "ld-linux-armhf.", 32),
+    (&[0x28, 0xB7], "ld-linux-aarch64_ilp32.", 32),
+    (&[0x28, 0xB7], "ld-linux-aarch64", 64),
+    (&[0x28, 0xB7], "ld-musl-arm", 32),
+    (&[0x28, 0xB7], "ld-musl-aarch64", 64),
+    // RISC-V
+    (&[0xF3], "ld-linux-riscv64-", 64),
+    (&[0xF3], "ld-linux-riscv32-", 32),
+    (&[0xF3], "ld-musl-riscv64", 64),
+    (&[0xF3], "ld-musl-riscv32", 32),
+    // PowerPC / PowerPC64
+    (&[0x14, 0x15], "ld64.so.", 64),
+    (&[0x14, 0x15], "ld.so.1", 32),
+    (&[0x14, 0x15], "ld-musl-powerpc64", 64),
+    (&[0x14, 0x15], "ld-musl-powerpc", 32),
+    // S/390 / z/Architecture
+    (&[0x16], "ld64.so.", 64),
+    (&[0x16], "ld.so.1", 32),
+    (&[0x16], "ld-musl-s390x", 64),
+    // LoongArch
+    (&[0x102], "ld-linux-loongarch-lp64", 64),
+    (&[0x102], "ld-linux-loongarch-ilp32", 32),
+    (&[0x102], "ld-musl-loongarch64", 64),
+];
+
+/// Word size implied by a dynamic loader's file name for an `e_machine`
+/// binary, when the name encodes one (see [`LOADER_BITS`]).
+pub fn interpreter_bits(path: &str, e_machine: u16) -> Option<u8> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    LOADER_BITS
+        .iter()
+        .find(|(machines, prefix, _)| machines.contains(&e_machine) && name.starts_with(prefix))
+        .map(|&(_, _, bits)| bits)
+}
+
 /// Complete e_machine value mapping.
 ///
 /// This includes all values from the official ELF specification
@@ -96,8 +1109,8 @@ pub fn e_machine_to_isa(e_machine: u16, ei_class: u8) -> (Isa, u8) {
             }
         }
 
-        // IBM SPU/SPC
-        0x17 => (Isa::Unknown(0x17), 32),
+        // IBM SPU/SPC (Cell Broadband Engine SPU)
+        0x17 => (Isa::CellSpu, 32),
 
         // NEC V800 / Renesas RH850 toolchains
         0x24 => (Isa::Rh850, 32),
@@ -267,8 +1280,14 @@ pub fn e_machine_to_isa(e_machine: u16, ei_class: u8) -> (Isa, u8) {
         // picoJava
         0x5B => (Isa::Unknown(0x5B), 32),
 
-        // OpenRISC
-        0x5C => (Isa::OpenRisc, 32),
+        // OpenRISC; 64-bit class is the non-standard OR64 proposal
+        0x5C => {
+            if is_64 {
+                (Isa::OpenRisc, 64)
+            } else {
+                (Isa::OpenRisc, 32)
+            }
+        }
 
         // ARC ARCompact
         0x5D => (Isa::ArcCompact, 32),
@@ -346,7 +1365,7 @@ pub fn e_machine_to_isa(e_machine: u16, ei_class: u8) -> (Isa, u8) {
         0x75 => (Isa::Unknown(0x75), 16),
 
         // Microchip dsPIC30F
-        0x76 => (Isa::Unknown(0x76), 16),
+        0x76 => (Isa::Pic24, 16),
 
         // Freescale CE
         0x77 => (Isa::Unknown(0x77), 32),
@@ -640,6 +1659,13 @@ pub fn e_machine_to_isa(e_machine: u16, ei_class: u8) -> (Isa, u8) {
         // ChipON KungFu32
         0x103 => (Isa::Unknown(0x103), 32),
 
+        // Fujitsu FR30, pre-registration value used by early toolchains
+        0x3330 => (Isa::Fr30, 32),
+
+        // Fujitsu FR-V: never registered; binutils and GCC write the
+        // Cygnus value. Tables listing EM_FRV as 87 conflict with EM_V850.
+        0x5441 => (Isa::Frv, 32),
+
         // DEC Alpha (official/experimental)
         0x9026 => (Isa::Alpha, 64),
 
@@ -1015,6 +2041,19 @@ fn parse_sparc_flags(e_flags: u32) -> (Variant, Vec<Extension>) {
 
 /// Main ELF parsing function.
 pub fn parse(data: &[u8], ei_class: u8, ei_data: u8) -> Result<ClassificationResult> {
+    parse_with_options(data, ei_class, ei_data, &ClassifierOptions::new())
+}
+
+/// Like [`parse`], honouring `options`: `input_len` is the length of the
+/// input `data` was read from (see [`Absent`]), and the heuristic analysis
+/// of an `EM_NONE` object's `.text` runs with them.
+pub fn parse_with_options(
+    data: &[u8],
+    ei_class: u8,
+    ei_data: u8,
+    options: &ClassifierOptions,
+) -> Result<ClassificationResult> {
+    let input_len = options.input_len;
     if data.len() < 16 {
         return Err(ClassifierError::FileTooSmall {
             expected: 16,
@@ -1041,6 +2080,9 @@ pub fn parse(data: &[u8], ei_class: u8, ei_data: u8) -> Result<ClassificationRes
         read_u32(data, 0x24, little_endian)?
     };
 
+    let e_type = read_u16(data, 0x10, little_endian)?;
+    let relocatable = e_type == et::ET_REL;
+
     // Read entry point
     let entry_point = if is_64 {
         read_u64(data, 0x18, little_endian)?
@@ -1059,13 +2101,33 @@ pub fn parse(data: &[u8], ei_class: u8, ei_data: u8) -> Result<ClassificationRes
         bitwidth = 32;
     }
 
+    // There is no ELFCLASS128 or RV128 e_flags bit; a 64-bit container whose
+    // RISC-V arch attribute names rv128 is the only header-level signal
+    if isa == Isa::RiscV64
+        && riscv_arch_attribute(data, is_64, little_endian)
+            .is_some_and(|arch| arch.starts_with("rv128"))
+    {
+        isa = Isa::RiscV128;
+        bitwidth = 128;
+    }
+
     // Parse architecture-specific flags
-    let (variant, mut extensions) = parse_e_flags(isa, e_flags, data);
+    let (mut variant, mut extensions) = parse_e_flags(isa, e_flags, data);
+
+    if isa == Isa::OpenRisc && bitwidth == 64 {
+        variant = Variant::with_profile("OR64", "non-standard 64-bit OpenRISC");
+    }
 
     if isa == Isa::PpcVle {
         extensions.push(Extension::new("VLE", ExtensionCategory::Compressed));
     }
 
+    if isa == Isa::Arm {
+        if let Some(attributes) = arm_attributes(data, is_64, little_endian) {
+            attributes.apply(&mut variant, &mut extensions);
+        }
+    }
+
     // For AArch64, also parse GNU property notes for BTI/PAC/GCS info
     if isa == Isa::AArch64 {
         let gnu_extensions = parse_aarch64_gnu_properties(data, is_64, little_endian);
@@ -1079,22 +2141,291 @@ pub fn parse(data: &[u8], ei_class: u8, ei_data: u8) -> Result<ClassificationRes
         }
     }
 
+    let (mut custom, mut notes, os) = os_abi_metadata(data, isa, is_64, little_endian);
+    if let Some(format) = reloc_format(data, is_64, little_endian) {
+        custom.push(MetadataEntry::custom(
+            "reloc_format",
+            MetadataValue::String(format.to_string()),
+            "Relocation Format",
+        ));
+    }
+    if variant.abi.is_none() {
+        variant.abi = os.map(str::to_string);
+    }
+
+    // A loader for the other word size cannot run the binary: a classic
+    // cross-compilation slip (wrong sysroot or --dynamic-linker)
+    let mut warnings = Vec::new();
+    if let Some(interp) = interpreter(data, is_64, little_endian) {
+        let class_bits = if is_64 { 64 } else { 32 };
+        if let Some(bits) = interpreter_bits(&interp, e_machine).filter(|&bits| bits != class_bits)
+        {
+            warnings.push(format!(
+                "Interpreter {interp} is a {bits}-bit loader but the binary is {class_bits}-bit ELF"
+            ));
+        }
+    }
+
+    // Relocatable objects have no entry point; their code is in the
+    // executable sections reported as code ranges below
+    let mut section_count = None;
+    let mut heuristic = None;
+    if relocatable {
+        if variant.name.is_empty() {
+            variant.name = "relocatable object".to_string();
+        }
+        notes.push("Relocatable object (ET_REL)".to_string());
+        section_count = header_tables(data, is_64, little_endian).map(|t| t.shnum);
+        // EM_NONE says nothing about the target; let the code decide
+        let text = text_section(data, is_64, little_endian).filter(|_| e_machine == 0);
+        if let Some((offset, size)) = text {
+            // The range is clamped to the input, so it converts losslessly
+            let text = &data[offset as usize..][..size as usize];
+            heuristic = crate::heuristics::analyze(text, options).ok();
+            if let Some(found) = &heuristic {
+                notes.push(format!(
+                    "e_machine is EM_NONE; {} identified by heuristic analysis of .text",
+                    found.isa
+                ));
+            }
+        }
+    }
+
+    if e_machine == 0 && heuristic.is_none() {
+        notes.push(NO_MACHINE_NOTE.to_string());
+    }
+
+    // A prefix of a larger file may stop short of the header tables; the
+    // verdict above only needs the file header
+    if let Some(t) = header_tables(data, is_64, little_endian) {
+        let tables = [
+            ("program headers", t.phoff, t.phnum, t.phentsize),
+            ("section headers", t.shoff, t.shnum, t.shentsize),
+        ];
+        warnings.extend(
+            tables
+                .into_iter()
+                .filter(|&(_, offset, _, _)| offset != 0)
+                .filter_map(|(name, offset, num, entsize)| {
+                    let len = (num as u64).saturating_mul(entsize as u64);
+                    Absent::check(data, input_len, name, offset, len)
+                })
+                .map(|absent| absent.note()),
+        );
+    }
+
     // Build metadata
     let metadata = ClassificationMetadata {
-        entry_point: Some(entry_point),
+        entry_point: (!relocatable).then_some(entry_point),
+        section_count,
         flags: Some(e_flags),
         raw_machine: Some(e_machine as u32),
+        notes,
+        warnings,
+        custom,
+        header_size: Some(header_extent(data, ei_class, ei_data)),
+        described_size: Some(image_extent(data, ei_class, ei_data)),
+        code_ranges: executable_ranges(data, is_64, little_endian),
         ..Default::default()
     };
 
-    let mut result = ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::Elf);
-    result.variant = variant;
+    let mut result = match heuristic {
+        Some(found) => {
+            let mut result = ClassificationResult::from_format(
+                found.isa,
+                found.bitwidth,
+                found.endianness,
+                FileFormat::Elf,
+            );
+            result.confidence = found.confidence;
+            result.source = ClassificationSource::Combined;
+            result
+        }
+        None => ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::Elf),
+    };
+    result.variant = variant.non_empty();
     result.extensions = extensions;
     result.metadata = metadata;
 
     Ok(result)
 }
 
+/// Note for images whose `e_machine` is `EM_NONE` and stays unclassified.
+pub const NO_MACHINE_NOTE: &str = "ELF declares no machine (EM_NONE)";
+
+/// Human name of an `EI_OSABI` value, and whether it names an OS.
+///
+/// Values 64 and above are machine-specific, so `isa` picks their meaning.
+/// Returns `None` for values without a known assignment.
+pub fn osabi_name(value: u8, isa: Isa) -> Option<(&'static str, bool)> {
+    Some(match value {
+        osabi::ELFOSABI_NONE => ("UNIX System V", false),
+        1 => ("HP-UX", true),
+        2 => ("NetBSD", true),
+        osabi::ELFOSABI_GNU => ("GNU/Linux", true),
+        4 => ("GNU Hurd", true),
+        6 => ("Solaris", true),
+        7 => ("AIX", true),
+        8 => ("IRIX", true),
+        osabi::ELFOSABI_FREEBSD => ("FreeBSD", true),
+        10 => ("Tru64", true),
+        11 => ("Novell Modesto", true),
+        12 => ("OpenBSD", true),
+        13 => ("OpenVMS", true),
+        14 => ("HP NonStop Kernel", true),
+        15 => ("AROS", true),
+        16 => ("FenixOS", true),
+        17 => ("CloudABI", true),
+        18 => ("OpenVOS", true),
+        osabi::ELFOSABI_ARM_AEABI if isa == Isa::Arm => ("ARM EABI", false),
+        osabi::ELFOSABI_C6000_ELFABI if isa == Isa::TiC6000 => ("C6000 ELFABI", false),
+        osabi::ELFOSABI_ARM if isa == Isa::Arm => ("ARM", false),
+        osabi::ELFOSABI_STANDALONE => ("Standalone", false),
+        _ => return None,
+    })
+}
+
+/// Target OS and minimum kernel version from an `NT_GNU_ABI_TAG` note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GnuAbiTag {
+    /// OS code (0 = Linux, 1 = Hurd, 2 = Solaris, 3 = FreeBSD, ...)
+    pub os: u32,
+    /// Minimum kernel version (major, minor, subminor)
+    pub kernel: [u32; 3],
+}
+
+impl GnuAbiTag {
+    /// Human name of the OS code.
+    pub fn os_name(&self) -> String {
+        match self.os {
+            0 => "Linux".to_string(),
+            1 => "GNU Hurd".to_string(),
+            2 => "Solaris".to_string(),
+            3 => "FreeBSD".to_string(),
+            4 => "NetBSD".to_string(),
+            5 => "Syllable".to_string(),
+            os => format!("unknown ({os})"),
+        }
+    }
+
+    /// Minimum kernel version as `major.minor.subminor`.
+    pub fn kernel_version(&self) -> String {
+        let [major, minor, subminor] = self.kernel;
+        format!("{major}.{minor}.{subminor}")
+    }
+}
+
+/// Find the `NT_GNU_ABI_TAG` note in a `PT_NOTE` segment or `SHT_NOTE`
+/// section (`.note.ABI-tag`).
+pub fn gnu_abi_tag(data: &[u8], is_64: bool, little_endian: bool) -> Option<GnuAbiTag> {
+    const SHT_NOTE: u32 = 7;
+
+    let segments = program_headers(data, is_64, little_endian)
+        .into_iter()
+        .filter(|ph| ph.p_type == pt::PT_NOTE)
+        .map(|ph| (ph.p_offset, ph.p_filesz));
+    let sections = section_headers(data, is_64, little_endian)
+        .into_iter()
+        .filter(|sh| sh.sh_type == SHT_NOTE)
+        .map(|sh| (sh.sh_offset, sh.sh_size));
+
+    segments.chain(sections).find_map(|(offset, size)| {
+        let notes = crate::formats::read_bytes(
+            data,
+            usize::try_from(offset).ok()?,
+            usize::try_from(size).ok()?,
+        )
+        .ok()?;
+        find_abi_tag(notes, little_endian)
+    })
+}
+
+/// Walk a note area (4-byte aligned, as the ABI tag always is).
+fn find_abi_tag(notes: &[u8], little_endian: bool) -> Option<GnuAbiTag> {
+    let align = |n: usize| n.checked_add(3).map(|n| n & !3);
+    let mut offset = 0;
+    while offset + 12 <= notes.len() {
+        let namesz = read_u32(notes, offset, little_endian).ok()? as usize;
+        let descsz = read_u32(notes, offset + 4, little_endian).ok()? as usize;
+        let note_type = read_u32(notes, offset + 8, little_endian).ok()?;
+        let name_start = offset + 12;
+        let desc_start = name_start.checked_add(align(namesz)?)?;
+
+        if note_type == nt::NT_GNU_ABI_TAG
+            && notes.get(name_start..name_start + namesz) == Some(&b"GNU\0"[..])
+            && descsz >= 16
+        {
+            let word = |i: usize| read_u32(notes, desc_start + 4 * i, little_endian).ok();
+            return Some(GnuAbiTag {
+                os: word(0)?,
+                kernel: [word(1)?, word(2)?, word(3)?],
+            });
+        }
+        offset = desc_start.checked_add(align(descsz)?)?;
+    }
+    None
+}
+
+/// OS ABI metadata from `e_ident` and the GNU ABI tag, if present.
+///
+/// Returns the metadata entries, the notes, and the OS name to use as the
+/// variant ABI when the header names one.
+fn os_abi_metadata(
+    data: &[u8],
+    isa: Isa,
+    is_64: bool,
+    little_endian: bool,
+) -> (Vec<MetadataEntry>, Vec<String>, Option<&'static str>) {
+    let mut entries = Vec::new();
+    let mut notes = Vec::new();
+    let mut os = None;
+
+    let value = data[ident::EI_OSABI];
+    let name = match osabi_name(value, isa) {
+        Some((name, names_os)) => {
+            os = names_os.then_some(name);
+            name.to_string()
+        }
+        None => format!("unknown ({value})"),
+    };
+    if value != osabi::ELFOSABI_NONE {
+        notes.push(format!("OS ABI: {name}"));
+    }
+    entries.push(MetadataEntry::custom(
+        "osabi",
+        MetadataValue::String(name),
+        "OS ABI",
+    ));
+    let abi_version = data[ident::EI_ABIVERSION];
+    if abi_version != 0 {
+        entries.push(MetadataEntry::custom(
+            "abi_version",
+            MetadataValue::Integer(u64::from(abi_version)),
+            "ABI Version",
+        ));
+    }
+
+    if let Some(tag) = gnu_abi_tag(data, is_64, little_endian) {
+        notes.push(format!(
+            "GNU ABI tag: {} {} or later",
+            tag.os_name(),
+            tag.kernel_version()
+        ));
+        entries.push(MetadataEntry::custom(
+            "abi_tag_os",
+            MetadataValue::String(tag.os_name()),
+            "ABI Tag OS",
+        ));
+        entries.push(MetadataEntry::custom(
+            "min_kernel_version",
+            MetadataValue::String(tag.kernel_version()),
+            "Minimum Kernel",
+        ));
+    }
+
+    (entries, notes, os)
+}
+
 fn has_ppc_vle_marker(data: &[u8]) -> bool {
     const MARKERS: [&[u8]; 3] = [b".vletext", b".PPC.EMB.vle", b"vle_off"]; // binutils/GCC conventions
 
@@ -1151,6 +2482,111 @@ mod tests {
         assert_eq!(result.isa, Isa::RiscV64);
     }
 
+    /// `.riscv.attributes` contents whose `riscv` subsection holds
+    /// `Tag_stack_align` 16 and `Tag_RISCV_arch` `arch`.
+    fn riscv_attributes_section(arch: &str) -> Vec<u8> {
+        let mut tags = vec![4u8, 16, 5];
+        tags.extend_from_slice(arch.as_bytes());
+        tags.push(0);
+        let mut file = vec![1u8]; // Tag_File
+        file.extend_from_slice(&u32::try_from(tags.len() + 5).unwrap().to_le_bytes());
+        file.extend_from_slice(&tags);
+        let mut section = vec![b'A'];
+        let len = u32::try_from(4 + 6 + file.len()).unwrap();
+        section.extend_from_slice(&len.to_le_bytes());
+        section.extend_from_slice(b"riscv\0");
+        section.extend_from_slice(&file);
+        section
+    }
+
+    /// Little-endian ELF with the given `(sh_type, contents)` sections after
+    /// the null section.
+    fn make_elf_with_sections(e_machine: u16, class: u8, sections: &[(u32, &[u8])]) -> Vec<u8> {
+        let is_64 = class == 2;
+        let shentsize = if is_64 { 64 } else { 40 };
+        let mut data = make_elf_header(e_machine, class, 1);
+        let mut headers = vec![0u8; shentsize]; // SHN_UNDEF
+        for (sh_type, contents) in sections {
+            let offset = data.len() as u64;
+            data.extend_from_slice(contents);
+            let mut sh = vec![0u8; shentsize];
+            sh[4..8].copy_from_slice(&sh_type.to_le_bytes());
+            if is_64 {
+                sh[0x18..0x20].copy_from_slice(&offset.to_le_bytes());
+                sh[0x20..0x28].copy_from_slice(&(contents.len() as u64).to_le_bytes());
+            } else {
+                sh[0x10..0x14].copy_from_slice(&(offset as u32).to_le_bytes());
+                sh[0x14..0x18].copy_from_slice(&(contents.len() as u32).to_le_bytes());
+            }
+            headers.extend_from_slice(&sh);
+        }
+        let shoff = data.len() as u64;
+        data.extend_from_slice(&headers);
+        let shnum = u16::try_from(sections.len() + 1).unwrap();
+        if is_64 {
+            data[0x28..0x30].copy_from_slice(&shoff.to_le_bytes());
+            data[0x3A..0x3C].copy_from_slice(&(shentsize as u16).to_le_bytes());
+            data[0x3C..0x3E].copy_from_slice(&shnum.to_le_bytes());
+        } else {
+            data[0x20..0x24].copy_from_slice(&(shoff as u32).to_le_bytes());
+            data[0x2E..0x30].copy_from_slice(&(shentsize as u16).to_le_bytes());
+            data[0x30..0x32].copy_from_slice(&shnum.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_width_follows_class_for_riscv_and_openrisc() {
+        // (e_machine, class, Tag_RISCV_arch, isa, bitwidth, variant)
+        type Case = (u16, u8, &'static str, Isa, u8, &'static str);
+        let cases: [Case; 6] = [
+            (0xF3, 1, "", Isa::RiscV32, 32, "RISC-V"),
+            (0xF3, 2, "", Isa::RiscV64, 64, "RISC-V"),
+            (0xF3, 2, "rv128i2p1_m2p0", Isa::RiscV128, 128, "RISC-V"),
+            (0xF3, 1, "rv128i2p1_m2p0", Isa::RiscV32, 32, "RISC-V"),
+            (0x5C, 1, "", Isa::OpenRisc, 32, ""),
+            (0x5C, 2, "", Isa::OpenRisc, 64, "OR64"),
+        ];
+        for (machine, class, arch, isa, bitwidth, variant) in cases {
+            let data = if arch.is_empty() {
+                make_elf_header(machine, class, 1)
+            } else {
+                let attributes = riscv_attributes_section(arch);
+                make_elf_with_sections(machine, class, &[(sht::SHT_RISCV_ATTRIBUTES, &attributes)])
+            };
+            let result = parse(&data, class, 1).unwrap();
+            assert_eq!(
+                (result.isa, result.bitwidth, result.variant_name()),
+                (isa, bitwidth, (!variant.is_empty()).then_some(variant)),
+                "e_machine 0x{machine:X}, class {class}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rv128_string_outside_attributes_is_ignored() {
+        let attributes = riscv_attributes_section("rv64i2p1_m2p0_a2p1_c2p0");
+        let rodata = b"usage: build --march=rv128imac\0".to_vec();
+        let data = make_elf_with_sections(
+            0xF3,
+            2,
+            &[
+                (sht::SHT_PROGBITS, &rodata),
+                (sht::SHT_RISCV_ATTRIBUTES, &attributes),
+            ],
+        );
+        assert_eq!(
+            riscv_arch_attribute(&data, true, true).as_deref(),
+            Some("rv64i2p1_m2p0_a2p1_c2p0")
+        );
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!((result.isa, result.bitwidth), (Isa::RiscV64, 64));
+
+        // Without any attributes section the string is not consulted either
+        let data = make_elf_with_sections(0xF3, 2, &[(sht::SHT_PROGBITS, &rodata)]);
+        assert_eq!(parse(&data, 2, 1).unwrap().isa, Isa::RiscV64);
+    }
+
     #[test]
     fn test_parse_arm_elf() {
         let data = make_elf_header(0x28, 1, 1);
@@ -1159,6 +2595,96 @@ mod tests {
         assert_eq!(result.bitwidth, 32);
     }
 
+    fn custom_value(result: &ClassificationResult, key: &str) -> Option<String> {
+        result
+            .metadata
+            .custom
+            .iter()
+            .find(|e| e.key == crate::types::MetadataKey::Custom(key.to_string()))
+            .map(|e| e.value.to_string())
+    }
+
+    #[test]
+    fn test_osabi() {
+        let mut data = make_elf_header(0x3E, 2, 1);
+        data[ident::EI_OSABI] = osabi::ELFOSABI_FREEBSD;
+        data[ident::EI_ABIVERSION] = 1;
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(custom_value(&result, "osabi").as_deref(), Some("FreeBSD"));
+        assert_eq!(custom_value(&result, "abi_version").as_deref(), Some("1"));
+        assert_eq!(
+            result.variant.as_ref().and_then(|v| v.abi.as_deref()),
+            Some("FreeBSD")
+        );
+
+        data[ident::EI_OSABI] = osabi::ELFOSABI_STANDALONE;
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(
+            custom_value(&result, "osabi").as_deref(),
+            Some("Standalone")
+        );
+        assert_eq!(result.variant, None);
+
+        data[ident::EI_OSABI] = 200;
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(
+            custom_value(&result, "osabi").as_deref(),
+            Some("unknown (200)")
+        );
+
+        // ARM EABI only means that on EM_ARM
+        assert_eq!(osabi_name(64, Isa::Arm), Some(("ARM EABI", false)));
+        assert_eq!(osabi_name(64, Isa::X86_64), None);
+    }
+
+    #[test]
+    fn test_gnu_abi_tag() {
+        let mut data = make_elf_header(0x3E, 2, 1);
+        let mut ph = vec![0u8; 56];
+        ph[0..4].copy_from_slice(&pt::PT_NOTE.to_le_bytes());
+        ph[8..16].copy_from_slice(&0x78u64.to_le_bytes());
+        ph[0x20..0x28].copy_from_slice(&32u64.to_le_bytes());
+        data.extend_from_slice(&ph);
+        data[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes()); // e_phoff
+        data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        data[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        // .note.ABI-tag: Linux 3.2.0
+        for word in [4u32, 16, nt::NT_GNU_ABI_TAG] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(b"GNU\0");
+        for word in [0u32, 3, 2, 0] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(
+            custom_value(&result, "osabi").as_deref(),
+            Some("UNIX System V")
+        );
+        assert_eq!(
+            custom_value(&result, "abi_tag_os").as_deref(),
+            Some("Linux")
+        );
+        assert_eq!(
+            custom_value(&result, "min_kernel_version").as_deref(),
+            Some("3.2.0")
+        );
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n == "GNU ABI tag: Linux 3.2.0 or later"));
+
+        let os = crate::detect_payload(&data, &crate::ClassifierOptions::new())
+            .unwrap()
+            .os
+            .unwrap();
+        assert_eq!(os.family, crate::types::OsFamily::Linux);
+        assert_eq!(os.version_hint.as_deref(), Some("3.2.0"));
+    }
+
     #[test]
     fn test_parse_ppc_vle_marker() {
         let mut data = make_elf_header(0x14, 1, 2);
@@ -1191,4 +2717,943 @@ mod tests {
         assert_eq!(e_machine_to_isa(0xF9, 1).0, Isa::Mips);
         assert_eq!(e_machine_to_isa(0x102, 2).0, Isa::LoongArch64);
     }
+
+    /// `(e_machine, ELF class, Isa)` for every ISA the parser reports from
+    /// `e_machine`.
+    const EM_COVERAGE: &[(u16, u8, Isa)] = &[
+        (0x03, 1, Isa::X86),
+        (0x3E, 2, Isa::X86_64),
+        (0x28, 1, Isa::Arm),
+        (0xB7, 2, Isa::AArch64),
+        (0xF3, 1, Isa::RiscV32),
+        (0xF3, 2, Isa::RiscV64),
+        (0x08, 1, Isa::Mips),
+        (0x08, 2, Isa::Mips64),
+        (0x14, 1, Isa::Ppc),
+        (0x15, 2, Isa::Ppc64),
+        (0x16, 1, Isa::S390),
+        (0x16, 2, Isa::S390x),
+        (0x02, 1, Isa::Sparc),
+        (0x2B, 2, Isa::Sparc64),
+        (0x04, 1, Isa::M68k),
+        (0x34, 1, Isa::ColdFire),
+        (0x2A, 1, Isa::Sh),
+        (0x32, 2, Isa::Ia64),
+        (0x29, 2, Isa::Alpha),
+        (0x0F, 1, Isa::Parisc),
+        (0x102, 1, Isa::LoongArch32),
+        (0x102, 2, Isa::LoongArch64),
+        (0xA4, 1, Isa::Hexagon),
+        (0x2D, 1, Isa::Arc),
+        (0x5D, 1, Isa::ArcCompact),
+        (0xC3, 1, Isa::ArcCompact2),
+        (0x5E, 1, Isa::Xtensa),
+        (0xBD, 1, Isa::MicroBlaze),
+        (0x71, 1, Isa::Nios2),
+        (0x5C, 1, Isa::OpenRisc),
+        (0xF4, 1, Isa::Lanai),
+        (0xFC, 1, Isa::Csky),
+        (0x57, 1, Isa::V850),
+        (0x24, 1, Isa::Rh850),
+        (0xC7, 1, Isa::K78k0r),
+        (0xAD, 1, Isa::Rx),
+        (0x8C, 1, Isa::TiC6000),
+        (0x8D, 1, Isa::TiC28x),
+        (0x8E, 1, Isa::TiC5500),
+        (0x90, 1, Isa::TiPru),
+        (0x6A, 1, Isa::Blackfin),
+        (0x85, 1, Isa::Sharc),
+        (0x2C, 1, Isa::Tricore),
+        (0x35, 1, Isa::Hcs12),
+        (0x46, 1, Isa::Hc11),
+        (0x74, 1, Isa::C166),
+        (0xC5, 1, Isa::Rl78),
+        (0x53, 1, Isa::Avr),
+        (0xB9, 1, Isa::Avr32),
+        (0x69, 1, Isa::Msp430),
+        (0xCC, 1, Isa::Pic),
+        (0x76, 1, Isa::Pic24),
+        (0xBA, 1, Isa::Stm8),
+        (0xE0, 2, Isa::AmdGpu),
+        (0xBE, 2, Isa::Cuda),
+        (0xF7, 2, Isa::Bpf),
+        (0x07, 1, Isa::I860),
+        (0x13, 1, Isa::I960),
+        (0x4B, 1, Isa::Vax),
+        (0x41, 1, Isa::Pdp11),
+        (0xDC, 1, Isa::Z80),
+        (0xFE, 1, Isa::Mcs6502),
+        (0x101, 1, Isa::W65816),
+        (0xBB, 1, Isa::Tile64),
+        (0xBC, 1, Isa::TilePro),
+        (0xBF, 2, Isa::TileGx),
+        (0x89, 1, Isa::VideoCore3),
+        (0xC6, 1, Isa::VideoCore5),
+        (0x100, 2, Isa::Kvx),
+        (0xAF, 2, Isa::McstElbrus),
+        (0x5441, 1, Isa::Frv),
+        (0x54, 1, Isa::Fr30),
+        (0x17, 1, Isa::CellSpu),
+    ];
+
+    /// ISAs with no `e_machine` of their own: refined from `e_flags`,
+    /// sharing another ISA's value, or not carried in ELF at all.
+    const NO_E_MACHINE: &[Isa] = &[
+        Isa::RiscV128, // no ELFCLASS128
+        Isa::Sh4,      // EM_SH + e_flags
+        Isa::PpcVle,   // EM_PPC + e_flags
+        Isa::TiC2000,  // shares EM_TI_C2000 with TiC28x
+        Isa::S12z,
+        Isa::Elbrus, // pre-e2k; e2k images are McstElbrus
+        Isa::Fr80,
+        Isa::Wasm,
+        Isa::Jvm,
+        Isa::Dalvik,
+        Isa::Clr,
+        Isa::Ebc,
+        Isa::Dis,
+    ];
+
+    /// Registered `e_machine` values for architectures the crate has no
+    /// `Isa` for; they stay `Isa::Unknown` until one is added.
+    const KNOWN_UNMAPPED: &[(u16, &str)] = &[
+        (0x25, "EM_FR20"),
+        (0x4C, "EM_CRIS"),
+        (0x58, "EM_M32R"),
+        (0x59, "EM_MN10300"),
+        (0x5A, "EM_MN10200"),
+    ];
+
+    #[test]
+    fn test_e_machine_completeness() {
+        let mut code = 1;
+        while let Some(isa) = Isa::from_code(code) {
+            let mapped = EM_COVERAGE.iter().any(|&(_, _, i)| i == isa);
+            assert!(
+                mapped != NO_E_MACHINE.contains(&isa),
+                "{isa:?} must be listed in exactly one of EM_COVERAGE and NO_E_MACHINE"
+            );
+            code += 1;
+        }
+
+        for &(e_machine, class, isa) in EM_COVERAGE {
+            assert_eq!(
+                e_machine_to_isa(e_machine, class).0,
+                isa,
+                "e_machine 0x{e_machine:X}"
+            );
+        }
+        for &(e_machine, name) in KNOWN_UNMAPPED {
+            assert_eq!(
+                e_machine_to_isa(e_machine, 1).0,
+                Isa::Unknown(u32::from(e_machine)),
+                "{name} now maps to an ISA; move it to EM_COVERAGE"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cygnus_fujitsu_machines() {
+        assert_eq!(e_machine_to_isa(0x3330, 1), (Isa::Fr30, 32));
+        assert_eq!(e_machine_to_isa(0x5441, 1), (Isa::Frv, 32));
+        // 87 is EM_V850, not FR-V
+        assert_eq!(e_machine_to_isa(87, 1).0, Isa::V850);
+    }
+
+    /// Build a little-endian ELF64 x86-64 image with one LOAD segment and
+    /// sections: NULL, .text, .data, `extra` filler sections, .shstrtab.
+    fn make_sectioned_elf(extra: usize) -> Vec<u8> {
+        let mut data = make_elf_header(0x3E, 2, 1);
+        data[0x10] = 2; // ET_EXEC
+
+        // Program header: PT_LOAD R-X at 0x40
+        let mut ph = vec![0u8; 56];
+        ph[0..4].copy_from_slice(&pt::PT_LOAD.to_le_bytes());
+        ph[4..8].copy_from_slice(&(pf::PF_R | pf::PF_X).to_le_bytes());
+        ph[0x10..0x18].copy_from_slice(&0x40_0000u64.to_le_bytes());
+        ph[0x20..0x28].copy_from_slice(&0x200u64.to_le_bytes());
+        ph[0x28..0x30].copy_from_slice(&0x200u64.to_le_bytes());
+        data.extend_from_slice(&ph);
+
+        let text_off = data.len() as u64;
+        data.extend_from_slice(&[0x90; 16]);
+        let data_off = data.len() as u64;
+        data.extend_from_slice(&[0u8; 8]);
+
+        let mut names = vec![0u8];
+        let mut entries: Vec<(u32, u64, u64, u64)> = vec![(0, 0, 0, 0)];
+        let add_name = |names: &mut Vec<u8>, name: &str| {
+            let off = u32::try_from(names.len()).unwrap();
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+            off
+        };
+        let n = add_name(&mut names, ".text");
+        entries.push((n, shf::SHF_ALLOC | shf::SHF_EXECINSTR, text_off, 16));
+        let n = add_name(&mut names, ".data");
+        entries.push((n, shf::SHF_ALLOC | shf::SHF_WRITE, data_off, 8));
+        for i in 0..extra {
+            let n = add_name(&mut names, &format!(".fill{i}"));
+            entries.push((n, 0, 0, 0));
+        }
+        let n = add_name(&mut names, ".shstrtab");
+        let strtab_off = data.len() as u64;
+        entries.push((n, 0, strtab_off, names.len() as u64));
+        data.extend_from_slice(&names);
+
+        let shoff = data.len() as u64;
+        for (name, flags, off, size) in &entries {
+            let mut sh = vec![0u8; 64];
+            sh[0..4].copy_from_slice(&name.to_le_bytes());
+            sh[4..8].copy_from_slice(&1u32.to_le_bytes());
+            sh[8..16].copy_from_slice(&flags.to_le_bytes());
+            sh[0x18..0x20].copy_from_slice(&off.to_le_bytes());
+            sh[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&sh);
+        }
+
+        data[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes()); // e_phoff
+        data[0x28..0x30].copy_from_slice(&shoff.to_le_bytes()); // e_shoff
+        data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        data[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+        data[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes()); // e_shentsize
+        let shnum = u16::try_from(entries.len()).unwrap();
+        data[0x3C..0x3E].copy_from_slice(&shnum.to_le_bytes());
+        data[0x3E..0x40].copy_from_slice(&(shnum - 1).to_le_bytes()); // e_shstrndx
+        data
+    }
+
+    #[test]
+    fn test_inventory_sections_and_segments() {
+        let data = make_sectioned_elf(0);
+        let inv = inventory(&data, 2, 1, 128);
+        let names: Vec<&str> = inv.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["", ".text", ".data", ".shstrtab"]);
+        assert!(inv.sections[1].flags_execinstr);
+        assert!(!inv.sections[1].flags_write);
+        assert!(inv.sections[2].flags_write);
+        assert!(!inv.sections[2].flags_execinstr);
+        assert_eq!(inv.sections[1].size, 16);
+
+        assert_eq!(inv.segments.len(), 1);
+        assert_eq!(inv.segments[0].kind, "LOAD");
+        assert!(inv.segments[0].flags_execute && !inv.segments[0].flags_write);
+        assert!(!inv.is_truncated());
+    }
+
+    #[test]
+    fn test_inventory_truncation_cap() {
+        let data = make_sectioned_elf(10);
+        let inv = inventory(&data, 2, 1, 5);
+        assert_eq!(inv.sections.len(), 5);
+        assert_eq!(inv.total_sections, 14);
+        assert!(inv.is_truncated());
+    }
+
+    #[test]
+    fn test_inventory_truncated_file() {
+        let data = make_sectioned_elf(0);
+        // Cut off the last section header
+        let inv = inventory(&data[..data.len() - 1], 2, 1, 128);
+        assert_eq!(inv.total_sections, 3);
+        // shstrtab header is gone, so names cannot be resolved
+        assert!(inv.sections.iter().all(|s| s.name.is_empty()));
+        assert!(inventory(&data[..0x30], 2, 1, 128).sections.is_empty());
+    }
+
+    #[test]
+    fn test_header_tables_beyond_input() {
+        let data = make_sectioned_elf(0);
+        assert!(parse(&data, 2, 1).unwrap().metadata.warnings.is_empty());
+
+        // Header and program headers only: the verdict stands
+        let prefix = &data[..0x40 + 56];
+        let result = parse(prefix, 2, 1).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        let shoff = read_u64(&data, 0x28, true).unwrap();
+        assert_eq!(
+            result.metadata.warnings,
+            [format!(
+                "section headers at offset 0x{shoff:x} not examined \
+                 (input supplied ends at 0x78)"
+            )]
+        );
+
+        // Header only
+        let result = parse(&data[..0x40], 2, 1).unwrap();
+        assert_eq!(result.metadata.warnings.len(), 2);
+        assert!(result.metadata.warnings[0].starts_with("program headers at offset 0x40 "));
+
+        // A prefix of a file known to be longer is still only unexamined
+        let options = ClassifierOptions {
+            input_len: Some(data.len() as u64),
+            ..ClassifierOptions::new()
+        };
+        let result = parse_with_options(prefix, 2, 1, &options).unwrap();
+        assert!(result.metadata.warnings[0].contains("(input supplied ends at 0x78)"));
+
+        // The whole file: the section headers are cut off
+        let options = ClassifierOptions {
+            input_len: Some(prefix.len() as u64),
+            ..options
+        };
+        let result = parse_with_options(prefix, 2, 1, &options).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(
+            result.metadata.warnings,
+            [format!(
+                "section headers at offset 0x{shoff:x} extends past the end of the file (truncated)"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_executable_ranges() {
+        let mut data = make_sectioned_elf(0);
+        let text = (64 + 56, 16);
+        assert_eq!(executable_ranges(&data, true, true), [text]);
+
+        // Stripped section headers: the R-X PT_LOAD, clamped to the input
+        data[0x28..0x30].fill(0);
+        let len = data.len() as u64;
+        assert_eq!(executable_ranges(&data, true, true), [(0, len)]);
+    }
+
+    #[test]
+    fn test_image_extent() {
+        // The PT_LOAD segment claims 0x200 bytes, past the section data
+        let data = make_sectioned_elf(0);
+        assert!(data.len() < 0x200);
+        assert_eq!(image_extent(&data, 2, 1), 0x200);
+        // No header tables: just the file header
+        assert_eq!(image_extent(&make_elf_header(0xB7, 2, 1), 2, 1), 64);
+    }
+
+    #[test]
+    fn test_concatenated_elves() {
+        let mut data = make_sectioned_elf(0);
+        data.resize(0x200, 0);
+        // Alignment padding before the second image
+        data.resize(0x240, 0);
+        data.extend_from_slice(&make_elf_header(0xB7, 2, 1));
+        // A stray magic with a bogus class is skipped
+        data.extend_from_slice(b"\x7FELF\x09\x01\x01\x00");
+        let mut mips = make_elf_header(0x08, 1, 2);
+        mips.truncate(52);
+        data.extend_from_slice(&mips);
+
+        let images = appended_images(&data, 2, 1);
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].offset, 0x240);
+        assert_eq!(images[0].size, 64);
+        assert_eq!(images[0].result.isa, Isa::AArch64);
+        assert_eq!(images[1].offset, 0x240 + 64 + 8);
+        assert_eq!(images[1].result.isa, Isa::Mips);
+        assert_eq!(images[1].result.endianness, Endianness::Big);
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.primary.isa, Isa::X86_64);
+        assert!(payload.slices.is_empty());
+
+        let options = crate::ClassifierOptions {
+            scan_concatenated_elf: true,
+            ..crate::ClassifierOptions::new()
+        };
+        let payload = crate::detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.primary.isa, Isa::X86_64);
+        let slices: Vec<(Isa, u64)> = payload.slices.iter().map(|s| (s.isa, s.offset)).collect();
+        assert_eq!(slices, [(Isa::AArch64, 0x240), (Isa::Mips, 0x288)]);
+        let offset = payload
+            .metadata
+            .iter()
+            .find(|m| m.key == crate::MetadataKey::Custom("elf_image_1_offset".into()))
+            .unwrap();
+        assert!(matches!(offset.value, crate::MetadataValue::Address(0x240)));
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("2 further ELF image(s)")));
+    }
+
+    #[test]
+    fn test_payload_collect_sections_option() {
+        let data = make_sectioned_elf(10);
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert!(payload.sections.is_empty());
+        assert!(payload.segments.is_empty());
+
+        let options = crate::ClassifierOptions {
+            collect_sections: true,
+            max_sections: 4,
+            ..crate::ClassifierOptions::new()
+        };
+        let payload = crate::detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.sections.len(), 4);
+        assert_eq!(payload.segments.len(), 1);
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("truncated to 4 of 14 sections")));
+    }
+
+    /// Knobs for [`make_hardened_elf`].
+    #[derive(Clone, Copy)]
+    struct Hardening {
+        e_type: u16,
+        interp: bool,
+        stack_exec: bool,
+        relro: bool,
+        bind_now: bool,
+        canary: bool,
+    }
+
+    const HARDENED: Hardening = Hardening {
+        e_type: et::ET_DYN,
+        interp: true,
+        stack_exec: false,
+        relro: true,
+        bind_now: true,
+        canary: true,
+    };
+
+    /// Little-endian ELF64 x86-64 image with a dynamic section whose
+    /// program headers and dynamic tags follow `h`.
+    fn make_hardened_elf(h: Hardening) -> Vec<u8> {
+        let mut data = make_elf_header(0x3E, 2, 1);
+        data[0x10..0x12].copy_from_slice(&h.e_type.to_le_bytes());
+        data.resize(0x400, 0);
+
+        let dynstr: &[u8] = if h.canary {
+            b"\0libc.so.6\0__stack_chk_fail\0"
+        } else {
+            b"\0libc.so.6\0puts\0"
+        };
+        data[0x200..0x200 + dynstr.len()].copy_from_slice(dynstr);
+
+        let mut tags = vec![
+            (dt::DT_STRTAB, 0x200u64),
+            (dt::DT_STRSZ, dynstr.len() as u64),
+        ];
+        if h.bind_now {
+            tags.push((dt::DT_FLAGS, df::DF_BIND_NOW));
+        }
+        tags.push((dt::DT_NULL, 0));
+        for (i, (tag, value)) in tags.iter().enumerate() {
+            let off = 0x300 + i * 16;
+            data[off..off + 8].copy_from_slice(&tag.to_le_bytes());
+            data[off + 8..off + 16].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let stack_flags = pf::PF_R | pf::PF_W | if h.stack_exec { pf::PF_X } else { 0 };
+        let mut phdrs = vec![
+            (pt::PT_LOAD, pf::PF_R | pf::PF_X, 0u64, 0x400u64),
+            (
+                pt::PT_DYNAMIC,
+                pf::PF_R | pf::PF_W,
+                0x300,
+                (tags.len() * 16) as u64,
+            ),
+            (pt::PT_GNU_STACK, stack_flags, 0, 0),
+        ];
+        if h.interp {
+            phdrs.push((pt::PT_INTERP, pf::PF_R, 0x1C0, 0x10));
+        }
+        if h.relro {
+            phdrs.push((pt::PT_GNU_RELRO, pf::PF_R, 0x300, 0x100));
+        }
+        for (i, (p_type, flags, off, size)) in phdrs.iter().enumerate() {
+            let base = 0x40 + i * 56;
+            data[base..base + 4].copy_from_slice(&p_type.to_le_bytes());
+            data[base + 4..base + 8].copy_from_slice(&flags.to_le_bytes());
+            data[base + 8..base + 0x10].copy_from_slice(&off.to_le_bytes());
+            data[base + 0x10..base + 0x18].copy_from_slice(&off.to_le_bytes());
+            data[base + 0x20..base + 0x28].copy_from_slice(&size.to_le_bytes());
+            data[base + 0x28..base + 0x30].copy_from_slice(&size.to_le_bytes());
+        }
+
+        data[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes()); // e_phoff
+        data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        let phnum = u16::try_from(phdrs.len()).unwrap();
+        data[0x38..0x3A].copy_from_slice(&phnum.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_hardening_flags() {
+        let h = hardening(&make_hardened_elf(HARDENED), 2, 1);
+        assert!(h.pie && h.nx && h.full_relro());
+        assert_eq!(h.stack_canary, Some(true));
+        assert_eq!(h.note, "PIE, NX, full RELRO, canary");
+        assert_eq!(h.high_entropy_va, None);
+
+        let exec = Hardening {
+            e_type: et::ET_EXEC,
+            ..HARDENED
+        };
+        assert!(!hardening(&make_hardened_elf(exec), 2, 1).pie);
+        // A shared library has no interpreter and is not a PIE
+        let dso = Hardening {
+            interp: false,
+            ..HARDENED
+        };
+        assert!(!hardening(&make_hardened_elf(dso), 2, 1).pie);
+
+        let h = hardening(
+            &make_hardened_elf(Hardening {
+                stack_exec: true,
+                ..HARDENED
+            }),
+            2,
+            1,
+        );
+        assert!(!h.nx);
+
+        let h = hardening(
+            &make_hardened_elf(Hardening {
+                bind_now: false,
+                ..HARDENED
+            }),
+            2,
+            1,
+        );
+        assert_eq!((h.relro, h.bind_now), (Some(true), Some(false)));
+        assert!(h.note.contains("partial RELRO"));
+
+        let h = hardening(
+            &make_hardened_elf(Hardening {
+                relro: false,
+                canary: false,
+                ..HARDENED
+            }),
+            2,
+            1,
+        );
+        assert!(!h.full_relro());
+        assert_eq!(h.stack_canary, Some(false));
+        assert!(h.note.contains("no RELRO") && h.note.contains("no canary"));
+    }
+
+    #[test]
+    fn test_hardening_payload_option() {
+        let data = make_hardened_elf(HARDENED);
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert!(payload.hardening.is_none());
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::thorough()).unwrap();
+        let h = payload.hardening.expect("thorough mode reports hardening");
+        assert!(h.pie && h.nx);
+        let json = serde_json::to_string(&h).unwrap();
+        assert!(json.contains("\"bind_now\":true"));
+        assert!(!json.contains("control_flow_guard"));
+    }
+
+    /// [`make_hardened_elf`] with its dynamic array replaced by `tags`.
+    fn with_dynamic_tags(mut data: Vec<u8>, tags: &[(u64, u64)]) -> Vec<u8> {
+        for (i, (tag, value)) in tags.iter().chain(&[(dt::DT_NULL, 0)]).enumerate() {
+            let off = 0x300 + i * 16;
+            data[off..off + 8].copy_from_slice(&tag.to_le_bytes());
+            data[off + 8..off + 16].copy_from_slice(&value.to_le_bytes());
+        }
+        // PT_DYNAMIC is the second program header
+        let size = ((tags.len() + 1) * 16) as u64;
+        data[0x40 + 56 + 0x20..0x40 + 56 + 0x28].copy_from_slice(&size.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_reloc_format() {
+        let base = || make_hardened_elf(HARDENED);
+        let relr = with_dynamic_tags(
+            base(),
+            &[
+                (dt::DT_RELA, 0x380),
+                (dt::DT_PLTREL, dt::DT_RELA),
+                (dt::DT_RELR, 0x3C0),
+            ],
+        );
+        assert_eq!(reloc_format(&relr, true, true), Some("relr"));
+
+        let rela = with_dynamic_tags(base(), &[(dt::DT_RELA, 0x380)]);
+        assert_eq!(reloc_format(&rela, true, true), Some("rela"));
+        let rel = with_dynamic_tags(base(), &[(dt::DT_PLTREL, dt::DT_REL)]);
+        assert_eq!(reloc_format(&rel, true, true), Some("rel"));
+        assert_eq!(reloc_format(&base(), true, true), None);
+
+        let payload = crate::detect_payload(&relr, &crate::ClassifierOptions::new()).unwrap();
+        let key = crate::types::MetadataKey::Custom("reloc_format".to_string());
+        let entry = payload.metadata.iter().find(|e| e.key == key).unwrap();
+        assert!(matches!(&entry.value, MetadataValue::String(v) if v == "relr"));
+    }
+
+    /// Little-endian ELF64 `ET_REL` object with sections NULL, .text
+    /// (holding `text`) and .shstrtab, and no program headers.
+    fn make_rel_object(e_machine: u16, text: &[u8]) -> Vec<u8> {
+        let mut data = make_elf_header(e_machine, 2, 1);
+        data[0x10..0x12].copy_from_slice(&et::ET_REL.to_le_bytes());
+
+        let text_off = data.len() as u64;
+        data.extend_from_slice(text);
+        let names = b"\0.text\0.shstrtab\0";
+        let strtab_off = data.len() as u64;
+        data.extend_from_slice(names);
+
+        let shoff = data.len() as u64;
+        let entries = [
+            (0u32, 0u32, 0u64, 0u64, 0u64),
+            (
+                1,
+                1,
+                shf::SHF_ALLOC | shf::SHF_EXECINSTR,
+                text_off,
+                text.len() as u64,
+            ),
+            (7, 3, 0, strtab_off, names.len() as u64),
+        ];
+        for (name, sh_type, flags, off, size) in entries {
+            let mut sh = vec![0u8; 64];
+            sh[0..4].copy_from_slice(&name.to_le_bytes());
+            sh[4..8].copy_from_slice(&sh_type.to_le_bytes());
+            sh[8..16].copy_from_slice(&flags.to_le_bytes());
+            sh[0x18..0x20].copy_from_slice(&off.to_le_bytes());
+            sh[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&sh);
+        }
+        data[0x28..0x30].copy_from_slice(&shoff.to_le_bytes()); // e_shoff
+        data[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes()); // e_shentsize
+        data[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes()); // e_shnum
+        data[0x3E..0x40].copy_from_slice(&2u16.to_le_bytes()); // e_shstrndx
+        data
+    }
+
+    #[test]
+    fn test_relocatable_object() {
+        // vpaddd ymm0, ymm0, ymm0 (AVX2) throughout .text
+        let text: Vec<u8> = [0xC5, 0xFD, 0xFE, 0xC0].repeat(32);
+        let data = make_rel_object(0x3E, &text);
+
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.variant_name(), Some("relocatable object"));
+        assert_eq!(result.metadata.entry_point, None);
+        assert_eq!(result.metadata.section_count, Some(3));
+        assert_eq!(text_section(&data, true, true), Some((64, 128)));
+        // Code scans look at .text only
+        assert!(result.metadata.opaque_ranges.is_empty());
+        assert_eq!(result.metadata.code_ranges, vec![(64, 128)]);
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.scanned_bytes, Some(128));
+        assert!(payload.extensions.iter().any(|e| e.name == "AVX"));
+        assert!(!payload
+            .metadata
+            .iter()
+            .any(|e| e.key == crate::types::MetadataKey::EntryPoint));
+
+        // Executables keep their entry point
+        let mut exec = data.clone();
+        exec[0x10..0x12].copy_from_slice(&et::ET_EXEC.to_le_bytes());
+        let result = parse(&exec, 2, 1).unwrap();
+        assert_eq!(result.metadata.entry_point, Some(0));
+        assert_eq!(result.metadata.section_count, None);
+    }
+
+    #[test]
+    fn test_executable_em_none() {
+        let mut data = make_elf_header(0, 2, 1);
+        data[0x10..0x12].copy_from_slice(&et::ET_EXEC.to_le_bytes());
+        data.extend(crate::test_fixtures::aarch64_code(1200));
+
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(result.isa, Isa::Unknown(0));
+        assert!(result.metadata.notes.iter().any(|n| n == NO_MACHINE_NOTE));
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.primary.isa, Isa::Unknown(0));
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.level == crate::types::NoteLevel::Info && n.message == NO_MACHINE_NOTE));
+
+        // A deep scan guesses the ISA from the code
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::thorough()).unwrap();
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        assert_eq!(payload.primary.source, ClassificationSource::Combined);
+        assert!(payload.primary.confidence < 1.0);
+        assert!(payload.notes.iter().any(|n| n.message == NO_MACHINE_NOTE));
+        assert!(payload.notes.iter().any(|n| n
+            .message
+            .contains("aarch64 identified by heuristic analysis")));
+    }
+
+    #[test]
+    fn test_relocatable_object_em_none() {
+        let text = crate::test_fixtures::aarch64_code(192);
+        let data = make_rel_object(0, &text);
+
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(result.isa, Isa::AArch64);
+        assert_eq!(result.source, ClassificationSource::Combined);
+        assert!(result.confidence < 1.0);
+        assert!(result.metadata.notes.iter().any(|n| n.contains("EM_NONE")));
+        assert_eq!(result.metadata.raw_machine, Some(0));
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        assert_eq!(payload.primary.source, ClassificationSource::Combined);
+
+        // The caller's options reach the .text analysis
+        let options = ClassifierOptions {
+            allowed_isas: vec![Isa::Ppc64, Isa::X86_64],
+            ..ClassifierOptions::new()
+        };
+        let result = parse_with_options(&data, 2, 1, &options).unwrap();
+        assert_ne!(result.isa, Isa::AArch64);
+    }
+
+    #[test]
+    fn test_plt_section_skipped_by_name() {
+        // Four BTI PLT entries: bti c; adrp x16; ldr x17, [x16]; add x16; br x17
+        let plt: Vec<u8> = (0..4u32)
+            .flat_map(|n| {
+                [
+                    0xD503_245F,
+                    0x9000_0110,
+                    0xF940_0211 | (n << 10),
+                    0x9100_0210,
+                    0xD61F_0220,
+                ]
+            })
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        let mut data = make_rel_object(0xB7, &plt);
+        let name = data.windows(5).position(|w| w == b".text").unwrap();
+        data[name..name + 5].copy_from_slice(b".plt\0");
+
+        assert_eq!(plt_sections(&data, true, true), vec![(64, 80)]);
+        let stubs =
+            crate::extensions::stubs::find(&data, Isa::AArch64, crate::types::Endianness::Little)
+                .unwrap();
+        assert_eq!((stubs.plt_stubs, stubs.veneers), (4, 0));
+        assert_eq!(stubs.ranges, vec![(64, 80)]);
+
+        // The landing pads belong to the linker, not the code
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert!(!payload.extensions.iter().any(|e| e.name == "BTI"));
+        let plt_stubs = payload
+            .metadata
+            .iter()
+            .find(|e| e.key == crate::types::MetadataKey::Custom("plt_stubs".to_string()))
+            .unwrap();
+        assert!(matches!(plt_stubs.value, MetadataValue::Integer(4)));
+    }
+
+    /// `.ARM.attributes` contents with one `aeabi` subsection holding the
+    /// file-scope attributes `tags`, after a vendor subsection to skip.
+    fn arm_attributes_section(tags: &[u8]) -> Vec<u8> {
+        let mut file = vec![1u8]; // Tag_File
+        file.extend_from_slice(&u32::try_from(tags.len() + 5).unwrap().to_le_bytes());
+        file.extend_from_slice(tags);
+        let mut section = vec![b'A'];
+        for (vendor, body) in [(&b"gnu\0"[..], &[4u8, 1][..]), (b"aeabi\0", &file)] {
+            let len = u32::try_from(4 + vendor.len() + body.len()).unwrap();
+            section.extend_from_slice(&len.to_le_bytes());
+            section.extend_from_slice(vendor);
+            section.extend_from_slice(body);
+        }
+        section
+    }
+
+    /// Little-endian ELF32 ARM executable whose only section is
+    /// `.ARM.attributes` with `section` as its contents.
+    fn make_arm_elf_with_attributes(section: &[u8]) -> Vec<u8> {
+        let mut data = make_elf_header(0x28, 1, 1);
+        data[0x10..0x12].copy_from_slice(&et::ET_EXEC.to_le_bytes());
+        data[0x24..0x28].copy_from_slice(&0x0500_0400u32.to_le_bytes()); // EABI5, hard-float
+        let attr_off = u32::try_from(data.len()).unwrap();
+        data.extend_from_slice(section);
+        let shoff = u32::try_from(data.len()).unwrap();
+        data.extend_from_slice(&[0u8; 40]); // SHN_UNDEF
+        let mut sh = vec![0u8; 40];
+        sh[4..8].copy_from_slice(&sht::SHT_ARM_ATTRIBUTES.to_le_bytes());
+        sh[0x10..0x14].copy_from_slice(&attr_off.to_le_bytes());
+        sh[0x14..0x18].copy_from_slice(&u32::try_from(section.len()).unwrap().to_le_bytes());
+        data.extend_from_slice(&sh);
+        data[0x20..0x24].copy_from_slice(&shoff.to_le_bytes()); // e_shoff
+        data[0x2E..0x30].copy_from_slice(&40u16.to_le_bytes()); // e_shentsize
+        data[0x30..0x32].copy_from_slice(&2u16.to_le_bytes()); // e_shnum
+        data
+    }
+
+    #[test]
+    fn test_arm_m_profile_attributes() {
+        // Tag_CPU_name "Cortex-M4", Tag_CPU_arch v7E-M, Tag_CPU_arch_profile
+        // 'M', Tag_ARM_ISA_use 0, Tag_THUMB_ISA_use 2, Tag_compatibility
+        let mut tags = vec![5u8];
+        tags.extend_from_slice(b"Cortex-M4\0");
+        tags.extend_from_slice(&[6, 13, 7, b'M', 8, 0, 9, 2, 32, 0, 0]);
+        let section = arm_attributes_section(&tags);
+        assert_eq!(
+            parse_arm_attributes(&section, true),
+            Some(ArmAttributes {
+                profile: Some(b'M'),
+                arm_isa_use: Some(0),
+                thumb_isa_use: Some(2),
+            })
+        );
+
+        let data = make_arm_elf_with_attributes(&section);
+        let result = parse(&data, 1, 1).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        let variant = result.variant.unwrap();
+        assert_eq!(variant.profile.as_deref(), Some(arm::M_PROFILE));
+        assert_eq!(variant.name, "EABI5, hard-float");
+        assert!(result.extensions.iter().any(|e| e.name == "Thumb-2"));
+
+        // A32-looking claims from the code scan are dropped on Cortex-M
+        let mut code = vec![
+            Extension::new("NEON", ExtensionCategory::Simd),
+            Extension::new("DSP", ExtensionCategory::Simd),
+        ];
+        crate::extensions::retain_profile_extensions(Isa::Arm, Some(&variant), &mut code);
+        let names: Vec<&str> = code.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["DSP"]);
+    }
+
+    #[test]
+    fn test_arm_a_profile_attributes() {
+        let data = make_arm_elf_with_attributes(&arm_attributes_section(&[7, b'A', 8, 1]));
+        let result = parse(&data, 1, 1).unwrap();
+        let variant = result.variant.unwrap();
+        assert_eq!(variant.profile.as_deref(), Some(arm::A_PROFILE));
+        assert!(!result
+            .extensions
+            .iter()
+            .any(|e| e.name.starts_with("Thumb")));
+
+        let mut code = vec![Extension::new("NEON", ExtensionCategory::Simd)];
+        crate::extensions::retain_profile_extensions(Isa::Arm, Some(&variant), &mut code);
+        assert_eq!(code.len(), 1);
+
+        // Not an attributes section
+        assert_eq!(parse_arm_attributes(b"B\0\0\0\0", true), None);
+    }
+
+    /// Little-endian ELF32 i386 executable whose `PT_INTERP` names `interp`.
+    fn make_elf32_with_interp(interp: &str) -> Vec<u8> {
+        let mut data = make_elf_header(0x03, 1, 1);
+        data.resize(0x100, 0);
+        data[0x10..0x12].copy_from_slice(&et::ET_EXEC.to_le_bytes());
+        data[0x1C..0x20].copy_from_slice(&0x34u32.to_le_bytes()); // e_phoff
+        data[0x2A..0x2C].copy_from_slice(&32u16.to_le_bytes()); // e_phentsize
+        data[0x2C..0x2E].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        let len = u32::try_from(interp.len() + 1).unwrap();
+        data[0x34..0x38].copy_from_slice(&pt::PT_INTERP.to_le_bytes());
+        data[0x38..0x3C].copy_from_slice(&0x80u32.to_le_bytes()); // p_offset
+        data[0x44..0x48].copy_from_slice(&len.to_le_bytes()); // p_filesz
+        data[0x48..0x4C].copy_from_slice(&len.to_le_bytes()); // p_memsz
+        data[0x80..0x80 + interp.len()].copy_from_slice(interp.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_interpreter_bits() {
+        assert_eq!(
+            interpreter_bits("/lib64/ld-linux-x86-64.so.2", 0x3E),
+            Some(64)
+        );
+        assert_eq!(
+            interpreter_bits("/lib64/ld-linux-x86-64.so.2", 0x03),
+            Some(64)
+        );
+        assert_eq!(
+            interpreter_bits("/libx32/ld-linux-x32.so.2", 0x3E),
+            Some(32)
+        );
+        assert_eq!(
+            interpreter_bits("/lib/ld-linux-aarch64.so.1", 0xB7),
+            Some(64)
+        );
+        assert_eq!(
+            interpreter_bits("/lib/ld-linux-aarch64_ilp32.so.1", 0xB7),
+            Some(32)
+        );
+        assert_eq!(interpreter_bits("/lib/ld-linux-armhf.so.3", 0x28), Some(32));
+        assert_eq!(interpreter_bits("/lib/ld-linux.so.2", 0x03), Some(32));
+        assert_eq!(
+            interpreter_bits("/lib/ld-musl-riscv64.so.1", 0xF3),
+            Some(64)
+        );
+        assert_eq!(interpreter_bits("/lib/ld-musl-s390x.so.1", 0x16), Some(64));
+        assert_eq!(interpreter_bits("/lib64/ld64.so.2", 0x15), Some(64));
+        assert_eq!(interpreter_bits("/lib/ld.so.1", 0x14), Some(32));
+        assert_eq!(interpreter_bits("/system/bin/linker64", 0xB7), None);
+
+        // Names that do not encode the word size on these machines
+        assert_eq!(interpreter_bits("/lib/ld-linux.so.2", 0x9026), None); // Alpha
+        assert_eq!(interpreter_bits("/lib64/ld-linux.so.2", 0x2B), None); // SPARC64
+        assert_eq!(interpreter_bits("/lib32/ld-linux-mipsn8.so.1", 0x08), None);
+        assert_eq!(interpreter_bits("/lib/ld-musl-mipsn32.so.1", 0x08), None);
+        assert_eq!(interpreter_bits("/lib/ld.so.1", 0x08), None);
+    }
+
+    #[test]
+    fn test_interpreter_bitwidth_mismatch() {
+        let data = make_elf32_with_interp("/lib64/ld-linux-x86-64.so.2");
+        assert_eq!(
+            interpreter(&data, false, true).as_deref(),
+            Some("/lib64/ld-linux-x86-64.so.2")
+        );
+        let result = parse(&data, 1, 1).unwrap();
+        assert_eq!(result.isa, Isa::X86);
+        assert_eq!(
+            result.metadata.warnings,
+            vec![
+                "Interpreter /lib64/ld-linux-x86-64.so.2 is a 64-bit loader \
+                 but the binary is 32-bit ELF"
+                    .to_string()
+            ]
+        );
+
+        let data = make_elf32_with_interp("/lib/ld-linux.so.2");
+        let result = parse(&data, 1, 1).unwrap();
+        assert!(result.metadata.warnings.is_empty());
+    }
+
+    fn make_elf64_with_interp(e_machine: u16, interp: &str) -> Vec<u8> {
+        let mut data = make_elf_header(e_machine, 2, 1);
+        data.resize(0x100, 0);
+        data[0x10..0x12].copy_from_slice(&et::ET_EXEC.to_le_bytes());
+        data[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes()); // e_phoff
+        data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes()); // e_phentsize
+        data[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        let len = u64::try_from(interp.len() + 1).unwrap();
+        data[0x40..0x44].copy_from_slice(&pt::PT_INTERP.to_le_bytes());
+        data[0x48..0x50].copy_from_slice(&0x80u64.to_le_bytes()); // p_offset
+        data[0x60..0x68].copy_from_slice(&len.to_le_bytes()); // p_filesz
+        data[0x68..0x70].copy_from_slice(&len.to_le_bytes()); // p_memsz
+        data[0x80..0x80 + interp.len()].copy_from_slice(interp.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_interpreter_name_checked_per_machine() {
+        // Alpha's 64-bit glibc loader shares the i386 name
+        let data = make_elf64_with_interp(0x9026, "/lib/ld-linux.so.2");
+        assert_eq!(
+            interpreter(&data, true, true).as_deref(),
+            Some("/lib/ld-linux.so.2")
+        );
+        let result = parse(&data, 2, 1).unwrap();
+        assert!(
+            result.metadata.warnings.is_empty(),
+            "{:?}",
+            result.metadata.warnings
+        );
+
+        let data = make_elf64_with_interp(0x3E, "/lib/ld-linux.so.2");
+        let result = parse(&data, 2, 1).unwrap();
+        assert_eq!(result.metadata.warnings.len(), 1);
+    }
 }
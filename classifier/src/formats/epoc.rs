@@ -77,7 +77,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     // Symbian corpus in this project is ARM-focused.
     let mut result =
         ClassificationResult::from_format(Isa::Arm, 32, Endianness::Little, FileFormat::Epoc);
-    result.variant = Variant::new("Symbian E32");
+    result.variant = Some(Variant::new("Symbian E32"));
     result.metadata = metadata;
     Ok(result)
 }
@@ -85,13 +85,14 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         section_count: Some(segment_count),
         entry_point: if entry != 0 { Some(entry as u64) } else { None },
+        raw_machine: (chip_id != u16::MAX).then_some(u32::from(chip_id)),
         notes,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(isa, 32, Endianness::Little, FileFormat::EspFirmware);
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(variant_name));
     result.metadata = metadata;
     Ok(result)
 }
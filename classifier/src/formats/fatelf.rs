@@ -4,7 +4,7 @@
 //! similar to macOS fat/universal binaries.
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::{read_u16, read_u32, read_u64};
+use crate::formats::{read_u16, read_u32, read_u64, Absent};
 use crate::types::{
     ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
 };
@@ -42,6 +42,12 @@ pub fn detect(data: &[u8]) -> bool {
 
 /// Parse FatELF header and records.
 pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
+    parse_with_input_len(data, None)
+}
+
+/// Like [`parse`], for `data` read from an input of `input_len` bytes
+/// (see [`Absent`]).
+pub fn parse_with_input_len(data: &[u8], input_len: Option<u64>) -> Result<ClassificationResult> {
     if data.len() < FATELF_HEADER_SIZE {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
@@ -63,20 +69,23 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let num_records = data[6];
     let _reserved = data[7];
 
+    // A prefix of a larger file may hold only some of the records; report
+    // the ones present
     let records_size = num_records as usize * FATELF_RECORD_SIZE;
-    if data.len() < FATELF_HEADER_SIZE + records_size {
-        return Err(ClassifierError::TruncatedData {
-            offset: FATELF_HEADER_SIZE,
-            expected: records_size,
-            actual: data.len().saturating_sub(FATELF_HEADER_SIZE),
-        });
-    }
+    let absent = Absent::check(
+        data,
+        input_len,
+        "FatELF records",
+        FATELF_HEADER_SIZE as u64,
+        records_size as u64,
+    );
+    let present = (data.len() - FATELF_HEADER_SIZE) / FATELF_RECORD_SIZE;
 
     // Parse records
     let mut records: Vec<FatElfRecord> = Vec::new();
     let mut arch_names: Vec<String> = Vec::new();
 
-    for i in 0..num_records as usize {
+    for i in 0..(num_records as usize).min(present) {
         let rec_off = FATELF_HEADER_SIZE + i * FATELF_RECORD_SIZE;
 
         let machine = read_u16(data, rec_off, true)?;
@@ -119,7 +128,9 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let metadata = ClassificationMetadata {
         section_count: Some(num_records as usize),
+        raw_machine: records.first().map(|r| u32::from(r.machine)),
         notes,
+        warnings: absent.iter().map(Absent::note).collect(),
         ..Default::default()
     };
 
@@ -139,7 +150,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(isa, bitwidth, endianness, FileFormat::FatElf);
-    result.variant = Variant::new(format!("{} architectures", num_records));
+    result.variant = Some(Variant::new(format!("{num_records} architectures")));
     result.metadata = metadata;
 
     Ok(result)
@@ -235,5 +246,35 @@ mod tests {
         let result = parse(&data).unwrap();
         assert_eq!(result.format, FileFormat::FatElf);
         assert_eq!(result.metadata.section_count, Some(2));
+        assert!(result.metadata.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_fatelf_prefix() {
+        // Only the first record made it into the buffer
+        let data = make_fatelf_header(2);
+        let prefix = &data[..FATELF_HEADER_SIZE + FATELF_RECORD_SIZE];
+        let result = parse(prefix).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.metadata.section_count, Some(2));
+        assert_eq!(
+            result.metadata.warnings,
+            [Absent {
+                structure: "FatELF records".to_string(),
+                offset: 8,
+                truncated: false,
+                available: prefix.len() as u64,
+            }
+            .note()]
+        );
+        assert!(result.metadata.warnings[0]
+            .starts_with("FatELF records at offset 0x8 not examined (input supplied ends at"));
+
+        let result = parse_with_input_len(prefix, Some(prefix.len() as u64)).unwrap();
+        assert_eq!(result.metadata.section_count, Some(2));
+        assert_eq!(
+            result.metadata.warnings,
+            ["FatELF records at offset 0x8 extends past the end of the file (truncated)"]
+        );
     }
 }
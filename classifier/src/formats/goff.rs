@@ -133,7 +133,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::S390x, 64, Endianness::Big, FileFormat::Goff);
-    result.variant = Variant::new("z/Architecture");
+    result.variant = Some(Variant::new("z/Architecture"));
     result.metadata = metadata;
 
     Ok(result)
@@ -7,7 +7,8 @@
 
 use crate::error::{ClassifierError, Result};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
 /// Intel HEX record types.
@@ -101,6 +102,11 @@ pub fn detect(data: &[u8]) -> Option<HexVariant> {
     None
 }
 
+/// Build the `complete` metadata entry from end-of-file record presence.
+fn completeness_entry(terminated: bool) -> MetadataEntry {
+    MetadataEntry::custom("complete", MetadataValue::Bool(terminated), "Complete")
+}
+
 /// Parse Intel HEX format.
 fn parse_intel_hex(data: &[u8], is_32bit: bool) -> Result<ClassificationResult> {
     let text = String::from_utf8_lossy(data);
@@ -182,14 +188,17 @@ fn parse_intel_hex(data: &[u8], is_32bit: bool) -> Result<ClassificationResult>
             min_addr, max_addr
         ));
     }
+    let mut warnings = Vec::new();
     if !has_eof {
-        notes.push("Warning: No EOF record".to_string());
+        warnings.push("Intel HEX file has no EOF record; file may be truncated".to_string());
     }
 
     let metadata = ClassificationMetadata {
         entry_point,
         code_size: Some(data_bytes),
         notes,
+        warnings,
+        custom: vec![completeness_entry(has_eof)],
         ..Default::default()
     };
 
@@ -200,7 +209,7 @@ fn parse_intel_hex(data: &[u8], is_32bit: bool) -> Result<ClassificationResult>
         Endianness::Little,
         FileFormat::IntelHex,
     );
-    result.variant = Variant::new(address_mode);
+    result.variant = Some(Variant::new(address_mode));
     result.metadata = metadata;
 
     Ok(result)
@@ -216,6 +225,7 @@ fn parse_srec(data: &[u8], addr_size: u8) -> Result<ClassificationResult> {
     let mut record_count = 0u32;
     let mut entry_point: Option<u64> = None;
     let mut has_header = false;
+    let mut has_termination = false;
 
     for line in text.lines() {
         let line = line.trim();
@@ -265,18 +275,21 @@ fn parse_srec(data: &[u8], addr_size: u8) -> Result<ClassificationResult> {
             }
             b'7' => {
                 // 32-bit start address
+                has_termination = true;
                 if line.len() >= 14 {
                     entry_point = Some(u32::from_str_radix(&line[4..12], 16).unwrap_or(0) as u64);
                 }
             }
             b'8' => {
                 // 24-bit start address
+                has_termination = true;
                 if line.len() >= 12 {
                     entry_point = Some(u32::from_str_radix(&line[4..10], 16).unwrap_or(0) as u64);
                 }
             }
             b'9' => {
                 // 16-bit start address
+                has_termination = true;
                 if line.len() >= 10 {
                     entry_point = Some(u16::from_str_radix(&line[4..8], 16).unwrap_or(0) as u64);
                 }
@@ -307,10 +320,19 @@ fn parse_srec(data: &[u8], addr_size: u8) -> Result<ClassificationResult> {
         notes.push("Has header record".to_string());
     }
 
+    let mut warnings = Vec::new();
+    if !has_termination {
+        warnings.push(
+            "S-record file has no S7/S8/S9 termination record; file may be truncated".to_string(),
+        );
+    }
+
     let metadata = ClassificationMetadata {
         entry_point,
         code_size: Some(data_bytes),
         notes,
+        warnings,
+        custom: vec![completeness_entry(has_termination)],
         ..Default::default()
     };
 
@@ -321,14 +343,14 @@ fn parse_srec(data: &[u8], addr_size: u8) -> Result<ClassificationResult> {
         Endianness::Big,
         FileFormat::Srec,
     );
-    result.variant = Variant::new(format!(
+    result.variant = Some(Variant::new(format!(
         "S{}",
         match addr_size {
             4 => "3",
             3 => "2",
             _ => "1",
         }
-    ));
+    )));
     result.metadata = metadata;
 
     Ok(result)
@@ -388,7 +410,7 @@ fn parse_ti_txt(data: &[u8]) -> Result<ClassificationResult> {
     // TI-TXT is commonly used for MSP430
     let mut result =
         ClassificationResult::from_format(Isa::Msp430, 16, Endianness::Little, FileFormat::TiTxt);
-    result.variant = Variant::new("TI-TXT");
+    result.variant = Some(Variant::new("TI-TXT"));
     result.metadata = metadata;
 
     Ok(result)
@@ -446,4 +468,66 @@ mod tests {
         let result = parse(data, variant).unwrap();
         assert_eq!(result.format, FileFormat::Srec);
     }
+
+    fn complete_flag(result: &ClassificationResult) -> Option<bool> {
+        result
+            .metadata
+            .custom
+            .iter()
+            .find_map(|e| match (&e.key, &e.value) {
+                (crate::types::MetadataKey::Custom(k), MetadataValue::Bool(b))
+                    if k == "complete" =>
+                {
+                    Some(*b)
+                }
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_intel_hex_complete() {
+        let data = b":10010000214601360121470136007EFE09D2190140\n:00000001FF\n";
+        let result = parse(data, detect(data).unwrap()).unwrap();
+        assert_eq!(complete_flag(&result), Some(true));
+        assert!(result.metadata.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_intel_hex_truncated() {
+        let data = b":10010000214601360121470136007EFE09D2190140\n:100110002146017E17C20001FF5F16002148011928\n";
+        let result = parse(data, detect(data).unwrap()).unwrap();
+        assert_eq!(complete_flag(&result), Some(false));
+        assert_eq!(result.metadata.warnings.len(), 1);
+        assert!(result.metadata.warnings[0].contains("EOF"));
+    }
+
+    #[test]
+    fn test_srec_complete() {
+        let data = b"S00600004844521B\nS1130000285F245F2212226A000424290008237C2A\nS9030000FC\n";
+        let result = parse(data, detect(data).unwrap()).unwrap();
+        assert_eq!(complete_flag(&result), Some(true));
+        assert!(result.metadata.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_srec_truncated() {
+        let data = b"S00600004844521B\nS1130000285F245F2212226A000424290008237C2A\nS5030001FB\n";
+        let result = parse(data, detect(data).unwrap()).unwrap();
+        assert_eq!(complete_flag(&result), Some(false));
+        assert!(result.metadata.warnings[0].contains("termination"));
+    }
+
+    #[test]
+    fn test_truncated_hex_payload_warning() {
+        let data = b":10010000214601360121470136007EFE09D2190140\n";
+        let payload =
+            crate::detect_payload(data, &crate::types::ClassifierOptions::default()).unwrap();
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.level == crate::types::NoteLevel::Warning));
+        assert!(payload.metadata.iter().any(|e| e.key
+            == crate::types::MetadataKey::Custom("complete".into())
+            && matches!(e.value, MetadataValue::Bool(false))));
+    }
 }
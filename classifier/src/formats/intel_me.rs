@@ -0,0 +1,415 @@
+//! Intel Management Engine / CSME firmware parser.
+//!
+//! ME regions lifted out of SPI flash dumps start with a `$FPT` flash
+//! partition table, either at offset 0 or after a 16-byte ROM bypass
+//! vector. Each code partition (FTPR, NFTP, ...) in turn starts with a
+//! `$CPD` code partition directory listing its modules. A standalone
+//! partition image starts directly with `$CPD`.
+//!
+//! ME 11 and later run on an x86 (Minute IA) core; earlier generations,
+//! whose tables have header version 1.0, ran on ARCompact. Many modules are
+//! Huffman-compressed, which defeats every heuristic scorer, so their
+//! ranges are reported as opaque.
+
+use crate::error::{ClassifierError, Result};
+use crate::formats::{read_bytes, read_u32};
+use crate::types::{
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
+};
+
+/// Partition table signature.
+pub const FPT_MAGIC: &[u8; 4] = b"$FPT";
+
+/// Code partition directory signature.
+pub const CPD_MAGIC: &[u8; 4] = b"$CPD";
+
+/// Size of the ROM bypass vector that may precede `$FPT`.
+const ROM_BYPASS_LEN: usize = 16;
+
+/// Size of a `$FPT` header up to and including the FIT version.
+const FPT_HEADER_MIN: usize = 0x20;
+
+/// Size of a `$FPT` partition entry.
+const FPT_ENTRY_LEN: usize = 0x20;
+
+/// Size of a `$CPD` module entry.
+const CPD_ENTRY_LEN: usize = 0x18;
+
+/// Upper bound on partition and module counts; real images have a few dozen.
+const MAX_ENTRIES: u32 = 256;
+
+/// `$CPD` offset field: module offset bits.
+const CPD_OFFSET_MASK: u32 = 0x01FF_FFFF;
+
+/// `$CPD` offset field: Huffman-compressed module flag.
+const CPD_HUFFMAN: u32 = 1 << 25;
+
+/// A partition listed in the `$FPT` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MePartition {
+    /// Four-character partition name (e.g. "FTPR")
+    pub name: String,
+    /// File offset of the partition
+    pub offset: usize,
+    /// Partition length in bytes
+    pub length: usize,
+}
+
+/// A module listed in a `$CPD` directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpdModule {
+    /// Module name (e.g. "kernel", "FTPR.man")
+    pub name: String,
+    /// File offset of the module
+    pub offset: usize,
+    /// Module length in bytes
+    pub length: usize,
+    /// Whether the module is Huffman-compressed
+    pub huffman: bool,
+}
+
+/// Find a valid `$FPT` (at 0 or 16) or `$CPD` (at 0) header.
+///
+/// Returns the header offset.
+pub fn detect(data: &[u8]) -> Option<usize> {
+    for offset in [0, ROM_BYPASS_LEN] {
+        if data.get(offset..offset + 4) == Some(FPT_MAGIC) && fpt_header(data, offset).is_some() {
+            return Some(offset);
+        }
+    }
+    (data.starts_with(CPD_MAGIC) && cpd_header(data, 0).is_some()).then_some(0)
+}
+
+/// Validated `$FPT` header: (entry count, header version, header length).
+///
+/// The count must be non-zero and bounded, the entry table must fit, and
+/// for header versions before 2.1 the header bytes must sum to zero.
+fn fpt_header(data: &[u8], offset: usize) -> Option<(usize, u8, usize)> {
+    let header = read_bytes(data, offset, FPT_HEADER_MIN).ok()?;
+    let count = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let version = header[8];
+    let header_len = usize::from(header[0x0A]);
+    if count == 0 || count > MAX_ENTRIES || !matches!(version, 0x10 | 0x20 | 0x21) {
+        return None;
+    }
+    if header_len < FPT_HEADER_MIN {
+        return None;
+    }
+    let count = count as usize;
+    read_bytes(data, offset + header_len, count * FPT_ENTRY_LEN).ok()?;
+    // Version 2.1 replaced the byte checksum with a CRC-32 over other fields
+    if version < 0x21 {
+        let header = read_bytes(data, offset, header_len).ok()?;
+        if header.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+            return None;
+        }
+    }
+    Some((count, version, header_len))
+}
+
+/// Validated `$CPD` header: (module count, header length).
+///
+/// Version 1 directories carry a byte checksum over header and entries;
+/// version 2 moved to a CRC-32, so only the counts are checked there.
+fn cpd_header(data: &[u8], offset: usize) -> Option<(usize, usize)> {
+    let header = read_bytes(data, offset, 0x10).ok()?;
+    let count = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let version = header[8];
+    let header_len = usize::from(header[0x0A]);
+    if count == 0 || count > MAX_ENTRIES || !matches!(version, 1 | 2) || header_len < 0x10 {
+        return None;
+    }
+    let count = count as usize;
+    let table = read_bytes(data, offset, header_len + count * CPD_ENTRY_LEN).ok()?;
+    if version == 1 && table.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+        return None;
+    }
+    Some((count, header_len))
+}
+
+/// Trimmed ASCII name from a fixed-size, NUL-padded field.
+fn field_name(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Parse the partition table at `offset`.
+///
+/// Entries marked invalid, empty, or pointing outside `data` are left out;
+/// the out-of-bounds ones are returned as warnings.
+fn parse_fpt(data: &[u8], offset: usize) -> Result<(Vec<MePartition>, u8, Vec<String>)> {
+    let (count, version, header_len) =
+        fpt_header(data, offset).ok_or_else(|| ClassifierError::InvalidChecksum {
+            expected: "valid $FPT header".to_string(),
+            actual: format!("invalid header at 0x{offset:X}"),
+        })?;
+
+    let mut partitions = Vec::new();
+    let mut warnings = Vec::new();
+    for i in 0..count {
+        let entry = read_bytes(data, offset + header_len + i * FPT_ENTRY_LEN, FPT_ENTRY_LEN)?;
+        let name = field_name(&entry[0..4]);
+        let part_offset = read_u32(entry, 0x08, true)? as usize;
+        let length = read_u32(entry, 0x0C, true)? as usize;
+        let flags = read_u32(entry, 0x1C, true)?;
+        // The top byte is 0xFF for unused entries
+        if flags >> 24 == 0xFF || length == 0 || part_offset == 0 {
+            continue;
+        }
+        match part_offset.checked_add(length) {
+            Some(end) if end <= data.len() => partitions.push(MePartition {
+                name,
+                offset: part_offset,
+                length,
+            }),
+            _ => warnings.push(format!(
+                "ME partition {name} at 0x{part_offset:X} (0x{length:X} bytes) lies outside the image"
+            )),
+        }
+    }
+    Ok((partitions, version, warnings))
+}
+
+/// Parse the `$CPD` directory at `offset`, bounded by `limit`.
+///
+/// Module offsets are relative to the directory; modules reaching past
+/// `limit` are left out.
+pub fn parse_cpd(data: &[u8], offset: usize, limit: usize) -> Option<(String, Vec<CpdModule>)> {
+    let limit = limit.min(data.len());
+    let (count, header_len) = cpd_header(&data[..limit], offset)?;
+    let partition = field_name(&data[offset + 0x0C..offset + 0x10]);
+    let mut modules = Vec::new();
+    for i in 0..count {
+        let entry = &data[offset + header_len + i * CPD_ENTRY_LEN..][..CPD_ENTRY_LEN];
+        let raw_offset = u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]);
+        let length = u32::from_le_bytes([entry[16], entry[17], entry[18], entry[19]]) as usize;
+        let module_offset = offset + (raw_offset & CPD_OFFSET_MASK) as usize;
+        if length == 0
+            || module_offset
+                .checked_add(length)
+                .map_or(true, |end| end > limit)
+        {
+            continue;
+        }
+        modules.push(CpdModule {
+            name: field_name(&entry[..12]),
+            offset: module_offset,
+            length,
+            huffman: raw_offset & CPD_HUFFMAN != 0,
+        });
+    }
+    Some((partition, modules))
+}
+
+/// Parse an ME/CSME image whose `$FPT` or `$CPD` header is at `offset`.
+pub fn parse(data: &[u8], offset: usize) -> Result<ClassificationResult> {
+    let mut warnings = Vec::new();
+    let (partitions, version) = if data.get(offset..offset + 4) == Some(FPT_MAGIC) {
+        let (partitions, version, skipped) = parse_fpt(data, offset)?;
+        warnings = skipped;
+        (partitions, Some(version))
+    } else {
+        let (name, _) =
+            parse_cpd(data, offset, data.len()).ok_or_else(|| ClassifierError::UnknownFormat {
+                magic: data[..data.len().min(4)].to_vec(),
+            })?;
+        let partition = MePartition {
+            name,
+            offset,
+            length: data.len() - offset,
+        };
+        (vec![partition], None)
+    };
+
+    let mut modules = Vec::new();
+    for partition in &partitions {
+        if data.get(partition.offset..partition.offset + 4) == Some(CPD_MAGIC) {
+            if let Some((_, found)) =
+                parse_cpd(data, partition.offset, partition.offset + partition.length)
+            {
+                modules.extend(found);
+            }
+        }
+    }
+
+    // Header version 1.0 tables belong to the ARCompact-based ME generations
+    let (isa, variant) = if version == Some(0x10) {
+        (Isa::ArcCompact, "ME (ARCompact, pre-11)")
+    } else {
+        (Isa::X86, "CSME (x86, ME 11+)")
+    };
+
+    let names: Vec<&str> = partitions.iter().map(|p| p.name.as_str()).collect();
+    let huffman: Vec<&CpdModule> = modules.iter().filter(|m| m.huffman).collect();
+
+    let mut notes = vec!["Intel ME/CSME firmware".to_string()];
+    if let Some(version) = version {
+        notes.push(format!(
+            "$FPT header version {}.{}",
+            version >> 4,
+            version & 0xF
+        ));
+    }
+    if !huffman.is_empty() {
+        notes.push(format!(
+            "{} Huffman-compressed module(s) excluded from code analysis",
+            huffman.len()
+        ));
+    }
+
+    let mut custom = vec![MetadataEntry::custom(
+        "me_partitions",
+        MetadataValue::String(names.join(", ")),
+        "ME Partitions",
+    )];
+    if !modules.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "cpd_modules",
+            MetadataValue::Integer(modules.len() as u64),
+            "CPD Modules",
+        ));
+        custom.push(MetadataEntry::custom(
+            "huffman_modules",
+            MetadataValue::Integer(huffman.len() as u64),
+            "Huffman Modules",
+        ));
+    }
+
+    let mut result =
+        ClassificationResult::from_format(isa, 32, Endianness::Little, FileFormat::IntelMe);
+    result.variant = Some(Variant::new(variant));
+    result.metadata = ClassificationMetadata {
+        section_count: Some(partitions.len()),
+        notes,
+        warnings,
+        custom,
+        opaque_ranges: huffman
+            .iter()
+            .map(|m| (m.offset as u64, m.length as u64))
+            .collect(),
+        ..Default::default()
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fix up a byte checksum at `at` so `bytes` sums to zero.
+    fn fix_checksum(bytes: &mut [u8], at: usize) {
+        bytes[at] = 0;
+        let sum = bytes.iter().fold(0u8, |s, &b| s.wrapping_add(b));
+        bytes[at] = sum.wrapping_neg();
+    }
+
+    fn cpd(partition: &[u8; 4], modules: &[(&str, u32, u32)]) -> Vec<u8> {
+        let mut cpd = Vec::new();
+        cpd.extend_from_slice(CPD_MAGIC);
+        cpd.extend_from_slice(&(modules.len() as u32).to_le_bytes());
+        cpd.extend_from_slice(&[1, 1, 0x10, 0]);
+        cpd.extend_from_slice(partition);
+        for (name, offset, length) in modules {
+            let mut field = [0u8; 12];
+            field[..name.len()].copy_from_slice(name.as_bytes());
+            cpd.extend_from_slice(&field);
+            cpd.extend_from_slice(&offset.to_le_bytes());
+            cpd.extend_from_slice(&length.to_le_bytes());
+            cpd.extend_from_slice(&[0; 4]);
+        }
+        fix_checksum(&mut cpd, 0x0B);
+        cpd
+    }
+
+    /// ROM bypass + `$FPT` with FTPR (holding a `$CPD`) and NFTP.
+    fn me_image() -> Vec<u8> {
+        let mut data = vec![0u8; 0x3000];
+        let fpt = ROM_BYPASS_LEN;
+        data[fpt..fpt + 4].copy_from_slice(FPT_MAGIC);
+        data[fpt + 4..fpt + 8].copy_from_slice(&2u32.to_le_bytes());
+        data[fpt + 8] = 0x20; // header version 2.0
+        data[fpt + 9] = 0x10;
+        data[fpt + 0x0A] = 0x20;
+
+        let entries = [(b"FTPR", 0x1000u32, 0x1000u32), (b"NFTP", 0x2000, 0x1000)];
+        for (i, (name, offset, length)) in entries.iter().enumerate() {
+            let e = fpt + 0x20 + i * FPT_ENTRY_LEN;
+            data[e..e + 4].copy_from_slice(*name);
+            data[e + 8..e + 12].copy_from_slice(&offset.to_le_bytes());
+            data[e + 12..e + 16].copy_from_slice(&length.to_le_bytes());
+        }
+        fix_checksum(&mut data[fpt..fpt + 0x20], 0x0B);
+
+        let dir = cpd(
+            b"FTPR",
+            &[
+                ("FTPR.man", 0x100, 0x80),
+                ("kernel", 0x200 | CPD_HUFFMAN, 0x400),
+                ("bogus", 0x7000, 0x100),
+            ],
+        );
+        data[0x1000..0x1000 + dir.len()].copy_from_slice(&dir);
+        data
+    }
+
+    #[test]
+    fn test_detect_fpt_and_cpd() {
+        let data = me_image();
+        assert_eq!(detect(&data), Some(ROM_BYPASS_LEN));
+        assert_eq!(detect(&data[ROM_BYPASS_LEN..]), Some(0));
+        assert_eq!(detect(&data[0x1000..0x2000]), Some(0));
+
+        // A corrupted checksum rejects the table
+        let mut bad = data.clone();
+        bad[ROM_BYPASS_LEN + 0x0C] ^= 1;
+        assert_eq!(detect(&bad), None);
+
+        // So does an entry table running off the end
+        assert_eq!(detect(&data[..ROM_BYPASS_LEN + 0x30]), None);
+    }
+
+    #[test]
+    fn test_parse_me_image() {
+        let data = me_image();
+        let result = parse(&data, ROM_BYPASS_LEN).unwrap();
+        assert_eq!(result.isa, Isa::X86);
+        assert_eq!(result.format, FileFormat::IntelMe);
+        assert_eq!(result.metadata.section_count, Some(2));
+        assert!(matches!(
+            &result.metadata.custom[0].value,
+            MetadataValue::String(names) if names == "FTPR, NFTP"
+        ));
+        // The out-of-bounds module is dropped; only "kernel" is compressed
+        assert!(matches!(
+            result.metadata.custom[1].value,
+            MetadataValue::Integer(2)
+        ));
+        assert_eq!(result.metadata.opaque_ranges, vec![(0x1200, 0x400)]);
+    }
+
+    #[test]
+    fn test_out_of_bounds_partition_is_skipped() {
+        let mut data = me_image();
+        let e = ROM_BYPASS_LEN + 0x20 + FPT_ENTRY_LEN;
+        data[e + 8..e + 12].copy_from_slice(&0x8000u32.to_le_bytes());
+        fix_checksum(&mut data[ROM_BYPASS_LEN..ROM_BYPASS_LEN + 0x20], 0x0B);
+
+        let result = parse(&data, ROM_BYPASS_LEN).unwrap();
+        assert_eq!(result.metadata.section_count, Some(1));
+        assert!(result.metadata.warnings[0].contains("NFTP"));
+    }
+
+    #[test]
+    fn test_payload_reports_me_format() {
+        let data = me_image();
+        assert!(matches!(
+            crate::formats::detect_format(&data),
+            crate::formats::DetectedFormat::IntelMe { offset: 16 }
+        ));
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.format.format, FileFormat::IntelMe);
+        assert_eq!(payload.format.magic_offset, Some(ROM_BYPASS_LEN));
+        assert_eq!(payload.primary.isa, Isa::X86);
+    }
+}
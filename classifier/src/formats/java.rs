@@ -149,7 +149,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::Jvm, 32, Endianness::Big, FileFormat::JavaClass);
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(variant_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -192,7 +192,7 @@ mod tests {
         let result = parse(&data).unwrap();
         assert_eq!(result.isa, Isa::Jvm);
         assert_eq!(result.format, FileFormat::JavaClass);
-        assert!(result.variant.name.contains("Java 8"));
+        assert!(result.variant_name().unwrap().contains("Java 8"));
     }
 
     #[test]
@@ -200,7 +200,7 @@ mod tests {
         let data = make_java_class(61, 0);
         let result = parse(&data).unwrap();
         assert_eq!(result.isa, Isa::Jvm);
-        assert!(result.variant.name.contains("Java 17"));
+        assert!(result.variant_name().unwrap().contains("Java 17"));
     }
 
     #[test]
@@ -208,6 +208,6 @@ mod tests {
         let data = make_java_class(65, 0);
         let result = parse(&data).unwrap();
         assert_eq!(result.isa, Isa::Jvm);
-        assert!(result.variant.name.contains("Java 21"));
+        assert!(result.variant_name().unwrap().contains("Java 21"));
     }
 }
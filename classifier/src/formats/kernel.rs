@@ -7,7 +7,7 @@
 //! - Device Tree Blob (DTB)
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::{read_u16, read_u32};
+use crate::formats::{read_cstr, read_u16, read_u32};
 use crate::types::{
     ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
 };
@@ -325,7 +325,7 @@ fn parse_linux_x86(data: &[u8], protocol: u16) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, 32, Endianness::Little, FileFormat::ZImage);
-    result.variant = Variant::new(kernel_type);
+    result.variant = Some(Variant::new(kernel_type));
     result.metadata = metadata;
 
     Ok(result)
@@ -356,7 +356,7 @@ fn parse_linux_arm64(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::AArch64, 64, Endianness::Little, FileFormat::ZImage);
-    result.variant = Variant::new("ARM64 Image");
+    result.variant = Some(Variant::new("ARM64 Image"));
     result.metadata = metadata;
 
     Ok(result)
@@ -373,7 +373,7 @@ fn parse_linux_riscv(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::RiscV64, 64, Endianness::Little, FileFormat::ZImage);
-    result.variant = Variant::new("RISC-V Image");
+    result.variant = Some(Variant::new("RISC-V Image"));
     result.metadata = metadata;
 
     Ok(result)
@@ -399,9 +399,7 @@ fn parse_uimage(data: &[u8], arch: u8, os: u8, image_type: u8) -> Result<Classif
     let compression = data[0x1F];
 
     // Read image name (null-terminated, 32 bytes max)
-    let name_bytes = &data[0x20..0x40];
-    let name_len = name_bytes.iter().position(|&b| b == 0).unwrap_or(32);
-    let name = String::from_utf8_lossy(&name_bytes[..name_len]);
+    let name = String::from_utf8_lossy(read_cstr(data, 0x20, 32)?);
 
     let (isa, bitwidth) = uimage_arch_to_isa(arch);
 
@@ -419,13 +417,18 @@ fn parse_uimage(data: &[u8], arch: u8, os: u8, image_type: u8) -> Result<Classif
     let metadata = ClassificationMetadata {
         entry_point: Some(entry_point as u64),
         code_size: Some(data_size as u64),
+        raw_machine: Some(u32::from(arch)),
         notes,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(isa, bitwidth, Endianness::Big, FileFormat::UImage);
-    result.variant = Variant::new(format!("{} {}", uimage_os_name(os), uimage_arch_name(arch)));
+    result.variant = Some(Variant::new(format!(
+        "{} {}",
+        uimage_os_name(os),
+        uimage_arch_name(arch)
+    )));
     result.metadata = metadata;
 
     Ok(result)
@@ -462,7 +465,7 @@ fn parse_dtb(data: &[u8]) -> Result<ClassificationResult> {
     // DTB is ISA-independent
     let mut result =
         ClassificationResult::from_format(Isa::Unknown(0), 0, Endianness::Big, FileFormat::Dtb);
-    result.variant = Variant::new("FDT");
+    result.variant = Some(Variant::new("FDT"));
     result.metadata = metadata;
 
     Ok(result)
@@ -80,8 +80,17 @@ pub fn parse(data: &[u8], variant: LlvmVariant) -> Result<ClassificationResult>
     // LLVM bitcode uses a complex bitstream format
     // Full parsing would require significant code
 
+    // The wrapper header carries a Mach-O cputype at offset 16
+    let raw_machine = match variant {
+        LlvmVariant::Wrapped => data
+            .get(16..20)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        _ => None,
+    };
+
     let metadata = ClassificationMetadata {
         code_size: Some(data.len() as u64),
+        raw_machine,
         notes,
         ..Default::default()
     };
@@ -93,7 +102,7 @@ pub fn parse(data: &[u8], variant: LlvmVariant) -> Result<ClassificationResult>
         Endianness::Little,
         FileFormat::LlvmBc,
     );
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(variant_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -4,10 +4,10 @@
 //! including fat/universal binaries.
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::read_u32;
+use crate::formats::{read_bytes, read_cstr, read_u32, read_u64, Absent};
 use crate::types::{
     ClassificationMetadata, ClassificationResult, Endianness, Extension, ExtensionCategory,
-    FileFormat, Isa, Variant,
+    FileFormat, HardeningSummary, Isa, IsaCandidate, MetadataEntry, MetadataValue, Variant,
 };
 
 /// Mach-O CPU type constants.
@@ -59,7 +59,7 @@ pub mod arm64_subtype {
 }
 
 /// Extract extensions from CPU type and subtype.
-fn extensions_from_cpu(cpu_type: u32, cpu_subtype: u32) -> Vec<Extension> {
+pub(crate) fn extensions_from_cpu(cpu_type: u32, cpu_subtype: u32) -> Vec<Extension> {
     let subtype = cpu_subtype & 0xFF;
 
     match cpu_type {
@@ -339,6 +339,785 @@ pub mod x86_64_subtype {
     pub const H: u32 = 8;
 }
 
+/// Mach-O header flags (`mach_header.flags`).
+pub mod mh {
+    /// Stack may be executable
+    pub const MH_ALLOW_STACK_EXECUTION: u32 = 0x2_0000;
+    /// Load the main executable at a random address
+    pub const MH_PIE: u32 = 0x20_0000;
+    /// Heap is not executable
+    pub const MH_NO_HEAP_EXECUTION: u32 = 0x100_0000;
+}
+
+/// Mach-O load command types.
+pub mod lc {
+    /// 32-bit segment (`segment_command`)
+    pub const LC_SEGMENT: u32 = 0x1;
+    /// 64-bit segment (`segment_command_64`)
+    pub const LC_SEGMENT_64: u32 = 0x19;
+    /// Code signature blob in `__LINKEDIT` (`linkedit_data_command`)
+    pub const LC_CODE_SIGNATURE: u32 = 0x1D;
+    /// Encrypted segment range (`encryption_info_command`)
+    pub const LC_ENCRYPTION_INFO: u32 = 0x21;
+    /// Dependent library (`dylib_command`)
+    pub const LC_LOAD_DYLIB: u32 = 0xC;
+    /// Install name of a dynamic library (`dylib_command`)
+    pub const LC_ID_DYLIB: u32 = 0xD;
+    /// Build UUID (`uuid_command`)
+    pub const LC_UUID: u32 = 0x1B;
+    /// Encrypted segment range (`encryption_info_command_64`)
+    pub const LC_ENCRYPTION_INFO_64: u32 = 0x2C;
+    /// Dependent library that may be missing at run time (`dylib_command`)
+    pub const LC_LOAD_WEAK_DYLIB: u32 = 0x8000_0018;
+    /// Run-path search directory (`rpath_command`)
+    pub const LC_RPATH: u32 = 0x8000_001C;
+    /// Minimum macOS version (`version_min_command`)
+    pub const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+    /// Minimum iOS version (`version_min_command`)
+    pub const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+    /// Minimum tvOS version (`version_min_command`)
+    pub const LC_VERSION_MIN_TVOS: u32 = 0x2F;
+    /// Minimum watchOS version (`version_min_command`)
+    pub const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+    /// Target platform and minimum OS version (`build_version_command`)
+    pub const LC_BUILD_VERSION: u32 = 0x32;
+}
+
+/// `LC_BUILD_VERSION` platform values.
+pub mod platform {
+    /// macOS
+    pub const MACOS: u32 = 1;
+    /// iOS
+    pub const IOS: u32 = 2;
+    /// tvOS
+    pub const TVOS: u32 = 3;
+    /// watchOS
+    pub const WATCHOS: u32 = 4;
+    /// bridgeOS (T2 coprocessor)
+    pub const BRIDGEOS: u32 = 5;
+    /// iOS apps built for macOS
+    pub const MACCATALYST: u32 = 6;
+    /// iOS Simulator
+    pub const IOSSIMULATOR: u32 = 7;
+    /// tvOS Simulator
+    pub const TVOSSIMULATOR: u32 = 8;
+    /// watchOS Simulator
+    pub const WATCHOSSIMULATOR: u32 = 9;
+    /// DriverKit user-space drivers
+    pub const DRIVERKIT: u32 = 10;
+    /// visionOS
+    pub const VISIONOS: u32 = 11;
+    /// visionOS Simulator
+    pub const VISIONOSSIMULATOR: u32 = 12;
+}
+
+/// Human name of an `LC_BUILD_VERSION` platform.
+pub fn platform_name(value: u32) -> Option<&'static str> {
+    Some(match value {
+        platform::MACOS => "macOS",
+        platform::IOS => "iOS",
+        platform::TVOS => "tvOS",
+        platform::WATCHOS => "watchOS",
+        platform::BRIDGEOS => "bridgeOS",
+        platform::MACCATALYST => "Mac Catalyst",
+        platform::IOSSIMULATOR => "iOS Simulator",
+        platform::TVOSSIMULATOR => "tvOS Simulator",
+        platform::WATCHOSSIMULATOR => "watchOS Simulator",
+        platform::DRIVERKIT => "DriverKit",
+        platform::VISIONOS => "visionOS",
+        platform::VISIONOSSIMULATOR => "visionOS Simulator",
+        _ => return None,
+    })
+}
+
+/// Code signing blob magics (stored big-endian).
+pub mod cs_magic {
+    /// Embedded signature superblob
+    pub const EMBEDDED_SIGNATURE: u32 = 0xFADE_0CC0;
+    /// Code directory blob
+    pub const CODEDIRECTORY: u32 = 0xFADE_0C02;
+}
+
+/// Code directory version that introduced `teamOffset`.
+const CS_SUPPORTSTEAMID: u32 = 0x2_0200;
+
+/// Code directory flag for ad-hoc (certificate-less) signatures.
+const CS_ADHOC: u32 = 0x2;
+
+/// Summary of an `LC_CODE_SIGNATURE` blob. The signature is not verified.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeSignature {
+    /// Signature blob size in bytes (`datasize`)
+    pub size: u32,
+    /// Whether the code directory is flagged ad-hoc; `None` when no code
+    /// directory could be read
+    pub adhoc: Option<bool>,
+    /// Team identifier from the code directory, if present
+    pub team_id: Option<String>,
+}
+
+/// Contents of `LC_ENCRYPTION_INFO`/`LC_ENCRYPTION_INFO_64`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncryptionInfo {
+    /// Offset of the encrypted range from the start of the Mach-O image
+    pub cryptoff: u32,
+    /// Size of the encrypted range in bytes
+    pub cryptsize: u32,
+    /// Encryption system; 0 means the range is not encrypted
+    pub cryptid: u32,
+}
+
+impl EncryptionInfo {
+    /// Whether the range is actually encrypted (FairPlay on App Store builds).
+    pub fn is_encrypted(&self) -> bool {
+        self.cryptid != 0
+    }
+
+    /// The encrypted range as `(offset, size)` from the start of the image.
+    pub fn range(&self) -> (u64, u64) {
+        (u64::from(self.cryptoff), u64::from(self.cryptsize))
+    }
+
+    /// Warning reported for an encrypted image.
+    pub fn warning(&self) -> String {
+        format!(
+            "__TEXT is encrypted (cryptid={}, {} bytes at offset 0x{:X}); \
+             code-pattern extension detection skips this range",
+            self.cryptid, self.cryptsize, self.cryptoff
+        )
+    }
+}
+
+/// Maximum number of dependent library paths reported in metadata.
+pub const MAX_REPORTED_DYLIBS: usize = 16;
+
+/// A library named by `LC_ID_DYLIB` or `LC_LOAD_(WEAK_)DYLIB`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DylibRef {
+    /// Install name / load path
+    pub path: String,
+    /// Current version, packed as `xxxx.yy.zz`
+    pub current_version: u32,
+    /// Compatibility version, packed as `xxxx.yy.zz`
+    pub compatibility_version: u32,
+    /// Loaded with `LC_LOAD_WEAK_DYLIB`
+    pub weak: bool,
+}
+
+/// Dynamic-linking identity of a Mach-O image, read from its load commands.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkInfo {
+    /// The library's own install name (`LC_ID_DYLIB`)
+    pub install_name: Option<DylibRef>,
+    /// Dependent libraries in load-command order
+    pub dylibs: Vec<DylibRef>,
+    /// Run-path search directories (`LC_RPATH`)
+    pub rpaths: Vec<String>,
+    /// Build UUID (`LC_UUID`)
+    pub uuid: Option<[u8; 16]>,
+}
+
+/// Render a packed `xxxx.yy.zz` dylib version.
+pub fn format_dylib_version(version: u32) -> String {
+    format!(
+        "{}.{}.{}",
+        version >> 16,
+        (version >> 8) & 0xFF,
+        version & 0xFF
+    )
+}
+
+/// Render a UUID in the canonical 8-4-4-4-12 uppercase form.
+pub fn format_uuid(uuid: &[u8; 16]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(32);
+    for b in uuid {
+        let _ = write!(hex, "{b:02X}");
+    }
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Find the first load command whose type is in `wanted`.
+///
+/// Returns the command type and its offset in `data`.
+fn find_load_command(
+    data: &[u8],
+    bits: u8,
+    little_endian: bool,
+    wanted: &[u32],
+) -> Option<(u32, usize)> {
+    let header_size = if bits == 64 { 32 } else { 28 };
+    let ncmds = read_u32(data, 16, little_endian).ok()?;
+
+    let mut offset = header_size;
+    for _ in 0..ncmds.min(4096) {
+        let cmd = read_u32(data, offset, little_endian).ok()?;
+        let cmdsize = read_u32(data, offset + 4, little_endian).ok()? as usize;
+        if wanted.contains(&cmd) {
+            return Some((cmd, offset));
+        }
+        if cmdsize < 8 {
+            break;
+        }
+        offset = offset.checked_add(cmdsize)?;
+    }
+    None
+}
+
+/// File offset one past the end of the load commands.
+pub fn header_extent(data: &[u8], bits: u8, little_endian: bool) -> u64 {
+    let header_size: u64 = if bits == 64 { 32 } else { 28 };
+    let sizeofcmds = read_u32(data, 20, little_endian).unwrap_or(0);
+    header_size + u64::from(sizeofcmds)
+}
+
+/// File offset one past the last byte the Mach-O image claims.
+///
+/// Covers the load commands and every segment's file range; the
+/// `__LINKEDIT` segment holds the symbol tables and code signature, so it
+/// usually sets the end.
+pub fn image_extent(data: &[u8], bits: u8, little_endian: bool) -> u64 {
+    let mut end = header_extent(data, bits, little_endian);
+    let ncmds = read_u32(data, 16, little_endian).unwrap_or(0);
+
+    let mut offset = if bits == 64 { 32 } else { 28 };
+    for _ in 0..ncmds.min(4096) {
+        let (Ok(cmd), Ok(cmdsize)) = (
+            read_u32(data, offset, little_endian),
+            read_u32(data, offset + 4, little_endian),
+        ) else {
+            break;
+        };
+        let segment = match cmd {
+            lc::LC_SEGMENT => (
+                read_u32(data, offset + 32, little_endian).map(u64::from),
+                read_u32(data, offset + 36, little_endian).map(u64::from),
+            ),
+            lc::LC_SEGMENT_64 => (
+                read_u64(data, offset + 40, little_endian),
+                read_u64(data, offset + 48, little_endian),
+            ),
+            _ => (Ok(0), Ok(0)),
+        };
+        if let (Ok(fileoff), Ok(filesize)) = segment {
+            end = end.max(fileoff.saturating_add(filesize));
+        }
+        if cmdsize < 8 {
+            break;
+        }
+        match offset.checked_add(cmdsize as usize) {
+            Some(next) => offset = next,
+            None => break,
+        }
+    }
+    end
+}
+
+/// Section attribute: the section holds only instructions.
+const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x8000_0000;
+
+/// Section attribute: the section holds some instructions.
+const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x0000_0400;
+
+/// Segment protection bit: the segment is mapped executable.
+const VM_PROT_EXECUTE: u32 = 0x4;
+
+/// `(fileoff, filesize, initprot, nsects)` of the segment command at `cmd`.
+fn segment_fields(data: &[u8], cmd: usize, is_64: bool, le: bool) -> Option<(u64, u64, u32, u32)> {
+    if is_64 {
+        Some((
+            read_u64(data, cmd + 40, le).ok()?,
+            read_u64(data, cmd + 48, le).ok()?,
+            read_u32(data, cmd + 60, le).ok()?,
+            read_u32(data, cmd + 64, le).ok()?,
+        ))
+    } else {
+        Some((
+            u64::from(read_u32(data, cmd + 32, le).ok()?),
+            u64::from(read_u32(data, cmd + 36, le).ok()?),
+            read_u32(data, cmd + 44, le).ok()?,
+            read_u32(data, cmd + 48, le).ok()?,
+        ))
+    }
+}
+
+/// `(offset, size, flags)` of the section entry at `sect`.
+fn section_fields(data: &[u8], sect: usize, is_64: bool, le: bool) -> Option<(u64, u64, u32)> {
+    if is_64 {
+        Some((
+            u64::from(read_u32(data, sect + 48, le).ok()?),
+            read_u64(data, sect + 40, le).ok()?,
+            read_u32(data, sect + 64, le).ok()?,
+        ))
+    } else {
+        Some((
+            u64::from(read_u32(data, sect + 40, le).ok()?),
+            u64::from(read_u32(data, sect + 36, le).ok()?),
+            read_u32(data, sect + 56, le).ok()?,
+        ))
+    }
+}
+
+/// File ranges `(offset, size)` of the executable code: every section
+/// flagged as holding instructions, or every segment mapped executable when
+/// no section is.
+///
+/// Ranges are clamped to `data`; empty ones are dropped.
+pub fn executable_ranges(data: &[u8], bits: u8, little_endian: bool) -> Vec<(u64, u64)> {
+    let le = little_endian;
+    let is_64 = bits == 64;
+    let segment_cmd = if is_64 {
+        lc::LC_SEGMENT_64
+    } else {
+        lc::LC_SEGMENT
+    };
+    // Segment command and section entry sizes
+    let (header, entry) = if is_64 { (72, 80) } else { (56, 68) };
+    let len = data.len() as u64;
+    let clamp = |offset: u64, size: u64| {
+        let start = offset.min(len);
+        (start, size.min(len - start))
+    };
+
+    let mut sections = Vec::new();
+    let mut segments = Vec::new();
+    let ncmds = read_u32(data, 16, le).unwrap_or(0);
+    let mut offset = if is_64 { 32 } else { 28 };
+    for _ in 0..ncmds.min(4096) {
+        let (Ok(cmd), Ok(cmdsize)) = (read_u32(data, offset, le), read_u32(data, offset + 4, le))
+        else {
+            break;
+        };
+        if cmd == segment_cmd {
+            if let Some((fileoff, filesize, initprot, nsects)) =
+                segment_fields(data, offset, is_64, le)
+            {
+                if initprot & VM_PROT_EXECUTE != 0 {
+                    segments.push(clamp(fileoff, filesize));
+                }
+                let instructions = S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS;
+                sections.extend(
+                    (0..nsects.min(256) as usize)
+                        .map_while(|i| section_fields(data, offset + header + i * entry, is_64, le))
+                        .filter(|&(_, _, flags)| flags & instructions != 0)
+                        .map(|(offset, size, _)| clamp(offset, size)),
+                );
+            }
+        }
+        if cmdsize < 8 {
+            break;
+        }
+        match offset.checked_add(cmdsize as usize) {
+            Some(next) => offset = next,
+            None => break,
+        }
+    }
+
+    sections.retain(|&(_, size)| size != 0);
+    segments.retain(|&(_, size)| size != 0);
+    if sections.is_empty() {
+        segments
+    } else {
+        sections
+    }
+}
+
+/// Summarize exploit mitigations from the header flags and segment names.
+///
+/// A `__RESTRICT` segment makes dyld ignore `DYLD_*` environment variables.
+pub fn hardening(data: &[u8], bits: u8, little_endian: bool) -> HardeningSummary {
+    let flags = read_u32(data, 24, little_endian).unwrap_or(0);
+    let ncmds = read_u32(data, 16, little_endian).unwrap_or(0);
+
+    let mut restrict = false;
+    let mut offset = if bits == 64 { 32 } else { 28 };
+    for _ in 0..ncmds.min(4096) {
+        let (Ok(cmd), Ok(cmdsize)) = (
+            read_u32(data, offset, little_endian),
+            read_u32(data, offset + 4, little_endian),
+        ) else {
+            break;
+        };
+        if matches!(cmd, lc::LC_SEGMENT | lc::LC_SEGMENT_64)
+            && read_cstr(data, offset + 8, 16).is_ok_and(|name| name == b"__RESTRICT")
+        {
+            restrict = true;
+        }
+        if cmdsize < 8 {
+            break;
+        }
+        match offset.checked_add(cmdsize as usize) {
+            Some(next) => offset = next,
+            None => break,
+        }
+    }
+
+    HardeningSummary {
+        pie: flags & mh::MH_PIE != 0,
+        nx: flags & mh::MH_ALLOW_STACK_EXECUTION == 0,
+        no_heap_execution: Some(flags & mh::MH_NO_HEAP_EXECUTION != 0),
+        restrict: Some(restrict),
+        ..Default::default()
+    }
+    .with_note()
+}
+
+/// Walk the load commands and summarize `LC_CODE_SIGNATURE`, if any.
+///
+/// Returns `None` for unsigned binaries.
+pub fn code_signature(data: &[u8], bits: u8, little_endian: bool) -> Option<CodeSignature> {
+    let (_, offset) = find_load_command(data, bits, little_endian, &[lc::LC_CODE_SIGNATURE])?;
+    let dataoff = read_u32(data, offset + 8, little_endian).ok()?;
+    let datasize = read_u32(data, offset + 12, little_endian).ok()?;
+    let blob = (dataoff as usize)
+        .checked_add(datasize as usize)
+        .and_then(|end| data.get(dataoff as usize..end));
+    let directory = blob.and_then(code_directory);
+    Some(CodeSignature {
+        size: datasize,
+        adhoc: directory.and_then(|(blob, cd_off)| {
+            let flags = read_u32(blob, cd_off + 12, false).ok()?;
+            Some(flags & CS_ADHOC != 0)
+        }),
+        team_id: directory.and_then(|(blob, cd_off)| signature_team_id(blob, cd_off)),
+    })
+}
+
+/// Walk the load commands for `LC_ENCRYPTION_INFO(_64)`, if any.
+pub fn encryption_info(data: &[u8], bits: u8, little_endian: bool) -> Option<EncryptionInfo> {
+    let (_, offset) = find_load_command(
+        data,
+        bits,
+        little_endian,
+        &[lc::LC_ENCRYPTION_INFO, lc::LC_ENCRYPTION_INFO_64],
+    )?;
+    Some(EncryptionInfo {
+        cryptoff: read_u32(data, offset + 8, little_endian).ok()?,
+        cryptsize: read_u32(data, offset + 12, little_endian).ok()?,
+        cryptid: read_u32(data, offset + 16, little_endian).ok()?,
+    })
+}
+
+/// Target platform and minimum OS version from `LC_BUILD_VERSION`, or from
+/// the older `LC_VERSION_MIN_*` commands, whichever comes first.
+///
+/// The version is formatted `major.minor`, with `.patch` when non-zero.
+pub fn build_version(data: &[u8], bits: u8, little_endian: bool) -> Option<(u32, String)> {
+    let (cmd, offset) = find_load_command(
+        data,
+        bits,
+        little_endian,
+        &[
+            lc::LC_BUILD_VERSION,
+            lc::LC_VERSION_MIN_MACOSX,
+            lc::LC_VERSION_MIN_IPHONEOS,
+            lc::LC_VERSION_MIN_TVOS,
+            lc::LC_VERSION_MIN_WATCHOS,
+        ],
+    )?;
+    let (platform, version) = match cmd {
+        lc::LC_BUILD_VERSION => (
+            read_u32(data, offset + 8, little_endian).ok()?,
+            read_u32(data, offset + 12, little_endian).ok()?,
+        ),
+        lc::LC_VERSION_MIN_MACOSX => (
+            platform::MACOS,
+            read_u32(data, offset + 8, little_endian).ok()?,
+        ),
+        lc::LC_VERSION_MIN_IPHONEOS => (
+            platform::IOS,
+            read_u32(data, offset + 8, little_endian).ok()?,
+        ),
+        lc::LC_VERSION_MIN_TVOS => (
+            platform::TVOS,
+            read_u32(data, offset + 8, little_endian).ok()?,
+        ),
+        _ => (
+            platform::WATCHOS,
+            read_u32(data, offset + 8, little_endian).ok()?,
+        ),
+    };
+
+    // xxxx.yy.zz nibbles
+    let (major, minor, patch) = (version >> 16, (version >> 8) & 0xFF, version & 0xFF);
+    let version = if patch == 0 {
+        format!("{major}.{minor}")
+    } else {
+        format!("{major}.{minor}.{patch}")
+    };
+    Some((platform, version))
+}
+
+/// Read the `lc_str` at `str_off` inside the command at `cmd_off`, bounded
+/// by the command's `cmdsize`.
+fn load_command_str(data: &[u8], cmd_off: usize, cmdsize: usize, str_off: u32) -> Option<String> {
+    let start = str_off as usize;
+    if start < 8 || start >= cmdsize {
+        return None;
+    }
+    let bytes = read_cstr(data, cmd_off.checked_add(start)?, cmdsize - start).ok()?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Walk the load commands for install name, dependent libraries, rpaths
+/// and UUID.
+pub fn link_info(data: &[u8], bits: u8, little_endian: bool) -> LinkInfo {
+    let mut info = LinkInfo::default();
+    let header_size = if bits == 64 { 32 } else { 28 };
+    let Ok(ncmds) = read_u32(data, 16, little_endian) else {
+        return info;
+    };
+
+    let mut offset = header_size;
+    for _ in 0..ncmds.min(4096) {
+        let (Ok(cmd), Ok(cmdsize)) = (
+            read_u32(data, offset, little_endian),
+            read_u32(data, offset + 4, little_endian),
+        ) else {
+            break;
+        };
+        let cmdsize = cmdsize as usize;
+        if cmdsize < 8 {
+            break;
+        }
+        let field = |at: usize| read_u32(data, offset + at, little_endian).ok();
+        match cmd {
+            lc::LC_ID_DYLIB | lc::LC_LOAD_DYLIB | lc::LC_LOAD_WEAK_DYLIB if cmdsize >= 24 => {
+                let dylib = field(8)
+                    .and_then(|name| load_command_str(data, offset, cmdsize, name))
+                    .map(|path| DylibRef {
+                        path,
+                        current_version: field(16).unwrap_or(0),
+                        compatibility_version: field(20).unwrap_or(0),
+                        weak: cmd == lc::LC_LOAD_WEAK_DYLIB,
+                    });
+                if let Some(dylib) = dylib {
+                    if cmd == lc::LC_ID_DYLIB {
+                        info.install_name.get_or_insert(dylib);
+                    } else {
+                        info.dylibs.push(dylib);
+                    }
+                }
+            }
+            lc::LC_RPATH if cmdsize >= 12 => {
+                if let Some(path) =
+                    field(8).and_then(|path| load_command_str(data, offset, cmdsize, path))
+                {
+                    info.rpaths.push(path);
+                }
+            }
+            lc::LC_UUID if cmdsize >= 24 => {
+                if let Ok(bytes) = read_bytes(data, offset + 8, 16) {
+                    info.uuid = bytes.try_into().ok();
+                }
+            }
+            _ => {}
+        }
+        let Some(next) = offset.checked_add(cmdsize) else {
+            break;
+        };
+        offset = next;
+    }
+    info
+}
+
+/// Build `install_name`, `dylib_count`, `dylibs`, `rpaths` and `uuid`
+/// metadata entries for a Mach-O image.
+fn link_metadata(data: &[u8], bits: u8, little_endian: bool) -> Vec<MetadataEntry> {
+    let info = link_info(data, bits, little_endian);
+    let mut custom = Vec::new();
+    if let Some(id) = &info.install_name {
+        custom.push(MetadataEntry::custom(
+            "install_name",
+            MetadataValue::String(id.path.clone()),
+            "Install Name",
+        ));
+        custom.push(MetadataEntry::custom(
+            "install_version",
+            MetadataValue::String(format!(
+                "{} (compatibility {})",
+                format_dylib_version(id.current_version),
+                format_dylib_version(id.compatibility_version)
+            )),
+            "Install Version",
+        ));
+    }
+    if !info.dylibs.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "dylib_count",
+            MetadataValue::Integer(info.dylibs.len() as u64),
+            "Dependent Libraries",
+        ));
+        let listed: Vec<String> = info
+            .dylibs
+            .iter()
+            .take(MAX_REPORTED_DYLIBS)
+            .map(|d| {
+                let weak = if d.weak { "weak, " } else { "" };
+                format!(
+                    "{} ({weak}compatibility {})",
+                    d.path,
+                    format_dylib_version(d.compatibility_version)
+                )
+            })
+            .collect();
+        custom.push(MetadataEntry::custom(
+            "dylibs",
+            MetadataValue::String(listed.join(", ")),
+            "Dependent Library Paths",
+        ));
+    }
+    if !info.rpaths.is_empty() {
+        custom.push(MetadataEntry::custom(
+            "rpaths",
+            MetadataValue::String(info.rpaths.join(", ")),
+            "Run Paths",
+        ));
+    }
+    if let Some(uuid) = &info.uuid {
+        custom.push(MetadataEntry::custom(
+            "uuid",
+            MetadataValue::String(format_uuid(uuid)),
+            "UUID",
+        ));
+    }
+    custom
+}
+
+/// Link metadata for the thin image at `offset` inside a fat binary, or
+/// nothing when the slice does not start with a Mach-O magic.
+fn slice_link_metadata(data: &[u8], offset: usize, size: usize) -> Vec<MetadataEntry> {
+    let end = offset.saturating_add(size).min(data.len());
+    let Some(slice) = data.get(offset..end) else {
+        return Vec::new();
+    };
+    match read_u32(slice, 0, false) {
+        Ok(0xFEEDFACE) => link_metadata(slice, 32, false),
+        Ok(0xCEFAEDFE) => link_metadata(slice, 32, true),
+        Ok(0xFEEDFACF) => link_metadata(slice, 64, false),
+        Ok(0xCFFAEDFE) => link_metadata(slice, 64, true),
+        _ => Vec::new(),
+    }
+}
+
+/// Locate the code directory in an embedded signature superblob.
+///
+/// Returns the blob and the code directory's offset within it.
+fn code_directory(blob: &[u8]) -> Option<(&[u8], usize)> {
+    if read_u32(blob, 0, false).ok()? != cs_magic::EMBEDDED_SIGNATURE {
+        return None;
+    }
+    let count = read_u32(blob, 8, false).ok()?;
+    for i in 0..count.min(64) as usize {
+        let index = 12 + i * 8;
+        let cd_off = read_u32(blob, index + 4, false).ok()? as usize;
+        if read_u32(blob, cd_off, false).ok()? == cs_magic::CODEDIRECTORY {
+            return Some((blob, cd_off));
+        }
+    }
+    None
+}
+
+/// Extract the Team ID from the code directory at `cd_off`.
+fn signature_team_id(blob: &[u8], cd_off: usize) -> Option<String> {
+    let version = read_u32(blob, cd_off + 8, false).ok()?;
+    if version < CS_SUPPORTSTEAMID {
+        return None;
+    }
+    let team_off = read_u32(blob, cd_off + 48, false).ok()? as usize;
+    if team_off == 0 {
+        return None;
+    }
+    let team = read_cstr(blob, cd_off.checked_add(team_off)?, 64).ok()?;
+    std::str::from_utf8(team)
+        .ok()
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+/// Architecture name for a Mach-O cpusubtype, as used by `lipo` and
+/// `clang -arch` (e.g. `armv7s`, `x86_64h`).
+///
+/// Covers 32-bit ARM, x86 and x86-64; returns `None` for other CPU types and
+/// for subtypes without a conventional name. Pentium M has no `lipo` name
+/// and takes the compiler's `-march=pentium-m` instead.
+pub fn cpu_subtype_name(cpu_type: u32, cpu_subtype: u32) -> Option<&'static str> {
+    let subtype = cpu_subtype & 0xFF;
+    let name = match cpu_type {
+        cpu_type::ARM => match subtype {
+            arm_subtype::ALL => "arm",
+            arm_subtype::V4T => "armv4t",
+            arm_subtype::V6 => "armv6",
+            arm_subtype::V5TEJ => "armv5",
+            arm_subtype::XSCALE => "xscale",
+            arm_subtype::V7 => "armv7",
+            arm_subtype::V7F => "armv7f",
+            arm_subtype::V7S => "armv7s",
+            arm_subtype::V7K => "armv7k",
+            arm_subtype::V8 => "armv8",
+            arm_subtype::V6M => "armv6m",
+            arm_subtype::V7M => "armv7m",
+            arm_subtype::V7EM => "armv7em",
+            _ => return None,
+        },
+        cpu_type::X86 => match subtype {
+            x86_subtype::I386 => "i386",
+            x86_subtype::I486 => "i486",
+            x86_subtype::I486SX => "i486SX",
+            x86_subtype::PENT => "pentium",
+            x86_subtype::PENTPRO => "pentpro",
+            x86_subtype::PENTII_M3 => "pentIIm3",
+            x86_subtype::PENTII_M5 => "pentIIm5",
+            x86_subtype::PENTIUM_M => "pentium-m",
+            x86_subtype::PENTIUM_4 => "pentium4",
+            _ => return None,
+        },
+        cpu_type::X86_64 => match subtype {
+            x86_64_subtype::ALL => "x86_64",
+            x86_64_subtype::H => "x86_64h",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Core or device class implied by a cpusubtype whose name alone does not
+/// say it.
+fn cpu_subtype_profile(cpu_type: u32, cpu_subtype: u32) -> Option<&'static str> {
+    match (cpu_type, cpu_subtype & 0xFF) {
+        (cpu_type::ARM, arm_subtype::V7F) => Some("Cortex-A9"),
+        (cpu_type::ARM, arm_subtype::V7S) => Some("Apple A6"),
+        (cpu_type::ARM, arm_subtype::V7K) => Some("Apple Watch"),
+        (cpu_type::ARM, arm_subtype::V6M | arm_subtype::V7M | arm_subtype::V7EM) => {
+            Some("Cortex-M")
+        }
+        (cpu_type::X86_64, x86_64_subtype::H) => Some("Haswell"),
+        _ => None,
+    }
+}
+
+/// Build the variant for a slice from [`cpu_type_to_isa`]'s name.
+pub(crate) fn subtype_variant(
+    cpu_type: u32,
+    cpu_subtype: u32,
+    name: Option<String>,
+) -> Option<Variant> {
+    let name = name?;
+    Some(match cpu_subtype_profile(cpu_type, cpu_subtype) {
+        Some(profile) => Variant::with_profile(name, profile),
+        None => Variant::new(name),
+    })
+}
+
 /// Map Mach-O CPU type to ISA.
 pub fn cpu_type_to_isa(cpu_type: u32, cpu_subtype: u32) -> (Isa, u8, Option<String>) {
     let variant = match cpu_type {
@@ -351,23 +1130,12 @@ pub fn cpu_type_to_isa(cpu_type: u32, cpu_subtype: u32) -> (Isa, u8, Option<Stri
         }
 
         cpu_type::X86 => {
-            let subtype_name = match cpu_subtype & 0xFF {
-                x86_subtype::I386 => Some("i386".to_string()),
-                x86_subtype::I486 => Some("i486".to_string()),
-                x86_subtype::PENT => Some("Pentium".to_string()),
-                x86_subtype::PENTPRO => Some("Pentium Pro".to_string()),
-                x86_subtype::PENTIUM_M => Some("Pentium M".to_string()),
-                x86_subtype::PENTIUM_4 => Some("Pentium 4".to_string()),
-                _ => None,
-            };
+            let subtype_name = cpu_subtype_name(cpu_type, cpu_subtype).map(str::to_string);
             return (Isa::X86, 32, subtype_name);
         }
 
         cpu_type::X86_64 => {
-            let subtype_name = match cpu_subtype & 0xFF {
-                x86_64_subtype::H => Some("Haswell".to_string()),
-                _ => None,
-            };
+            let subtype_name = cpu_subtype_name(cpu_type, cpu_subtype).map(str::to_string);
             return (Isa::X86_64, 64, subtype_name);
         }
 
@@ -380,21 +1148,7 @@ pub fn cpu_type_to_isa(cpu_type: u32, cpu_subtype: u32) -> (Isa, u8, Option<Stri
         }
 
         cpu_type::ARM => {
-            let subtype_name = match cpu_subtype & 0xFF {
-                arm_subtype::V4T => Some("ARMv4T".to_string()),
-                arm_subtype::V6 => Some("ARMv6".to_string()),
-                arm_subtype::V5TEJ => Some("ARMv5TEJ".to_string()),
-                arm_subtype::XSCALE => Some("XScale".to_string()),
-                arm_subtype::V7 => Some("ARMv7".to_string()),
-                arm_subtype::V7F => Some("ARMv7 Cortex-A9".to_string()),
-                arm_subtype::V7S => Some("ARMv7S (A6)".to_string()),
-                arm_subtype::V7K => Some("ARMv7K (Watch)".to_string()),
-                arm_subtype::V8 => Some("ARMv8".to_string()),
-                arm_subtype::V6M => Some("ARMv6-M".to_string()),
-                arm_subtype::V7M => Some("ARMv7-M".to_string()),
-                arm_subtype::V7EM => Some("ARMv7E-M".to_string()),
-                _ => None,
-            };
+            let subtype_name = cpu_subtype_name(cpu_type, cpu_subtype).map(str::to_string);
             return (Isa::Arm, 32, subtype_name);
         }
 
@@ -471,18 +1225,80 @@ pub fn parse(data: &[u8], bits: u8, big_endian: bool) -> Result<ClassificationRe
     };
 
     // Build variant
-    let variant = match variant_note {
-        Some(note) => Variant::new(note),
-        None => Variant::default(),
-    };
+    let variant = subtype_variant(cpu_type, cpu_subtype, variant_note);
 
     // Extract extensions from CPU type/subtype
     let extensions = extensions_from_cpu(cpu_type, cpu_subtype);
 
     // Build metadata
+    let signature = code_signature(data, bits, little_endian);
+    let mut custom = vec![MetadataEntry::custom(
+        "signed",
+        MetadataValue::Bool(signature.is_some()),
+        "Code Signed",
+    )];
+    if let Some(signature) = signature {
+        custom.push(MetadataEntry::custom(
+            "signature_size",
+            MetadataValue::Integer(u64::from(signature.size)),
+            "Signature Size",
+        ));
+        if let Some(adhoc) = signature.adhoc {
+            custom.push(MetadataEntry::custom(
+                "adhoc_signed",
+                MetadataValue::Bool(adhoc),
+                "Ad-hoc Signed",
+            ));
+        }
+        if let Some(team_id) = signature.team_id {
+            custom.push(MetadataEntry::custom(
+                "team_id",
+                MetadataValue::String(team_id),
+                "Team ID",
+            ));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut opaque_ranges = Vec::new();
+    if let Some(encryption) = encryption_info(data, bits, little_endian) {
+        custom.push(MetadataEntry::custom(
+            "encrypted",
+            MetadataValue::Bool(encryption.is_encrypted()),
+            "Encrypted",
+        ));
+        custom.push(MetadataEntry::custom(
+            "cryptid",
+            MetadataValue::Integer(u64::from(encryption.cryptid)),
+            "Crypt ID",
+        ));
+        custom.push(MetadataEntry::custom(
+            "cryptoff",
+            MetadataValue::Address(u64::from(encryption.cryptoff)),
+            "Encrypted Offset",
+        ));
+        custom.push(MetadataEntry::custom(
+            "cryptsize",
+            MetadataValue::Integer(u64::from(encryption.cryptsize)),
+            "Encrypted Size",
+        ));
+        if encryption.is_encrypted() {
+            warnings.push(encryption.warning());
+            opaque_ranges.push(encryption.range());
+        }
+    }
+
+    custom.extend(link_metadata(data, bits, little_endian));
+
     let metadata = ClassificationMetadata {
         raw_machine: Some(cpu_type),
         notes: vec![format!("CPU subtype: 0x{:08X}", cpu_subtype)],
+        warnings,
+        custom,
+        opaque_ranges,
+        code_ranges: executable_ranges(data, bits, little_endian),
+        header_size: Some(header_extent(data, bits, little_endian)),
+        described_size: Some(image_extent(data, bits, little_endian)),
         ..Default::default()
     };
 
@@ -495,11 +1311,87 @@ pub fn parse(data: &[u8], bits: u8, big_endian: bool) -> Result<ClassificationRe
     Ok(result)
 }
 
+/// Magic of a fat header whose entries are 32-byte `fat_arch_64` structs.
+const FAT_MAGIC_64: u32 = 0xCAFE_BABF;
+
+/// One `fat_arch` or `fat_arch_64` table entry.
+struct FatArch {
+    cpu_type: u32,
+    cpu_subtype: u32,
+    offset: u64,
+    size: u64,
+    align: u32,
+}
+
+/// Read entry `index` of the fat_arch table, or `None` if it lies past the
+/// end of `data`.
+fn fat_arch(data: &[u8], index: usize, fat64: bool) -> Option<FatArch> {
+    if fat64 {
+        // fat_arch_64: cpu_type(4) cpu_subtype(4) offset(8) size(8) align(4) reserved(4)
+        let off = 8 + index * 32;
+        Some(FatArch {
+            cpu_type: read_u32(data, off, false).ok()?,
+            cpu_subtype: read_u32(data, off + 4, false).ok()?,
+            offset: read_u64(data, off + 8, false).ok()?,
+            size: read_u64(data, off + 16, false).ok()?,
+            align: read_u32(data, off + 24, false).ok()?,
+        })
+    } else {
+        let off = 8 + index * 20;
+        Some(FatArch {
+            cpu_type: read_u32(data, off, false).ok()?,
+            cpu_subtype: read_u32(data, off + 4, false).ok()?,
+            offset: u64::from(read_u32(data, off + 8, false).ok()?),
+            size: u64::from(read_u32(data, off + 12, false).ok()?),
+            align: read_u32(data, off + 16, false).ok()?,
+        })
+    }
+}
+
+/// Bitwidth and byte order of a slice, from its Mach-O magic.
+///
+/// A slice that is not in `data` or carries no Mach-O magic falls back to
+/// the CPU type: its ABI width and the CPU's native byte order.
+fn slice_shape(data: &[u8], offset: u64, cpu_type: u32, isa: Isa) -> (u8, Endianness) {
+    let magic = usize::try_from(offset)
+        .ok()
+        .and_then(|off| read_u32(data, off, false).ok());
+    match magic {
+        Some(0xFEEDFACE) => (32, Endianness::Big),
+        Some(0xCEFAEDFE) => (32, Endianness::Little),
+        Some(0xFEEDFACF) => (64, Endianness::Big),
+        Some(0xCFFAEDFE) => (64, Endianness::Little),
+        _ => {
+            let bits = if cpu_type & cpu_type::ABI64 != 0 {
+                64
+            } else {
+                32
+            };
+            (bits, crate::architectures::default_endianness(isa))
+        }
+    }
+}
+
 /// Parse a fat/universal Mach-O binary.
 ///
-/// Returns the result for the first architecture in the fat binary.
+/// Returns the result for the first architecture in the fat binary, with
+/// every slice whose `fat_arch` entry is present listed in `candidates`.
 /// For comprehensive analysis of all architectures, use `parse_fat_all`.
+///
+/// The fat header's own byte order (`big_endian`) says nothing about the
+/// slices: each is read from its Mach-O magic, or from its CPU type when it
+/// has none (static libraries) or lies past the end of `data`.
 pub fn parse_fat(data: &[u8], big_endian: bool) -> Result<ClassificationResult> {
+    parse_fat_with_input_len(data, big_endian, None)
+}
+
+/// Like [`parse_fat`], for `data` read from an input of `input_len` bytes
+/// (see [`Absent`]).
+pub fn parse_fat_with_input_len(
+    data: &[u8],
+    _big_endian: bool,
+    input_len: Option<u64>,
+) -> Result<ClassificationResult> {
     if data.len() < 8 {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
@@ -510,6 +1402,8 @@ pub fn parse_fat(data: &[u8], big_endian: bool) -> Result<ClassificationResult>
 
     // Fat header is always big-endian
     let nfat_arch = read_u32(data, 4, false)?;
+    let fat64 = read_u32(data, 0, false)? == FAT_MAGIC_64;
+    let entry_size: usize = if fat64 { 32 } else { 20 };
 
     if nfat_arch == 0 {
         return Err(ClassifierError::MachOParseError {
@@ -517,57 +1411,46 @@ pub fn parse_fat(data: &[u8], big_endian: bool) -> Result<ClassificationResult>
         });
     }
 
-    // Parse first fat_arch entry (offset 8)
-    if data.len() < 28 {
-        return Err(ClassifierError::TruncatedData {
-            offset: 8,
-            expected: 20,
-            actual: data.len().saturating_sub(8),
-        });
-    }
-
-    let cpu_type = read_u32(data, 8, false)?;
-    let cpu_subtype = read_u32(data, 12, false)?;
-    let offset = read_u32(data, 16, false)? as usize;
-    let size = read_u32(data, 20, false)? as usize;
-
-    // Validate slice offset
-    if offset + 4 > data.len() {
-        return Err(ClassifierError::TruncatedData {
-            offset,
-            expected: 4,
-            actual: data.len().saturating_sub(offset),
-        });
-    }
-
-    // Determine if the slice is 64-bit from magic
-    let slice_magic = read_u32(data, offset, false)?;
-    let (slice_bits, slice_big_endian) = match slice_magic {
-        0xFEEDFACE => (32, true),
-        0xCEFAEDFE => (32, false),
-        0xFEEDFACF => (64, true),
-        0xCFFAEDFE => (64, false),
-        _ => {
-            // Guess from CPU type
-            let is_64 = cpu_type & cpu_type::ABI64 != 0;
-            (if is_64 { 64 } else { 32 }, big_endian)
-        }
-    };
+    // The first fat_arch entry (offset 8) carries the verdict
+    let first = fat_arch(data, 0, fat64).ok_or_else(|| ClassifierError::TruncatedData {
+        offset: 8,
+        expected: entry_size,
+        actual: data.len().saturating_sub(8),
+    })?;
+    let (cpu_type, cpu_subtype) = (first.cpu_type, first.cpu_subtype);
+    let offset = first.offset as usize;
+    let size = first.size as usize;
+
+    // The fat_arch entry is enough for a verdict; the slice itself may lie
+    // past the end of a prefix read
+    let absent = [
+        Absent::check(
+            data,
+            input_len,
+            "fat_arch table",
+            8,
+            u64::from(nfat_arch) * entry_size as u64,
+        ),
+        Absent::check(data, input_len, "first slice header", first.offset, 4),
+    ];
 
     // Map to ISA
     let (isa, _, variant_note) = cpu_type_to_isa(cpu_type, cpu_subtype);
 
-    let endianness = if slice_big_endian {
-        Endianness::Big
-    } else {
-        Endianness::Little
-    };
+    let (slice_bits, endianness) = slice_shape(data, first.offset, cpu_type, isa);
+
+    // Every slice named by the part of the table in `data`
+    let slices: Vec<(FatArch, Isa, u8, Endianness)> = (0..nfat_arch as usize)
+        .map_while(|i| fat_arch(data, i, fat64))
+        .map(|arch| {
+            let (slice_isa, _, _) = cpu_type_to_isa(arch.cpu_type, arch.cpu_subtype);
+            let (bits, order) = slice_shape(data, arch.offset, arch.cpu_type, slice_isa);
+            (arch, slice_isa, bits, order)
+        })
+        .collect();
 
     // Build variant
-    let variant = match variant_note {
-        Some(note) => Variant::new(note),
-        None => Variant::default(),
-    };
+    let variant = subtype_variant(cpu_type, cpu_subtype, variant_note);
 
     // Extract extensions from CPU type/subtype
     let extensions = extensions_from_cpu(cpu_type, cpu_subtype);
@@ -575,10 +1458,20 @@ pub fn parse_fat(data: &[u8], big_endian: bool) -> Result<ClassificationResult>
     // Build metadata
     let metadata = ClassificationMetadata {
         raw_machine: Some(cpu_type),
-        notes: vec![
-            format!("Fat binary with {} architectures", nfat_arch),
-            format!("First slice: offset={}, size={}", offset, size),
-        ],
+        notes: std::iter::once(format!("Fat binary with {} architectures", nfat_arch))
+            .chain(slices.iter().enumerate().map(|(i, (arch, isa, bits, _))| {
+                format!(
+                    "Slice {}: {} ({}-bit), offset={}, size={}",
+                    i + 1,
+                    isa.name(),
+                    bits,
+                    arch.offset,
+                    arch.size
+                )
+            }))
+            .collect(),
+        warnings: absent.iter().flatten().map(Absent::note).collect(),
+        custom: slice_link_metadata(data, offset, size),
         ..Default::default()
     };
 
@@ -587,6 +1480,10 @@ pub fn parse_fat(data: &[u8], big_endian: bool) -> Result<ClassificationResult>
     result.variant = variant;
     result.extensions = extensions;
     result.metadata = metadata;
+    result.candidates = slices
+        .iter()
+        .map(|&(_, isa, bits, order)| IsaCandidate::new(isa, bits, order, 0, 1.0))
+        .collect();
 
     Ok(result)
 }
@@ -614,6 +1511,16 @@ pub struct FatArchEntry {
 /// (with 64-bit offset, size, and an extra reserved field).
 /// When false, each entry is the standard 20-byte `fat_arch`.
 pub fn parse_fat_all(data: &[u8], fat64: bool) -> Result<Vec<FatArchEntry>> {
+    parse_fat_all_with_input_len(data, fat64, None)
+}
+
+/// Like [`parse_fat_all`], for `data` read from an input of `input_len`
+/// bytes (see [`Absent`]).
+pub fn parse_fat_all_with_input_len(
+    data: &[u8],
+    fat64: bool,
+    input_len: Option<u64>,
+) -> Result<Vec<FatArchEntry>> {
     if data.len() < 8 {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
@@ -640,45 +1547,32 @@ pub fn parse_fat_all(data: &[u8], fat64: bool) -> Result<Vec<FatArchEntry>> {
     for i in 0..nfat_arch {
         let entry_off = 8 + i * entry_size;
 
-        let cpu_type = read_u32(data, entry_off, false)?;
-        let cpu_subtype = read_u32(data, entry_off + 4, false)?;
-
-        let (offset, size, align) = if fat64 {
-            // fat_arch_64: cpu_type(4) cpu_subtype(4) offset(8) size(8) align(4) reserved(4)
-            let off_hi = read_u32(data, entry_off + 8, false)? as u64;
-            let off_lo = read_u32(data, entry_off + 12, false)? as u64;
-            let offset = (off_hi << 32) | off_lo;
-            let sz_hi = read_u32(data, entry_off + 16, false)? as u64;
-            let sz_lo = read_u32(data, entry_off + 20, false)? as u64;
-            let size = (sz_hi << 32) | sz_lo;
-            let align = read_u32(data, entry_off + 24, false)?;
-            (offset, size, align)
-        } else {
-            let offset = read_u32(data, entry_off + 8, false)? as u64;
-            let size = read_u32(data, entry_off + 12, false)? as u64;
-            let align = read_u32(data, entry_off + 16, false)?;
-            (offset, size, align)
-        };
+        let FatArch {
+            cpu_type,
+            cpu_subtype,
+            offset,
+            size,
+            align,
+        } = fat_arch(data, i, fat64).ok_or(ClassifierError::TruncatedData {
+            offset: entry_off,
+            expected: entry_size,
+            actual: data.len().saturating_sub(entry_off),
+        })?;
 
-        // Map to ISA
-        let (isa, bits, variant_note) = cpu_type_to_isa(cpu_type, cpu_subtype);
+        // Map to ISA; the slice's magic refines width and byte order
+        let (isa, _, variant_note) = cpu_type_to_isa(cpu_type, cpu_subtype);
+        let (bits, endianness) = slice_shape(data, offset, cpu_type, isa);
 
-        // Determine endianness from slice if possible
         let off = offset as usize;
-        let endianness = if off + 4 <= data.len() {
-            let magic = read_u32(data, off, false)?;
-            match magic {
-                0xFEEDFACE | 0xFEEDFACF => Endianness::Big,
-                _ => Endianness::Little,
-            }
-        } else {
-            Endianness::Little
-        };
+        let absent = Absent::check(
+            data,
+            input_len,
+            format!("slice {} header", i + 1),
+            offset,
+            4,
+        );
 
-        let variant = match variant_note {
-            Some(note) => Variant::new(note),
-            None => Variant::default(),
-        };
+        let variant = subtype_variant(cpu_type, cpu_subtype, variant_note);
 
         // Extract extensions from CPU type/subtype
         let extensions = extensions_from_cpu(cpu_type, cpu_subtype);
@@ -687,6 +1581,24 @@ pub fn parse_fat_all(data: &[u8], fat64: bool) -> Result<Vec<FatArchEntry>> {
             ClassificationResult::from_format(isa, bits, endianness, FileFormat::MachO);
         classification.variant = variant;
         classification.extensions = extensions;
+        classification.metadata.custom = slice_link_metadata(data, off, size as usize);
+        classification
+            .metadata
+            .warnings
+            .extend(absent.as_ref().map(Absent::note));
+        // Opaque ranges stay relative to the slice, like the rest of its
+        // metadata; callers scanning the whole fat file shift them by `offset`
+        let encryption = data
+            .get(off..off.saturating_add(size as usize))
+            .and_then(|slice| encryption_info(slice, bits, endianness == Endianness::Little))
+            .filter(EncryptionInfo::is_encrypted);
+        if let Some(encryption) = encryption {
+            classification.metadata.warnings.push(encryption.warning());
+            classification
+                .metadata
+                .opaque_ranges
+                .push(encryption.range());
+        }
 
         entries.push(FatArchEntry {
             cpu_type,
@@ -772,7 +1684,7 @@ mod tests {
         let data = make_macho_header(cpu_type::ARM64, arm64_subtype::E, 64);
         let result = parse(&data, 64, false).unwrap();
         assert_eq!(result.isa, Isa::AArch64);
-        assert!(result.variant.name.contains("PAC"));
+        assert!(result.variant_name().unwrap().contains("PAC"));
         // ARM64E should have PAC extension detected from subtype
         assert!(
             result.extensions.iter().any(|e| e.name == "PAC"),
@@ -826,7 +1738,7 @@ mod tests {
         let data = make_macho_header(cpu_type::ARM, arm_subtype::V7, 32);
         let result = parse(&data, 32, false).unwrap();
         assert_eq!(result.isa, Isa::Arm);
-        assert!(result.variant.name.contains("ARMv7"));
+        assert_eq!(result.variant_name(), Some("armv7"));
 
         // ARMv7 should have Thumb, NEON, VFP
         assert!(result.extensions.iter().any(|e| e.name == "Thumb"));
@@ -872,6 +1784,70 @@ mod tests {
         assert!(result.extensions.iter().any(|e| e.name == "AltiVec"));
     }
 
+    #[test]
+    fn test_arm_cpusubtype_names() {
+        let data = make_macho_header(cpu_type::ARM, arm_subtype::V7S, 32);
+        let result = parse(&data, 32, false).unwrap();
+        assert_eq!(result.variant_name(), Some("armv7s"));
+        assert_eq!(
+            result.variant.as_ref().and_then(|v| v.profile.as_deref()),
+            Some("Apple A6")
+        );
+
+        let data = make_macho_header(cpu_type::ARM, arm_subtype::V7K, 32);
+        assert_eq!(
+            parse(&data, 32, false).unwrap().variant_name(),
+            Some("armv7k")
+        );
+
+        let data = make_macho_header(cpu_type::ARM, arm_subtype::V7, 32);
+        let result = parse(&data, 32, false).unwrap();
+        assert_eq!(result.variant_name(), Some("armv7"));
+        assert_eq!(result.variant.unwrap().profile, None);
+
+        assert_eq!(
+            cpu_subtype_name(cpu_type::ARM, arm_subtype::ALL),
+            Some("arm")
+        );
+        assert_eq!(cpu_subtype_name(cpu_type::ARM, 0xFF), None);
+    }
+
+    #[test]
+    fn test_x86_cpusubtype_names() {
+        let data = make_macho_header(cpu_type::X86, x86_subtype::I386, 32);
+        assert_eq!(
+            parse(&data, 32, false).unwrap().variant_name(),
+            Some("i386")
+        );
+
+        let data = make_macho_header(cpu_type::X86, x86_subtype::PENTIUM_4, 32);
+        assert_eq!(
+            parse(&data, 32, false).unwrap().variant_name(),
+            Some("pentium4")
+        );
+
+        let data = make_macho_header(cpu_type::X86, x86_subtype::PENTIUM_M, 32);
+        assert_eq!(
+            parse(&data, 32, false).unwrap().variant_name(),
+            Some("pentium-m")
+        );
+
+        let data = make_macho_header(cpu_type::X86_64, x86_64_subtype::H, 64);
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(result.variant_name(), Some("x86_64h"));
+        assert_eq!(
+            result.variant.as_ref().and_then(|v| v.profile.as_deref()),
+            Some("Haswell")
+        );
+
+        // Capability bits in the high byte do not affect the name
+        let data = make_macho_header(cpu_type::X86_64, 0x8000_0000 | x86_64_subtype::ALL, 64);
+        assert_eq!(
+            parse(&data, 64, false).unwrap().variant_name(),
+            Some("x86_64")
+        );
+    }
+
     #[test]
     fn test_arm_v7s_extensions() {
         // V7S (Apple A6) has VFPv4
@@ -905,4 +1881,462 @@ mod tests {
         assert_eq!(cpu_type_to_isa(cpu_type::POWERPC, 0).0, Isa::Ppc);
         assert_eq!(cpu_type_to_isa(cpu_type::POWERPC64, 0).0, Isa::Ppc64);
     }
+
+    fn custom_value(result: &ClassificationResult, key: &str) -> Option<String> {
+        result
+            .metadata
+            .custom
+            .iter()
+            .find(|m| m.key == crate::types::MetadataKey::Custom(key.to_string()))
+            .map(|m| m.value.to_string())
+    }
+
+    #[test]
+    fn test_unsigned_macho() {
+        let data = make_macho_header(cpu_type::ARM64, arm64_subtype::ALL, 64);
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "signed").as_deref(), Some("false"));
+        assert_eq!(custom_value(&result, "team_id"), None);
+    }
+
+    #[test]
+    fn test_code_signature_team_id() {
+        let mut data = make_macho_header(cpu_type::ARM64, arm64_subtype::ALL, 64);
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+        data[20..24].copy_from_slice(&16u32.to_le_bytes()); // sizeofcmds
+        data[32..36].copy_from_slice(&lc::LC_CODE_SIGNATURE.to_le_bytes());
+        data[36..40].copy_from_slice(&16u32.to_le_bytes());
+        data[40..44].copy_from_slice(&64u32.to_le_bytes()); // dataoff
+
+        // Superblob with a single code directory slot (big-endian)
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&cs_magic::EMBEDDED_SIGNATURE.to_be_bytes());
+        blob.extend_from_slice(&0u32.to_be_bytes()); // length, patched below
+        blob.extend_from_slice(&1u32.to_be_bytes()); // count
+        blob.extend_from_slice(&0u32.to_be_bytes()); // CSSLOT_CODEDIRECTORY
+        blob.extend_from_slice(&20u32.to_be_bytes());
+        let mut cd = vec![0u8; 88];
+        cd[0..4].copy_from_slice(&cs_magic::CODEDIRECTORY.to_be_bytes());
+        cd[8..12].copy_from_slice(&0x2_0400u32.to_be_bytes()); // version
+        cd[48..52].copy_from_slice(&88u32.to_be_bytes()); // teamOffset
+        cd.extend_from_slice(b"ABCDE12345\0");
+        let cd_len = u32::try_from(cd.len()).unwrap();
+        cd[4..8].copy_from_slice(&cd_len.to_be_bytes());
+        blob.extend_from_slice(&cd);
+        let blob_len = u32::try_from(blob.len()).unwrap();
+        blob[4..8].copy_from_slice(&blob_len.to_be_bytes());
+
+        data[44..48].copy_from_slice(&blob_len.to_le_bytes()); // datasize
+        data.extend_from_slice(&blob);
+
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "signed").as_deref(), Some("true"));
+        assert_eq!(
+            custom_value(&result, "team_id").as_deref(),
+            Some("ABCDE12345")
+        );
+        assert_eq!(
+            custom_value(&result, "adhoc_signed").as_deref(),
+            Some("false")
+        );
+
+        // Signed but with an unparseable blob still reports signed=true
+        data.truncate(64);
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "signed").as_deref(), Some("true"));
+        assert_eq!(custom_value(&result, "team_id"), None);
+    }
+
+    #[test]
+    fn test_adhoc_code_signature() {
+        let mut data = make_macho_header(cpu_type::ARM64, arm64_subtype::ALL, 64);
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+        data[20..24].copy_from_slice(&16u32.to_le_bytes()); // sizeofcmds
+        data[32..36].copy_from_slice(&lc::LC_CODE_SIGNATURE.to_le_bytes());
+        data[36..40].copy_from_slice(&16u32.to_le_bytes());
+        data[40..44].copy_from_slice(&64u32.to_le_bytes()); // dataoff
+
+        // Superblob with a pre-team-ID code directory flagged ad-hoc
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&cs_magic::EMBEDDED_SIGNATURE.to_be_bytes());
+        blob.extend_from_slice(&64u32.to_be_bytes()); // length
+        blob.extend_from_slice(&1u32.to_be_bytes()); // count
+        blob.extend_from_slice(&0u32.to_be_bytes()); // CSSLOT_CODEDIRECTORY
+        blob.extend_from_slice(&20u32.to_be_bytes());
+        let mut cd = vec![0u8; 44];
+        cd[0..4].copy_from_slice(&cs_magic::CODEDIRECTORY.to_be_bytes());
+        cd[4..8].copy_from_slice(&44u32.to_be_bytes());
+        cd[8..12].copy_from_slice(&0x2_0100u32.to_be_bytes()); // version
+        cd[12..16].copy_from_slice(&(CS_ADHOC | 0x2_0000).to_be_bytes()); // flags
+        blob.extend_from_slice(&cd);
+        data[44..48].copy_from_slice(&64u32.to_le_bytes()); // datasize
+        data.extend_from_slice(&blob);
+
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "signed").as_deref(), Some("true"));
+        assert_eq!(
+            custom_value(&result, "signature_size").as_deref(),
+            Some("64")
+        );
+        assert_eq!(
+            custom_value(&result, "adhoc_signed").as_deref(),
+            Some("true")
+        );
+        assert_eq!(custom_value(&result, "team_id"), None);
+
+        // A code directory offset past the blob yields no flags, not a panic
+        data[64 + 16..64 + 20].copy_from_slice(&0xFFFF_FFF0u32.to_be_bytes());
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "signed").as_deref(), Some("true"));
+        assert_eq!(custom_value(&result, "adhoc_signed"), None);
+    }
+
+    /// x86-64 image with `LC_ENCRYPTION_INFO_64` covering `0x100..0x200`.
+    fn make_encrypted_macho(cryptid: u32) -> Vec<u8> {
+        let mut data = make_macho_header(cpu_type::X86_64, x86_64_subtype::ALL, 64);
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+        data[20..24].copy_from_slice(&24u32.to_le_bytes()); // sizeofcmds
+        data[32..36].copy_from_slice(&lc::LC_ENCRYPTION_INFO_64.to_le_bytes());
+        data[36..40].copy_from_slice(&24u32.to_le_bytes());
+        data[40..44].copy_from_slice(&0x100u32.to_le_bytes()); // cryptoff
+        data[44..48].copy_from_slice(&0x100u32.to_le_bytes()); // cryptsize
+        data[48..52].copy_from_slice(&cryptid.to_le_bytes());
+        data.resize(0x100, 0x90);
+        // VEX-prefixed instructions inside the encrypted range
+        for _ in 0..64 {
+            data.extend_from_slice(&[0xC5, 0xFD, 0xFE, 0xC0]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_encrypted_macho() {
+        let data = make_encrypted_macho(1);
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "encrypted").as_deref(), Some("true"));
+        assert_eq!(custom_value(&result, "cryptid").as_deref(), Some("1"));
+        assert_eq!(custom_value(&result, "cryptoff").as_deref(), Some("0x100"));
+        assert_eq!(custom_value(&result, "cryptsize").as_deref(), Some("256"));
+        assert_eq!(result.metadata.opaque_ranges, vec![(0x100, 0x100)]);
+        assert_eq!(result.metadata.warnings.len(), 1);
+
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert!(payload.notes.iter().any(
+            |n| n.level == crate::types::NoteLevel::Warning && n.message.contains("encrypted")
+        ));
+        assert!(!payload.extensions.iter().any(|e| e.name == "AVX"));
+
+        // Decrypted (cryptid=0) images are scanned as usual
+        let data = make_encrypted_macho(0);
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(custom_value(&result, "encrypted").as_deref(), Some("false"));
+        assert!(result.metadata.opaque_ranges.is_empty());
+        assert!(result.metadata.warnings.is_empty());
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        assert!(payload.extensions.iter().any(|e| e.name == "AVX"));
+    }
+
+    /// x86-64 image with header `flags` and one `LC_SEGMENT_64` named `segname`.
+    fn make_flagged_macho(flags: u32, segname: &[u8]) -> Vec<u8> {
+        let mut data = make_macho_header(cpu_type::X86_64, x86_64_subtype::ALL, 64);
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+        data[20..24].copy_from_slice(&72u32.to_le_bytes()); // sizeofcmds
+        data[24..28].copy_from_slice(&flags.to_le_bytes());
+        data.resize(32 + 72, 0);
+        data[32..36].copy_from_slice(&lc::LC_SEGMENT_64.to_le_bytes());
+        data[36..40].copy_from_slice(&72u32.to_le_bytes());
+        data[40..40 + segname.len()].copy_from_slice(segname);
+        data
+    }
+
+    #[test]
+    fn test_hardening_flags() {
+        let h = hardening(
+            &make_flagged_macho(mh::MH_PIE | mh::MH_NO_HEAP_EXECUTION, b"__RESTRICT"),
+            64,
+            true,
+        );
+        assert!(h.pie && h.nx);
+        assert_eq!(h.no_heap_execution, Some(true));
+        assert_eq!(h.restrict, Some(true));
+        assert_eq!(h.note, "PIE, NX, heap NX, restricted");
+
+        let h = hardening(
+            &make_flagged_macho(mh::MH_ALLOW_STACK_EXECUTION, b"__TEXT"),
+            64,
+            true,
+        );
+        assert!(!h.pie && !h.nx);
+        assert_eq!(h.no_heap_execution, Some(false));
+        assert_eq!(h.restrict, Some(false));
+        assert_eq!(h.stack_canary, None);
+    }
+
+    #[test]
+    fn test_build_version() {
+        let with_command = |cmd: u32, fields: &[u32]| {
+            let mut data = make_macho_header(cpu_type::ARM64, 0, 64);
+            let cmdsize = 8 + 4 * fields.len() as u32;
+            data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+            data[20..24].copy_from_slice(&cmdsize.to_le_bytes());
+            data.truncate(32);
+            data.extend_from_slice(&cmd.to_le_bytes());
+            data.extend_from_slice(&cmdsize.to_le_bytes());
+            for field in fields {
+                data.extend_from_slice(&field.to_le_bytes());
+            }
+            build_version(&data, 64, true)
+        };
+
+        // platform, minos 14.0, sdk 14.2, no tools
+        assert_eq!(
+            with_command(
+                lc::LC_BUILD_VERSION,
+                &[platform::MACOS, 0x000E_0000, 0x000E_0200, 0]
+            ),
+            Some((platform::MACOS, "14.0".to_string()))
+        );
+        assert_eq!(
+            with_command(lc::LC_BUILD_VERSION, &[platform::TVOS, 0x0011_0201, 0, 0]),
+            Some((platform::TVOS, "17.2.1".to_string()))
+        );
+        // version, sdk
+        assert_eq!(
+            with_command(lc::LC_VERSION_MIN_IPHONEOS, &[0x0009_0300, 0x000A_0000]),
+            Some((platform::IOS, "9.3".to_string()))
+        );
+        assert_eq!(with_command(lc::LC_UUID, &[0; 4]), None);
+        assert_eq!(platform_name(platform::MACCATALYST), Some("Mac Catalyst"));
+    }
+
+    /// Append a load command with a trailing NUL-padded string at `str_off`.
+    fn push_str_command(cmds: &mut Vec<u8>, cmd: u32, fields: &[u32], text: &str) {
+        let str_off = 8 + 4 * fields.len();
+        let cmdsize = (str_off + text.len() + 1 + 7) & !7;
+        let start = cmds.len();
+        cmds.extend_from_slice(&cmd.to_le_bytes());
+        cmds.extend_from_slice(&(cmdsize as u32).to_le_bytes());
+        for &field in fields {
+            cmds.extend_from_slice(&field.to_le_bytes());
+        }
+        cmds.extend_from_slice(text.as_bytes());
+        cmds.resize(start + cmdsize, 0);
+    }
+
+    /// x86-64 dylib with `LC_ID_DYLIB`, a strong and a weak
+    /// `LC_LOAD_DYLIB`, one `LC_RPATH` and an `LC_UUID`.
+    fn make_linked_dylib() -> Vec<u8> {
+        let mut cmds = Vec::new();
+        // dylib_command: name offset, timestamp, current, compatibility
+        push_str_command(
+            &mut cmds,
+            lc::LC_ID_DYLIB,
+            &[24, 2, 0x0001_0203, 0x0001_0000],
+            "@rpath/libfoo.dylib",
+        );
+        push_str_command(
+            &mut cmds,
+            lc::LC_LOAD_DYLIB,
+            &[24, 2, 0x0515_0000, 0x0001_0000],
+            "/usr/lib/libSystem.B.dylib",
+        );
+        push_str_command(
+            &mut cmds,
+            lc::LC_LOAD_WEAK_DYLIB,
+            &[24, 2, 0x0001_0000, 0x0001_0000],
+            "@rpath/libbar.dylib",
+        );
+        push_str_command(&mut cmds, lc::LC_RPATH, &[12], "@loader_path/../lib");
+        cmds.extend_from_slice(&lc::LC_UUID.to_le_bytes());
+        cmds.extend_from_slice(&24u32.to_le_bytes());
+        cmds.extend((0u8..16).map(|i| i * 0x11));
+
+        let mut data = make_macho_header(cpu_type::X86_64, 3, 64);
+        data.truncate(32);
+        data[16..20].copy_from_slice(&5u32.to_le_bytes()); // ncmds
+        data[20..24].copy_from_slice(&(cmds.len() as u32).to_le_bytes()); // sizeofcmds
+        data.extend_from_slice(&cmds);
+        data
+    }
+
+    #[test]
+    fn test_link_info() {
+        let data = make_linked_dylib();
+        let info = link_info(&data, 64, true);
+        let id = info.install_name.as_ref().unwrap();
+        assert_eq!(id.path, "@rpath/libfoo.dylib");
+        assert_eq!(format_dylib_version(id.current_version), "1.2.3");
+        assert_eq!(info.dylibs.len(), 2);
+        assert!(!info.dylibs[0].weak);
+        assert!(info.dylibs[1].weak);
+        assert_eq!(info.rpaths, vec!["@loader_path/../lib".to_string()]);
+
+        let result = parse(&data, 64, false).unwrap();
+        assert_eq!(
+            custom_value(&result, "install_name").as_deref(),
+            Some("@rpath/libfoo.dylib")
+        );
+        assert_eq!(
+            custom_value(&result, "install_version").as_deref(),
+            Some("1.2.3 (compatibility 1.0.0)")
+        );
+        assert_eq!(custom_value(&result, "dylib_count").as_deref(), Some("2"));
+        assert_eq!(
+            custom_value(&result, "dylibs").as_deref(),
+            Some(
+                "/usr/lib/libSystem.B.dylib (compatibility 1.0.0), \
+                 @rpath/libbar.dylib (weak, compatibility 1.0.0)"
+            )
+        );
+        assert_eq!(
+            custom_value(&result, "rpaths").as_deref(),
+            Some("@loader_path/../lib")
+        );
+        assert_eq!(
+            custom_value(&result, "uuid").as_deref(),
+            Some("00112233-4455-6677-8899-AABBCCDDEEFF")
+        );
+    }
+
+    #[test]
+    fn test_link_info_bounded_by_cmdsize() {
+        let mut data = make_linked_dylib();
+        // Point the install name past its own command
+        data[32 + 8..32 + 12].copy_from_slice(&0x200u32.to_le_bytes());
+        let info = link_info(&data, 64, true);
+        assert_eq!(info.install_name, None);
+        assert_eq!(info.dylibs.len(), 2);
+    }
+
+    #[test]
+    fn test_fat_slice_link_info() {
+        let slice = make_linked_dylib();
+        let mut data = vec![0u8; 0x1000];
+        data[0..4].copy_from_slice(&0xCAFE_BABEu32.to_be_bytes());
+        data[4..8].copy_from_slice(&1u32.to_be_bytes());
+        data[8..12].copy_from_slice(&cpu_type::X86_64.to_be_bytes());
+        data[12..16].copy_from_slice(&3u32.to_be_bytes());
+        data[16..20].copy_from_slice(&0x1000u32.to_be_bytes());
+        data[20..24].copy_from_slice(&(slice.len() as u32).to_be_bytes());
+        data.extend_from_slice(&slice);
+
+        let result = parse_fat(&data, true).unwrap();
+        assert_eq!(
+            custom_value(&result, "install_name").as_deref(),
+            Some("@rpath/libfoo.dylib")
+        );
+        let slices = parse_fat_all(&data, false).unwrap();
+        assert!(slices[0]
+            .classification
+            .metadata
+            .custom
+            .iter()
+            .any(|m| m.value.to_string() == "00112233-4455-6677-8899-AABBCCDDEEFF"));
+    }
+
+    #[test]
+    fn test_fat_slices_beyond_input() {
+        let mut data = vec![0u8; 0x1000];
+        data[0..4].copy_from_slice(&0xCAFE_BABEu32.to_be_bytes());
+        data[4..8].copy_from_slice(&2u32.to_be_bytes());
+        data[8..12].copy_from_slice(&cpu_type::X86_64.to_be_bytes());
+        data[16..20].copy_from_slice(&0x1000u32.to_be_bytes());
+        data[20..24].copy_from_slice(&0x4000u32.to_be_bytes());
+        data[28..32].copy_from_slice(&cpu_type::ARM64.to_be_bytes());
+        data[36..40].copy_from_slice(&0x8000u32.to_be_bytes());
+        data[40..44].copy_from_slice(&0x4000u32.to_be_bytes());
+        data.extend_from_slice(&0xCFFA_EDFEu32.to_be_bytes());
+
+        // The second slice starts past the end of the buffer
+        let result = parse_fat(&data, true).unwrap();
+        assert!(result.metadata.warnings.is_empty());
+        let slices = parse_fat_all(&data, false).unwrap();
+        assert_eq!(slices.len(), 2);
+        assert_eq!(slices[1].classification.isa, Isa::AArch64);
+        assert!(slices[0].classification.metadata.warnings.is_empty());
+        assert_eq!(
+            slices[1].classification.metadata.warnings,
+            [format!(
+                "slice 2 header at offset 0x8000 not examined \
+                     (input supplied ends at 0x{:x})",
+                data.len()
+            )]
+        );
+
+        // Header-only prefix: the fat_arch entry still names the CPU
+        let result = parse_fat(&data[..0x100], true).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.bitwidth, 64);
+        assert_eq!(result.endianness, Endianness::Little);
+        assert_eq!(
+            result.metadata.warnings,
+            ["first slice header at offset 0x1000 not examined \
+                 (input supplied ends at 0x100)"]
+        );
+
+        // The whole file: the second slice is missing, not unexamined
+        let slices = parse_fat_all_with_input_len(&data, false, Some(data.len() as u64)).unwrap();
+        assert_eq!(slices[1].classification.isa, Isa::AArch64);
+        assert_eq!(
+            slices[1].classification.metadata.warnings,
+            ["slice 2 header at offset 0x8000 extends past the end of the file (truncated)"]
+        );
+        let len = Some(0x100);
+        let result = parse_fat_with_input_len(&data[..0x100], true, len).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(
+            result.metadata.warnings,
+            ["first slice header at offset 0x1000 extends past the end of the file (truncated)"]
+        );
+    }
+
+    #[test]
+    fn test_fat64_lists_every_slice() {
+        // fat_arch_64 entries: x86_64, arm64e (with the PAC ABI bit) and a
+        // CPU type this crate does not know
+        let entries: [(u32, u32, u64); 3] = [
+            (cpu_type::X86_64, 3, 0x1000),
+            (cpu_type::ARM64, 0x8000_0000 | arm64_subtype::E, 0x2000),
+            (0x0000_1234, 0, 0x3000),
+        ];
+        let mut data = vec![0u8; 0x3000];
+        data[0..4].copy_from_slice(&FAT_MAGIC_64.to_be_bytes());
+        data[4..8].copy_from_slice(&3u32.to_be_bytes());
+        for (i, &(cpu, subtype, offset)) in entries.iter().enumerate() {
+            let entry = 8 + i * 32;
+            data[entry..entry + 4].copy_from_slice(&cpu.to_be_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&subtype.to_be_bytes());
+            data[entry + 8..entry + 16].copy_from_slice(&offset.to_be_bytes());
+            data[entry + 16..entry + 24].copy_from_slice(&0x1000u64.to_be_bytes());
+            data[entry + 24..entry + 28].copy_from_slice(&12u32.to_be_bytes());
+        }
+        data[0x1000..0x1004].copy_from_slice(&0xCFFA_EDFEu32.to_be_bytes());
+        data[0x2000..0x2004].copy_from_slice(&0xCFFA_EDFEu32.to_be_bytes());
+
+        let result = parse_fat(&data, true).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.bitwidth, 64);
+        let isas: Vec<Isa> = result.candidates.iter().map(|c| c.isa).collect();
+        assert_eq!(isas, [Isa::X86_64, Isa::AArch64, Isa::Unknown(0x1234)]);
+        assert!(result
+            .candidates
+            .iter()
+            .all(|c| c.endianness == Endianness::Little));
+        assert!(result
+            .metadata
+            .notes
+            .contains(&"Slice 2: AArch64 (ARM64) (64-bit), offset=8192, size=4096".to_string()));
+
+        let slices = parse_fat_all(&data, true).unwrap();
+        assert_eq!(slices.len(), 3);
+        assert_eq!(slices[1].offset, 0x2000);
+        assert_eq!(slices[1].align, 12);
+        assert!(slices[1]
+            .classification
+            .extensions
+            .iter()
+            .any(|e| e.name == "PAC"));
+        assert_eq!(slices[2].classification.isa, Isa::Unknown(0x1234));
+        assert_eq!(slices[2].classification.bitwidth, 32);
+    }
 }
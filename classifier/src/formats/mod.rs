@@ -16,6 +16,7 @@
 //! - Game console formats (XBE, XEX, SELF, NSO, DOL)
 //! - Boot/kernel images (zImage, uImage, FIT)
 //! - Hex formats (Intel HEX, S-record, TI-TXT)
+//! - Intel ME/CSME firmware ($FPT / $CPD)
 //! - Archive formats (ar)
 //! - Raw binary analysis
 
@@ -26,8 +27,11 @@ pub mod ar;
 pub mod bcf;
 pub mod bflt;
 pub mod coff;
+pub mod com;
 pub mod console;
 pub mod dex;
+pub mod dis;
+pub mod dotnet;
 pub mod ecoff;
 pub mod elf;
 pub mod epoc;
@@ -37,11 +41,14 @@ pub mod fatelf;
 pub mod frf;
 pub mod goff;
 pub mod hex;
+pub mod intel_me;
 pub mod java;
 pub mod kernel;
 pub mod llvm_bc;
 pub mod macho;
 pub mod mz;
+#[cfg(feature = "object-backend")]
+pub mod object_backend;
 pub mod ols;
 pub mod omf;
 pub mod os9;
@@ -54,8 +61,10 @@ pub mod som;
 pub mod sox;
 pub mod tds;
 pub mod vbf;
+pub mod vms;
 pub mod wasm;
 pub mod xcoff;
+pub mod zip;
 
 use crate::error::{ClassifierError, Result};
 use crate::types::ClassificationResult;
@@ -191,6 +200,8 @@ pub enum DetectedFormat {
     Console { variant: console::ConsoleFormat },
     /// Kernel/boot images
     Kernel { variant: kernel::KernelFormat },
+    /// Intel ME/CSME firmware with `$FPT`/`$CPD` header offset
+    IntelMe { offset: usize },
     /// ar archive
     Ar { variant: ar::ArVariant },
     /// Intel HEX / S-record / TI-TXT
@@ -217,12 +228,16 @@ pub enum DetectedFormat {
     Goff,
     /// LLVM bitcode
     LlvmBc { variant: llvm_bc::LlvmVariant },
+    /// Inferno Dis module
+    Dis,
     /// FatELF multi-architecture
     FatElf,
     /// WinOLS Project File container
     Ols,
     /// ECU EEPROM/Flash dump container (EPR)
     Epr,
+    /// OpenVMS image (VAX IHD / Alpha EIHD)
+    Vms { variant: vms::VmsVariant },
     /// VW ODIS SGO firmware container
     Sgo,
     /// Volvo/Ford VBF firmware container
@@ -237,85 +252,186 @@ pub enum DetectedFormat {
     Raw,
 }
 
-/// Detect the file format from magic bytes.
-pub fn detect_format(data: &[u8]) -> DetectedFormat {
-    if data.len() < 4 {
-        // Check for text-based hex formats (can work with minimal data)
-        if let Some(variant) = hex::detect(data) {
-            return DetectedFormat::Hex { variant };
-        }
-        return DetectedFormat::Raw;
+impl DetectedFormat {
+    /// Whether this detection rests on structural guesswork rather than an
+    /// unambiguous magic number.
+    ///
+    /// `0xCAFEBABE` is shared by 32-bit fat Mach-O and Java class files and
+    /// is split by sniffing the next header words; COFF objects, OMF record
+    /// chains, Palm databases, OpenVMS images, EPR dumps and SOX containers
+    /// have no magic at all. ESP images are recognised by a single byte and
+    /// a plausible entry point, and BSD a.out by a 16-bit magic that is
+    /// also a common instruction word.
+    pub fn is_heuristic(&self) -> bool {
+        matches!(
+            self,
+            DetectedFormat::MachOFat {
+                big_endian: true,
+                fat64: false,
+            } | DetectedFormat::JavaClass
+                | DetectedFormat::Coff { .. }
+                | DetectedFormat::Aout {
+                    variant: aout::AoutVariant::Bsd { .. }
+                }
+                | DetectedFormat::Omf
+                | DetectedFormat::Esp
+                | DetectedFormat::Palm
+                | DetectedFormat::Vms { .. }
+                | DetectedFormat::Epr
+                | DetectedFormat::Sox
+        )
     }
+}
 
-    // ELF
-    if data[..4] == magic::ELF {
-        if data.len() >= 6 {
-            return DetectedFormat::Elf {
-                class: data[4],
-                endian: data[5],
-            };
-        }
-        return DetectedFormat::Raw;
-    }
+/// A format detector: the detection when `data` (at least 4 bytes) has
+/// the format's signature.
+type Probe = fn(&[u8]) -> Option<DetectedFormat>;
 
+/// Format detectors in order of precedence. Formats with a strong magic
+/// come first; structural heuristics without one come last.
+const PROBES: &[Probe] = &[
+    // ELF
+    |data| {
+        (data[..4] == magic::ELF).then(|| match data.get(4..6) {
+            Some(&[class, endian]) => DetectedFormat::Elf { class, endian },
+            _ => DetectedFormat::Raw,
+        })
+    },
     // FatELF (check before Mach-O since magic could conflict)
-    if fatelf::detect(data) {
-        return DetectedFormat::FatElf;
-    }
+    |data| fatelf::detect(data).then_some(DetectedFormat::FatElf),
+    probe_mz,
+    probe_macho,
+    // Java class (after Mach-O fat check since 0xCAFEBABE conflicts)
+    |data| java::detect(data).then_some(DetectedFormat::JavaClass),
+    // WebAssembly
+    |data| wasm::detect(data).then_some(DetectedFormat::Wasm),
+    // DEX/ODEX/VDEX/ART (Android)
+    |data| dex::detect(data).map(|variant| DetectedFormat::Dex { variant }),
+    // PEF (Classic Mac OS)
+    |data| pef::detect(data).then_some(DetectedFormat::Pef),
+    // bFLT (uClinux)
+    |data| bflt::detect(data).then_some(DetectedFormat::Bflt),
+    // LLVM bitcode
+    |data| llvm_bc::detect(data).map(|variant| DetectedFormat::LlvmBc { variant }),
+    // Inferno Dis module (4-byte magic operand, header checked)
+    |data| dis::detect(data).then_some(DetectedFormat::Dis),
+    // VW ODIS SGO firmware container (strong 16-byte magic)
+    |data| sgo::detect(data).then_some(DetectedFormat::Sgo),
+    // Volvo/Ford VBF firmware container (ASCII magic "vbf_version")
+    |data| vbf::detect(data).then_some(DetectedFormat::Vbf),
+    // VW/Audi FRF encrypted flash container (8-byte magic)
+    |data| frf::detect(data).then_some(DetectedFormat::Frf),
+    // SMS-Soft CombiLoader BCF container (16-byte ASCII magic)
+    |data| bcf::detect(data).then_some(DetectedFormat::Bcf),
+    // VAG/Simos SOX encrypted container (structural header check)
+    |data| sox::detect(data).then_some(DetectedFormat::Sox),
+    // ar archive
+    |data| ar::detect(data).map(|variant| DetectedFormat::Ar { variant }),
+    // Game console formats
+    |data| console::detect(data).map(|variant| DetectedFormat::Console { variant }),
+    // Intel ME/CSME partition table or code partition directory
+    |data| intel_me::detect(data).map(|offset| DetectedFormat::IntelMe { offset }),
+    // Kernel/boot images
+    |data| kernel::detect(data).map(|variant| DetectedFormat::Kernel { variant }),
+    // GOFF (IBM z/Architecture) - check before generic COFF
+    |data| goff::detect(data).then_some(DetectedFormat::Goff),
+    // a.out (BSD, Plan 9, Minix)
+    |data| aout::detect(data).map(|variant| DetectedFormat::Aout { variant }),
+    // XCOFF
+    |data| match [data[0], data[1]] {
+        magic::XCOFF_32 => Some(DetectedFormat::Xcoff { bits: 32 }),
+        magic::XCOFF_64 => Some(DetectedFormat::Xcoff { bits: 64 }),
+        _ => None,
+    },
+    // ECOFF (check before standalone COFF since ECOFF has specific magic)
+    |data| ecoff::detect(data).map(|variant| DetectedFormat::Ecoff { variant }),
+    // Text-based hex formats (Intel HEX, S-record, TI-TXT)
+    |data| hex::detect(data).map(|variant| DetectedFormat::Hex { variant }),
+    // Standalone COFF (Windows object files)
+    // Must be checked after other formats since COFF detection is heuristic-based
+    |data| coff::detect(data).map(|machine| DetectedFormat::Coff { machine }),
+    // OMF object modules
+    |data| omf::detect(data).then_some(DetectedFormat::Omf),
+    // Turbo Debugger symbols
+    |data| tds::detect(data).then_some(DetectedFormat::Tds),
+    // HP-UX SOM
+    |data| som::detect(data).then_some(DetectedFormat::Som),
+    // ARM AOF/AXF
+    |data| aof::detect(data).then_some(DetectedFormat::Aof),
+    // Amiga Hunk
+    |data| amiga_hunk::detect(data).then_some(DetectedFormat::AmigaHunk),
+    // OS-9 modules
+    |data| os9::detect(data).then_some(DetectedFormat::Os9),
+    // Espressif firmware image
+    |data| esp::detect(data).then_some(DetectedFormat::Esp),
+    // Palm containers
+    |data| palm::detect(data).then_some(DetectedFormat::Palm),
+    // Symbian EPOC/E32 images
+    |data| epoc::detect(data).then_some(DetectedFormat::Epoc),
+    // WinOLS Project File
+    |data| ols::detect(data).then_some(DetectedFormat::Ols),
+    // OpenVMS images have no magic; header fields are validated instead
+    |data| vms::detect(data).map(|variant| DetectedFormat::Vms { variant }),
+    // ECU EEPROM/Flash dump (EPR container)
+    // Checked late because it uses structural heuristics (no magic bytes)
+    |data| epr::detect(data).then_some(DetectedFormat::Epr),
+];
 
-    // PE/COFF - check for PE first, then fall through to MZ/NE/LE/LX
-    if data.len() >= 2 && data[..2] == magic::MZ {
-        if data.len() >= 0x40 {
-            let pe_offset = u32::from_le_bytes([data[0x3C], data[0x3D], data[0x3E], data[0x3F]]);
-            let pe_off = pe_offset as usize;
-            if pe_off + 4 <= data.len() && data[pe_off..pe_off + 4] == magic::PE {
-                return DetectedFormat::Pe { pe_offset };
+/// PE/COFF - check for PE first, then fall through to MZ/NE/LE/LX
+fn probe_mz(data: &[u8]) -> Option<DetectedFormat> {
+    if data[..2] != magic::MZ {
+        return None;
+    }
+    if let Some(pe_off) = mz::lfanew(data, 4) {
+        if data[pe_off..pe_off + 4] == magic::PE {
+            if let Ok(pe_offset) = u32::try_from(pe_off) {
+                return Some(DetectedFormat::Pe { pe_offset });
             }
         }
-        // Not PE, try MZ/NE/LE/LX
-        if let Some(variant) = mz::detect(data) {
-            return DetectedFormat::Mz { variant };
-        }
     }
+    // Not PE, try MZ/NE/LE/LX
+    mz::detect(data).map(|variant| DetectedFormat::Mz { variant })
+}
 
-    // Mach-O (check before Java class since 0xCAFEBABE conflicts with fat binary)
+/// Mach-O (check before Java class since 0xCAFEBABE conflicts with fat binary)
+fn probe_macho(data: &[u8]) -> Option<DetectedFormat> {
     let magic4 = &data[..4];
     if magic4 == magic::MACHO_32_BE {
-        return DetectedFormat::MachO {
+        return Some(DetectedFormat::MachO {
             bits: 32,
             big_endian: true,
-        };
+        });
     }
     if magic4 == magic::MACHO_32_LE {
-        return DetectedFormat::MachO {
+        return Some(DetectedFormat::MachO {
             bits: 32,
             big_endian: false,
-        };
+        });
     }
     if magic4 == magic::MACHO_64_BE {
-        return DetectedFormat::MachO {
+        return Some(DetectedFormat::MachO {
             bits: 64,
             big_endian: true,
-        };
+        });
     }
     if magic4 == magic::MACHO_64_LE {
-        return DetectedFormat::MachO {
+        return Some(DetectedFormat::MachO {
             bits: 64,
             big_endian: false,
-        };
+        });
     }
     // Mach-O fat 64-bit (0xCAFEBABF / 0xBFBAFECA) — check before 32-bit since no Java conflict
     if magic4 == magic::MACHO_FAT64_BE {
-        return DetectedFormat::MachOFat {
+        return Some(DetectedFormat::MachOFat {
             big_endian: true,
             fat64: true,
-        };
+        });
     }
     if magic4 == magic::MACHO_FAT64_LE {
-        return DetectedFormat::MachOFat {
+        return Some(DetectedFormat::MachOFat {
             big_endian: false,
             fat64: true,
-        };
+        });
     }
     // Mach-O fat 32-bit uses 0xCAFEBABE which conflicts with Java class files
     // Differentiate by checking if it looks like valid fat header
@@ -334,10 +450,10 @@ pub fn detect_format(data: &[u8]) -> DetectedFormat {
                     let cputype = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
                     // Valid cputypes are typically 7, 12, 18, 0x01000007, 0x0100000c, etc.
                     if cputype > 0 && (cputype < 100 || cputype > 0x01000000) {
-                        return DetectedFormat::MachOFat {
+                        return Some(DetectedFormat::MachOFat {
                             big_endian: true,
                             fat64: false,
-                        };
+                        });
                     }
                 }
             }
@@ -345,175 +461,77 @@ pub fn detect_format(data: &[u8]) -> DetectedFormat {
         // Fall through to check for Java class
     }
     if magic4 == magic::MACHO_FAT_LE {
-        return DetectedFormat::MachOFat {
+        return Some(DetectedFormat::MachOFat {
             big_endian: false,
             fat64: false,
-        };
-    }
-
-    // Java class (after Mach-O fat check since 0xCAFEBABE conflicts)
-    if java::detect(data) {
-        return DetectedFormat::JavaClass;
-    }
-
-    // WebAssembly
-    if wasm::detect(data) {
-        return DetectedFormat::Wasm;
-    }
-
-    // DEX/ODEX/VDEX/ART (Android)
-    if let Some(variant) = dex::detect(data) {
-        return DetectedFormat::Dex { variant };
-    }
-
-    // PEF (Classic Mac OS)
-    if pef::detect(data) {
-        return DetectedFormat::Pef;
-    }
-
-    // bFLT (uClinux)
-    if bflt::detect(data) {
-        return DetectedFormat::Bflt;
-    }
-
-    // LLVM bitcode
-    if let Some(variant) = llvm_bc::detect(data) {
-        return DetectedFormat::LlvmBc { variant };
-    }
-
-    // VW ODIS SGO firmware container (strong 16-byte magic)
-    if sgo::detect(data) {
-        return DetectedFormat::Sgo;
-    }
-
-    // Volvo/Ford VBF firmware container (ASCII magic "vbf_version")
-    if vbf::detect(data) {
-        return DetectedFormat::Vbf;
-    }
-
-    // VW/Audi FRF encrypted flash container (8-byte magic)
-    if frf::detect(data) {
-        return DetectedFormat::Frf;
-    }
-
-    // SMS-Soft CombiLoader BCF container (16-byte ASCII magic)
-    if bcf::detect(data) {
-        return DetectedFormat::Bcf;
-    }
-
-    // VAG/Simos SOX encrypted container (structural header check)
-    if sox::detect(data) {
-        return DetectedFormat::Sox;
-    }
-
-    // ar archive
-    if let Some(variant) = ar::detect(data) {
-        return DetectedFormat::Ar { variant };
-    }
-
-    // Game console formats
-    if let Some(variant) = console::detect(data) {
-        return DetectedFormat::Console { variant };
-    }
-
-    // Kernel/boot images
-    if let Some(variant) = kernel::detect(data) {
-        return DetectedFormat::Kernel { variant };
-    }
-
-    // GOFF (IBM z/Architecture) - check before generic COFF
-    if goff::detect(data) {
-        return DetectedFormat::Goff;
-    }
-
-    // a.out (BSD, Plan 9, Minix)
-    if let Some(variant) = aout::detect(data) {
-        return DetectedFormat::Aout { variant };
+        });
     }
+    None
+}
 
-    // XCOFF
-    if data.len() >= 2 {
-        if data[..2] == magic::XCOFF_32 {
-            return DetectedFormat::Xcoff { bits: 32 };
-        }
-        if data[..2] == magic::XCOFF_64 {
-            return DetectedFormat::Xcoff { bits: 64 };
+/// Detect the file format from magic bytes.
+pub fn detect_format(data: &[u8]) -> DetectedFormat {
+    if data.len() < 4 {
+        // Check for text-based hex formats (can work with minimal data)
+        if let Some(variant) = hex::detect(data) {
+            return DetectedFormat::Hex { variant };
         }
+        return DetectedFormat::Raw;
     }
 
-    // ECOFF (check before standalone COFF since ECOFF has specific magic)
-    if let Some(variant) = ecoff::detect(data) {
-        return DetectedFormat::Ecoff { variant };
-    }
-
-    // Text-based hex formats (Intel HEX, S-record, TI-TXT)
-    if let Some(variant) = hex::detect(data) {
-        return DetectedFormat::Hex { variant };
-    }
-
-    // Standalone COFF (Windows object files)
-    // Must be checked after other formats since COFF detection is heuristic-based
-    if let Some(machine) = coff::detect(data) {
-        return DetectedFormat::Coff { machine };
-    }
-
-    // OMF object modules
-    if omf::detect(data) {
-        return DetectedFormat::Omf;
-    }
-
-    // Turbo Debugger symbols
-    if tds::detect(data) {
-        return DetectedFormat::Tds;
-    }
-
-    // HP-UX SOM
-    if som::detect(data) {
-        return DetectedFormat::Som;
-    }
-
-    // ARM AOF/AXF
-    if aof::detect(data) {
-        return DetectedFormat::Aof;
-    }
-
-    // Amiga Hunk
-    if amiga_hunk::detect(data) {
-        return DetectedFormat::AmigaHunk;
-    }
-
-    // OS-9 modules
-    if os9::detect(data) {
-        return DetectedFormat::Os9;
-    }
-
-    // Espressif firmware image
-    if esp::detect(data) {
-        return DetectedFormat::Esp;
-    }
+    PROBES
+        .iter()
+        .find_map(|probe| probe(data))
+        .unwrap_or(DetectedFormat::Raw)
+}
 
-    // Palm containers
-    if palm::detect(data) {
-        return DetectedFormat::Palm;
+/// Every format whose detector matches `data`, in order of precedence, so
+/// the first entry is what [`detect_format`] reports. Empty for raw data.
+///
+/// More than one entry means the file carries several formats' signatures:
+/// a polyglot, or a format whose magic collides with another's.
+pub fn detect_all(data: &[u8]) -> Vec<DetectedFormat> {
+    if data.len() < 4 {
+        return hex::detect(data)
+            .map(|variant| DetectedFormat::Hex { variant })
+            .into_iter()
+            .collect();
     }
+    PROBES
+        .iter()
+        .filter_map(|probe| probe(data))
+        .filter(|detected| !matches!(detected, DetectedFormat::Raw))
+        .collect()
+}
 
-    // Symbian EPOC/E32 images
-    if epoc::detect(data) {
-        return DetectedFormat::Epoc;
+/// Detect the file format, accepting only unambiguous magic numbers.
+///
+/// Detections for which [`DetectedFormat::is_heuristic`] holds are
+/// reported as [`DetectedFormat::Raw`], trading recall for precision.
+pub fn detect_format_strict(data: &[u8]) -> DetectedFormat {
+    match detect_format(data) {
+        detected if detected.is_heuristic() => DetectedFormat::Raw,
+        detected => detected,
     }
+}
 
-    // WinOLS Project File
-    if ols::detect(data) {
-        return DetectedFormat::Ols;
+/// Pass an ELF, PE or Mach-O parser's result through the `object` crate
+/// backend when the `object-backend` feature is enabled.
+///
+/// Without the feature the result is returned unchanged.
+pub fn with_backend(
+    data: &[u8],
+    builtin: Result<ClassificationResult>,
+) -> Result<ClassificationResult> {
+    #[cfg(feature = "object-backend")]
+    {
+        object_backend::reconcile(data, builtin)
     }
-
-    // ECU EEPROM/Flash dump (EPR container)
-    // Checked late because it uses structural heuristics (no magic bytes)
-    if epr::detect(data) {
-        return DetectedFormat::Epr;
+    #[cfg(not(feature = "object-backend"))]
+    {
+        let _ = data;
+        builtin
     }
-
-    DetectedFormat::Raw
 }
 
 /// Parse a binary file and return classification result.
@@ -521,9 +539,13 @@ pub fn parse_binary(data: &[u8]) -> Result<ClassificationResult> {
     let format = detect_format(data);
 
     match format {
-        DetectedFormat::Elf { class, endian } => elf::parse(data, class, endian),
-        DetectedFormat::Pe { pe_offset } => pe::parse(data, pe_offset),
-        DetectedFormat::MachO { bits, big_endian } => macho::parse(data, bits, big_endian),
+        DetectedFormat::Elf { class, endian } => {
+            with_backend(data, elf::parse(data, class, endian))
+        }
+        DetectedFormat::Pe { pe_offset } => with_backend(data, pe::parse(data, pe_offset)),
+        DetectedFormat::MachO { bits, big_endian } => {
+            with_backend(data, macho::parse(data, bits, big_endian))
+        }
         DetectedFormat::MachOFat {
             big_endian,
             fat64: _,
@@ -540,6 +562,7 @@ pub fn parse_binary(data: &[u8]) -> Result<ClassificationResult> {
         DetectedFormat::Bflt => bflt::parse(data),
         DetectedFormat::Console { variant } => console::parse(data, variant),
         DetectedFormat::Kernel { variant } => kernel::parse(data, variant),
+        DetectedFormat::IntelMe { offset } => intel_me::parse(data, offset),
         DetectedFormat::Ar { variant } => ar::parse(data, variant),
         DetectedFormat::Hex { variant } => hex::parse(data, variant),
         DetectedFormat::Omf => omf::parse(data),
@@ -553,9 +576,11 @@ pub fn parse_binary(data: &[u8]) -> Result<ClassificationResult> {
         DetectedFormat::Os9 => os9::parse(data),
         DetectedFormat::Goff => goff::parse(data),
         DetectedFormat::LlvmBc { variant } => llvm_bc::parse(data, variant),
+        DetectedFormat::Dis => dis::parse(data),
         DetectedFormat::FatElf => fatelf::parse(data),
         DetectedFormat::Ols => ols::parse(data),
         DetectedFormat::Epr => epr::parse(data),
+        DetectedFormat::Vms { variant } => vms::parse(data, variant),
         DetectedFormat::Sgo => sgo::parse(data),
         DetectedFormat::Vbf => vbf::parse(data),
         DetectedFormat::Frf => frf::parse(data),
@@ -565,16 +590,92 @@ pub fn parse_binary(data: &[u8]) -> Result<ClassificationResult> {
     }
 }
 
+/// A structure the format declares past the end of the input.
+///
+/// The input may be only a prefix of the file (a partial read or a streaming
+/// reader), so a declared structure that is missing is not
+/// necessarily truncation. Parsers conclude what they can from the bytes at
+/// hand and add [`Absent::note`] to their warnings;
+/// [`ClassifierError::TruncatedData`] is reserved for structures a parser
+/// cannot do without, such as its fixed header. When the length of the whole
+/// input is known ([`ClassifierOptions::input_len`]), a structure that runs
+/// past it is reported as truncation instead.
+///
+/// [`ClassifierOptions::input_len`]: crate::types::ClassifierOptions::input_len
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Absent {
+    /// What was skipped (`"section headers"`, `"member 3"`)
+    pub structure: String,
+    /// File offset the structure starts at
+    pub offset: u64,
+    /// The structure runs past the end of the whole input, not just `data`
+    pub truncated: bool,
+    /// Length of the bytes at hand, where an unexamined structure was cut off
+    pub available: u64,
+}
+
+impl Absent {
+    /// `Some` when the `len` bytes at `offset` are not all within `data`.
+    ///
+    /// `input_len` is the length of the whole input `data` was read from,
+    /// when known.
+    pub fn check(
+        data: &[u8],
+        input_len: Option<u64>,
+        structure: impl Into<String>,
+        offset: u64,
+        len: u64,
+    ) -> Option<Self> {
+        let end = offset.checked_add(len);
+        let present = end.is_some_and(|end| end <= data.len() as u64);
+        (!present).then(|| Self {
+            structure: structure.into(),
+            offset,
+            truncated: input_len.is_some_and(|input_len| end.map_or(true, |end| end > input_len)),
+            available: data.len() as u64,
+        })
+    }
+
+    /// The warning attached to results that skipped this structure.
+    pub fn note(&self) -> String {
+        if self.truncated {
+            format!(
+                "{} at offset 0x{:x} extends past the end of the file (truncated)",
+                self.structure, self.offset
+            )
+        } else {
+            format!(
+                "{} at offset 0x{:x} not examined (input supplied ends at 0x{:x})",
+                self.structure, self.offset, self.available
+            )
+        }
+    }
+}
+
 /// Read bytes with bounds checking.
+///
+/// The end of the range is computed with checked arithmetic, so offsets
+/// near `usize::MAX` yield [`ClassifierError::TruncatedData`] rather than
+/// wrapping. A zero-length read is valid at any offset up to and including
+/// `data.len()` and returns an empty slice.
 pub fn read_bytes<'a>(data: &'a [u8], offset: usize, len: usize) -> Result<&'a [u8]> {
-    if offset + len > data.len() {
-        return Err(ClassifierError::TruncatedData {
+    match offset.checked_add(len) {
+        Some(end) if end <= data.len() => Ok(&data[offset..end]),
+        _ => Err(ClassifierError::TruncatedData {
             offset,
             expected: len,
             actual: data.len().saturating_sub(offset),
-        });
+        }),
     }
-    Ok(&data[offset..offset + len])
+}
+
+/// Read a single byte.
+///
+/// # Errors
+///
+/// Returns [`ClassifierError::TruncatedData`] if `offset` is out of range.
+pub fn read_u8(data: &[u8], offset: usize) -> Result<u8> {
+    Ok(read_bytes(data, offset, 1)?[0])
 }
 
 /// Read a u16 with specified endianness.
@@ -611,6 +712,45 @@ pub fn read_u64(data: &[u8], offset: usize, little_endian: bool) -> Result<u64>
     })
 }
 
+/// Read an i32 with specified endianness.
+///
+/// # Errors
+///
+/// Returns [`ClassifierError::TruncatedData`] if fewer than four bytes remain.
+pub fn read_i32(data: &[u8], offset: usize, little_endian: bool) -> Result<i32> {
+    let bytes = read_bytes(data, offset, 4)?;
+    Ok(if little_endian {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    } else {
+        i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    })
+}
+
+/// Read a NUL-terminated string of at most `max_len` bytes.
+///
+/// Returns the bytes before the first NUL. If no NUL occurs within
+/// `max_len` bytes (or before the end of `data`), the bounded field is
+/// returned as-is, which matches fixed-width name fields that fill their
+/// whole slot. The terminator is never included in the result.
+///
+/// # Errors
+///
+/// Returns [`ClassifierError::TruncatedData`] if `offset` lies past the end
+/// of `data`.
+pub fn read_cstr(data: &[u8], offset: usize, max_len: usize) -> Result<&[u8]> {
+    let available = data
+        .len()
+        .checked_sub(offset)
+        .ok_or(ClassifierError::TruncatedData {
+            offset,
+            expected: 1,
+            actual: 0,
+        })?;
+    let field = read_bytes(data, offset, max_len.min(available))?;
+    let end = memchr::memchr(0, field).unwrap_or(field.len());
+    Ok(&field[..end])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -627,6 +767,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_mz_with_wild_lfanew() {
+        let mut data = vec![0u8; 0x80];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3C..0x40].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        assert!(matches!(
+            detect_format(&data),
+            DetectedFormat::Mz {
+                variant: mz::ExtendedType::Mz
+            }
+        ));
+        assert!(parse_binary(&data).is_ok());
+
+        // In range of the data but past MAX_LFANEW
+        let mut data = vec![0u8; mz::MAX_LFANEW + 0x100];
+        data[..2].copy_from_slice(b"MZ");
+        let far = mz::MAX_LFANEW + 0x10;
+        data[0x3C..0x40].copy_from_slice(&u32::try_from(far).unwrap().to_le_bytes());
+        data[far..far + 4].copy_from_slice(&magic::PE);
+        assert!(matches!(detect_format(&data), DetectedFormat::Mz { .. }));
+    }
+
     #[test]
     fn test_detect_macho() {
         let data = [0xCF, 0xFA, 0xED, 0xFE];
@@ -646,6 +808,71 @@ mod tests {
         assert_eq!(read_u32(&data, 0, false).unwrap(), 0x01020304);
     }
 
+    #[test]
+    fn test_read_bytes_boundaries() {
+        let data = [0u8; 8];
+        for offset in 0..=10usize {
+            for len in 0..=10usize {
+                let result = read_bytes(&data, offset, len);
+                if offset + len <= data.len() {
+                    assert_eq!(result.unwrap().len(), len);
+                } else {
+                    assert!(result.is_err(), "offset={offset} len={len}");
+                }
+            }
+        }
+
+        // Zero-length reads at the end boundary succeed with an empty slice.
+        assert_eq!(read_bytes(&data, 8, 0).unwrap(), &[] as &[u8]);
+        assert!(read_bytes(&data, 9, 0).is_err());
+    }
+
+    #[test]
+    fn test_read_bytes_overflow() {
+        let data = [0u8; 8];
+        for (offset, len) in [
+            (usize::MAX, 1),
+            (usize::MAX, usize::MAX),
+            (1, usize::MAX),
+            (usize::MAX - 3, 4),
+            (4, usize::MAX - 3),
+        ] {
+            match read_bytes(&data, offset, len) {
+                Err(ClassifierError::TruncatedData { .. }) => {}
+                other => panic!("offset={offset} len={len}: {other:?}"),
+            }
+        }
+        assert!(read_u16(&data, usize::MAX, true).is_err());
+        assert!(read_u32(&data, usize::MAX - 1, true).is_err());
+        assert!(read_u64(&data, usize::MAX - 7, false).is_err());
+        assert!(read_u8(&data, usize::MAX).is_err());
+        assert!(read_i32(&data, usize::MAX, true).is_err());
+        assert!(read_cstr(&data, usize::MAX, 16).is_err());
+    }
+
+    #[test]
+    fn test_read_u8_i32() {
+        let data = [0xFE, 0xFF, 0xFF, 0xFF, 0x80];
+        assert_eq!(read_u8(&data, 4).unwrap(), 0x80);
+        assert!(read_u8(&data, 5).is_err());
+        assert_eq!(read_i32(&data, 0, true).unwrap(), -2);
+        assert_eq!(read_i32(&data, 0, false).unwrap(), -16_777_217);
+        assert!(read_i32(&data, 2, true).is_err());
+    }
+
+    #[test]
+    fn test_read_cstr() {
+        let data = b"abc\0defgh";
+        assert_eq!(read_cstr(data, 0, 16).unwrap(), b"abc");
+        assert_eq!(read_cstr(data, 4, 16).unwrap(), b"defgh");
+        assert_eq!(read_cstr(data, 4, 3).unwrap(), b"def");
+        assert_eq!(read_cstr(data, 3, 16).unwrap(), b"");
+        assert_eq!(read_cstr(data, 0, 0).unwrap(), b"");
+        assert_eq!(read_cstr(data, data.len(), 16).unwrap(), b"");
+        assert!(read_cstr(data, data.len() + 1, 16).is_err());
+        assert_eq!(read_cstr(data, 4, usize::MAX).unwrap(), b"defgh");
+    }
+
     #[test]
     fn test_detect_wasm() {
         let data = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
@@ -665,6 +892,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strict_detection() {
+        // 0xCAFEBABE with a plausible fat header, and as a Java class
+        let mut fat = vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x01];
+        fat.extend_from_slice(&[0x01, 0x00, 0x00, 0x07]);
+        fat.resize(64, 0);
+        assert!(matches!(
+            detect_format(&fat),
+            DetectedFormat::MachOFat { .. }
+        ));
+        assert!(matches!(detect_format_strict(&fat), DetectedFormat::Raw));
+
+        let java = [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34];
+        assert!(matches!(detect_format_strict(&java), DetectedFormat::Raw));
+
+        // Unambiguous magics are unaffected
+        let wasm = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        assert!(matches!(detect_format_strict(&wasm), DetectedFormat::Wasm));
+        let fat64 = [0xCA, 0xFE, 0xBA, 0xBF, 0x00, 0x00, 0x00, 0x01];
+        assert!(!detect_format_strict(&fat64).is_heuristic());
+    }
+
+    #[test]
+    fn test_heuristic_formats() {
+        let heuristic = [
+            DetectedFormat::MachOFat {
+                big_endian: true,
+                fat64: false,
+            },
+            DetectedFormat::JavaClass,
+            DetectedFormat::Coff { machine: 0x14C },
+            DetectedFormat::Aout {
+                variant: aout::AoutVariant::Bsd {
+                    mid: 134,
+                    magic: 0o413,
+                },
+            },
+            DetectedFormat::Omf,
+            DetectedFormat::Esp,
+            DetectedFormat::Palm,
+            DetectedFormat::Vms {
+                variant: vms::VmsVariant::VaxIhd,
+            },
+            DetectedFormat::Epr,
+            DetectedFormat::Sox,
+        ];
+        for detected in &heuristic {
+            assert!(detected.is_heuristic(), "{detected:?}");
+        }
+
+        let magic = [
+            DetectedFormat::MachOFat {
+                big_endian: true,
+                fat64: true,
+            },
+            DetectedFormat::Aout {
+                variant: aout::AoutVariant::Plan9 {
+                    magic: aout::plan9_magic::I_MAGIC,
+                },
+            },
+            DetectedFormat::Pef,
+            DetectedFormat::Dis,
+            DetectedFormat::Raw,
+        ];
+        for detected in &magic {
+            assert!(!detected.is_heuristic(), "{detected:?}");
+        }
+
+        // ESP: 0xE9, one segment, entry in IRAM
+        let mut esp = vec![0xE9, 0x01, 0x02, 0x20];
+        esp.extend_from_slice(&0x4008_0000u32.to_le_bytes());
+        esp.resize(64, 0);
+        assert!(matches!(detect_format(&esp), DetectedFormat::Esp));
+        assert!(matches!(detect_format_strict(&esp), DetectedFormat::Raw));
+    }
+
     #[test]
     fn test_detect_dex() {
         let data = [b'd', b'e', b'x', b'\n', b'0', b'3', b'5', 0x00];
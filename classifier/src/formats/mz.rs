@@ -33,6 +33,22 @@ pub const NE_HEADER_SIZE: usize = 64;
 /// LE/LX header size.
 pub const LE_HEADER_SIZE: usize = 196;
 
+/// Largest extended header offset (`e_lfanew`) accepted.
+///
+/// Real linkers place the PE/NE/LE header within the first few KB; larger
+/// values come from corrupt or hostile headers.
+pub const MAX_LFANEW: usize = 4 * 1024 * 1024;
+
+/// Read `e_lfanew`, the offset of the extended header.
+///
+/// Returns `None` unless the offset leaves room for a `min_size`-byte header
+/// inside `data` and is at most [`MAX_LFANEW`].
+pub fn lfanew(data: &[u8], min_size: usize) -> Option<usize> {
+    let offset = usize::try_from(read_u32(data, 0x3C, true).ok()?).ok()?;
+    let end = offset.checked_add(min_size)?;
+    (offset <= MAX_LFANEW && end <= data.len()).then_some(offset)
+}
+
 /// NE target OS values.
 pub mod ne_os {
     pub const UNKNOWN: u8 = 0;
@@ -91,11 +107,8 @@ pub fn detect(data: &[u8]) -> Option<ExtendedType> {
         return None;
     }
 
-    // Check for extended header pointer at offset 0x3C
-    let lfanew = u32::from_le_bytes([data[0x3C], data[0x3D], data[0x3E], data[0x3F]]) as usize;
-
-    // Check for extended header if pointer is valid
-    if lfanew >= MZ_HEADER_SIZE && lfanew + 2 <= data.len() {
+    // Check for extended header if the pointer at 0x3C is valid
+    if let Some(lfanew) = lfanew(data, 2).filter(|&off| off >= MZ_HEADER_SIZE) {
         let sig = &data[lfanew..lfanew + 2];
 
         // Check for NE
@@ -183,7 +196,7 @@ fn parse_mz(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, 16, Endianness::Little, FileFormat::Mz);
-    result.variant = Variant::new("DOS real mode");
+    result.variant = Some(Variant::new("DOS real mode"));
     result.metadata = metadata;
 
     Ok(result)
@@ -192,17 +205,14 @@ fn parse_mz(data: &[u8]) -> Result<ClassificationResult> {
 /// Parse NE (New Executable).
 fn parse_ne(data: &[u8], target_os: u8) -> Result<ClassificationResult> {
     // Find NE header offset
-    let lfanew = read_u32(data, 0x3C, true)? as usize;
-
-    if lfanew + NE_HEADER_SIZE > data.len() {
+    let Some(ne_off) = lfanew(data, NE_HEADER_SIZE) else {
+        let offset = read_u32(data, 0x3C, true)? as usize;
         return Err(ClassifierError::TruncatedData {
-            offset: lfanew,
+            offset,
             expected: NE_HEADER_SIZE,
-            actual: data.len().saturating_sub(lfanew),
+            actual: data.len().saturating_sub(offset),
         });
-    }
-
-    let ne_off = lfanew;
+    };
 
     // Parse NE header
     let linker_major = data[ne_off + 2];
@@ -257,7 +267,7 @@ fn parse_ne(data: &[u8], target_os: u8) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, 16, Endianness::Little, FileFormat::Ne);
-    result.variant = Variant::new(os_name);
+    result.variant = Some(Variant::new(os_name));
     result.metadata = metadata;
 
     Ok(result)
@@ -265,20 +275,15 @@ fn parse_ne(data: &[u8], target_os: u8) -> Result<ClassificationResult> {
 
 /// Parse LE/LX (Linear Executable).
 fn parse_le_lx(data: &[u8], is_lx: bool, cpu: u16, os: u16) -> Result<ClassificationResult> {
-    let lfanew = read_u32(data, 0x3C, true)? as usize;
-
-    if lfanew + LE_HEADER_SIZE > data.len() {
-        // Allow partial header
-        if lfanew + 32 > data.len() {
-            return Err(ClassifierError::TruncatedData {
-                offset: lfanew,
-                expected: LE_HEADER_SIZE,
-                actual: data.len().saturating_sub(lfanew),
-            });
-        }
-    }
-
-    let le_off = lfanew;
+    // Allow a partial header
+    let Some(le_off) = lfanew(data, 32) else {
+        let offset = read_u32(data, 0x3C, true)? as usize;
+        return Err(ClassifierError::TruncatedData {
+            offset,
+            expected: LE_HEADER_SIZE,
+            actual: data.len().saturating_sub(offset),
+        });
+    };
 
     // Parse LE/LX header
     let byte_order = data[le_off + 2];
@@ -356,12 +361,13 @@ fn parse_le_lx(data: &[u8], is_lx: bool, cpu: u16, os: u16) -> Result<Classifica
         entry_point: Some(eip_offset as u64),
         flags: Some(module_flags),
         section_count: Some(module_pages as usize),
+        raw_machine: Some(u32::from(cpu)),
         notes,
         ..Default::default()
     };
 
     let mut result = ClassificationResult::from_format(isa, bitwidth, Endianness::Little, format);
-    result.variant = Variant::new(format!("{} {}", os_name, cpu_name));
+    result.variant = Some(Variant::new(format!("{os_name} {cpu_name}")));
     result.metadata = metadata;
 
     Ok(result)
@@ -0,0 +1,319 @@
+//! ELF, PE and Mach-O header parsing through the `object` crate.
+//!
+//! Enabled by the `object-backend` feature. `object` is the primary parser
+//! for these formats: it reads the headers, and its raw machine fields go
+//! through [`Machine::classify`], the one mapping onto the built-in ISA
+//! tables ([`elf::e_machine_to_isa`], [`pe::machine_to_isa`],
+//! [`macho::cpu_type_to_isa`]), so both paths agree on naming.
+//!
+//! The built-in parsers are the fallback. Their result is used when
+//! `object` rejects an image, and otherwise supplies what `object` does not
+//! report (OS ABI notes, code signatures, CLR metadata, ...). Other formats
+//! never reach this module.
+
+use object::read::elf::FileHeader;
+use object::read::macho::MachHeader;
+use object::read::pe::ImageNtHeaders;
+use object::{LittleEndian, Object};
+
+use crate::error::Result;
+use crate::formats::{elf, macho, pe};
+use crate::types::{
+    ClassificationMetadata, ClassificationResult, Endianness, Extension, FileFormat, Isa, Variant,
+};
+
+/// Raw machine fields read from an image's headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Machine {
+    Elf {
+        e_machine: u16,
+        e_flags: u32,
+        class: u8,
+    },
+    MachO {
+        cpu_type: u32,
+        cpu_subtype: u32,
+        bits: u8,
+    },
+    Pe {
+        machine: u16,
+    },
+}
+
+/// ISA, word size, byte order, variant and extensions for a [`Machine`].
+struct Mapped {
+    isa: Isa,
+    bits: u8,
+    endianness: Endianness,
+    variant: Option<Variant>,
+    extensions: Vec<Extension>,
+}
+
+impl Machine {
+    /// Read the machine fields, or `None` for formats other than ELF, PE
+    /// and Mach-O.
+    fn read(file: &object::File) -> Option<Self> {
+        Some(match file {
+            object::File::Elf32(f) => {
+                let header = f.elf_header();
+                Self::Elf {
+                    e_machine: header.e_machine(f.endian()),
+                    e_flags: header.e_flags(f.endian()),
+                    class: elf::class::ELFCLASS32,
+                }
+            }
+            object::File::Elf64(f) => {
+                let header = f.elf_header();
+                Self::Elf {
+                    e_machine: header.e_machine(f.endian()),
+                    e_flags: header.e_flags(f.endian()),
+                    class: elf::class::ELFCLASS64,
+                }
+            }
+            object::File::MachO32(f) => {
+                let header = f.macho_header();
+                Self::MachO {
+                    cpu_type: header.cputype(f.endian()),
+                    cpu_subtype: header.cpusubtype(f.endian()),
+                    bits: 32,
+                }
+            }
+            object::File::MachO64(f) => {
+                let header = f.macho_header();
+                Self::MachO {
+                    cpu_type: header.cputype(f.endian()),
+                    cpu_subtype: header.cpusubtype(f.endian()),
+                    bits: 64,
+                }
+            }
+            object::File::Pe32(f) => Self::Pe {
+                machine: f.nt_headers().file_header().machine.get(LittleEndian),
+            },
+            object::File::Pe64(f) => Self::Pe {
+                machine: f.nt_headers().file_header().machine.get(LittleEndian),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Map the fields onto an ISA. `little` is the byte order `object`
+    /// read the headers in; PE machines carry their own.
+    fn classify(self, data: &[u8], little: bool) -> Mapped {
+        let endianness = if little {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        };
+        match self {
+            Self::Elf {
+                e_machine,
+                e_flags,
+                class,
+            } => {
+                let (isa, bits) = elf::e_machine_to_isa(e_machine, class);
+                let (variant, extensions) = elf::parse_e_flags(isa, e_flags, data);
+                Mapped {
+                    isa,
+                    bits,
+                    endianness,
+                    variant: variant.non_empty(),
+                    extensions,
+                }
+            }
+            Self::MachO {
+                cpu_type,
+                cpu_subtype,
+                bits,
+            } => {
+                let (isa, isa_bits, note) = macho::cpu_type_to_isa(cpu_type, cpu_subtype);
+                Mapped {
+                    isa,
+                    bits: isa_bits.max(bits),
+                    endianness,
+                    variant: macho::subtype_variant(cpu_type, cpu_subtype, note),
+                    extensions: macho::extensions_from_cpu(cpu_type, cpu_subtype),
+                }
+            }
+            Self::Pe { machine } => {
+                let (isa, bits, endianness, note) = pe::machine_to_isa(machine);
+                Mapped {
+                    isa,
+                    bits,
+                    endianness,
+                    variant: note.map(Variant::new),
+                    extensions: Vec::new(),
+                }
+            }
+        }
+    }
+
+    fn format(self) -> FileFormat {
+        match self {
+            Self::Elf { .. } => FileFormat::Elf,
+            Self::MachO { .. } => FileFormat::MachO,
+            Self::Pe { .. } => FileFormat::Pe,
+        }
+    }
+
+    fn raw(self) -> u32 {
+        match self {
+            Self::Elf { e_machine, .. } => u32::from(e_machine),
+            Self::MachO { cpu_type, .. } => cpu_type,
+            Self::Pe { machine } => u32::from(machine),
+        }
+    }
+
+    fn flags(self) -> Option<u32> {
+        match self {
+            Self::Elf { e_flags, .. } => Some(e_flags),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `data` with `object`, or `None` when it is not an ELF, PE or
+/// Mach-O image that `object` accepts.
+pub fn parse(data: &[u8]) -> Option<ClassificationResult> {
+    let file = object::File::parse(data).ok()?;
+    let machine = Machine::read(&file)?;
+    let mapped = machine.classify(data, file.is_little_endian());
+
+    let metadata = ClassificationMetadata {
+        entry_point: Some(file.entry()).filter(|&entry| entry != 0),
+        section_count: Some(file.sections().count()).filter(|&count| count != 0),
+        raw_machine: Some(machine.raw()),
+        flags: machine.flags(),
+        notes: vec![BACKEND_NOTE.to_string()],
+        ..Default::default()
+    };
+
+    let mut result = ClassificationResult::from_format(
+        mapped.isa,
+        mapped.bits,
+        mapped.endianness,
+        machine.format(),
+    );
+    result.variant = mapped.variant;
+    result.extensions = mapped.extensions;
+    result.metadata = metadata;
+    Some(result)
+}
+
+const BACKEND_NOTE: &str = "Headers parsed by the object crate backend";
+
+/// Combine the backend with a built-in parser's `builtin` result.
+///
+/// When `object` accepts the image its header fields win: ISA, word size,
+/// byte order, entry point, section count and raw machine. The built-in
+/// result contributes the metadata `object` does not read, and a warning
+/// is added when it mapped the machine differently. When `object` rejects
+/// the image the built-in result is returned as is.
+pub fn reconcile(
+    data: &[u8],
+    builtin: Result<ClassificationResult>,
+) -> Result<ClassificationResult> {
+    let Some(mut backend) = parse(data) else {
+        return builtin;
+    };
+    let mut result = match builtin {
+        Ok(result) => result,
+        Err(err) => {
+            backend
+                .metadata
+                .warnings
+                .push(format!("built-in parser rejected the headers: {err}"));
+            return Ok(backend);
+        }
+    };
+
+    if result.isa != backend.isa {
+        result.metadata.warnings.push(format!(
+            "built-in parser maps this machine to {}; using the object crate's {}",
+            result.isa, backend.isa
+        ));
+        result.variant = backend.variant;
+        result.extensions = backend.extensions;
+    }
+    result.isa = backend.isa;
+    result.bitwidth = backend.bitwidth;
+    result.endianness = backend.endianness;
+    let metadata = &mut result.metadata;
+    metadata.entry_point = backend.metadata.entry_point.or(metadata.entry_point);
+    metadata.section_count = backend.metadata.section_count.or(metadata.section_count);
+    metadata.raw_machine = backend.metadata.raw_machine;
+    metadata.notes.push(BACKEND_NOTE.to_string());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Isa;
+
+    /// Minimal little-endian ELF64 executable header for `e_machine`.
+    fn make_elf64(e_machine: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&crate::formats::magic::ELF);
+        data[4] = elf::class::ELFCLASS64;
+        data[5] = elf::data::ELFDATA2LSB;
+        data[6] = 1; // EV_CURRENT
+        data[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        data[18..20].copy_from_slice(&e_machine.to_le_bytes());
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+        data[24..32].copy_from_slice(&0x40_1000u64.to_le_bytes());
+        data[52..54].copy_from_slice(&64u16.to_le_bytes()); // e_ehsize
+        data
+    }
+
+    #[test]
+    fn test_backend_matches_builtin_elf() {
+        for e_machine in [0x3E, 0xB7, 0xF3, 0x15] {
+            let data = make_elf64(e_machine);
+            let builtin =
+                elf::parse(&data, elf::class::ELFCLASS64, elf::data::ELFDATA2LSB).unwrap();
+            let backend = parse(&data).unwrap();
+            assert_eq!(backend.isa, builtin.isa, "e_machine 0x{e_machine:X}");
+            assert_eq!(backend.bitwidth, builtin.bitwidth);
+            assert_eq!(backend.endianness, builtin.endianness);
+            assert_eq!(backend.format, FileFormat::Elf);
+            assert_eq!(backend.metadata.entry_point, Some(0x40_1000));
+        }
+    }
+
+    #[test]
+    fn test_reconcile_falls_back_to_backend() {
+        let data = make_elf64(0x3E);
+        let rejected = Err(crate::error::ClassifierError::ElfParseError {
+            message: "synthetic rejection".to_string(),
+        });
+        let result = reconcile(&data, rejected).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert!(result.metadata.warnings[0].contains("synthetic rejection"));
+    }
+
+    #[test]
+    fn test_backend_header_fields_take_precedence() {
+        let data = make_elf64(0xB7);
+        let mut builtin =
+            elf::parse(&data, elf::class::ELFCLASS64, elf::data::ELFDATA2LSB).unwrap();
+        builtin.isa = Isa::X86_64;
+        builtin.metadata.notes.push("built-in note".to_string());
+
+        let result = reconcile(&data, Ok(builtin)).unwrap();
+        assert_eq!(result.isa, Isa::AArch64);
+        assert_eq!(result.metadata.entry_point, Some(0x40_1000));
+        assert!(result.metadata.notes.iter().any(|n| n == "built-in note"));
+        assert!(result
+            .metadata
+            .warnings
+            .iter()
+            .any(|w| w.contains("built-in parser maps")));
+    }
+
+    #[test]
+    fn test_non_object_formats_pass_through() {
+        assert!(parse(&[0u8; 64]).is_none());
+        let builtin = Err(crate::error::ClassifierError::UnknownFormat { magic: vec![0; 4] });
+        assert!(reconcile(&[0u8; 64], builtin).is_err());
+    }
+}
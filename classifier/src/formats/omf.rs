@@ -5,9 +5,21 @@
 
 use crate::error::{ClassifierError, Result};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
+/// Translator header: opens an object module with its name
+const THEADR: u8 = 0x80;
+/// Library module header: opens a module inside a library
+const LHEADR: u8 = 0x82;
+/// Library header: first record of a library, padded to one page
+const LIBHDR: u8 = 0xF0;
+/// Segment definition (16-bit form)
+const SEGDEF: u8 = 0x98;
+/// `P` bit of a SEGDEF attribute byte: the segment is `USE32`
+const ACBP_USE32: u8 = 0x01;
+
 /// OMF record types we treat as valid for detection.
 const VALID_RECORD_TYPES: &[u8] = &[
     0x80, // THEADR
@@ -68,12 +80,36 @@ fn looks_like_malformed_intel_omf(data: &[u8]) -> bool {
     rec_len == data.len().saturating_sub(1)
 }
 
+/// Page size of an OMF library, which starts with a LIBHDR record padded
+/// to one page and keeps each module on a page boundary.
+fn library_page(data: &[u8]) -> Option<usize> {
+    if data.len() < 3 || data[0] != LIBHDR {
+        return None;
+    }
+    let page = u16::from_le_bytes([data[1], data[2]]) as usize + 3;
+    let first = *data.get(page)?;
+    (page.is_power_of_two() && page >= 16 && matches!(first, THEADR | LHEADR)).then_some(page)
+}
+
+/// Module name from a THEADR/LHEADR payload: a length-prefixed string.
+///
+/// `payload` excludes the trailing checksum byte.
+fn module_name(payload: &[u8]) -> Option<String> {
+    let (&len, rest) = payload.split_first()?;
+    let name = rest.get(..len as usize)?;
+    (!name.is_empty()).then(|| String::from_utf8_lossy(name).into_owned())
+}
+
 /// Detect OMF format.
 pub fn detect(data: &[u8]) -> bool {
     if data.len() < 4 {
         return false;
     }
 
+    if let Some(page) = library_page(data) {
+        return detect(&data[page..]);
+    }
+
     // Some IDA corpus samples are malformed Intel-style OMF where the first
     // COMDEF-like record advertises a payload exactly equal to file_size - 1.
     // Accept this narrow signature to avoid falling back to raw heuristics.
@@ -126,7 +162,22 @@ pub fn detect(data: &[u8]) -> bool {
 }
 
 /// Parse OMF file.
+///
+/// For a library, the first module is reported.
 pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
+    if let Some(page) = library_page(data) {
+        let mut result = parse_module(&data[page..])?;
+        result.metadata.notes[0] = format!("OMF library ({page}-byte pages)");
+        result.variant = result
+            .variant
+            .map(|v| Variant::new(format!("{} library", v.name)));
+        return Ok(result);
+    }
+    parse_module(data)
+}
+
+/// Parse one OMF object module.
+fn parse_module(data: &[u8]) -> Result<ClassificationResult> {
     if data.len() < 4 {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
@@ -141,6 +192,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let mut uses_32bit_records = false;
     let mut checksum_mismatch = 0usize;
     let mut malformed_first_record = false;
+    let mut name = None;
 
     while pos + 3 <= data.len() {
         let rec_type = data[pos];
@@ -169,6 +221,16 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
             checksum_mismatch += 1;
         }
 
+        // Payload between the length field and the checksum
+        let payload = &rec[3..rec.len() - 1];
+        match rec_type {
+            THEADR | LHEADR if name.is_none() => name = module_name(payload),
+            SEGDEF if payload.first().is_some_and(|acbp| acbp & ACBP_USE32 != 0) => {
+                uses_32bit_records = true;
+            }
+            _ => {}
+        }
+
         record_count += 1;
         if rec_type & 1 == 1 {
             uses_32bit_records = true;
@@ -189,9 +251,18 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let bitwidth = if uses_32bit_records { 32 } else { 16 };
     let mut notes = vec!["OMF object module".to_string()];
+    let mut custom = Vec::new();
+    if let Some(name) = name {
+        notes.push(format!("Module: {name}"));
+        custom.push(MetadataEntry::custom(
+            "omf_module",
+            MetadataValue::String(name),
+            "OMF Module",
+        ));
+    }
     notes.push(format!("Records: {}", record_count));
     if uses_32bit_records {
-        notes.push("Contains 32-bit record variants".to_string());
+        notes.push("Contains 32-bit record variants or USE32 segments".to_string());
     }
     if !has_modend {
         notes.push("No MODEND record found (possibly malformed/truncated)".to_string());
@@ -208,16 +279,17 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let metadata = ClassificationMetadata {
         notes,
+        custom,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, bitwidth, Endianness::Little, FileFormat::Omf);
-    result.variant = Variant::new(if uses_32bit_records {
+    result.variant = Some(Variant::new(if uses_32bit_records {
         "32-bit OMF"
     } else {
         "16-bit OMF"
-    });
+    }));
     result.metadata = metadata;
     Ok(result)
 }
@@ -255,6 +327,65 @@ mod tests {
         assert_eq!(result.format, FileFormat::Omf);
     }
 
+    fn module_metadata(result: &ClassificationResult) -> Option<String> {
+        result.metadata.custom.iter().find_map(|e| match &e.value {
+            MetadataValue::String(name) if e.label == "OMF Module" => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_parse_theadr_module_name() {
+        let mut data = make_record(THEADR, b"\x07hello.c");
+        data.extend(make_record(0x96, b"\x04CODE"));
+        data.extend(make_record(0x8A, &[0]));
+        assert!(detect(&data));
+        let result = parse(&data).unwrap();
+        assert_eq!(result.isa, Isa::X86);
+        assert_eq!(result.bitwidth, 16);
+        assert_eq!(module_metadata(&result).as_deref(), Some("hello.c"));
+        assert!(result.metadata.notes.iter().any(|n| n == "Module: hello.c"));
+
+        // A name longer than its record is ignored
+        let mut data = make_record(THEADR, b"\x40abc");
+        data.extend(make_record(0x8A, &[0]));
+        let result = parse(&data).unwrap();
+        assert_eq!(module_metadata(&result), None);
+    }
+
+    #[test]
+    fn test_use32_segdef() {
+        // ACBP: byte aligned, public, USE32
+        let mut data = make_record(THEADR, b"\x05start");
+        data.extend(make_record(SEGDEF, &[0x29, 0x00, 0x10, 0x02, 0x01, 0x01]));
+        data.extend(make_record(0x8A, &[0]));
+        let result = parse(&data).unwrap();
+        assert_eq!(result.bitwidth, 32);
+
+        // Same attributes without USE32, after the 10-byte THEADR
+        data[13] = 0x28;
+        assert_eq!(parse(&data).unwrap().bitwidth, 16);
+    }
+
+    #[test]
+    fn test_parse_library() {
+        let page = 16;
+        let mut data = make_record(LIBHDR, &[0; 12]);
+        assert_eq!(data.len(), page);
+        data.extend(make_record(THEADR, b"\x05crt0s"));
+        data.extend(make_record(0x8A, &[0]));
+        assert!(detect(&data));
+        let result = parse(&data).unwrap();
+        assert_eq!(result.format, FileFormat::Omf);
+        assert_eq!(module_metadata(&result).as_deref(), Some("crt0s"));
+        assert_eq!(result.metadata.notes[0], "OMF library (16-byte pages)");
+        assert_eq!(result.variant.unwrap().name, "16-bit OMF library");
+
+        // The first module must sit at the page boundary
+        data.insert(page, 0);
+        assert!(!detect(&data));
+    }
+
     #[test]
     fn test_reject_non_omf() {
         let data = b"\x7FELF\x02\x01\x01\x00";
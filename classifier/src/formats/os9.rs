@@ -129,7 +129,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     // Use M68k family as the closest legacy bucket for now.
     let mut result =
         ClassificationResult::from_format(Isa::M68k, 32, Endianness::Big, FileFormat::Os9);
-    result.variant = Variant::new("OS-9 module");
+    result.variant = Some(Variant::new("OS-9 module"));
     result.metadata = metadata;
     Ok(result)
 }
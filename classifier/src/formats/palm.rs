@@ -147,7 +147,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     };
 
     let mut result = ClassificationResult::from_format(isa, 32, endianness, FileFormat::PalmPdb);
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(variant_name));
     result.metadata = metadata;
     Ok(result)
 }
@@ -4,9 +4,10 @@
 //! modern architectures like ARM64, ARM64EC, and RISC-V.
 
 use crate::error::{ClassifierError, Result};
-use crate::formats::{read_u16, read_u32};
+use crate::formats::{dotnet, read_u16, read_u32};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, HardeningSummary, Isa,
+    MetadataEntry, MetadataValue, Variant,
 };
 
 /// PE machine type constants.
@@ -113,12 +114,220 @@ pub mod optional_magic {
     pub const ROM: u16 = 0x107;
 }
 
+/// Optional header `DllCharacteristics` flags.
+pub mod dll_characteristics {
+    /// ASLR with a 64-bit address space
+    pub const HIGH_ENTROPY_VA: u16 = 0x0020;
+    /// Image can be relocated at load time (ASLR)
+    pub const DYNAMIC_BASE: u16 = 0x0040;
+    /// Image is compatible with data execution prevention
+    pub const NX_COMPAT: u16 = 0x0100;
+    /// Image supports Control Flow Guard
+    pub const GUARD_CF: u16 = 0x4000;
+}
+
+/// Optional header `Subsystem` values.
+pub mod subsystem {
+    /// Device drivers and native system processes
+    pub const NATIVE: u16 = 1;
+    /// Windows graphical application
+    pub const WINDOWS_GUI: u16 = 2;
+    /// Windows console application
+    pub const WINDOWS_CUI: u16 = 3;
+    /// OS/2 console application
+    pub const OS2_CUI: u16 = 5;
+    /// POSIX console application
+    pub const POSIX_CUI: u16 = 7;
+    /// Native Win9x driver
+    pub const NATIVE_WINDOWS: u16 = 8;
+    /// Windows CE application
+    pub const WINDOWS_CE_GUI: u16 = 9;
+    /// EFI application
+    pub const EFI_APPLICATION: u16 = 10;
+    /// EFI driver with boot services
+    pub const EFI_BOOT_SERVICE_DRIVER: u16 = 11;
+    /// EFI driver with run-time services
+    pub const EFI_RUNTIME_DRIVER: u16 = 12;
+    /// EFI ROM image
+    pub const EFI_ROM: u16 = 13;
+    /// Xbox executable
+    pub const XBOX: u16 = 14;
+    /// Windows boot application
+    pub const WINDOWS_BOOT_APPLICATION: u16 = 16;
+}
+
+/// Offset of `MajorSubsystemVersion` in the optional header (PE32 and PE32+).
+const SUBSYSTEM_VERSION_OFFSET: usize = 48;
+
+/// Offset of `Subsystem` in the optional header (PE32 and PE32+).
+const SUBSYSTEM_OFFSET: usize = 68;
+
+/// Offset of `DllCharacteristics` in the optional header (PE32 and PE32+).
+const DLL_CHARACTERISTICS_OFFSET: usize = 70;
+
+/// Data directory index of the Authenticode certificate table.
+const SECURITY_DIRECTORY: usize = 4;
+
+/// Data directory index of the CLR runtime header.
+const CLR_DIRECTORY: usize = 14;
+
+/// Size of a section table entry.
+const SECTION_ENTRY_LEN: usize = 40;
+
+/// Map an RVA to a file offset through the section table at `table_off`.
+fn rva_to_offset(data: &[u8], table_off: usize, num_sections: u16, rva: u32) -> Option<usize> {
+    (0..usize::from(num_sections)).find_map(|i| {
+        let entry = table_off + i * SECTION_ENTRY_LEN;
+        let virtual_size = read_u32(data, entry + 8, true).ok()?;
+        let virtual_address = read_u32(data, entry + 12, true).ok()?;
+        let raw_size = read_u32(data, entry + 16, true).ok()?;
+        let raw_offset = read_u32(data, entry + 20, true).ok()?;
+        let delta = rva.checked_sub(virtual_address)?;
+        (delta < virtual_size.max(1) && delta < raw_size)
+            .then(|| raw_offset as usize + delta as usize)
+    })
+}
+
+/// File offset one past the last section's raw data.
+///
+/// The section table starts at `table_off`; entries past the end of the
+/// input are ignored.
+fn sections_extent(data: &[u8], table_off: usize, num_sections: u16) -> u64 {
+    (0..usize::from(num_sections))
+        .map_while(|i| {
+            let entry = table_off + i * SECTION_ENTRY_LEN;
+            let raw_size = read_u32(data, entry + 16, true).ok()?;
+            let raw_offset = read_u32(data, entry + 20, true).ok()?;
+            Some(u64::from(raw_offset) + u64::from(raw_size))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Section flag: the section contains executable code.
+const IMAGE_SCN_CNT_CODE: u32 = 0x0000_0020;
+
+/// Section flag: the section can be executed.
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+/// File ranges `(offset, size)` of the sections holding code, clamped to
+/// `data`.
+fn code_sections(data: &[u8], table_off: usize, num_sections: u16) -> Vec<(u64, u64)> {
+    let len = data.len() as u64;
+    (0..usize::from(num_sections))
+        .map_while(|i| {
+            let entry = table_off + i * SECTION_ENTRY_LEN;
+            let raw_size = read_u32(data, entry + 16, true).ok()?;
+            let raw_offset = read_u32(data, entry + 20, true).ok()?;
+            let characteristics = read_u32(data, entry + 36, true).ok()?;
+            Some((u64::from(raw_offset), u64::from(raw_size), characteristics))
+        })
+        .filter(|&(_, _, flags)| flags & (IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE) != 0)
+        .map(|(offset, size, _)| {
+            let start = offset.min(len);
+            (start, size.min(len - start))
+        })
+        .filter(|&(_, size)| size != 0)
+        .collect()
+}
+
+/// Read the CLR runtime header at `clr_rva` and describe its assembly.
+///
+/// Returns `None` when the header or metadata root cannot be reached.
+fn clr_metadata(
+    data: &[u8],
+    table_off: usize,
+    num_sections: u16,
+    clr_rva: u32,
+) -> Option<dotnet::ClrMetadata> {
+    let header = rva_to_offset(data, table_off, num_sections, clr_rva)?;
+    let metadata_rva = read_u32(data, header + 8, true).ok()?;
+    let metadata_size = read_u32(data, header + 12, true).ok()? as usize;
+    let root = rva_to_offset(data, table_off, num_sections, metadata_rva)?;
+    let end = root.checked_add(metadata_size)?.min(data.len());
+    dotnet::parse_metadata(data.get(root..end)?)
+}
+
+/// Metadata entries for a managed (.NET) image.
+fn clr_entries(clr: &dotnet::ClrMetadata) -> Vec<MetadataEntry> {
+    let mut entries = vec![MetadataEntry::custom(
+        "clr_runtime_version",
+        MetadataValue::String(clr.runtime_version.clone()),
+        "CLR Runtime",
+    )];
+    if let Some(assembly) = &clr.assembly {
+        if let Some(name) = &assembly.name {
+            entries.push(MetadataEntry::custom(
+                "assembly_name",
+                MetadataValue::String(name.clone()),
+                "Assembly Name",
+            ));
+        }
+        entries.push(MetadataEntry::custom(
+            "assembly_version",
+            MetadataValue::String(assembly.version_string()),
+            "Assembly Version",
+        ));
+        entries.push(MetadataEntry::custom(
+            "strong_named",
+            MetadataValue::Bool(assembly.strong_named),
+            "Strong-Named",
+        ));
+    }
+    entries
+}
+
+/// Summarize exploit mitigations from the optional header's
+/// `DllCharacteristics`.
+///
+/// Returns `None` when the image has no optional header that reaches the
+/// field.
+pub fn hardening(data: &[u8], pe_offset: u32) -> Option<HardeningSummary> {
+    let coff_off = (pe_offset as usize).checked_add(4)?;
+    let size_of_optional = usize::from(read_u16(data, coff_off + 16, true).ok()?);
+    if size_of_optional < DLL_CHARACTERISTICS_OFFSET + 2 {
+        return None;
+    }
+    let flags = read_u16(data, coff_off + 20 + DLL_CHARACTERISTICS_OFFSET, true).ok()?;
+    let has = |flag: u16| flags & flag != 0;
+
+    Some(
+        HardeningSummary {
+            pie: has(dll_characteristics::DYNAMIC_BASE),
+            nx: has(dll_characteristics::NX_COMPAT),
+            high_entropy_va: Some(has(dll_characteristics::HIGH_ENTROPY_VA)),
+            control_flow_guard: Some(has(dll_characteristics::GUARD_CF)),
+            ..Default::default()
+        }
+        .with_note(),
+    )
+}
+
+/// The optional header's `Subsystem` with its major and minor
+/// `SubsystemVersion`.
+///
+/// Returns `None` when the image has no optional header that reaches the
+/// field.
+pub fn subsystem(data: &[u8], pe_offset: u32) -> Option<(u16, u16, u16)> {
+    let coff_off = (pe_offset as usize).checked_add(4)?;
+    let size_of_optional = usize::from(read_u16(data, coff_off + 16, true).ok()?);
+    if size_of_optional < SUBSYSTEM_OFFSET + 2 {
+        return None;
+    }
+    let opt_off = coff_off + 20;
+    Some((
+        read_u16(data, opt_off + SUBSYSTEM_OFFSET, true).ok()?,
+        read_u16(data, opt_off + SUBSYSTEM_VERSION_OFFSET, true).ok()?,
+        read_u16(data, opt_off + SUBSYSTEM_VERSION_OFFSET + 2, true).ok()?,
+    ))
+}
+
 /// Parse PE/COFF file.
 pub fn parse(data: &[u8], pe_offset: u32) -> Result<ClassificationResult> {
     let pe_off = pe_offset as usize;
 
     // Verify PE signature
-    if pe_off + 4 > data.len() {
+    if pe_off.checked_add(4).map_or(true, |end| end > data.len()) {
         return Err(ClassifierError::TruncatedData {
             offset: pe_off,
             expected: 4,
@@ -155,9 +364,14 @@ pub fn parse(data: &[u8], pe_offset: u32) -> Result<ClassificationResult> {
     // Read optional header if present
     let mut entry_point = None;
     let mut is_pe32plus = false;
+    let mut clr = None;
+    let opt_off = coff_off + 20;
+    let table_off = opt_off + usize::from(size_of_optional);
+    // Headers, sections, and the certificate table (addressed by file offset)
+    let mut header_size = (table_off + usize::from(num_sections) * SECTION_ENTRY_LEN) as u64;
+    let mut described_size = header_size.max(sections_extent(data, table_off, num_sections));
 
     if size_of_optional > 0 {
-        let opt_off = coff_off + 20;
         if opt_off + 2 <= data.len() {
             let magic = read_u16(data, opt_off, true)?;
             is_pe32plus = magic == optional_magic::PE32PLUS;
@@ -168,6 +382,33 @@ pub fn parse(data: &[u8], pe_offset: u32) -> Result<ClassificationResult> {
                 entry_point = Some(ep as u64);
             }
         }
+
+        // SizeOfHeaders covers the headers rounded up to FileAlignment
+        if let Ok(size_of_headers) = read_u32(data, opt_off + 60, true) {
+            header_size = header_size.max(u64::from(size_of_headers));
+            described_size = described_size.max(header_size);
+        }
+
+        // Managed images point the CLR runtime header directory at metadata
+        let dirs_off = opt_off + if is_pe32plus { 112 } else { 96 };
+        let clr_entry = dirs_off + CLR_DIRECTORY * 8;
+        let dir_count = read_u32(data, dirs_off - 4, true).unwrap_or(0) as usize;
+        if dir_count > CLR_DIRECTORY && clr_entry + 8 <= table_off {
+            let clr_rva = read_u32(data, clr_entry, true).unwrap_or(0);
+            if clr_rva != 0 {
+                clr = Some(clr_metadata(data, table_off, num_sections, clr_rva));
+            }
+        }
+
+        // The certificate table is the one directory given as a file offset
+        let security_entry = dirs_off + SECURITY_DIRECTORY * 8;
+        if dir_count > SECURITY_DIRECTORY && security_entry + 8 <= table_off {
+            let offset = read_u32(data, security_entry, true).unwrap_or(0);
+            let size = read_u32(data, security_entry + 4, true).unwrap_or(0);
+            if offset != 0 {
+                described_size = described_size.max(u64::from(offset) + u64::from(size));
+            }
+        }
     }
 
     // Determine actual bitwidth from PE32+
@@ -178,13 +419,10 @@ pub fn parse(data: &[u8], pe_offset: u32) -> Result<ClassificationResult> {
     };
 
     // Build variant
-    let variant = match variant_note {
-        Some(note) => Variant::new(note),
-        None => Variant::default(),
-    };
+    let variant = variant_note.map(Variant::new);
 
     // Build metadata
-    let metadata = ClassificationMetadata {
+    let mut metadata = ClassificationMetadata {
         entry_point,
         section_count: Some(num_sections as usize),
         raw_machine: Some(machine as u32),
@@ -193,8 +431,21 @@ pub fn parse(data: &[u8], pe_offset: u32) -> Result<ClassificationResult> {
         } else {
             vec!["PE32 format".to_string()]
         },
+        header_size: Some(header_size),
+        described_size: Some(described_size),
+        code_ranges: code_sections(data, table_off, num_sections),
         ..Default::default()
     };
+    match clr {
+        Some(Some(clr)) => {
+            metadata.notes.push(".NET CLR assembly".to_string());
+            metadata.custom = clr_entries(&clr);
+        }
+        Some(None) => metadata
+            .warnings
+            .push("CLR runtime header present but metadata is unreadable".to_string()),
+        None => {}
+    }
 
     let mut result =
         ClassificationResult::from_format(isa, actual_bitwidth, endianness, FileFormat::Pe);
@@ -283,6 +534,66 @@ mod tests {
         data
     }
 
+    #[test]
+    fn test_described_size() {
+        let mut data = make_pe_header(machine::AMD64);
+        data.resize(0x400, 0);
+        let opt_off = 0x98;
+        data[opt_off + 60..opt_off + 64].copy_from_slice(&0x400u32.to_le_bytes()); // SizeOfHeaders
+        data[opt_off + 108..opt_off + 112].copy_from_slice(&16u32.to_le_bytes());
+
+        // .text, .data, .reloc laid out back to back after the headers
+        let table_off = opt_off + 0xF0;
+        for (i, (offset, size)) in [(0x400u32, 0x200u32), (0x600, 0x200), (0x800, 0x100)]
+            .into_iter()
+            .enumerate()
+        {
+            let entry = table_off + i * SECTION_ENTRY_LEN;
+            data[entry + 16..entry + 20].copy_from_slice(&size.to_le_bytes());
+            data[entry + 20..entry + 24].copy_from_slice(&offset.to_le_bytes());
+        }
+
+        let result = parse(&data, 0x80).unwrap();
+        assert_eq!(result.metadata.header_size, Some(0x400));
+        assert_eq!(result.metadata.described_size, Some(0x900));
+
+        // An Authenticode blob appended after the last section
+        let security = opt_off + 112 + SECURITY_DIRECTORY * 8;
+        data[security..security + 4].copy_from_slice(&0x900u32.to_le_bytes());
+        data[security + 4..security + 8].copy_from_slice(&0x180u32.to_le_bytes());
+        let result = parse(&data, 0x80).unwrap();
+        assert_eq!(result.metadata.described_size, Some(0xA80));
+    }
+
+    #[test]
+    fn test_code_ranges() {
+        let mut data = make_pe_header(machine::AMD64);
+        data.resize(0x900, 0);
+        // .text (code, execute), .rdata, and an executable .stub cut short
+        let table_off = 0x98 + 0xF0;
+        let sections = [
+            (
+                0x400u32,
+                0x200u32,
+                IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE,
+            ),
+            (0x600, 0x200, 0x4000_0040),
+            (0x800, 0x200, IMAGE_SCN_MEM_EXECUTE),
+        ];
+        for (i, (offset, size, flags)) in sections.into_iter().enumerate() {
+            let entry = table_off + i * SECTION_ENTRY_LEN;
+            data[entry + 16..entry + 20].copy_from_slice(&size.to_le_bytes());
+            data[entry + 20..entry + 24].copy_from_slice(&offset.to_le_bytes());
+            data[entry + 36..entry + 40].copy_from_slice(&flags.to_le_bytes());
+        }
+
+        let result = parse(&data, 0x80).unwrap();
+        assert_eq!(
+            result.metadata.code_ranges,
+            [(0x400, 0x200), (0x800, 0x100)]
+        );
+    }
+
     #[test]
     fn test_parse_x64_pe() {
         let data = make_pe_header(machine::AMD64);
@@ -304,7 +615,7 @@ mod tests {
         let data = make_pe_header(machine::ARM64EC);
         let result = parse(&data, 0x80).unwrap();
         assert_eq!(result.isa, Isa::AArch64);
-        assert!(result.variant.name.contains("ARM64EC"));
+        assert!(result.variant_name().unwrap().contains("ARM64EC"));
     }
 
     #[test]
@@ -324,4 +635,54 @@ mod tests {
         assert_eq!(machine_to_isa(machine::RISCV64).0, Isa::RiscV64);
         assert_eq!(machine_to_isa(machine::LOONGARCH64).0, Isa::LoongArch64);
     }
+
+    #[test]
+    fn test_hardening_dll_characteristics() {
+        let with_flags = |flags: u16| {
+            let mut data = make_pe_header(machine::AMD64);
+            let off = 0x98 + DLL_CHARACTERISTICS_OFFSET;
+            data[off..off + 2].copy_from_slice(&flags.to_le_bytes());
+            hardening(&data, 0x80).unwrap()
+        };
+
+        let h = with_flags(
+            dll_characteristics::DYNAMIC_BASE
+                | dll_characteristics::HIGH_ENTROPY_VA
+                | dll_characteristics::NX_COMPAT
+                | dll_characteristics::GUARD_CF,
+        );
+        assert!(h.pie && h.nx);
+        assert_eq!(h.high_entropy_va, Some(true));
+        assert_eq!(h.control_flow_guard, Some(true));
+        assert_eq!(h.relro, None);
+        assert_eq!(h.note, "PIE, NX, high-entropy VA, CFG");
+
+        let h = with_flags(dll_characteristics::NX_COMPAT);
+        assert!(!h.pie && h.nx);
+        assert_eq!(h.control_flow_guard, Some(false));
+
+        let h = with_flags(dll_characteristics::DYNAMIC_BASE);
+        assert!(h.pie && !h.nx);
+        assert_eq!(h.high_entropy_va, Some(false));
+
+        // No optional header, nothing to report
+        let mut data = make_pe_header(machine::I386);
+        data[0x84 + 16] = 0;
+        assert_eq!(hardening(&data, 0x80), None);
+    }
+
+    #[test]
+    fn test_subsystem() {
+        let mut data = make_pe_header(machine::AMD64);
+        data[0x98 + SUBSYSTEM_OFFSET] = subsystem::EFI_APPLICATION as u8;
+        data[0x98 + SUBSYSTEM_VERSION_OFFSET] = 6;
+        data[0x98 + SUBSYSTEM_VERSION_OFFSET + 2] = 2;
+        assert_eq!(
+            self::subsystem(&data, 0x80),
+            Some((subsystem::EFI_APPLICATION, 6, 2))
+        );
+
+        data[0x84 + 16] = 0;
+        assert_eq!(self::subsystem(&data, 0x80), None);
+    }
 }
@@ -128,13 +128,14 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         code_size: if code_size > 0 { Some(code_size) } else { None },
         section_count: Some(section_count as usize),
+        raw_machine: Some(architecture),
         notes,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(isa, bitwidth, Endianness::Big, FileFormat::Pef);
-    result.variant = Variant::new(arch_name);
+    result.variant = Some(Variant::new(arch_name));
     result.metadata = metadata;
 
     Ok(result)
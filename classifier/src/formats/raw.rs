@@ -5,7 +5,7 @@
 
 use crate::error::Result;
 use crate::heuristics;
-use crate::types::{ClassificationResult, ClassifierOptions};
+use crate::types::{ClassificationResult, ClassifierOptions, Endianness, Isa};
 
 /// Analyze raw binary data to identify the ISA.
 ///
@@ -23,6 +23,239 @@ pub fn analyze_with_options(
     heuristics::analyze(data, options)
 }
 
+/// Bytes sampled by [`text_screen`].
+pub const TEXT_SCREEN_WINDOW: usize = 64 * 1024;
+
+/// Inputs shorter than this are never screened as text.
+const TEXT_SCREEN_MIN_LEN: usize = 64;
+
+/// Minimum fraction of printable characters for text.
+const TEXT_MIN_PRINTABLE: f64 = 0.97;
+
+/// Minimum fraction of spaces and line breaks for text.
+///
+/// Alphanumeric shellcode and other small-alphabet code is fully printable
+/// but has almost no whitespace; prose, source and config files do.
+const TEXT_MIN_WHITESPACE: f64 = 0.05;
+
+/// Unicode encoding announced by a byte-order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextEncoding {
+    /// UTF-8 with the `EF BB BF` signature
+    Utf8,
+    /// UTF-16 little-endian (`FF FE`)
+    Utf16Le,
+    /// UTF-16 big-endian (`FE FF`)
+    Utf16Be,
+    /// UTF-32 little-endian (`FF FE 00 00`)
+    Utf32Le,
+    /// UTF-32 big-endian (`00 00 FE FF`)
+    Utf32Be,
+}
+
+impl TextEncoding {
+    /// Encoding name, e.g. "UTF-16LE".
+    pub fn name(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Utf32Le => "UTF-32LE",
+            TextEncoding::Utf32Be => "UTF-32BE",
+        }
+    }
+
+    /// The byte-order mark itself.
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            TextEncoding::Utf16Le => &[0xFF, 0xFE],
+            TextEncoding::Utf16Be => &[0xFE, 0xFF],
+            TextEncoding::Utf32Le => &[0xFF, 0xFE, 0x00, 0x00],
+            TextEncoding::Utf32Be => &[0x00, 0x00, 0xFE, 0xFF],
+        }
+    }
+}
+
+/// Identify a leading byte-order mark.
+///
+/// `FF FE 00 00` is read as UTF-32LE rather than UTF-16LE text starting
+/// with U+0000, which no real document does.
+pub fn detect_bom(data: &[u8]) -> Option<TextEncoding> {
+    [
+        TextEncoding::Utf32Le,
+        TextEncoding::Utf32Be,
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+    ]
+    .into_iter()
+    .find(|enc| data.starts_with(enc.bom()))
+}
+
+/// Result of the plain-text pre-screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextScreen {
+    /// Fraction of printable characters in the sampled window.
+    pub printable_ratio: f64,
+    /// Whether the sampled window is valid UTF-8.
+    pub utf8: bool,
+    /// Encoding announced by a leading byte-order mark, if any.
+    pub bom: Option<TextEncoding>,
+}
+
+/// Cheap check for inputs that are overwhelmingly plain text.
+///
+/// Only the first [`TEXT_SCREEN_WINDOW`] bytes are sampled. Any NUL byte
+/// rejects the input, as does a low whitespace ratio. Hex and S-record
+/// files have their own format detection and must be checked before this.
+pub fn text_screen(data: &[u8]) -> Option<TextScreen> {
+    if data.len() < TEXT_SCREEN_MIN_LEN {
+        return None;
+    }
+    let window = &data[..data.len().min(TEXT_SCREEN_WINDOW)];
+    if memchr::memchr(0, window).is_some() {
+        return None;
+    }
+
+    // A multi-byte character may straddle the end of the window.
+    let utf8 = match std::str::from_utf8(window) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none() && window.len() - e.valid_up_to() < 4,
+    };
+
+    let mut printable = 0usize;
+    let mut whitespace = 0usize;
+    for &b in window {
+        match b {
+            b' ' | b'\n' => {
+                printable += 1;
+                whitespace += 1;
+            }
+            b'\t' | b'\r' | 0x0C | 0x21..=0x7E => printable += 1,
+            0x80..=0xFF if utf8 => printable += 1,
+            _ => {}
+        }
+    }
+
+    let total = window.len() as f64;
+    let printable_ratio = printable as f64 / total;
+    if printable_ratio < TEXT_MIN_PRINTABLE || (whitespace as f64 / total) < TEXT_MIN_WHITESPACE {
+        return None;
+    }
+    Some(TextScreen {
+        printable_ratio,
+        utf8,
+        bom: None,
+    })
+}
+
+/// Check for Unicode text introduced by a byte-order mark.
+///
+/// Runs before format detection: a UTF-16 `FF FE` or `FE FF` would
+/// otherwise read as an endianness marker to the weaker heuristics. The
+/// first [`TEXT_SCREEN_WINDOW`] bytes after the mark are decoded; a U+0000,
+/// an invalid sequence or too few printable characters rejects the input,
+/// so a binary that merely starts with the same bytes still goes through
+/// normal detection. No whitespace ratio is required, since CJK prose has
+/// little of it.
+pub fn bom_screen(data: &[u8]) -> Option<TextScreen> {
+    let encoding = detect_bom(data)?;
+    let window = &data[..data.len().min(TEXT_SCREEN_WINDOW)];
+    let body = &window[encoding.bom().len()..];
+
+    let chars: Vec<Option<char>> = match encoding {
+        TextEncoding::Utf8 => {
+            let valid = match std::str::from_utf8(body) {
+                Ok(text) => text,
+                // A multi-byte character may straddle the end of the window.
+                Err(e) if e.error_len().is_none() && data.len() > window.len() => {
+                    std::str::from_utf8(&body[..e.valid_up_to()]).ok()?
+                }
+                Err(_) => return None,
+            };
+            valid.chars().map(Some).collect()
+        }
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let unit = |pair: &[u8]| {
+                let pair = [pair[0], pair[1]];
+                if encoding == TextEncoding::Utf16Le {
+                    u16::from_le_bytes(pair)
+                } else {
+                    u16::from_be_bytes(pair)
+                }
+            };
+            let mut units: Vec<u16> = body.chunks_exact(2).map(unit).collect();
+            // A surrogate pair may straddle the end of the window: carry
+            // the low half in from past the edge.
+            let end = encoding.bom().len() + units.len() * 2;
+            if matches!(units.last(), Some(0xD800..=0xDBFF)) {
+                if let Some(next) = data.get(end..end + 2) {
+                    units.push(unit(next));
+                }
+            }
+            char::decode_utf16(units)
+                .map(std::result::Result::ok)
+                .collect()
+        }
+        TextEncoding::Utf32Le | TextEncoding::Utf32Be => body
+            .chunks_exact(4)
+            .map(|quad| {
+                let quad = [quad[0], quad[1], quad[2], quad[3]];
+                char::from_u32(if encoding == TextEncoding::Utf32Le {
+                    u32::from_le_bytes(quad)
+                } else {
+                    u32::from_be_bytes(quad)
+                })
+            })
+            .collect(),
+    };
+
+    let mut printable = 0usize;
+    for c in &chars {
+        match c {
+            None | Some('\0') => return None,
+            Some('\t' | '\n' | '\r' | '\x0C') => printable += 1,
+            Some(c) if !c.is_control() => printable += 1,
+            Some(_) => {}
+        }
+    }
+
+    let printable_ratio = if chars.is_empty() {
+        1.0
+    } else {
+        printable as f64 / chars.len() as f64
+    };
+    if printable_ratio < TEXT_MIN_PRINTABLE {
+        return None;
+    }
+    Some(TextScreen {
+        printable_ratio,
+        utf8: encoding == TextEncoding::Utf8,
+        bom: Some(encoding),
+    })
+}
+
+/// Build the result reported for inputs rejected by [`text_screen`].
+pub fn text_result(screen: &TextScreen) -> ClassificationResult {
+    let mut result =
+        ClassificationResult::from_heuristics(Isa::Unknown(0), 0, Endianness::Little, 0.0);
+    let note = match screen.bom {
+        Some(encoding) => format!(
+            "Text data ({} with byte-order mark, {:.1}% printable); not a binary",
+            encoding.name(),
+            screen.printable_ratio * 100.0
+        ),
+        None => format!(
+            "Text data ({}{:.1}% printable, no NUL bytes); not a binary",
+            if screen.utf8 { "UTF-8, " } else { "" },
+            screen.printable_ratio * 100.0
+        ),
+    };
+    result.metadata.notes.push(note);
+    result
+}
+
 /// Quick check if data might contain code for a specific ISA.
 ///
 /// This is a fast preliminary check before full analysis.
@@ -205,4 +438,118 @@ mod tests {
         let data = [0x13, 0x00, 0x00, 0x00];
         assert!(quick_check_isa(&data, Isa::RiscV64));
     }
+
+    #[test]
+    fn test_text_screen_utf8() {
+        let text = "Grüße aus Köln! This is a README file.\n\nIt has prose, \
+                    punctuation and a few non-ASCII characters: naïve café.\n"
+            .repeat(4);
+        let screen = text_screen(text.as_bytes()).expect("text");
+        assert!(screen.utf8);
+        assert!(screen.printable_ratio > 0.99);
+
+        let result = text_result(&screen);
+        assert_eq!(result.isa, Isa::Unknown(0));
+        assert!(result.metadata.notes[0].contains("Text data"));
+    }
+
+    #[test]
+    fn test_text_screen_borderline() {
+        // Alphanumeric x86 shellcode: fully printable, no whitespace.
+        let shellcode = b"PYIIIIIIIIIIQZVTX30VX4AP0A3HH0A00ABAABTAAQ2AB2BB0BBXP8ACJJIKLJHOTEPGPC0LKQUGLLKCLC5D8EQJOLKPOB8LKQOGPEQJKPILKFTLKEQJNP1IPLYNLLDIPD4C7IQHJDMC1HBJKKDGKPTQ4GXCEJELKQOFDEQJKCVLKDLPKLKQOELEQJKDCFLLKMYBLFDELE1HCP1IKE4LKG3P0LKG0DLLKBPELNMLKG0C8QNCXLNPNDNJLF0KOHVE6F3CVCXP3GBE8D7D3FRQOF4KOHPE8HKZMKLGKF0KOHVQOK9M5E6K1JMC8C2F5CZDBKOHPCXN9C9KENMPWKOIFPSPSPSF3QCG3PSPCKOHPE6BHB1QLE6PSK9M1MEBHOTDZD0IWF7KOIFBJDPPQF5KOHPCXOTNMFNM9F7KOIFPSF5KOHPE8M5G9LFG9QGKOHVPPQDQDF5KOHPOCE8KWCIHFBYQGKOHVF5KOHPBFBJE4E6CXBCBMK9M5CZF0G9Q9HLMYM7BJG4MYM2FQIPKCNJKNQRFMKNQRFLMCLMBZGHNKNKNKE8CBKNNSDVKOCEQTKOIFQKPWF2F1F1F1CZEQF1F1PUF1KOHPE8NMN9C5HNQCKOIFCZC0F7KOIFF0PWKOIFPPK8F7M9JFCIPWKOHUPTKOHUGPLKG7E4LKD4KPPQKTLKQDMPMQKPLKP9GLPSPRPBPXKOHUF2KOIEK9E7KOIE";
+        assert!(text_screen(shellcode).is_none());
+
+        // Mostly text with embedded NULs is binary.
+        let mut mixed = b"Hello world, this is a string table entry.\n".repeat(4);
+        mixed[40] = 0;
+        assert!(text_screen(&mixed).is_none());
+
+        // Too short to judge.
+        assert!(text_screen(b"short text\n").is_none());
+    }
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        let mut bytes = if big_endian {
+            vec![0xFE, 0xFF]
+        } else {
+            vec![0xFF, 0xFE]
+        };
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            });
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_bom_screen_utf16() {
+        let text = "Release notes\r\n\r\n- Fixed the bootloader timeout (Köln build).\r\n";
+
+        let le = utf16(text, false);
+        let screen = bom_screen(&le).expect("UTF-16LE text");
+        assert_eq!(screen.bom, Some(TextEncoding::Utf16Le));
+        assert!(!screen.utf8);
+        // Every other byte is NUL, so the plain screen cannot catch it
+        assert!(text_screen(&le).is_none());
+
+        let be = utf16(text, true);
+        let screen = bom_screen(&be).expect("UTF-16BE text");
+        assert_eq!(screen.bom, Some(TextEncoding::Utf16Be));
+        let result = text_result(&screen);
+        assert_eq!(result.isa, Isa::Unknown(0));
+        assert!(result.metadata.notes[0].contains("UTF-16BE with byte-order mark"));
+
+        // Short files still count once the mark is there
+        assert!(bom_screen(&utf16("hi", false)).is_some());
+    }
+
+    #[test]
+    fn test_bom_screen_utf16_pair_straddles_window() {
+        // Pad so the high half of U+1F600 is the last unit in the window
+        let pad = (TEXT_SCREEN_WINDOW - 2) / 2 - 1;
+        let text = format!(
+            "{}\u{1F600} tail\n",
+            "a b\n".repeat(pad / 4 + 1)[..pad].to_owned()
+        );
+        for big_endian in [false, true] {
+            let data = utf16(&text, big_endian);
+            let edge = TEXT_SCREEN_WINDOW - 2;
+            let high = [data[edge], data[edge + 1]];
+            let high = if big_endian {
+                u16::from_be_bytes(high)
+            } else {
+                u16::from_le_bytes(high)
+            };
+            assert_eq!(high, 0xD83D);
+            let screen = bom_screen(&data).expect("pair carried across the window");
+            assert!(screen.printable_ratio > 0.99);
+        }
+    }
+
+    #[test]
+    fn test_bom_screen_rejects_binary() {
+        assert_eq!(
+            detect_bom(&[0xFF, 0xFE, 0x00, 0x00, 0x41]),
+            Some(TextEncoding::Utf32Le)
+        );
+        assert_eq!(detect_bom(b"\xEF\xBB\xBFhi"), Some(TextEncoding::Utf8));
+        assert_eq!(detect_bom(b"plain"), None);
+
+        // Code after an FF FE prefix decodes to U+0000 and controls
+        let mut code = vec![0xFF, 0xFE];
+        code.extend_from_slice(&[
+            0x1F, 0x20, 0x03, 0xD5, 0xC0, 0x03, 0x5F, 0xD6, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        assert!(bom_screen(&code).is_none());
+
+        // Unpaired surrogate
+        assert!(bom_screen(&[0xFE, 0xFF, 0xD8, 0x00, 0x00, 0x41]).is_none());
+        // A high surrogate ending the input is still unpaired
+        assert!(bom_screen(&[0xFE, 0xFF, 0x00, 0x41, 0xD8, 0x3D]).is_none());
+        // Invalid UTF-8 after the signature
+        assert!(bom_screen(b"\xEF\xBB\xBFok\xFF\xFE").is_none());
+    }
 }
@@ -1,111 +1,224 @@
 //! HP-UX SOM (System Object Model) parser.
 //!
 //! SOM is the native executable/object format for PA-RISC HP-UX systems.
+//! Every file starts with a fixed 128-byte big-endian header: a system ID
+//! naming the PA-RISC version the code targets, a magic number giving the
+//! file type, a header version ID and the locations of the space,
+//! subspace, symbol and fixup tables. The last header word is the XOR of
+//! the 31 words before it.
 
 use crate::error::{ClassifierError, Result};
 use crate::formats::{read_u16, read_u32};
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
+/// SOM header size.
+pub const SOM_HEADER_SIZE: usize = 0x80;
+
 /// Minimum SOM header size we rely on.
-pub const SOM_HEADER_MIN_SIZE: usize = 0x60;
+pub const SOM_HEADER_MIN_SIZE: usize = SOM_HEADER_SIZE;
 
 /// Common SOM magic values (big-endian).
 pub mod magic {
+    /// Relocatable object
+    pub const RELOC_MAGIC: u16 = 0x0106;
     pub const EXEC_MAGIC: u16 = 0x0107;
     pub const SHARE_MAGIC: u16 = 0x0108;
     pub const DEMAND_MAGIC: u16 = 0x010B;
     pub const DL_MAGIC: u16 = 0x010D;
+    /// Shared library
+    pub const SHL_MAGIC: u16 = 0x010E;
 }
 
 /// Known HP-UX system IDs for PA-RISC.
 pub mod system_id {
-    pub const HP9000S700: u16 = 0x020B;
-    pub const HP9000S800: u16 = 0x0210;
+    /// PA-RISC 1.0
+    pub const PA_RISC_1_0: u16 = 0x020B;
+    /// PA-RISC 1.1
+    pub const PA_RISC_1_1: u16 = 0x0210;
+    /// PA-RISC 1.2
+    pub const PA_RISC_1_2: u16 = 0x0211;
+    /// PA-RISC 2.0
+    pub const PA_RISC_2_0: u16 = 0x0214;
+    /// Earlier name of [`PA_RISC_1_0`]
+    pub const HP9000S700: u16 = PA_RISC_1_0;
+    /// Earlier name of [`PA_RISC_1_1`]
+    pub const HP9000S800: u16 = PA_RISC_1_1;
+}
+
+/// Header version IDs (`VERSION_ID` and `NEW_VERSION_ID` in `<filehdr.h>`).
+pub mod version_id {
+    /// Original SOM header
+    pub const VERSION_ID: u32 = 85_082_112;
+    /// SOM header with the extended auxiliary headers
+    pub const NEW_VERSION_ID: u32 = 87_102_412;
+}
+
+/// Header field offsets.
+mod offset {
+    pub const SYSTEM_ID: usize = 0x00;
+    pub const MAGIC: usize = 0x02;
+    pub const VERSION_ID: usize = 0x04;
+    pub const ENTRY_OFFSET: usize = 0x18;
+    pub const SOM_LENGTH: usize = 0x24;
+    pub const SPACE_LOCATION: usize = 0x2C;
+    pub const SPACE_TOTAL: usize = 0x30;
+    pub const SUBSPACE_LOCATION: usize = 0x34;
+    pub const SUBSPACE_TOTAL: usize = 0x38;
+    pub const SYMBOL_TOTAL: usize = 0x60;
+    pub const CHECKSUM: usize = 0x7C;
 }
 
-#[inline]
-fn is_valid_magic(m: u16) -> bool {
-    matches!(
-        m,
-        magic::EXEC_MAGIC | magic::SHARE_MAGIC | magic::DEMAND_MAGIC | magic::DL_MAGIC
-    )
+/// PA-RISC architecture version named by a system ID.
+fn pa_risc_version(sid: u16) -> Option<&'static str> {
+    match sid {
+        system_id::PA_RISC_1_0 => Some("PA-RISC 1.0"),
+        system_id::PA_RISC_1_1 => Some("PA-RISC 1.1"),
+        system_id::PA_RISC_1_2 => Some("PA-RISC 1.2"),
+        system_id::PA_RISC_2_0 => Some("PA-RISC 2.0"),
+        _ => None,
+    }
+}
+
+/// File type named by a magic number.
+fn file_type(mag: u16) -> Option<&'static str> {
+    match mag {
+        magic::RELOC_MAGIC => Some("Relocatable object"),
+        magic::EXEC_MAGIC => Some("Executable"),
+        magic::SHARE_MAGIC => Some("Shared executable"),
+        magic::DEMAND_MAGIC => Some("Demand-load executable"),
+        magic::DL_MAGIC => Some("Dynamic-load library"),
+        magic::SHL_MAGIC => Some("Shared library"),
+        _ => None,
+    }
+}
+
+fn be32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// XOR of the header words before the checksum.
+fn header_checksum(data: &[u8]) -> u32 {
+    (0..offset::CHECKSUM)
+        .step_by(4)
+        .fold(0, |sum, offset| sum ^ be32(data, offset))
 }
 
 /// Detect SOM format.
+///
+/// Requires a PA-RISC system ID, a known file type, a known header version
+/// and space/subspace tables that lie inside the declared SOM length.
 pub fn detect(data: &[u8]) -> bool {
-    if data.len() < SOM_HEADER_MIN_SIZE {
+    if data.len() < SOM_HEADER_SIZE {
         return false;
     }
 
     let sid = u16::from_be_bytes([data[0], data[1]]);
     let mag = u16::from_be_bytes([data[2], data[3]]);
-
-    if sid != system_id::HP9000S700 && sid != system_id::HP9000S800 {
+    if pa_risc_version(sid).is_none() || file_type(mag).is_none() {
         return false;
     }
-    if !is_valid_magic(mag) {
+    if !matches!(
+        be32(data, offset::VERSION_ID),
+        version_id::VERSION_ID | version_id::NEW_VERSION_ID
+    ) {
         return false;
     }
 
-    let som_len = u32::from_be_bytes([data[0x20], data[0x21], data[0x22], data[0x23]]) as usize;
-    if som_len == 0 {
+    let som_length = u64::from(be32(data, offset::SOM_LENGTH));
+    if som_length < SOM_HEADER_SIZE as u64 {
         return false;
     }
-
-    true
+    let table_fits = |location: usize| {
+        let location = u64::from(be32(data, location));
+        location == 0 || (SOM_HEADER_SIZE as u64..som_length).contains(&location)
+    };
+    table_fits(offset::SPACE_LOCATION) && table_fits(offset::SUBSPACE_LOCATION)
 }
 
 /// Parse SOM file.
 pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
-    if data.len() < SOM_HEADER_MIN_SIZE {
+    if data.len() < SOM_HEADER_SIZE {
         return Err(ClassifierError::TruncatedData {
             offset: 0,
-            expected: SOM_HEADER_MIN_SIZE,
+            expected: SOM_HEADER_SIZE,
             actual: data.len(),
         });
     }
 
-    let sid = read_u16(data, 0, false)?;
-    let mag = read_u16(data, 2, false)?;
-    let version_id = read_u32(data, 4, false)?;
-    let entry_offset = read_u32(data, 0x14, false)?;
-    let som_length = read_u32(data, 0x20, false)?;
-    let symbol_total = read_u32(data, 0x5C, false).unwrap_or(0);
-
-    let variant_name = match mag {
-        magic::EXEC_MAGIC => "Executable",
-        magic::SHARE_MAGIC => "Shared library",
-        magic::DEMAND_MAGIC => "Demand-load executable",
-        magic::DL_MAGIC => "Dynamic-load library",
-        _ => "Unknown",
-    };
+    let sid = read_u16(data, offset::SYSTEM_ID, false)?;
+    let mag = read_u16(data, offset::MAGIC, false)?;
+    let version = read_u32(data, offset::VERSION_ID, false)?;
+    let entry_offset = read_u32(data, offset::ENTRY_OFFSET, false)?;
+    let som_length = read_u32(data, offset::SOM_LENGTH, false)?;
+    let space_total = read_u32(data, offset::SPACE_TOTAL, false)?;
+    let subspace_total = read_u32(data, offset::SUBSPACE_TOTAL, false)?;
+    let symbol_total = read_u32(data, offset::SYMBOL_TOTAL, false)?;
+    let checksum = read_u32(data, offset::CHECKSUM, false)?;
+
+    let pa_version = pa_risc_version(sid).unwrap_or("PA-RISC");
+    let type_name = file_type(mag).unwrap_or("Unknown");
 
     let mut notes = vec!["HP-UX SOM container".to_string()];
-    notes.push(format!("System ID: 0x{sid:04X}"));
-    notes.push(format!("Magic: 0x{mag:04X} ({variant_name})"));
-    notes.push(format!("Version ID: 0x{version_id:08X}"));
-    if entry_offset != 0 {
-        notes.push(format!("Entry offset: 0x{entry_offset:08X}"));
-    }
+    notes.push(format!("System ID: 0x{sid:04X} ({pa_version})"));
+    notes.push(format!("Magic: 0x{mag:04X} ({type_name})"));
+    notes.push(format!("Version ID: {version}"));
     if som_length != 0 {
         notes.push(format!("Declared length: {som_length} bytes"));
     }
 
+    let mut warnings = Vec::new();
+    if checksum != 0 && checksum != header_checksum(data) {
+        warnings.push(format!(
+            "SOM header checksum 0x{checksum:08X} does not match"
+        ));
+    }
+    if som_length as usize > data.len() {
+        warnings.push(format!(
+            "SOM length {som_length} exceeds the {} bytes present",
+            data.len()
+        ));
+    }
+
+    let custom = vec![
+        MetadataEntry::custom(
+            "som_type",
+            MetadataValue::String(type_name.to_string()),
+            "SOM File Type",
+        ),
+        MetadataEntry::custom(
+            "spaces",
+            MetadataValue::Integer(u64::from(space_total)),
+            "Spaces",
+        ),
+        MetadataEntry::custom(
+            "subspaces",
+            MetadataValue::Integer(u64::from(subspace_total)),
+            "Subspaces",
+        ),
+    ];
+
     let metadata = ClassificationMetadata {
-        symbol_count: if symbol_total > 0 {
-            Some(symbol_total as usize)
-        } else {
-            None
-        },
+        entry_point: (entry_offset != 0).then_some(u64::from(entry_offset)),
+        symbol_count: (symbol_total > 0).then_some(symbol_total as usize),
+        raw_machine: Some(u32::from(sid)),
+        header_size: Some(SOM_HEADER_SIZE as u64),
         notes,
+        warnings,
+        custom,
         ..Default::default()
     };
 
     let mut result =
         ClassificationResult::from_format(Isa::Parisc, 32, Endianness::Big, FileFormat::Som);
-    result.variant = Variant::new(variant_name);
+    result.variant = Some(Variant::new(pa_version));
     result.metadata = metadata;
     Ok(result)
 }
@@ -114,28 +227,85 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 mod tests {
     use super::*;
 
-    fn make_som_header(magic_value: u16) -> Vec<u8> {
-        let mut data = vec![0u8; SOM_HEADER_MIN_SIZE];
-        data[0..2].copy_from_slice(&system_id::HP9000S800.to_be_bytes());
+    /// Header of a PA-RISC SOM file with its space and subspace tables
+    /// right behind it.
+    fn make_som_header(sid: u16, magic_value: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 0x200];
+        let mut put = |offset: usize, value: u32| {
+            data[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+        };
+        put(offset::VERSION_ID, version_id::NEW_VERSION_ID);
+        put(offset::ENTRY_OFFSET, 0x1000);
+        put(offset::SOM_LENGTH, 0x200);
+        put(offset::SPACE_LOCATION, 0x80);
+        put(offset::SPACE_TOTAL, 2);
+        put(offset::SUBSPACE_LOCATION, 0xC0);
+        put(offset::SUBSPACE_TOTAL, 5);
+        put(offset::SYMBOL_TOTAL, 7);
+        data[0..2].copy_from_slice(&sid.to_be_bytes());
         data[2..4].copy_from_slice(&magic_value.to_be_bytes());
-        data[4..8].copy_from_slice(&0x0512_4000u32.to_be_bytes());
-        data[0x20..0x24].copy_from_slice(&(SOM_HEADER_MIN_SIZE as u32).to_be_bytes());
-        data[0x5C..0x60].copy_from_slice(&7u32.to_be_bytes());
+        let checksum = header_checksum(&data);
+        data[offset::CHECKSUM..offset::CHECKSUM + 4].copy_from_slice(&checksum.to_be_bytes());
         data
     }
 
     #[test]
     fn test_detect_som() {
-        let data = make_som_header(magic::SHARE_MAGIC);
+        let data = make_som_header(system_id::HP9000S800, magic::SHARE_MAGIC);
         assert!(detect(&data));
     }
 
     #[test]
     fn test_parse_som() {
-        let data = make_som_header(magic::EXEC_MAGIC);
+        let data = make_som_header(system_id::PA_RISC_2_0, magic::DEMAND_MAGIC);
         let result = parse(&data).unwrap();
         assert_eq!(result.isa, Isa::Parisc);
         assert_eq!(result.format, FileFormat::Som);
+        assert_eq!(result.endianness, Endianness::Big);
+        assert_eq!(result.variant_name(), Some("PA-RISC 2.0"));
+        assert_eq!(result.metadata.entry_point, Some(0x1000));
+        assert_eq!(result.metadata.symbol_count, Some(7));
+        assert!(result.metadata.warnings.is_empty());
+        assert!(matches!(
+            &result.metadata.custom[0].value,
+            MetadataValue::String(t) if t == "Demand-load executable"
+        ));
+
+        let data = make_som_header(system_id::PA_RISC_1_1, magic::SHL_MAGIC);
+        let result = parse(&data).unwrap();
+        assert_eq!(result.variant_name(), Some("PA-RISC 1.1"));
+        assert!(matches!(
+            &result.metadata.custom[0].value,
+            MetadataValue::String(t) if t == "Shared library"
+        ));
+    }
+
+    #[test]
+    fn test_som_header_validation() {
+        let good = make_som_header(system_id::PA_RISC_1_0, magic::EXEC_MAGIC);
+        assert!(detect(&good));
+
+        // Unknown header version
+        let mut data = good.clone();
+        data[offset::VERSION_ID..offset::VERSION_ID + 4].copy_from_slice(&[0, 0, 0, 1]);
+        assert!(!detect(&data));
+
+        // Subspace table past the declared length
+        let mut data = good.clone();
+        data[offset::SUBSPACE_LOCATION..offset::SUBSPACE_LOCATION + 4]
+            .copy_from_slice(&0x400u32.to_be_bytes());
+        assert!(!detect(&data));
+
+        // Not a PA-RISC system ID
+        let mut data = good.clone();
+        data[0..2].copy_from_slice(&0x020Cu16.to_be_bytes());
+        assert!(!detect(&data));
+
+        // A corrupted header still parses, with a warning
+        let mut data = good;
+        data[offset::SPACE_TOTAL + 3] ^= 1;
+        let result = parse(&data).unwrap();
+        assert!(result.metadata.warnings[0].contains("checksum"));
     }
 
     #[test]
@@ -45,7 +45,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
 
     let mut result =
         ClassificationResult::from_format(Isa::X86, 32, Endianness::Little, FileFormat::Tds);
-    result.variant = Variant::new("Borland TDS");
+    result.variant = Some(Variant::new("Borland TDS"));
     result.metadata = metadata;
     Ok(result)
 }
@@ -0,0 +1,302 @@
+//! OpenVMS image header parser.
+//!
+//! OpenVMS images carry no magic number; the architecture is implied by the
+//! header generation:
+//! - VAX images start with the original IHD image header (16-bit offsets,
+//!   ASCII major/minor IDs).
+//! - Alpha images start with the extended EIHD header (32-bit fields,
+//!   major ID 3 / minor ID 0).
+//!
+//! OpenVMS I64 (Itanium) images are ELF files with `EI_OSABI` set to
+//! OpenVMS and are handled by the ELF parser, so they never reach here.
+//!
+//! Detection is therefore structural: every header field we rely on is
+//! range-checked before a file is accepted.
+
+use crate::error::{ClassifierError, Result};
+use crate::formats::{read_u16, read_u32};
+use crate::types::{
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+};
+
+/// OpenVMS disk block size; image headers occupy whole blocks.
+pub const VMS_BLOCK_SIZE: usize = 512;
+
+/// Minimum fixed EIHD size we rely on (through `EIHD$L_HDRBLKCNT`).
+pub const EIHD_MIN_SIZE: usize = 0x68;
+
+/// Minimum fixed IHD size we rely on (through `IHD$B_IMGTYPE`).
+pub const IHD_MIN_SIZE: usize = 0x30;
+
+/// EIHD field offsets (Alpha, little-endian 32-bit fields).
+pub mod eihd {
+    pub const MAJORID: usize = 0x00;
+    pub const MINORID: usize = 0x04;
+    pub const SIZE: usize = 0x08;
+    pub const ISDOFF: usize = 0x0C;
+    pub const ACTIVOFF: usize = 0x10;
+    pub const IMGTYPE: usize = 0x30;
+    pub const SUBTYPE: usize = 0x34;
+    pub const HDRBLKCNT: usize = 0x48;
+    pub const LNKFLAGS: usize = 0x4C;
+
+    /// `EIHD$K_MAJORID`
+    pub const K_MAJORID: u32 = 3;
+    /// `EIHD$K_MINORID`
+    pub const K_MINORID: u32 = 0;
+}
+
+/// IHD field offsets (VAX, little-endian 16-bit fields).
+pub mod ihd {
+    pub const SIZE: usize = 0x00;
+    pub const ACTIVOFF: usize = 0x02;
+    pub const MAJORID: usize = 0x0C;
+    pub const MINORID: usize = 0x0E;
+    pub const HDRBLKCNT: usize = 0x10;
+    pub const IMGTYPE: usize = 0x11;
+}
+
+/// Image type codes shared by IHD and EIHD.
+pub mod image_type {
+    /// Executable image (`$K_EXE`).
+    pub const EXE: u32 = 1;
+    /// Linkable (shareable) image (`$K_LIM`).
+    pub const LIM: u32 = 2;
+}
+
+/// OpenVMS image header generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmsVariant {
+    /// VAX image header (IHD)
+    VaxIhd,
+    /// Alpha extended image header (EIHD)
+    AlphaEihd,
+}
+
+impl VmsVariant {
+    /// Architecture implied by the header generation.
+    pub fn isa(self) -> Isa {
+        match self {
+            VmsVariant::VaxIhd => Isa::Vax,
+            VmsVariant::AlphaEihd => Isa::Alpha,
+        }
+    }
+
+    /// Native word size for the architecture.
+    pub fn bits(self) -> u8 {
+        match self {
+            VmsVariant::VaxIhd => 32,
+            VmsVariant::AlphaEihd => 64,
+        }
+    }
+}
+
+fn image_type_name(imgtype: u32) -> Option<&'static str> {
+    match imgtype {
+        image_type::EXE => Some("Executable"),
+        image_type::LIM => Some("Shareable image"),
+        _ => None,
+    }
+}
+
+fn detect_eihd(data: &[u8]) -> bool {
+    if data.len() < EIHD_MIN_SIZE {
+        return false;
+    }
+    let field = |off| read_u32(data, off, true).unwrap_or(0);
+
+    if field(eihd::MAJORID) != eihd::K_MAJORID || field(eihd::MINORID) != eihd::K_MINORID {
+        return false;
+    }
+    if image_type_name(field(eihd::IMGTYPE)).is_none() {
+        return false;
+    }
+
+    let blocks = field(eihd::HDRBLKCNT) as usize;
+    if blocks == 0 || blocks > 128 {
+        return false;
+    }
+    let header_span = blocks * VMS_BLOCK_SIZE;
+
+    let size = field(eihd::SIZE) as usize;
+    if size < EIHD_MIN_SIZE || size > header_span {
+        return false;
+    }
+
+    // Image section descriptors follow the fixed header inside the header blocks.
+    let isdoff = field(eihd::ISDOFF) as usize;
+    if isdoff < EIHD_MIN_SIZE || isdoff >= header_span {
+        return false;
+    }
+
+    let activoff = field(eihd::ACTIVOFF) as usize;
+    activoff == 0 || (activoff >= EIHD_MIN_SIZE && activoff < size)
+}
+
+fn detect_ihd(data: &[u8]) -> bool {
+    if data.len() < IHD_MIN_SIZE {
+        return false;
+    }
+
+    let ids = &data[ihd::MAJORID..ihd::MINORID + 2];
+    if !ids.iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+    if image_type_name(u32::from(data[ihd::IMGTYPE])).is_none() {
+        return false;
+    }
+    if data[ihd::HDRBLKCNT] == 0 {
+        return false;
+    }
+
+    let size = usize::from(u16::from_le_bytes([data[ihd::SIZE], data[ihd::SIZE + 1]]));
+    if !(IHD_MIN_SIZE..=VMS_BLOCK_SIZE).contains(&size) {
+        return false;
+    }
+
+    let activoff = usize::from(u16::from_le_bytes([
+        data[ihd::ACTIVOFF],
+        data[ihd::ACTIVOFF + 1],
+    ]));
+    activoff == 0 || (activoff >= IHD_MIN_SIZE && activoff < size)
+}
+
+/// Detect an OpenVMS image header.
+pub fn detect(data: &[u8]) -> Option<VmsVariant> {
+    if detect_eihd(data) {
+        Some(VmsVariant::AlphaEihd)
+    } else if detect_ihd(data) {
+        Some(VmsVariant::VaxIhd)
+    } else {
+        None
+    }
+}
+
+/// Parse an OpenVMS image header.
+pub fn parse(data: &[u8], variant: VmsVariant) -> Result<ClassificationResult> {
+    let min_size = match variant {
+        VmsVariant::AlphaEihd => EIHD_MIN_SIZE,
+        VmsVariant::VaxIhd => IHD_MIN_SIZE,
+    };
+    if data.len() < min_size {
+        return Err(ClassifierError::TruncatedData {
+            offset: 0,
+            expected: min_size,
+            actual: data.len(),
+        });
+    }
+
+    let mut notes = Vec::new();
+    let (imgtype, header_size, blocks) = match variant {
+        VmsVariant::AlphaEihd => {
+            let imgtype = read_u32(data, eihd::IMGTYPE, true)?;
+            let size = read_u32(data, eihd::SIZE, true)?;
+            let blocks = read_u32(data, eihd::HDRBLKCNT, true)?;
+            let subtype = read_u32(data, eihd::SUBTYPE, true)?;
+            let lnkflags = read_u32(data, eihd::LNKFLAGS, true)?;
+            notes.push("OpenVMS Alpha image (EIHD)".to_string());
+            notes.push(format!(
+                "Header version: {}.{}",
+                eihd::K_MAJORID,
+                eihd::K_MINORID
+            ));
+            notes.push(format!("Image subtype: {subtype}"));
+            notes.push(format!("Link flags: 0x{lnkflags:08X}"));
+            (imgtype, size, blocks)
+        }
+        VmsVariant::VaxIhd => {
+            let imgtype = u32::from(data[ihd::IMGTYPE]);
+            let size = u32::from(read_u16(data, ihd::SIZE, true)?);
+            let blocks = u32::from(data[ihd::HDRBLKCNT]);
+            let major = String::from_utf8_lossy(&data[ihd::MAJORID..ihd::MAJORID + 2]);
+            let minor = String::from_utf8_lossy(&data[ihd::MINORID..ihd::MINORID + 2]);
+            notes.push("OpenVMS VAX image (IHD)".to_string());
+            notes.push(format!("Header version: {major}.{minor}"));
+            (imgtype, size, blocks)
+        }
+    };
+
+    let type_name = image_type_name(imgtype).unwrap_or("Unknown");
+    notes.push(format!("Image type: {type_name} ({imgtype})"));
+    notes.push(format!("Header blocks: {blocks}"));
+
+    let metadata = ClassificationMetadata {
+        header_size: Some(u64::from(header_size)),
+        notes,
+        ..Default::default()
+    };
+
+    let mut result = ClassificationResult::from_format(
+        variant.isa(),
+        variant.bits(),
+        Endianness::Little,
+        FileFormat::Vms,
+    );
+    result.variant = Some(Variant::new(type_name));
+    result.metadata = metadata;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_eihd_header(imgtype: u32) -> Vec<u8> {
+        let mut data = vec![0u8; VMS_BLOCK_SIZE];
+        let mut put = |off: usize, v: u32| data[off..off + 4].copy_from_slice(&v.to_le_bytes());
+        put(eihd::MAJORID, eihd::K_MAJORID);
+        put(eihd::MINORID, eihd::K_MINORID);
+        put(eihd::SIZE, 0x1C8);
+        put(eihd::ISDOFF, 0x1C8);
+        put(eihd::ACTIVOFF, 0xD0);
+        put(eihd::IMGTYPE, imgtype);
+        put(eihd::SUBTYPE, 1);
+        put(eihd::HDRBLKCNT, 1);
+        data
+    }
+
+    fn make_ihd_header() -> Vec<u8> {
+        let mut data = vec![0u8; VMS_BLOCK_SIZE];
+        data[ihd::SIZE..ihd::SIZE + 2].copy_from_slice(&0x30u16.to_le_bytes());
+        data[ihd::MAJORID..ihd::MAJORID + 2].copy_from_slice(b"02");
+        data[ihd::MINORID..ihd::MINORID + 2].copy_from_slice(b"02");
+        data[ihd::HDRBLKCNT] = 1;
+        data[ihd::IMGTYPE] = image_type::EXE as u8;
+        data
+    }
+
+    #[test]
+    fn test_detect_eihd_alpha() {
+        let data = make_eihd_header(image_type::EXE);
+        assert_eq!(detect(&data), Some(VmsVariant::AlphaEihd));
+    }
+
+    #[test]
+    fn test_parse_eihd_alpha() {
+        let data = make_eihd_header(image_type::LIM);
+        let result = parse(&data, VmsVariant::AlphaEihd).unwrap();
+        assert_eq!(result.isa, Isa::Alpha);
+        assert_eq!(result.bitwidth, 64);
+        assert_eq!(result.format, FileFormat::Vms);
+        assert_eq!(result.variant_name(), Some("Shareable image"));
+        assert_eq!(result.metadata.header_size, Some(0x1C8));
+    }
+
+    #[test]
+    fn test_parse_ihd_vax() {
+        let data = make_ihd_header();
+        assert_eq!(detect(&data), Some(VmsVariant::VaxIhd));
+        let result = parse(&data, VmsVariant::VaxIhd).unwrap();
+        assert_eq!(result.isa, Isa::Vax);
+        assert_eq!(result.variant_name(), Some("Executable"));
+    }
+
+    #[test]
+    fn test_reject_non_vms() {
+        let mut data = make_eihd_header(image_type::EXE);
+        data[eihd::IMGTYPE] = 7;
+        assert_eq!(detect(&data), None);
+        assert_eq!(detect(&[0u8; VMS_BLOCK_SIZE]), None);
+        assert_eq!(detect(b"\x7FELF\x02\x01\x01\x00"), None);
+    }
+}
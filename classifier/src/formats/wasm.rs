@@ -6,7 +6,8 @@
 use crate::error::{ClassifierError, Result};
 use crate::formats::read_u32;
 use crate::types::{
-    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, Variant,
+    ClassificationMetadata, ClassificationResult, Endianness, FileFormat, Isa, MetadataEntry,
+    MetadataValue, Variant,
 };
 
 /// WebAssembly magic bytes: "\0asm"
@@ -35,6 +36,55 @@ pub mod section {
     pub const DATA_COUNT: u8 = 12;
 }
 
+/// Custom section marking an Emscripten side module.
+pub const DYLINK_SECTION: &str = "dylink.0";
+
+/// Pre-2021 name of [`DYLINK_SECTION`], without subsections.
+pub const DYLINK_LEGACY_SECTION: &str = "dylink";
+
+/// `dylink.0` subsection carrying the memory and table requirements.
+const DYLINK_MEM_INFO: u8 = 1;
+
+/// Memory and table requirements of a side module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DylinkMemInfo {
+    memory_size: u32,
+    table_size: u32,
+}
+
+/// Read the memory info fields (size, alignment, size, alignment).
+fn read_mem_info(data: &[u8], offset: &mut usize) -> Option<DylinkMemInfo> {
+    let memory_size = read_leb128_u32(data, offset)?;
+    read_leb128_u32(data, offset)?;
+    let table_size = read_leb128_u32(data, offset)?;
+    read_leb128_u32(data, offset)?;
+    Some(DylinkMemInfo {
+        memory_size,
+        table_size,
+    })
+}
+
+/// Parse the payload of a dylink custom section (after its name).
+fn parse_dylink(payload: &[u8], legacy: bool) -> Option<DylinkMemInfo> {
+    let mut offset = 0;
+    if legacy {
+        return read_mem_info(payload, &mut offset);
+    }
+    while offset < payload.len() {
+        let kind = payload[offset];
+        offset += 1;
+        let size = read_leb128_u32(payload, &mut offset)? as usize;
+        let end = offset
+            .checked_add(size)
+            .filter(|&end| end <= payload.len())?;
+        if kind == DYLINK_MEM_INFO {
+            return read_mem_info(&payload[..end], &mut offset);
+        }
+        offset = end;
+    }
+    None
+}
+
 /// Detect WebAssembly format.
 pub fn detect(data: &[u8]) -> bool {
     if data.len() < WASM_HEADER_SIZE {
@@ -106,6 +156,8 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     let mut code_size = 0u64;
     let mut has_start = false;
     let mut custom_sections: Vec<String> = Vec::new();
+    let mut side_module = false;
+    let mut dylink_info = None;
 
     while offset < data.len() {
         // Read section ID
@@ -132,6 +184,13 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
                     if name_off + name_len <= section_end {
                         if let Ok(name) = std::str::from_utf8(&data[name_off..name_off + name_len])
                         {
+                            if name == DYLINK_SECTION || name == DYLINK_LEGACY_SECTION {
+                                side_module = true;
+                                dylink_info = parse_dylink(
+                                    &data[name_off + name_len..section_end],
+                                    name == DYLINK_LEGACY_SECTION,
+                                );
+                            }
                             custom_sections.push(name.to_string());
                         }
                     }
@@ -206,10 +265,30 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
     if !custom_sections.is_empty() {
         notes.push(format!("Custom sections: {}", custom_sections.join(", ")));
     }
+    if side_module {
+        let note = "Emscripten side module: loaded into a main module at runtime";
+        notes.push(match dylink_info {
+            Some(info) => format!(
+                "{note} (needs {} bytes of memory, {} table entries)",
+                info.memory_size, info.table_size
+            ),
+            None => note.to_string(),
+        });
+    }
 
+    let module_type = if side_module {
+        "side-module"
+    } else {
+        "main-module"
+    };
     let metadata = ClassificationMetadata {
         code_size: if code_size > 0 { Some(code_size) } else { None },
         notes,
+        custom: vec![MetadataEntry::custom(
+            "wasm_module_type",
+            MetadataValue::String(module_type.to_string()),
+            "Module Type",
+        )],
         ..Default::default()
     };
 
@@ -222,7 +301,7 @@ pub fn parse(data: &[u8]) -> Result<ClassificationResult> {
         Endianness::Little,
         FileFormat::Wasm,
     );
-    result.variant = Variant::new(format!("WebAssembly {}", version));
+    result.variant = Some(Variant::new(format!("WebAssembly {version}")));
     result.metadata = metadata;
 
     Ok(result)
@@ -281,5 +360,32 @@ mod tests {
         assert_eq!(result.isa, Isa::Wasm);
         assert_eq!(result.bitwidth, 32);
         assert_eq!(result.format, FileFormat::Wasm);
+        assert!(matches!(
+            &result.metadata.custom[0].value,
+            MetadataValue::String(kind) if kind == "main-module"
+        ));
+    }
+
+    #[test]
+    fn test_parse_side_module() {
+        let mut data = make_wasm_module();
+        // dylink.0 custom section with a mem_info subsection
+        let mut payload = vec![DYLINK_SECTION.len() as u8];
+        payload.extend_from_slice(DYLINK_SECTION.as_bytes());
+        payload.extend_from_slice(&[DYLINK_MEM_INFO, 5, 0x80, 0x02, 4, 3, 0]);
+        data.insert(8, section::CUSTOM);
+        data.insert(9, payload.len() as u8);
+        data.splice(10..10, payload);
+
+        let result = parse(&data).unwrap();
+        assert!(matches!(
+            &result.metadata.custom[0].value,
+            MetadataValue::String(kind) if kind == "side-module"
+        ));
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("side module") && n.contains("256 bytes of memory, 3 table")));
     }
 }
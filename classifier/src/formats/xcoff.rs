@@ -141,7 +141,7 @@ fn parse_xcoff32(data: &[u8]) -> Result<ClassificationResult> {
     }
 
     // XCOFF is big-endian
-    let _magic = read_u16(data, 0, false)?;
+    let magic = read_u16(data, 0, false)?;
     let num_sections = read_u16(data, 2, false)?;
     let _timestamp = read_u32(data, 4, false)?;
     let _sym_offset = read_u32(data, 8, false)?;
@@ -195,6 +195,7 @@ fn parse_xcoff32(data: &[u8]) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         entry_point,
         section_count: Some(num_sections as usize),
+        raw_machine: Some(u32::from(magic)),
         symbol_count: if num_symbols > 0 {
             Some(num_symbols as usize)
         } else {
@@ -222,7 +223,7 @@ fn parse_xcoff64(data: &[u8]) -> Result<ClassificationResult> {
     }
 
     // XCOFF is big-endian
-    let _magic = read_u16(data, 0, false)?;
+    let magic = read_u16(data, 0, false)?;
     let num_sections = read_u16(data, 2, false)?;
     let _timestamp = read_u32(data, 4, false)?;
     let _sym_offset = read_u64(data, 8, false)?;
@@ -267,6 +268,7 @@ fn parse_xcoff64(data: &[u8]) -> Result<ClassificationResult> {
     let metadata = ClassificationMetadata {
         entry_point,
         section_count: Some(num_sections as usize),
+        raw_machine: Some(u32::from(magic)),
         symbol_count: if num_symbols > 0 {
             Some(num_symbols as usize)
         } else {
@@ -403,6 +405,7 @@ mod tests {
         assert_eq!(result.endianness, Endianness::Big);
         assert_eq!(result.format, FileFormat::Xcoff);
         assert_eq!(result.metadata.section_count, Some(3));
+        assert_eq!(result.metadata.raw_machine, Some(0x01DF));
     }
 
     #[test]
@@ -414,6 +417,7 @@ mod tests {
         assert_eq!(result.endianness, Endianness::Big);
         assert_eq!(result.format, FileFormat::Xcoff);
         assert_eq!(result.metadata.section_count, Some(5));
+        assert_eq!(result.metadata.raw_machine, Some(0x01F7));
     }
 
     #[test]
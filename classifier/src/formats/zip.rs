@@ -0,0 +1,100 @@
+//! ZIP archive signature detection.
+//!
+//! ZIP is read from the end: the end of central directory record (EOCD)
+//! sits in the last 22 bytes plus an optional comment of up to 64 KiB, and
+//! points back at the central directory. Anything may precede the archive,
+//! which is how self-extracting executables and PE/ZIP polyglots work, so
+//! this detector only reports that a ZIP is present. It is not a
+//! [`DetectedFormat`](super::DetectedFormat); the leading format still
+//! decides classification.
+
+/// End of central directory record signature ("PK\x05\x06").
+pub const EOCD_MAGIC: [u8; 4] = [b'P', b'K', 0x05, 0x06];
+
+/// Central directory file header signature ("PK\x01\x02").
+pub const CENTRAL_MAGIC: [u8; 4] = [b'P', b'K', 0x01, 0x02];
+
+/// Size of the EOCD record without its comment.
+pub const EOCD_SIZE: usize = 22;
+
+/// Largest EOCD comment.
+const MAX_COMMENT: usize = 0xFFFF;
+
+/// Offset of the end of central directory record, searching backwards from
+/// the end of `data` over the possible comment lengths.
+pub fn find_eocd(data: &[u8]) -> Option<usize> {
+    if data.len() < EOCD_SIZE {
+        return None;
+    }
+    let lowest = data.len().saturating_sub(EOCD_SIZE + MAX_COMMENT);
+    (lowest..=data.len() - EOCD_SIZE).rev().find(|&off| {
+        if data[off..off + 4] != EOCD_MAGIC {
+            return false;
+        }
+        let comment_len = u16::from_le_bytes([data[off + 20], data[off + 21]]) as usize;
+        off + EOCD_SIZE + comment_len <= data.len()
+    })
+}
+
+/// Detect a ZIP archive anywhere in `data`: an EOCD whose central
+/// directory lies inside the file and, when non-empty, starts with a
+/// central directory header.
+pub fn detect(data: &[u8]) -> bool {
+    let Some(eocd) = find_eocd(data) else {
+        return false;
+    };
+    let entries = u16::from_le_bytes([data[eocd + 10], data[eocd + 11]]);
+    let cd_size = u32::from_le_bytes([
+        data[eocd + 12],
+        data[eocd + 13],
+        data[eocd + 14],
+        data[eocd + 15],
+    ]) as usize;
+    if entries == 0 {
+        return cd_size == 0;
+    }
+    // Self-extracting archives keep offsets relative to the ZIP start, not
+    // the file start, so locate the central directory from the EOCD.
+    let Some(cd_start) = eocd.checked_sub(cd_size) else {
+        return false;
+    };
+    data[cd_start..].starts_with(&CENTRAL_MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eocd(entries: u16, cd_size: u32, cd_offset: u32) -> Vec<u8> {
+        let mut record = EOCD_MAGIC.to_vec();
+        record.extend_from_slice(&[0; 4]);
+        record.extend_from_slice(&entries.to_le_bytes());
+        record.extend_from_slice(&entries.to_le_bytes());
+        record.extend_from_slice(&cd_size.to_le_bytes());
+        record.extend_from_slice(&cd_offset.to_le_bytes());
+        record.extend_from_slice(&0u16.to_le_bytes());
+        record
+    }
+
+    #[test]
+    fn test_detect_zip() {
+        // Empty archive
+        assert!(detect(&eocd(0, 0, 0)));
+
+        // One central directory entry after some leading bytes
+        let mut data = vec![0xCCu8; 100];
+        let mut central = CENTRAL_MAGIC.to_vec();
+        central.resize(46, 0);
+        data.extend_from_slice(&central);
+        data.extend_from_slice(&eocd(1, 46, 0));
+        assert!(detect(&data));
+
+        // The central directory size points at non-ZIP bytes
+        let mut bad = vec![0xCCu8; 100];
+        bad.extend_from_slice(&eocd(1, 46, 0));
+        assert!(!detect(&bad));
+
+        assert!(!detect(b"PK\x05\x06"));
+        assert!(!detect(&[0u8; 64]));
+    }
+}
@@ -1,18 +1,57 @@
 //! Output formatters for detection payloads.
 //!
 //! This module provides trait-based formatters for rendering detection results
-//! in various output formats (human-readable, JSON, compact).
-
+//! in various output formats (human-readable, JSON, NDJSON, compact).
+//!
+//! # NDJSON records
+//!
+//! [`NdjsonFormatter`] and the `ndjson_*` helpers produce newline-delimited
+//! JSON: one self-contained object per line, meant to be written (and
+//! flushed) as soon as it is produced, e.g. with [`Output::record`].
+//! [`write_ndjson`] writes a batch of `file_result` records to any writer.
+//! Every record carries a `"type"` discriminator:
+//!
+//! | `type` | Emitted | Fields |
+//! |--------|---------|--------|
+//! | `file_result` | per classified file | the [`JsonFormatter`] document |
+//! | `region` | per ISA found by multi-ISA detection | `file`, `isa`, `bitwidth`, `endianness`, `window_count`, `total_bytes`, `avg_score` |
+//! | `error` | per file that could not be analyzed | `file`, `message` |
+//! | `summary` | once, last | `files`, `results`, `regions`, `errors`, `by_isa` (count per ISA) |
+//!
+//! `file` records also carry `file_bytes` for non-UTF-8 paths (see
+//! [`path_bytes_hex`]).
+//!
+//! [`Output::record`]: crate::output::Output::record
+//!
+//! # Numbers
+//!
+//! Every formatter renders numbers through the helpers below, so the same
+//! payload prints identically on every machine:
+//!
+//! | Value | Machine formats (JSON) | Human formats |
+//! |-------|------------------------|---------------|
+//! | confidence | [`confidence_value`]: 4 decimal places | [`confidence_percent`]: percent, 1 decimal |
+//! | raw score | integer | integer |
+//! | averaged score | [`score_value`]: 1 decimal place | [`format_score`]: 1 decimal |
+
+use crate::heuristics::DetectedIsa;
+use crate::output::{ColorChoice, Style};
 use crate::types::{
-    DetectionPayload, ExtensionDetection, FormatDetection, IsaCandidate, IsaClassification,
-    MetadataEntry, Note, NoteLevel,
+    round_confidence, DetectionPayload, ExtensionDetection, FormatDetection, HardeningSummary,
+    IsaCandidate, IsaClassification, MetadataEntry, Note, NoteLevel, OsFingerprint, SectionInfo,
+    SegmentInfo,
 };
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Trait for formatting detection payloads.
 ///
 /// Implementors provide methods for rendering each component of a detection
-/// payload, plus a method to render the complete payload.
+/// payload, plus a method to render the complete payload. The built-in
+/// formatters are `Send + Sync`, so one instance can serve every worker of a
+/// parallel pipeline; code that shares a trait object across threads should
+/// ask for `dyn PayloadFormatter + Send + Sync`.
 pub trait PayloadFormatter {
     /// Format the file path header.
     fn format_file(&self, path: &Path) -> String;
@@ -66,6 +105,28 @@ pub trait PayloadFormatter {
     }
 }
 
+/// Confidence for machine-readable output: clamped and rounded to 4
+/// decimal places.
+pub fn confidence_value(confidence: f64) -> f64 {
+    round_confidence(confidence)
+}
+
+/// Confidence for human-readable output: a percentage with 1 decimal.
+pub fn confidence_percent(confidence: f64) -> String {
+    format!("{:.1}%", round_confidence(confidence) * 100.0)
+}
+
+/// Averaged (non-integer) score for machine-readable output, rounded to 1
+/// decimal place.
+pub fn score_value(score: f64) -> f64 {
+    (score * 10.0).round() / 10.0
+}
+
+/// Averaged (non-integer) score for human-readable output.
+pub fn format_score(score: f64) -> String {
+    format!("{:.1}", score_value(score))
+}
+
 /// Human-readable output formatter.
 #[derive(Debug, Clone)]
 pub struct HumanFormatter {
@@ -75,6 +136,9 @@ pub struct HumanFormatter {
     pub quiet: bool,
     /// Show candidates even if not requested
     pub show_candidates: bool,
+    /// Whether to style the output; `Auto` is treated as `Never`, so pass
+    /// a choice already resolved with [`ColorChoice::resolve`]
+    pub color: ColorChoice,
 }
 
 impl Default for HumanFormatter {
@@ -83,6 +147,7 @@ impl Default for HumanFormatter {
             verbose: false,
             quiet: false,
             show_candidates: false,
+            color: ColorChoice::Never,
         }
     }
 }
@@ -99,6 +164,7 @@ impl HumanFormatter {
             verbose: true,
             quiet: false,
             show_candidates: false,
+            color: ColorChoice::Never,
         }
     }
 
@@ -108,7 +174,92 @@ impl HumanFormatter {
             verbose: false,
             quiet: true,
             show_candidates: false,
+            color: ColorChoice::Never,
+        }
+    }
+
+    /// Style the output with `color`.
+    pub fn with_color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Render a confidence as a percentage, colored by strength.
+    fn confidence(&self, confidence: f64) -> String {
+        self.color.paint(
+            &confidence_percent(confidence),
+            Style::for_confidence(confidence),
+        )
+    }
+
+    /// Format the input, scanned, and described sizes (verbose only).
+    fn format_sizes(&self, payload: &DetectionPayload) -> Option<String> {
+        if !self.verbose {
+            return None;
+        }
+
+        let scanned = payload
+            .scanned_bytes
+            .map_or_else(|| "unknown".to_string(), |size| size.to_string());
+        let described = payload
+            .described_size
+            .map(|size| format!(", describes {size}"))
+            .unwrap_or_default();
+        Some(format!(
+            "  Size:       {} bytes (scanned {scanned}{described})\n",
+            payload.input_size
+        ))
+    }
+
+    /// Format the hardening summary (verbose only).
+    fn format_hardening(&self, hardening: Option<&HardeningSummary>) -> Option<String> {
+        if !self.verbose {
+            return None;
         }
+        hardening.map(|h| format!("  Hardening:  {}\n", h.note))
+    }
+
+    /// Format the section/segment inventory as a compact table (verbose only).
+    fn format_sections(
+        &self,
+        sections: &[SectionInfo],
+        segments: &[SegmentInfo],
+    ) -> Option<String> {
+        if !self.verbose || (sections.is_empty() && segments.is_empty()) {
+            return None;
+        }
+
+        let mut s = String::new();
+        if !sections.is_empty() {
+            s.push_str("  Sections:\n");
+            for section in sections {
+                s.push_str(&format!(
+                    "    {:<20} off 0x{:08X} size 0x{:08X} {}{}\n",
+                    section.name,
+                    section.offset,
+                    section.size,
+                    if section.flags_write { 'W' } else { '-' },
+                    if section.flags_execinstr { 'X' } else { '-' },
+                ));
+            }
+        }
+        if !segments.is_empty() {
+            s.push_str("  Segments:\n");
+            for segment in segments {
+                s.push_str(&format!(
+                    "    {:<20} off 0x{:08X} vaddr 0x{:08X} filesz 0x{:08X} memsz 0x{:08X} {}{}{}\n",
+                    segment.kind,
+                    segment.offset,
+                    segment.vaddr,
+                    segment.file_size,
+                    segment.mem_size,
+                    if segment.flags_read { 'R' } else { '-' },
+                    if segment.flags_write { 'W' } else { '-' },
+                    if segment.flags_execute { 'X' } else { '-' },
+                ));
+            }
+        }
+        Some(s)
     }
 }
 
@@ -139,10 +290,17 @@ impl PayloadFormatter for HumanFormatter {
         }
 
         let mut s = String::new();
-        s.push_str(&format!("  ISA:        {} ({})\n", isa.isa, isa.isa.name()));
+        s.push_str(&format!(
+            "  ISA:        {} ({})\n",
+            self.color.paint(&isa.isa.to_string(), Style::Bold),
+            isa.isa.name()
+        ));
         s.push_str(&format!("  Bitwidth:   {}-bit\n", isa.bitwidth));
         s.push_str(&format!("  Endianness: {}\n", isa.endianness));
-        s.push_str(&format!("  Confidence: {:.1}%\n", isa.confidence * 100.0));
+        s.push_str(&format!(
+            "  Confidence: {}\n",
+            self.confidence(isa.confidence)
+        ));
 
         if let Some(ref variant) = isa.variant {
             if !variant.name.is_empty() {
@@ -170,13 +328,13 @@ impl PayloadFormatter for HumanFormatter {
         let mut s = String::from("  Candidates:\n");
         for (i, c) in candidates.iter().take(5).enumerate() {
             s.push_str(&format!(
-                "    {}. {} ({}-bit, {}) - score: {}, {:.1}%\n",
+                "    {}. {} ({}-bit, {}) - score: {}, {}\n",
                 i + 1,
                 c.isa,
                 c.bitwidth,
                 c.endianness,
                 c.raw_score,
-                c.confidence * 100.0
+                self.confidence(c.confidence)
             ));
         }
         Some(s)
@@ -187,8 +345,42 @@ impl PayloadFormatter for HumanFormatter {
             return None;
         }
 
-        let names: Vec<&str> = extensions.iter().map(|e| e.name.as_str()).collect();
-        Some(format!("  Extensions: {}\n", names.join(", ")))
+        if !self.verbose {
+            // Baseline extensions (SSE2 on x86-64, ...) only show up verbose
+            let names: Vec<&str> = extensions
+                .iter()
+                .filter(|e| !e.baseline)
+                .map(|e| e.name.as_str())
+                .collect();
+            if names.is_empty() {
+                return None;
+            }
+            return Some(format!("  Extensions: {}\n", names.join(", ")));
+        }
+
+        let categories: Vec<String> = extensions.iter().map(|e| e.category.to_string()).collect();
+        let name_w = column_width("Name", extensions.iter().map(|e| e.name.len()));
+        let cat_w = column_width("Category", categories.iter().map(String::len));
+
+        let mut s = String::from("  Extensions:\n");
+        s.push_str(&format!(
+            "    {:<name_w$}  {:<cat_w$}  {:>10}  Source\n",
+            "Name", "Category", "Confidence"
+        ));
+        for (ext, category) in extensions.iter().zip(&categories) {
+            // Pad before painting so escape codes don't skew the column
+            let confidence = format!("{:>10}", confidence_percent(ext.confidence));
+            s.push_str(&format!(
+                "    {:<name_w$}  {:<cat_w$}  {}  {}{}\n",
+                ext.name,
+                category,
+                self.color
+                    .paint(&confidence, Style::for_confidence(ext.confidence)),
+                ext.source,
+                if ext.baseline { " (baseline)" } else { "" }
+            ));
+        }
+        Some(s)
     }
 
     fn format_metadata(&self, metadata: &[MetadataEntry]) -> Option<String> {
@@ -196,9 +388,25 @@ impl PayloadFormatter for HumanFormatter {
             return None;
         }
 
-        let mut s = String::new();
-        for entry in metadata {
-            s.push_str(&format!("  {:10}  {}\n", entry.label, entry.value));
+        let keys: Vec<String> = metadata.iter().map(|e| e.key.to_string()).collect();
+        let values: Vec<String> = metadata.iter().map(|e| e.value.to_string()).collect();
+        let key_w = column_width("Key", keys.iter().map(String::len));
+        let label_w = column_width("Label", metadata.iter().map(|e| e.label.len()));
+        let value_w = column_width("Value", values.iter().map(String::len));
+
+        let mut s = String::from("  Metadata:\n");
+        s.push_str(&format!(
+            "    {:<key_w$}  {:<label_w$}  {:<value_w$}  Origin\n",
+            "Key", "Label", "Value"
+        ));
+        for ((entry, key), value) in metadata.iter().zip(&keys).zip(&values) {
+            s.push_str(&format!(
+                "    {:<key_w$}  {:<label_w$}  {:<value_w$}  {}\n",
+                key,
+                entry.label,
+                value,
+                entry.origin.unwrap_or("-")
+            ));
         }
         Some(s)
     }
@@ -222,14 +430,31 @@ impl PayloadFormatter for HumanFormatter {
             return None;
         }
 
-        let mut s = String::new();
+        let marker = |level: NoteLevel, width: usize| match level {
+            NoteLevel::Info => format!("{:<width$}", "[info]"),
+            NoteLevel::Warning => self
+                .color
+                .paint(&format!("{:<width$}", "[warn]"), Style::Yellow),
+            NoteLevel::Error => self
+                .color
+                .paint(&format!("{:<width$}", "[error]"), Style::Red),
+        };
+
+        if !self.verbose {
+            let mut s = String::new();
+            for note in to_show {
+                s.push_str(&format!("  {} {}\n", marker(note.level, 0), note.message));
+            }
+            return Some(s);
+        }
+
+        let mut s = String::from("  Notes:\n");
         for note in to_show {
-            let prefix = match note.level {
-                NoteLevel::Info => "  [info]",
-                NoteLevel::Warning => "  [warn]",
-                NoteLevel::Error => "  [error]",
-            };
-            s.push_str(&format!("{} {}\n", prefix, note.message));
+            s.push_str(&format!("    {}  {}", marker(note.level, 7), note.message));
+            if let Some(ref context) = note.context {
+                s.push_str(&format!("  ({context})"));
+            }
+            s.push('\n');
         }
         Some(s)
     }
@@ -251,12 +476,24 @@ impl PayloadFormatter for HumanFormatter {
         if let Some(s) = self.format_format(&payload.format) {
             parts.push(s);
         }
+        if let Some(os) = &payload.os {
+            parts.push(format!("  OS:         {os}\n"));
+        }
+        if let Some(s) = self.format_sizes(payload) {
+            parts.push(s);
+        }
+        if let Some(s) = self.format_hardening(payload.hardening.as_ref()) {
+            parts.push(s);
+        }
         if let Some(s) = self.format_extensions(&payload.extensions) {
             parts.push(s);
         }
         if let Some(s) = self.format_metadata(&payload.metadata) {
             parts.push(s);
         }
+        if let Some(s) = self.format_sections(&payload.sections, &payload.segments) {
+            parts.push(s);
+        }
         if let Some(s) = self.format_candidates(&payload.candidates) {
             parts.push(s);
         }
@@ -269,6 +506,11 @@ impl PayloadFormatter for HumanFormatter {
     }
 }
 
+/// Width of a table column: its widest cell, or the header if wider.
+fn column_width(header: &str, cells: impl Iterator<Item = usize>) -> usize {
+    cells.fold(header.len(), usize::max)
+}
+
 /// JSON output formatter.
 #[derive(Debug, Clone)]
 pub struct JsonFormatter {
@@ -324,110 +566,7 @@ impl PayloadFormatter for JsonFormatter {
     }
 
     fn format_payload(&self, payload: &DetectionPayload, path: &Path) -> String {
-        #[derive(serde::Serialize)]
-        struct JsonOutput<'a> {
-            file: String,
-            format: &'a str,
-            format_variant: Option<&'a str>,
-            isa: String,
-            isa_name: &'static str,
-            bitwidth: u8,
-            endianness: String,
-            confidence: f64,
-            source: String,
-            variant: Option<String>,
-            extensions: Vec<ExtensionJson<'a>>,
-            metadata: Vec<MetadataJson<'a>>,
-            candidates: Vec<CandidateJson>,
-            notes: Vec<NoteJson<'a>>,
-        }
-
-        #[derive(serde::Serialize)]
-        struct ExtensionJson<'a> {
-            name: &'a str,
-            category: String,
-            confidence: f64,
-            source: String,
-        }
-
-        #[derive(serde::Serialize)]
-        struct MetadataJson<'a> {
-            key: String,
-            value: String,
-            label: &'a str,
-        }
-
-        #[derive(serde::Serialize)]
-        struct CandidateJson {
-            isa: String,
-            bitwidth: u8,
-            endianness: String,
-            raw_score: i64,
-            confidence: f64,
-        }
-
-        #[derive(serde::Serialize)]
-        struct NoteJson<'a> {
-            level: &'a str,
-            message: &'a str,
-            context: Option<&'a str>,
-        }
-
-        let output = JsonOutput {
-            file: path.display().to_string(),
-            format: format_name(&payload.format.format),
-            format_variant: payload.format.variant_name.as_deref(),
-            isa: payload.primary.isa.to_string(),
-            isa_name: payload.primary.isa.name(),
-            bitwidth: payload.primary.bitwidth,
-            endianness: payload.primary.endianness.to_string(),
-            confidence: payload.primary.confidence,
-            source: format!("{:?}", payload.primary.source),
-            variant: payload.primary.variant.as_ref().map(|v| v.to_string()),
-            extensions: payload
-                .extensions
-                .iter()
-                .map(|e| ExtensionJson {
-                    name: &e.name,
-                    category: format!("{:?}", e.category),
-                    confidence: e.confidence,
-                    source: format!("{:?}", e.source),
-                })
-                .collect(),
-            metadata: payload
-                .metadata
-                .iter()
-                .map(|m| MetadataJson {
-                    key: format!("{:?}", m.key),
-                    value: m.value.to_string(),
-                    label: &m.label,
-                })
-                .collect(),
-            candidates: payload
-                .candidates
-                .iter()
-                .map(|c| CandidateJson {
-                    isa: c.isa.to_string(),
-                    bitwidth: c.bitwidth,
-                    endianness: c.endianness.to_string(),
-                    raw_score: c.raw_score,
-                    confidence: c.confidence,
-                })
-                .collect(),
-            notes: payload
-                .notes
-                .iter()
-                .map(|n| NoteJson {
-                    level: match n.level {
-                        NoteLevel::Info => "info",
-                        NoteLevel::Warning => "warning",
-                        NoteLevel::Error => "error",
-                    },
-                    message: &n.message,
-                    context: n.context.as_deref(),
-                })
-                .collect(),
-        };
+        let output = json_record(payload, path);
 
         if self.pretty {
             serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
@@ -437,6 +576,356 @@ impl PayloadFormatter for JsonFormatter {
     }
 }
 
+/// The JSON document for one payload, shared by [`JsonFormatter`] and
+/// [`NdjsonFormatter`].
+#[derive(serde::Serialize)]
+struct JsonRecord<'a> {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_bytes: Option<String>,
+    format: &'a str,
+    format_variant: Option<&'a str>,
+    input_size: u64,
+    scanned_bytes: Option<u64>,
+    described_size: Option<u64>,
+    isa: String,
+    isa_name: &'static str,
+    bitwidth: u8,
+    endianness: String,
+    confidence: f64,
+    source: String,
+    variant: Option<String>,
+    extensions: Vec<ExtensionJson<'a>>,
+    extensions_analyzed: bool,
+    metadata: Vec<MetadataJson<'a>>,
+    candidates: Vec<CandidateJson>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    architectures: Vec<ArchitectureJson>,
+    notes: Vec<NoteJson<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    sections: &'a [SectionInfo],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    segments: &'a [SegmentInfo],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hardening: Option<&'a HardeningSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    os: Option<&'a OsFingerprint>,
+}
+
+#[derive(serde::Serialize)]
+struct ExtensionJson<'a> {
+    name: &'a str,
+    category: String,
+    confidence: f64,
+    source: String,
+    baseline: bool,
+}
+
+#[derive(serde::Serialize)]
+struct MetadataJson<'a> {
+    key: String,
+    value: String,
+    label: &'a str,
+}
+
+#[derive(serde::Serialize)]
+struct CandidateJson {
+    isa: String,
+    bitwidth: u8,
+    endianness: String,
+    raw_score: i64,
+    confidence: f64,
+}
+
+/// One slice of a multi-architecture container such as a fat Mach-O.
+#[derive(serde::Serialize)]
+struct ArchitectureJson {
+    isa: String,
+    isa_name: &'static str,
+    bitwidth: u8,
+    endianness: String,
+    variant: Option<String>,
+    offset: u64,
+    size: u64,
+}
+
+#[derive(serde::Serialize)]
+struct NoteJson<'a> {
+    level: &'a str,
+    message: &'a str,
+    context: Option<&'a str>,
+}
+
+fn json_record<'a>(payload: &'a DetectionPayload, path: &Path) -> JsonRecord<'a> {
+    JsonRecord {
+        file: path.display().to_string(),
+        file_bytes: path_bytes_hex(path),
+        format: format_name(&payload.format.format),
+        format_variant: payload.format.variant_name.as_deref(),
+        input_size: payload.input_size,
+        scanned_bytes: payload.scanned_bytes,
+        described_size: payload.described_size,
+        isa: payload.primary.isa.to_string(),
+        isa_name: payload.primary.isa.name(),
+        bitwidth: payload.primary.bitwidth,
+        endianness: payload.primary.endianness.to_string(),
+        confidence: confidence_value(payload.primary.confidence),
+        source: format!("{:?}", payload.primary.source),
+        variant: payload.primary.variant.as_ref().map(|v| v.to_string()),
+        extensions: payload
+            .extensions
+            .iter()
+            .map(|e| ExtensionJson {
+                name: &e.name,
+                category: format!("{:?}", e.category),
+                confidence: confidence_value(e.confidence),
+                source: format!("{:?}", e.source),
+                baseline: e.baseline,
+            })
+            .collect(),
+        extensions_analyzed: payload.extensions_analyzed,
+        metadata: payload
+            .metadata
+            .iter()
+            .map(|m| MetadataJson {
+                key: format!("{:?}", m.key),
+                value: m.value.to_string(),
+                label: &m.label,
+            })
+            .collect(),
+        candidates: payload
+            .candidates
+            .iter()
+            .map(|c| CandidateJson {
+                isa: c.isa.to_string(),
+                bitwidth: c.bitwidth,
+                endianness: c.endianness.to_string(),
+                raw_score: c.raw_score,
+                confidence: confidence_value(c.confidence),
+            })
+            .collect(),
+        architectures: payload
+            .slices
+            .iter()
+            .map(|s| ArchitectureJson {
+                isa: s.isa.to_string(),
+                isa_name: s.isa.name(),
+                bitwidth: s.bitwidth,
+                endianness: s.endianness.to_string(),
+                variant: s.variant.as_ref().map(|v| v.to_string()),
+                offset: s.offset,
+                size: s.size,
+            })
+            .collect(),
+        notes: payload
+            .notes
+            .iter()
+            .map(|n| NoteJson {
+                level: match n.level {
+                    NoteLevel::Info => "info",
+                    NoteLevel::Warning => "warning",
+                    NoteLevel::Error => "error",
+                },
+                message: &n.message,
+                context: n.context.as_deref(),
+            })
+            .collect(),
+        sections: &payload.sections,
+        segments: &payload.segments,
+        hardening: payload.hardening.as_ref(),
+        os: payload.os.as_ref(),
+    }
+}
+
+/// Newline-delimited JSON formatter for streaming consumers.
+///
+/// Each payload becomes one `file_result` record: the [`JsonFormatter`]
+/// document on a single line with a `"type"` field added. See the module
+/// documentation for the other record types.
+#[derive(Debug, Clone, Default)]
+pub struct NdjsonFormatter;
+
+impl NdjsonFormatter {
+    /// Create a new NDJSON formatter.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// A record tagged with its NDJSON `"type"`.
+#[derive(serde::Serialize)]
+struct Tagged<T> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(flatten)]
+    record: T,
+}
+
+/// Serialize `record` as one NDJSON line tagged `kind`, newline included.
+fn ndjson_line<T: serde::Serialize>(kind: &'static str, record: T) -> String {
+    let mut line = serde_json::to_string(&Tagged { kind, record })
+        .unwrap_or_else(|_| format!("{{\"type\":\"{kind}\"}}"));
+    line.push('\n');
+    line
+}
+
+impl PayloadFormatter for NdjsonFormatter {
+    fn format_file(&self, _path: &Path) -> String {
+        String::new() // Handled in format_payload
+    }
+
+    fn format_format(&self, _format: &FormatDetection) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_isa(&self, _isa: &IsaClassification) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_candidates(&self, _candidates: &[IsaCandidate]) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_extensions(&self, _extensions: &[ExtensionDetection]) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_metadata(&self, _metadata: &[MetadataEntry]) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_notes(&self, _notes: &[Note]) -> Option<String> {
+        None // Handled in format_payload
+    }
+
+    fn format_payload(&self, payload: &DetectionPayload, path: &Path) -> String {
+        ndjson_line("file_result", json_record(payload, path))
+    }
+}
+
+/// NDJSON `region` record for one ISA found by multi-ISA detection.
+pub fn ndjson_region(path: &Path, region: &DetectedIsa) -> String {
+    #[derive(serde::Serialize)]
+    struct Region {
+        file: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_bytes: Option<String>,
+        isa: String,
+        bitwidth: u8,
+        endianness: String,
+        window_count: usize,
+        total_bytes: usize,
+        avg_score: f64,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        extensions: Vec<String>,
+    }
+
+    ndjson_line(
+        "region",
+        Region {
+            file: path.display().to_string(),
+            file_bytes: path_bytes_hex(path),
+            isa: region.isa.to_string(),
+            bitwidth: region.bitwidth,
+            endianness: region.endianness.to_string(),
+            window_count: region.window_count,
+            total_bytes: region.total_bytes,
+            avg_score: score_value(region.avg_score),
+            extensions: region.extensions.iter().map(|e| e.name.clone()).collect(),
+        },
+    )
+}
+
+/// NDJSON `error` record for a file that could not be analyzed.
+pub fn ndjson_error(path: &Path, message: &str) -> String {
+    #[derive(serde::Serialize)]
+    struct Error<'a> {
+        file: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_bytes: Option<String>,
+        message: &'a str,
+    }
+
+    ndjson_line(
+        "error",
+        Error {
+            file: path.display().to_string(),
+            file_bytes: path_bytes_hex(path),
+            message,
+        },
+    )
+}
+
+/// Running totals for the closing NDJSON `summary` record.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct NdjsonSummary {
+    /// Files attempted
+    pub files: u64,
+    /// `file_result` records emitted
+    pub results: u64,
+    /// `region` records emitted
+    pub regions: u64,
+    /// `error` records emitted
+    pub errors: u64,
+    /// Files (or regions) per primary ISA
+    pub by_isa: BTreeMap<String, u64>,
+}
+
+impl NdjsonSummary {
+    /// Create empty totals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a classified file.
+    pub fn add_result(&mut self, payload: &DetectionPayload) {
+        self.files += 1;
+        self.results += 1;
+        *self
+            .by_isa
+            .entry(payload.primary.isa.to_string())
+            .or_default() += 1;
+    }
+
+    /// Count a multi-ISA file and its regions.
+    pub fn add_regions(&mut self, regions: &[DetectedIsa]) {
+        self.files += 1;
+        self.regions += regions.len() as u64;
+        for region in regions {
+            *self.by_isa.entry(region.isa.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Count a file that failed.
+    pub fn add_error(&mut self) {
+        self.files += 1;
+        self.errors += 1;
+    }
+
+    /// The `summary` record, newline included.
+    pub fn to_line(&self) -> String {
+        ndjson_line("summary", self)
+    }
+}
+
+/// Write one NDJSON `file_result` record per payload to `w`, the same
+/// lines `--format ndjson` prints. No `summary` record is appended; use
+/// [`NdjsonSummary`] for that.
+///
+/// # Errors
+///
+/// Returns the first write error from `w`.
+pub fn write_ndjson<W: Write>(
+    payloads: &[(PathBuf, DetectionPayload)],
+    w: &mut W,
+) -> io::Result<()> {
+    let formatter = NdjsonFormatter::new();
+    for (path, payload) in payloads {
+        w.write_all(formatter.format_payload(payload, path).as_bytes())?;
+    }
+    Ok(())
+}
+
 /// Compact single-line output formatter.
 #[derive(Debug, Clone, Default)]
 pub struct ShortFormatter;
@@ -486,12 +975,12 @@ impl PayloadFormatter for ShortFormatter {
         };
 
         format!(
-            "{}\t{}\t{}\t{}\t{:.0}%{}\n",
+            "{}\t{}\t{}\t{}\t{}{}\n",
             path.display(),
             payload.primary.isa,
             payload.primary.bitwidth,
             payload.primary.endianness,
-            payload.primary.confidence * 100.0,
+            confidence_percent(payload.primary.confidence),
             exts
         )
     }
@@ -529,13 +1018,13 @@ impl PayloadFormatter for CandidatesFormatter {
         let mut s = String::new();
         for (i, c) in candidates.iter().enumerate() {
             s.push_str(&format!(
-                "  {}. {} ({}-bit, {}) - score: {}, confidence: {:.1}%\n",
+                "  {}. {} ({}-bit, {}) - score: {}, confidence: {}\n",
                 i + 1,
                 c.isa,
                 c.bitwidth,
                 c.endianness,
                 c.raw_score,
-                c.confidence * 100.0
+                confidence_percent(c.confidence)
             ));
         }
         Some(s)
@@ -563,6 +1052,29 @@ impl PayloadFormatter for CandidatesFormatter {
     }
 }
 
+/// Hex-encode the raw bytes of a path that is not valid UTF-8.
+///
+/// Returns `None` for UTF-8 paths, whose lossy display is already exact.
+/// On Unix the bytes are the raw name; on Windows they are the UTF-16LE
+/// code units.
+///
+/// JSON output carries this next to the lossy `file` string so consumers can
+/// join against the original names.
+pub fn path_bytes_hex(path: &Path) -> Option<String> {
+    if path.to_str().is_some() {
+        return None;
+    }
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()).to_vec();
+    #[cfg(windows)]
+    let bytes: Vec<u8> = std::os::windows::ffi::OsStrExt::encode_wide(path.as_os_str())
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    #[cfg(not(any(unix, windows)))]
+    let bytes = path.to_string_lossy().into_owned().into_bytes();
+    Some(hex::encode(bytes))
+}
+
 /// Helper function to get format display name.
 fn format_name(format: &crate::types::FileFormat) -> &'static str {
     use crate::types::FileFormat;
@@ -588,6 +1100,7 @@ fn format_name(format: &crate::types::FileFormat) -> &'static str {
         FileFormat::Aof => "AOF/AXF",
         FileFormat::Epoc => "EPOC",
         FileFormat::EspFirmware => "ESP Firmware",
+        FileFormat::IntelMe => "Intel ME",
         FileFormat::PalmPdb => "Palm PDB/PRC",
         FileFormat::AmigaHunk => "Amiga Hunk",
         FileFormat::Os9 => "OS-9",
@@ -610,9 +1123,11 @@ fn format_name(format: &crate::types::FileFormat) -> &'static str {
         FileFormat::Vdex => "VDEX",
         FileFormat::Art => "ART",
         FileFormat::LlvmBc => "LLVM Bitcode",
+        FileFormat::Dis => "Inferno Dis",
         FileFormat::FatElf => "FatELF",
         FileFormat::Archive => "ar Archive",
         FileFormat::WindowsLib => "Windows .lib",
+        FileFormat::Zip => "ZIP",
         FileFormat::Xbe => "XBE",
         FileFormat::Xex => "XEX",
         FileFormat::SelfPs3 => "PS3 SELF",
@@ -622,6 +1137,7 @@ fn format_name(format: &crate::types::FileFormat) -> &'static str {
         FileFormat::Nro => "NRO",
         FileFormat::Dol => "DOL",
         FileFormat::Rel => "REL",
+        FileFormat::IpBin => "Dreamcast IP.BIN",
         FileFormat::ZImage => "zImage",
         FileFormat::UImage => "uImage",
         FileFormat::Fit => "FIT",
@@ -640,7 +1156,8 @@ fn format_name(format: &crate::types::FileFormat) -> &'static str {
 mod tests {
     use super::*;
     use crate::types::{
-        ClassificationSource, Endianness, ExtensionCategory, ExtensionSource, FileFormat, Isa,
+        ClassificationSource, ContainedArch, Endianness, ExtensionCategory, ExtensionSource,
+        FileFormat, Isa,
     };
     use std::path::PathBuf;
 
@@ -657,6 +1174,39 @@ mod tests {
         .with_metadata(MetadataEntry::entry_point(0x401000))
     }
 
+    #[test]
+    fn test_write_ndjson() {
+        let payloads = vec![
+            (PathBuf::from("/bin/a"), sample_payload()),
+            (
+                PathBuf::from("/bin/b"),
+                DetectionPayload::new(
+                    FormatDetection::new(FileFormat::Elf),
+                    IsaClassification::from_format(Isa::AArch64, 64, Endianness::Little),
+                ),
+            ),
+        ];
+        let mut out = Vec::new();
+        write_ndjson(&payloads, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with('\n'));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for ((path, _), line) in payloads.iter().zip(&lines) {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["type"], "file_result");
+            assert_eq!(record["file"], path.display().to_string());
+        }
+        assert_eq!(
+            lines[0],
+            NdjsonFormatter::new()
+                .format_payload(&payloads[0].1, &payloads[0].0)
+                .trim_end()
+        );
+        assert!(lines[1].contains("\"isa\":\"aarch64\""), "{}", lines[1]);
+    }
+
     #[test]
     fn test_human_formatter() {
         let formatter = HumanFormatter::new();
@@ -669,6 +1219,46 @@ mod tests {
         assert!(output.contains("AVX2"));
     }
 
+    #[test]
+    fn test_human_formatter_color() {
+        let payload = sample_payload().with_note(Note::warning("section headers stripped"));
+        let path = PathBuf::from("/bin/test");
+
+        let plain = HumanFormatter::new()
+            .with_color(ColorChoice::Never)
+            .format_payload(&payload, &path);
+        assert_eq!(
+            plain,
+            concat!(
+                "File: /bin/test\n",
+                "  ISA:        x86_64 (x86-64 (AMD64))\n",
+                "  Bitwidth:   64-bit\n",
+                "  Endianness: little\n",
+                "  Confidence: 100.0%\n",
+                "  Format:     ELF\n",
+                "  Extensions: AVX2\n",
+                "  [warn] section headers stripped\n",
+                "\n",
+            )
+        );
+        assert_eq!(plain, HumanFormatter::new().format_payload(&payload, &path));
+
+        let colored = HumanFormatter::new()
+            .with_color(ColorChoice::Always)
+            .format_payload(&payload, &path);
+        assert!(colored.contains("  \x1b[33m[warn]\x1b[0m section headers stripped\n"));
+        assert!(colored.contains("ISA:        \x1b[1mx86_64\x1b[0m"));
+        assert!(colored.contains("Confidence: \x1b[32m100.0%\x1b[0m"));
+
+        // Machine-readable formats ignore color entirely
+        for output in [
+            JsonFormatter::new().format_payload(&payload, &path),
+            ShortFormatter::new().format_payload(&payload, &path),
+        ] {
+            assert!(!output.contains('\x1b'));
+        }
+    }
+
     #[test]
     fn test_human_formatter_quiet() {
         let formatter = HumanFormatter::quiet();
@@ -688,6 +1278,50 @@ mod tests {
         assert!(output.contains("\"file\": \"/bin/test\""));
         assert!(output.contains("\"isa\": \"x86_64\""));
         assert!(output.contains("\"AVX2\""));
+        assert!(!output.contains("\"architectures\""));
+    }
+
+    #[test]
+    fn test_json_formatter_architectures() {
+        let mut payload = DetectionPayload::new(
+            FormatDetection::new(FileFormat::MachOFat),
+            IsaClassification::from_format(Isa::X86_64, 64, Endianness::Little),
+        );
+        payload.slices = [(Isa::X86_64, 0x4000), (Isa::AArch64, 0x8000)]
+            .into_iter()
+            .map(|(isa, offset)| ContainedArch {
+                isa,
+                bitwidth: 64,
+                endianness: Endianness::Little,
+                variant: None,
+                offset,
+                size: 0x4000,
+                extensions: Vec::new(),
+            })
+            .collect();
+        let output = JsonFormatter::new().format_payload(&payload, &PathBuf::from("/bin/ls"));
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let archs = json["architectures"].as_array().unwrap();
+        assert_eq!(archs.len(), 2);
+        assert_eq!(archs[0]["isa"], "x86_64");
+        assert_eq!(archs[1]["isa"], "aarch64");
+        assert_eq!(archs[1]["offset"], 0x8000);
+    }
+
+    #[test]
+    fn test_baseline_extensions_hidden_unless_verbose() {
+        let mut sse2 = ExtensionDetection::from_code("SSE2", ExtensionCategory::Simd, 0.9);
+        sse2.baseline = true;
+        let payload = sample_payload().with_extension(sse2);
+        let path = PathBuf::from("/bin/test");
+
+        let output = HumanFormatter::new().format_payload(&payload, &path);
+        assert!(output.contains("  Extensions: AVX2\n"));
+        let verbose = HumanFormatter::verbose().format_payload(&payload, &path);
+        assert!(verbose.contains("code (baseline)"));
+        let json = JsonFormatter::new().format_payload(&payload, &path);
+        assert!(json.contains("\"baseline\": true"));
+        assert!(json.contains("\"baseline\": false"));
     }
 
     #[test]
@@ -699,4 +1333,207 @@ mod tests {
         assert!(output.contains("/bin/test\tx86_64\t64\tlittle"));
         assert!(output.contains("[AVX2]"));
     }
+
+    #[test]
+    fn test_section_inventory_output() {
+        let mut payload = sample_payload();
+        payload.sections.push(SectionInfo {
+            name: ".text".to_string(),
+            offset: 0x1000,
+            size: 0x200,
+            flags_execinstr: true,
+            flags_write: false,
+        });
+
+        let path = PathBuf::from("/bin/test");
+        let normal = HumanFormatter::new().format_payload(&payload, &path);
+        assert!(!normal.contains("Sections:"));
+        let verbose = HumanFormatter::verbose().format_payload(&payload, &path);
+        assert!(verbose.contains("Sections:"));
+        assert!(verbose.contains(".text"));
+
+        let json = JsonFormatter::new().format_payload(&payload, &path);
+        assert!(json.contains("\"sections\""));
+        assert!(json.contains("\"flags_execinstr\": true"));
+        assert!(!json.contains("\"segments\""));
+    }
+
+    #[test]
+    fn test_hardening_output() {
+        let mut payload = sample_payload();
+        let path = PathBuf::from("/bin/test");
+        assert!(!JsonFormatter::new()
+            .format_payload(&payload, &path)
+            .contains("hardening"));
+
+        payload.hardening = Some(
+            HardeningSummary {
+                pie: true,
+                nx: true,
+                relro: Some(true),
+                bind_now: Some(false),
+                ..Default::default()
+            }
+            .with_note(),
+        );
+        let normal = HumanFormatter::new().format_payload(&payload, &path);
+        assert!(!normal.contains("Hardening:"));
+        let verbose = HumanFormatter::verbose().format_payload(&payload, &path);
+        assert!(verbose.contains("Hardening:  PIE, NX, partial RELRO\n"));
+
+        let json = JsonFormatter::new().format_payload(&payload, &path);
+        assert!(json.contains("\"hardening\""));
+        assert!(json.contains("\"relro\": true"));
+        assert!(!json.contains("stack_canary"));
+    }
+
+    /// RISC-V ELF whose e_flags imply C/F/D, followed by vector code.
+    fn riscv_elf_with_vector_code() -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2; // 64-bit
+        data[5] = 1; // little-endian
+        data[6] = 1;
+        data[0x12] = 0xF3; // EM_RISCV
+        data[0x30..0x34].copy_from_slice(&0x0005u32.to_le_bytes()); // RVC | double-float ABI
+        for _ in 0..16 {
+            // vadd.vv v1, v2, v3
+            data.extend_from_slice(&0x022180D7u32.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_human_formatter_verbose_provenance() {
+        let data = riscv_elf_with_vector_code();
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new())
+            .unwrap()
+            .with_note(Note::warning("no section headers").with_context("ELF parsing"));
+        let path = PathBuf::from("/bin/test");
+
+        let verbose = HumanFormatter::verbose().format_payload(&payload, &path);
+        assert_eq!(
+            verbose,
+            concat!(
+                "File: /bin/test\n",
+                "  ISA:        riscv64 (RISC-V (64-bit))\n",
+                "  Bitwidth:   64-bit\n",
+                "  Endianness: little\n",
+                "  Confidence: 100.0%\n",
+                "  Variant:    RISC-V [double-float]\n",
+                "  Source:     FileFormat\n",
+                "  Format:     ELF\n",
+                "  Size:       128 bytes (scanned 128, describes 64)\n",
+                "  Extensions:\n",
+                "    Name  Category        Confidence  Source\n",
+                "    C     compressed          100.0%  format\n",
+                "    F     floating-point      100.0%  format\n",
+                "    D     floating-point      100.0%  format\n",
+                "    V     SIMD                100.0%  code\n",
+                "  Metadata:\n",
+                "    Key          Label               Value          Origin\n",
+                "    entry_point  Entry Point         0x0            elf\n",
+                "    flags        Flags               0x00000005     elf\n",
+                "    raw_machine  Machine Type        0x000000F3     elf\n",
+                "    osabi        OS ABI              UNIX System V  elf\n",
+                "    target_env   Target Environment  os-hosted      target_env\n",
+                "  Notes:\n",
+                "    [warn]   no section headers  (ELF parsing)\n",
+                "\n",
+            )
+        );
+
+        // Default mode keeps the one-line summaries
+        let normal = HumanFormatter::new().format_payload(&payload, &path);
+        assert!(normal.contains("  Extensions: C, F, D, V\n"));
+        assert!(normal.contains("  [warn] no section headers\n"));
+        assert!(!normal.contains("Origin"));
+    }
+
+    #[test]
+    fn test_json_formatter_utf8_path_has_no_file_bytes() {
+        let output = JsonFormatter::new().format_payload(&sample_payload(), Path::new("/bin/test"));
+        assert!(!output.contains("file_bytes"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_json_formatter_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        // "テスト.bin" in Shift-JIS
+        let name = b"\x83\x65\x83\x58\x83\x67.bin";
+        let path = dir.path().join(OsStr::from_bytes(name));
+        std::fs::write(&path, b"not a binary").unwrap();
+        assert!(std::fs::read(&path).is_ok());
+
+        let output = JsonFormatter::new().format_payload(&sample_payload(), &path);
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let bytes = hex::decode(json["file_bytes"].as_str().unwrap()).unwrap();
+        assert_eq!(bytes, path.as_os_str().as_bytes());
+        assert!(bytes.ends_with(name));
+        assert!(json["file"].as_str().unwrap().contains('\u{FFFD}'));
+    }
+
+    /// Payload whose confidences carry float noise from the computation.
+    fn awkward_payload() -> DetectionPayload {
+        let mut payload = DetectionPayload::new(
+            FormatDetection::raw(),
+            IsaClassification::from_heuristics(Isa::Arm, 32, Endianness::Little, 0.0),
+        );
+        payload.primary.confidence = 1.0 / 3.0;
+        let mut candidate = IsaCandidate::new(Isa::Arm, 32, Endianness::Little, 1234, 0.0);
+        candidate.confidence = 0.1 + 0.2;
+        payload.candidates.push(candidate);
+        let mut ext = ExtensionDetection::from_code("NEON", ExtensionCategory::Simd, 0.0);
+        ext.confidence = 2.0 / 3.0;
+        payload.with_extension(ext)
+    }
+
+    #[test]
+    fn test_number_formatting_is_stable() {
+        let payload = awkward_payload();
+        let path = PathBuf::from("fw.bin");
+
+        let json = JsonFormatter::compact().format_payload(&payload, &path);
+        assert!(json.contains("\"confidence\":0.3333,"));
+        assert!(json.contains(
+            "\"candidates\":[{\"isa\":\"arm\",\"bitwidth\":32,\"endianness\":\"little\",\
+             \"raw_score\":1234,\"confidence\":0.3}]"
+        ));
+        assert!(json.contains("\"name\":\"NEON\",\"category\":\"Simd\",\"confidence\":0.6667,"));
+
+        assert_eq!(
+            ShortFormatter::new().format_payload(&payload, &path),
+            "fw.bin\tarm\t32\tlittle\t33.3% [NEON]\n"
+        );
+        assert_eq!(
+            CandidatesFormatter::new().format_payload(&payload, &path),
+            "Candidates for fw.bin:\n  1. arm (32-bit, little) - score: 1234, confidence: 30.0%\n\n"
+        );
+        let human = HumanFormatter {
+            show_candidates: true,
+            ..HumanFormatter::new()
+        }
+        .format_payload(&payload, &path);
+        assert!(human.contains("  Confidence: 33.3%\n"));
+        assert!(human.contains("    1. arm (32-bit, little) - score: 1234, 30.0%\n"));
+    }
+
+    #[test]
+    fn test_number_helpers() {
+        assert_eq!(confidence_value(1.0 / 3.0), 0.3333);
+        assert_eq!(confidence_value(1.7), 1.0);
+        assert_eq!(confidence_value(f64::NAN), 0.0);
+        assert_eq!(confidence_percent(0.99996), "100.0%");
+        assert_eq!(score_value(10.0 / 3.0), 3.3);
+        assert_eq!(format_score(2.0 / 3.0), "0.7");
+        assert_eq!(
+            IsaCandidate::new(Isa::X86, 32, Endianness::Little, 1, 2.0 / 3.0).confidence,
+            0.6667
+        );
+    }
 }
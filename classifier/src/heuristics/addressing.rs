@@ -0,0 +1,221 @@
+//! Position-independent vs fixed-address inference for raw code.
+//!
+//! Loading a raw blob into an emulator needs to know whether it runs
+//! anywhere or only at the address it was linked for. Built on the
+//! [`constants`](super::constants) pass: code that computes its addresses
+//! from the program counter is position-independent, while absolute
+//! constants clustering inside one image-sized window betray the base the
+//! image was linked at.
+
+use crate::types::{Endianness, Isa, MetadataEntry, MetadataValue};
+
+use super::constants::{self, ConstantScan};
+
+/// Fewest references of the winning kind needed for a verdict.
+pub const MIN_REFERENCES: usize = 4;
+
+/// Granularity of the inferred base (one 4 KiB page).
+const BASE_ALIGN: u64 = 0x1000;
+
+/// How raw code addresses itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Addressing {
+    /// PC-relative address computations dominate
+    PositionIndependent {
+        /// PC-relative computations seen
+        pc_relative: usize,
+    },
+    /// Absolute references cluster around one load address
+    Fixed {
+        /// Inferred load address, rounded down to a page
+        base: u64,
+        /// Absolute references inside the image window
+        references: usize,
+    },
+}
+
+impl Addressing {
+    /// Inferred load address, for fixed-address code.
+    pub fn base(&self) -> Option<u64> {
+        match *self {
+            Addressing::Fixed { base, .. } => Some(base),
+            Addressing::PositionIndependent { .. } => None,
+        }
+    }
+
+    /// Human-readable verdict.
+    pub fn describe(&self) -> String {
+        match *self {
+            Addressing::PositionIndependent { .. } => {
+                "position-independent (PC-relative addressing dominates)".to_string()
+            }
+            Addressing::Fixed { base, references } => format!(
+                "fixed base address \u{2248} 0x{base:08X} ({references} absolute references clustered)"
+            ),
+        }
+    }
+
+    /// Metadata entries: the verdict, plus the base for fixed-address code.
+    pub fn to_metadata(&self) -> Vec<MetadataEntry> {
+        let mut entries = vec![MetadataEntry::custom(
+            "addressing",
+            MetadataValue::String(self.describe()),
+            "Addressing",
+        )
+        .with_origin("addressing")];
+        if let Some(base) = self.base() {
+            entries.push(
+                MetadataEntry::custom(
+                    "load_base",
+                    MetadataValue::Address(base),
+                    "Inferred Load Base",
+                )
+                .with_origin("addressing"),
+            );
+        }
+        entries
+    }
+}
+
+/// Infer how `data`, decoded as `isa`, addresses itself.
+///
+/// Returns `None` for unsupported ISAs and when neither kind of reference
+/// reaches [`MIN_REFERENCES`].
+pub fn analyze(data: &[u8], isa: Isa, endianness: Endianness) -> Option<Addressing> {
+    let scan = constants::collect(data, isa, endianness);
+    classify(&scan, data.len() as u64)
+}
+
+/// Decide from a constant scan of an image `image_len` bytes long.
+fn classify(scan: &ConstantScan, image_len: u64) -> Option<Addressing> {
+    let (base, references) = cluster(scan, image_len.max(BASE_ALIGN));
+
+    if references >= MIN_REFERENCES && references > scan.pc_relative {
+        Some(Addressing::Fixed { base, references })
+    } else if scan.pc_relative >= MIN_REFERENCES {
+        Some(Addressing::PositionIndependent {
+            pc_relative: scan.pc_relative,
+        })
+    } else {
+        None
+    }
+}
+
+/// Densest `window`-wide run of address-like constants: its page-aligned
+/// start and size. Ties go to the lowest window.
+fn cluster(scan: &ConstantScan, window: u64) -> (u64, usize) {
+    let mut addresses: Vec<u64> = scan.addresses().collect();
+    addresses.sort_unstable();
+
+    let mut best = (0, 0);
+    let mut start = 0;
+    for end in 0..addresses.len() {
+        while addresses[end] - addresses[start] >= window {
+            start += 1;
+        }
+        let count = end - start + 1;
+        if count > best.1 {
+            best = (addresses[start] & !(BASE_ALIGN - 1), count);
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_le(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    /// AArch64 function reaching its data through `adrp` + `add`/`ldr`.
+    fn pic_aarch64() -> Vec<u8> {
+        let function = [
+            0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+            0x9000_0080, // adrp x0, page
+            0x9100_4000, // add x0, x0, #16
+            0x9000_0081, // adrp x1, page
+            0xF940_0421, // ldr x1, [x1, #8]
+            0x9400_0010, // bl
+            0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+            0xD65F_03C0, // ret
+        ];
+        words_le(&function.repeat(16))
+    }
+
+    /// STM32 (Cortex-M) code linked at the start of flash, 0x0800_0000:
+    /// literal loads of its own function and data addresses, plus a few
+    /// peripheral registers.
+    fn stm32_flash() -> Vec<u8> {
+        let mut code = Vec::new();
+        for n in 0..32u32 {
+            // push {r4, lr}; ldr r0, [pc, #8]; ldr r1, [pc, #8]; bl; pop {r4, pc}
+            // followed by the literal pool
+            for hw in [0xB510u16, 0x4802, 0x4902, 0xF000, 0xF800, 0xBD10] {
+                code.extend(hw.to_le_bytes());
+            }
+            code.extend((0x0800_0101 + n * 0x20).to_le_bytes());
+            code.extend(
+                if n % 8 == 0 {
+                    0x4002_1000u32 // RCC
+                } else {
+                    0x0800_0400 + n * 4
+                }
+                .to_le_bytes(),
+            );
+        }
+        code
+    }
+
+    #[test]
+    fn test_pic_aarch64() {
+        let code = pic_aarch64();
+        let addressing = analyze(&code, Isa::AArch64, Endianness::Little).unwrap();
+        assert_eq!(
+            addressing,
+            Addressing::PositionIndependent { pc_relative: 32 }
+        );
+        assert_eq!(addressing.base(), None);
+        assert!(addressing.describe().starts_with("position-independent"));
+    }
+
+    #[test]
+    fn test_stm32_fixed_base() {
+        let code = stm32_flash();
+        let addressing = analyze(&code, Isa::Arm, Endianness::Little).unwrap();
+        let base = addressing.base().unwrap();
+        // STM32 flash: 0x0800_0000 up to 2 MiB
+        assert!((0x0800_0000..0x0820_0000).contains(&base), "0x{base:X}");
+        let Addressing::Fixed { references, .. } = addressing else {
+            panic!("{addressing:?}");
+        };
+        assert!(references >= 56, "{references}");
+        assert!(addressing.describe().contains("0x08000000"));
+
+        let metadata = addressing.to_metadata();
+        assert_eq!(metadata.len(), 2);
+        assert!(matches!(metadata[1].value, MetadataValue::Address(b) if b == base));
+    }
+
+    #[test]
+    fn test_too_little_evidence() {
+        let scan = ConstantScan {
+            absolute: vec![0x0800_0000, 0x0800_0010],
+            pc_relative: 1,
+            ..Default::default()
+        };
+        assert_eq!(classify(&scan, 4096), None);
+        assert_eq!(analyze(&[0u8; 256], Isa::X86_64, Endianness::Little), None);
+    }
+
+    #[test]
+    fn test_cluster_ignores_small_values() {
+        let scan = ConstantScan {
+            absolute: vec![4, 8, 0x2000_0010, 0x2000_0020, u64::MAX],
+            ..Default::default()
+        };
+        let (base, count) = cluster(&scan, 0x1000);
+        assert_eq!((base, count), (0x2000_0000, 2));
+    }
+}
@@ -0,0 +1,90 @@
+//! Byte-swapped views of raw input.
+//!
+//! Flash dumps read through 16-bit-wide programmers often come out with
+//! every byte pair (or every 32-bit group) reversed relative to execution
+//! order. [`SwappedView`] undoes that one range at a time, so the scorers
+//! can be run over a swapped view without copying the whole input.
+
+/// A fixed-width byte-order reversal applied to every group of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteSwap {
+    /// Bytes exchanged within each 16-bit pair (`AB CD` -> `BA DC`)
+    Swap16,
+    /// Bytes reversed within each 32-bit group (`ABCD` -> `DCBA`)
+    Swap32,
+}
+
+impl ByteSwap {
+    /// All transformations tried by the swap-detection pass.
+    pub const ALL: [ByteSwap; 2] = [ByteSwap::Swap16, ByteSwap::Swap32];
+
+    /// Group width in bytes.
+    pub fn width(self) -> usize {
+        match self {
+            ByteSwap::Swap16 => 2,
+            ByteSwap::Swap32 => 4,
+        }
+    }
+
+    /// Short description, e.g. "16-bit byte-swapped".
+    pub fn name(self) -> &'static str {
+        match self {
+            ByteSwap::Swap16 => "16-bit byte-swapped",
+            ByteSwap::Swap32 => "32-bit byte-swapped",
+        }
+    }
+}
+
+/// Lazily byte-swapped view over a slice.
+///
+/// Groups are anchored at offset 0 of the underlying slice; a trailing
+/// partial group is passed through unchanged.
+#[derive(Debug, Clone, Copy)]
+pub struct SwappedView<'a> {
+    data: &'a [u8],
+    swap: ByteSwap,
+}
+
+impl<'a> SwappedView<'a> {
+    /// Wrap `data`.
+    pub fn new(data: &'a [u8], swap: ByteSwap) -> Self {
+        Self { data, swap }
+    }
+
+    /// The transformation this view applies.
+    pub fn swap(&self) -> ByteSwap {
+        self.swap
+    }
+
+    /// Swapped copy of `data[start..end]`.
+    ///
+    /// `start` is rounded down to a group boundary so the groups line up with
+    /// the whole-input ones; the returned bytes begin at that boundary.
+    pub fn range(&self, start: usize, end: usize) -> Vec<u8> {
+        let width = self.swap.width();
+        let start = start - start % width;
+        let mut bytes = self.data[start..end].to_vec();
+        for group in bytes.chunks_exact_mut(width) {
+            group.reverse();
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swapped_view_ranges() {
+        let data = [0u8, 1, 2, 3, 4, 5, 6, 7, 8];
+        let view = SwappedView::new(&data, ByteSwap::Swap16);
+        assert_eq!(view.range(0, 9), [1, 0, 3, 2, 5, 4, 7, 6, 8]);
+        // An odd start snaps back to the pair boundary
+        assert_eq!(view.range(3, 6), [3, 2, 5, 4]);
+
+        let view = SwappedView::new(&data, ByteSwap::Swap32);
+        assert_eq!(view.range(0, 9), [3, 2, 1, 0, 7, 6, 5, 4, 8]);
+        assert_eq!(view.range(5, 8), [7, 6, 5, 4]);
+    }
+}
@@ -0,0 +1,418 @@
+//! Address-constant collection over raw code.
+//!
+//! Code names an address in one of two ways: as an absolute constant (a
+//! literal-pool word, a `movw`/`movt` or `lui`/`addi` pair) or relative to
+//! the program counter (`adr`/`adrp`, `add rd, pc`, `auipc`). [`collect`]
+//! walks the code of the winning ISA once and records both, so analyses
+//! that reason about addresses (load-address inference in
+//! [`super::addressing`], pointer widths in [`super::pointer_width`])
+//! share the same pass.
+//!
+//! Supported ISAs: ARM (A32 and Thumb), AArch64 and RISC-V. Others yield an
+//! empty [`ConstantScan`].
+
+use crate::types::{Endianness, Isa};
+
+/// Address constants found in a code region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstantScan {
+    /// Absolute constants, in scan order
+    pub absolute: Vec<u64>,
+    /// PC-relative address computations
+    pub pc_relative: usize,
+    /// Absolute constants loaded into or built in 32-bit registers on a
+    /// 64-bit ISA and then used to address memory (AArch64 `ldr wN`
+    /// literals and `movz`/`movk wN` chains followed by a load or store
+    /// based on `xN` or indexed by `wN, uxtw`)
+    pub narrow: usize,
+}
+
+impl ConstantScan {
+    /// Total address references of either kind.
+    pub fn references(&self) -> usize {
+        self.absolute.len() + self.pc_relative
+    }
+
+    /// Absolute constants that look like addresses rather than offsets,
+    /// counts, masks or all-ones sentinels.
+    pub fn addresses(&self) -> impl Iterator<Item = u64> + '_ {
+        self.absolute
+            .iter()
+            .copied()
+            .filter(|&c| c >= ADDRESS_FLOOR && c != u64::from(u32::MAX) && c != u64::MAX)
+    }
+}
+
+/// Smallest constant taken for an address (one 4 KiB page).
+const ADDRESS_FLOOR: u64 = 0x1000;
+
+/// Instructions searched after a narrow AArch64 constant for a load or
+/// store that uses it as an address.
+const ADDRESS_USE_WINDOW: usize = 8;
+
+/// Collect the address constants of `data` decoded as `isa`.
+pub fn collect(data: &[u8], isa: Isa, endianness: Endianness) -> ConstantScan {
+    let le = endianness != Endianness::Big;
+    match isa {
+        Isa::Arm => {
+            // Raw blobs do not say which instruction set they use; keep the
+            // decoding that explains more of the input.
+            let thumb = scan_thumb(data, le);
+            let arm = scan_a32(data, le);
+            if thumb.references() >= arm.references() {
+                thumb
+            } else {
+                arm
+            }
+        }
+        Isa::AArch64 => scan_aarch64(data, le),
+        Isa::RiscV32 => scan_riscv(data, false),
+        Isa::RiscV64 => scan_riscv(data, true),
+        _ => ConstantScan::default(),
+    }
+}
+
+fn read16(data: &[u8], offset: usize, le: bool) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    let bytes = [bytes[0], bytes[1]];
+    Some(if le {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn read32(data: &[u8], offset: usize, le: bool) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    Some(if le {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn read64(data: &[u8], offset: usize, le: bool) -> Option<u64> {
+    let low = u64::from(read32(data, offset, le)?);
+    let high = u64::from(read32(data, offset + 4, le)?);
+    Some(if le {
+        high << 32 | low
+    } else {
+        low << 32 | high
+    })
+}
+
+/// Literal-pool target `base ± imm`, or `None` when it falls before the data.
+fn literal_target(base: usize, imm: usize, add: bool) -> Option<usize> {
+    if add {
+        base.checked_add(imm)
+    } else {
+        base.checked_sub(imm)
+    }
+}
+
+/// Thumb/Thumb-2: `ldr rt, [pc, #imm]`, `movw`/`movt`, `adr` and `add rd, pc`.
+fn scan_thumb(data: &[u8], le: bool) -> ConstantScan {
+    let mut scan = ConstantScan::default();
+    // Low halves from `movw`, by destination register
+    let mut movw = [None; 16];
+    let mut i = 0;
+
+    while let Some(hw) = read16(data, i, le) {
+        if hw >> 11 < 0x1D {
+            if hw & 0xF800 == 0x4800 {
+                // LDR Rt, [PC, #imm8*4]
+                let target = ((i + 4) & !3) + usize::from(hw & 0xFF) * 4;
+                if let Some(value) = read32(data, target, le) {
+                    scan.absolute.push(u64::from(value));
+                }
+            } else if hw & 0xF800 == 0xA000 || hw & 0xFF78 == 0x4478 {
+                // ADR Rd, label / ADD Rdn, PC
+                scan.pc_relative += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        let Some(hw2) = read16(data, i + 2, le) else {
+            break;
+        };
+        if hw & 0xFBF0 == 0xF240 || hw & 0xFBF0 == 0xF2C0 {
+            // MOVW / MOVT Rd, #imm16
+            let imm16 = (u32::from(hw & 0xF) << 12)
+                | (u32::from((hw >> 10) & 1) << 11)
+                | (u32::from((hw2 >> 12) & 7) << 8)
+                | u32::from(hw2 & 0xFF);
+            let rd = usize::from((hw2 >> 8) & 0xF);
+            if hw & 0xFBF0 == 0xF240 {
+                movw[rd] = Some(imm16);
+            } else if let Some(low) = movw[rd].take() {
+                scan.absolute.push(u64::from(imm16 << 16 | low));
+            }
+        } else if hw & 0xFF7F == 0xF85F {
+            // LDR.W Rt, [PC, #±imm12]
+            let imm = usize::from(hw2 & 0xFFF);
+            let target = literal_target((i + 4) & !3, imm, hw & 0x80 != 0);
+            if let Some(value) = target.and_then(|t| read32(data, t, le)) {
+                scan.absolute.push(u64::from(value));
+            }
+        }
+        i += 4;
+    }
+    scan
+}
+
+/// A32: `ldr rt, [pc, #imm]`, `movw`/`movt`, `adr` and `add rd, pc, rm`.
+fn scan_a32(data: &[u8], le: bool) -> ConstantScan {
+    let mut scan = ConstantScan::default();
+    let mut movw = [None; 16];
+
+    for i in (0..data.len().saturating_sub(3)).step_by(4) {
+        let Some(w) = read32(data, i, le) else {
+            break;
+        };
+        if w >> 28 == 0xF {
+            // Unconditional space
+            continue;
+        }
+        let rd = ((w >> 12) & 0xF) as usize;
+        if w & 0x0F7F_0000 == 0x051F_0000 {
+            // LDR Rt, [PC, #±imm12]
+            let imm = (w & 0xFFF) as usize;
+            let target = literal_target(i + 8, imm, w & 0x0080_0000 != 0);
+            if let Some(value) = target.and_then(|t| read32(data, t, le)) {
+                scan.absolute.push(u64::from(value));
+            }
+        } else if w & 0x0FF0_0000 == 0x0300_0000 {
+            // MOVW Rd, #imm16
+            movw[rd] = Some(((w >> 4) & 0xF000) | (w & 0xFFF));
+        } else if w & 0x0FF0_0000 == 0x0340_0000 {
+            // MOVT Rd, #imm16
+            if let Some(low) = movw[rd].take() {
+                let high = ((w >> 4) & 0xF000) | (w & 0xFFF);
+                scan.absolute.push(u64::from(high << 16 | low));
+            }
+        } else if matches!(w & 0x0FFF_0000, 0x028F_0000 | 0x024F_0000)
+            || w & 0x0FFF_0010 == 0x008F_0000
+        {
+            // ADR (ADD/SUB Rd, PC, #imm) / ADD Rd, PC, Rm
+            scan.pc_relative += 1;
+        }
+    }
+    scan
+}
+
+/// AArch64: `ldr` literal, `movz`/`movk` chains and `adrp` + `add`/`ldr`.
+fn scan_aarch64(data: &[u8], le: bool) -> ConstantScan {
+    let mut scan = ConstantScan::default();
+    // Value built so far by a `movz`/`movk` chain, and whether it is built
+    // in a `W` register, by destination register
+    let mut chain: [Option<(u64, bool)>; 32] = [None; 32];
+
+    for i in (0..data.len().saturating_sub(3)).step_by(4) {
+        let Some(w) = read32(data, i, le) else {
+            break;
+        };
+        let rd = (w & 0x1F) as usize;
+        if w & 0xBF00_0000 == 0x1800_0000 {
+            // LDR Wt/Xt, label
+            let imm19 = (w >> 5) & 0x7_FFFF;
+            let offset = ((imm19 << 13) as i32 >> 13) as isize * 4;
+            let target = i.checked_add_signed(offset);
+            let value = if w & 0x4000_0000 != 0 {
+                target.and_then(|t| read64(data, t, le))
+            } else {
+                let value = target.and_then(|t| read32(data, t, le)).map(u64::from);
+                scan.narrow += usize::from(value.is_some() && addresses_memory(data, i, rd, le));
+                value
+            };
+            scan.absolute.extend(value);
+        } else if w & 0x9F00_0000 == 0x9000_0000 {
+            // ADRP Xd, page, consumed by the next instruction
+            let next = read32(data, i + 4, le);
+            let consumed = next.is_some_and(|n| {
+                let add_imm = n & 0x7F80_0000 == 0x1100_0000;
+                let load_store = n & 0x3B00_0000 == 0x3900_0000;
+                (add_imm || load_store) && ((n >> 5) & 0x1F) as usize == rd
+            });
+            if consumed {
+                scan.pc_relative += 1;
+            }
+        } else if w & 0x7F80_0000 == 0x5280_0000 || w & 0x7F80_0000 == 0x7280_0000 {
+            // MOVZ / MOVK Xd|Wd, #imm16, LSL #(16*hw)
+            let wide = w & 0x8000_0000 != 0;
+            let shift = 16 * ((w >> 21) & 3);
+            let part = u64::from((w >> 5) & 0xFFFF) << shift;
+            if w & 0x7F80_0000 == 0x5280_0000 {
+                chain[rd] = Some((part, !wide));
+            } else if let Some((value, narrow)) = chain[rd].as_mut() {
+                *value |= part;
+                let next = read32(data, i + 4, le);
+                let continues = next.is_some_and(|n| {
+                    n & 0xFF80_0000 == w & 0xFF80_0000 && (n & 0x1F) as usize == rd
+                });
+                if !continues {
+                    scan.absolute.push(*value);
+                    scan.narrow += usize::from(*narrow && addresses_memory(data, i, rd, le));
+                    chain[rd] = None;
+                }
+            }
+        }
+    }
+    scan
+}
+
+/// Whether one of the instructions after offset `i` loads or stores
+/// through register `reg`: as the base (`[xN, ...]`, pairs included) or as
+/// a zero-extended index (`[xM, wN, uxtw]`).
+///
+/// LP64 code builds plenty of 32-bit values in `W` registers (hash seeds,
+/// masks, sizes); only dereferenced ones are ILP32 pointers.
+fn addresses_memory(data: &[u8], i: usize, reg: usize, le: bool) -> bool {
+    (1..=ADDRESS_USE_WINDOW)
+        .map_while(|k| read32(data, i + 4 * k, le))
+        .any(|n| {
+            let base = ((n >> 5) & 0x1F) as usize == reg;
+            let single = n & 0x3B00_0000 == 0x3900_0000 || n & 0x3B20_0000 == 0x3800_0000;
+            let pair = n & 0x3A00_0000 == 0x2800_0000;
+            let uxtw_index = n & 0x3B20_EC00 == 0x3820_4800 && ((n >> 16) & 0x1F) as usize == reg;
+            ((single || pair) && base) || uxtw_index
+        })
+}
+
+/// RISC-V: `lui` (+ `addi`/load/store/`jalr` offset) and `auipc` pairs.
+///
+/// Compressed instructions are stepped over, not decoded.
+fn scan_riscv(data: &[u8], rv64: bool) -> ConstantScan {
+    let mut scan = ConstantScan::default();
+    let mut i = 0;
+
+    while let Some(w) = read32(data, i, true) {
+        if w & 3 != 3 {
+            i += 2;
+            continue;
+        }
+        let opcode = w & 0x7F;
+        let rd = (w >> 7) & 0x1F;
+        if rd != 0 && (opcode == 0x37 || opcode == 0x17) {
+            // Second half of the pair: an I- or S-type using rd as its base
+            let next = read32(data, i + 4, true).filter(|n| (n >> 15) & 0x1F == rd);
+            let low = next.and_then(|n| match n & 0x7F {
+                // ADDI, loads, JALR
+                0x13 if (n >> 12) & 7 == 0 => Some((n as i32) >> 20),
+                0x03 | 0x67 => Some((n as i32) >> 20),
+                // Stores
+                0x23 => Some((((n & 0xFE00_0000) as i32) >> 20) | ((n >> 7) & 0x1F) as i32),
+                _ => None,
+            });
+
+            if opcode == 0x17 {
+                if low.is_some() {
+                    scan.pc_relative += 1;
+                }
+            } else {
+                let value = i64::from((w & 0xFFFF_F000) as i32) + i64::from(low.unwrap_or(0));
+                scan.absolute.push(if rv64 {
+                    value as u64
+                } else {
+                    u64::from(value as u32)
+                });
+            }
+        }
+        i += 4;
+    }
+    scan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_le(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_thumb_literal_and_movw_movt() {
+        let mut code = Vec::new();
+        // ldr r0, [pc, #8] -> the word at offset 12
+        code.extend(0x4802u16.to_le_bytes());
+        // movw r1, #0x5678; movt r1, #0x1234
+        code.extend([0x45, 0xF2, 0x78, 0x61]);
+        code.extend([0xC1, 0xF2, 0x34, 0x21]);
+        // add r2, pc
+        code.extend(0x447Au16.to_le_bytes());
+        code.extend(0x0800_0101u32.to_le_bytes());
+
+        let scan = scan_thumb(&code, true);
+        assert_eq!(scan.absolute, vec![0x0800_0101, 0x1234_5678]);
+        assert_eq!(scan.pc_relative, 1);
+    }
+
+    #[test]
+    fn test_aarch64_literal_adrp_and_movk() {
+        let code = words_le(&[
+            0x5800_0060, // ldr x0, #12
+            0x9000_0001, // adrp x1, page
+            0x9100_0021, // add x1, x1, #0
+            0x0000_1000, // literal low
+            0x0000_0000, // literal high
+            0xD290_0002, // movz x2, #0x8000
+            0xF2A0_0202, // movk x2, #0x10, lsl #16
+        ]);
+        let scan = collect(&code, Isa::AArch64, Endianness::Little);
+        assert_eq!(scan.absolute, vec![0x1000, 0x0010_8000]);
+        assert_eq!(scan.pc_relative, 1);
+    }
+
+    #[test]
+    fn test_aarch64_narrow_constants() {
+        let code = words_le(&[
+            0x1800_00A0, // ldr w0, #20
+            0x5290_0002, // movz w2, #0x8000
+            0x72A0_0202, // movk w2, #0x10, lsl #16
+            0xB940_0001, // ldr w1, [x0]
+            0xB862_4823, // ldr w3, [x1, w2, uxtw]
+            0x0040_1000, // literal
+        ]);
+        let scan = collect(&code, Isa::AArch64, Endianness::Little);
+        assert_eq!(scan.absolute, vec![0x0040_1000, 0x0010_8000]);
+        assert_eq!(scan.narrow, 2);
+        assert_eq!(scan.addresses().count(), 2);
+    }
+
+    #[test]
+    fn test_aarch64_w_register_values_need_a_memory_use() {
+        let code = words_le(&[
+            0x5290_0002, // movz w2, #0x8000
+            0x72A0_0202, // movk w2, #0x10, lsl #16
+            0x4A02_0000, // eor w0, w0, w2
+            0xB940_0021, // ldr w1, [x1]
+        ]);
+        let scan = collect(&code, Isa::AArch64, Endianness::Little);
+        assert_eq!(scan.absolute, vec![0x0010_8000]);
+        assert_eq!(scan.narrow, 0);
+    }
+
+    #[test]
+    fn test_riscv_lui_and_auipc() {
+        let code = words_le(&[
+            0x8000_02B7, // lui t0, 0x80000
+            0x1002_8293, // addi t0, t0, 256
+            0x0000_0317, // auipc t1, 0
+            0x0103_0313, // addi t1, t1, 16
+        ]);
+        let scan = collect(&code, Isa::RiscV32, Endianness::Little);
+        assert_eq!(scan.absolute, vec![0x8000_0100]);
+        assert_eq!(scan.pc_relative, 1);
+        // RV64 sign-extends lui
+        let scan = collect(&code, Isa::RiscV64, Endianness::Little);
+        assert_eq!(scan.absolute, vec![0xFFFF_FFFF_8000_0100]);
+    }
+
+    #[test]
+    fn test_unsupported_isa_is_empty() {
+        let scan = collect(&[0x90; 64], Isa::X86_64, Endianness::Little);
+        assert_eq!(scan, ConstantScan::default());
+    }
+}
@@ -0,0 +1,226 @@
+//! Named instruction idioms behind a heuristic verdict.
+//!
+//! Scorers return a total, not the matches that made it up. To say *what*
+//! identified an ISA, this module lists the distinctive fixed encodings the
+//! scorers weigh most (returns, frame set-up and tear-down, NOPs) and counts
+//! them for the winning ISA only. ISAs without entries are reported by
+//! their total alone.
+
+use crate::types::{Endianness, Isa};
+
+/// A fixed encoding, possibly with don't-care bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Idiom {
+    /// ISAs the encoding belongs to
+    pub isas: &'static [Isa],
+    /// Assembly shown in evidence
+    pub name: &'static str,
+    /// Encoding length in bytes (1-4)
+    pub len: usize,
+    /// Alignment of the slots searched, in bytes
+    pub align: usize,
+    /// Bits that must match, as read in the ISA's byte order; x86 byte
+    /// sequences are read little-endian
+    pub mask: u32,
+    /// Required value of the masked bits
+    pub value: u32,
+}
+
+impl Idiom {
+    const fn exact(isas: &'static [Isa], name: &'static str, len: usize, value: u32) -> Self {
+        Self::masked(isas, name, len, len, u32::MAX, value)
+    }
+
+    const fn masked(
+        isas: &'static [Isa],
+        name: &'static str,
+        len: usize,
+        align: usize,
+        mask: u32,
+        value: u32,
+    ) -> Self {
+        Self {
+            isas,
+            name,
+            len,
+            align,
+            mask,
+            value,
+        }
+    }
+}
+
+const X86: &[Isa] = &[Isa::X86];
+const X86_64: &[Isa] = &[Isa::X86_64];
+const X86_ANY: &[Isa] = &[Isa::X86, Isa::X86_64];
+const AARCH64: &[Isa] = &[Isa::AArch64];
+const ARM: &[Isa] = &[Isa::Arm];
+const RISCV: &[Isa] = &[Isa::RiscV32, Isa::RiscV64];
+const MIPS: &[Isa] = &[Isa::Mips, Isa::Mips64];
+const MIPS64: &[Isa] = &[Isa::Mips64];
+const PPC: &[Isa] = &[Isa::Ppc, Isa::Ppc64];
+const SPARC: &[Isa] = &[Isa::Sparc, Isa::Sparc64];
+
+/// Idioms per ISA, in no particular order.
+pub const IDIOMS: &[Idiom] = &[
+    Idiom::masked(
+        X86_64,
+        "push rbp; mov rbp, rsp",
+        4,
+        1,
+        u32::MAX,
+        0xE589_4855,
+    ),
+    Idiom::masked(X86, "push ebp; mov ebp, esp", 3, 1, u32::MAX, 0x00E5_8955),
+    Idiom::masked(X86_64, "endbr64", 4, 1, u32::MAX, 0xFA1E_0FF3),
+    Idiom::masked(X86, "endbr32", 4, 1, u32::MAX, 0xFB1E_0FF3),
+    Idiom::masked(X86_ANY, "pop rbp/ebp; ret", 2, 1, u32::MAX, 0xC35D),
+    Idiom::masked(X86_ANY, "leave; ret", 2, 1, u32::MAX, 0xC3C9),
+    Idiom::exact(AARCH64, "ret", 4, 0xD65F_03C0),
+    Idiom::masked(
+        AARCH64,
+        "stp x29, x30, [sp, #-N]!",
+        4,
+        4,
+        0xFFC0_7FFF,
+        0xA980_7BFD,
+    ),
+    Idiom::masked(
+        AARCH64,
+        "ldp x29, x30, [sp], #N",
+        4,
+        4,
+        0xFFC0_7FFF,
+        0xA8C0_7BFD,
+    ),
+    Idiom::exact(AARCH64, "mov x29, sp", 4, 0x9100_03FD),
+    Idiom::exact(AARCH64, "paciasp", 4, 0xD503_233F),
+    Idiom::exact(AARCH64, "nop", 4, 0xD503_201F),
+    Idiom::exact(ARM, "bx lr", 4, 0xE12F_FF1E),
+    Idiom::masked(ARM, "push {..., lr}", 4, 4, 0xFFFF_4000, 0xE92D_4000),
+    Idiom::masked(ARM, "pop {..., pc}", 4, 4, 0xFFFF_8000, 0xE8BD_8000),
+    Idiom::exact(ARM, "bx lr (Thumb)", 2, 0x4770),
+    Idiom::masked(ARM, "push {..., lr} (Thumb)", 2, 2, 0xFF00, 0xB500),
+    Idiom::masked(ARM, "pop {..., pc} (Thumb)", 2, 2, 0xFF00, 0xBD00),
+    Idiom::masked(RISCV, "ret", 4, 2, u32::MAX, 0x0000_8067),
+    Idiom::exact(RISCV, "c.ret", 2, 0x8082),
+    Idiom::masked(RISCV, "addi sp, sp, -N", 4, 2, 0x800F_FFFF, 0x8001_0113),
+    Idiom::exact(MIPS, "jr ra", 4, 0x03E0_0008),
+    Idiom::masked(MIPS, "addiu sp, sp, -N", 4, 4, 0xFFFF_8000, 0x27BD_8000),
+    Idiom::masked(MIPS, "sw ra, N(sp)", 4, 4, 0xFFFF_0000, 0xAFBF_0000),
+    Idiom::masked(MIPS64, "sd ra, N(sp)", 4, 4, 0xFFFF_0000, 0xFFBF_0000),
+    Idiom::masked(MIPS64, "daddiu sp, sp, -N", 4, 4, 0xFFFF_8000, 0x67BD_8000),
+    Idiom::exact(PPC, "blr", 4, 0x4E80_0020),
+    Idiom::exact(PPC, "mflr r0", 4, 0x7C08_02A6),
+    Idiom::exact(PPC, "mtlr r0", 4, 0x7C08_03A6),
+    Idiom::masked(
+        &[Isa::Ppc],
+        "stwu r1, -N(r1)",
+        4,
+        4,
+        0xFFFF_8000,
+        0x9421_8000,
+    ),
+    Idiom::masked(
+        &[Isa::Ppc64],
+        "stdu r1, -N(r1)",
+        4,
+        4,
+        0xFFFF_8003,
+        0xF821_8001,
+    ),
+    Idiom::exact(SPARC, "retl", 4, 0x81C3_E008),
+    Idiom::exact(SPARC, "ret", 4, 0x81C7_E008),
+    Idiom::masked(SPARC, "save %sp, -N, %sp", 4, 4, 0xFFFF_E000, 0x9DE3_A000),
+    Idiom::exact(SPARC, "restore", 4, 0x81E8_0000),
+    Idiom::exact(&[Isa::S390x], "br %r14", 2, 0x07FE),
+    Idiom::masked(
+        &[Isa::S390x],
+        "stmg %r6, %r15, N(%r15)",
+        4,
+        2,
+        0xFFFF_F000,
+        0xEB6F_F000,
+    ),
+    Idiom::exact(&[Isa::LoongArch64], "ret", 4, 0x4C00_0020),
+    Idiom::exact(&[Isa::Hexagon], "dealloc_return", 4, 0x961E_C01E),
+    Idiom::masked(
+        &[Isa::Hexagon],
+        "allocframe",
+        4,
+        4,
+        0xFFFF_E000,
+        0xA09D_C000,
+    ),
+    Idiom::exact(&[Isa::Msp430], "ret", 2, 0x4130),
+    Idiom::exact(&[Isa::Msp430], "reti", 2, 0x1300),
+    Idiom::exact(&[Isa::Avr], "ret", 2, 0x9508),
+    Idiom::exact(&[Isa::Avr], "reti", 2, 0x9518),
+    Idiom::exact(&[Isa::M68k], "rts", 2, 0x4E75),
+    Idiom::exact(&[Isa::M68k], "link a6, #-N", 2, 0x4E56),
+    Idiom::exact(&[Isa::Sh], "rts", 2, 0x000B),
+    Idiom::exact(&[Isa::Sh], "sts.l pr, @-r15", 2, 0x4F22),
+];
+
+/// Occurrences of `isa`'s idioms in `data`, most frequent first; idioms
+/// that never occur are left out.
+pub fn count(data: &[u8], isa: Isa, endianness: Endianness) -> Vec<(&'static str, usize)> {
+    let mut counts: Vec<(&'static str, usize)> = IDIOMS
+        .iter()
+        .filter(|idiom| idiom.isas.contains(&isa))
+        .map(|idiom| (idiom.name, occurrences(data, idiom, isa, endianness)))
+        .filter(|&(_, n)| n > 0)
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+    counts
+}
+
+fn occurrences(data: &[u8], idiom: &Idiom, isa: Isa, endianness: Endianness) -> usize {
+    let little = matches!(isa, Isa::X86 | Isa::X86_64) || endianness != Endianness::Big;
+    (0..data.len().saturating_sub(idiom.len - 1))
+        .step_by(idiom.align)
+        .filter(|&i| {
+            let bytes = &data[i..i + idiom.len];
+            let word = if little {
+                bytes.iter().rev().fold(0u32, |w, &b| w << 8 | u32::from(b))
+            } else {
+                bytes.iter().fold(0u32, |w, &b| w << 8 | u32::from(b))
+            };
+            word & idiom.mask == idiom.value
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_only_the_requested_isa() {
+        let code: Vec<u8> = [0xA9BF_7BFDu32, 0x9100_03FD, 0xA8C1_7BFD, 0xD65F_03C0]
+            .repeat(4)
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect();
+        let counts = count(&code, Isa::AArch64, Endianness::Little);
+        assert_eq!(counts.len(), 4);
+        assert!(counts.iter().all(|&(_, n)| n == 4));
+        assert!(counts.iter().any(|&(name, _)| name == "ret"));
+        assert!(count(&code, Isa::Ppc, Endianness::Big).is_empty());
+    }
+
+    #[test]
+    fn test_byte_order_and_alignment() {
+        // blr, big-endian, then at an unaligned offset
+        let mut code = 0x4E80_0020u32.to_be_bytes().to_vec();
+        code.extend_from_slice(&[0, 0x4E, 0x80, 0x00, 0x20, 0, 0, 0]);
+        assert_eq!(count(&code, Isa::Ppc, Endianness::Big), [("blr", 1)]);
+        assert!(count(&code, Isa::Ppc, Endianness::Little).is_empty());
+
+        // x86 sequences are byte aligned
+        let code = [0x90, 0x55, 0x48, 0x89, 0xE5, 0x5D, 0xC3];
+        let counts = count(&code, Isa::X86_64, Endianness::Little);
+        assert!(counts.contains(&("push rbp; mov rbp, rsp", 1)));
+        assert!(counts.contains(&("pop rbp/ebp; ret", 1)));
+    }
+}
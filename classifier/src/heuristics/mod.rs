@@ -4,14 +4,24 @@
 //! to identify the instruction set architecture when no file format
 //! header is present.
 
+pub mod addressing;
+pub mod byteswap;
+pub mod constants;
+pub mod idioms;
+pub mod pointer_width;
 pub mod scorer;
+pub mod verify;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+use crate::architectures::{arm, padding};
 use crate::error::{ClassifierError, Result};
 use crate::types::{
-    ClassificationResult, ClassificationSource, ClassifierOptions, Endianness, FileFormat, Isa,
+    ClassificationResult, ClassificationSource, ClassifierOptions, Endianness, Extension,
+    FileFormat, Isa, IsaCandidate, IsaFamily, MetadataEntry, MetadataValue, SampleMode, Variant,
 };
+use byteswap::{ByteSwap, SwappedView};
 
 pub use scorer::*;
 
@@ -63,9 +73,17 @@ pub const SUPPORTED_ARCHITECTURES: &[(Isa, &str)] = &[
     (Isa::K78k0r, "NEC 78K0R"),
     (Isa::S12z, "NXP/Freescale S12Z"),
     (Isa::Fr30, "Fujitsu FR30"),
+    (Isa::Frv, "Fujitsu FR-V"),
+    (Isa::VideoCore5, "Broadcom VideoCore"),
+    (Isa::TileGx, "Tilera TILE-Gx"),
     (Isa::Fr80, "Fujitsu FR80"),
     (Isa::PpcVle, "PowerPC VLE"),
     (Isa::TiC6000, "TI TMS320C6000"),
+    (Isa::Pic, "Microchip PIC"),
+    (Isa::Pic24, "Microchip PIC24/dsPIC"),
+    (Isa::Z80, "Zilog Z80"),
+    (Isa::Mcs6502, "MOS 6502"),
+    (Isa::W65816, "WDC 65816"),
 ];
 
 /// Result of heuristic scoring for a single architecture.
@@ -73,14 +91,24 @@ pub const SUPPORTED_ARCHITECTURES: &[(Isa, &str)] = &[
 pub struct ArchitectureScore {
     /// The ISA being scored
     pub isa: Isa,
-    /// Raw score (sum of pattern matches)
+    /// Raw score (sum of pattern matches); grows with the amount of input
     pub raw_score: i64,
     /// Normalized confidence (0.0 - 1.0)
     pub confidence: f64,
+    /// Score on the common scale candidates are ranked by: points per KiB
+    /// scanned (see [`scorer::normalize`])
+    pub normalized: f64,
     /// Detected endianness
     pub endianness: Endianness,
     /// Bitwidth
     pub bitwidth: u8,
+    /// Number of distinctive instruction idioms (returns, calls, prologues
+    /// and the like) the scorer counted; `None` for scorers that do not
+    /// keep such a tally
+    pub match_count: Option<u64>,
+    /// Bytes of alignment padding the scorer treated as neutral; they are
+    /// left out of the [`normalized`](Self::normalized) denominator
+    pub padding_bytes: usize,
 }
 
 /// Analyze raw binary data and return the best classification.
@@ -92,8 +120,23 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
         });
     }
 
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("heuristics", scan_bytes = options.max_scan_bytes).entered();
+
     // Score all architectures
     let scores = score_all_architectures(data, options);
+    #[cfg(feature = "tracing")]
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        for score in &scores {
+            tracing::debug!(
+                isa = %score.isa,
+                bitwidth = score.bitwidth,
+                endianness = %score.endianness,
+                raw_score = score.raw_score,
+                "scorer"
+            );
+        }
+    }
 
     if scores.is_empty() {
         return Err(ClassifierError::HeuristicInconclusive {
@@ -102,9 +145,62 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
         });
     }
 
+    // Fallbacks rescore a sub-window, but callers always see the whole-input
+    // ranking; the function estimate follows whichever ISA wins
+    let candidates = rank_candidates(&scores, options.max_candidates);
+    let finish = |mut result: ClassificationResult| {
+        result.candidates.clone_from(&candidates);
+        if let Some(functions) = padding::estimated_functions(result.isa, data) {
+            result.metadata.custom.push(MetadataEntry::custom(
+                "estimated_functions",
+                MetadataValue::Integer(u64::from(functions)),
+                "Estimated Functions",
+            ));
+        }
+        if let Some(windows) = options
+            .sample_mode
+            .windows(data.len(), options.max_scan_bytes)
+        {
+            result.metadata.notes.push(format!(
+                "Scores aggregated over {} sampled windows of {} bytes",
+                windows.len(),
+                windows[0].1 - windows[0].0
+            ));
+        }
+        result
+    };
+
+    // A reset/interrupt vector table is a table of pointers, not code: with
+    // little code behind it, name the ISA it implies but cap the confidence
+    if let Some(table) = structural_table(data) {
+        let code = (table.code)(&data[table.len..]);
+        if code < table.min_code {
+            if STRUCTURAL_ONLY_CONFIDENCE < options.min_confidence {
+                return Err(ClassifierError::HeuristicInconclusive {
+                    confidence: STRUCTURAL_ONLY_CONFIDENCE * 100.0,
+                    threshold: options.min_confidence * 100.0,
+                });
+            }
+            let mut result = ClassificationResult::from_heuristics(
+                table.isa,
+                table.bitwidth,
+                table.endianness,
+                STRUCTURAL_ONLY_CONFIDENCE,
+            );
+            result.source = ClassificationSource::Heuristic;
+            result.format = FileFormat::Raw;
+            result.match_count = Some(u64::from(code));
+            result.metadata.notes.push(format!(
+                "Classification based primarily on {}; little executable code present",
+                table.name
+            ));
+            return Ok(finish(result));
+        }
+    }
+
     // Find the best and second-best matches
     let mut sorted_scores: Vec<_> = scores.iter().collect();
-    sorted_scores.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+    sorted_scores.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
 
     let mut best = sorted_scores[0];
 
@@ -129,7 +225,7 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
         }
     }
 
-    let second_best = sorted_scores
+    let mut second_best = sorted_scores
         .iter()
         .copied()
         .filter(|s| {
@@ -137,23 +233,43 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
         })
         .next();
 
+    // Decode-walk the top two: statistics alone can crown an ISA whose
+    // encodings the data never actually forms
+    let (mut best_walk, mut second_walk) = if options.deep_scan {
+        verify_candidates(data, options, best, second_best)
+    } else {
+        (None, None)
+    };
+    let weight = |walk: Option<verify::WalkStats>| walk.map_or(1.0, |w| w.multiplier());
+    if let Some(second) = second_best {
+        if second.normalized * weight(second_walk) > best.normalized * weight(best_walk) {
+            second_best = Some(best);
+            best = second;
+            std::mem::swap(&mut best_walk, &mut second_walk);
+        }
+    }
+    let best_weight = weight(best_walk);
+    let second_weight = weight(second_walk);
+
     // Calculate confidence using multiple factors:
     // 1. Share of total (original method)
     // 2. Margin over second place (how decisive is the win?)
     // 3. Absolute score threshold (does it look like real code at all?)
-    let total_positive: i64 = scores.iter().map(|s| s.raw_score.max(0)).sum();
+    let total_positive: f64 = scores.iter().map(|s| s.normalized.max(0.0)).sum();
 
-    let share_confidence = if total_positive > 0 {
-        best.raw_score.max(0) as f64 / total_positive as f64
+    let share_confidence = if total_positive > 0.0 {
+        best.normalized.max(0.0) / total_positive
     } else {
         0.0
     };
 
     // Margin confidence: how much better is the winner than second place?
     let margin_confidence = if let Some(second) = second_best {
-        if second.raw_score > 0 {
+        if second.normalized > 0.0 {
             // Margin as a ratio: if winner is 50% higher than second, margin = 0.5
-            let margin = (best.raw_score - second.raw_score) as f64 / second.raw_score as f64;
+            let best_score = best.normalized * best_weight;
+            let second_score = second.normalized * second_weight;
+            let margin = (best_score - second_score) / second_score;
             // Scale margin to a confidence: margin of 0.2 (20% better) → ~0.5 confidence
             // margin of 1.0 (100% better) → ~0.9 confidence
             (margin / (margin + 0.25)).min(0.95)
@@ -166,15 +282,35 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
 
     // Combined confidence: use the higher of share or margin-based confidence
     // This helps when many architectures score but one clearly dominates
-    let mut confidence = share_confidence.max(margin_confidence * 0.8);
+    let mut confidence = share_confidence.max(margin_confidence * 0.8) * best_weight;
+    let mut swap_checked = false;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        winner = %best.isa,
+        raw_score = best.raw_score,
+        runner_up = second_best.map(|s| tracing::field::display(s.isa)),
+        margin = second_best.map_or(best.raw_score, |s| best.raw_score - s.raw_score),
+        confidence,
+        "winner"
+    );
 
     if confidence < options.min_confidence {
         if let Some(subregion) = try_wrapper_subregion_fallback(data, options) {
-            return Ok(subregion);
+            return Ok(finish(subregion));
         }
 
         if let Some(fallback) = try_anchor_window_fallback(data, options) {
-            return Ok(fallback);
+            return Ok(finish(fallback));
+        }
+
+        // A dump read through a 16-bit programmer looks like noise until swapped
+        swap_checked = true;
+        if let Some(swapped) = try_byte_swapped(data, best.raw_score, options) {
+            return Ok(swapped);
+        }
+
+        if let Some(unknown) = family_tie_result(best, &sorted_scores, confidence) {
+            return Ok(finish(unknown));
         }
 
         if let Some(boosted_confidence) =
@@ -191,10 +327,21 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
 
     if let Some(subregion) = try_wrapper_subregion_fallback(data, options) {
         if subregion.confidence >= confidence + 0.10 {
-            return Ok(subregion);
+            return Ok(finish(subregion));
         }
     }
 
+    if options.detect_byte_swap && !swap_checked {
+        if let Some(swapped) = try_byte_swapped(data, best.raw_score, options) {
+            return Ok(swapped);
+        }
+    }
+
+    // Distinct ISAs of one family neck and neck: name the family, not a coin toss
+    if let Some(unknown) = family_tie_result(best, &sorted_scores, confidence) {
+        return Ok(finish(unknown));
+    }
+
     // Build result
     let mut detected_isa = best.isa;
     if detected_isa == Isa::V850 && has_marker(data, b"RH850") {
@@ -209,6 +356,37 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
     );
     result.source = ClassificationSource::Heuristic;
     result.format = FileFormat::Raw;
+    result.match_count = best.match_count;
+    let window = &data[..data.len().min(options.max_scan_bytes)];
+    // Linker stubs are A32 even in Thumb-only images
+    let stubs = crate::extensions::stubs::find(window, detected_isa, best.endianness);
+    if detected_isa == Isa::Arm
+        && arm::is_thumb_only(&stubs.map_or(Cow::Borrowed(window), |s| s.strip(window)))
+    {
+        result.variant = Some(Variant::new(arm::THUMB_ONLY_VARIANT));
+    }
+    pointer_width::corroborate(&mut result, window);
+    for (key, label, walk) in [
+        ("decode_walk", "Decode Walk", best_walk),
+        (
+            "decode_walk_runner_up",
+            "Decode Walk (runner-up)",
+            second_walk,
+        ),
+    ] {
+        if let Some(walk) = walk {
+            result.metadata.custom.push(MetadataEntry::custom(
+                key,
+                MetadataValue::String(format!(
+                    "mean run {:.1} insns, {:.0}% clean over {} walks",
+                    walk.mean_run,
+                    walk.clean_fraction * 100.0,
+                    walk.trials
+                )),
+                label,
+            ));
+        }
+    }
 
     // Add extensions if requested
     if options.detect_extensions {
@@ -216,7 +394,258 @@ pub fn analyze(data: &[u8], options: &ClassifierOptions) -> Result<Classificatio
         result.extensions = extensions;
     }
 
-    Ok(result)
+    Ok(finish(result))
+}
+
+/// Decode-walk the two leading candidates over the scan window.
+fn verify_candidates(
+    data: &[u8],
+    options: &ClassifierOptions,
+    best: &ArchitectureScore,
+    second: Option<&ArchitectureScore>,
+) -> (Option<verify::WalkStats>, Option<verify::WalkStats>) {
+    let window = &data[..data.len().min(options.max_scan_bytes)];
+    let walk = |score: &ArchitectureScore| verify::walk(window, score.isa, score.endianness);
+    let best_walk = walk(best);
+    let second_walk = second.and_then(walk);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        best = ?best_walk,
+        runner_up = ?second_walk,
+        "decode walk"
+    );
+    (best_walk, second_walk)
+}
+
+/// Confidence ceiling for an input that is mostly a vector table.
+const STRUCTURAL_ONLY_CONFIDENCE: f64 = 0.45;
+
+/// Instructions the ISA's scorer must decode after a table before the input
+/// is judged as code rather than as the table alone.
+const MIN_STRUCTURAL_INSTRUCTIONS: u32 = 64;
+
+/// Returns, calls and prologues the x86 scorer must find after a boot
+/// sector. x86 has no fixed instruction width to decode by, so its
+/// distinctive idioms stand in for instructions (roughly one per eight).
+const MIN_STRUCTURAL_X86_IDIOMS: u32 = 8;
+
+/// A table recognised from its layout alone.
+struct StructuralTable {
+    /// Human name used in the note
+    name: &'static str,
+    /// Architecture the table implies
+    isa: Isa,
+    /// Bit width the table implies
+    bitwidth: u8,
+    /// Byte order the table implies
+    endianness: Endianness,
+    /// Bytes the table occupies from offset 0
+    len: usize,
+    /// Code the ISA's scorer recognises in the bytes after the table
+    code: fn(&[u8]) -> u32,
+    /// Least `code` that outweighs the table
+    min_code: u32,
+}
+
+/// Recognise a reset/interrupt vector table, trap table or boot sector at
+/// the start of `data`.
+///
+/// Every layout-only detector belongs here, so none of them can claim more
+/// confidence than the code after the table supports.
+fn structural_table(data: &[u8]) -> Option<StructuralTable> {
+    use crate::architectures::{arm, avr, sparc, x86};
+
+    if let Some(len) = arm::cortex_m_vector_table_len(data) {
+        return Some(StructuralTable {
+            name: "a Cortex-M vector table",
+            isa: Isa::Arm,
+            bitwidth: 32,
+            endianness: Endianness::Little,
+            len,
+            code: arm::thumb_valid_count,
+            min_code: MIN_STRUCTURAL_INSTRUCTIONS,
+        });
+    }
+    if let Some(len) = avr::vector_table_len(data) {
+        return Some(StructuralTable {
+            name: "an AVR interrupt vector table",
+            isa: Isa::Avr,
+            bitwidth: 16,
+            endianness: Endianness::Little,
+            len,
+            code: |code| avr::score_with_valid(code).1,
+            min_code: MIN_STRUCTURAL_INSTRUCTIONS,
+        });
+    }
+    if let Some(len) = sparc::trap_table_len(data) {
+        return Some(StructuralTable {
+            name: "a SPARC (LEON) trap table",
+            isa: Isa::Sparc,
+            bitwidth: 32,
+            endianness: Endianness::Big,
+            len,
+            code: |code| sparc::score_with_valid(code, false).1,
+            min_code: MIN_STRUCTURAL_INSTRUCTIONS,
+        });
+    }
+    x86::boot_sector_len(data).map(|len| StructuralTable {
+        name: "a PC boot sector",
+        isa: Isa::X86,
+        bitwidth: 16,
+        endianness: Endianness::Little,
+        len,
+        code: |code| x86::score_with_matches(code, 16).1,
+        min_code: MIN_STRUCTURAL_X86_IDIOMS,
+    })
+}
+
+/// Factor by which a byte-swapped view's winner must outscore the unswapped
+/// winner before the input is taken to be swapped.
+const BYTE_SWAP_WIN_RATIO: f64 = 2.0;
+
+/// Classify `data` through a byte-swapped view if one scores dramatically
+/// better than the raw bytes.
+///
+/// Every architecture is rescored, since the true ISA need not rank near the
+/// top of a scrambled view. The result always carries a warning and a
+/// `byte_swap` metadata entry naming the transformation, so a swapped-view
+/// answer is never mistaken for one read from the bytes as stored.
+fn try_byte_swapped(
+    data: &[u8],
+    unswapped_best: i64,
+    options: &ClassifierOptions,
+) -> Option<ClassificationResult> {
+    let mut winner: Option<(ByteSwap, Vec<ArchitectureScore>)> = None;
+    for swap in ByteSwap::ALL {
+        let scores = score_view(data, Some(swap), options);
+        let Some(top) = scores.first() else {
+            continue;
+        };
+        let bar = match &winner {
+            Some((_, best)) => best[0].raw_score as f64,
+            None => unswapped_best.max(0) as f64 * BYTE_SWAP_WIN_RATIO,
+        };
+        if top.raw_score as f64 > bar && top.confidence >= options.min_confidence {
+            winner = Some((swap, scores));
+        }
+    }
+
+    let (swap, scores) = winner?;
+    let top = &scores[0];
+    let mut result = ClassificationResult::from_heuristics(
+        top.isa,
+        top.bitwidth,
+        top.endianness,
+        top.confidence,
+    );
+    result.source = ClassificationSource::Heuristic;
+    result.format = FileFormat::Raw;
+    result.match_count = top.match_count;
+    result.candidates = rank_candidates(&scores, options.max_candidates);
+    result.metadata.warnings.push(format!(
+        "input appears {}; classification performed on swapped view",
+        swap.name()
+    ));
+    result.metadata.custom.push(MetadataEntry::custom(
+        "byte_swap",
+        MetadataValue::String(swap.name().to_string()),
+        "Byte Swap",
+    ));
+    Some(result)
+}
+
+/// Fraction of the winning score a different ISA of the same family must
+/// reach for the pick between them to count as arbitrary.
+const FAMILY_TIE_RATIO: f64 = 0.95;
+
+/// Report `Isa::Unknown` with a family note when the winner is not decisive.
+///
+/// Applies when a different ISA of the winner's [`IsaFamily`] (not merely a
+/// bitwidth variant the scorers already tell apart) scores within
+/// [`FAMILY_TIE_RATIO`] of it, and no other family comes that close.
+fn family_tie_result(
+    best: &ArchitectureScore,
+    sorted_scores: &[&ArchitectureScore],
+    confidence: f64,
+) -> Option<ClassificationResult> {
+    let family = best.isa.family();
+    if family == IsaFamily::Other || best.raw_score <= 0 {
+        return None;
+    }
+
+    let floor = best.normalized * FAMILY_TIE_RATIO;
+    let mut tied = vec![best.isa];
+    for score in sorted_scores {
+        if score.normalized < floor {
+            break;
+        }
+        if score.isa.family() != family {
+            return None;
+        }
+        let score_family = confidence_family(score.isa);
+        if tied
+            .iter()
+            .all(|&isa| confidence_family(isa) != score_family)
+        {
+            tied.push(score.isa);
+        }
+    }
+    if tied.len() < 2 {
+        return None;
+    }
+
+    let bitwidth = if tied
+        .iter()
+        .all(|isa| isa.default_bitwidth() == best.isa.default_bitwidth())
+    {
+        best.bitwidth
+    } else {
+        0
+    };
+    let names: Vec<&str> = tied
+        .iter()
+        .map(|isa| isa.name().split(" (").next().unwrap_or_default())
+        .collect();
+
+    let mut result = ClassificationResult::from_heuristics(
+        Isa::Unknown(0),
+        bitwidth,
+        best.endianness,
+        confidence,
+    );
+    result.source = ClassificationSource::Heuristic;
+    result.format = FileFormat::Raw;
+    result.metadata.notes.push(format!(
+        "likely {}-family ({} ambiguous)",
+        family,
+        names.join("/")
+    ));
+    Some(result)
+}
+
+/// Default number of ranked candidates reported alongside a heuristic
+/// result (see [`ClassifierOptions::max_candidates`]).
+pub const MAX_CANDIDATES: usize = ClassifierOptions::DEFAULT_MAX_CANDIDATES;
+
+/// Rank positive-scoring architectures, best first, keeping at most
+/// `limit` for reporting.
+///
+/// Ties are broken by bitwidth, name and endianness so the order does not
+/// depend on the order the scorers ran in.
+pub fn rank_candidates(scores: &[ArchitectureScore], limit: usize) -> Vec<IsaCandidate> {
+    let mut sorted: Vec<_> = scores.iter().filter(|c| c.raw_score > 0).collect();
+    sorted.sort_by(|a, b| {
+        b.normalized
+            .total_cmp(&a.normalized)
+            .then(a.bitwidth.cmp(&b.bitwidth))
+            .then_with(|| a.isa.name().cmp(b.isa.name()))
+            .then((a.endianness == Endianness::Big).cmp(&(b.endianness == Endianness::Big)))
+    });
+    sorted
+        .into_iter()
+        .take(limit)
+        .map(|c| IsaCandidate::new(c.isa, c.bitwidth, c.endianness, c.raw_score, c.confidence))
+        .collect()
 }
 
 fn has_marker(data: &[u8], marker: &[u8]) -> bool {
@@ -319,52 +748,74 @@ fn is_wrapper_noise_window(data: &[u8]) -> bool {
 fn score_wrapper_architectures(data: &[u8]) -> Vec<ArchitectureScore> {
     let mut scores = Vec::with_capacity(16);
 
-    let x86_64 = scorer::score_x86(data, 64);
+    let (x86_64, _, x86_64_padding) = scorer::score_x86_with_padding(data, 64);
     scores.push(ArchitectureScore {
         isa: Isa::X86_64,
         raw_score: x86_64,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: x86_64_padding,
     });
 
-    let x86_32 = scorer::score_x86(data, 32);
+    let (x86_32, _, x86_32_padding) = scorer::score_x86_with_padding(data, 32);
     scores.push(ArchitectureScore {
         isa: Isa::X86,
         raw_score: x86_32,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: x86_32_padding,
     });
 
+    let (arm, arm_padding) = scorer::score_arm_with_padding(data);
     scores.push(ArchitectureScore {
         isa: Isa::Arm,
-        raw_score: scorer::score_arm(data),
+        raw_score: arm,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: arm_padding,
     });
+    let (aarch64, _, aarch64_padding) = scorer::score_aarch64_with_padding(data);
     scores.push(ArchitectureScore {
         isa: Isa::AArch64,
-        raw_score: scorer::score_aarch64(data),
+        raw_score: aarch64,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: aarch64_padding,
     });
 
+    let (riscv64, _, riscv64_padding) = scorer::score_riscv_with_padding(data, 64);
     scores.push(ArchitectureScore {
         isa: Isa::RiscV64,
-        raw_score: scorer::score_riscv(data, 64),
+        raw_score: riscv64,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: riscv64_padding,
     });
+    let (riscv32, _, riscv32_padding) = scorer::score_riscv_with_padding(data, 32);
     scores.push(ArchitectureScore {
         isa: Isa::RiscV32,
-        raw_score: scorer::score_riscv(data, 32),
+        raw_score: riscv32,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: riscv32_padding,
     });
 
     let (mips_be, mips_le) = scorer::score_mips(data, false);
@@ -377,8 +828,11 @@ fn score_wrapper_architectures(data: &[u8]) -> Vec<ArchitectureScore> {
         isa: Isa::Mips,
         raw_score: mips_raw,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: mips_endian,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     let (mips64_be, mips64_le) = scorer::score_mips(data, true);
@@ -391,8 +845,11 @@ fn score_wrapper_architectures(data: &[u8]) -> Vec<ArchitectureScore> {
         isa: Isa::Mips64,
         raw_score: mips64_raw,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: mips64_endian,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     let (sh_be, sh_le) = scorer::score_superh(data);
@@ -405,66 +862,66 @@ fn score_wrapper_architectures(data: &[u8]) -> Vec<ArchitectureScore> {
         isa: Isa::Sh,
         raw_score: sh_raw,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: sh_endian,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     scores.push(ArchitectureScore {
         isa: Isa::Arc,
         raw_score: scorer::score_arc(data),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
     scores.push(ArchitectureScore {
         isa: Isa::Xtensa,
         raw_score: scorer::score_xtensa(data),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
     scores.push(ArchitectureScore {
         isa: Isa::V850,
         raw_score: scorer::score_v850(data),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
     scores.push(ArchitectureScore {
         isa: Isa::Csky,
         raw_score: scorer::score_csky(data),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
     scores.push(ArchitectureScore {
         isa: Isa::TiC6000,
         raw_score: scorer::score_tic6000(data),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
-    scores.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
-    let total_positive: i64 = scores.iter().map(|s| s.raw_score.max(0)).sum();
-    if total_positive > 0 {
-        let best = scores[0].raw_score.max(0);
-        let second = scores.get(1).map(|s| s.raw_score.max(0)).unwrap_or(0);
-        let margin_conf = if second > 0 {
-            let margin = (best - second) as f64 / second as f64;
-            (margin / (margin + 0.25)).min(0.95)
-        } else {
-            0.95
-        };
-        for (idx, score) in scores.iter_mut().enumerate() {
-            let share = score.raw_score.max(0) as f64 / total_positive as f64;
-            score.confidence = if idx == 0 {
-                share.max(margin_conf * 0.8)
-            } else {
-                share
-            };
-        }
-    }
+    assign_normalized(&mut scores, data.len());
+    rank_by_normalized(&mut scores);
 
     scores
 }
@@ -488,7 +945,7 @@ fn try_wrapper_subregion_fallback(
     let max_windows = 4usize;
     let step = ((scan.len().saturating_sub(window_size)) / max_windows).max(4 * 1024);
 
-    let mut by_isa: HashMap<(Isa, u8, Endianness), (u32, i64, f64)> = HashMap::new();
+    let mut by_isa: HashMap<(Isa, u8, Endianness), (usize, u32, i64, f64)> = HashMap::new();
     let mut informative_windows = 0u32;
     let mut examined_windows = 0u32;
 
@@ -525,10 +982,11 @@ fn try_wrapper_subregion_fallback(
 
         informative_windows += 1;
         let key = (best.isa, best.bitwidth, best.endianness);
-        let entry = by_isa.entry(key).or_insert((0, 0, 0.0));
-        entry.0 += 1;
-        entry.1 += best.raw_score;
-        entry.2 += best.confidence;
+        let order = by_isa.len();
+        let entry = by_isa.entry(key).or_insert((order, 0, 0, 0.0));
+        entry.1 += 1;
+        entry.2 += best.raw_score;
+        entry.3 += best.confidence;
 
         if informative_windows >= 64 {
             break;
@@ -539,20 +997,22 @@ fn try_wrapper_subregion_fallback(
         return None;
     }
 
-    let mut ranked: Vec<((Isa, u8, Endianness), (u32, i64, f64))> = by_isa.into_iter().collect();
+    let mut ranked: Vec<((Isa, u8, Endianness), (usize, u32, i64, f64))> =
+        by_isa.into_iter().collect();
     ranked.sort_by(|a, b| {
-        b.1 .0
-            .cmp(&a.1 .0)
-            .then_with(|| b.1 .1.cmp(&a.1 .1))
+        b.1 .1
+            .cmp(&a.1 .1)
+            .then_with(|| b.1 .2.cmp(&a.1 .2))
             .then_with(|| {
-                b.1 .2
-                    .partial_cmp(&a.1 .2)
+                b.1 .3
+                    .partial_cmp(&a.1 .3)
                     .unwrap_or(std::cmp::Ordering::Equal)
             })
+            .then_with(|| a.1 .0.cmp(&b.1 .0))
     });
 
-    let ((isa, bitwidth, endianness), (wins, _raw_sum, conf_sum)) = ranked[0];
-    let second_wins = ranked.get(1).map(|r| r.1 .0).unwrap_or(0);
+    let ((isa, bitwidth, endianness), (_, wins, _raw_sum, conf_sum)) = ranked[0];
+    let second_wins = ranked.get(1).map(|r| r.1 .1).unwrap_or(0);
     let dominance = if second_wins > 0 {
         wins as f64 / second_wins as f64
     } else {
@@ -640,6 +1100,50 @@ fn apply_low_evidence_confuser_penalties(scores: &mut [ArchitectureScore]) {
     }
 }
 
+/// Fill in [`ArchitectureScore::normalized`] for scores over `scanned_bytes`.
+///
+/// Padding runs the ISA's scorer treated as neutral
+/// ([`ArchitectureScore::padding_bytes`]) are left out of its denominator,
+/// so alignment filler neither dilutes nor inflates the score.
+fn assign_normalized(scores: &mut [ArchitectureScore], scanned_bytes: usize) {
+    for score in scores.iter_mut() {
+        let code_bytes = scanned_bytes.saturating_sub(score.padding_bytes);
+        score.normalized = scorer::normalize(score.isa, score.raw_score, code_bytes);
+    }
+}
+
+/// Sort `scores` best first by normalized score and fill in each
+/// [`ArchitectureScore::confidence`].
+///
+/// A score's confidence is its share of the positive normalized total; the
+/// winner's is raised to its margin over the runner-up when that is higher.
+fn rank_by_normalized(scores: &mut [ArchitectureScore]) {
+    scores.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
+
+    let total_positive: f64 = scores.iter().map(|s| s.normalized.max(0.0)).sum();
+    if total_positive <= 0.0 {
+        return;
+    }
+
+    let best = scores[0].normalized.max(0.0);
+    let second = scores.get(1).map_or(0.0, |s| s.normalized.max(0.0));
+    let margin_conf = if second > 0.0 {
+        let margin = (best - second) / second;
+        (margin / (margin + 0.25)).min(0.95)
+    } else {
+        0.95
+    };
+
+    for (idx, score) in scores.iter_mut().enumerate() {
+        let share = score.normalized.max(0.0) / total_positive;
+        score.confidence = if idx == 0 {
+            share.max(margin_conf * 0.8)
+        } else {
+            share
+        };
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 enum ConfidenceFamily {
     X86,
@@ -938,6 +1442,7 @@ fn try_anchor_window_fallback(
                 max_scan_bytes: head_len,
                 detect_extensions: false,
                 fast_mode: false,
+                ..ClassifierOptions::default()
             };
             let scores = score_all_architectures_raw(head, &opts);
             if let Some(v) = scores
@@ -1211,6 +1716,7 @@ fn try_anchor_window_fallback(
             max_scan_bytes: window_size,
             detect_extensions: false,
             fast_mode: false,
+            ..ClassifierOptions::default()
         };
 
         for &anchor_off in &offset_candidates {
@@ -1393,71 +1899,106 @@ fn collect_informative_spans(
 
 /// Score all supported architectures.
 pub fn score_all_architectures(data: &[u8], options: &ClassifierOptions) -> Vec<ArchitectureScore> {
-    let target_informative_bytes = options.max_scan_bytes.min(data.len());
-    let informative_spans =
-        collect_informative_spans(data, target_informative_bytes, HOMOGENEOUS_RUN_SKIP_BYTES);
+    score_view(data, None, options)
+}
+
+/// Score all supported architectures against `data` as seen through `swap`.
+///
+/// Informative spans are picked from the raw bytes (swapping does not change
+/// homogeneous runs); each chunk is swapped just before it is scored.
+fn score_view(
+    data: &[u8],
+    swap: Option<ByteSwap>,
+    options: &ClassifierOptions,
+) -> Vec<ArchitectureScore> {
+    let informative_spans = match options
+        .sample_mode
+        .windows(data.len(), options.max_scan_bytes)
+    {
+        // Informative bytes of each sampled window
+        Some(windows) => windows
+            .into_iter()
+            .flat_map(|(start, end)| {
+                collect_informative_spans(
+                    &data[start..end],
+                    end - start,
+                    HOMOGENEOUS_RUN_SKIP_BYTES,
+                )
+                .into_iter()
+                .map(move |(s, e)| (start + s, start + e))
+            })
+            .collect(),
+        None => collect_informative_spans(
+            data,
+            options.max_scan_bytes.min(data.len()),
+            HOMOGENEOUS_RUN_SKIP_BYTES,
+        ),
+    };
 
     if informative_spans.is_empty() {
         return Vec::new();
     }
 
     let mut accumulated = std::collections::HashMap::new();
+    let mut scanned_bytes = 0usize;
 
+    let view = swap.map(|swap| SwappedView::new(data, swap));
     for (start, end) in informative_spans {
-        let span = &data[start..end];
-        for chunk in span.chunks(SCORE_CHUNK_SIZE) {
+        let start = view.map_or(start, |v| start - start % v.swap().width());
+        for chunk_start in (start..end).step_by(SCORE_CHUNK_SIZE) {
+            let chunk_end = (chunk_start + SCORE_CHUNK_SIZE).min(end);
+            let swapped;
+            let chunk = match view {
+                Some(view) => {
+                    swapped = view.range(chunk_start, chunk_end);
+                    &swapped[..]
+                }
+                None => &data[chunk_start..chunk_end],
+            };
+            let scanned = chunk.len().min(options.max_scan_bytes);
+            scanned_bytes += scanned;
             let chunk_scores = score_all_architectures_raw(chunk, options);
             for score in chunk_scores {
+                let order = accumulated.len();
                 let entry = accumulated
                     .entry((score.isa.clone(), score.bitwidth, score.endianness))
-                    .or_insert(0i64);
-                *entry += score.raw_score;
+                    .or_insert((order, 0i64, None, 0usize));
+                entry.1 += score.raw_score;
+                if let Some(matches) = score.match_count {
+                    entry.2 = Some(entry.2.unwrap_or(0u64) + matches);
+                }
+                entry.3 += score.padding_bytes;
             }
         }
     }
 
+    // Restore scorer order so that ties break the same way on every run,
+    // independent of the map's hash seed.
+    let mut accumulated: Vec<_> = accumulated.into_iter().collect();
+    accumulated.sort_by_key(|(_, (order, _, _, _))| *order);
+
     let mut final_scores = Vec::new();
-    for ((isa, bitwidth, endianness), raw_score) in accumulated {
+    for ((isa, bitwidth, endianness), (_, raw_score, match_count, padding_bytes)) in accumulated {
         final_scores.push(ArchitectureScore {
             isa,
             raw_score,
             confidence: 0.0,
+            normalized: 0.0,
             endianness,
             bitwidth,
+            match_count,
+            padding_bytes,
         });
     }
 
-    apply_low_evidence_confuser_penalties(&mut final_scores);
-
-    // Sort by score to find winner and runner-up
-    final_scores.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
-
-    let total_positive: i64 = final_scores.iter().map(|s| s.raw_score.max(0)).sum();
-
-    if total_positive > 0 && !final_scores.is_empty() {
-        let best_score = final_scores[0].raw_score.max(0);
-        let second_score = final_scores.get(1).map(|s| s.raw_score.max(0)).unwrap_or(0);
-
-        // Calculate margin confidence for winner
-        let margin_conf = if second_score > 0 {
-            let margin = (best_score - second_score) as f64 / second_score as f64;
-            (margin / (margin + 0.25)).min(0.95)
-        } else {
-            0.95
-        };
-
-        for (i, score) in final_scores.iter_mut().enumerate() {
-            let share = score.raw_score.max(0) as f64 / total_positive as f64;
-
-            // For the top scorer, also consider margin of victory
-            if i == 0 {
-                score.confidence = share.max(margin_conf * 0.8);
-            } else {
-                score.confidence = share;
-            }
-        }
+    if !options.allowed_isas.is_empty() {
+        final_scores.retain(|score| options.allowed_isas.contains(&score.isa));
     }
 
+    apply_low_evidence_confuser_penalties(&mut final_scores);
+    assign_normalized(&mut final_scores, scanned_bytes);
+    rank_by_normalized(&mut final_scores);
+
     final_scores
 }
 
@@ -1468,133 +2009,175 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
     let mut scores = Vec::with_capacity(SUPPORTED_ARCHITECTURES.len());
 
     // x86/x86-64
-    let x86_32_score = scorer::score_x86(scan_data, 32);
+    let (x86_32_score, x86_32_matches, x86_32_padding) =
+        scorer::score_x86_with_padding(scan_data, 32);
     scores.push(ArchitectureScore {
         isa: Isa::X86,
         raw_score: x86_32_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: Some(u64::from(x86_32_matches)),
+        padding_bytes: x86_32_padding,
     });
 
-    let x86_64_score = scorer::score_x86(scan_data, 64);
+    let (x86_64_score, x86_64_matches, x86_64_padding) =
+        scorer::score_x86_with_padding(scan_data, 64);
     scores.push(ArchitectureScore {
         isa: Isa::X86_64,
         raw_score: x86_64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: Some(u64::from(x86_64_matches)),
+        padding_bytes: x86_64_padding,
     });
 
     // ARM
-    let arm_score = scorer::score_arm(scan_data);
+    let (arm_score, arm_padding) = scorer::score_arm_with_padding(scan_data);
     scores.push(ArchitectureScore {
         isa: Isa::Arm,
         raw_score: arm_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: arm_padding,
     });
 
     // AArch64
-    let aarch64_score = scorer::score_aarch64(scan_data);
+    let (aarch64_score, aarch64_matches, aarch64_padding) =
+        scorer::score_aarch64_with_padding(scan_data);
     scores.push(ArchitectureScore {
         isa: Isa::AArch64,
         raw_score: aarch64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: Some(u64::from(aarch64_matches)),
+        padding_bytes: aarch64_padding,
     });
 
     // RISC-V
-    let riscv32_score = scorer::score_riscv(scan_data, 32);
+    let (riscv32_score, riscv32_matches, riscv32_padding) =
+        scorer::score_riscv_with_padding(scan_data, 32);
     scores.push(ArchitectureScore {
         isa: Isa::RiscV32,
         raw_score: riscv32_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: Some(u64::from(riscv32_matches)),
+        padding_bytes: riscv32_padding,
     });
 
-    let riscv64_score = scorer::score_riscv(scan_data, 64);
+    let (riscv64_score, riscv64_matches, riscv64_padding) =
+        scorer::score_riscv_with_padding(scan_data, 64);
     scores.push(ArchitectureScore {
         isa: Isa::RiscV64,
         raw_score: riscv64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: Some(u64::from(riscv64_matches)),
+        padding_bytes: riscv64_padding,
     });
 
     // MIPS 32-bit (both endiannesses)
-    let (mips32_be, mips32_le) = scorer::score_mips(scan_data, false);
-    let (mips32_score, mips32_endian) = if mips32_be >= mips32_le {
-        (mips32_be, Endianness::Big)
+    let ((mips32_be, be_matches), (mips32_le, le_matches)) =
+        scorer::score_mips_with_matches(scan_data, false);
+    let (mips32_score, mips32_endian, mips32_matches) = if mips32_be >= mips32_le {
+        (mips32_be, Endianness::Big, be_matches)
     } else {
-        (mips32_le, Endianness::Little)
+        (mips32_le, Endianness::Little, le_matches)
     };
     scores.push(ArchitectureScore {
         isa: Isa::Mips,
         raw_score: mips32_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: mips32_endian,
         bitwidth: 32,
+        match_count: Some(u64::from(mips32_matches)),
+        padding_bytes: 0,
     });
 
     // MIPS 64-bit (both endiannesses, separate scoring for 64-bit opcodes)
-    let (mips64_be, mips64_le) = scorer::score_mips(scan_data, true);
-    let (mips64_score, mips64_endian) = if mips64_be >= mips64_le {
-        (mips64_be, Endianness::Big)
+    let ((mips64_be, be_matches), (mips64_le, le_matches)) =
+        scorer::score_mips_with_matches(scan_data, true);
+    let (mips64_score, mips64_endian, mips64_matches) = if mips64_be >= mips64_le {
+        (mips64_be, Endianness::Big, be_matches)
     } else {
-        (mips64_le, Endianness::Little)
+        (mips64_le, Endianness::Little, le_matches)
     };
     scores.push(ArchitectureScore {
         isa: Isa::Mips64,
         raw_score: mips64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: mips64_endian,
         bitwidth: 64,
+        match_count: Some(u64::from(mips64_matches)),
+        padding_bytes: 0,
     });
 
     // PowerPC (big-endian)
-    let ppc_be_score = scorer::score_ppc(scan_data);
+    let (ppc_be_score, ppc_be_matches) = scorer::score_ppc_with_matches(scan_data);
     scores.push(ArchitectureScore {
         isa: Isa::Ppc,
         raw_score: ppc_be_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: Some(u64::from(ppc_be_matches)),
+        padding_bytes: 0,
     });
 
     // PowerPC 64-bit: take max of BE and LE scores
-    let ppc64_le_score = scorer::score_ppc_le(scan_data);
-    let (ppc64_score, ppc64_endian) = if ppc_be_score >= ppc64_le_score {
-        (ppc_be_score, Endianness::Big)
+    let (ppc64_le_score, ppc64_le_matches) = scorer::score_ppc_le_with_matches(scan_data);
+    let (ppc64_score, ppc64_endian, ppc64_matches) = if ppc_be_score >= ppc64_le_score {
+        (ppc_be_score, Endianness::Big, ppc_be_matches)
     } else {
-        (ppc64_le_score, Endianness::Little)
+        (ppc64_le_score, Endianness::Little, ppc64_le_matches)
     };
     scores.push(ArchitectureScore {
         isa: Isa::Ppc64,
         raw_score: ppc64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: ppc64_endian,
         bitwidth: 64,
+        match_count: Some(u64::from(ppc64_matches)),
+        padding_bytes: 0,
     });
 
-    // SPARC (32-bit and 64-bit)
-    let sparc_score = scorer::score_sparc(scan_data);
+    // SPARC V8 (32-bit) and V9 (64-bit)
     scores.push(ArchitectureScore {
         isa: Isa::Sparc,
-        raw_score: sparc_score,
+        raw_score: scorer::score_sparc(scan_data, false),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
     scores.push(ArchitectureScore {
         isa: Isa::Sparc64,
-        raw_score: sparc_score, // Same scoring logic for 64-bit
+        raw_score: scorer::score_sparc(scan_data, true),
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // s390x
@@ -1603,18 +2186,24 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::S390x,
         raw_score: s390x_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // m68k
-    let m68k_score = scorer::score_m68k(scan_data);
+    let (m68k_score, m68k_matches) = scorer::score_m68k_with_matches(scan_data);
     scores.push(ArchitectureScore {
         isa: Isa::M68k,
         raw_score: m68k_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: Some(u64::from(m68k_matches)),
+        padding_bytes: 0,
     });
 
     // SuperH (both endiannesses — SH-1/SH-2 are typically BE, SH-3/SH-4 typically LE)
@@ -1628,8 +2217,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Sh,
         raw_score: sh_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: sh_endian,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Alpha
@@ -1638,18 +2230,24 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Alpha,
         raw_score: alpha_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // LoongArch
-    let loongarch_score = scorer::score_loongarch(scan_data);
+    let (loongarch_score, loongarch_matches) = scorer::score_loongarch_with_matches(scan_data);
     scores.push(ArchitectureScore {
         isa: Isa::LoongArch64,
         raw_score: loongarch_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: Some(u64::from(loongarch_matches)),
+        padding_bytes: 0,
     });
 
     // Hexagon
@@ -1658,8 +2256,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Hexagon,
         raw_score: hexagon_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // AVR
@@ -1668,8 +2269,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Avr,
         raw_score: avr_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 8,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // MSP430
@@ -1678,8 +2282,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Msp430,
         raw_score: msp430_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // PA-RISC
@@ -1688,8 +2295,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Parisc,
         raw_score: parisc_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // ARC
@@ -1698,8 +2308,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Arc,
         raw_score: arc_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Xtensa
@@ -1708,8 +2321,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Xtensa,
         raw_score: xtensa_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // MicroBlaze
@@ -1718,8 +2334,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::MicroBlaze,
         raw_score: microblaze_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Nios II
@@ -1728,8 +2347,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Nios2,
         raw_score: nios2_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // OpenRISC
@@ -1738,8 +2360,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::OpenRisc,
         raw_score: openrisc_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Lanai
@@ -1748,8 +2373,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Lanai,
         raw_score: lanai_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // JVM Bytecode
@@ -1758,8 +2386,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Jvm,
         raw_score: jvm_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
-        bitwidth: 32, // Stack-based, but operand stack is 32-bit slots
+        bitwidth: 32, // Stack-based, but operand stack is 32-bit slots,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // WebAssembly
@@ -1768,8 +2399,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Wasm,
         raw_score: wasm_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
-        bitwidth: 32, // WASM 1.0 is 32-bit memory addressing
+        bitwidth: 32, // WASM 1.0 is 32-bit memory addressing,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Dalvik Bytecode
@@ -1778,8 +2412,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Dalvik,
         raw_score: dalvik_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Blackfin DSP
@@ -1788,8 +2425,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Blackfin,
         raw_score: blackfin_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // IA-64/Itanium
@@ -1798,8 +2438,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Ia64,
         raw_score: ia64_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // DEC VAX
@@ -1808,8 +2451,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Vax,
         raw_score: vax_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Intel i860
@@ -1818,8 +2464,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::I860,
         raw_score: i860_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Cell SPU
@@ -1828,8 +2477,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::CellSpu,
         raw_score: cellspu_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // TriCore
@@ -1838,8 +2490,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Tricore,
         raw_score: tricore_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // HCS12/HCS12X (Freescale/NXP MC68HC12 / CPU12)
@@ -1848,8 +2503,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Hcs12,
         raw_score: hcs12_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Motorola 68HC11
@@ -1858,8 +2516,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Hc11,
         raw_score: hc11_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 8,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // C166/C167/ST10 (Infineon/Siemens)
@@ -1868,8 +2529,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::C166,
         raw_score: c166_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // C-SKY
@@ -1878,8 +2542,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Csky,
         raw_score: csky_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Renesas RL78 (successor to NEC 78K) — 8/16-bit little-endian MCU
@@ -1888,8 +2555,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Rl78,
         raw_score: rl78_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     // Renesas/NEC V850
@@ -1900,8 +2570,48 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::Fr30,
         raw_score: fr30_score,
         confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Big,
+        bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
+    });
+
+    let frv_score = scorer::score_frv(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::Frv,
+        raw_score: frv_score,
+        confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
+    });
+
+    // VideoCore IV and VI share the scalar encoding; report the newer one
+    let videocore_score = scorer::score_videocore(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::VideoCore5,
+        raw_score: videocore_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
+    });
+
+    let tilegx_score = scorer::score_tilegx(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::TileGx,
+        raw_score: tilegx_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 64,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     let s12z_score = scorer::score_s12z(scan_data);
@@ -1909,8 +2619,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::S12z,
         raw_score: s12z_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     let ppcvle_score = scorer::score_ppcvle(scan_data);
@@ -1918,8 +2631,11 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::PpcVle,
         raw_score: ppcvle_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Big,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
     let tic6000_score = scorer::score_tic6000(scan_data);
@@ -1927,47 +2643,89 @@ fn score_all_architectures_raw(data: &[u8], options: &ClassifierOptions) -> Vec<
         isa: Isa::TiC6000,
         raw_score: tic6000_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
         bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
     });
 
+    let pic_score = scorer::score_pic(scan_data);
     scores.push(ArchitectureScore {
-        isa: Isa::V850,
-        raw_score: v850_score,
+        isa: Isa::Pic,
+        raw_score: pic_score,
         confidence: 0.0,
+        normalized: 0.0,
         endianness: Endianness::Little,
-        bitwidth: 32,
+        bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
     });
 
-    // Calculate confidence using margin-based approach
-    // Sort by score to find winner and runner-up
-    scores.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+    let pic24_score = scorer::score_pic24(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::Pic24,
+        raw_score: pic24_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
+    });
 
-    let total_positive: i64 = scores.iter().map(|s| s.raw_score.max(0)).sum();
+    // Zilog Z80
+    let z80_score = scorer::score_z80(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::Z80,
+        raw_score: z80_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 8,
+        match_count: None,
+        padding_bytes: 0,
+    });
 
-    if total_positive > 0 && !scores.is_empty() {
-        let best_score = scores[0].raw_score.max(0);
-        let second_score = scores.get(1).map(|s| s.raw_score.max(0)).unwrap_or(0);
+    // MOS 6502
+    let mos6502_score = scorer::score_6502(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::Mcs6502,
+        raw_score: mos6502_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 8,
+        match_count: None,
+        padding_bytes: 0,
+    });
 
-        // Calculate margin confidence for winner
-        let margin_conf = if second_score > 0 {
-            let margin = (best_score - second_score) as f64 / second_score as f64;
-            (margin / (margin + 0.25)).min(0.95)
-        } else {
-            0.95
-        };
+    // WDC 65816
+    let w65816_score = scorer::score_w65816(scan_data);
+    scores.push(ArchitectureScore {
+        isa: Isa::W65816,
+        raw_score: w65816_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 16,
+        match_count: None,
+        padding_bytes: 0,
+    });
 
-        for (i, score) in scores.iter_mut().enumerate() {
-            let share = score.raw_score.max(0) as f64 / total_positive as f64;
+    scores.push(ArchitectureScore {
+        isa: Isa::V850,
+        raw_score: v850_score,
+        confidence: 0.0,
+        normalized: 0.0,
+        endianness: Endianness::Little,
+        bitwidth: 32,
+        match_count: None,
+        padding_bytes: 0,
+    });
 
-            // For the top scorer, also consider margin of victory
-            if i == 0 {
-                score.confidence = share.max(margin_conf * 0.8);
-            } else {
-                score.confidence = share;
-            }
-        }
-    }
+    assign_normalized(&mut scores, scan_data.len());
+    rank_by_normalized(&mut scores);
 
     scores
 }
@@ -1987,8 +2745,66 @@ pub struct DetectedIsa {
     pub endianness: Endianness,
     /// Bitwidth
     pub bitwidth: u8,
+    /// Extensions found in this ISA's contiguous regions of winning
+    /// windows; empty unless [`ClassifierOptions::detect_extensions`] is set
+    pub extensions: Vec<Extension>,
+}
+
+/// Regions shorter than this are not scanned for extensions: a few
+/// hundred bytes of code yield noise, not evidence.
+const MIN_REGION_EXTENSION_BYTES: usize = 1024;
+
+/// Extensions of every detected ISA, each name once, in detection order.
+///
+/// A file-level convenience; [`DetectedIsa::extensions`] says which region
+/// uses which extension.
+pub fn union_extensions(detected: &[DetectedIsa]) -> Vec<Extension> {
+    let mut union: Vec<Extension> = Vec::new();
+    for ext in detected.iter().flat_map(|d| &d.extensions) {
+        if !union.iter().any(|e| e.name == ext.name) {
+            union.push(ext.clone());
+        }
+    }
+    union
+}
+
+/// Extension detection over each contiguous run of `isa`'s winning
+/// windows, bounded to `budget` bytes per run.
+fn region_extensions(
+    data: &[u8],
+    isa: Isa,
+    windows: &[(i64, Endianness, u8, usize)],
+    window_size: usize,
+    budget: usize,
+) -> Vec<Extension> {
+    let mut extensions: Vec<Extension> = Vec::new();
+    let mut i = 0;
+    while i < windows.len() {
+        let (_, endianness, _, start) = windows[i];
+        let mut end = start + window_size;
+        i += 1;
+        while i < windows.len() && windows[i].3 == end {
+            end += window_size;
+            i += 1;
+        }
+
+        if end - start < MIN_REGION_EXTENSION_BYTES {
+            continue;
+        }
+        let region = &data[start..end.min(start + budget)];
+        for ext in crate::extensions::detect_from_code(region, isa, endianness) {
+            if !extensions.iter().any(|e| e.name == ext.name) {
+                extensions.push(ext);
+            }
+        }
+    }
+    extensions
 }
 
+/// Window-winner confidence threshold per unit of
+/// [`ClassifierOptions::min_confidence`]; the default 0.3 gives 0.14.
+const WINDOW_CONFIDENCE_SCALE: f64 = 0.14 / 0.3;
+
 /// Detect multiple ISAs in a binary using sliding-window analysis.
 ///
 /// Divides the data into fixed-size non-overlapping windows, scores each
@@ -1998,10 +2814,39 @@ pub struct DetectedIsa {
 ///
 /// This is designed for firmware images that contain code sections from
 /// multiple ISA families (e.g., AArch64 + ARM32, or Hexagon + AVR).
+///
+/// Of `options`, only these apply in windowed mode:
+///
+/// - `min_confidence` scales the confidence a window's winner needs to be
+///   counted (0.14 at the default 0.3);
+/// - `allowed_isas` limits the ISAs a window can be won by;
+/// - `deep_scan` enables the deeper scorers in each window;
+/// - `detect_extensions` runs extension detection for each detected ISA
+///   over each contiguous region of windows it won, skipping regions under
+///   1 KiB;
+/// - `region_extension_budget` caps the bytes of one region scanned for
+///   extensions.
+///
+/// Each window is scanned in full, so `max_scan_bytes` and `sample_mode`
+/// are ignored, as are the options that only affect whole-file analysis.
 pub fn detect_multi_isa(
     data: &[u8],
     options: &ClassifierOptions,
     window_size: usize,
+) -> Vec<DetectedIsa> {
+    detect_multi_isa_excluding(data, options, window_size, &[])
+}
+
+/// [`detect_multi_isa`], skipping every window that overlaps one of the
+/// `(offset, length)` ranges in `opaque`.
+///
+/// Formats report compressed or encrypted regions as opaque; scoring them
+/// only yields noise ISAs.
+pub fn detect_multi_isa_excluding(
+    data: &[u8],
+    options: &ClassifierOptions,
+    window_size: usize,
+    opaque: &[(u64, u64)],
 ) -> Vec<DetectedIsa> {
     let min_windows: usize = 3;
     let min_bytes: usize = 2048;
@@ -2009,46 +2854,22 @@ pub fn detect_multi_isa(
     // score_all_architectures computes confidence = max(share, margin*0.8).
     // On noise data, confidence is typically 0.05-0.15 (many ISAs score similarly).
     // On real code, the correct ISA gets 0.20+ confidence.
-    let min_window_confidence: f64 = 0.14;
-
-    // Per-ISA accumulation: (raw_score, endianness, bitwidth) per window
-    let mut isa_windows: HashMap<Isa, Vec<(i64, Endianness, u8)>> = HashMap::new();
+    let min_window_confidence = options.min_confidence * WINDOW_CONFIDENCE_SCALE;
 
-    // Use window-appropriate options: scan entire window, low confidence threshold
-    let window_opts = ClassifierOptions {
-        min_confidence: 0.01,
-        max_scan_bytes: window_size,
-        deep_scan: false,
-        detect_extensions: false,
-        fast_mode: false,
-    };
-
-    let mut offset = 0;
-    while offset + window_size <= data.len() {
-        let window = &data[offset..offset + window_size];
-
-        // Pre-filter: skip obvious non-code windows
-        if is_padding_or_empty(window) || is_string_data(window) || is_high_entropy(window) {
-            offset += window_size;
+    // Per-ISA accumulation: (raw_score, endianness, bitwidth, offset) per window
+    let mut isa_windows: HashMap<Isa, Vec<(i64, Endianness, u8, usize)>> = HashMap::new();
+    for window in classify_windows_excluding(data, options, window_size, opaque) {
+        let Some(isa) = window.isa else {
             continue;
+        };
+        if window.raw_score > 0 && window.confidence >= min_window_confidence {
+            isa_windows.entry(isa).or_default().push((
+                window.raw_score,
+                window.endianness,
+                window.bitwidth,
+                window.offset,
+            ));
         }
-
-        // Score this window against all architectures
-        let scores = score_all_architectures(window, &window_opts);
-
-        // The scores are sorted by raw_score descending with confidence computed.
-        // Only count the winner if its confidence exceeds our threshold.
-        if let Some(best) = scores.first() {
-            if best.raw_score > 0 && best.confidence >= min_window_confidence {
-                isa_windows.entry(best.isa).or_default().push((
-                    best.raw_score,
-                    best.endianness,
-                    best.bitwidth,
-                ));
-            }
-        }
-
-        offset += window_size;
     }
 
     // Total classified windows (those that passed confidence filter)
@@ -2080,6 +2901,16 @@ pub fn detect_multi_isa(
             let endianness = windows[0].1;
             let bitwidth = windows[0].2;
 
+            let extensions = if options.detect_extensions {
+                let budget = match options.region_extension_budget {
+                    0 => ClassifierOptions::DEFAULT_REGION_EXTENSION_BUDGET,
+                    n => n,
+                };
+                region_extensions(data, isa, &windows, window_size, budget)
+            } else {
+                Vec::new()
+            };
+
             DetectedIsa {
                 isa,
                 window_count: count,
@@ -2087,6 +2918,7 @@ pub fn detect_multi_isa(
                 avg_score,
                 endianness,
                 bitwidth,
+                extensions,
             }
         })
         .collect();
@@ -2097,6 +2929,143 @@ pub fn detect_multi_isa(
     results
 }
 
+/// Why [`classify_windows`] left a window unscored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// A single repeated byte
+    Padding,
+    /// Mostly printable ASCII
+    Strings,
+    /// Near-uniform byte distribution (compressed or encrypted)
+    HighEntropy,
+    /// Overlaps a range the container marks opaque
+    Opaque,
+}
+
+/// The dominant ISA of one window of a file.
+///
+/// One entry per window is the data a UI needs to draw an architecture
+/// strip over the file.
+#[derive(Debug, Clone)]
+pub struct WindowClassification {
+    /// File offset of the window
+    pub offset: usize,
+    /// Top scorer; `None` when the window was skipped
+    pub isa: Option<Isa>,
+    /// The top scorer's confidence (0.0 for skipped windows)
+    pub confidence: f64,
+    /// The top scorer's raw score
+    pub raw_score: i64,
+    /// The top scorer's endianness
+    pub endianness: Endianness,
+    /// The top scorer's bitwidth
+    pub bitwidth: u8,
+    /// Shannon entropy of the window in bits per byte (0.0 to 8.0)
+    pub entropy: f64,
+    /// Why the window was not scored, if it was not
+    pub skipped: Option<SkipReason>,
+}
+
+/// Classify each non-overlapping `window_size` window of `data`.
+///
+/// Returns one entry per whole window, in file order; a trailing partial
+/// window is left out. Padding, string and high-entropy windows are not
+/// scored and carry a [`SkipReason`]. Windows are scored as in
+/// [`detect_multi_isa`], with the same options applying; the winner is
+/// reported whatever its confidence.
+pub fn classify_windows(
+    data: &[u8],
+    options: &ClassifierOptions,
+    window_size: usize,
+) -> Vec<WindowClassification> {
+    classify_windows_excluding(data, options, window_size, &[])
+}
+
+/// [`classify_windows`], marking every window that overlaps one of the
+/// `(offset, length)` ranges in `opaque` as skipped.
+pub fn classify_windows_excluding(
+    data: &[u8],
+    options: &ClassifierOptions,
+    window_size: usize,
+    opaque: &[(u64, u64)],
+) -> Vec<WindowClassification> {
+    if window_size == 0 {
+        return Vec::new();
+    }
+
+    // Use window-appropriate options: scan entire window, low confidence threshold
+    let window_opts = ClassifierOptions {
+        min_confidence: 0.01,
+        deep_scan: options.deep_scan,
+        max_scan_bytes: window_size,
+        sample_mode: SampleMode::Prefix,
+        detect_extensions: false,
+        fast_mode: false,
+        allowed_isas: options.allowed_isas.clone(),
+        ..ClassifierOptions::default()
+    };
+
+    data.chunks_exact(window_size)
+        .enumerate()
+        .map(|(i, window)| {
+            let offset = i * window_size;
+            let (start, end) = (offset as u64, (offset + window_size) as u64);
+            let is_opaque = opaque
+                .iter()
+                .any(|&(o, len)| o < end && start < o.saturating_add(len));
+
+            // Pre-filter: skip opaque and obvious non-code windows
+            let skipped = if is_opaque {
+                Some(SkipReason::Opaque)
+            } else if is_padding_or_empty(window) {
+                Some(SkipReason::Padding)
+            } else if is_string_data(window) {
+                Some(SkipReason::Strings)
+            } else if is_high_entropy(window) {
+                Some(SkipReason::HighEntropy)
+            } else {
+                None
+            };
+
+            // The scores are sorted by normalized score descending with confidence computed
+            let best = skipped
+                .is_none()
+                .then(|| score_all_architectures(window, &window_opts))
+                .and_then(|scores| scores.into_iter().next());
+            WindowClassification {
+                offset,
+                isa: best.as_ref().map(|b| b.isa),
+                confidence: best.as_ref().map_or(0.0, |b| b.confidence),
+                raw_score: best.as_ref().map_or(0, |b| b.raw_score),
+                endianness: best.as_ref().map_or(Endianness::Little, |b| b.endianness),
+                bitwidth: best.as_ref().map_or(0, |b| b.bitwidth),
+                entropy: shannon_entropy(window),
+                skipped,
+            }
+        })
+        .collect()
+}
+
+/// Shannon entropy of `data` in bits per byte.
+fn shannon_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for &b in data {
+        counts[b as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c != 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
 /// Check if a window is padding (all same byte or all zeros/0xFF).
 fn is_padding_or_empty(data: &[u8]) -> bool {
     if data.is_empty() {
@@ -2146,7 +3115,7 @@ pub fn top_candidates(
     options: &ClassifierOptions,
 ) -> Vec<ArchitectureScore> {
     let mut scores = score_all_architectures(data, options);
-    scores.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+    scores.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
     scores.truncate(50);
     scores
 }
@@ -2154,6 +3123,7 @@ pub fn top_candidates(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::{self, AARCH64_FUNCTION};
 
     #[test]
     fn test_x86_detection() {
@@ -2204,6 +3174,218 @@ mod tests {
         assert_eq!(result.isa, Isa::AArch64);
     }
 
+    /// Cortex-M33 style Thumb-2 functions: IT blocks, CBZ/CBNZ, wide
+    /// loads and BL, with no A32 anywhere.
+    fn cortex_m33_code() -> Vec<u8> {
+        let mut code = Vec::new();
+        for n in 0..48u16 {
+            for hw in [
+                0xB5F0,           // push {r4-r7, lr}
+                0xB084,           // sub sp, #16
+                0xB120 | (n & 3), // cbz rN
+                0x2805,           // cmp r0, #5
+                0xBF0C,           // ite eq
+                0x2001,           // moveq r0, #1
+                0x2000,           // movne r0, #0
+                0x4B04,           // ldr r3, [pc, #16]
+                0xF8D0,
+                0x1004, // ldr.w r1, [r0, #4]
+                0x6019, // str r1, [r3]
+                0xF000,
+                0xF800 | ((n * 37) & 0x7FF), // bl
+                0xB909,                      // cbnz r1
+                0xF04F,
+                0x0001, // mov.w r0, #1
+                0xBF18, // it ne
+                0x2300, // movne r3, #0
+                0xB004, // add sp, #16
+                0xBDF0, // pop {r4-r7, pc}
+            ] {
+                code.extend(hw.to_le_bytes());
+            }
+        }
+        code
+    }
+
+    #[test]
+    fn test_cortex_m33_thumb_only_variant() {
+        let code = cortex_m33_code();
+        let result = analyze(&code, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        assert_eq!(result.variant_name(), Some(arm::THUMB_ONLY_VARIANT));
+    }
+
+    #[test]
+    fn test_aarch64_ilp32_keeps_isa() {
+        let mut code = Vec::new();
+        for n in 0..16u32 {
+            for word in [
+                0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+                0x1800_00C0, // ldr w0, #24
+                0x5282_0001, // movz w1, #0x1000
+                0x72A0_0801, // movk w1, #0x40, lsl #16
+                0xB940_0000, // ldr w0, [x0]
+                0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+                0xD65F_03C0, // ret
+                0x0041_0000 + n * 0x40,
+            ] {
+                code.extend(word.to_le_bytes());
+            }
+        }
+        let result = analyze(&code, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::AArch64);
+        assert_eq!(
+            result.variant.as_ref().and_then(|v| v.abi.as_deref()),
+            Some("ILP32")
+        );
+        assert!(result.metadata.notes.iter().any(|n| n.contains("ILP32")));
+    }
+
+    #[test]
+    fn test_normalized_scores_invariant_under_doubling() {
+        let block = [
+            0xFD, 0x7B, 0xBF, 0xA9, // stp x29, x30, [sp, #-16]!
+            0xFD, 0x03, 0x00, 0x91, // mov x29, sp
+            0xE0, 0x03, 0x00, 0xAA, // mov x0, x0
+            0x1F, 0x20, 0x03, 0xD5, // nop
+            0xFD, 0x7B, 0xC1, 0xA8, // ldp x29, x30, [sp], #16
+            0xC0, 0x03, 0x5F, 0xD6, // ret
+        ];
+        let once: Vec<u8> = block.repeat(16);
+        let twice: Vec<u8> = once.repeat(2);
+        let options = ClassifierOptions::thorough();
+
+        let a = score_all_architectures(&once, &options);
+        let b = score_all_architectures(&twice, &options);
+        assert_eq!(a[0].isa, Isa::AArch64);
+        assert_eq!(b[0].isa, Isa::AArch64);
+        assert!(b[0].raw_score > a[0].raw_score);
+
+        // Every scorer's normalized value, not just the winner's, must stay
+        // put, so the whole ranking (and hence margins) is preserved
+        for (x, y) in a.iter().zip(&b).take(5) {
+            assert_eq!(x.isa, y.isa);
+            if x.normalized > 0.0 {
+                let ratio = y.normalized / x.normalized;
+                assert!((0.9..=1.1).contains(&ratio), "{:?} ratio {ratio}", x.isa);
+            }
+        }
+        assert!((b[0].confidence - a[0].confidence).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_vector_table_weight_does_not_outrank_code() {
+        // 256 bytes of llc-compiled MSP430 code: an epilogue popping r4-r10,
+        // then a function saving r7-r10, calling helpers and spilling to the
+        // stack frame. Read as big-endian words, the last 128 bytes look like
+        // an HCS12 interrupt vector table clustered in a few flash pages,
+        // worth about 300 raw points; per-KiB alone let that one structure
+        // outrank 128 valid MSP430 instructions.
+        #[rustfmt::skip]
+        let window: [u8; 256] = [
+            0x0A, 0x5E, 0x1E, 0x43, 0x0E, 0xCC, 0x0E, 0x5A, 0x18, 0x43, 0x08, 0xCD, 0x08, 0x5E, 0x0C, 0x4F,
+            0x0D, 0x45, 0x0E, 0x4B, 0x0F, 0x48, 0x31, 0x50, 0x08, 0x00, 0x34, 0x41, 0x35, 0x41, 0x36, 0x41,
+            0x37, 0x41, 0x38, 0x41, 0x39, 0x41, 0x3A, 0x41, 0x30, 0x41, 0x0A, 0x12, 0x09, 0x12, 0x08, 0x12,
+            0x07, 0x12, 0x0A, 0x4F, 0x09, 0x4E, 0x08, 0x4D, 0x07, 0x4C, 0xB0, 0x12, 0x00, 0x00, 0x0C, 0x93,
+            0x1B, 0x24, 0x1D, 0x41, 0x12, 0x00, 0x1E, 0x42, 0x00, 0x00, 0x9D, 0x42, 0x00, 0x00, 0x00, 0x00,
+            0x8D, 0x4E, 0x02, 0x00, 0x1E, 0x42, 0x00, 0x00, 0x9D, 0x42, 0x00, 0x00, 0x04, 0x00, 0x8D, 0x4E,
+            0x06, 0x00, 0xB0, 0x12, 0x00, 0x00, 0x3C, 0x40, 0x00, 0x00, 0xB0, 0x12, 0x00, 0x00, 0x0C, 0x47,
+            0x0D, 0x48, 0x0E, 0x49, 0x0F, 0x4A, 0x04, 0x3C, 0x3C, 0x43, 0x3D, 0x43, 0x3E, 0x43, 0x3F, 0x43,
+            0x37, 0x41, 0x38, 0x41, 0x39, 0x41, 0x3A, 0x41, 0x30, 0x41, 0x0A, 0x12, 0x09, 0x12, 0x08, 0x12,
+            0x07, 0x12, 0x06, 0x12, 0x05, 0x12, 0x04, 0x12, 0x31, 0x80, 0x16, 0x00, 0x81, 0x4F, 0x0A, 0x00,
+            0x81, 0x4E, 0x00, 0x00, 0x81, 0x4D, 0x04, 0x00, 0x81, 0x4C, 0x02, 0x00, 0x07, 0x43, 0x1E, 0x41,
+            0x2C, 0x00, 0x15, 0x41, 0x2A, 0x00, 0x1C, 0x41, 0x28, 0x00, 0x81, 0x4C, 0x10, 0x00, 0x1C, 0x41,
+            0x26, 0x00, 0x81, 0x4C, 0x12, 0x00, 0x04, 0x43, 0x0C, 0x43, 0x81, 0x4C, 0x0E, 0x00, 0x0F, 0x43,
+            0x06, 0x43, 0x0D, 0x43, 0x4E, 0x3C, 0x1B, 0x41, 0x06, 0x00, 0x0C, 0x4B, 0x0C, 0x5C, 0x0C, 0x5C,
+            0x0C, 0x5C, 0x0C, 0x5C, 0x04, 0x45, 0x84, 0x10, 0x44, 0x44, 0x12, 0xC3, 0x04, 0x10, 0x04, 0x11,
+            0x04, 0x11, 0x04, 0x11, 0x04, 0xDC, 0x07, 0x57, 0x07, 0x57, 0x1C, 0x41, 0x2E, 0x00, 0x07, 0x5C,
+        ];
+        let options = ClassifierOptions::new();
+        let scores = score_all_architectures(&window, &options);
+        let points = |isa| {
+            let s = scores.iter().find(|s| s.isa == isa).unwrap();
+            (s.raw_score, s.normalized)
+        };
+        let (msp430_raw, msp430) = points(Isa::Msp430);
+        let (hcs12_raw, hcs12) = points(Isa::Hcs12);
+        // The pair points-per-KiB alone mis-ranked
+        assert!(hcs12_raw > msp430_raw, "{hcs12_raw} vs {msp430_raw}");
+        assert!(msp430 > hcs12, "{msp430} vs {hcs12}");
+
+        let result = analyze(&window, &options).unwrap();
+        assert_eq!(result.isa, Isa::Msp430);
+    }
+
+    #[test]
+    fn test_winner_stable_under_window_length() {
+        let words = |words: &[u32], big_endian: bool| -> Vec<u8> {
+            words
+                .iter()
+                .flat_map(|w| {
+                    if big_endian {
+                        w.to_be_bytes()
+                    } else {
+                        w.to_le_bytes()
+                    }
+                })
+                .collect()
+        };
+        let samples = [
+            (
+                Isa::AArch64,
+                test_fixtures::aarch64_code(AARCH64_FUNCTION.len()),
+            ),
+            (
+                Isa::Mips,
+                words(
+                    &[
+                        0x27BD_FFE0, // addiu sp, sp, -32
+                        0xAFBF_001C, // sw ra, 28(sp)
+                        0x8C82_0000, // lw v0, 0(a0)
+                        0x0C10_0000, // jal
+                        0x0000_0000, // nop
+                        0x8FBF_001C, // lw ra, 28(sp)
+                        0x03E0_0008, // jr ra
+                        0x27BD_0020, // addiu sp, sp, 32
+                    ],
+                    true,
+                ),
+            ),
+            (
+                Isa::X86_64,
+                vec![
+                    0x55, // push rbp
+                    0x48, 0x89, 0xE5, // mov rbp, rsp
+                    0x48, 0x83, 0xEC, 0x20, // sub rsp, 0x20
+                    0x48, 0x89, 0x7D, 0xF8, // mov [rbp-8], rdi
+                    0xE8, 0x00, 0x00, 0x00, 0x00, // call
+                    0x48, 0x8B, 0x45, 0xF8, // mov rax, [rbp-8]
+                    0xC9, // leave
+                    0xC3, // ret
+                ],
+            ),
+        ];
+
+        let options = ClassifierOptions::new();
+        let min = ClassifierOptions::MIN_STABLE_SCAN_BYTES;
+        for (isa, block) in samples {
+            let data = block.repeat(4 * min / block.len() + 1);
+            for top in [min, min + 333, 2 * min + 7] {
+                for len in top - 16..=top {
+                    let truncated = analyze(&data[..len], &options).unwrap();
+                    assert_eq!(truncated.isa, isa, "{isa} truncated to {len}");
+
+                    let windowed = ClassifierOptions {
+                        max_scan_bytes: len,
+                        ..options.clone()
+                    };
+                    let windowed = analyze(&data, &windowed).unwrap();
+                    assert_eq!(windowed.isa, isa, "{isa} window {len}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_riscv_detection() {
         // RISC-V with high-scoring patterns: NOP, RET, and compressed instructions
@@ -2270,22 +3452,31 @@ mod tests {
                 isa: Isa::X86_64,
                 raw_score: 2200,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::X86,
                 raw_score: 2100,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::RiscV64,
                 raw_score: 700,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
         ];
 
@@ -2309,22 +3500,31 @@ mod tests {
                 isa: Isa::X86_64,
                 raw_score: 20,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::X86,
                 raw_score: 20,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::Ppc,
                 raw_score: 14,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Big,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
         ];
 
@@ -2346,22 +3546,31 @@ mod tests {
                 isa: Isa::X86_64,
                 raw_score: 8083,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::X86,
                 raw_score: 7961,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::LoongArch64,
                 raw_score: 6969,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
         ];
 
@@ -2383,29 +3592,41 @@ mod tests {
                 isa: Isa::X86_64,
                 raw_score: 420,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::Parisc,
                 raw_score: 360,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Big,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::RiscV64,
                 raw_score: 410,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::Arm,
                 raw_score: 320,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
         ];
 
@@ -2427,29 +3648,41 @@ mod tests {
                 isa: Isa::RiscV64,
                 raw_score: 259,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 64,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::RiscV32,
                 raw_score: 259,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::Rl78,
                 raw_score: 204,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 16,
+                match_count: None,
+                padding_bytes: 0,
             },
             ArchitectureScore {
                 isa: Isa::Arm,
                 raw_score: 102,
                 confidence: 0.0,
+                normalized: 0.0,
                 endianness: Endianness::Little,
                 bitwidth: 32,
+                match_count: None,
+                padding_bytes: 0,
             },
         ];
 
@@ -2576,6 +3809,96 @@ mod tests {
         assert!(result.confidence >= options.min_confidence);
     }
 
+    #[test]
+    fn test_family_tie_reports_unknown_with_family_note() {
+        let aarch64 = [
+            0xA9BF7BFDu32, // stp x29, x30, [sp, #-16]!
+            0x910003FD,    // mov x29, sp
+            0xAA0003E0,    // mov x0, x0
+            0xAA0103E1,    // mov x1, x1
+            0xD503201F,    // nop
+            0xA8C17BFD,    // ldp x29, x30, [sp], #16
+            0xD65F03C0,    // ret
+            0x52800000,    // mov w0, #0
+        ];
+        let arm = [
+            0xE92D4010u32, // push {r4, lr}
+            0xE1A00000,    // nop (mov r0, r0)
+            0xE3A00000,    // mov r0, #0
+            0xE8BD4010,    // pop {r4, lr}
+            0xE12FFF1E,    // bx lr
+            0xE5910000,    // ldr r0, [r1]
+        ];
+        let mut data = Vec::new();
+        for w in aarch64.iter().cycle().take(aarch64.len() * 3) {
+            data.extend_from_slice(&w.to_le_bytes());
+        }
+        for w in arm.iter().cycle().take(arm.len() * 7) {
+            data.extend_from_slice(&w.to_le_bytes());
+        }
+
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Unknown(0));
+        assert_eq!(
+            result.metadata.notes,
+            vec!["likely ARM-family (AArch64/ARM ambiguous)".to_string()]
+        );
+        assert_eq!(result.candidates[0].isa, Isa::AArch64);
+        assert_eq!(result.candidates[1].isa, Isa::Arm);
+    }
+
+    fn aarch64_sample() -> Vec<u8> {
+        test_fixtures::aarch64_code(AARCH64_FUNCTION.len() * 64)
+    }
+
+    #[test]
+    fn test_byte_swapped_input_classified_through_swapped_view() {
+        let options = ClassifierOptions::new();
+        let sample = aarch64_sample();
+
+        let plain = analyze(&sample, &options).unwrap();
+        assert_eq!(plain.isa, Isa::AArch64);
+        assert!(plain.metadata.warnings.is_empty());
+
+        let swapped: Vec<u8> = sample
+            .chunks_exact(2)
+            .flat_map(|pair| [pair[1], pair[0]])
+            .collect();
+        let result = analyze(&swapped, &options).unwrap();
+        assert_eq!(result.isa, Isa::AArch64);
+        assert_eq!(
+            result.metadata.warnings,
+            ["input appears 16-bit byte-swapped; classification performed on swapped view"]
+        );
+        let entry = result
+            .metadata
+            .custom
+            .iter()
+            .find(|m| m.key == crate::types::MetadataKey::Custom("byte_swap".to_string()))
+            .unwrap();
+        assert_eq!(entry.value.to_string(), "16-bit byte-swapped");
+
+        let payload = crate::detect_payload(&swapped, &options).unwrap();
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        assert!(payload.notes.iter().any(|n| {
+            n.level == crate::types::NoteLevel::Warning && n.message.contains("byte-swapped")
+        }));
+        let payload = crate::detect_payload(&sample, &options).unwrap();
+        assert!(!payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("byte-swapped")));
+
+        // Opting in never flips a confident, correctly ordered input
+        let eager = ClassifierOptions {
+            detect_byte_swap: true,
+            ..ClassifierOptions::new()
+        };
+        let plain = analyze(&sample, &eager).unwrap();
+        assert_eq!(plain.isa, Isa::AArch64);
+        assert!(plain.metadata.warnings.is_empty());
+    }
+
     #[test]
     fn test_analyze_inconclusive_for_large_uniform_data() {
         let data = vec![0xFF; 12 * 1024];
@@ -2587,4 +3910,351 @@ mod tests {
             Err(ClassifierError::HeuristicInconclusive { .. })
         ));
     }
+
+    /// 512 bytes of Cortex-M vectors: SP in SRAM, then Thumb handlers with
+    /// a few reserved (zero) slots.
+    fn cortex_m_vector_table() -> Vec<u8> {
+        let mut data = 0x2000_8000u32.to_le_bytes().to_vec();
+        for i in 1..128u32 {
+            let handler = if i % 5 == 3 {
+                0
+            } else {
+                0x0800_0201 + i * 0x40
+            };
+            data.extend_from_slice(&handler.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_vector_table_only_caps_confidence() {
+        let data = cortex_m_vector_table();
+        assert_eq!(data.len(), 512);
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        assert!(result.confidence <= STRUCTURAL_ONLY_CONFIDENCE);
+        assert_eq!(result.match_count, Some(0));
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("primarily on a Cortex-M vector table")));
+    }
+
+    #[test]
+    fn test_vector_table_with_code_is_uncapped() {
+        let mut data = cortex_m_vector_table();
+        // push {r4-r6, lr}; ldr r0, [r1, #4]; adds r0, #1; str r0, [r1, #4];
+        // cmp r0, r2; bne; bl; ldr r3, [pc, #8]; pop {r4-r6, pc}; bx lr
+        let thumb: &[u8] = &[
+            0x70, 0xB5, 0x48, 0x68, 0x01, 0x30, 0x48, 0x60, 0x90, 0x42, 0x02, 0xD1, 0x00, 0xF0,
+            0x08, 0xF8, 0x02, 0x4B, 0x70, 0xBD, 0x70, 0x47,
+        ];
+        while data.len() < 512 + 2048 {
+            data.extend_from_slice(thumb);
+        }
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        assert!(result.confidence > STRUCTURAL_ONLY_CONFIDENCE);
+        assert!(!result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("little executable code")));
+    }
+
+    #[test]
+    fn test_vector_table_with_fill_is_capped() {
+        // Fill words are not Thumb code, however many follow the table
+        let mut data = cortex_m_vector_table();
+        data.extend([0xAD, 0xDE, 0xEF, 0xBE].repeat(256));
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        assert!(result.confidence <= STRUCTURAL_ONLY_CONFIDENCE);
+        assert_eq!(result.match_count, Some(0));
+    }
+
+    #[test]
+    fn test_trap_table_only() {
+        // LEON trap table: mov %psr, %l0; sethi %hi(h), %l4;
+        // jmp %l4 + %lo(h); mov %tbr, %l3
+        let mut data = Vec::new();
+        for k in 0..256u32 {
+            let handler = 0x4000_1000 + k * 0x40;
+            for word in [
+                0xA148_0000,
+                0x2900_0000 | handler >> 10,
+                0x81C5_2000 | (handler & 0x3FF),
+                0xA750_0000,
+            ] {
+                data.extend_from_slice(&u32::to_be_bytes(word));
+            }
+        }
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Sparc);
+        assert_eq!(result.endianness, Endianness::Big);
+        assert!(result.confidence <= STRUCTURAL_ONLY_CONFIDENCE);
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("primarily on a SPARC (LEON) trap table")));
+    }
+
+    #[test]
+    fn test_boot_sector_only() {
+        // jmp short 0x3E; nop; BPB; cli; xor ax, ax; mov ss, ax; ...; 55 AA
+        let mut data = vec![0u8; 512];
+        data[..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+        data[0x3E..0x48]
+            .copy_from_slice(&[0xFA, 0x31, 0xC0, 0x8E, 0xD0, 0xBC, 0x00, 0x7C, 0xFB, 0xC3]);
+        data[510..].copy_from_slice(&[0x55, 0xAA]);
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!((result.isa, result.bitwidth), (Isa::X86, 16));
+        assert!(result.confidence <= STRUCTURAL_ONLY_CONFIDENCE);
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("primarily on a PC boot sector")));
+    }
+
+    #[test]
+    fn test_sample_mode_windows() {
+        let strided = SampleMode::Strided {
+            windows: 4,
+            window_size: 100,
+        };
+        assert_eq!(
+            strided.windows(1000, 200),
+            Some(vec![(0, 100), (300, 400), (600, 700), (900, 1000)])
+        );
+        // Small inputs and full coverage scan as a prefix
+        assert_eq!(strided.windows(200, 200), None);
+        assert_eq!(strided.windows(400, 200), None);
+        assert_eq!(SampleMode::Prefix.windows(1000, 200), None);
+        let uniform = SampleMode::Uniform { n_samples: 2 };
+        assert_eq!(
+            uniform.windows(1000, 200),
+            Some(vec![(0, 100), (900, 1000)])
+        );
+    }
+
+    #[test]
+    fn test_strided_sampling_finds_code_at_end() {
+        // 1 MiB of noise, then AArch64 code
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut data: Vec<u8> = (0..1024 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect();
+        while data.len() < (1024 + 32) * 1024 {
+            data.extend_from_slice(&aarch64_sample());
+        }
+
+        let prefix = ClassifierOptions {
+            max_scan_bytes: 64 * 1024,
+            ..ClassifierOptions::new()
+        };
+        assert!(analyze(&data, &prefix).map_or(true, |r| r.isa != Isa::AArch64));
+
+        let strided = ClassifierOptions {
+            sample_mode: SampleMode::Strided {
+                windows: 8,
+                window_size: 8192,
+            },
+            ..prefix
+        };
+        let result = analyze(&data, &strided).unwrap();
+        assert_eq!(result.isa, Isa::AArch64);
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n == "Scores aggregated over 8 sampled windows of 8192 bytes"));
+    }
+
+    #[test]
+    fn test_multi_isa_honors_options() {
+        // 8 KiB of x86-64 code using AVX, then 8 KiB of AArch64
+        let mut data = Vec::new();
+        while data.len() < 8 * 1024 {
+            // push rbp; mov rbp, rsp; vpaddd ymm0, ymm1, ymm2; call rel32;
+            // mov rax, [rbp-8]; pop rbp; ret
+            data.extend_from_slice(&[
+                0x55, 0x48, 0x89, 0xE5, 0xC5, 0xF5, 0xFE, 0xC2, 0xE8, 0x10, 0x00, 0x00, 0x00, 0x48,
+                0x8B, 0x45, 0xF8, 0x5D, 0xC3,
+            ]);
+        }
+        data.truncate(8 * 1024);
+        while data.len() < 16 * 1024 {
+            data.extend_from_slice(&aarch64_sample());
+        }
+        data.truncate(16 * 1024);
+
+        let isas = |options: &ClassifierOptions| -> Vec<Isa> {
+            let mut isas: Vec<Isa> = detect_multi_isa(&data, options, 1024)
+                .into_iter()
+                .map(|d| d.isa)
+                .collect();
+            isas.sort_by_key(ToString::to_string);
+            isas
+        };
+
+        let options = ClassifierOptions::new();
+        assert_eq!(isas(&options), vec![Isa::AArch64, Isa::X86_64]);
+
+        let only_arm = ClassifierOptions {
+            allowed_isas: vec![Isa::AArch64],
+            ..ClassifierOptions::new()
+        };
+        assert_eq!(isas(&only_arm), vec![Isa::AArch64]);
+
+        // A window winner needs min_confidence * 0.47; 2.0 is out of reach
+        let strict = ClassifierOptions {
+            min_confidence: 2.0,
+            ..ClassifierOptions::new()
+        };
+        assert!(isas(&strict).is_empty());
+
+        // Extensions only with detect_extensions, from the ISA's own windows
+        let detected = detect_multi_isa(&data, &ClassifierOptions::fast(), 1024);
+        assert!(detected.iter().all(|d| d.extensions.is_empty()));
+        let detected = detect_multi_isa(&data, &options, 1024);
+        let x86 = detected.iter().find(|d| d.isa == Isa::X86_64).unwrap();
+        assert!(x86.extensions.iter().any(|e| e.name == "AVX"));
+        let arm = detected.iter().find(|d| d.isa == Isa::AArch64).unwrap();
+        assert!(arm.extensions.iter().all(|e| e.name != "AVX"));
+    }
+
+    #[test]
+    fn test_classify_windows() {
+        let mut data = Vec::new();
+        while data.len() < 4 * 1024 {
+            data.extend_from_slice(&aarch64_sample());
+        }
+        data.truncate(4 * 1024);
+        data.extend_from_slice(&[0u8; 1024]);
+        data.extend(b"Press any key to continue. ".iter().cycle().take(1024));
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        data.extend((0..1024).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u8
+        }));
+        // A trailing partial window is not reported
+        data.extend_from_slice(&[0x90; 100]);
+
+        let windows = classify_windows(&data, &ClassifierOptions::new(), 1024);
+        assert_eq!(windows.len(), data.len() / 1024);
+        assert_eq!(windows.len(), 7);
+        for (i, w) in windows.iter().enumerate() {
+            assert_eq!(w.offset, i * 1024);
+            assert!((0.0..=8.0).contains(&w.entropy));
+        }
+        for w in &windows[..4] {
+            assert_eq!(w.isa, Some(Isa::AArch64));
+            assert_eq!(w.skipped, None);
+            assert!(w.confidence > 0.0 && w.raw_score > 0);
+        }
+        let skipped: Vec<_> = windows[4..].iter().map(|w| w.skipped).collect();
+        assert_eq!(
+            skipped,
+            [
+                Some(SkipReason::Padding),
+                Some(SkipReason::Strings),
+                Some(SkipReason::HighEntropy)
+            ]
+        );
+        assert!(windows[4..].iter().all(|w| w.isa.is_none()));
+        assert!(windows[4].entropy.abs() < f64::EPSILON);
+        assert!(windows[6].entropy > 7.5);
+
+        let opaque = classify_windows_excluding(&data, &ClassifierOptions::new(), 1024, &[(0, 1)]);
+        assert_eq!(opaque[0].skipped, Some(SkipReason::Opaque));
+        assert_eq!(opaque[1].isa, Some(Isa::AArch64));
+    }
+
+    #[test]
+    fn test_multi_isa_extensions_per_region() {
+        // 8 KiB of AArch64 with PAC and AES, then 8 KiB of x86-64 with AVX
+        let arm_function: [u32; 8] = [
+            0xD503_233F, // paciasp
+            0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+            0x9100_03FD, // mov x29, sp
+            0x4E28_4800, // aese v0.16b, v0.16b
+            0x9400_0010, // bl +0x40
+            0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+            0xD503_23BF, // autiasp
+            0xD65F_03C0, // ret
+        ];
+        let arm: Vec<u8> = arm_function.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let mut data = Vec::new();
+        while data.len() < 8 * 1024 {
+            data.extend_from_slice(&arm);
+        }
+        data.truncate(8 * 1024);
+        while data.len() < 16 * 1024 {
+            // push rbp; mov rbp, rsp; vpaddd ymm0, ymm1, ymm2; call rel32;
+            // mov rax, [rbp-8]; pop rbp; ret
+            data.extend_from_slice(&[
+                0x55, 0x48, 0x89, 0xE5, 0xC5, 0xF5, 0xFE, 0xC2, 0xE8, 0x10, 0x00, 0x00, 0x00, 0x48,
+                0x8B, 0x45, 0xF8, 0x5D, 0xC3,
+            ]);
+        }
+        data.truncate(16 * 1024);
+
+        let detected = detect_multi_isa(&data, &ClassifierOptions::new(), 1024);
+        let names = |isa: Isa| -> Vec<String> {
+            let d = detected.iter().find(|d| d.isa == isa).unwrap();
+            d.extensions.iter().map(|e| e.name.clone()).collect()
+        };
+        let arm_names = names(Isa::AArch64);
+        assert!(arm_names.contains(&"PAC".to_string()), "{arm_names:?}");
+        assert!(arm_names.contains(&"AES".to_string()), "{arm_names:?}");
+        assert!(!arm_names.iter().any(|n| n.starts_with("AVX")));
+        let x86_names = names(Isa::X86_64);
+        assert!(x86_names.contains(&"AVX".to_string()), "{x86_names:?}");
+        assert!(!x86_names.iter().any(|n| n == "PAC" || n == "AES"));
+
+        let union: Vec<String> = union_extensions(&detected)
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        for name in arm_names.iter().chain(&x86_names) {
+            assert_eq!(union.iter().filter(|n| *n == name).count(), 1);
+        }
+
+        // A budget of one window still sees the first window of each region
+        let tight = ClassifierOptions {
+            region_extension_budget: 1024,
+            ..ClassifierOptions::new()
+        };
+        let detected = detect_multi_isa(&data, &tight, 1024);
+        let arm = detected.iter().find(|d| d.isa == Isa::AArch64).unwrap();
+        assert!(arm.extensions.iter().any(|e| e.name == "PAC"));
+
+        // Regions under 1 KiB are not scanned at all
+        let windows = [(1, Endianness::Little, 64, 0)];
+        assert!(region_extensions(&data, Isa::AArch64, &windows, 512, usize::MAX).is_empty());
+        assert!(!region_extensions(&data, Isa::AArch64, &windows, 1024, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_avr_vector_table_only() {
+        // jmp 0x0068 followed by jmp __bad_interrupt, as avr-libc lays it out
+        let mut data = vec![0x0C, 0x94, 0x34, 0x00];
+        for _ in 0..25 {
+            data.extend_from_slice(&[0x0C, 0x94, 0x51, 0x00]);
+        }
+        data.resize(256, 0xFF);
+        let result = analyze(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Avr);
+        assert!(result.confidence <= STRUCTURAL_ONLY_CONFIDENCE);
+    }
 }
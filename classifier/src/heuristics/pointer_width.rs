@@ -0,0 +1,164 @@
+//! Pointer-width corroboration for raw code.
+//!
+//! Instruction statistics name an ISA; the address constants the code
+//! materializes say how wide its pointers are. Built on the
+//! [`constants`](super::constants) pass.
+//!
+//! The check is ARM-aware. On the ARM family the instruction set fixes the
+//! mode: AArch64 encodings cannot run as A32 or Thumb, so 32-bit pointers
+//! in AArch64 code mean the ILP32 ABI (`arm64_32`, `-mabi=ilp32`), never a
+//! 32-bit Arm image. The result keeps [`Isa::AArch64`] and gains a note.
+
+use crate::types::{ClassificationResult, Endianness, Isa, MetadataEntry, MetadataValue, Variant};
+
+use super::constants::{self, ConstantScan};
+
+/// Fewest address constants needed before the pointer width is judged.
+pub const MIN_ADDRESSES: usize = 4;
+
+/// Pointer width, in bits, implied by the address constants of `data`
+/// decoded as `isa`.
+///
+/// Returns `None` for unsupported ISAs, with too few address constants,
+/// and for AArch64 code whose low addresses are built in 64-bit registers
+/// (LP64 code linked below 4 GiB).
+pub fn observed(data: &[u8], isa: Isa, endianness: Endianness) -> Option<u8> {
+    if !matches!(isa, Isa::Arm | Isa::AArch64) {
+        return None;
+    }
+    judge(&constants::collect(data, isa, endianness), isa)
+}
+
+/// Decide from a constant scan of code decoded as `isa`.
+fn judge(scan: &ConstantScan, isa: Isa) -> Option<u8> {
+    let addresses: Vec<u64> = scan.addresses().collect();
+    if addresses.len() < MIN_ADDRESSES {
+        return None;
+    }
+    if addresses.iter().any(|&a| a > u64::from(u32::MAX)) {
+        return Some(64);
+    }
+    // Low addresses alone do not make ILP32: it also loads and builds
+    // them in W registers and dereferences them
+    if isa == Isa::AArch64 && scan.narrow * 2 < scan.absolute.len() {
+        return None;
+    }
+    Some(32)
+}
+
+/// Check `result` against the pointer width of `data` and annotate it.
+///
+/// Never changes the ISA: AArch64 with 32-bit pointers is reported as
+/// ILP32 AArch64.
+pub fn corroborate(result: &mut ClassificationResult, data: &[u8]) {
+    if result.isa != Isa::AArch64 {
+        return;
+    }
+    if observed(data, result.isa, result.endianness) != Some(32) {
+        return;
+    }
+    result.variant = Some(Variant::with_abi("AArch64", "ILP32"));
+    result.metadata.custom.push(
+        MetadataEntry::custom("pointer_width", MetadataValue::Integer(32), "Pointer Width")
+            .with_origin("pointer_width"),
+    );
+    result
+        .metadata
+        .notes
+        .push("32-bit pointer constants with AArch64 encodings: ILP32 AArch64 code".to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_le(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    /// AArch64 ILP32 function: pointers come from `ldr wN` literals and
+    /// `movz`/`movk wN` pairs and are dereferenced as 32-bit words.
+    fn ilp32_aarch64() -> Vec<u8> {
+        let mut code = Vec::new();
+        for n in 0..16u32 {
+            code.extend(words_le(&[
+                0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+                0x1800_00E0, // ldr w0, #28
+                0x5282_0001, // movz w1, #0x1000
+                0x72A0_0801, // movk w1, #0x40, lsl #16
+                0xB940_0000, // ldr w0, [x0]
+                0xB940_0022, // ldr w2, [x1]
+                0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+                0xD65F_03C0, // ret
+            ]));
+            code.extend((0x0041_0000 + n * 0x40).to_le_bytes());
+        }
+        code
+    }
+
+    #[test]
+    fn test_ilp32_keeps_aarch64() {
+        let code = ilp32_aarch64();
+        assert_eq!(observed(&code, Isa::AArch64, Endianness::Little), Some(32));
+
+        let mut result =
+            ClassificationResult::from_heuristics(Isa::AArch64, 64, Endianness::Little, 0.9);
+        corroborate(&mut result, &code);
+        assert_eq!(result.isa, Isa::AArch64);
+        assert_eq!(
+            result.variant.as_ref().and_then(|v| v.abi.as_deref()),
+            Some("ILP32")
+        );
+        assert!(result.metadata.notes.iter().any(|n| n.contains("ILP32")));
+        assert!(matches!(
+            result.metadata.custom[0].value,
+            MetadataValue::Integer(32)
+        ));
+    }
+
+    #[test]
+    fn test_lp64_w_register_constants_are_not_ilp32() {
+        // LP64 code mixing 32-bit constants into W registers arithmetically
+        // (hash seeds, masks) without ever dereferencing them
+        let mut code = Vec::new();
+        for _ in 0..16 {
+            code.extend(words_le(&[
+                0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+                0x9100_03FD, // mov x29, sp
+                0x5282_0008, // movz w8, #0x1000
+                0x72A0_0808, // movk w8, #0x40, lsl #16
+                0x4A08_0000, // eor w0, w0, w8
+                0xF940_0009, // ldr x9, [x0]
+                0x0B09_0000, // add w0, w0, w9
+                0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+                0xD65F_03C0, // ret
+            ]));
+        }
+        assert_eq!(observed(&code, Isa::AArch64, Endianness::Little), None);
+
+        let mut result =
+            ClassificationResult::from_heuristics(Isa::AArch64, 64, Endianness::Little, 0.9);
+        corroborate(&mut result, &code);
+        assert!(result.variant.is_none());
+        assert!(result.metadata.notes.is_empty());
+    }
+
+    #[test]
+    fn test_lp64_low_addresses_stay_unjudged() {
+        // movz/movk x-register chains below 4 GiB: LP64 linked low
+        let scan = ConstantScan {
+            absolute: vec![0x0040_0000, 0x0040_1000, 0x0040_2000, 0x0040_3000],
+            narrow: 0,
+            ..Default::default()
+        };
+        assert_eq!(judge(&scan, Isa::AArch64), None);
+        assert_eq!(judge(&scan, Isa::Arm), Some(32));
+
+        let scan = ConstantScan {
+            absolute: vec![0x5555_0000_1000; 4],
+            ..Default::default()
+        };
+        assert_eq!(judge(&scan, Isa::AArch64), Some(64));
+        assert_eq!(observed(&[0u8; 64], Isa::X86_64, Endianness::Little), None);
+    }
+}
@@ -7,14 +7,126 @@
 //! that architecture.
 //!
 //! The actual scoring logic is implemented in `crate::architectures::*::score()`.
+//!
+//! # Score scale
+//!
+//! Scorers return raw points summed over the bytes they were given, so raw
+//! scores grow with input size. The heuristics engine converts every score
+//! to a common normalized scale, **points per KiB scanned** ([`normalize`]),
+//! leaving out of each ISA's denominator the alignment padding its scorer
+//! treated as neutral. Candidates are ranked, and share and margin
+//! confidences computed, on the normalized scale; doubling an input by
+//! concatenating it with itself leaves both approximately unchanged. Raw
+//! scores remain for the absolute evidence floors a few fallbacks apply.
+//!
+//! Normalization is per scorer: a scorer whose largest per-match weight
+//! ([`weight_ceiling`]) exceeds the shared [`SLOT_WEIGHT_CEILING`] has its
+//! points scaled down by the ratio of the two, so a 150-point C6000
+//! signature word counts as much as a 60-point AArch64 system instruction.
+//! Without this, a single HCS12 reset vector (200 points) outweighs a whole
+//! 256-byte window of valid MSP430 code.
+//!
+//! Within a scorer, point weights follow a shared rough scale per matched
+//! instruction slot:
+//!
+//! | Weight | Evidence |
+//! |--------|----------|
+//! | 1-5    | common opcode classes (moves, ALU, loads/stores) |
+//! | 5-15   | control flow (branches, calls) |
+//! | 15-30  | highly distinctive idioms (returns, prologues, NOP encodings) |
+//! | 30-60  | encodings unique to the ISA (system registers, exception returns) |
+//! | <0     | encodings invalid for the ISA |
+//!
+//! An instruction slot is one naturally aligned position for the ISA: one
+//! byte for byte-aligned ISAs (x86, VAX, bytecodes), two bytes for 16-bit
+//! aligned ones (Thumb, SH, RISC-V C) and four bytes for fixed 32-bit
+//! ISAs. Scorers that validate whole packets or bundles (Hexagon packets,
+//! IA-64 bundles, TI C6000 fetch packets) award points per packet; one
+//! packet counts as its constituent slots, so a valid 16-byte bundle carries
+//! the weight of four 32-bit slots.
+//!
+//! A few existing scorers use larger weights for exact-word signatures (TI
+//! C6000 awards 150 per known word, HC11 and HCS12 up to 200 for a reset
+//! vector); those magnitudes were tuned against the
+//! accuracy corpus (`analyze_accuracy.py`) and should only change together
+//! with a re-run of it. Such scorers must list their ceiling in
+//! [`weight_ceiling`] so they are rescaled rather than dominating the
+//! ranking. New scorers should use [`normalized_points`] when reasoning
+//! about expected magnitudes.
+//!
+//! # Scan windows
+//!
+//! Every delegate below hands its scorer a window trimmed to whole
+//! instruction slots with [`slots`]: two bytes for 16-bit aligned ISAs, four
+//! for fixed 32-bit ISAs and sixteen for IA-64 bundles. Byte-aligned scorers
+//! (x86, VAX, the 8/16-bit microcontrollers, bytecodes) see the window as
+//! given. Scorers must not read past the slice they receive; a trailing
+//! instruction whose encoding runs off the end is skipped, not scored.
+
+use crate::types::Isa;
 
 use crate::architectures::{
-    aarch64, alpha, arc, arm, avr, blackfin, c166, cellspu, csky, dalvik, fr30, hc11, hcs12,
-    hexagon, i860, ia64, jvm, lanai, loongarch, m68k, microblaze, mips, msp430, nios2, openrisc,
-    parisc, ppc, ppcvle, riscv, rl78, s12z, s390x, sparc, superh, tic6000, tricore, v850, vax,
-    wasm, x86, xtensa,
+    aarch64, alpha, arc, arm, avr, blackfin, c166, cellspu, csky, dalvik, fr30, frv, hc11, hcs12,
+    hexagon, i860, ia64, jvm, lanai, loongarch, m68k, microblaze, mips, mos6502, msp430, nios2,
+    openrisc, parisc, pic, pic24, ppc, ppcvle, riscv, rl78, s12z, s390x, sparc, superh, tic6000,
+    tilegx, tricore, v850, vax, videocore, wasm, x86, xtensa, z80,
 };
 
+/// Bytes per normalization unit (normalized scores are points per KiB).
+pub const NORMALIZATION_UNIT: usize = 1024;
+
+/// Largest weight the shared scale awards one match.
+pub const SLOT_WEIGHT_CEILING: i64 = 60;
+
+/// Largest weight `isa`'s scorer awards one match: an instruction, idiom,
+/// signature word or single vector-table entry.
+///
+/// Multi-slot idioms and packets count per constituent slot. Bonuses for a
+/// structure validated as a whole (ARM and SuperH vector tables) are gated
+/// by their own checks and are not per-match weights. Scorers within the
+/// shared scale report [`SLOT_WEIGHT_CEILING`].
+pub fn weight_ceiling(isa: Isa) -> i64 {
+    match isa {
+        Isa::TiC6000 => 150,
+        Isa::Hc11 => 150,
+        Isa::Hcs12 => 200,
+        _ => SLOT_WEIGHT_CEILING,
+    }
+}
+
+/// Convert `isa`'s raw score over `scanned_bytes` into points per KiB on
+/// the shared scale.
+///
+/// Returns 0.0 when nothing was scanned.
+pub fn normalize(isa: Isa, raw: i64, scanned_bytes: usize) -> f64 {
+    if scanned_bytes == 0 {
+        return 0.0;
+    }
+    let scale = SLOT_WEIGHT_CEILING as f64 / weight_ceiling(isa).max(SLOT_WEIGHT_CEILING) as f64;
+    raw as f64 * NORMALIZATION_UNIT as f64 / scanned_bytes as f64 * scale
+}
+
+/// Normalized value of `hits` pattern matches of `weight` points each by
+/// `isa`'s scorer over `scanned_bytes`.
+pub fn normalized_points(isa: Isa, hits: u64, weight: i64, scanned_bytes: usize) -> f64 {
+    normalize(
+        isa,
+        i64::try_from(hits)
+            .unwrap_or(i64::MAX)
+            .saturating_mul(weight),
+        scanned_bytes,
+    )
+}
+
+/// The longest prefix of `data` made of whole `slot`-byte instruction slots.
+///
+/// Scorers only ever see whole slots, so a window cut mid-instruction
+/// scores exactly like the same window cut at the previous slot boundary.
+#[inline]
+pub fn slots(data: &[u8], slot: usize) -> &[u8] {
+    &data[..data.len() - data.len() % slot]
+}
+
 // =============================================================================
 // Delegating functions to architecture modules
 // =============================================================================
@@ -27,12 +139,36 @@ pub fn score_x86(data: &[u8], bits: u8) -> i64 {
     x86::score(data, bits)
 }
 
+/// Like [`score_x86`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::x86::score_with_matches()`.
+#[inline]
+pub fn score_x86_with_matches(data: &[u8], bits: u8) -> (i64, u32) {
+    x86::score_with_matches(data, bits)
+}
+
+/// Like [`score_x86_with_matches`], also returning the padding bytes skipped.
+///
+/// Delegates to `crate::architectures::x86::score_with_padding()`.
+#[inline]
+pub fn score_x86_with_padding(data: &[u8], bits: u8) -> (i64, u32, usize) {
+    x86::score_with_padding(data, bits)
+}
+
 /// Score likelihood of ARM32 code.
 ///
 /// Delegates to `crate::architectures::arm::score()`.
 #[inline]
 pub fn score_arm(data: &[u8]) -> i64 {
-    arm::score(data)
+    arm::score(slots(data, 2))
+}
+
+/// Like [`score_arm`], also returning the padding bytes skipped.
+///
+/// Delegates to `crate::architectures::arm::score_with_padding()`.
+#[inline]
+pub fn score_arm_with_padding(data: &[u8]) -> (i64, usize) {
+    arm::score_with_padding(slots(data, 2))
 }
 
 /// Score likelihood of AArch64 code.
@@ -40,7 +176,24 @@ pub fn score_arm(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::aarch64::score()`.
 #[inline]
 pub fn score_aarch64(data: &[u8]) -> i64 {
-    aarch64::score(data)
+    aarch64::score(slots(data, 4))
+}
+
+/// Like [`score_aarch64`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::aarch64::score_with_matches()`.
+#[inline]
+pub fn score_aarch64_with_matches(data: &[u8]) -> (i64, u32) {
+    aarch64::score_with_matches(slots(data, 4))
+}
+
+/// Like [`score_aarch64_with_matches`], also returning the padding bytes
+/// skipped.
+///
+/// Delegates to `crate::architectures::aarch64::score_with_padding()`.
+#[inline]
+pub fn score_aarch64_with_padding(data: &[u8]) -> (i64, u32, usize) {
+    aarch64::score_with_padding(slots(data, 4))
 }
 
 /// Score likelihood of RISC-V code.
@@ -48,7 +201,24 @@ pub fn score_aarch64(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::riscv::score()`.
 #[inline]
 pub fn score_riscv(data: &[u8], bits: u8) -> i64 {
-    riscv::score(data, bits)
+    riscv::score(slots(data, 2), bits)
+}
+
+/// Like [`score_riscv`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::riscv::score_with_matches()`.
+#[inline]
+pub fn score_riscv_with_matches(data: &[u8], bits: u8) -> (i64, u32) {
+    riscv::score_with_matches(slots(data, 2), bits)
+}
+
+/// Like [`score_riscv_with_matches`], also returning the padding bytes
+/// skipped.
+///
+/// Delegates to `crate::architectures::riscv::score_with_padding()`.
+#[inline]
+pub fn score_riscv_with_padding(data: &[u8], bits: u8) -> (i64, u32, usize) {
+    riscv::score_with_padding(slots(data, 2), bits)
 }
 
 /// Score likelihood of MIPS code.
@@ -57,7 +227,15 @@ pub fn score_riscv(data: &[u8], bits: u8) -> i64 {
 /// Delegates to `crate::architectures::mips::score()`.
 #[inline]
 pub fn score_mips(data: &[u8], is_64: bool) -> (i64, i64) {
-    mips::score(data, is_64)
+    mips::score(slots(data, 4), is_64)
+}
+
+/// Like [`score_mips`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::mips::score_with_matches()`.
+#[inline]
+pub fn score_mips_with_matches(data: &[u8], is_64: bool) -> ((i64, u32), (i64, u32)) {
+    mips::score_with_matches(slots(data, 4), is_64)
 }
 
 /// Score likelihood of PowerPC code (big-endian).
@@ -65,7 +243,15 @@ pub fn score_mips(data: &[u8], is_64: bool) -> (i64, i64) {
 /// Delegates to `crate::architectures::ppc::score()`.
 #[inline]
 pub fn score_ppc(data: &[u8]) -> i64 {
-    ppc::score(data)
+    ppc::score(slots(data, 4))
+}
+
+/// Like [`score_ppc`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::ppc::score_with_matches()`.
+#[inline]
+pub fn score_ppc_with_matches(data: &[u8]) -> (i64, u32) {
+    ppc::score_with_matches(slots(data, 4))
 }
 
 /// Score likelihood of PowerPC code (little-endian).
@@ -73,15 +259,23 @@ pub fn score_ppc(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::ppc::score_le()`.
 #[inline]
 pub fn score_ppc_le(data: &[u8]) -> i64 {
-    ppc::score_le(data)
+    ppc::score_le(slots(data, 4))
+}
+
+/// Like [`score_ppc_le`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::ppc::score_le_with_matches()`.
+#[inline]
+pub fn score_ppc_le_with_matches(data: &[u8]) -> (i64, u32) {
+    ppc::score_le_with_matches(slots(data, 4))
 }
 
-/// Score likelihood of SPARC code.
+/// Score likelihood of SPARC code (V9 when `is_64`, V8 otherwise).
 ///
 /// Delegates to `crate::architectures::sparc::score()`.
 #[inline]
-pub fn score_sparc(data: &[u8]) -> i64 {
-    sparc::score(data)
+pub fn score_sparc(data: &[u8], is_64: bool) -> i64 {
+    sparc::score(slots(data, 4), is_64)
 }
 
 /// Score likelihood of s390x code.
@@ -89,7 +283,7 @@ pub fn score_sparc(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::s390x::score()`.
 #[inline]
 pub fn score_s390x(data: &[u8]) -> i64 {
-    s390x::score(data)
+    s390x::score(slots(data, 2))
 }
 
 /// Score likelihood of m68k code.
@@ -97,7 +291,15 @@ pub fn score_s390x(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::m68k::score()`.
 #[inline]
 pub fn score_m68k(data: &[u8]) -> i64 {
-    m68k::score(data)
+    m68k::score(slots(data, 2))
+}
+
+/// Like [`score_m68k`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::m68k::score_with_matches()`.
+#[inline]
+pub fn score_m68k_with_matches(data: &[u8]) -> (i64, u32) {
+    m68k::score_with_matches(slots(data, 2))
 }
 
 /// Score likelihood of SuperH code.
@@ -106,7 +308,7 @@ pub fn score_m68k(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::superh::score()`.
 #[inline]
 pub fn score_superh(data: &[u8]) -> (i64, i64) {
-    superh::score(data)
+    superh::score(slots(data, 2))
 }
 
 /// Score likelihood of AVR code.
@@ -114,7 +316,7 @@ pub fn score_superh(data: &[u8]) -> (i64, i64) {
 /// Delegates to `crate::architectures::avr::score()`.
 #[inline]
 pub fn score_avr(data: &[u8]) -> i64 {
-    avr::score(data)
+    avr::score(slots(data, 2))
 }
 
 /// Score likelihood of MSP430 code.
@@ -122,7 +324,7 @@ pub fn score_avr(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::msp430::score()`.
 #[inline]
 pub fn score_msp430(data: &[u8]) -> i64 {
-    msp430::score(data)
+    msp430::score(slots(data, 2))
 }
 
 /// Score likelihood of LoongArch code.
@@ -130,7 +332,15 @@ pub fn score_msp430(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::loongarch::score()`.
 #[inline]
 pub fn score_loongarch(data: &[u8]) -> i64 {
-    loongarch::score(data)
+    loongarch::score(slots(data, 4))
+}
+
+/// Like [`score_loongarch`], also returning the distinctive idiom count.
+///
+/// Delegates to `crate::architectures::loongarch::score_with_matches()`.
+#[inline]
+pub fn score_loongarch_with_matches(data: &[u8]) -> (i64, u32) {
+    loongarch::score_with_matches(slots(data, 4))
 }
 
 /// Score likelihood of Lanai code.
@@ -138,7 +348,7 @@ pub fn score_loongarch(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::lanai::score()`.
 #[inline]
 pub fn score_lanai(data: &[u8]) -> i64 {
-    lanai::score(data)
+    lanai::score(slots(data, 4))
 }
 
 /// Score likelihood of Hexagon code.
@@ -146,7 +356,7 @@ pub fn score_lanai(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::hexagon::score()`.
 #[inline]
 pub fn score_hexagon(data: &[u8]) -> i64 {
-    hexagon::score(data)
+    hexagon::score(slots(data, 4))
 }
 
 /// Score likelihood of TriCore code.
@@ -154,7 +364,7 @@ pub fn score_hexagon(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::tricore::score()`.
 #[inline]
 pub fn score_tricore(data: &[u8]) -> i64 {
-    tricore::score(data)
+    tricore::score(slots(data, 2))
 }
 
 // =============================================================================
@@ -166,7 +376,7 @@ pub fn score_tricore(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::alpha::score()`.
 #[inline]
 pub fn score_alpha(data: &[u8]) -> i64 {
-    alpha::score(data)
+    alpha::score(slots(data, 4))
 }
 
 /// Score likelihood of PA-RISC code.
@@ -174,7 +384,7 @@ pub fn score_alpha(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::parisc::score()`.
 #[inline]
 pub fn score_parisc(data: &[u8]) -> i64 {
-    parisc::score(data)
+    parisc::score(slots(data, 4))
 }
 
 /// Score likelihood of ARC code.
@@ -182,7 +392,7 @@ pub fn score_parisc(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::arc::score()`.
 #[inline]
 pub fn score_arc(data: &[u8]) -> i64 {
-    arc::score(data)
+    arc::score(slots(data, 2))
 }
 
 /// Score likelihood of Xtensa code.
@@ -198,7 +408,7 @@ pub fn score_xtensa(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::microblaze::score()`.
 #[inline]
 pub fn score_microblaze(data: &[u8]) -> i64 {
-    microblaze::score(data)
+    microblaze::score(slots(data, 4))
 }
 
 /// Score likelihood of Nios II code.
@@ -206,7 +416,7 @@ pub fn score_microblaze(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::nios2::score()`.
 #[inline]
 pub fn score_nios2(data: &[u8]) -> i64 {
-    nios2::score(data)
+    nios2::score(slots(data, 4))
 }
 
 /// Score likelihood of OpenRISC code.
@@ -214,7 +424,7 @@ pub fn score_nios2(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::openrisc::score()`.
 #[inline]
 pub fn score_openrisc(data: &[u8]) -> i64 {
-    openrisc::score(data)
+    openrisc::score(slots(data, 4))
 }
 
 // =============================================================================
@@ -242,7 +452,7 @@ pub fn score_wasm(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::dalvik::score()`.
 #[inline]
 pub fn score_dalvik(data: &[u8]) -> i64 {
-    dalvik::score(data)
+    dalvik::score(slots(data, 2))
 }
 
 // =============================================================================
@@ -254,7 +464,7 @@ pub fn score_dalvik(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::blackfin::score()`.
 #[inline]
 pub fn score_blackfin(data: &[u8]) -> i64 {
-    blackfin::score(data)
+    blackfin::score(slots(data, 2))
 }
 
 /// Score likelihood of IA-64/Itanium code.
@@ -262,7 +472,7 @@ pub fn score_blackfin(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::ia64::score()`.
 #[inline]
 pub fn score_ia64(data: &[u8]) -> i64 {
-    ia64::score(data)
+    ia64::score(slots(data, 16))
 }
 
 /// Score likelihood of VAX code.
@@ -278,7 +488,7 @@ pub fn score_vax(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::i860::score()`.
 #[inline]
 pub fn score_i860(data: &[u8]) -> i64 {
-    i860::score(data)
+    i860::score(slots(data, 4))
 }
 
 /// Score likelihood of Cell SPU code.
@@ -286,7 +496,7 @@ pub fn score_i860(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::cellspu::score()`.
 #[inline]
 pub fn score_cellspu(data: &[u8]) -> i64 {
-    cellspu::score(data)
+    cellspu::score(slots(data, 4))
 }
 
 /// Score likelihood of Freescale/NXP HCS12/HCS12X (MC68HC12 / CPU12) code.
@@ -310,7 +520,7 @@ pub fn score_hc11(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::c166::score()`.
 #[inline]
 pub fn score_c166(data: &[u8]) -> i64 {
-    c166::score(data)
+    c166::score(slots(data, 2))
 }
 
 /// Score likelihood of C-SKY code.
@@ -318,7 +528,7 @@ pub fn score_c166(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::csky::score()`.
 #[inline]
 pub fn score_csky(data: &[u8]) -> i64 {
-    csky::score(data)
+    csky::score(slots(data, 2))
 }
 
 /// Score likelihood of Renesas RL78 (successor to NEC 78K) code.
@@ -334,13 +544,53 @@ pub fn score_rl78(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::v850::score()`.
 #[inline]
 pub fn score_v850(data: &[u8]) -> i64 {
-    v850::score(data)
+    v850::score(slots(data, 2))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_partial_trailing_slot_never_scores() {
+        // ldr x0, [x1]; ret; with a c.nop/c.ret pair for the 16-bit scorers
+        let code: Vec<u8> = [0xF940_0020u32, 0xD65F_03C0, 0x8082_0001]
+            .iter()
+            .cycle()
+            .take(24)
+            .flat_map(|w| w.to_le_bytes())
+            .collect();
+        for len in 64..code.len() {
+            let whole = |slot: usize| &code[..len - len % slot];
+            assert_eq!(score_aarch64(&code[..len]), score_aarch64(whole(4)));
+            assert_eq!(score_riscv(&code[..len], 64), score_riscv(whole(2), 64));
+            assert_eq!(score_arm(&code[..len]), score_arm(whole(2)));
+            assert_eq!(score_ia64(&code[..len]), score_ia64(whole(16)));
+        }
+    }
+
+    #[test]
+    fn test_normalize_points_per_kib() {
+        assert!((normalize(Isa::Mips, 100, 1024) - 100.0).abs() < f64::EPSILON);
+        assert!((normalize(Isa::Mips, 100, 512) - 200.0).abs() < f64::EPSILON);
+        assert!(
+            (normalize(Isa::Mips, 200, 2048) - normalize(Isa::Mips, 100, 1024)).abs()
+                < f64::EPSILON
+        );
+        assert!(normalize(Isa::Mips, 100, 0).abs() < f64::EPSILON);
+        assert!((normalized_points(Isa::Mips, 4, 25, 1024) - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_normalize_rescales_heavy_scorers() {
+        // One C6000 signature word weighs as much as one top-weight match
+        // of any scorer on the shared scale
+        let c6000 = normalized_points(Isa::TiC6000, 1, weight_ceiling(Isa::TiC6000), 1024);
+        let mips = normalized_points(Isa::Mips, 1, SLOT_WEIGHT_CEILING, 1024);
+        assert!((c6000 - mips).abs() < f64::EPSILON);
+        assert!(weight_ceiling(Isa::X86_64) == SLOT_WEIGHT_CEILING);
+    }
+
     #[test]
     fn test_x86_scoring() {
         let code = [0x55, 0x48, 0x89, 0xE5, 0x90, 0xC3];
@@ -399,7 +649,8 @@ mod tests {
             0x01, 0x00, 0x00, 0x00, // NOP
             0x81, 0xC3, 0xE0, 0x08, // RETL
         ];
-        assert!(score_sparc(&code) > 0);
+        assert!(score_sparc(&code, false) > 0);
+        assert!(score_sparc(&code, true) > 0);
     }
 
     #[test]
@@ -601,7 +852,25 @@ mod tests {
 /// Score likelihood of FR30 code.
 #[inline]
 pub fn score_fr30(data: &[u8]) -> i64 {
-    fr30::score(data)
+    fr30::score(slots(data, 2))
+}
+
+/// Score likelihood of FR-V code.
+#[inline]
+pub fn score_frv(data: &[u8]) -> i64 {
+    frv::score(slots(data, 4))
+}
+
+/// Score likelihood of TILE-Gx code.
+#[inline]
+pub fn score_tilegx(data: &[u8]) -> i64 {
+    tilegx::score(slots(data, 8))
+}
+
+/// Score likelihood of VideoCore IV/VI code.
+#[inline]
+pub fn score_videocore(data: &[u8]) -> i64 {
+    videocore::score(slots(data, 2))
 }
 
 /// Score likelihood of S12Z code.
@@ -613,7 +882,7 @@ pub fn score_s12z(data: &[u8]) -> i64 {
 /// Score likelihood of PPC VLE code.
 #[inline]
 pub fn score_ppcvle(data: &[u8]) -> i64 {
-    ppcvle::score(data)
+    ppcvle::score(slots(data, 2))
 }
 
 /// Score likelihood of TI TMS320C6000 code.
@@ -621,5 +890,45 @@ pub fn score_ppcvle(data: &[u8]) -> i64 {
 /// Delegates to `crate::architectures::tic6000::score()`.
 #[inline]
 pub fn score_tic6000(data: &[u8]) -> i64 {
-    tic6000::score(data)
+    tic6000::score(slots(data, 4))
+}
+
+/// Score likelihood of 8-bit Microchip PIC code.
+///
+/// Delegates to `crate::architectures::pic::score()`.
+#[inline]
+pub fn score_pic(data: &[u8]) -> i64 {
+    pic::score(slots(data, 2))
+}
+
+/// Score likelihood of Microchip PIC24/dsPIC code.
+///
+/// Delegates to `crate::architectures::pic24::score()`.
+#[inline]
+pub fn score_pic24(data: &[u8]) -> i64 {
+    pic24::score(slots(data, 4))
+}
+
+/// Score likelihood of Zilog Z80 code.
+///
+/// Delegates to `crate::architectures::z80::score()`.
+#[inline]
+pub fn score_z80(data: &[u8]) -> i64 {
+    z80::score(data)
+}
+
+/// Score likelihood of MOS 6502 code.
+///
+/// Delegates to `crate::architectures::mos6502::score()`.
+#[inline]
+pub fn score_6502(data: &[u8]) -> i64 {
+    mos6502::score(data)
+}
+
+/// Score likelihood of WDC 65816 code.
+///
+/// Delegates to `crate::architectures::mos6502::score_65816()`.
+#[inline]
+pub fn score_w65816(data: &[u8]) -> i64 {
+    mos6502::score_65816(data)
 }
@@ -0,0 +1,715 @@
+//! Decode-assisted verification of heuristic candidates.
+//!
+//! Pattern counting can be fooled by data whose byte statistics happen to
+//! resemble an ISA (prefix-heavy noise looks like x86). Walking the bytes one
+//! instruction at a time is a much stronger check: real code decodes cleanly
+//! for long stretches, while data hits an illegal encoding within a handful
+//! of instructions.
+//!
+//! Each walk decodes only enough of an instruction to learn its length and
+//! whether the encoding is legal:
+//! - x86 and x86-64 use a length decoder (prefixes, opcode maps, ModRM, SIB,
+//!   displacement and immediate).
+//! - AArch64 and A32 check the top-level encoding tables.
+//! - Thumb and RISC-V apply the 16-vs-32-bit length rule plus legality.
+//!
+//! Other ISAs are not verified.
+
+use crate::types::{Endianness, Isa};
+
+/// Instructions a single walk decodes before it counts as fully clean.
+pub const MAX_WALK: usize = 128;
+
+/// Walks started per candidate, spread evenly over the window.
+const TRIALS: usize = 8;
+
+/// Windows shorter than this are not verified.
+pub const MIN_VERIFY_BYTES: usize = 256;
+
+/// Lowest confidence multiplier, for data that never decodes.
+const MIN_MULTIPLIER: f64 = 0.4;
+
+/// Outcome of decoding one instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// A legal instruction of this many bytes
+    Insn(usize),
+    /// An encoding the ISA does not define
+    Illegal,
+    /// The instruction runs past the end of the input
+    End,
+}
+
+/// Run-length statistics of the decode walks for one candidate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkStats {
+    /// Walks performed (trial offsets inside padding are skipped)
+    pub trials: usize,
+    /// Mean instructions decoded before an illegal encoding, capped at
+    /// [`MAX_WALK`]
+    pub mean_run: f64,
+    /// Mean share of each walk that decoded cleanly: 1.0 when every walk
+    /// reached [`MAX_WALK`] or the end of the input
+    pub clean_fraction: f64,
+}
+
+impl WalkStats {
+    /// Factor applied to the candidate's confidence, from 0.4 (never
+    /// decodes) to 1.0 (always decodes).
+    pub fn multiplier(&self) -> f64 {
+        MIN_MULTIPLIER + (1.0 - MIN_MULTIPLIER) * self.clean_fraction
+    }
+
+    /// Whether the walks decoded cleanly enough to call the input code.
+    pub fn verified(&self) -> bool {
+        self.clean_fraction >= 0.75
+    }
+}
+
+/// Whether [`walk`] can verify `isa`.
+pub fn supports(isa: Isa) -> bool {
+    matches!(
+        isa,
+        Isa::X86 | Isa::X86_64 | Isa::AArch64 | Isa::Arm | Isa::RiscV32 | Isa::RiscV64
+    )
+}
+
+/// Walk `data` as `isa` from several trial offsets.
+///
+/// Returns `None` for unsupported ISAs, windows shorter than
+/// [`MIN_VERIFY_BYTES`], and inputs where every trial offset lands in
+/// padding.
+pub fn walk(data: &[u8], isa: Isa, endianness: Endianness) -> Option<WalkStats> {
+    if !supports(isa) || data.len() < MIN_VERIFY_BYTES {
+        return None;
+    }
+
+    let mut runs = Vec::with_capacity(TRIALS);
+    for t in 0..TRIALS {
+        let start = t * data.len() / TRIALS;
+        let head = &data[start..data.len().min(start + 16)];
+        if head.iter().all(|&b| b == head[0]) {
+            continue;
+        }
+        let run = match isa {
+            Isa::X86 => best_phase(data, start, 4, |d| x86_step(d, false)),
+            Isa::X86_64 => best_phase(data, start, 4, |d| x86_step(d, true)),
+            Isa::AArch64 => best_phase(data, start & !3, 1, aarch64_step),
+            Isa::RiscV32 | Isa::RiscV64 => best_phase(data, start & !1, 1, riscv_step),
+            Isa::Arm => {
+                let le = endianness == Endianness::Little;
+                best_phase(data, start & !3, 1, |d| a32_step(d, le)).max(best_phase(
+                    data,
+                    start & !1,
+                    1,
+                    |d| thumb_step(d, le),
+                ))
+            }
+            _ => return None,
+        };
+        runs.push(run);
+    }
+
+    if runs.is_empty() {
+        return None;
+    }
+    let trials = runs.len();
+    let mean_run = runs.iter().map(|&(n, _)| n as f64).sum::<f64>() / trials as f64;
+    let clean_fraction = runs
+        .iter()
+        .map(|&(n, clean)| {
+            if clean {
+                1.0
+            } else {
+                n as f64 / MAX_WALK as f64
+            }
+        })
+        .sum::<f64>()
+        / trials as f64;
+    Some(WalkStats {
+        trials,
+        mean_run,
+        clean_fraction,
+    })
+}
+
+/// Walk from `phases` consecutive start offsets and keep the longest run.
+///
+/// Returns the instructions decoded and whether the walk ended cleanly
+/// (at [`MAX_WALK`] or the end of the input) rather than on an illegal
+/// encoding.
+fn best_phase(
+    data: &[u8],
+    start: usize,
+    phases: usize,
+    step: impl Fn(&[u8]) -> Step,
+) -> (usize, bool) {
+    (start..(start + phases).min(data.len()))
+        .map(|from| {
+            let mut pos = from;
+            for n in 0..MAX_WALK {
+                match step(&data[pos..]) {
+                    Step::Insn(len) => pos += len,
+                    Step::Illegal => return (n, false),
+                    Step::End => return (n, true),
+                }
+            }
+            (MAX_WALK, true)
+        })
+        .max_by_key(|&(n, clean)| (clean, n))
+        .unwrap_or((0, false))
+}
+
+fn u16_at(data: &[u8], le: bool) -> Option<u16> {
+    let bytes = [*data.first()?, *data.get(1)?];
+    Some(if le {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn u32_at(data: &[u8], le: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(..4)?.try_into().ok()?;
+    Some(if le {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+// =============================================================================
+// x86 / x86-64 length decoder
+// =============================================================================
+
+/// Architectural maximum instruction length.
+const X86_MAX_LEN: usize = 15;
+
+/// One-byte opcodes undefined in 64-bit mode.
+const X86_INVALID_64: &[u8] = &[
+    0x06, 0x07, 0x0E, 0x16, 0x17, 0x1E, 0x1F, 0x27, 0x2F, 0x37, 0x3F, 0x60, 0x61, 0x82, 0x9A, 0xCE,
+    0xD4, 0xD5, 0xD6, 0xEA,
+];
+
+/// Two-byte (`0F xx`) opcodes that are undefined, reserved or deliberately
+/// undefined (`UD0`/`UD1`/`UD2`).
+const X86_INVALID_0F: &[u8] = &[
+    0x04, 0x0A, 0x0B, 0x0C, 0x0F, 0x24, 0x25, 0x26, 0x27, 0x36, 0x39, 0x3B, 0x3C, 0x3D, 0x3E, 0x3F,
+    0x7A, 0x7B, 0xA6, 0xA7, 0xB9, 0xFF,
+];
+
+/// One-byte opcodes that take a ModRM byte.
+fn x86_has_modrm(op: u8) -> bool {
+    matches!(
+        op,
+        0x00..=0x03
+            | 0x08..=0x0B
+            | 0x10..=0x13
+            | 0x18..=0x1B
+            | 0x20..=0x23
+            | 0x28..=0x2B
+            | 0x30..=0x33
+            | 0x38..=0x3B
+            | 0x62
+            | 0x63
+            | 0x69
+            | 0x6B
+            | 0x80..=0x8F
+            | 0xC0
+            | 0xC1
+            | 0xC4..=0xC7
+            | 0xD0..=0xD3
+            | 0xD8..=0xDF
+            | 0xF6
+            | 0xF7
+            | 0xFE
+            | 0xFF
+    )
+}
+
+/// Two-byte (`0F xx`) opcodes without a ModRM byte.
+fn x86_0f_no_modrm(op: u8) -> bool {
+    matches!(
+        op,
+        0x05..=0x09
+            | 0x0E
+            | 0x30..=0x37
+            | 0x77
+            | 0x80..=0x8F
+            | 0xA0..=0xA2
+            | 0xA8..=0xAA
+            | 0xC8..=0xCF
+    )
+}
+
+/// Opcodes a `LOCK` prefix may precede (memory destination only).
+fn x86_lockable(op: u8, two_byte: bool) -> bool {
+    if two_byte {
+        matches!(
+            op,
+            0xAB | 0xB0 | 0xB1 | 0xB3 | 0xBA | 0xBB | 0xC0 | 0xC1 | 0xC7
+        )
+    } else {
+        matches!(
+            op,
+            0x00 | 0x01
+                | 0x08
+                | 0x09
+                | 0x10
+                | 0x11
+                | 0x18
+                | 0x19
+                | 0x20
+                | 0x21
+                | 0x28
+                | 0x29
+                | 0x30
+                | 0x31
+                | 0x80..=0x83 | 0x86 | 0x87 | 0xF6 | 0xF7 | 0xFE | 0xFF
+        )
+    }
+}
+
+/// Byte `i`, or [`Step::End`] past the end of the input.
+fn byte(data: &[u8], i: usize) -> Result<u8, Step> {
+    data.get(i).copied().ok_or(Step::End)
+}
+
+/// Bytes of ModRM, SIB and displacement starting at `data[0]` (the ModRM).
+fn x86_modrm_len(data: &[u8], addr16: bool) -> Result<usize, Step> {
+    let modrm = byte(data, 0)?;
+    let md = modrm >> 6;
+    let rm = modrm & 7;
+    if addr16 {
+        return Ok(match (md, rm) {
+            (0, 6) | (2, _) => 3,
+            (1, _) => 2,
+            _ => 1,
+        });
+    }
+    let mut len = 1;
+    let mut base = rm;
+    if md != 3 && rm == 4 {
+        base = byte(data, 1)? & 7;
+        len += 1;
+    }
+    len += match md {
+        0 if rm == 5 || (rm == 4 && base == 5) => 4,
+        1 => 1,
+        2 => 4,
+        _ => 0,
+    };
+    Ok(len)
+}
+
+/// Decode the length of one x86 instruction.
+fn x86_step(data: &[u8], long_mode: bool) -> Step {
+    match x86_decode(data, long_mode) {
+        Ok(len) if len > X86_MAX_LEN => Step::Illegal,
+        Ok(len) if len > data.len() => Step::End,
+        Ok(len) => Step::Insn(len),
+        Err(step) => step,
+    }
+}
+
+/// The instruction length, or the [`Step`] that ended decoding early. The
+/// length may exceed the input when only the immediate is cut off.
+fn x86_decode(data: &[u8], long_mode: bool) -> Result<usize, Step> {
+    let mut i = 0;
+    let mut opsize16 = false;
+    let mut addr_override = false;
+    let mut lock = false;
+    let mut rex_w = false;
+    let mut prefixes = 0;
+
+    let op = loop {
+        let b = byte(data, i)?;
+        i += 1;
+        match b {
+            0x66 => opsize16 = true,
+            0x67 => addr_override = true,
+            0xF0 => lock = true,
+            0xF2 | 0xF3 | 0x26 | 0x2E | 0x36 | 0x3E | 0x64 | 0x65 => {}
+            0x40..=0x4F if long_mode => {
+                // REX must be last; a legacy prefix after it discards it
+                rex_w = b & 0x08 != 0;
+                let next = byte(data, i)?;
+                if matches!(next, 0x40..=0x4F) {
+                    return Err(Step::Illegal);
+                }
+                prefixes += 1;
+                continue;
+            }
+            _ => break b,
+        }
+        rex_w = false;
+        prefixes += 1;
+        if prefixes > 4 {
+            return Err(Step::Illegal);
+        }
+    };
+    let addr16 = !long_mode && addr_override;
+    let imm_z = if opsize16 { 2 } else { 4 };
+
+    // VEX (C4/C5) and EVEX (62); outside long mode only when ModRM.mod is 3
+    if matches!(op, 0xC4 | 0xC5 | 0x62) && (long_mode || byte(data, i)? >> 6 == 3) {
+        if lock || opsize16 {
+            return Err(Step::Illegal);
+        }
+        let (map, opcode_at) = match op {
+            0xC5 => (1, i + 1),
+            0xC4 => (byte(data, i)? & 0x1F, i + 2),
+            _ => {
+                let p1 = byte(data, i + 1)?;
+                if p1 & 0x04 == 0 {
+                    return Err(Step::Illegal);
+                }
+                (byte(data, i)? & 0x07, i + 3)
+            }
+        };
+        if !matches!(map, 1..=3 | 5 | 6) || (op != 0x62 && map > 3) {
+            return Err(Step::Illegal);
+        }
+        let opcode = byte(data, opcode_at)?;
+        let mut len = opcode_at + 1;
+        // VZEROUPPER/VZEROALL have no ModRM
+        if !(map == 1 && opcode == 0x77 && op != 0x62) {
+            len += x86_modrm_len(&data[len..], false)?;
+        }
+        if map == 3 || (map == 1 && matches!(opcode, 0x70..=0x73 | 0xC2 | 0xC4..=0xC6)) {
+            len += 1;
+        }
+        return Ok(len);
+    }
+
+    if op == 0x0F {
+        let op2 = byte(data, i)?;
+        i += 1;
+        if X86_INVALID_0F.contains(&op2) {
+            return Err(Step::Illegal);
+        }
+        let (modrm, imm) = match op2 {
+            0x38 => {
+                i += 1;
+                byte(data, i - 1)?;
+                (true, 0)
+            }
+            0x3A => {
+                i += 1;
+                byte(data, i - 1)?;
+                (true, 1)
+            }
+            0x80..=0x8F => (false, 4),
+            0x70..=0x73 | 0xA4 | 0xAC | 0xBA | 0xC2 | 0xC4..=0xC6 => (true, 1),
+            _ => (!x86_0f_no_modrm(op2), 0),
+        };
+        if lock {
+            let mem = modrm && byte(data, i)? >> 6 != 3;
+            if !mem || !x86_lockable(op2, true) {
+                return Err(Step::Illegal);
+            }
+        }
+        if modrm {
+            i += x86_modrm_len(&data[i..], addr16)?;
+        }
+        return Ok(i + imm);
+    }
+
+    if long_mode && X86_INVALID_64.contains(&op) {
+        return Err(Step::Illegal);
+    }
+    if op == 0xD6 {
+        return Err(Step::Illegal);
+    }
+
+    let modrm = x86_has_modrm(op);
+    let mut reg = 0;
+    if modrm {
+        let byte = byte(data, i)?;
+        reg = (byte >> 3) & 7;
+        let md = byte >> 6;
+        let illegal = match op {
+            // LEA needs a memory operand
+            0x8D => md == 3,
+            // POP r/m only; other /r values are XOP on AMD
+            0x8F => reg != 0,
+            0xC6 | 0xC7 => reg != 0 && byte != 0xF8,
+            0xFE => reg > 1,
+            0xFF => reg == 7,
+            _ => false,
+        };
+        if illegal || (lock && (md == 3 || !x86_lockable(op, false))) {
+            return Err(Step::Illegal);
+        }
+        i += x86_modrm_len(&data[i..], addr16)?;
+    } else if lock {
+        return Err(Step::Illegal);
+    }
+
+    let imm = match op {
+        0x04 | 0x0C | 0x14 | 0x1C | 0x24 | 0x2C | 0x34 | 0x3C => 1,
+        0x6A | 0x6B | 0x70..=0x7F | 0x80 | 0x82 | 0x83 | 0xA8 | 0xB0..=0xB7 => 1,
+        0xC0 | 0xC1 | 0xC6 | 0xCD | 0xD4 | 0xD5 | 0xE0..=0xE7 | 0xEB => 1,
+        0x05 | 0x0D | 0x15 | 0x1D | 0x25 | 0x2D | 0x35 | 0x3D => imm_z,
+        0x68 | 0x69 | 0x81 | 0xA9 => imm_z,
+        0xC7 if data.get(i - 1) == Some(&0xF8) && reg == 7 => 4,
+        0xC7 => imm_z,
+        0xE8 | 0xE9 => {
+            if long_mode {
+                4
+            } else {
+                imm_z
+            }
+        }
+        0xB8..=0xBF => {
+            if rex_w {
+                8
+            } else {
+                imm_z
+            }
+        }
+        0xC2 | 0xCA => 2,
+        0xC8 => 3,
+        0x9A | 0xEA => imm_z + 2,
+        0xA0..=0xA3 => match (long_mode, addr_override) {
+            (true, false) => 8,
+            (true, true) | (false, false) => 4,
+            (false, true) => 2,
+        },
+        0xF6 if reg < 2 => 1,
+        0xF7 if reg < 2 => imm_z,
+        _ => 0,
+    };
+    Ok(i + imm)
+}
+
+// =============================================================================
+// Fixed-width and compressed RISC legality
+// =============================================================================
+
+/// AArch64: reject the unallocated top-level encoding groups.
+fn aarch64_step(data: &[u8]) -> Step {
+    let Some(w) = u32_at(data, true) else {
+        return Step::End;
+    };
+    let op0 = (w >> 25) & 0xF;
+    let legal = match op0 {
+        // UDF, or SME when bit 31 is set
+        0b0000 => w >> 31 == 1,
+        0b0001 | 0b0011 => false,
+        _ => w != 0xFFFF_FFFF,
+    };
+    if legal {
+        Step::Insn(4)
+    } else {
+        Step::Illegal
+    }
+}
+
+/// A32: reject unallocated unconditional space and the permanent UDF.
+fn a32_step(data: &[u8], le: bool) -> Step {
+    let Some(w) = u32_at(data, le) else {
+        return Step::End;
+    };
+    let illegal = (w >> 28 == 0xF && matches!((w >> 24) & 0xF, 0x0 | 0x2 | 0x3 | 0xF))
+        || w & 0x0FF0_00F0 == 0x07F0_00F0;
+    if illegal {
+        Step::Illegal
+    } else {
+        Step::Insn(4)
+    }
+}
+
+/// Thumb-2: 32-bit when the first halfword starts `0b11101`/`0b1111x`;
+/// reject UDF and the unallocated 32-bit space.
+fn thumb_step(data: &[u8], le: bool) -> Step {
+    let Some(h) = u16_at(data, le) else {
+        return Step::End;
+    };
+    if h >> 11 < 0x1D {
+        return if h >> 8 == 0xDE {
+            Step::Illegal
+        } else {
+            Step::Insn(2)
+        };
+    }
+    let Some(h2) = data.get(2..).and_then(|rest| u16_at(rest, le)) else {
+        return Step::End;
+    };
+    // UDF.W
+    if h & 0xFFF0 == 0xF7F0 && h2 & 0xF000 == 0xA000 {
+        return Step::Illegal;
+    }
+    Step::Insn(4)
+}
+
+/// RISC-V: 16-bit unless the low two bits are `11`; reject the defined
+/// illegal instructions, reserved compressed encodings, longer-than-32-bit
+/// formats and unassigned major opcodes.
+fn riscv_step(data: &[u8]) -> Step {
+    let Some(h) = u16_at(data, true) else {
+        return Step::End;
+    };
+    if h & 3 != 3 {
+        let funct3 = h >> 13;
+        // All-zero is illegal; quadrant 0 funct3 100 is reserved
+        let illegal = h == 0 || (h & 3 == 0 && funct3 == 0b100);
+        return if illegal {
+            Step::Illegal
+        } else {
+            Step::Insn(2)
+        };
+    }
+    let Some(w) = u32_at(data, true) else {
+        return Step::End;
+    };
+    let legal = matches!(
+        w & 0x7F,
+        0x03 | 0x07
+            | 0x0F
+            | 0x13
+            | 0x17
+            | 0x1B
+            | 0x23
+            | 0x27
+            | 0x2F
+            | 0x33
+            | 0x37
+            | 0x3B
+            | 0x43
+            | 0x47
+            | 0x4B
+            | 0x4F
+            | 0x53
+            | 0x57
+            | 0x63
+            | 0x67
+            | 0x6F
+            | 0x73
+    );
+    if legal && w != 0xFFFF_FFFF {
+        Step::Insn(4)
+    } else {
+        Step::Illegal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A few x86-64 functions covering prefixes, ModRM/SIB forms,
+    /// displacements and immediates.
+    fn x86_64_code() -> Vec<u8> {
+        let functions: &[&[u8]] = &[
+            &[0x55],                                        // push rbp
+            &[0x48, 0x89, 0xE5],                            // mov rbp, rsp
+            &[0x48, 0x83, 0xEC, 0x20],                      // sub rsp, 0x20
+            &[0x48, 0x89, 0x7D, 0xF8],                      // mov [rbp-8], rdi
+            &[0x89, 0x75, 0xF4],                            // mov [rbp-12], esi
+            &[0x48, 0x8B, 0x04, 0xC5, 0, 0x10, 0, 0],       // mov rax, [rax*8+0x1000]
+            &[0x8B, 0x44, 0x24, 0x08],                      // mov eax, [rsp+8]
+            &[0x48, 0x8D, 0x05, 0x10, 0x20, 0, 0],          // lea rax, [rip+0x2010]
+            &[0xE8, 0x00, 0x01, 0x00, 0x00],                // call
+            &[0x85, 0xC0],                                  // test eax, eax
+            &[0x74, 0x0A],                                  // je
+            &[0x48, 0xB8, 1, 2, 3, 4, 5, 6, 7, 8],          // movabs rax, imm64
+            &[0x66, 0x0F, 0x6F, 0x45, 0xE0],                // movdqa xmm0, [rbp-0x20]
+            &[0xC5, 0xFD, 0xFE, 0xC1],                      // vpaddd ymm0, ymm0, ymm1
+            &[0x0F, 0xB6, 0x45, 0xFF],                      // movzx eax, byte [rbp-1]
+            &[0xF0, 0x48, 0x0F, 0xB1, 0x0A],                // lock cmpxchg [rdx], rcx
+            &[0x0F, 0x84, 0x10, 0x00, 0x00, 0x00],          // je rel32
+            &[0xC7, 0x45, 0xFC, 0x00, 0x00, 0x00, 0x00],    // mov dword [rbp-4], 0
+            &[0x81, 0xF9, 0x00, 0x10, 0x00, 0x00],          // cmp ecx, 0x1000
+            &[0xF3, 0x48, 0xAB],                            // rep stosq
+            &[0x48, 0x83, 0xC4, 0x20],                      // add rsp, 0x20
+            &[0x5D],                                        // pop rbp
+            &[0xC3],                                        // ret
+            &[0x66, 0x2E, 0x0F, 0x1F, 0x84, 0, 0, 0, 0, 0], // nop word cs:[rax+rax]
+        ];
+        functions.concat().repeat(16)
+    }
+
+    /// JPEG header followed by pseudo-random entropy-coded data.
+    fn jpeg() -> Vec<u8> {
+        let mut data = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00, 0x01, 0x01, 0x00,
+            0x00, 0x48, 0x00, 0x48, 0x00, 0x00, 0xFF, 0xDA, 0x00, 0x0C,
+        ];
+        let mut state = 0x1234_5678u32;
+        while data.len() < 4096 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let byte = (state >> 16) as u8;
+            data.push(byte);
+            if byte == 0xFF {
+                data.push(0x00); // byte stuffing
+            }
+        }
+        data.extend_from_slice(&[0xFF, 0xD9]);
+        data
+    }
+
+    #[test]
+    fn test_x86_lengths() {
+        let cases: &[(&[u8], usize)] = &[
+            (&[0x90], 1),
+            (&[0x48, 0x89, 0xE5], 3),
+            (&[0x48, 0x8B, 0x04, 0xC5, 0, 0, 0, 0], 8),
+            (&[0x48, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0], 10),
+            (&[0x66, 0xB8, 0, 0], 4),
+            (&[0xC4, 0xE3, 0x7D, 0x18, 0xC1, 0x01], 6),
+            (&[0x62, 0xF1, 0x7C, 0x48, 0x58, 0xC1], 6),
+            (&[0xA1, 0, 0, 0, 0, 0, 0, 0, 0], 9),
+        ];
+        for (bytes, len) in cases {
+            assert_eq!(x86_step(bytes, true), Step::Insn(*len), "{bytes:02X?}");
+        }
+        assert_eq!(x86_step(&[0xA1, 0, 0, 0, 0], false), Step::Insn(5));
+        assert_eq!(x86_step(&[0x06], true), Step::Illegal);
+        assert_eq!(x86_step(&[0x06], false), Step::Insn(1));
+        assert_eq!(x86_step(&[0x0F, 0x0B], true), Step::Illegal);
+        assert_eq!(x86_step(&[0xF0, 0x90], true), Step::Illegal);
+        assert_eq!(x86_step(&[0x8D, 0xC0], true), Step::Illegal);
+        assert_eq!(x86_step(&[0xE8, 0x00], true), Step::End);
+    }
+
+    #[test]
+    fn test_x86_code_walks_cleanly() {
+        let code = x86_64_code();
+        let stats = walk(&code, Isa::X86_64, Endianness::Little).unwrap();
+        assert!(stats.verified(), "{stats:?}");
+        assert!(stats.mean_run >= 100.0, "{stats:?}");
+        assert!(stats.multiplier() > 0.95);
+    }
+
+    #[test]
+    fn test_rotated_code_walks_short() {
+        let rotated: Vec<u8> = x86_64_code().iter().map(|b| b.rotate_left(1)).collect();
+        let stats = walk(&rotated, Isa::X86_64, Endianness::Little).unwrap();
+        assert!(!stats.verified(), "{stats:?}");
+        assert!(stats.mean_run < 32.0, "{stats:?}");
+    }
+
+    #[test]
+    fn test_jpeg_never_verifies() {
+        let data = jpeg();
+        for isa in [
+            Isa::X86,
+            Isa::X86_64,
+            Isa::AArch64,
+            Isa::RiscV32,
+            Isa::RiscV64,
+        ] {
+            let stats = walk(&data, isa, Endianness::Little).unwrap();
+            assert!(!stats.verified(), "{isa}: {stats:?}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_and_short_inputs() {
+        assert_eq!(walk(&x86_64_code(), Isa::Mips, Endianness::Big), None);
+        assert_eq!(walk(&[0x90; 64], Isa::X86_64, Endianness::Little), None);
+        // Every trial lands in padding
+        assert_eq!(walk(&[0u8; 1024], Isa::AArch64, Endianness::Little), None);
+    }
+}
@@ -50,6 +50,36 @@
 //! - XCOFF (AIX)
 //! - ECOFF (older MIPS/Alpha)
 //! - Raw binary (heuristic analysis)
+//!
+//! # Thread Safety
+//!
+//! Every public type is `Send + Sync`: options, results, payloads and
+//! formatters hold only owned data, and the scorers keep no global or
+//! thread-local state. A single [`ClassifierOptions`] or formatter can be
+//! shared by reference across worker threads, and classification functions
+//! may be called concurrently. The trait [`PayloadFormatter`] does not
+//! require `Send + Sync` of its implementations; share a formatter trait
+//! object across threads as `dyn PayloadFormatter + Send + Sync`.
+//!
+//! # Reproducible Output
+//!
+//! Identical input and options give byte-identical output. Classification
+//! payloads and the formatters carry no timestamps or environment data.
+//! Outputs that need a time take it from an explicit parameter, which the
+//! CLI fills from `SOURCE_DATE_EPOCH` when set (see `batch::clock` with
+//! the `batch` feature). Serialized maps are ordered by key, never by
+//! `HashMap` iteration. The only version string embedded in output is
+//! [`version`], the crate version.
+//!
+//! # Panics
+//!
+//! No entry point panics on any input, however truncated or corrupt;
+//! malformed data surfaces as an error or a low-confidence result.
+//! [`classify_bytes_with_options`] and [`detect_payload`] also agree on the
+//! ISA, bitwidth, endianness and format for every input. The `differential`
+//! module (behind the `differential` feature) checks both properties; its
+//! corpus replay runs with the test suite and the `differential` target under
+//! `fuzz/` drives it with cargo-fuzz.
 
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "wasm"), deny(unsafe_code))]
@@ -62,11 +92,21 @@
 #![allow(clippy::too_many_lines)]
 
 pub mod architectures;
+pub mod boot;
+#[cfg(any(test, feature = "differential"))]
+pub mod differential;
 pub mod error;
 pub mod extensions;
+pub mod fingerprint;
 pub mod formats;
 pub mod formatter;
 pub mod heuristics;
+pub mod libc;
+pub mod os;
+pub mod output;
+pub mod reclassify;
+pub mod target_env;
+pub mod triple;
 pub mod types;
 
 #[cfg(feature = "batch")]
@@ -75,17 +115,30 @@ pub mod batch;
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+#[cfg(feature = "std")]
+pub mod walk;
+
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+
 pub use error::{ClassifierError, Result};
 pub use formatter::{
-    CandidatesFormatter, HumanFormatter, JsonFormatter, PayloadFormatter, ShortFormatter,
+    write_ndjson, CandidatesFormatter, HumanFormatter, JsonFormatter, NdjsonFormatter,
+    PayloadFormatter, ShortFormatter,
 };
-pub use heuristics::DetectedIsa;
+pub use heuristics::{DetectedIsa, SkipReason, WindowClassification};
+pub use output::{ColorChoice, Output};
+pub use reclassify::Reclassifier;
 pub use types::{
     ClassificationMetadata, ClassificationResult, ClassificationSource, ClassifierOptions,
-    ContainedArch, DetectionPayload, Endianness, Extension, ExtensionCategory, ExtensionDetection,
-    ExtensionSource, FileFormat, FormatDetection, Isa, IsaCandidate, IsaClassification,
-    MetadataEntry, MetadataKey, MetadataValue, Note, NoteLevel, Variant,
+    ContainedArch, DetectionPayload, Endianness, Evidence, EvidenceKind, Extension,
+    ExtensionCategory, ExtensionDetection, ExtensionSource, FileFormat, FormatDetection,
+    FormatFamily, HardeningSummary, Isa, IsaCandidate, IsaClassification, IsaFamily, MetadataEntry,
+    MetadataKey, MetadataValue, Note, NoteLevel, OsFamily, OsFingerprint, RegisterInfo, SampleMode,
+    SectionInfo, SegmentInfo, Variant,
 };
+#[cfg(feature = "std")]
+pub use walk::{classify_dir, ClassifyDir};
 
 use std::path::Path;
 
@@ -114,7 +167,11 @@ use std::path::Path;
 /// ```
 pub fn classify_file<P: AsRef<Path>>(path: P) -> Result<ClassificationResult> {
     let data = std::fs::read(path)?;
-    classify_bytes(&data)
+    let options = ClassifierOptions {
+        input_len: Some(data.len() as u64),
+        ..ClassifierOptions::new()
+    };
+    classify_bytes_with_options(&data, &options)
 }
 
 /// Classify binary data from a byte slice.
@@ -175,19 +232,38 @@ pub fn classify_bytes_with_options(
     data: &[u8],
     options: &ClassifierOptions,
 ) -> Result<ClassificationResult> {
+    // Unicode text announced by a byte-order mark never reaches the parsers
+    if let Some(screen) = options
+        .screen_text
+        .then(|| formats::raw::bom_screen(data))
+        .flatten()
+    {
+        return Ok(formats::raw::text_result(&screen));
+    }
+
     // Try to parse as known format first
-    let format = formats::detect_format(data);
+    let format = detect_format_with(data, options);
 
     let mut result = match format {
-        formats::DetectedFormat::Elf { class, endian } => formats::elf::parse(data, class, endian)?,
-        formats::DetectedFormat::Pe { pe_offset } => formats::pe::parse(data, pe_offset)?,
+        formats::DetectedFormat::Elf { class, endian } => formats::with_backend(
+            data,
+            formats::elf::parse_with_options(data, class, endian, options),
+        )?,
+        formats::DetectedFormat::Pe { pe_offset } => {
+            formats::with_backend(data, formats::pe::parse(data, pe_offset))?
+        }
         formats::DetectedFormat::MachO { bits, big_endian } => {
-            formats::macho::parse(data, bits, big_endian)?
+            formats::with_backend(data, formats::macho::parse(data, bits, big_endian))?
         }
         formats::DetectedFormat::MachOFat {
             big_endian,
             fat64: _,
-        } => formats::macho::parse_fat(data, big_endian)?,
+        } => {
+            let mut fat =
+                formats::macho::parse_fat_with_input_len(data, big_endian, options.input_len)?;
+            fat.candidates.truncate(options.max_candidates);
+            fat
+        }
         formats::DetectedFormat::Coff { machine: _ } => formats::coff::parse(data)?,
         formats::DetectedFormat::Xcoff { bits } => formats::xcoff::parse(data, bits)?,
         formats::DetectedFormat::Ecoff { variant } => formats::ecoff::parse(data, variant)?,
@@ -200,10 +276,14 @@ pub fn classify_bytes_with_options(
         formats::DetectedFormat::Bflt => formats::bflt::parse(data)?,
         formats::DetectedFormat::Console { variant } => formats::console::parse(data, variant)?,
         formats::DetectedFormat::Kernel { variant } => formats::kernel::parse(data, variant)?,
-        formats::DetectedFormat::Ar { variant } => formats::ar::parse(data, variant)?,
+        formats::DetectedFormat::IntelMe { offset } => formats::intel_me::parse(data, offset)?,
+        formats::DetectedFormat::Ar { variant } => {
+            formats::ar::parse_with_input_len(data, variant, options.input_len)?
+        }
         formats::DetectedFormat::Hex { variant } => formats::hex::parse(data, variant)?,
         formats::DetectedFormat::Omf => formats::omf::parse(data)?,
         formats::DetectedFormat::Som => formats::som::parse(data)?,
+        formats::DetectedFormat::Vms { variant } => formats::vms::parse(data, variant)?,
         formats::DetectedFormat::Aof => formats::aof::parse(data)?,
         formats::DetectedFormat::Epoc => formats::epoc::parse(data)?,
         formats::DetectedFormat::Esp => formats::esp::parse(data)?,
@@ -213,7 +293,10 @@ pub fn classify_bytes_with_options(
         formats::DetectedFormat::Os9 => formats::os9::parse(data)?,
         formats::DetectedFormat::Goff => formats::goff::parse(data)?,
         formats::DetectedFormat::LlvmBc { variant } => formats::llvm_bc::parse(data, variant)?,
-        formats::DetectedFormat::FatElf => formats::fatelf::parse(data)?,
+        formats::DetectedFormat::Dis => formats::dis::parse(data)?,
+        formats::DetectedFormat::FatElf => {
+            formats::fatelf::parse_with_input_len(data, options.input_len)?
+        }
         formats::DetectedFormat::Ols => formats::ols::parse(data)?,
         formats::DetectedFormat::Epr => formats::epr::parse(data)?,
         formats::DetectedFormat::Sgo => formats::sgo::parse(data)?,
@@ -222,14 +305,109 @@ pub fn classify_bytes_with_options(
         formats::DetectedFormat::Bcf => formats::bcf::parse(data)?,
         formats::DetectedFormat::Sox => formats::sox::parse(data)?,
         formats::DetectedFormat::Raw => {
-            // Fall back to heuristic analysis
-            heuristics::analyze(data, options)?
+            // Plain text has no ISA; skip the scorers entirely
+            let text = if options.screen_text {
+                formats::raw::text_screen(data)
+            } else {
+                None
+            };
+            if let Some(screen) = text {
+                return Ok(formats::raw::text_result(&screen));
+            }
+            let heuristic = heuristics::analyze(data, options);
+            match assess_com(data, options) {
+                // Headerless CP/M or DOS .COM image with system-call idioms
+                Some(com) => {
+                    let mut result = com.to_result();
+                    if let Ok(heuristic) = heuristic {
+                        result.candidates = heuristic.candidates;
+                    }
+                    result
+                }
+                None => heuristic?,
+            }
         }
     };
 
-    // Detect extensions if requested
+    merge_code_extensions(data, &mut result, options);
+    if let Some(env) = target_env::identify(data, result.format, result.isa) {
+        result.metadata.custom.push(target_env::metadata_entry(env));
+    }
+    annotate_boot_protocol(data, &mut result);
+
+    Ok(result)
+}
+
+/// Classify data that is known to be ELF, skipping format detection.
+///
+/// A shortcut for ELF-only pipelines: the magic is checked and the image is
+/// parsed directly, without running the [`formats::detect_format`] cascade.
+/// The result matches [`classify_bytes_with_options`] for the same ELF input.
+///
+/// # Errors
+///
+/// Returns [`ClassifierError::InvalidMagic`](error::ClassifierError::InvalidMagic)
+/// when `data` does not start with the ELF magic, and the ELF parser's error
+/// when the header is malformed.
+pub fn classify_elf(data: &[u8], options: &ClassifierOptions) -> Result<ClassificationResult> {
+    if !data.starts_with(&formats::magic::ELF) {
+        return Err(error::ClassifierError::InvalidMagic {
+            expected: "\\x7FELF".to_string(),
+            actual: format!("{:02X?}", &data[..data.len().min(4)]),
+        });
+    }
+    let (Some(&class), Some(&endian)) = (data.get(4), data.get(5)) else {
+        return Err(error::ClassifierError::FileTooSmall {
+            expected: 16,
+            actual: data.len(),
+        });
+    };
+
+    let mut result = formats::with_backend(
+        data,
+        formats::elf::parse_with_options(data, class, endian, options),
+    )?;
+    merge_code_extensions(data, &mut result, options);
+    if let Some(env) = target_env::identify(data, result.format, result.isa) {
+        result.metadata.custom.push(target_env::metadata_entry(env));
+    }
+    annotate_boot_protocol(data, &mut result);
+    Ok(result)
+}
+
+/// Report a boot protocol header found in an ELF or headerless kernel.
+fn annotate_boot_protocol(data: &[u8], result: &mut ClassificationResult) {
+    if !matches!(result.format, FileFormat::Elf | FileFormat::Raw) {
+        return;
+    }
+    if let Some(header) = boot::identify(data) {
+        header.apply_variant(&mut result.variant);
+        result.metadata.custom.extend(header.to_metadata());
+    }
+}
+
+/// Add extensions found by scanning the code to the format-reported ones,
+/// when `options.detect_extensions` is set. Format-reported names are
+/// canonicalized first so aliases don't duplicate detections.
+fn merge_code_extensions(
+    data: &[u8],
+    result: &mut ClassificationResult,
+    options: &ClassifierOptions,
+) {
+    extensions::canonicalize(result.isa, &mut result.extensions);
     if options.detect_extensions {
-        let code_extensions = extensions::detect_from_code(data, result.isa, result.endianness);
+        let (mut code_extensions, stub_metadata) = scan_code_extensions(
+            data,
+            result.isa,
+            result.endianness,
+            &scan_exclusions(data.len(), &result.metadata, options),
+        );
+        result.metadata.custom.extend(stub_metadata);
+        extensions::retain_profile_extensions(
+            result.isa,
+            result.variant.as_ref(),
+            &mut code_extensions,
+        );
 
         // Merge code-detected extensions with format-detected extensions
         if result.extensions.is_empty() {
@@ -245,8 +423,57 @@ pub fn classify_bytes_with_options(
             }
         }
     }
+}
 
-    Ok(result)
+/// Ranges a code-pattern scan skips: the format's opaque ranges and, with
+/// [`ClassifierOptions::scan_executable_only`], everything outside the code
+/// ranges the format declares.
+fn scan_exclusions(
+    len: usize,
+    metadata: &types::ClassificationMetadata,
+    options: &ClassifierOptions,
+) -> Vec<(u64, u64)> {
+    let mut skipped = metadata.opaque_ranges.clone();
+    if options.scan_executable_only && !metadata.code_ranges.is_empty() {
+        let mut code = metadata.code_ranges.clone();
+        code.sort_unstable();
+        let mut cursor = 0;
+        for (offset, size) in code {
+            if offset > cursor {
+                skipped.push((cursor, offset - cursor));
+            }
+            cursor = cursor.max(offset.saturating_add(size));
+        }
+        let len = len as u64;
+        if len > cursor {
+            skipped.push((cursor, len - cursor));
+        }
+    }
+    skipped
+}
+
+/// Code-pattern extensions of `data`, skipping the `opaque` ranges and, on
+/// ARM and AArch64, linker stubs (see [`extensions::stubs`]); the stub
+/// counts come back as metadata.
+fn scan_code_extensions(
+    data: &[u8],
+    isa: Isa,
+    endianness: Endianness,
+    opaque: &[(u64, u64)],
+) -> (Vec<Extension>, Vec<MetadataEntry>) {
+    match extensions::stubs::find(data, isa, endianness) {
+        Some(stubs) => {
+            let skipped: Vec<_> = opaque.iter().chain(&stubs.ranges).copied().collect();
+            (
+                extensions::detect_from_code_excluding(data, isa, endianness, &skipped),
+                stubs.to_metadata(),
+            )
+        }
+        None => (
+            extensions::detect_from_code_excluding(data, isa, endianness, opaque),
+            Vec::new(),
+        ),
+    }
 }
 
 /// Detect and analyze a binary file, returning a structured payload.
@@ -265,7 +492,11 @@ pub fn classify_bytes_with_options(
 /// * `Err(ClassifierError)` - If the file cannot be read or analyzed
 pub fn detect_file<P: AsRef<Path>>(path: P) -> Result<DetectionPayload> {
     let data = std::fs::read(path)?;
-    detect_payload(&data, &ClassifierOptions::new())
+    let options = ClassifierOptions {
+        input_len: Some(data.len() as u64),
+        ..ClassifierOptions::new()
+    };
+    detect_payload(&data, &options)
 }
 
 /// Detect and analyze binary data, returning a structured payload.
@@ -302,21 +533,217 @@ pub fn detect_bytes(data: &[u8]) -> Result<DetectionPayload> {
 ///   - Metadata entries
 ///   - Analysis notes
 pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<DetectionPayload> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("detect_payload", input_size = data.len()).entered();
+
+    let mut payload = detect_payload_inner(data, options)?;
+
+    payload.input_size = data.len() as u64;
+
+    // Separate architecturally guaranteed extensions from optional ones
+    extensions::mark_baseline(
+        &mut payload.extensions,
+        payload.primary.isa,
+        payload.primary.variant.as_ref(),
+    );
+    for slice in &mut payload.slices {
+        extensions::mark_baseline(&mut slice.extensions, slice.isa, slice.variant.as_ref());
+    }
+
+    if let Some(profile) = extensions::riscv_profile(payload.primary.isa, &payload.extensions) {
+        let variant = payload
+            .primary
+            .variant
+            .get_or_insert_with(|| Variant::new("RISC-V"));
+        variant.profile.get_or_insert_with(|| profile.to_string());
+    }
+
+    if options.detect_libc {
+        payload
+            .metadata
+            .push(libc::metadata_entry(libc::identify(data).as_ref()));
+    }
+
+    if let Some(env) = target_env::identify(data, payload.format.format, payload.primary.isa) {
+        payload.metadata.push(target_env::metadata_entry(env));
+    }
+
+    payload.os = os::resolve(&os::signals(data, &payload));
+    if let Some(fingerprint) = &payload.os {
+        for conflict in &fingerprint.conflicts {
+            payload.notes.push(Note::info(format!(
+                "OS signals disagree: {conflict}, kept {}",
+                fingerprint.family
+            )));
+        }
+    }
+
+    if matches!(payload.format.format, FileFormat::Elf | FileFormat::Raw) {
+        if let Some(header) = boot::identify(data) {
+            header.apply_variant(&mut payload.primary.variant);
+            payload.metadata.extend(header.to_metadata());
+        }
+    }
+
+    if options.report_registers {
+        if let Some(info) = payload.primary.isa.register_info() {
+            payload.metadata.extend(
+                info.to_metadata()
+                    .into_iter()
+                    .map(|entry| entry.with_origin("isa")),
+            );
+        }
+    }
+
+    if options.infer_addressing
+        && payload.format.format == FileFormat::Raw
+        && payload.primary.source == ClassificationSource::Heuristic
+    {
+        if let Some(addressing) =
+            heuristics::addressing::analyze(data, payload.primary.isa, payload.primary.endianness)
+        {
+            payload.metadata.extend(addressing.to_metadata());
+        }
+    }
+
+    if options.warn_deprecated && payload.primary.isa.is_deprecated() {
+        payload.notes.push(Note::warning(format!(
+            "{} targets a discontinued architecture",
+            payload.primary.isa.name()
+        )));
+    }
+
+    for other in polyglot_formats(data, payload.format.format) {
+        payload
+            .notes
+            .push(Note::warning(format!("polyglot: also matches {other}")));
+    }
+
+    payload.evidence = collect_evidence(&payload, data);
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug!(
+            isa = %payload.primary.isa,
+            bitwidth = payload.primary.bitwidth,
+            endianness = %payload.primary.endianness,
+            confidence = payload.primary.confidence,
+            source = ?payload.primary.source,
+            "classified"
+        );
+        for note in &payload.notes {
+            tracing::debug!(level = ?note.level, message = %note.message, "note");
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Format detection and dispatch for [`detect_payload`], before
+/// option-driven post-processing.
+fn detect_payload_inner(data: &[u8], options: &ClassifierOptions) -> Result<DetectionPayload> {
+    if let Some(screen) = options
+        .screen_text
+        .then(|| formats::raw::bom_screen(data))
+        .flatten()
+    {
+        let format_detection = detected_to_format(&formats::DetectedFormat::Raw);
+        return Ok(text_payload(format_detection, &screen, data.len()));
+    }
+
+    let detected = detect_format_with(data, options);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(format = ?detected, "detected format");
+    let origin = metadata_origin(&detected);
+    let mut payload = parse_payload(data, options, detected)?;
+    // Entries not tagged more precisely come from the format's parser
+    for entry in &mut payload.metadata {
+        entry.origin.get_or_insert(origin);
+    }
+    Ok(payload)
+}
+
+/// Parse `data` as the `detected` format into a payload.
+fn parse_payload(
+    data: &[u8],
+    options: &ClassifierOptions,
+    detected: formats::DetectedFormat,
+) -> Result<DetectionPayload> {
     use types::{
         DetectionPayload, ExtensionDetection, ExtensionSource, IsaCandidate, IsaClassification,
     };
 
-    // Detect file format
-    let detected = formats::detect_format(data);
     let format_detection = detected_to_format(&detected);
 
+    // Section/segment inventory, collected on request for formats that have one
+    let mut inventory = None;
+    // Ranges the format marks as unanalyzable, skipped by code-pattern scans
+    let mut opaque_ranges = Vec::new();
+    // Further ELF images concatenated after the first, found on request
+    let mut appended_elves = Vec::new();
+    // Exploit-mitigation flags, summarized on request for PE, ELF and Mach-O
+    let mut hardening = None;
+
     // Parse based on format
-    let (primary, initial_extensions, metadata) = match detected {
+    let (primary, initial_extensions, (metadata, warnings, sizes)) = match detected {
         formats::DetectedFormat::Elf { class, endian } => {
-            let result = formats::elf::parse(data, class, endian)?;
+            let mut result = formats::with_backend(
+                data,
+                formats::elf::parse_with_options(data, class, endian, options),
+            )?;
+            extensions::canonicalize(result.isa, &mut result.extensions);
+            let mut notes = Vec::new();
+            if result.isa == Isa::Unknown(0) && result.metadata.raw_machine == Some(0) {
+                notes.push(Note::info(formats::elf::NO_MACHINE_NOTE));
+                // A deep scan guesses the real target from everything past
+                // the headers
+                let header_size = result.metadata.header_size.unwrap_or(0);
+                let body = usize::try_from(header_size)
+                    .ok()
+                    .and_then(|start| data.get(start..))
+                    .unwrap_or(data);
+                if options.deep_scan {
+                    if let Ok(found) = heuristics::analyze(body, options) {
+                        notes.push(Note::info(format!(
+                            "{} identified by heuristic analysis of the image",
+                            found.isa
+                        )));
+                        result.isa = found.isa;
+                        result.bitwidth = found.bitwidth;
+                        result.endianness = found.endianness;
+                        result.confidence = found.confidence;
+                        result.source = ClassificationSource::Combined;
+                    }
+                }
+            }
+            if options.collect_sections {
+                let max = match options.max_sections {
+                    0 => ClassifierOptions::DEFAULT_MAX_SECTIONS,
+                    n => n,
+                };
+                inventory = Some(formats::elf::inventory(data, class, endian, max));
+            }
+            if options.report_hardening {
+                hardening = Some(formats::elf::hardening(data, class, endian));
+            }
+            opaque_ranges = scan_exclusions(data.len(), &result.metadata, options);
+            if options.scan_concatenated_elf {
+                appended_elves = formats::elf::appended_images(data, class, endian);
+                // Later images are other programs, not code of the first
+                opaque_ranges.extend(
+                    appended_elves
+                        .iter()
+                        .map(|image| (image.offset, image.size)),
+                );
+            }
+            let mut primary =
+                IsaClassification::from_format(result.isa, result.bitwidth, result.endianness);
+            primary.variant.clone_from(&result.variant);
+            // EM_NONE objects are classified from their code
+            primary.confidence = types::round_confidence(result.confidence);
+            primary.source = result.source;
             (
-                IsaClassification::from_format(result.isa, result.bitwidth, result.endianness)
-                    .with_variant(result.variant.clone()),
+                primary,
                 result
                     .extensions
                     .iter()
@@ -325,32 +752,55 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                         category: e.category,
                         confidence: e.confidence,
                         source: ExtensionSource::FormatAttribute,
+                        baseline: false,
                     })
                     .collect::<Vec<_>>(),
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result).into_iter().chain(notes).collect(),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Pe { pe_offset } => {
-            let result = formats::pe::parse(data, pe_offset)?;
+            let result = formats::with_backend(data, formats::pe::parse(data, pe_offset))?;
+            opaque_ranges = scan_exclusions(data.len(), &result.metadata, options);
+            if options.report_hardening {
+                hardening = formats::pe::hardening(data, pe_offset);
+            }
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::MachO { bits, big_endian } => {
-            let result = formats::macho::parse(data, bits, big_endian)?;
+            let result =
+                formats::with_backend(data, formats::macho::parse(data, bits, big_endian))?;
+            opaque_ranges = scan_exclusions(data.len(), &result.metadata, options);
+            if options.report_hardening {
+                hardening = Some(formats::macho::hardening(data, bits, !big_endian));
+            }
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::MachOFat {
             big_endian: _,
             fat64,
         } => {
-            let entries = formats::macho::parse_fat_all(data, fat64)?;
+            let entries =
+                formats::macho::parse_fat_all_with_input_len(data, fat64, options.input_len)?;
             // Use the first slice as the primary classification
             let first = entries
                 .first()
@@ -374,11 +824,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             let slices: Vec<types::ContainedArch> = entries
                 .iter()
                 .map(|entry| {
-                    let variant = if entry.classification.variant == Variant::default() {
-                        None
-                    } else {
-                        Some(entry.classification.variant.clone())
-                    };
+                    let variant = entry.classification.variant.clone();
                     let extensions = entry
                         .classification
                         .extensions
@@ -402,17 +848,48 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             payload.extensions = initial_extensions;
             payload.metadata = metadata;
             payload.slices = slices;
+            // Every slice is a format-level verdict, not a heuristic guess
+            payload.candidates = entries
+                .iter()
+                .take(options.max_candidates)
+                .map(|entry| {
+                    IsaCandidate::new(
+                        entry.classification.isa,
+                        entry.classification.bitwidth,
+                        entry.classification.endianness,
+                        0,
+                        1.0,
+                    )
+                })
+                .collect();
             payload.notes.push(types::Note::info(format!(
-                "Universal binary containing {} architectures",
+                "Universal binary with {} architectures",
                 entries.len()
             )));
+            for entry in &entries {
+                payload
+                    .notes
+                    .extend(extract_warnings(&entry.classification));
+            }
 
             // Add code-detected extensions if requested
             if options.detect_extensions {
-                let code_exts = extensions::detect_from_code(
+                payload.extensions_analyzed = extensions::has_code_detector(payload.primary.isa);
+                // Slice opaque ranges (encrypted __TEXT) are slice-relative
+                let opaque: Vec<(u64, u64)> = entries
+                    .iter()
+                    .flat_map(|entry| {
+                        let ranges = &entry.classification.metadata.opaque_ranges;
+                        ranges.iter().filter_map(move |&(start, len)| {
+                            Some((entry.offset.checked_add(start)?, len))
+                        })
+                    })
+                    .collect();
+                let code_exts = extensions::detect_from_code_excluding(
                     data,
                     payload.primary.isa,
                     payload.primary.endianness,
+                    &opaque,
                 );
                 let existing: std::collections::HashSet<String> =
                     payload.extensions.iter().map(|e| e.name.clone()).collect();
@@ -423,6 +900,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                             category: ext.category,
                             confidence: ext.confidence,
                             source: ExtensionSource::CodePattern,
+                            baseline: false,
                         });
                     }
                 }
@@ -435,7 +913,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Xcoff { bits } => {
@@ -443,7 +925,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Ecoff { variant } => {
@@ -451,7 +937,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Aout { variant } => {
@@ -459,7 +949,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Mz { variant } => {
@@ -467,7 +961,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Pef => {
@@ -475,7 +973,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Wasm => {
@@ -483,7 +985,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::JavaClass => {
@@ -491,7 +997,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Dex { variant } => {
@@ -499,7 +1009,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Bflt => {
@@ -507,7 +1021,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Console { variant } => {
@@ -515,7 +1033,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Kernel { variant } => {
@@ -523,15 +1045,40 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
+            )
+        }
+        formats::DetectedFormat::IntelMe { offset } => {
+            let result = formats::intel_me::parse(data, offset)?;
+            // Huffman-compressed modules carry no recognizable code
+            opaque_ranges.clone_from(&result.metadata.opaque_ranges);
+            (
+                IsaClassification {
+                    variant: result.variant.clone(),
+                    ..IsaClassification::from_format(result.isa, result.bitwidth, result.endianness)
+                },
+                vec![],
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Ar { variant } => {
-            let result = formats::ar::parse(data, variant)?;
+            let result = formats::ar::parse_with_input_len(data, variant, options.input_len)?;
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Hex { variant } => {
@@ -539,7 +1086,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Omf => {
@@ -547,15 +1098,38 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Som => {
             let result = formats::som::parse(data)?;
+            (
+                IsaClassification {
+                    variant: result.variant.clone(),
+                    ..IsaClassification::from_format(result.isa, result.bitwidth, result.endianness)
+                },
+                vec![],
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
+            )
+        }
+        formats::DetectedFormat::Vms { variant } => {
+            let result = formats::vms::parse(data, variant)?;
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Aof => {
@@ -563,7 +1137,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Epoc => {
@@ -571,7 +1149,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Esp => {
@@ -579,7 +1161,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Palm => {
@@ -587,7 +1173,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::AmigaHunk => {
@@ -595,7 +1185,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Tds => {
@@ -603,7 +1197,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Os9 => {
@@ -611,7 +1209,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Goff => {
@@ -619,7 +1221,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::LlvmBc { variant } => {
@@ -627,15 +1233,35 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
+            )
+        }
+        formats::DetectedFormat::Dis => {
+            let result = formats::dis::parse(data)?;
+            (
+                IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
+                vec![],
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::FatElf => {
-            let result = formats::fatelf::parse(data)?;
+            let result = formats::fatelf::parse_with_input_len(data, options.input_len)?;
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Ols => {
@@ -643,7 +1269,11 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             (
                 IsaClassification::from_format(result.isa, result.bitwidth, result.endianness),
                 vec![],
-                extract_metadata(&result),
+                (
+                    extract_metadata(&result),
+                    extract_warnings(&result),
+                    extract_sizes(&result),
+                ),
             )
         }
         formats::DetectedFormat::Sgo => {
@@ -659,6 +1289,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                 confidence: result.confidence,
                 source: result.source,
                 variant: None,
+                match_count: None,
             };
 
             let mut payload = DetectionPayload::new(format_detection, primary);
@@ -684,6 +1315,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                     confidence: result.confidence,
                     source: result.source,
                     variant: None,
+                    match_count: None,
                 };
 
                 let mut payload = DetectionPayload::new(format_detection, primary);
@@ -699,10 +1331,10 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                         heuristics::score_all_architectures(&extracted_payload, options);
                     let mut sorted_candidates: Vec<_> =
                         candidates.iter().filter(|c| c.raw_score > 0).collect();
-                    sorted_candidates.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+                    sorted_candidates.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
                     payload.candidates = sorted_candidates
                         .into_iter()
-                        .take(10)
+                        .take(options.max_candidates)
                         .map(|c| {
                             IsaCandidate::new(
                                 c.isa,
@@ -727,19 +1359,20 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
 
                 match best {
                     Some(b) if b.confidence >= options.min_confidence => {
-                        let primary = IsaClassification::from_heuristics(
+                        let mut primary = IsaClassification::from_heuristics(
                             b.isa,
                             b.bitwidth,
                             b.endianness,
                             b.confidence,
                         );
+                        primary.match_count = b.match_count;
 
                         let mut sorted_candidates: Vec<_> =
                             candidates.iter().filter(|c| c.raw_score > 0).collect();
-                        sorted_candidates.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+                        sorted_candidates.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
                         let candidate_list: Vec<IsaCandidate> = sorted_candidates
                             .into_iter()
-                            .take(10)
+                            .take(options.max_candidates)
                             .map(|c| {
                                 IsaCandidate::new(
                                     c.isa,
@@ -795,6 +1428,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                 confidence: result.confidence,
                 source: result.source,
                 variant: None,
+                match_count: None,
             };
             let mut payload = DetectionPayload::new(format_detection, primary);
             for note in &result.metadata.notes {
@@ -813,6 +1447,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                 confidence: result.confidence,
                 source: result.source,
                 variant: None,
+                match_count: None,
             };
             let mut payload = DetectionPayload::new(format_detection, primary);
             for note in &result.metadata.notes {
@@ -831,6 +1466,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                 confidence: result.confidence,
                 source: result.source,
                 variant: None,
+                match_count: None,
             };
             let mut payload = DetectionPayload::new(format_detection, primary);
             for note in &result.metadata.notes {
@@ -853,6 +1489,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                     confidence: result.confidence,
                     source: result.source,
                     variant: None,
+                    match_count: None,
                 };
 
                 let mut payload = DetectionPayload::new(format_detection, primary);
@@ -869,10 +1506,10 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                         heuristics::score_all_architectures(&extracted_payload, options);
                     let mut sorted_candidates: Vec<_> =
                         candidates.iter().filter(|c| c.raw_score > 0).collect();
-                    sorted_candidates.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+                    sorted_candidates.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
                     payload.candidates = sorted_candidates
                         .into_iter()
-                        .take(10)
+                        .take(options.max_candidates)
                         .map(|c| {
                             IsaCandidate::new(
                                 c.isa,
@@ -897,19 +1534,20 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
 
                 match best {
                     Some(b) if b.confidence >= options.min_confidence => {
-                        let primary = IsaClassification::from_heuristics(
+                        let mut primary = IsaClassification::from_heuristics(
                             b.isa,
                             b.bitwidth,
                             b.endianness,
                             b.confidence,
                         );
+                        primary.match_count = b.match_count;
 
                         let mut sorted_candidates: Vec<_> =
                             candidates.iter().filter(|c| c.raw_score > 0).collect();
-                        sorted_candidates.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
+                        sorted_candidates.sort_by(|a, b| b.normalized.total_cmp(&a.normalized));
                         let candidate_list: Vec<IsaCandidate> = sorted_candidates
                             .into_iter()
-                            .take(10)
+                            .take(options.max_candidates)
                             .map(|c| {
                                 IsaCandidate::new(
                                     c.isa,
@@ -957,63 +1595,174 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
             return Ok(payload);
         }
         formats::DetectedFormat::Raw => {
+            let text = if options.screen_text {
+                formats::raw::text_screen(data)
+            } else {
+                None
+            };
+            if let Some(screen) = text {
+                return Ok(text_payload(format_detection, &screen, data.len()));
+            }
+
             // Heuristic analysis: use the same decision path as classify_bytes*
             // (including any fallback logic in heuristics::analyze), and still
             // provide top candidates for UI output.
-            let primary_result = heuristics::analyze(data, options)?;
+            let heuristic = heuristics::analyze(data, options);
+
+            if let Some(com) = assess_com(data, options) {
+                let result = com.to_result();
+                let mut format_detection =
+                    FormatDetection::with_variant(FileFormat::Com, com.flavor.name());
+                format_detection.confidence = com.confidence;
+                format_detection.magic_offset = None;
+                let primary = IsaClassification::from_heuristics(
+                    result.isa,
+                    result.bitwidth,
+                    result.endianness,
+                    result.confidence,
+                );
+                let mut payload = DetectionPayload::new(format_detection, primary);
+                if let Ok(heuristic) = heuristic {
+                    payload.candidates = heuristic.candidates;
+                }
+                payload.scanned_bytes = Some(data.len() as u64);
+                payload.metadata.clone_from(&result.metadata.custom);
+                for note in &result.metadata.notes {
+                    payload.notes.push(Note::info(note.clone()));
+                }
+                if options.detect_extensions {
+                    attach_code_extensions(&mut payload, data, result.isa, result.endianness);
+                }
+                return Ok(payload);
+            }
 
-            let primary = IsaClassification::from_heuristics(
+            let primary_result = heuristic?;
+
+            let mut primary = IsaClassification::from_heuristics(
                 primary_result.isa,
                 primary_result.bitwidth,
                 primary_result.endianness,
                 primary_result.confidence,
             );
-
-            let candidates = heuristics::score_all_architectures(data, options);
-            let mut sorted_candidates: Vec<_> =
-                candidates.iter().filter(|c| c.raw_score > 0).collect();
-            sorted_candidates.sort_by(|a, b| b.raw_score.cmp(&a.raw_score));
-            let candidate_list: Vec<IsaCandidate> = sorted_candidates
-                .into_iter()
-                .take(10)
-                .map(|c| {
-                    IsaCandidate::new(c.isa, c.bitwidth, c.endianness, c.raw_score, c.confidence)
-                })
-                .collect();
-
-            let mut payload =
-                DetectionPayload::new(format_detection, primary).with_candidates(candidate_list);
+            primary.match_count = primary_result.match_count;
+            primary.variant.clone_from(&primary_result.variant);
+
+            let mut payload = DetectionPayload::new(format_detection, primary)
+                .with_candidates(primary_result.candidates.clone());
+            payload.metadata = extract_metadata(&primary_result);
+            payload.notes = extract_warnings(&primary_result);
+            // Scoring stops at max_scan_bytes or reads the sampled windows;
+            // extension detection reads it all
+            payload.scanned_bytes = Some(if options.detect_extensions {
+                data.len() as u64
+            } else {
+                options
+                    .sample_mode
+                    .windows(data.len(), options.max_scan_bytes)
+                    .map_or(data.len().min(options.max_scan_bytes), |windows| {
+                        windows.iter().map(|(start, end)| end - start).sum()
+                    }) as u64
+            });
+            for note in &primary_result.metadata.notes {
+                payload.notes.push(Note::info(note.clone()));
+            }
 
             if options.detect_extensions {
-                let code_exts = extensions::detect_from_code(
+                attach_code_extensions(
+                    &mut payload,
                     data,
                     primary_result.isa,
                     primary_result.endianness,
                 );
-                payload.extensions = code_exts
-                    .into_iter()
-                    .map(|e| ExtensionDetection {
-                        name: e.name,
-                        category: e.category,
-                        confidence: e.confidence,
-                        source: ExtensionSource::CodePattern,
-                    })
-                    .collect();
             }
 
             return Ok(payload);
         }
     };
 
+    let (header_size, described_size) = sizes;
+
     // Build payload
     let mut payload = DetectionPayload::new(format_detection, primary);
     payload.extensions = initial_extensions;
     payload.metadata = metadata;
+    payload.notes = warnings;
+    payload.described_size = described_size;
+    payload.hardening = hardening;
+    // Without a code scan only the headers are read; parsers that do not
+    // report their header extent leave it unknown
+    payload.scanned_bytes = if options.detect_extensions {
+        Some(unmasked_len(data.len(), &opaque_ranges))
+    } else {
+        header_size.map(|size| size.min(data.len() as u64))
+    };
+
+    if let Some(inventory) = inventory {
+        if inventory.is_truncated() {
+            payload.notes.push(Note::info(format!(
+                "Section inventory truncated to {} of {} sections and {} of {} segments",
+                inventory.sections.len(),
+                inventory.total_sections,
+                inventory.segments.len(),
+                inventory.total_segments
+            )));
+        }
+        payload.sections = inventory.sections;
+        payload.segments = inventory.segments;
+    }
+
+    if !appended_elves.is_empty() {
+        payload.notes.push(Note::info(format!(
+            "{} further ELF image(s) concatenated after the first",
+            appended_elves.len()
+        )));
+        for (i, image) in appended_elves.iter().enumerate() {
+            payload.metadata.push(MetadataEntry::custom(
+                format!("elf_image_{}_offset", i + 1),
+                MetadataValue::Address(image.offset),
+                format!("ELF Image {} Offset", i + 1),
+            ));
+        }
+        payload.slices = appended_elves
+            .into_iter()
+            .map(|image| types::ContainedArch {
+                isa: image.result.isa,
+                bitwidth: image.result.bitwidth,
+                endianness: image.result.endianness,
+                variant: image.result.variant,
+                offset: image.offset,
+                size: image.size,
+                extensions: image
+                    .result
+                    .extensions
+                    .iter()
+                    .map(|e| ExtensionDetection {
+                        name: e.name.clone(),
+                        category: e.category,
+                        confidence: e.confidence,
+                        source: ExtensionSource::FormatAttribute,
+                        baseline: false,
+                    })
+                    .collect(),
+            })
+            .collect();
+    }
 
     // Add code-detected extensions if requested
     if options.detect_extensions {
-        let code_exts =
-            extensions::detect_from_code(data, payload.primary.isa, payload.primary.endianness);
+        payload.extensions_analyzed = extensions::has_code_detector(payload.primary.isa);
+        let (mut code_exts, stub_metadata) = scan_code_extensions(
+            data,
+            payload.primary.isa,
+            payload.primary.endianness,
+            &opaque_ranges,
+        );
+        payload.metadata.extend(stub_metadata);
+        extensions::retain_profile_extensions(
+            payload.primary.isa,
+            payload.primary.variant.as_ref(),
+            &mut code_exts,
+        );
         let existing: std::collections::HashSet<String> =
             payload.extensions.iter().map(|e| e.name.clone()).collect();
 
@@ -1024,6 +1773,7 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
                     category: ext.category,
                     confidence: ext.confidence,
                     source: ExtensionSource::CodePattern,
+                    baseline: false,
                 });
             }
         }
@@ -1032,6 +1782,94 @@ pub fn detect_payload(data: &[u8], options: &ClassifierOptions) -> Result<Detect
     Ok(payload)
 }
 
+/// Detect the format of `data`, honoring [`ClassifierOptions::strict_magic`].
+fn detect_format_with(data: &[u8], options: &ClassifierOptions) -> formats::DetectedFormat {
+    if options.strict_magic {
+        formats::detect_format_strict(data)
+    } else {
+        formats::detect_format(data)
+    }
+}
+
+/// Assess a raw input as a headerless .COM image, honoring
+/// [`ClassifierOptions::detect_com`] and [`ClassifierOptions::strict_magic`].
+fn assess_com(data: &[u8], options: &ClassifierOptions) -> Option<formats::com::ComAssessment> {
+    if !options.detect_com || options.strict_magic {
+        return None;
+    }
+    formats::com::assess(data).filter(|com| com.scorers_agree(data))
+}
+
+/// Scan raw code for instruction-set extensions of `isa` and add them to
+/// `payload`.
+fn attach_code_extensions(
+    payload: &mut DetectionPayload,
+    data: &[u8],
+    isa: Isa,
+    endianness: Endianness,
+) {
+    payload.extensions_analyzed = extensions::has_code_detector(isa);
+    let (code_exts, stub_metadata) = scan_code_extensions(data, isa, endianness, &[]);
+    payload.metadata.extend(stub_metadata);
+    payload.extensions = code_exts
+        .into_iter()
+        .map(|e| ExtensionDetection {
+            name: e.name,
+            category: e.category,
+            confidence: e.confidence,
+            source: ExtensionSource::CodePattern,
+            baseline: false,
+        })
+        .collect();
+}
+
+/// Name of the parser that handles `detected`, used as the metadata origin.
+fn metadata_origin(detected: &formats::DetectedFormat) -> &'static str {
+    use formats::DetectedFormat as D;
+    match detected {
+        D::Elf { .. } => "elf",
+        D::Pe { .. } => "pe",
+        D::MachO { .. } | D::MachOFat { .. } => "macho",
+        D::Coff { .. } => "coff",
+        D::Xcoff { .. } => "xcoff",
+        D::Ecoff { .. } => "ecoff",
+        D::Aout { .. } => "aout",
+        D::Mz { .. } => "mz",
+        D::Pef => "pef",
+        D::Wasm => "wasm",
+        D::JavaClass => "java",
+        D::Dex { .. } => "dex",
+        D::Bflt => "bflt",
+        D::Console { .. } => "console",
+        D::Kernel { .. } => "kernel",
+        D::IntelMe { .. } => "intel_me",
+        D::Ar { .. } => "ar",
+        D::Hex { .. } => "hex",
+        D::Omf => "omf",
+        D::Som => "som",
+        D::Aof => "aof",
+        D::Epoc => "epoc",
+        D::Esp => "esp",
+        D::Palm => "palm",
+        D::AmigaHunk => "amiga_hunk",
+        D::Tds => "tds",
+        D::Os9 => "os9",
+        D::Goff => "goff",
+        D::LlvmBc { .. } => "llvm_bc",
+        D::Dis => "dis",
+        D::FatElf => "fatelf",
+        D::Ols => "ols",
+        D::Epr => "epr",
+        D::Vms { .. } => "vms",
+        D::Sgo => "sgo",
+        D::Vbf => "vbf",
+        D::Frf => "frf",
+        D::Bcf => "bcf",
+        D::Sox => "sox",
+        D::Raw => "heuristics",
+    }
+}
+
 /// Convert DetectedFormat to FormatDetection.
 fn detected_to_format(detected: &formats::DetectedFormat) -> FormatDetection {
     use formats::DetectedFormat;
@@ -1062,6 +1900,12 @@ fn detected_to_format(detected: &formats::DetectedFormat) -> FormatDetection {
         DetectedFormat::Kernel { variant } => {
             FormatDetection::with_variant(format_for_kernel(variant), format!("{:?}", variant))
         }
+        DetectedFormat::IntelMe { offset } => {
+            let mut detection =
+                FormatDetection::with_variant(FileFormat::IntelMe, "Intel ME/CSME firmware");
+            detection.magic_offset = Some(*offset);
+            detection
+        }
         DetectedFormat::Ar { variant } => {
             FormatDetection::with_variant(FileFormat::Archive, format!("{:?}", variant))
         }
@@ -1079,9 +1923,13 @@ fn detected_to_format(detected: &formats::DetectedFormat) -> FormatDetection {
         DetectedFormat::Os9 => FormatDetection::new(FileFormat::Os9),
         DetectedFormat::Goff => FormatDetection::new(FileFormat::Goff),
         DetectedFormat::LlvmBc { .. } => FormatDetection::new(FileFormat::LlvmBc),
+        DetectedFormat::Dis => FormatDetection::new(FileFormat::Dis),
         DetectedFormat::FatElf => FormatDetection::new(FileFormat::FatElf),
         DetectedFormat::Ols => FormatDetection::new(FileFormat::Ols),
         DetectedFormat::Epr => FormatDetection::new(FileFormat::Epr),
+        DetectedFormat::Vms { variant } => {
+            FormatDetection::with_variant(FileFormat::Vms, format!("{:?}", variant))
+        }
         DetectedFormat::Sgo => FormatDetection::new(FileFormat::Sgo),
         DetectedFormat::Vbf => FormatDetection::new(FileFormat::Vbf),
         DetectedFormat::Frf => FormatDetection::new(FileFormat::Frf),
@@ -1103,6 +1951,7 @@ fn format_for_console(variant: &formats::console::ConsoleFormat) -> FileFormat {
         ConsoleFormat::Nso => FileFormat::Nso,
         ConsoleFormat::Nro => FileFormat::Nro,
         ConsoleFormat::Dol => FileFormat::Dol,
+        ConsoleFormat::DreamcastIp => FileFormat::IpBin,
     }
 }
 
@@ -1145,16 +1994,198 @@ fn extract_metadata(result: &ClassificationResult) -> Vec<MetadataEntry> {
     if let Some(machine) = result.metadata.raw_machine {
         entries.push(MetadataEntry::raw_machine(machine));
     }
+    entries.extend(result.metadata.custom.iter().cloned());
 
     entries
 }
 
+/// Payload for inputs the text screens identify as plain text.
+fn text_payload(
+    format_detection: FormatDetection,
+    screen: &formats::raw::TextScreen,
+    input_len: usize,
+) -> DetectionPayload {
+    let result = formats::raw::text_result(screen);
+    let primary = IsaClassification::from_heuristics(
+        result.isa,
+        result.bitwidth,
+        result.endianness,
+        result.confidence,
+    );
+    let mut payload = DetectionPayload::new(format_detection, primary);
+    payload.scanned_bytes = Some(input_len.min(formats::raw::TEXT_SCREEN_WINDOW) as u64);
+    for note in &result.metadata.notes {
+        payload.notes.push(Note::info(note.clone()));
+    }
+    payload
+}
+
+/// Header and described extents recorded by the parser, if any.
+fn extract_sizes(result: &ClassificationResult) -> (Option<u64>, Option<u64>) {
+    (result.metadata.header_size, result.metadata.described_size)
+}
+
+/// Bytes of a `len`-byte input outside the `(offset, length)` ranges.
+fn unmasked_len(len: usize, opaque: &[(u64, u64)]) -> u64 {
+    let len = len as u64;
+    let mut ranges: Vec<(u64, u64)> = opaque
+        .iter()
+        .map(|&(offset, size)| (offset.min(len), offset.saturating_add(size).min(len)))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut masked = 0;
+    let mut cursor = 0;
+    for (start, end) in ranges {
+        let start = start.max(cursor);
+        if end > start {
+            masked += end - start;
+            cursor = end;
+        }
+    }
+    len - masked
+}
+
+/// Convert parser warnings into payload notes.
+fn extract_warnings(result: &ClassificationResult) -> Vec<Note> {
+    result
+        .metadata
+        .warnings
+        .iter()
+        .map(|w| Note::warning(w.clone()))
+        .collect()
+}
+
+/// Section name prefixes that tie an image to one ISA's toolchain.
+const ISA_SECTION_PREFIXES: &[&str] = &[
+    ".ARM.",
+    ".riscv.",
+    ".MIPS.",
+    ".mdebug.",
+    ".hexagon.",
+    ".xtensa.",
+    ".PARISC.",
+];
+
+/// Most named idioms of the winning ISA reported as instruction-pattern
+/// evidence.
+const MAX_PATTERN_EVIDENCE: usize = 3;
+
+/// Collect the evidence behind `payload`'s primary classification of
+/// `data`.
+fn collect_evidence(payload: &DetectionPayload, data: &[u8]) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    let format = &payload.format;
+    let primary = &payload.primary;
+
+    if format.format != FileFormat::Raw {
+        if let Some(offset) = format.magic_offset {
+            let description = match &format.variant_name {
+                Some(variant) => format!("{} magic ({})", format.format, variant),
+                None => format!("{} magic", format.format),
+            };
+            evidence.push(
+                Evidence::new(EvidenceKind::MagicMatch, description, format.confidence)
+                    .at(offset as u64),
+            );
+        }
+    }
+
+    if primary.source == ClassificationSource::Heuristic {
+        // Only the winner's matches explain the verdict; runners-up stay
+        // in the candidate list
+        let winner = payload
+            .candidates
+            .iter()
+            .find(|c| c.isa == primary.isa && c.raw_score > 0);
+        evidence.push(Evidence::new(
+            EvidenceKind::InstructionPattern,
+            match winner {
+                Some(c) => format!(
+                    "{} instruction patterns scored {} points",
+                    primary.isa.name(),
+                    c.raw_score
+                ),
+                None => format!("Code patterns identify {}", primary.isa.name()),
+            },
+            primary.confidence,
+        ));
+        let scanned = payload
+            .scanned_bytes
+            .map_or(data.len(), |n| usize::try_from(n).unwrap_or(usize::MAX))
+            .min(data.len());
+        let idioms = heuristics::idioms::count(&data[..scanned], primary.isa, primary.endianness);
+        evidence.extend(
+            idioms
+                .into_iter()
+                .take(MAX_PATTERN_EVIDENCE)
+                .map(|(name, n)| {
+                    Evidence::new(
+                        EvidenceKind::InstructionPattern,
+                        format!("{} `{}` matched {} times", primary.isa.name(), name, n),
+                        primary.confidence,
+                    )
+                }),
+        );
+    } else {
+        let machine = payload.metadata.iter().find_map(|entry| match entry.value {
+            MetadataValue::Hex(value) if entry.key == MetadataKey::RawMachine => Some(value),
+            _ => None,
+        });
+        let description = match machine {
+            Some(machine) => {
+                format!(
+                    "Machine field 0x{:X} identifies {}",
+                    machine,
+                    primary.isa.name()
+                )
+            }
+            None => format!("Format header identifies {}", primary.isa.name()),
+        };
+        evidence.push(Evidence::new(
+            EvidenceKind::HeaderField,
+            description,
+            primary.confidence,
+        ));
+    }
+
+    for section in &payload.sections {
+        if ISA_SECTION_PREFIXES
+            .iter()
+            .any(|prefix| section.name.starts_with(prefix))
+        {
+            evidence.push(
+                Evidence::new(
+                    EvidenceKind::SectionName,
+                    format!("Section {}", section.name),
+                    0.5,
+                )
+                .at(section.offset),
+            );
+        }
+    }
+
+    for note in &payload.notes {
+        if note.level >= NoteLevel::Warning {
+            evidence.push(Evidence::new(EvidenceKind::Note, note.message.clone(), 0.0));
+        }
+    }
+
+    // Stable, so equal weights keep the order they were gathered in
+    evidence.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    evidence
+}
+
 /// Get version information for this library.
 pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
 
 /// Get the list of supported ISAs.
+///
+/// Some entries are only reported from format headers; see
+/// [`heuristics::SUPPORTED_ARCHITECTURES`] for what raw-data analysis can
+/// identify. `RiscV128` is format-only.
 pub fn supported_isas() -> Vec<Isa> {
     vec![
         Isa::X86,
@@ -1163,6 +2194,7 @@ pub fn supported_isas() -> Vec<Isa> {
         Isa::AArch64,
         Isa::RiscV32,
         Isa::RiscV64,
+        Isa::RiscV128,
         Isa::Mips,
         Isa::Mips64,
         Isa::Ppc,
@@ -1200,14 +2232,79 @@ pub fn supported_isas() -> Vec<Isa> {
 /// # Arguments
 ///
 /// * `data` - Raw binary data (firmware image)
+/// * `options` - Classification options; see [`heuristics::detect_multi_isa`]
+///   for the ones that apply in windowed mode
 /// * `window_size` - Window size in bytes (recommended: 1024 or 2048)
 ///
 /// # Returns
 ///
 /// Vector of detected ISAs, sorted by dominance (most windows first).
-pub fn detect_multi_isa(data: &[u8], window_size: usize) -> Vec<DetectedIsa> {
-    let options = ClassifierOptions::new();
-    heuristics::detect_multi_isa(data, &options, window_size)
+pub fn detect_multi_isa(
+    data: &[u8],
+    options: &ClassifierOptions,
+    window_size: usize,
+) -> Vec<DetectedIsa> {
+    let opaque = opaque_ranges(data);
+    heuristics::detect_multi_isa_excluding(data, options, window_size, &opaque)
+}
+
+/// Classify each window of a file for an architecture heatmap.
+///
+/// Returns the winning ISA, confidence and entropy of every whole
+/// `window_size` window in file order; see [`heuristics::classify_windows`].
+/// Windows in regions a recognized container marks opaque are reported as
+/// skipped.
+pub fn classify_windows(
+    data: &[u8],
+    options: &ClassifierOptions,
+    window_size: usize,
+) -> Vec<WindowClassification> {
+    let opaque = opaque_ranges(data);
+    heuristics::classify_windows_excluding(data, options, window_size, &opaque)
+}
+
+/// Regions a recognized container marks opaque (e.g. compressed modules).
+fn opaque_ranges(data: &[u8]) -> Vec<(u64, u64)> {
+    match formats::detect_format(data) {
+        formats::DetectedFormat::Raw => Vec::new(),
+        _ => formats::parse_binary(data)
+            .map(|result| result.metadata.opaque_ranges)
+            .unwrap_or_default(),
+    }
+}
+
+/// Every file format whose signature `data` carries, in order of precedence.
+///
+/// The first entry is the format classification uses. Further entries mean
+/// a polyglot, such as a self-extracting executable with a ZIP archive
+/// appended. An empty result means no signature matched and the data is
+/// treated as raw code.
+pub fn detect_all_formats(data: &[u8]) -> Vec<FileFormat> {
+    let mut found: Vec<FileFormat> = formats::detect_all(data)
+        .iter()
+        .map(|detected| detected_to_format(detected).format)
+        .collect();
+    if formats::zip::detect(data) {
+        found.push(FileFormat::Zip);
+    }
+    found
+}
+
+/// Formats other than `primary` whose signature `data` carries. Detections
+/// without a real magic number are left out, since they match by accident.
+fn polyglot_formats(data: &[u8], primary: FileFormat) -> Vec<FileFormat> {
+    let mut others: Vec<FileFormat> = Vec::new();
+    let detected = formats::detect_all(data)
+        .into_iter()
+        .filter(|detected| !detected.is_heuristic())
+        .map(|detected| detected_to_format(&detected).format);
+    let zip = formats::zip::detect(data).then_some(FileFormat::Zip);
+    for format in detected.chain(zip) {
+        if format != primary && !others.contains(&format) {
+            others.push(format);
+        }
+    }
+    others
 }
 
 /// Quick check if a file is likely a specific ISA.
@@ -1242,6 +2339,12 @@ mod tests {
         assert!(!isas.is_empty());
         assert!(isas.contains(&Isa::X86_64));
         assert!(isas.contains(&Isa::AArch64));
+
+        // Format-only: reachable from ELF, never from the scorers
+        assert!(isas.contains(&Isa::RiscV128));
+        assert!(!heuristics::SUPPORTED_ARCHITECTURES
+            .iter()
+            .any(|(isa, _)| *isa == Isa::RiscV128));
     }
 
     #[test]
@@ -1262,6 +2365,35 @@ mod tests {
         assert_eq!(result.format, FileFormat::Elf);
     }
 
+    #[test]
+    fn test_file_api_reports_truncation() {
+        // Section headers declared past the end of a 64-byte file
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0x3E;
+        data[0x28..0x30].copy_from_slice(&0x1000u64.to_le_bytes());
+        data[0x3A] = 64; // e_shentsize
+        data[0x3C] = 4; // e_shnum
+
+        // Bytes alone may be a prefix of a larger file
+        let result = classify_bytes(&data).unwrap();
+        assert!(result.metadata.warnings[0].contains("not examined (input supplied ends at"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short.elf");
+        std::fs::write(&path, &data).unwrap();
+        let truncated =
+            "section headers at offset 0x1000 extends past the end of the file (truncated)";
+        let result = classify_file(&path).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.metadata.warnings, [truncated]);
+        let payload = detect_file(&path).unwrap();
+        assert!(payload.notes.iter().any(|n| n.message == truncated));
+    }
+
     #[test]
     fn test_classify_elf_aarch64() {
         let mut data = vec![0u8; 64];
@@ -1291,6 +2423,123 @@ mod tests {
         assert_eq!(result.isa, Isa::RiscV64);
     }
 
+    #[test]
+    fn test_classify_elf_fast_path() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2; // 64-bit
+        data[5] = 1; // Little-endian
+        data[6] = 1;
+        data[0x12] = 0xF3; // RISC-V
+        data[0x30..0x34].copy_from_slice(&0x0005u32.to_le_bytes()); // RVC | double-float ABI
+        for _ in 0..16 {
+            // vadd.vv v1, v2, v3
+            data.extend_from_slice(&0x022180D7u32.to_le_bytes());
+        }
+
+        let options = ClassifierOptions::new();
+        let fast = classify_elf(&data, &options).unwrap();
+        let general = classify_bytes_with_options(&data, &options).unwrap();
+        assert_eq!(fast.format, FileFormat::Elf);
+        assert_eq!(
+            (fast.isa, fast.bitwidth, fast.endianness),
+            (general.isa, general.bitwidth, general.endianness)
+        );
+        assert_eq!(fast.variant, general.variant);
+        let names = |r: &ClassificationResult| {
+            r.extensions
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&fast), names(&general));
+        assert!(!fast.extensions.is_empty());
+
+        // PE input is rejected rather than classified
+        let mut pe = vec![0u8; 512];
+        pe[0..2].copy_from_slice(b"MZ");
+        pe[0x3C] = 0x80;
+        pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+        pe[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+        assert!(classify_bytes_with_options(&pe, &options).is_ok());
+        assert!(matches!(
+            classify_elf(&pe, &options),
+            Err(error::ClassifierError::InvalidMagic { .. })
+        ));
+        assert!(classify_elf(&[0x7F, b'E'], &options).is_err());
+        assert!(classify_elf(&data[..5], &options).is_err());
+    }
+
+    #[test]
+    fn test_suggest_target_triples() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0xB7; // AArch64
+
+        let result = classify_bytes(&data).unwrap();
+        assert_eq!(
+            result.suggest_target_triples()[0],
+            "aarch64-unknown-linux-gnu"
+        );
+
+        let payload = detect_payload(&data, &ClassifierOptions::new())
+            .unwrap()
+            .with_metadata(libc::metadata_entry(Some(&libc::LibcInfo {
+                name: "musl",
+                version: None,
+                source: libc::LibcSource::EntrySequence,
+            })));
+        assert_eq!(
+            payload.suggest_target_triples(),
+            [
+                "aarch64-unknown-linux-musl",
+                "aarch64-unknown-linux-gnu",
+                "aarch64-unknown-none"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_register_info() {
+        let x64 = Isa::X86_64.register_info().unwrap();
+        assert_eq!((x64.gpr_count, x64.gpr_width), (16, 64));
+        assert!(x64.vector_registers);
+
+        let a64 = Isa::AArch64.register_info().unwrap();
+        assert_eq!((a64.gpr_count, a64.gpr_width), (31, 64));
+        assert!(a64.separate_sp);
+        assert_eq!(a64.to_string(), "31 x 64-bit GPRs + SP, FP, vector");
+
+        assert_eq!(Isa::RiscV32.register_info().unwrap().gpr_count, 32);
+        assert_eq!(Isa::RiscV64.register_info().unwrap().gpr_width, 64);
+        assert!(!Isa::Arm.register_info().unwrap().fp_registers);
+        assert!(Isa::Wasm.register_info().is_none());
+        assert!(Isa::Unknown(0).register_info().is_none());
+
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0xF3; // RISC-V
+        let key = |payload: &DetectionPayload| {
+            payload
+                .metadata
+                .iter()
+                .find(|e| e.key == MetadataKey::Custom("gpr_count".into()))
+                .map(|e| (e.value.to_string(), e.origin))
+        };
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(key(&payload), None);
+        let mut opts = ClassifierOptions::new();
+        opts.report_registers = true;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(key(&payload), Some(("32".to_string(), Some("isa"))));
+    }
+
     #[test]
     fn test_options() {
         let default = ClassifierOptions::new();
@@ -1359,6 +2608,28 @@ mod tests {
         assert_eq!(payload.slices[1].bitwidth, 64);
         assert_eq!(payload.slices[0].offset, 64);
         assert_eq!(payload.slices[1].offset, 128);
+        let isas: Vec<Isa> = payload.candidates.iter().map(|c| c.isa).collect();
+        assert_eq!(isas, [Isa::X86_64, Isa::AArch64]);
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.message == "Universal binary with 2 architectures"));
+        let classified = classify_bytes(&data).unwrap();
+        assert_eq!(classified.candidates.len(), 2);
+
+        // The candidate cap applies to slices as well; the slices list stays whole
+        let mut capped = ClassifierOptions::new();
+        capped.max_candidates = 1;
+        let payload_capped = detect_payload(&data, &capped).unwrap();
+        assert_eq!(payload_capped.candidates.len(), 1);
+        assert_eq!(payload_capped.candidates[0].isa, Isa::X86_64);
+        assert_eq!(payload_capped.slices.len(), 2);
+        capped.max_candidates = 0;
+        let payload_primary_only = detect_payload(&data, &capped).unwrap();
+        assert!(payload_primary_only.candidates.is_empty());
+        let classified_capped = classify_bytes_with_options(&data, &capped).unwrap();
+        assert!(classified_capped.candidates.is_empty());
+        assert_eq!(classified_capped.isa, Isa::X86_64);
 
         // Verify JSON serialization includes slices
         let json = serde_json::to_string(&payload).unwrap();
@@ -1367,4 +2638,854 @@ mod tests {
         // AArch64 serializes as "a_arch64" with serde rename_all = "snake_case"
         assert!(json.contains("a_arch64"));
     }
+
+    #[test]
+    fn test_fat_macho_encrypted_slice_is_skipped() {
+        // x86-64 slice at 0x1000 whose LC_ENCRYPTION_INFO_64 covers its
+        // VEX-encoded code at slice offset 0x100, then a plain arm64 slice
+        let fat = |cryptid: u32| {
+            let mut data = vec![0u8; 0x1000];
+            data[0..8].copy_from_slice(&[0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2]);
+            for (i, (cpu, sub, offset, size)) in [
+                (0x0100_0007u32, 3u32, 0x1000u32, 0x200u32),
+                (0x0100_000C, 0, 0x2000, 0x100),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let entry = 8 + i * 20;
+                for (k, field) in [cpu, sub, offset, size, 12].into_iter().enumerate() {
+                    data[entry + k * 4..entry + k * 4 + 4].copy_from_slice(&field.to_be_bytes());
+                }
+            }
+
+            let mut slice = vec![0u8; 56];
+            slice[0..4].copy_from_slice(&[0xCF, 0xFA, 0xED, 0xFE]);
+            slice[4..8].copy_from_slice(&0x0100_0007u32.to_le_bytes());
+            slice[8..12].copy_from_slice(&3u32.to_le_bytes());
+            slice[12..16].copy_from_slice(&2u32.to_le_bytes()); // MH_EXECUTE
+            slice[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+            slice[20..24].copy_from_slice(&24u32.to_le_bytes()); // sizeofcmds
+            slice[32..36].copy_from_slice(&0x2Cu32.to_le_bytes()); // LC_ENCRYPTION_INFO_64
+            slice[36..40].copy_from_slice(&24u32.to_le_bytes());
+            slice[40..44].copy_from_slice(&0x100u32.to_le_bytes()); // cryptoff
+            slice[44..48].copy_from_slice(&0x100u32.to_le_bytes()); // cryptsize
+            slice[48..52].copy_from_slice(&cryptid.to_le_bytes());
+            slice.resize(0x100, 0x90);
+            for _ in 0..64 {
+                slice.extend_from_slice(&[0xC5, 0xFD, 0xFE, 0xC0]); // vpaddd ymm0, ymm0, ymm0
+            }
+            data.extend_from_slice(&slice);
+
+            data.resize(0x2000, 0);
+            data.extend_from_slice(&[0xCF, 0xFA, 0xED, 0xFE]);
+            data.resize(0x2100, 0);
+            data
+        };
+
+        let payload = detect_payload(&fat(1), &ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.format.format, FileFormat::MachOFat);
+        assert_eq!(payload.primary.isa, Isa::X86_64);
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.level == types::NoteLevel::Warning && n.message.contains("encrypted")));
+        assert!(!payload.extensions.iter().any(|e| e.name == "AVX"));
+
+        // Decrypted slices are scanned as usual
+        let payload = detect_payload(&fat(0), &ClassifierOptions::new()).unwrap();
+        assert!(payload.extensions.iter().any(|e| e.name == "AVX"));
+    }
+
+    #[test]
+    fn test_match_count_tracks_instruction_evidence() {
+        let function = [
+            0xFD, 0x7B, 0xBF, 0xA9, // stp x29, x30, [sp, #-16]!
+            0xFD, 0x03, 0x00, 0x91, // mov x29, sp
+            0xE0, 0x03, 0x00, 0xAA, // mov x0, x0
+            0x1F, 0x20, 0x03, 0xD5, // nop
+            0xFD, 0x7B, 0xC1, 0xA8, // ldp x29, x30, [sp], #16
+            0xC0, 0x03, 0x5F, 0xD6, // ret
+        ];
+        let opts = ClassifierOptions {
+            min_confidence: 0.15,
+            ..ClassifierOptions::thorough()
+        };
+
+        let poor = detect_payload(&function.repeat(2), &opts).unwrap();
+        let rich = detect_payload(&function.repeat(64), &opts).unwrap();
+        assert_eq!(poor.primary.isa, Isa::AArch64);
+        assert_eq!(rich.primary.isa, Isa::AArch64);
+        let poor_matches = poor.primary.match_count().unwrap();
+        let rich_matches = rich.primary.match_count().unwrap();
+        assert!(poor_matches > 0);
+        assert!(
+            rich_matches >= poor_matches * 16,
+            "{rich_matches} vs {poor_matches}"
+        );
+        assert_eq!(
+            rich.to_classification_result().match_count,
+            Some(rich_matches)
+        );
+
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[0x12] = 0xB7;
+        let elf = detect_payload(&elf, &opts).unwrap();
+        assert_eq!(elf.primary.isa, Isa::AArch64);
+        assert_eq!(elf.primary.match_count(), None);
+    }
+
+    #[test]
+    fn test_detect_payload_libc_entry() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0x3E;
+        data.extend_from_slice(b"uClibc-ng 1.0.45\0");
+
+        let mut opts = ClassifierOptions::new();
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert!(!payload
+            .metadata
+            .iter()
+            .any(|m| m.key == MetadataKey::Custom("libc".to_string())));
+
+        opts.detect_libc = true;
+        let payload = detect_payload(&data, &opts).unwrap();
+        let entry = payload
+            .metadata
+            .iter()
+            .find(|m| m.key == MetadataKey::Custom("libc".to_string()))
+            .unwrap();
+        assert_eq!(entry.value.to_string(), "uClibc-ng 1.0.45");
+    }
+
+    #[test]
+    fn test_detect_payload_deprecated_warning() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0x32; // IA-64
+
+        let mut opts = ClassifierOptions::new();
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.primary.isa, Isa::Ia64);
+        assert!(!payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("discontinued")));
+
+        opts.warn_deprecated = true;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert!(payload.notes.iter().any(|n| n.level == NoteLevel::Warning
+            && n.message.contains("targets a discontinued architecture")));
+
+        data[0x12] = 0xB7; // AArch64
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert!(!payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("discontinued")));
+    }
+
+    #[test]
+    fn test_polyglot_pe_zip() {
+        // x86-64 PE headers
+        let mut data = vec![0u8; 512];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3C] = 0x80;
+        data[0x80..0x84].copy_from_slice(b"PE\0\0");
+        data[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+        data[0x94] = 0xF0; // SizeOfOptionalHeader
+        data[0x98..0x9A].copy_from_slice(&0x20Bu16.to_le_bytes()); // PE32+
+
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(detect_all_formats(&data), vec![FileFormat::Pe]);
+        assert!(!payload.notes.iter().any(|n| n.message.contains("polyglot")));
+
+        // Append a ZIP archive: one central directory entry and the EOCD
+        let mut central = b"PK\x01\x02".to_vec();
+        central.resize(46, 0);
+        data.extend_from_slice(&central);
+        data.extend_from_slice(b"PK\x05\x06\0\0\0\0");
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&46u32.to_le_bytes());
+        data.extend_from_slice(&512u32.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+
+        assert_eq!(
+            detect_all_formats(&data),
+            vec![FileFormat::Pe, FileFormat::Zip]
+        );
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(payload.format.format, FileFormat::Pe);
+        assert_eq!(payload.primary.isa, Isa::X86_64);
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.level == NoteLevel::Warning && n.message == "polyglot: also matches ZIP"));
+
+        assert!(detect_all_formats(&[0u8; 64]).is_empty());
+    }
+
+    #[test]
+    fn test_text_prescreen() {
+        let text = "# Build notes\n\nRun `make` and then copy the output to the board.\n".repeat(8);
+
+        let result = classify_bytes(text.as_bytes()).unwrap();
+        assert_eq!(result.isa, Isa::Unknown(0));
+        assert_eq!(result.format, FileFormat::Raw);
+        assert!(result
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("Text data")));
+
+        let payload = detect_payload(text.as_bytes(), &ClassifierOptions::new()).unwrap();
+        assert!(payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("Text data")));
+
+        // Intel HEX is text too, but has its own detection.
+        let ihex = ":10000000214601360121470136007EFE09D2190140\n:00000001FF\n".repeat(4);
+        let result = classify_bytes(ihex.as_bytes()).unwrap();
+        assert_ne!(result.format, FileFormat::Raw);
+
+        let mut opts = ClassifierOptions::new();
+        opts.screen_text = false;
+        let result = classify_bytes_with_options(text.as_bytes(), &opts);
+        assert!(result.map_or(true, |r| !r
+            .metadata
+            .notes
+            .iter()
+            .any(|n| n.contains("Text data"))));
+    }
+
+    #[test]
+    fn test_unicode_bom_short_circuits() {
+        let text = "Changelog\n\nv1.2: faster boot\n";
+        let mut le = vec![0xFF, 0xFE];
+        let mut be = vec![0xFE, 0xFF];
+        for unit in text.encode_utf16() {
+            le.extend_from_slice(&unit.to_le_bytes());
+            be.extend_from_slice(&unit.to_be_bytes());
+        }
+
+        for (data, name) in [(&le, "UTF-16LE"), (&be, "UTF-16BE")] {
+            let result = classify_bytes(data).unwrap();
+            assert_eq!(result.isa, Isa::Unknown(0));
+            assert_eq!(result.format, FileFormat::Raw);
+            assert!(result.metadata.notes.iter().any(|n| n.contains(name)));
+
+            let payload = detect_payload(data, &ClassifierOptions::new()).unwrap();
+            assert_eq!(payload.primary.isa, Isa::Unknown(0));
+            assert!(payload
+                .notes
+                .iter()
+                .any(|n| n.message.contains(&format!("Text data ({name}"))));
+        }
+
+        let mut opts = ClassifierOptions::new();
+        opts.screen_text = false;
+        let payload = detect_payload(&le, &opts).unwrap();
+        assert!(!payload
+            .notes
+            .iter()
+            .any(|n| n.message.contains("Text data")));
+    }
+
+    fn raw_machine_of(data: &[u8]) -> Option<String> {
+        detect_payload(data, &ClassifierOptions::new())
+            .unwrap()
+            .metadata
+            .iter()
+            .find(|m| m.key == MetadataKey::RawMachine)
+            .map(|m| m.value.to_string())
+    }
+
+    #[test]
+    fn test_raw_machine_present_across_formats() {
+        // ELF, including an e_machine the classifier does not know
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[0x12] = 0x3E;
+        assert_eq!(raw_machine_of(&elf).as_deref(), Some("0x0000003E"));
+        elf[0x12..0x14].copy_from_slice(&0x7FF0u16.to_le_bytes());
+        assert_eq!(raw_machine_of(&elf).as_deref(), Some("0x00007FF0"));
+
+        // PE32+ for ARM64
+        let mut pe = vec![0u8; 512];
+        pe[0..2].copy_from_slice(b"MZ");
+        pe[0x3C] = 0x80;
+        pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+        pe[0x84..0x86].copy_from_slice(&0xAA64u16.to_le_bytes());
+        pe[0x86] = 1;
+        pe[0x94] = 0xF0;
+        pe[0x98..0x9A].copy_from_slice(&0x020Bu16.to_le_bytes());
+        assert_eq!(raw_machine_of(&pe).as_deref(), Some("0x0000AA64"));
+
+        // Thin 64-bit Mach-O for arm64
+        let mut macho = vec![0u8; 64];
+        macho[0..4].copy_from_slice(&[0xCF, 0xFA, 0xED, 0xFE]);
+        macho[4..8].copy_from_slice(&0x0100_000Cu32.to_le_bytes());
+        assert_eq!(raw_machine_of(&macho).as_deref(), Some("0x0100000C"));
+
+        // COFF object for i386
+        let mut coff = vec![0u8; 20 + 40 + 256];
+        coff[0..2].copy_from_slice(&0x014Cu16.to_le_bytes());
+        coff[2] = 1;
+        coff[4..8].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+        coff[8..12].copy_from_slice(&60u32.to_le_bytes());
+        coff[12..16].copy_from_slice(&10u32.to_le_bytes());
+        assert_eq!(raw_machine_of(&coff).as_deref(), Some("0x0000014C"));
+    }
+
+    #[test]
+    fn test_max_candidates_option() {
+        let data = test_fixtures::aarch64_code(600);
+
+        let mut opts = ClassifierOptions::new();
+        opts.max_candidates = usize::MAX;
+        let full = detect_payload(&data, &opts).unwrap().candidates;
+        assert!(full.len() > 3);
+
+        opts.max_candidates = 3;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.candidates, full[..3]);
+        let legacy = classify_bytes_with_options(&data, &opts).unwrap();
+        assert_eq!(legacy.candidates.len(), 3);
+
+        opts.max_candidates = 0;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        assert!(payload.candidates.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_api_candidates_match_payload() {
+        let data = test_fixtures::aarch64_code(600);
+
+        let opts = ClassifierOptions::new();
+        let legacy = classify_bytes_with_options(&data, &opts).unwrap();
+        let payload = detect_payload(&data, &opts).unwrap();
+
+        assert_eq!(legacy.isa, Isa::AArch64);
+        assert!(!legacy.candidates.is_empty());
+        assert_eq!(legacy.candidates, payload.candidates);
+        assert_eq!(
+            payload.to_classification_result().candidates,
+            legacy.candidates
+        );
+        assert!(legacy
+            .candidates
+            .windows(2)
+            .all(|w| w[0].raw_score >= w[1].raw_score));
+
+        // Format-based results carry no candidates
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[0x12] = 0xB7;
+        assert!(classify_bytes(&elf).unwrap().candidates.is_empty());
+        let payload = detect_payload(&elf, &opts).unwrap();
+        assert!(payload.to_classification_result().candidates.is_empty());
+    }
+
+    /// Writer collecting formatted trace output for inspection.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct TraceCapture(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    #[cfg(feature = "tracing")]
+    impl std::io::Write for TraceCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_classification_trace() {
+        let data = test_fixtures::aarch64_code(1024);
+
+        let capture = TraceCapture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let payload = tracing::subscriber::with_default(subscriber, || {
+            detect_payload(&data, &ClassifierOptions::new()).unwrap()
+        });
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+
+        let log = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(log.contains("detected format format=Raw"));
+        assert!(log.contains("scorer isa=aarch64"));
+        assert!(log.contains("winner winner=aarch64"));
+        assert!(log.contains("margin="));
+        assert!(log.contains("classified isa=aarch64"));
+        // Events are recorded inside the payload span
+        assert!(log.contains("detect_payload{input_size=4096}"));
+    }
+
+    #[test]
+    fn test_payload_sizes() {
+        // 256 KiB of AArch64 code, scored through a 64 KiB window
+        let data = test_fixtures::aarch64_code(64 * 1024);
+        let opts = ClassifierOptions {
+            max_scan_bytes: 64 * 1024,
+            detect_extensions: false,
+            ..ClassifierOptions::new()
+        };
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.input_size, 256 * 1024);
+        assert_eq!(payload.scanned_bytes, Some(64 * 1024));
+        assert_eq!(payload.described_size, None);
+
+        // Extension detection reads the whole input
+        let opts = ClassifierOptions {
+            detect_extensions: true,
+            ..opts
+        };
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.scanned_bytes, Some(256 * 1024));
+
+        // An ELF that claims less than the input carries trailing data
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[0x12] = 0xB7;
+        elf.resize(4096, 0);
+        let payload = detect_payload(&elf, &opts).unwrap();
+        assert_eq!(payload.input_size, 4096);
+        assert_eq!(payload.described_size, Some(64));
+
+        // Parsers that do not report their header extent leave it unknown
+        let mut bflt = b"bFLT".to_vec();
+        bflt.extend_from_slice(&4u32.to_be_bytes());
+        bflt.resize(4096, 0);
+        let opts = ClassifierOptions {
+            detect_extensions: false,
+            ..opts
+        };
+        let payload = detect_payload(&bflt, &opts).unwrap();
+        assert_eq!(payload.format.format, FileFormat::Bflt);
+        assert_eq!(payload.scanned_bytes, None);
+    }
+
+    #[test]
+    fn test_payload_evidence() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0x3E;
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        let kinds: Vec<EvidenceKind> = payload.evidence.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            &kinds[..2],
+            &[EvidenceKind::MagicMatch, EvidenceKind::HeaderField]
+        );
+        assert_eq!(payload.evidence[0].offset, Some(0));
+        assert!(payload.evidence[1].description.contains("0x3E"));
+        assert!(payload.explain().starts_with("[magic] ELF magic at 0x0"));
+
+        let data = test_fixtures::aarch64_code(600);
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        let first = &payload.evidence[0];
+        assert_eq!(first.kind, EvidenceKind::InstructionPattern);
+        assert!(first.description.starts_with("AArch64"));
+        assert!(!payload
+            .evidence
+            .iter()
+            .any(|e| e.kind == EvidenceKind::MagicMatch));
+        // Every pattern line explains the winner, naming what matched;
+        // runners-up appear only as candidates
+        assert!(payload.candidates.len() > 1);
+        let patterns: Vec<&str> = payload.evidence[1..]
+            .iter()
+            .filter(|e| e.kind == EvidenceKind::InstructionPattern)
+            .map(|e| e.description.as_str())
+            .collect();
+        assert_eq!(patterns.len(), MAX_PATTERN_EVIDENCE);
+        let name = Isa::AArch64.name();
+        assert!(patterns.iter().all(|d| d.starts_with(&format!("{name} `"))));
+        assert!(patterns.contains(&format!("{name} `ret` matched 60 times").as_str()));
+        assert!(payload
+            .evidence
+            .iter()
+            .all(|e| !e.description.contains("MIPS") && !e.description.contains("x86")));
+    }
+
+    #[test]
+    fn test_infer_addressing_option() {
+        let function: [u32; 8] = [
+            0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+            0x9000_0080, // adrp x0, page
+            0x9100_4000, // add x0, x0, #16
+            0x9000_0081, // adrp x1, page
+            0xF940_0421, // ldr x1, [x1, #8]
+            0x9400_0010, // bl
+            0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+            0xD65F_03C0, // ret
+        ];
+        let data: Vec<u8> = function
+            .repeat(64)
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect();
+
+        let mut options = ClassifierOptions::new();
+        let payload = detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        assert!(payload
+            .metadata
+            .iter()
+            .all(|e| e.origin != Some("addressing")));
+
+        options.infer_addressing = true;
+        let payload = detect_payload(&data, &options).unwrap();
+        let entry = payload
+            .metadata
+            .iter()
+            .find(|e| e.origin == Some("addressing"))
+            .unwrap();
+        assert!(matches!(
+            &entry.value,
+            MetadataValue::String(s) if s.starts_with("position-independent")
+        ));
+    }
+
+    #[test]
+    fn test_strict_magic_option() {
+        // 0xCAFEBABE, one fat_arch entry for x86 (cputype 7), then AArch64 code
+        let mut data = vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x01];
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.resize(32, 0);
+        data.extend(test_fixtures::aarch64_code(512));
+
+        let mut options = ClassifierOptions::new();
+        let payload = detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.format.format, FileFormat::MachOFat);
+
+        options.strict_magic = true;
+        let payload = detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.format.format, FileFormat::Raw);
+        assert_eq!(payload.primary.isa, Isa::AArch64);
+        let result = classify_bytes_with_options(&data, &options).unwrap();
+        assert_eq!(result.format, FileFormat::Raw);
+    }
+
+    #[test]
+    fn test_detect_com_option() {
+        // mov ah,9 / mov dx,msg / int 21h / int 20h / msg db "Hello$"
+        let mut data = vec![0xB4, 0x09, 0xBA, 0x09, 0x01, 0xCD, 0x21, 0xCD, 0x20];
+        data.extend_from_slice(b"Hello, world!$");
+
+        let mut options = ClassifierOptions::new();
+        let result = classify_bytes_with_options(&data, &options).unwrap();
+        assert_eq!(result.format, FileFormat::Raw);
+
+        options.detect_com = true;
+        let result = classify_bytes_with_options(&data, &options).unwrap();
+        assert_eq!(result.format, FileFormat::Com);
+        assert_eq!((result.isa, result.bitwidth), (Isa::X86, 16));
+        assert!(!result.candidates.is_empty());
+
+        let payload = detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.format.format, FileFormat::Com);
+        assert_eq!(payload.primary.isa, Isa::X86);
+        assert!(!payload.candidates.is_empty());
+        assert!(payload.extensions_analyzed);
+
+        options.strict_magic = true;
+        let payload = detect_payload(&data, &options).unwrap();
+        assert_eq!(payload.format.format, FileFormat::Raw);
+    }
+
+    /// Throughput floor guarding against scorer slowdowns on machines
+    /// that do not run the criterion benchmarks.
+    #[test]
+    fn test_classification_time_budget() {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut data = Vec::with_capacity(1 << 20);
+        while data.len() < 1 << 20 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let rd = (state & 0xF) as u32;
+            let imm = ((state >> 8) & 0xFFF) as u32;
+            for word in [
+                0xA9BF_7BFD,                            // stp x29, x30, [sp, #-16]!
+                0x9100_03FD,                            // mov x29, sp
+                0x9100_0000 | imm << 10 | rd << 5 | rd, // add xd, xd, #imm
+                0xF940_0000 | (imm & 0x1FF) << 10 | rd, // ldr xd, [x0, #imm]
+                0x9400_0000 | imm,                      // bl
+                0xA8C1_7BFD,                            // ldp x29, x30, [sp], #16
+                0xD65F_03C0u32,                         // ret
+            ] {
+                data.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+
+        let start = std::time::Instant::now();
+        let result = classify_bytes(&data).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(result.isa, Isa::AArch64);
+        // About 2 MiB/s at the test profile; the floor leaves room for a
+        // loaded machine. See benches/classifier.rs for real measurements
+        const MIN_BYTES_PER_SEC: f64 = 512.0 * 1024.0;
+        let throughput = data.len() as f64 / elapsed.as_secs_f64();
+        assert!(
+            throughput >= MIN_BYTES_PER_SEC,
+            "classifying {} bytes took {elapsed:?} ({:.0} KiB/s)",
+            data.len(),
+            throughput / 1024.0
+        );
+    }
+
+    #[test]
+    fn test_extensions_analyzed_flag() {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12] = 0x3E;
+        // xor eax, eax; ret
+        data.extend_from_slice(&[0x31, 0xC0, 0xC3]);
+
+        let mut opts = ClassifierOptions::new();
+        opts.detect_extensions = false;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.primary.isa, Isa::X86_64);
+        assert!(!payload.extensions_analyzed);
+
+        opts.detect_extensions = true;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert!(payload.extensions_analyzed);
+
+        // No code detector for AVR: still "didn't look"
+        data[4] = 1;
+        data[0x12] = 0x53;
+        let payload = detect_payload(&data, &opts).unwrap();
+        assert_eq!(payload.primary.isa, Isa::Avr);
+        assert!(!payload.extensions_analyzed);
+    }
+
+    #[test]
+    fn test_scan_executable_only() {
+        // x86-64 executable: AVX code in .text, a .rodata blob of 0x62 bytes
+        // (EVEX prefixes to a whole-file scan), .shstrtab, section headers
+        let code = [
+            0x55, 0x48, 0x89, 0xE5, 0xC5, 0xF5, 0xFE, 0xC2, 0xE8, 0x10, 0x00, 0x00, 0x00, 0x48,
+            0x8B, 0x45, 0xF8, 0x5D, 0xC3,
+        ]
+        .repeat(16);
+        let names = b"\0.text\0.rodata\0.shstrtab\0";
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&formats::magic::ELF);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x10] = 2; // ET_EXEC
+        data[0x12] = 0x3E;
+        let text = (data.len() as u64, code.len() as u64);
+        data.extend_from_slice(&code);
+        let rodata = (data.len() as u64, 4096);
+        data.resize(data.len() + 4096, 0x62);
+        let strtab = (data.len() as u64, names.len() as u64);
+        data.extend_from_slice(names);
+        let shoff = data.len() as u64;
+        // (name, type, flags, (offset, size)): SHT_PROGBITS = 1, SHT_STRTAB = 3
+        let sections = [
+            (0u32, 0u32, 0u64, (0, 0)),
+            (1, 1, 0x6, text),
+            (7, 1, 0x2, rodata),
+            (15, 3, 0, strtab),
+        ];
+        for (name, kind, flags, (offset, size)) in sections {
+            let mut sh = [0u8; 64];
+            sh[0..4].copy_from_slice(&name.to_le_bytes());
+            sh[4..8].copy_from_slice(&kind.to_le_bytes());
+            sh[8..16].copy_from_slice(&flags.to_le_bytes());
+            sh[0x18..0x20].copy_from_slice(&offset.to_le_bytes());
+            sh[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&sh);
+        }
+        data[0x28..0x30].copy_from_slice(&shoff.to_le_bytes());
+        data[0x3A] = 64; // e_shentsize
+        data[0x3C] = 4; // e_shnum
+        data[0x3E] = 3; // e_shstrndx
+
+        let names = |options: &ClassifierOptions| -> (Vec<String>, Vec<String>) {
+            let result = classify_bytes_with_options(&data, options).unwrap();
+            let payload = detect_payload(&data, options).unwrap();
+            (
+                result.extensions.into_iter().map(|e| e.name).collect(),
+                payload.extensions.into_iter().map(|e| e.name).collect(),
+            )
+        };
+        let mut options = ClassifierOptions::new();
+        assert!(options.scan_executable_only);
+        let (result, payload) = names(&options);
+        for found in [result, payload] {
+            assert!(found.iter().any(|n| n == "AVX"), "{found:?}");
+            assert!(!found.iter().any(|n| n.starts_with("AVX-512")), "{found:?}");
+        }
+
+        // The old whole-file scan reads .rodata as EVEX code
+        options.scan_executable_only = false;
+        let (result, payload) = names(&options);
+        for found in [result, payload] {
+            assert!(found.iter().any(|n| n == "AVX-512F"), "{found:?}");
+        }
+    }
+
+    #[test]
+    fn test_variant_is_optional() {
+        let options = ClassifierOptions::new();
+
+        // A plain PE header says nothing beyond the machine
+        let mut pe = vec![0u8; 512];
+        pe[0..2].copy_from_slice(b"MZ");
+        pe[0x3C] = 0x80;
+        pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+        pe[0x84..0x86].copy_from_slice(&0x8664u16.to_le_bytes());
+        let result = classify_bytes_with_options(&pe, &options).unwrap();
+        assert_eq!(result.isa, Isa::X86_64);
+        assert_eq!(result.variant, None);
+        assert!(!result.to_string().contains("Variant"));
+        let payload = detect_payload(&pe, &options).unwrap();
+        assert_eq!(payload.primary.variant, None);
+        assert_eq!(payload.to_classification_result().variant, None);
+
+        // RISC-V ELF flags: RVC, double-float ABI
+        let mut elf = vec![0u8; 64];
+        elf[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[6] = 1;
+        elf[0x12] = 0xF3;
+        elf[0x30..0x34].copy_from_slice(&0x0005u32.to_le_bytes());
+        let result = classify_bytes_with_options(&elf, &options).unwrap();
+        assert_eq!(result.variant_name(), Some("RISC-V"));
+        assert!(result.to_string().contains("Variant: "));
+        let payload = detect_payload(&elf, &options).unwrap();
+        assert_eq!(payload.primary.variant, result.variant);
+        assert_eq!(payload.to_classification_result().variant, result.variant);
+    }
+}
+
+/// Compile-time check that the public types are `Send + Sync`.
+///
+/// Add new public types here; a type that cannot be shared across threads
+/// must be listed in the crate-level "Thread Safety" docs instead.
+#[cfg(test)]
+mod thread_safety {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_public_types_are_send_sync() {
+        assert_send_sync::<ClassifierError>();
+        assert_send_sync::<Result<ClassificationResult>>();
+
+        assert_send_sync::<ClassificationMetadata>();
+        assert_send_sync::<ClassificationResult>();
+        assert_send_sync::<ClassificationSource>();
+        assert_send_sync::<ClassifierOptions>();
+        assert_send_sync::<ContainedArch>();
+        assert_send_sync::<DetectionPayload>();
+        assert_send_sync::<Endianness>();
+        assert_send_sync::<Evidence>();
+        assert_send_sync::<EvidenceKind>();
+        assert_send_sync::<Extension>();
+        assert_send_sync::<ExtensionCategory>();
+        assert_send_sync::<ExtensionDetection>();
+        assert_send_sync::<ExtensionSource>();
+        assert_send_sync::<FileFormat>();
+        assert_send_sync::<FormatDetection>();
+        assert_send_sync::<FormatFamily>();
+        assert_send_sync::<HardeningSummary>();
+        assert_send_sync::<Isa>();
+        assert_send_sync::<IsaFamily>();
+        assert_send_sync::<IsaCandidate>();
+        assert_send_sync::<IsaClassification>();
+        assert_send_sync::<MetadataEntry>();
+        assert_send_sync::<MetadataKey>();
+        assert_send_sync::<MetadataValue>();
+        assert_send_sync::<Note>();
+        assert_send_sync::<NoteLevel>();
+        assert_send_sync::<OsFamily>();
+        assert_send_sync::<OsFingerprint>();
+        assert_send_sync::<RegisterInfo>();
+        assert_send_sync::<SampleMode>();
+        assert_send_sync::<SectionInfo>();
+        assert_send_sync::<SegmentInfo>();
+        assert_send_sync::<Variant>();
+
+        assert_send_sync::<DetectedIsa>();
+        assert_send_sync::<SkipReason>();
+        assert_send_sync::<WindowClassification>();
+        assert_send_sync::<heuristics::ArchitectureScore>();
+        assert_send_sync::<fingerprint::Fingerprint>();
+        assert_send_sync::<extensions::KnownExtension>();
+
+        assert_send_sync::<CandidatesFormatter>();
+        assert_send_sync::<HumanFormatter>();
+        assert_send_sync::<JsonFormatter>();
+        assert_send_sync::<NdjsonFormatter>();
+        assert_send_sync::<ShortFormatter>();
+        assert_send_sync::<Box<dyn PayloadFormatter + Send + Sync>>();
+        assert_send_sync::<ColorChoice>();
+        assert_send_sync::<Output>();
+        assert_send_sync::<Reclassifier<'static>>();
+
+        #[cfg(feature = "std")]
+        assert_send_sync::<ClassifyDir>();
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_batch_types_are_send_sync() {
+        use crate::batch::*;
+
+        assert_send_sync::<KeyConfig>();
+        assert_send_sync::<PipelineConfig>();
+        assert_send_sync::<PipelineResult>();
+        assert_send_sync::<PipelineStats>();
+        assert_send_sync::<RoutingConfig>();
+        assert_send_sync::<RoutingDecision>();
+        assert_send_sync::<StagingWriter>();
+        assert_send_sync::<MetadataSidecar>();
+        assert_send_sync::<LedgerEntry>();
+        assert_send_sync::<RunManifest>();
+    }
 }
@@ -0,0 +1,428 @@
+//! Static C library identification.
+//!
+//! Statically linked binaries carry no `PT_INTERP` or `DT_NEEDED`, so the C
+//! library they were built against has to be recognised from the code and
+//! data that got linked in. Two independent signals are used:
+//!
+//! - **Version banners**: glibc, uClibc-ng and musl embed recognisable
+//!   strings (`"GNU C Library ... release version 2.31"`, `"uClibc-ng 1.0.39"`,
+//!   `"musl libc"`). The scan is bounded to [`MAX_BANNER_SCAN`] bytes.
+//! - **Start-up sequences**: for static ELF executables the code at `e_entry`
+//!   is `_start` from the libc's `crt1.o`, which is hand-written assembly and
+//!   differs between libcs. It is compared against per-ISA templates for
+//!   x86, x86-64, AArch64, ARM (including Thumb-2) and MIPS.
+//!
+//! Banners win over start-up templates since they also carry a version.
+//! Identification never fails hard; callers get `None` and report "unknown".
+
+use memchr::memmem;
+
+use crate::formats::elf::{self, class, data, pt};
+use crate::formats::{read_u16, read_u32, read_u64};
+use crate::types::{Isa, MetadataEntry, MetadataValue};
+
+/// Maximum number of bytes searched for version banners.
+pub const MAX_BANNER_SCAN: usize = 16 * 1024 * 1024;
+
+/// Number of bytes after the entry point searched for a start-up template.
+const ENTRY_WINDOW: usize = 64;
+
+/// How a C library was identified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibcSource {
+    /// A version banner string was found
+    Banner,
+    /// The ELF entry point matched a known `_start` sequence
+    EntrySequence,
+}
+
+/// An identified C library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibcInfo {
+    /// Library name ("glibc", "musl", "uClibc-ng", "uClibc")
+    pub name: &'static str,
+    /// Version, when a banner carried one
+    pub version: Option<String>,
+    /// Which signal identified the library
+    pub source: LibcSource,
+}
+
+impl LibcInfo {
+    /// Render as "name version", or just the name when unversioned.
+    pub fn display(&self) -> String {
+        match &self.version {
+            Some(v) => format!("{} {v}", self.name),
+            None => self.name.to_string(),
+        }
+    }
+}
+
+/// A `_start` byte template for one libc on one ISA.
+///
+/// Patterns are space-separated hex bytes; `?` matches any nibble. MIPS
+/// templates are written big-endian and matched word-swapped on
+/// little-endian targets.
+struct StartTemplate {
+    libc: &'static str,
+    isa: Isa,
+    pattern: &'static str,
+}
+
+const START_TEMPLATES: &[StartTemplate] = &[
+    // x86-64: xor ebp,ebp; mov r9,rdx; pop rsi; mov rdx,rsp; and rsp,-16; push rax; push rsp
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::X86_64,
+        pattern: "31 ed 49 89 d1 5e 48 89 e2 48 83 e4 f0 50 54",
+    },
+    // x86-64: xor rbp,rbp; mov rdi,rsp; lea rsi,[rip+_DYNAMIC]; and rsp,-16; call _start_c
+    StartTemplate {
+        libc: "musl",
+        isa: Isa::X86_64,
+        pattern: "48 31 ed 48 89 e7 48 8d 35 ?? ?? ?? ?? 48 83 e4 f0 e8",
+    },
+    // i386: xor ebp,ebp; pop esi; mov ecx,esp; and esp,-16; push eax; push esp; push edx
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::X86,
+        pattern: "31 ed 5e 89 e1 83 e4 f0 50 54 52",
+    },
+    // i386: xor ebp,ebp; mov eax,esp; and esp,-16; push eax; push eax; call 1f
+    StartTemplate {
+        libc: "musl",
+        isa: Isa::X86,
+        pattern: "31 ed 89 e0 83 e4 f0 50 50 e8 00 00 00 00",
+    },
+    // AArch64: mov x29,#0; mov x30,#0; mov x5,x0; ldr x1,[sp]; add x2,sp,#8; mov x6,sp
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::AArch64,
+        pattern: "1d 00 80 d2 1e 00 80 d2 e5 03 00 aa e1 03 40 f9 e2 23 00 91 e6 03 00 91",
+    },
+    // AArch64: mov x29,#0; mov x30,#0; mov x0,sp; adrp x1,_DYNAMIC
+    StartTemplate {
+        libc: "musl",
+        isa: Isa::AArch64,
+        pattern: "1d 00 80 d2 1e 00 80 d2 e0 03 00 91 ?1 ?? ?? ?0",
+    },
+    // ARM: mov fp,#0; mov lr,#0; pop {r1}; mov r2,sp; push {r2}; push {r0}
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::Arm,
+        pattern: "00 b0 a0 e3 00 e0 a0 e3 04 10 9d e4 0d 20 a0 e1 04 20 2d e5 04 00 2d e5",
+    },
+    // Thumb-2: mov.w fp,#0; mov.w lr,#0; pop {r1}; mov r2,sp
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::Arm,
+        pattern: "4f f0 00 0b 4f f0 00 0e 02 bc 6a 46",
+    },
+    // ARM: mov fp,#0; mov lr,#0; ldr r1,[pc,#n]; add r1,pc,r1; mov r0,sp
+    StartTemplate {
+        libc: "musl",
+        isa: Isa::Arm,
+        pattern: "00 b0 a0 e3 00 e0 a0 e3 ?? 1? 9f e5 01 10 8f e0 0d 00 a0 e1",
+    },
+    // MIPS: lw a1,0(sp); addiu a2,sp,4; li at,-8; and sp,sp,at
+    StartTemplate {
+        libc: "glibc",
+        isa: Isa::Mips,
+        pattern: "8f a5 00 00 27 a6 00 04 24 01 ff f8 03 a1 e8 24",
+    },
+    // MIPS: bal 1f; move fp,zero; .gpword x3; 1: lw gp,0(ra); subu gp,ra,gp
+    StartTemplate {
+        libc: "musl",
+        isa: Isa::Mips,
+        pattern:
+            "04 11 00 04 00 00 f0 25 ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? 8f fc 00 00 03 fc e0 23",
+    },
+];
+
+/// Identify the C library linked into `data`.
+///
+/// Returns `None` when neither a banner nor a start-up sequence matched.
+pub fn identify(data: &[u8]) -> Option<LibcInfo> {
+    scan_banners(data).or_else(|| match_elf_entry(data))
+}
+
+/// Build the `libc` metadata entry, reporting "unknown" when unidentified.
+pub fn metadata_entry(info: Option<&LibcInfo>) -> MetadataEntry {
+    let value = info.map_or_else(|| "unknown".to_string(), LibcInfo::display);
+    MetadataEntry::custom("libc", MetadataValue::String(value), "C Library").with_origin("libc")
+}
+
+/// Search for libc version banners in the first [`MAX_BANNER_SCAN`] bytes.
+fn scan_banners(data: &[u8]) -> Option<LibcInfo> {
+    let hay = &data[..data.len().min(MAX_BANNER_SCAN)];
+    let banner = |name, version| {
+        Some(LibcInfo {
+            name,
+            version,
+            source: LibcSource::Banner,
+        })
+    };
+
+    if let Some(pos) = memmem::find(hay, b"uClibc-ng ") {
+        return banner("uClibc-ng", parse_version(&hay[pos + 10..]));
+    }
+    if memmem::find(hay, b"uClibc").is_some() {
+        let version = memmem::find(hay, b"uClibc ").and_then(|p| parse_version(&hay[p + 7..]));
+        return banner("uClibc", version);
+    }
+    if memmem::find(hay, b"musl libc").is_some() {
+        return banner("musl", None);
+    }
+    if let Some(pos) = memmem::find(hay, b"GNU C Library") {
+        let tail = &hay[pos..hay.len().min(pos + 256)];
+        let version =
+            memmem::find(tail, b"release version ").and_then(|p| parse_version(&tail[p + 16..]));
+        return banner("glibc", version);
+    }
+    None
+}
+
+/// Parse a dotted numeric version at the start of `bytes`.
+fn parse_version(bytes: &[u8]) -> Option<String> {
+    let len = bytes
+        .iter()
+        .take(32)
+        .take_while(|b| b.is_ascii_digit() || **b == b'.')
+        .count();
+    let version = std::str::from_utf8(&bytes[..len])
+        .ok()?
+        .trim_end_matches('.');
+    (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+        .then(|| version.to_string())
+}
+
+/// Match the code at a static ELF executable's entry point against the
+/// start-up templates for its ISA.
+fn match_elf_entry(bytes: &[u8]) -> Option<LibcInfo> {
+    if bytes.len() < 0x34 || bytes[..4] != [0x7F, b'E', b'L', b'F'] {
+        return None;
+    }
+    let ei_class = bytes[4];
+    let is_64 = ei_class == class::ELFCLASS64;
+    let le = bytes[5] == data::ELFDATA2LSB;
+
+    let e_machine = read_u16(bytes, 0x12, le).ok()?;
+    let entry = if is_64 {
+        read_u64(bytes, 0x18, le).ok()?
+    } else {
+        u64::from(read_u32(bytes, 0x18, le).ok()?)
+    };
+
+    let phdrs = elf::program_headers(bytes, is_64, le);
+    if phdrs.iter().any(|p| p.p_type == pt::PT_INTERP) {
+        // Dynamically linked: _start lives in the executable but the libc does not.
+        return None;
+    }
+
+    // Thumb entry points have the low bit set
+    let (isa, _) = elf::e_machine_to_isa(e_machine, ei_class);
+    let entry = if isa == Isa::Arm { entry & !1 } else { entry };
+
+    let offset = phdrs
+        .iter()
+        .filter(|p| p.p_type == pt::PT_LOAD)
+        .find(|p| entry >= p.p_vaddr && entry - p.p_vaddr < p.p_filesz)
+        .and_then(|p| usize::try_from(p.p_offset.checked_add(entry - p.p_vaddr)?).ok())?;
+    let window = bytes.get(offset..bytes.len().min(offset.checked_add(ENTRY_WINDOW)?))?;
+
+    let family = match isa {
+        Isa::Mips64 => Isa::Mips,
+        other => other,
+    };
+    let step = match family {
+        Isa::X86 | Isa::X86_64 => 1,
+        Isa::Arm => 2,
+        _ => 4,
+    };
+    let swap_words = family == Isa::Mips && le;
+
+    START_TEMPLATES
+        .iter()
+        .filter(|t| t.isa == family)
+        .find(|t| {
+            let pattern = parse_pattern(t.pattern);
+            (0..window.len())
+                .step_by(step)
+                .any(|start| matches_at(window, start, &pattern, swap_words))
+        })
+        .map(|t| LibcInfo {
+            name: t.libc,
+            version: None,
+            source: LibcSource::EntrySequence,
+        })
+}
+
+/// Parse a template into `(value, mask)` byte pairs.
+fn parse_pattern(pattern: &str) -> Vec<(u8, u8)> {
+    pattern
+        .split_whitespace()
+        .map(|tok| {
+            tok.chars().fold((0u8, 0u8), |(value, mask), c| {
+                let (v, m) = c
+                    .to_digit(16)
+                    .map_or((0, 0), |d| (u8::try_from(d).unwrap_or(0), 0xF));
+                ((value << 4) | v, (mask << 4) | m)
+            })
+        })
+        .collect()
+}
+
+/// Check whether `pattern` matches `window` at `start`.
+fn matches_at(window: &[u8], start: usize, pattern: &[(u8, u8)], swap_words: bool) -> bool {
+    if start + pattern.len() > window.len() {
+        return false;
+    }
+    pattern.iter().enumerate().all(|(j, &(value, mask))| {
+        let j = if swap_words {
+            (j & !3) | (3 - (j & 3))
+        } else {
+            j
+        };
+        window[start + j] & mask == value
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal static ELF with one `PT_LOAD` at 0x400000 and `code` at the entry.
+    fn make_static_elf(e_machine: u16, is_64: bool, le: bool, code: &[u8]) -> Vec<u8> {
+        let (ehsize, phentsize) = if is_64 { (64, 56) } else { (52, 32) };
+        let code_off = 0x100usize;
+        let vaddr = 0x40_0000u64;
+        let mut out = vec![0u8; code_off];
+        out[..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        out[4] = if is_64 { 2 } else { 1 };
+        out[5] = if le { 1 } else { 2 };
+        out[6] = 1;
+
+        let put16 = |buf: &mut Vec<u8>, off: usize, v: u16| {
+            let b = if le { v.to_le_bytes() } else { v.to_be_bytes() };
+            buf[off..off + 2].copy_from_slice(&b);
+        };
+        let put32 = |buf: &mut Vec<u8>, off: usize, v: u32| {
+            let b = if le { v.to_le_bytes() } else { v.to_be_bytes() };
+            buf[off..off + 4].copy_from_slice(&b);
+        };
+        let put64 = |buf: &mut Vec<u8>, off: usize, v: u64| {
+            let b = if le { v.to_le_bytes() } else { v.to_be_bytes() };
+            buf[off..off + 8].copy_from_slice(&b);
+        };
+
+        put16(&mut out, 0x10, 2); // ET_EXEC
+        put16(&mut out, 0x12, e_machine);
+        put32(&mut out, 0x14, 1);
+        let entry = vaddr + code_off as u64;
+        let total = (code_off + code.len()) as u64;
+        if is_64 {
+            put64(&mut out, 0x18, entry);
+            put64(&mut out, 0x20, u64::from(ehsize)); // e_phoff
+            put16(&mut out, 0x34, ehsize);
+            put16(&mut out, 0x36, phentsize);
+            put16(&mut out, 0x38, 1);
+            let ph = usize::from(ehsize);
+            put32(&mut out, ph, pt::PT_LOAD);
+            put32(&mut out, ph + 4, 5);
+            put64(&mut out, ph + 8, 0);
+            put64(&mut out, ph + 16, vaddr);
+            put64(&mut out, ph + 24, vaddr);
+            put64(&mut out, ph + 32, total);
+            put64(&mut out, ph + 40, total);
+        } else {
+            put32(&mut out, 0x18, u32::try_from(entry).unwrap());
+            put32(&mut out, 0x1C, u32::from(ehsize)); // e_phoff
+            put16(&mut out, 0x28, ehsize);
+            put16(&mut out, 0x2A, phentsize);
+            put16(&mut out, 0x2C, 1);
+            let ph = usize::from(ehsize);
+            put32(&mut out, ph, pt::PT_LOAD);
+            put32(&mut out, ph + 4, 0);
+            put32(&mut out, ph + 8, u32::try_from(vaddr).unwrap());
+            put32(&mut out, ph + 12, u32::try_from(vaddr).unwrap());
+            put32(&mut out, ph + 16, u32::try_from(total).unwrap());
+            put32(&mut out, ph + 20, u32::try_from(total).unwrap());
+        }
+        out.extend_from_slice(code);
+        out
+    }
+
+    #[test]
+    fn test_glibc_banner_with_version() {
+        let mut data = vec![0u8; 64];
+        data.extend_from_slice(
+            b"GNU C Library (Ubuntu GLIBC 2.35-0ubuntu3.8) stable release version 2.35.\n",
+        );
+        let info = identify(&data).unwrap();
+        assert_eq!(info.name, "glibc");
+        assert_eq!(info.version.as_deref(), Some("2.35"));
+        assert_eq!(info.source, LibcSource::Banner);
+    }
+
+    #[test]
+    fn test_uclibc_ng_and_musl_banners() {
+        let info = identify(b"\0\0uClibc-ng 1.0.45\0").unwrap();
+        assert_eq!(info.display(), "uClibc-ng 1.0.45");
+
+        let info = identify(b"\0musl libc (x86_64)\0Version %s\n\0").unwrap();
+        assert_eq!(info.display(), "musl");
+    }
+
+    #[test]
+    fn test_x86_64_start_sequences() {
+        // musl 1.2.x crt1.o _start
+        let musl = [
+            0x48, 0x31, 0xED, 0x48, 0x89, 0xE7, 0x48, 0x8D, 0x35, 0x1B, 0x00, 0x00, 0x00, 0x48,
+            0x83, 0xE4, 0xF0, 0xE8, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let info = identify(&make_static_elf(62, true, true, &musl)).unwrap();
+        assert_eq!(info.name, "musl");
+        assert_eq!(info.source, LibcSource::EntrySequence);
+
+        // glibc 2.35 crt1.o _start (CET-enabled)
+        let glibc = [
+            0xF3, 0x0F, 0x1E, 0xFA, 0x31, 0xED, 0x49, 0x89, 0xD1, 0x5E, 0x48, 0x89, 0xE2, 0x48,
+            0x83, 0xE4, 0xF0, 0x50, 0x54, 0x45, 0x31, 0xC0,
+        ];
+        let info = identify(&make_static_elf(62, true, true, &glibc)).unwrap();
+        assert_eq!(info.name, "glibc");
+    }
+
+    #[test]
+    fn test_aarch64_start_sequences() {
+        let glibc = [
+            0x1D, 0x00, 0x80, 0xD2, 0x1E, 0x00, 0x80, 0xD2, 0xE5, 0x03, 0x00, 0xAA, 0xE1, 0x03,
+            0x40, 0xF9, 0xE2, 0x23, 0x00, 0x91, 0xE6, 0x03, 0x00, 0x91,
+        ];
+        let info = identify(&make_static_elf(183, true, true, &glibc)).unwrap();
+        assert_eq!(info.name, "glibc");
+
+        let musl = [
+            0x1D, 0x00, 0x80, 0xD2, 0x1E, 0x00, 0x80, 0xD2, 0xE0, 0x03, 0x00, 0x91, 0x01, 0x00,
+            0x00, 0x90, 0x21, 0x00, 0x00, 0x91, 0x1F, 0x6C, 0x7C, 0x92,
+        ];
+        let info = identify(&make_static_elf(183, true, true, &musl)).unwrap();
+        assert_eq!(info.name, "musl");
+    }
+
+    #[test]
+    fn test_mips_little_endian_word_swap() {
+        let glibc_be: [u32; 4] = [0x8FA5_0000, 0x27A6_0004, 0x2401_FFF8, 0x03A1_E824];
+        let mut code = vec![0u8; 8];
+        code.extend(glibc_be.iter().flat_map(|w| w.to_le_bytes()));
+        let info = identify(&make_static_elf(8, false, true, &code)).unwrap();
+        assert_eq!(info.name, "glibc");
+    }
+
+    #[test]
+    fn test_unidentified_reports_unknown() {
+        let elf = make_static_elf(62, true, true, &[0x90; 32]);
+        assert_eq!(identify(&elf), None);
+        let entry = metadata_entry(None);
+        assert!(matches!(entry.value, MetadataValue::String(ref v) if v == "unknown"));
+    }
+}
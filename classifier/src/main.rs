@@ -3,11 +3,17 @@
 //! Command-line tool for identifying processor architectures in binary files.
 
 use clap::{Parser, Subcommand, ValueEnum};
+use isa_classifier::formatter::{
+    format_score, ndjson_error, ndjson_region, path_bytes_hex, score_value, NdjsonSummary,
+};
+use isa_classifier::heuristics::union_extensions;
+use isa_classifier::output::Style;
 use isa_classifier::{
-    detect_multi_isa, detect_payload, CandidatesFormatter, ClassifierOptions, DetectionPayload,
-    HumanFormatter, JsonFormatter, PayloadFormatter, ShortFormatter,
+    detect_multi_isa, detect_payload, CandidatesFormatter, ClassifierOptions, ColorChoice,
+    DetectionPayload, FileFormat, HumanFormatter, Isa, JsonFormatter, NdjsonFormatter, NoteLevel,
+    Output, PayloadFormatter, ShortFormatter,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 /// Universal binary architecture classifier.
@@ -24,6 +30,11 @@ struct Cli {
     /// Input file(s) to analyze (for default classify mode)
     files: Vec<PathBuf>,
 
+    /// Read additional input paths from a file ("-" for stdin), one per line.
+    /// Lines are taken as raw bytes, so non-UTF-8 names are preserved on Unix.
+    #[arg(long, value_name = "LIST")]
+    files_from: Option<PathBuf>,
+
     /// Output format
     #[arg(short, long, default_value = "human")]
     format: OutputFormat,
@@ -52,6 +63,10 @@ struct Cli {
     #[arg(long, default_value = "0.3")]
     min_confidence: f64,
 
+    /// Only consider this ISA in heuristic analysis (repeatable), e.g. `--allow-isa aarch64`
+    #[arg(long = "allow-isa", value_name = "ISA", value_parser = parse_isa)]
+    allow_isas: Vec<Isa>,
+
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
@@ -59,6 +74,75 @@ struct Cli {
     /// Quiet mode (only output essential info)
     #[arg(short, long)]
     quiet: bool,
+
+    /// Identify the C library (glibc, musl, uClibc-ng) linked into static binaries
+    #[arg(long)]
+    detect_libc: bool,
+
+    /// Warn when a file targets a discontinued architecture (IA-64, Alpha, PA-RISC, ...)
+    #[arg(long)]
+    warn_deprecated: bool,
+
+    /// Run the heuristic scorers even on inputs that look like plain text
+    #[arg(long)]
+    no_text_screen: bool,
+
+    /// Also classify ELF images packed back-to-back after the first one
+    #[arg(long)]
+    concatenated_elf: bool,
+
+    /// Check raw inputs for byte-swapped (wrong-endian dumped) firmware even when confident
+    #[arg(long)]
+    detect_byte_swap: bool,
+
+    /// Report PE/ELF/Mach-O hardening flags (PIE, NX, RELRO, canary, CFG); on in thorough mode
+    #[arg(long)]
+    hardening: bool,
+
+    /// Only trust unambiguous magic numbers; analyze heuristically detected formats as raw code
+    #[arg(long)]
+    strict_magic: bool,
+
+    /// Recognize small headerless inputs as CP/M or DOS .COM programs
+    #[arg(long)]
+    detect_com: bool,
+
+    /// Scan the whole ELF/PE/Mach-O file for extensions, not just its executable sections
+    #[arg(long)]
+    scan_whole_file: bool,
+
+    /// Report whether raw code is position-independent or expects a fixed load address
+    #[arg(long)]
+    infer_base: bool,
+
+    /// Print the most likely Rust/LLVM target triple per file instead of the report
+    #[arg(long)]
+    suggest_triple: bool,
+
+    /// Print a structural "same program" fingerprint per file, in hex, instead of the report
+    #[arg(long)]
+    fingerprint: bool,
+
+    /// Exit with code 6 if any primary classification is below this confidence (0.0 - 1.0)
+    #[arg(
+        long,
+        value_name = "CONFIDENCE",
+        value_parser = parse_confidence,
+        conflicts_with = "multi_isa"
+    )]
+    fail_below_confidence: Option<f64>,
+
+    /// Exit with code 7 if any file yields an unknown ISA
+    #[arg(long, conflicts_with = "multi_isa")]
+    fail_on_unknown: bool,
+
+    /// Exit with code 8 if any note at or above this level was produced
+    #[arg(long, value_name = "LEVEL", conflicts_with = "multi_isa")]
+    fail_on_notes: Option<NoteLevelArg>,
+
+    /// Colorize human-readable output (honors NO_COLOR and CLICOLOR_FORCE under auto)
+    #[arg(long, value_name = "WHEN", default_value = "auto", global = true)]
+    color: ColorArg,
 }
 
 #[derive(Subcommand, Debug)]
@@ -167,6 +251,74 @@ enum OutputFormat {
     Json,
     /// Compact single-line output
     Short,
+    /// Newline-delimited JSON, one record per file (or region) as it is produced
+    Ndjson,
+}
+
+/// Note level options for `--fail-on-notes`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum NoteLevelArg {
+    /// Any note
+    Info,
+    /// Warnings and errors
+    Warning,
+    /// Errors only
+    Error,
+}
+
+impl From<NoteLevelArg> for NoteLevel {
+    fn from(level: NoteLevelArg) -> Self {
+        match level {
+            NoteLevelArg::Info => NoteLevel::Info,
+            NoteLevelArg::Warning => NoteLevel::Warning,
+            NoteLevelArg::Error => NoteLevel::Error,
+        }
+    }
+}
+
+/// Color options for `--color`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ColorArg {
+    /// Color when writing to a terminal
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl From<ColorArg> for ColorChoice {
+    fn from(choice: ColorArg) -> Self {
+        match choice {
+            ColorArg::Auto => ColorChoice::Auto,
+            ColorArg::Always => ColorChoice::Always,
+            ColorArg::Never => ColorChoice::Never,
+        }
+    }
+}
+
+/// Gatekeeping failures, ordered by severity.
+///
+/// The discriminant is the process exit code; when several files fail,
+/// the highest code wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PolicyFailure {
+    /// Primary confidence below `--fail-below-confidence`
+    LowConfidence = 6,
+    /// Unknown ISA with `--fail-on-unknown`
+    Unknown = 7,
+    /// Note at or above `--fail-on-notes`
+    Notes = 8,
+}
+
+impl PolicyFailure {
+    fn describe(self) -> &'static str {
+        match self {
+            PolicyFailure::LowConfidence => "confidence below threshold",
+            PolicyFailure::Unknown => "unknown ISA",
+            PolicyFailure::Notes => "notes at or above requested level",
+        }
+    }
 }
 
 /// Analysis mode options.
@@ -182,26 +334,38 @@ enum AnalysisMode {
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
+    let out = Output::new(cli.color.into());
 
     // Handle subcommands
     if let Some(command) = &cli.command {
         #[cfg(feature = "batch")]
         {
             return match command {
-                Commands::Batch(args) => run_batch(args),
+                Commands::Batch(args) => run_batch(args, &out),
             };
         }
         #[cfg(not(feature = "batch"))]
         {
             let _ = command;
-            eprintln!("Error: subcommand not available. Rebuild with --features batch.");
+            out.eprintln("Error: subcommand not available. Rebuild with --features batch.");
             return ExitCode::FAILURE;
         }
     }
 
     // Default mode: classify individual files
-    if cli.files.is_empty() {
-        eprintln!("Error: no input files specified. Use --help for usage.");
+    let mut files = cli.files.clone();
+    if let Some(list) = &cli.files_from {
+        match read_files_from(list) {
+            Ok(paths) => files.extend(paths),
+            Err(e) => {
+                out.eprintln(format_args!("Error reading {}: {}", list.display(), e));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if files.is_empty() {
+        out.eprintln("Error: no input files specified. Use --help for usage.");
         return ExitCode::FAILURE;
     }
 
@@ -214,31 +378,60 @@ fn main() -> ExitCode {
 
     let options = build_options(&cli);
     let mut success = true;
-
-    for path in &cli.files {
-        if cli.multi_isa {
-            match analyze_multi_isa(path, &cli) {
-                Ok(()) => {}
-                Err(e) => {
-                    if !cli.quiet {
-                        eprintln!("Error analyzing {}: {}", path.display(), e);
+    let mut worst: Option<PolicyFailure> = None;
+    let mut offenders: Vec<(PathBuf, PolicyFailure)> = Vec::new();
+
+    if matches!(cli.format, OutputFormat::Ndjson) {
+        success = stream_ndjson(
+            &files,
+            &options,
+            &cli,
+            &mut |line| out.record(line),
+            &mut |path, payload| {
+                for failure in check_policy(payload, &cli) {
+                    worst = worst.max(Some(failure));
+                    offenders.push((path.to_path_buf(), failure));
+                }
+            },
+        );
+    } else {
+        for path in &files {
+            if cli.multi_isa {
+                match analyze_multi_isa(path, &options, &cli, &out) {
+                    Ok(()) => {}
+                    Err(e) => {
+                        if !cli.quiet {
+                            out.eprintln(format_args!("Error analyzing {}: {}", path.display(), e));
+                        }
+                        success = false;
                     }
-                    success = false;
                 }
-            }
-        } else {
-            match analyze_file(path, &options, &cli) {
-                Ok(()) => {}
-                Err(e) => {
-                    if !cli.quiet {
-                        eprintln!("Error analyzing {}: {}", path.display(), e);
+            } else {
+                match analyze_file(path, &options, &cli, &out) {
+                    Ok(payload) => {
+                        for failure in check_policy(&payload, &cli) {
+                            worst = worst.max(Some(failure));
+                            offenders.push((path.to_path_buf(), failure));
+                        }
+                    }
+                    Err(e) => {
+                        if !cli.quiet {
+                            out.eprintln(format_args!("Error analyzing {}: {}", path.display(), e));
+                        }
+                        success = false;
                     }
-                    success = false;
                 }
             }
         }
     }
 
+    if let Some(failure) = worst {
+        for (path, failure) in &offenders {
+            out.eprintln(format_args!("{}: {}", path.display(), failure.describe()));
+        }
+        return ExitCode::from(failure as u8);
+    }
+
     if success {
         ExitCode::SUCCESS
     } else {
@@ -246,12 +439,88 @@ fn main() -> ExitCode {
     }
 }
 
+/// Classify `files` as a stream of NDJSON records.
+///
+/// Every record goes to `emit` as soon as its file is done, followed by one
+/// closing `summary` record. Failures become `error` records rather than
+/// stderr text, so the stream stays the single source of results.
+/// `on_payload` sees each classified payload for the `--fail-*` checks.
+/// Returns `false` if any file failed.
+fn stream_ndjson(
+    files: &[PathBuf],
+    options: &ClassifierOptions,
+    cli: &Cli,
+    emit: &mut dyn FnMut(&str),
+    on_payload: &mut dyn FnMut(&Path, &DetectionPayload),
+) -> bool {
+    let formatter = NdjsonFormatter::new();
+    let mut summary = NdjsonSummary::new();
+    let mut success = true;
+
+    for path in files {
+        let result: Result<(), Box<dyn std::error::Error>> =
+            std::fs::read(path).map_err(Into::into).and_then(|data| {
+                if cli.multi_isa {
+                    let detected = detect_multi_isa(&data, options, cli.window_size);
+                    for region in &detected {
+                        emit(&ndjson_region(path, region));
+                    }
+                    summary.add_regions(&detected);
+                } else {
+                    let payload = detect_payload(&data, &file_options(options, &data))?;
+                    emit(&formatter.format_payload(&payload, path));
+                    summary.add_result(&payload);
+                    on_payload(path, &payload);
+                }
+                Ok(())
+            });
+
+        if let Err(e) = result {
+            emit(&ndjson_error(path, &e.to_string()));
+            summary.add_error();
+            success = false;
+        }
+    }
+
+    emit(&summary.to_line());
+    success
+}
+
+/// Evaluate the `--fail-*` gatekeeping flags against a payload.
+fn check_policy(payload: &DetectionPayload, cli: &Cli) -> Vec<PolicyFailure> {
+    let mut failures = Vec::new();
+
+    if let Some(threshold) = cli.fail_below_confidence {
+        if payload.primary.confidence < threshold {
+            failures.push(PolicyFailure::LowConfidence);
+        }
+    }
+
+    if cli.fail_on_unknown {
+        let unknown_isa = matches!(payload.primary.isa, Isa::Unknown(_));
+        let no_heuristic_answer =
+            payload.format.format == FileFormat::Raw && payload.primary.confidence <= 0.0;
+        if unknown_isa || no_heuristic_answer {
+            failures.push(PolicyFailure::Unknown);
+        }
+    }
+
+    if let Some(level) = cli.fail_on_notes {
+        let level = NoteLevel::from(level);
+        if payload.notes.iter().any(|note| note.level >= level) {
+            failures.push(PolicyFailure::Notes);
+        }
+    }
+
+    failures
+}
+
 // ---------------------------------------------------------------------------
 // Batch subcommand
 // ---------------------------------------------------------------------------
 
 #[cfg(feature = "batch")]
-fn run_batch(args: &BatchArgs) -> ExitCode {
+fn run_batch(args: &BatchArgs, out: &Output) -> ExitCode {
     use isa_classifier::batch::{
         pipeline::{run_pipeline, PipelineConfig},
         routing::RoutingConfig,
@@ -270,16 +539,16 @@ fn run_batch(args: &BatchArgs) -> ExitCode {
 
     // Validate input directory
     if !args.input.is_dir() {
-        eprintln!(
+        out.eprintln(format_args!(
             "Error: input path is not a directory: {}",
             args.input.display()
-        );
+        ));
         return ExitCode::FAILURE;
     }
 
     // Create output directory if needed
     if let Err(e) = std::fs::create_dir_all(&args.output) {
-        eprintln!("Error creating output directory: {}", e);
+        out.eprintln(format_args!("Error creating output directory: {}", e));
         return ExitCode::FAILURE;
     }
 
@@ -319,6 +588,7 @@ fn run_batch(args: &BatchArgs) -> ExitCode {
         skip_existing: args.skip_existing,
         deep_scan: args.deep_scan,
         detect_extensions,
+        source_date: isa_classifier::batch::clock::source_date_epoch(),
     };
 
     let stats = Arc::new(PipelineStats::new());
@@ -329,13 +599,13 @@ fn run_batch(args: &BatchArgs) -> ExitCode {
     let _ = ctrlc_handler(shutdown_ctrlc);
 
     if !args.quiet {
-        eprintln!("ISA Harvester Batch Classifier");
-        eprintln!("  Run ID:   {}", run_id);
-        eprintln!("  Input:    {}", args.input.display());
-        eprintln!("  Output:   {}", args.output.display());
-        eprintln!("  Workers:  {}", args.jobs);
-        eprintln!("  Prefix:   {}", args.prefix);
-        eprintln!();
+        out.eprintln("ISA Harvester Batch Classifier");
+        out.eprintln(format_args!("  Run ID:   {}", run_id));
+        out.eprintln(format_args!("  Input:    {}", args.input.display()));
+        out.eprintln(format_args!("  Output:   {}", args.output.display()));
+        out.eprintln(format_args!("  Workers:  {}", args.jobs));
+        out.eprintln(format_args!("  Prefix:   {}", args.prefix));
+        out.eprintln("");
     }
 
     // Start TUI or simple progress in a separate thread
@@ -392,23 +662,29 @@ fn run_batch(args: &BatchArgs) -> ExitCode {
 
             if !args.quiet {
                 let m = &result.run_manifest;
-                eprintln!();
-                eprintln!("Batch complete:");
-                eprintln!("  Status:     {:?}", m.status);
-                eprintln!("  Processed:  {}", m.counts.processed);
-                eprintln!("  Classified: {}", m.counts.classified);
-                eprintln!("  Ambiguous:  {}", m.counts.ambiguous);
-                eprintln!("  Duplicates: {}", m.counts.duplicates);
-                eprintln!("  Errors:     {}", m.counts.errors);
-                eprintln!("  Duration:   {:.1}s", m.timing.duration_seconds);
-                eprintln!("  Throughput: {:.1} files/sec", m.timing.files_per_second);
-                eprintln!();
-                eprintln!("Staging directory: {}", args.output.display());
-                eprintln!(
+                out.eprintln("");
+                out.eprintln("Batch complete:");
+                out.eprintln(format_args!("  Status:     {:?}", m.status));
+                out.eprintln(format_args!("  Processed:  {}", m.counts.processed));
+                out.eprintln(format_args!("  Classified: {}", m.counts.classified));
+                out.eprintln(format_args!("  Ambiguous:  {}", m.counts.ambiguous));
+                out.eprintln(format_args!("  Duplicates: {}", m.counts.duplicates));
+                out.eprintln(format_args!("  Errors:     {}", m.counts.errors));
+                out.eprintln(format_args!(
+                    "  Duration:   {:.1}s",
+                    m.timing.duration_seconds
+                ));
+                out.eprintln(format_args!(
+                    "  Throughput: {:.1} files/sec",
+                    m.timing.files_per_second
+                ));
+                out.eprintln("");
+                out.eprintln(format_args!("Staging directory: {}", args.output.display()));
+                out.eprintln(format_args!(
                     "Upload with: rclone sync {}/ hetzner:bucket/{}/",
                     args.output.display(),
                     args.prefix
-                );
+                ));
             }
 
             ExitCode::SUCCESS
@@ -417,7 +693,7 @@ fn run_batch(args: &BatchArgs) -> ExitCode {
             if let Some(Some(handle)) = tui_handle {
                 let _ = handle.join();
             }
-            eprintln!("Pipeline error: {}", e);
+            out.eprintln(format_args!("Pipeline error: {}", e));
             ExitCode::FAILURE
         }
     }
@@ -441,7 +717,7 @@ fn ctrlc_handler(shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>) {
 
 /// Build classifier options from CLI args.
 fn build_options(cli: &Cli) -> ClassifierOptions {
-    match cli.mode {
+    let mut opts = match cli.mode {
         AnalysisMode::Normal => {
             let mut opts = ClassifierOptions::new();
             opts.min_confidence = cli.min_confidence;
@@ -460,41 +736,87 @@ fn build_options(cli: &Cli) -> ClassifierOptions {
             opts.detect_extensions = true;
             opts
         }
-    }
+    };
+
+    // Flags that apply the same way in every mode
+    opts.detect_libc = cli.detect_libc || cli.suggest_triple;
+    opts.warn_deprecated = cli.warn_deprecated;
+    opts.screen_text = !cli.no_text_screen;
+    opts.scan_concatenated_elf = cli.concatenated_elf;
+    opts.detect_byte_swap = cli.detect_byte_swap;
+    opts.report_registers = cli.verbose;
+    opts.report_hardening |= cli.hardening;
+    opts.collect_sections |= cli.fingerprint;
+    opts.infer_addressing = cli.infer_base;
+    opts.strict_magic = cli.strict_magic;
+    opts.detect_com = cli.detect_com;
+    opts.scan_executable_only = !cli.scan_whole_file;
+    opts.allowed_isas = cli.allow_isas.clone();
+    opts
 }
 
 /// Analyze a single file and output results using the appropriate formatter.
 fn analyze_file(
-    path: &PathBuf,
+    path: &Path,
     options: &ClassifierOptions,
     cli: &Cli,
-) -> Result<(), Box<dyn std::error::Error>> {
+    out: &Output,
+) -> Result<DetectionPayload, Box<dyn std::error::Error>> {
     let data = std::fs::read(path)?;
-    let payload = detect_payload(&data, options)?;
-    let output = format_output(&payload, path, cli);
-    print!("{}", output);
+    let payload = detect_payload(&data, &file_options(options, &data))?;
+    if cli.suggest_triple {
+        let triples = payload.suggest_target_triples();
+        let triple = triples.first().map_or("(none)", String::as_str);
+        out.println(format_args!("{}: {}", path.display(), triple));
+        return Ok(payload);
+    }
+    if cli.fingerprint {
+        let fingerprint = payload.structural_fingerprint(&data);
+        out.println(format_args!("{}: {}", path.display(), fingerprint.to_hex()));
+        return Ok(payload);
+    }
+    let output = format_output(&payload, path, cli, out.stdout_color());
+    out.print(output);
 
     if cli.candidates && !payload.candidates.is_empty() {
         let candidates_formatter = CandidatesFormatter::new();
-        print!("{}", candidates_formatter.format_payload(&payload, path));
+        out.print(candidates_formatter.format_payload(&payload, path));
     }
 
-    Ok(())
+    Ok(payload)
+}
+
+/// `options` for the whole contents of a file, `data`.
+fn file_options(options: &ClassifierOptions, data: &[u8]) -> ClassifierOptions {
+    ClassifierOptions {
+        input_len: Some(data.len() as u64),
+        ..options.clone()
+    }
 }
 
 /// Analyze a file for multiple ISAs using windowed detection.
-fn analyze_multi_isa(path: &PathBuf, cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+fn analyze_multi_isa(
+    path: &Path,
+    options: &ClassifierOptions,
+    cli: &Cli,
+    out: &Output,
+) -> Result<(), Box<dyn std::error::Error>> {
     let data = std::fs::read(path)?;
-    let detected = detect_multi_isa(&data, cli.window_size);
+    let detected = detect_multi_isa(&data, options, cli.window_size);
 
     match cli.format {
         OutputFormat::Json => {
             #[derive(serde::Serialize)]
             struct MultiIsaOutput {
                 file: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                file_bytes: Option<String>,
                 multi_isa: bool,
                 detected_isas: Vec<IsaEntry>,
                 primary_isa: Option<String>,
+                /// Union of the per-ISA extensions
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                extensions: Vec<String>,
             }
 
             #[derive(serde::Serialize)]
@@ -505,6 +827,8 @@ fn analyze_multi_isa(path: &PathBuf, cli: &Cli) -> Result<(), Box<dyn std::error
                 window_count: usize,
                 total_bytes: usize,
                 avg_score: f64,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                extensions: Vec<String>,
             }
 
             let primary = detected.first().map(|d| d.isa.to_string());
@@ -516,47 +840,120 @@ fn analyze_multi_isa(path: &PathBuf, cli: &Cli) -> Result<(), Box<dyn std::error
                     endianness: d.endianness.to_string(),
                     window_count: d.window_count,
                     total_bytes: d.total_bytes,
-                    avg_score: d.avg_score,
+                    avg_score: score_value(d.avg_score),
+                    extensions: d.extensions.iter().map(|e| e.name.clone()).collect(),
                 })
                 .collect();
 
             let output = MultiIsaOutput {
                 file: path.display().to_string(),
+                file_bytes: path_bytes_hex(path),
                 multi_isa: entries.len() > 1,
                 detected_isas: entries,
                 primary_isa: primary,
+                extensions: union_extensions(&detected)
+                    .into_iter()
+                    .map(|e| e.name)
+                    .collect(),
             };
 
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            out.println(serde_json::to_string_pretty(&output)?);
         }
         OutputFormat::Human => {
-            println!("File: {}", path.display());
+            out.println(format_args!("File: {}", path.display()));
             if detected.is_empty() {
-                println!("  No ISAs detected");
+                out.println("  No ISAs detected");
             } else {
-                println!("  Detected {} ISA(s):", detected.len());
+                out.println(format_args!("  Detected {} ISA(s):", detected.len()));
                 for d in &detected {
-                    println!(
-                        "    {:<12} {:>3} windows, {:>6} bytes, avg_score {:.1}",
-                        d.isa.to_string(),
+                    out.println(format_args!(
+                        "    {} {:>3} windows, {:>6} bytes, avg_score {}",
+                        out.stdout_color()
+                            .paint(&format!("{:<12}", d.isa.to_string()), Style::Bold),
                         d.window_count,
                         d.total_bytes,
-                        d.avg_score,
-                    );
+                        format_score(d.avg_score),
+                    ));
+                    if !d.extensions.is_empty() {
+                        let names: Vec<&str> =
+                            d.extensions.iter().map(|e| e.name.as_str()).collect();
+                        out.println(format_args!("      extensions: {}", names.join(", ")));
+                    }
                 }
             }
         }
         OutputFormat::Short => {
             let isas: Vec<String> = detected.iter().map(|d| d.isa.to_string()).collect();
-            println!("{}: {}", path.display(), isas.join("+"));
+            out.println(format_args!("{}: {}", path.display(), isas.join("+")));
+        }
+        OutputFormat::Ndjson => {
+            for d in &detected {
+                out.record(&ndjson_region(path, d));
+            }
         }
     }
 
     Ok(())
 }
 
+/// Read a `--files-from` list ("-" for stdin).
+fn read_files_from(list: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let bytes = if list.as_os_str() == "-" {
+        let mut buf = Vec::new();
+        std::io::Read::read_to_end(&mut std::io::stdin(), &mut buf)?;
+        buf
+    } else {
+        std::fs::read(list)?
+    };
+    Ok(parse_path_list(&bytes))
+}
+
+/// Parse an ISA name as printed in reports (`x86_64`, `aarch64`, ...),
+/// ignoring case. Only ISAs the heuristics can identify are accepted.
+fn parse_isa(name: &str) -> Result<Isa, String> {
+    isa_classifier::heuristics::SUPPORTED_ARCHITECTURES
+        .iter()
+        .map(|&(isa, _)| isa)
+        .find(|isa| isa.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown or unsupported ISA `{name}`"))
+}
+
+/// Parse a confidence threshold, which must lie in `0.0..=1.0`.
+fn parse_confidence(value: &str) -> Result<f64, String> {
+    let confidence: f64 = value
+        .parse()
+        .map_err(|_| format!("`{value}` is not a number"))?;
+    if (0.0..=1.0).contains(&confidence) {
+        Ok(confidence)
+    } else {
+        Err(format!("`{value}` is outside 0.0 - 1.0"))
+    }
+}
+
+/// Split a newline-separated path list without requiring UTF-8.
+fn parse_path_list(bytes: &[u8]) -> Vec<PathBuf> {
+    bytes
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
 /// Format the payload using the appropriate formatter.
-fn format_output(payload: &DetectionPayload, path: &PathBuf, cli: &Cli) -> String {
+///
+/// `color` only affects human output; JSON, NDJSON and short output stay plain.
+fn format_output(payload: &DetectionPayload, path: &Path, cli: &Cli, color: ColorChoice) -> String {
     match cli.format {
         OutputFormat::Human => {
             let formatter = if cli.quiet {
@@ -566,7 +963,7 @@ fn format_output(payload: &DetectionPayload, path: &PathBuf, cli: &Cli) -> Strin
             } else {
                 HumanFormatter::new()
             };
-            formatter.format_payload(payload, path)
+            formatter.with_color(color).format_payload(payload, path)
         }
         OutputFormat::Json => {
             let formatter = JsonFormatter::new();
@@ -576,12 +973,17 @@ fn format_output(payload: &DetectionPayload, path: &PathBuf, cli: &Cli) -> Strin
             let formatter = ShortFormatter::new();
             formatter.format_payload(payload, path)
         }
+        OutputFormat::Ndjson => {
+            let formatter = NdjsonFormatter::new();
+            formatter.format_payload(payload, path)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use isa_classifier::{Endianness, FormatDetection, IsaClassification, Note};
 
     #[test]
     fn test_args_parsing() {
@@ -603,6 +1005,50 @@ mod tests {
         assert!(matches!(cli.format, OutputFormat::Json));
     }
 
+    #[test]
+    fn test_color_flag() {
+        let cli = Cli::try_parse_from(["isa-classify", "test.bin"]).unwrap();
+        assert_eq!(ColorChoice::from(cli.color), ColorChoice::Auto);
+        let cli = Cli::try_parse_from(["isa-classify", "--color", "never", "test.bin"]).unwrap();
+        assert_eq!(ColorChoice::from(cli.color), ColorChoice::Never);
+        assert!(Cli::try_parse_from(["isa-classify", "--color", "sometimes", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_suggest_triple_enables_libc_detection() {
+        let cli = Cli::try_parse_from(["isa-classify", "--suggest-triple", "x"]).unwrap();
+        assert!(cli.suggest_triple);
+        assert!(build_options(&cli).detect_libc);
+        let cli = Cli::try_parse_from(["isa-classify", "x"]).unwrap();
+        assert!(!build_options(&cli).detect_libc);
+    }
+
+    #[test]
+    fn test_fingerprint_collects_sections() {
+        let cli =
+            Cli::try_parse_from(["isa-classify", "--fingerprint", "-m", "fast", "x"]).unwrap();
+        assert!(cli.fingerprint);
+        assert!(build_options(&cli).collect_sections);
+    }
+
+    #[test]
+    fn test_machine_formats_stay_plain_with_color() {
+        let mut payload = payload(Isa::Arm, 0.4);
+        payload.notes.push(Note::warning("weak evidence"));
+        let path = Path::new("test.bin");
+
+        for format in ["json", "ndjson", "short"] {
+            let cli = Cli::try_parse_from(["isa-classify", "--color", "always", "-f", format, "x"])
+                .unwrap();
+            let output = format_output(&payload, path, &cli, ColorChoice::Always);
+            assert!(!output.contains('\x1b'), "{format}: {output}");
+        }
+
+        let cli = Cli::try_parse_from(["isa-classify", "--color", "always", "x"]).unwrap();
+        let human = format_output(&payload, path, &cli, ColorChoice::Always);
+        assert!(human.contains("\x1b[33m[warn]\x1b[0m weak evidence"));
+    }
+
     #[test]
     fn test_multi_isa_flag() {
         let cli = Cli::try_parse_from(["isa-classify", "--multi-isa", "test.bin"]).unwrap();
@@ -610,6 +1056,22 @@ mod tests {
         assert_eq!(cli.window_size, 1024);
     }
 
+    #[test]
+    fn test_allow_isa() {
+        let cli = Cli::try_parse_from([
+            "isa-classify",
+            "--allow-isa",
+            "AArch64",
+            "--allow-isa",
+            "x86_64",
+            "test.bin",
+        ])
+        .unwrap();
+        assert_eq!(cli.allow_isas, vec![Isa::AArch64, Isa::X86_64]);
+        assert_eq!(build_options(&cli).allowed_isas, cli.allow_isas);
+        assert!(Cli::try_parse_from(["isa-classify", "--allow-isa", "z80000", "x"]).is_err());
+    }
+
     #[test]
     fn test_window_size() {
         let cli = Cli::try_parse_from([
@@ -622,4 +1084,190 @@ mod tests {
         .unwrap();
         assert_eq!(cli.window_size, 2048);
     }
+
+    fn payload(isa: Isa, confidence: f64) -> DetectionPayload {
+        let primary = IsaClassification::from_heuristics(isa, 32, Endianness::Little, confidence);
+        DetectionPayload::new(FormatDetection::raw(), primary)
+    }
+
+    #[test]
+    fn test_fail_flags_parsing() {
+        let cli = Cli::try_parse_from([
+            "isa-classify",
+            "--fail-below-confidence",
+            "0.9",
+            "--fail-on-unknown",
+            "--fail-on-notes",
+            "warning",
+            "test.bin",
+        ])
+        .unwrap();
+        assert_eq!(cli.fail_below_confidence, Some(0.9));
+        assert!(cli.fail_on_unknown);
+        assert!(matches!(cli.fail_on_notes, Some(NoteLevelArg::Warning)));
+    }
+
+    #[test]
+    fn test_fail_flags_rejected() {
+        for value in ["1.5", "-0.1", "NaN", "high"] {
+            assert!(
+                Cli::try_parse_from(["isa-classify", "--fail-below-confidence", value, "t"])
+                    .is_err(),
+                "{value}"
+            );
+        }
+        // Windowed results carry no primary classification or notes to check
+        for flag in [
+            &["--fail-below-confidence", "0.5"][..],
+            &["--fail-on-unknown"],
+            &["--fail-on-notes", "info"],
+        ] {
+            let args = ["isa-classify", "--multi-isa"]
+                .iter()
+                .chain(flag)
+                .chain(&["t"]);
+            assert!(Cli::try_parse_from(args).is_err(), "{flag:?}");
+        }
+    }
+
+    #[test]
+    fn test_policy_defaults_never_fail() {
+        let cli = Cli::try_parse_from(["isa-classify", "test.bin"]).unwrap();
+        let payload = payload(Isa::Unknown(0), 0.0).with_note(Note::error("boom"));
+        assert!(check_policy(&payload, &cli).is_empty());
+    }
+
+    #[test]
+    fn test_policy_low_confidence() {
+        let cli =
+            Cli::try_parse_from(["isa-classify", "--fail-below-confidence", "0.8", "t"]).unwrap();
+        assert_eq!(
+            check_policy(&payload(Isa::Arm, 0.5), &cli),
+            vec![PolicyFailure::LowConfidence]
+        );
+        assert!(check_policy(&payload(Isa::Arm, 0.85), &cli).is_empty());
+    }
+
+    #[test]
+    fn test_policy_unknown() {
+        let cli = Cli::try_parse_from(["isa-classify", "--fail-on-unknown", "t"]).unwrap();
+        assert_eq!(
+            check_policy(&payload(Isa::Unknown(0x1234), 0.9), &cli),
+            vec![PolicyFailure::Unknown]
+        );
+        assert_eq!(
+            check_policy(&payload(Isa::Arm, 0.0), &cli),
+            vec![PolicyFailure::Unknown]
+        );
+        assert!(check_policy(&payload(Isa::Arm, 0.6), &cli).is_empty());
+    }
+
+    #[test]
+    fn test_policy_notes_level() {
+        let cli = Cli::try_parse_from(["isa-classify", "--fail-on-notes", "warning", "t"]).unwrap();
+        let info = payload(Isa::Arm, 0.9).with_note(Note::info("fine"));
+        assert!(check_policy(&info, &cli).is_empty());
+        let warning = payload(Isa::Arm, 0.9).with_note(Note::warning("hmm"));
+        assert_eq!(check_policy(&warning, &cli), vec![PolicyFailure::Notes]);
+    }
+
+    #[test]
+    fn test_policy_worst_code_wins() {
+        let cli = Cli::try_parse_from([
+            "isa-classify",
+            "--fail-below-confidence",
+            "0.9",
+            "--fail-on-notes",
+            "info",
+            "t",
+        ])
+        .unwrap();
+        let failures = check_policy(&payload(Isa::Arm, 0.5).with_note(Note::info("x")), &cli);
+        assert_eq!(failures.iter().max(), Some(&PolicyFailure::Notes));
+        assert_eq!(PolicyFailure::Notes as u8, 8);
+        assert_eq!(PolicyFailure::Unknown as u8, 7);
+        assert_eq!(PolicyFailure::LowConfidence as u8, 6);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_files_from_preserves_raw_bytes() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("list");
+        std::fs::write(&list, b"/fw/a.bin\n/fw/\xff\xfe.bin\n\n").unwrap();
+
+        let paths = read_files_from(&list).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], PathBuf::from("/fw/a.bin"));
+        assert_eq!(paths[1].as_os_str().as_bytes(), b"/fw/\xff\xfe.bin");
+        assert_eq!(
+            path_bytes_hex(&paths[1]).as_deref(),
+            Some("2f66772ffffe2e62696e")
+        );
+        assert_eq!(path_bytes_hex(&paths[0]), None);
+    }
+
+    #[test]
+    fn test_ndjson_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = Vec::new();
+        for (name, byte) in [("a.bin", 0x90u8), ("b.bin", 0x00), ("c.bin", 0x1F)] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, vec![byte; 512]).unwrap();
+            files.push(path);
+        }
+
+        let cli = Cli::try_parse_from(["isa-classify", "-f", "ndjson", "x"]).unwrap();
+        let mut lines = Vec::new();
+        let mut payloads = 0;
+        let success = stream_ndjson(
+            &files,
+            &build_options(&cli),
+            &cli,
+            &mut |line| lines.push(line.to_string()),
+            &mut |_, _| payloads += 1,
+        );
+        assert!(success);
+        assert_eq!(payloads, 3);
+        assert_eq!(lines.len(), 4);
+
+        let records: Vec<serde_json::Value> = lines
+            .iter()
+            .map(|line| {
+                assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+                serde_json::from_str(line).unwrap()
+            })
+            .collect();
+        for (record, path) in records.iter().zip(&files) {
+            assert_eq!(record["type"], "file_result");
+            assert_eq!(record["file"], path.display().to_string());
+        }
+        let summary = &records[3];
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["files"], 3);
+        assert_eq!(summary["results"], 3);
+        assert_eq!(summary["errors"], 0);
+    }
+
+    #[test]
+    fn test_ndjson_error_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec![dir.path().join("missing.bin")];
+        let cli = Cli::try_parse_from(["isa-classify", "-f", "ndjson", "x"]).unwrap();
+        let mut lines = Vec::new();
+        let success = stream_ndjson(
+            &files,
+            &build_options(&cli),
+            &cli,
+            &mut |line| lines.push(line.to_string()),
+            &mut |_, _| {},
+        );
+        assert!(!success);
+        let error: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(error["type"], "error");
+        let summary: serde_json::Value = serde_json::from_str(&lines[1]).unwrap();
+        assert_eq!(summary["errors"], 1);
+    }
 }
@@ -0,0 +1,556 @@
+//! Operating-system fingerprint.
+//!
+//! Format parsers report OS hints in different places: ELF has `EI_OSABI`,
+//! the GNU ABI tag note and the `PT_INTERP` path, PE has the optional
+//! header subsystem, Mach-O has `LC_BUILD_VERSION`, Go binaries embed their
+//! `GOOS`, WebAssembly modules import WASI, and some containers only exist
+//! on one platform. [`resolve`] combines them into one [`OsFingerprint`].
+//!
+//! Signals are weighed in a fixed order, highest precedence first:
+//!
+//! | Rank | Signal | Example |
+//! |------|--------|---------|
+//! | 1 | Platform record: Mach-O `LC_BUILD_VERSION`/`LC_VERSION_MIN_*`, PE subsystem | `macOS 14.0`, `EFI application` |
+//! | 2 | ELF GNU ABI tag note | `Linux 3.2.0` |
+//! | 3 | ELF `EI_OSABI` naming an OS | `FreeBSD` |
+//! | 4 | ELF interpreter path | `/system/bin/linker64` |
+//! | 5 | Go build info `GOOS` | `GOOS=linux` |
+//! | 6 | WASI imports in a WebAssembly module | `wasi_snapshot_preview1` |
+//! | 7 | Container format's default platform | XBE, DEX, XCOFF, PE |
+//! | 8 | Bare-metal target environment ([`crate::target_env`]) | Intel HEX image |
+//!
+//! The highest-ranked signal picks the family. Signals naming the same
+//! family, or a family it refines (WASI refines WebAssembly), join its
+//! evidence; signals naming another family are kept as conflicts. The
+//! container format only votes when no signal above it names an OS: an
+//! iOS Mach-O, an EFI PE or a WASI module is not in conflict with the
+//! platform its format usually targets. Signals naming an OS outside [`OsFamily`] (HP-UX, IRIX, ...)
+//! resolve to [`OsFamily::Unknown`] only when nothing else names an OS. The
+//! version hint comes from the highest-ranked agreeing signal that has one.
+//!
+//! [`resolve`] is pure; [`signals`] gathers the inputs from a payload.
+
+use memchr::memmem;
+
+use crate::formats::pe::{self, subsystem};
+use crate::formats::{elf, macho, read_u32};
+use crate::target_env::{self, TargetEnv};
+use crate::types::{DetectionPayload, FileFormat, MetadataKey, OsFamily, OsFingerprint};
+
+/// Marker preceding the target OS in Go build info (`go version -m`).
+const GO_GOOS_MARKER: &[u8] = b"build\tGOOS=";
+
+/// Import module names of the WASI preview 1 and preview 2 interfaces.
+const WASI_MODULES: [&[u8]; 3] = [b"wasi_snapshot_preview1", b"wasi_unstable", b"wasi:"];
+
+/// The OS signals found in one binary. Every field is optional, so
+/// synthetic combinations can be resolved directly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OsSignals {
+    /// Mach-O `LC_BUILD_VERSION` platform and minimum OS version
+    pub macho_platform: Option<(u32, String)>,
+    /// PE optional header subsystem and subsystem version (major, minor)
+    pub pe_subsystem: Option<(u16, u16, u16)>,
+    /// GNU ABI tag OS name and minimum kernel version
+    pub abi_tag: Option<(String, String)>,
+    /// ELF `EI_OSABI` name, as reported in the `osabi` metadata
+    pub elf_osabi: Option<String>,
+    /// ELF `PT_INTERP` path
+    pub interpreter: Option<String>,
+    /// `GOOS` from Go build info
+    pub go_goos: Option<String>,
+    /// A WebAssembly module importing a WASI interface
+    pub wasi: bool,
+    /// Detected container format
+    pub format: Option<FileFormat>,
+    /// Bare-metal versus OS-hosted triage
+    pub target_env: Option<TargetEnv>,
+}
+
+/// One signal mapped to the family it names.
+struct Vote {
+    family: OsFamily,
+    version: Option<String>,
+    evidence: String,
+}
+
+impl Vote {
+    fn new(family: OsFamily, evidence: String) -> Self {
+        Self {
+            family,
+            version: None,
+            evidence,
+        }
+    }
+
+    fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version.filter(|v| !v.is_empty());
+        self
+    }
+}
+
+/// Gather the OS signals for `payload`, classified from `data`.
+pub fn signals(data: &[u8], payload: &DetectionPayload) -> OsSignals {
+    let format = payload.format.format;
+    let custom = |key: &str| {
+        payload
+            .metadata
+            .iter()
+            .find(|m| matches!(&m.key, MetadataKey::Custom(k) if k == key))
+            .map(|m| m.value.to_string())
+    };
+
+    let mut signals = OsSignals {
+        format: Some(format),
+        target_env: target_env::identify(data, format, payload.primary.isa),
+        go_goos: go_goos(data),
+        ..OsSignals::default()
+    };
+    match format {
+        FileFormat::Elf if data.len() > 5 => {
+            signals.elf_osabi = custom("osabi");
+            signals.abi_tag = custom("abi_tag_os").zip(custom("min_kernel_version"));
+            signals.interpreter = elf::interpreter(data, data[4] == 2, data[5] == 1);
+        }
+        FileFormat::Pe => {
+            signals.pe_subsystem = read_u32(data, 0x3C, true)
+                .ok()
+                .and_then(|pe_offset| pe::subsystem(data, pe_offset));
+        }
+        FileFormat::MachO => {
+            let layout = match read_u32(data, 0, false) {
+                Ok(0xFEED_FACE) => Some((32, false)),
+                Ok(0xCEFA_EDFE) => Some((32, true)),
+                Ok(0xFEED_FACF) => Some((64, false)),
+                Ok(0xCFFA_EDFE) => Some((64, true)),
+                _ => None,
+            };
+            signals.macho_platform =
+                layout.and_then(|(bits, le)| macho::build_version(data, bits, le));
+        }
+        FileFormat::Wasm => {
+            signals.wasi = WASI_MODULES
+                .iter()
+                .any(|module| memmem::find(data, module).is_some());
+        }
+        _ => {}
+    }
+    signals
+}
+
+/// `GOOS` from the build settings Go 1.18+ embeds in every binary.
+fn go_goos(data: &[u8]) -> Option<String> {
+    let start = memmem::find(data, GO_GOOS_MARKER)? + GO_GOOS_MARKER.len();
+    let value = &data[start..];
+    let end = value
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(value.len());
+    let goos = std::str::from_utf8(&value[..end.min(32)]).ok()?;
+    (!goos.is_empty()).then(|| goos.to_string())
+}
+
+/// Resolve `signals` into one fingerprint, or `None` when no signal names
+/// an OS. See the module documentation for the precedence.
+pub fn resolve(signals: &OsSignals) -> Option<OsFingerprint> {
+    let mut votes: Vec<Vote> = [
+        signals.macho_platform.as_ref().and_then(macho_vote),
+        signals.pe_subsystem.and_then(pe_vote),
+        signals.abi_tag.as_ref().map(abi_tag_vote),
+        signals.elf_osabi.as_deref().and_then(osabi_vote),
+        signals.interpreter.as_deref().and_then(interpreter_vote),
+        signals.go_goos.as_deref().map(goos_vote),
+        signals
+            .wasi
+            .then(|| Vote::new(OsFamily::Wasi, "WASI imports".to_string())),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    // The format's default platform is a fallback, not a competing claim
+    if votes.iter().all(|vote| vote.family == OsFamily::Unknown) {
+        votes.extend(signals.format.and_then(format_vote));
+    }
+    if signals.target_env == Some(TargetEnv::BareMetal) {
+        votes.push(Vote::new(
+            OsFamily::BareMetal,
+            format!("target environment: {}", TargetEnv::BareMetal.as_str()),
+        ));
+    }
+
+    let family = votes
+        .iter()
+        .map(|vote| &vote.family)
+        .find(|family| **family != OsFamily::Unknown)
+        .or_else(|| votes.first().map(|vote| &vote.family))?
+        .clone();
+    // A lower-ranked vote that only narrows the family down still names it
+    let family = votes
+        .iter()
+        .map(|vote| &vote.family)
+        .find(|specific| refines(specific, &family))
+        .cloned()
+        .unwrap_or(family);
+
+    let mut fingerprint = OsFingerprint {
+        family,
+        version_hint: None,
+        evidence: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    for vote in votes {
+        if vote.family == fingerprint.family || refines(&fingerprint.family, &vote.family) {
+            if fingerprint.version_hint.is_none() {
+                fingerprint.version_hint = vote.version;
+            }
+            fingerprint.evidence.push(vote.evidence);
+        } else if vote.family == OsFamily::Unknown {
+            fingerprint.evidence.push(vote.evidence);
+        } else {
+            fingerprint
+                .conflicts
+                .push(format!("{} suggests {}", vote.evidence, vote.family));
+        }
+    }
+    Some(fingerprint)
+}
+
+/// Whether `specific` is a more precise name for `general`: a WASI module
+/// is still a WebAssembly module.
+fn refines(specific: &OsFamily, general: &OsFamily) -> bool {
+    matches!((specific, general), (OsFamily::Wasi, OsFamily::Wasm))
+}
+
+fn macho_vote((platform, version): &(u32, String)) -> Option<Vote> {
+    let name = macho::platform_name(*platform)?;
+    let family = match *platform {
+        macho::platform::MACOS
+        | macho::platform::BRIDGEOS
+        | macho::platform::MACCATALYST
+        | macho::platform::DRIVERKIT => OsFamily::MacOs,
+        _ => OsFamily::Ios,
+    };
+    Some(
+        Vote::new(family, format!("Mach-O platform: {name} {version}"))
+            .with_version(Some(version.clone())),
+    )
+}
+
+fn pe_vote((value, major, minor): (u16, u16, u16)) -> Option<Vote> {
+    let (family, name) = match value {
+        subsystem::NATIVE => (OsFamily::Windows, "native"),
+        subsystem::WINDOWS_GUI => (OsFamily::Windows, "Windows GUI"),
+        subsystem::WINDOWS_CUI => (OsFamily::Windows, "Windows console"),
+        subsystem::POSIX_CUI => (OsFamily::Windows, "POSIX console"),
+        subsystem::NATIVE_WINDOWS => (OsFamily::Windows, "native Win9x driver"),
+        subsystem::WINDOWS_CE_GUI => (OsFamily::Windows, "Windows CE GUI"),
+        subsystem::WINDOWS_BOOT_APPLICATION => (OsFamily::Windows, "Windows boot application"),
+        subsystem::EFI_APPLICATION => (OsFamily::Uefi, "EFI application"),
+        subsystem::EFI_BOOT_SERVICE_DRIVER => (OsFamily::Uefi, "EFI boot service driver"),
+        subsystem::EFI_RUNTIME_DRIVER => (OsFamily::Uefi, "EFI runtime driver"),
+        subsystem::EFI_ROM => (OsFamily::Uefi, "EFI ROM"),
+        subsystem::XBOX => (OsFamily::GameConsole("Xbox".to_string()), "Xbox"),
+        subsystem::OS2_CUI => (OsFamily::Unknown, "OS/2 console"),
+        _ => return None,
+    };
+    let version = (family == OsFamily::Windows && major != 0).then(|| format!("{major}.{minor}"));
+    Some(Vote::new(family, format!("PE subsystem: {name}")).with_version(version))
+}
+
+fn abi_tag_vote((os, kernel): &(String, String)) -> Vote {
+    let family = match os.as_str() {
+        "Linux" => OsFamily::Linux,
+        "Solaris" => OsFamily::Solaris,
+        "FreeBSD" => OsFamily::FreeBsd,
+        "NetBSD" => OsFamily::NetBsd,
+        _ => OsFamily::Unknown,
+    };
+    Vote::new(family, format!("GNU ABI tag: {os} {kernel}")).with_version(Some(kernel.clone()))
+}
+
+fn osabi_vote(name: &str) -> Option<Vote> {
+    let family = match name {
+        "GNU/Linux" => OsFamily::Linux,
+        "FreeBSD" => OsFamily::FreeBsd,
+        "OpenBSD" => OsFamily::OpenBsd,
+        "NetBSD" => OsFamily::NetBsd,
+        "Solaris" => OsFamily::Solaris,
+        "AIX" => OsFamily::Aix,
+        "Standalone" => OsFamily::BareMetal,
+        // Generic System V and processor-specific ABIs name no OS
+        "UNIX System V" | "ARM EABI" | "ARM" | "C6000 ELFABI" => return None,
+        _ if name.starts_with("unknown") => return None,
+        _ => OsFamily::Unknown,
+    };
+    Some(Vote::new(family, format!("ELF OS ABI: {name}")))
+}
+
+fn interpreter_vote(path: &str) -> Option<Vote> {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let family = if path.starts_with("/system/bin/linker") {
+        OsFamily::Android
+    } else if file.starts_with("ld-linux")
+        || file.starts_with("ld-musl")
+        || file == "ld64.so.1"
+        || file == "ld64.so.2"
+        || path == "/lib/ld.so.1"
+        || file.starts_with("ld-uClibc")
+    {
+        OsFamily::Linux
+    } else if file == "ld-elf.so.1" || file == "ld-elf32.so.1" {
+        OsFamily::FreeBsd
+    } else if file == "ld.elf_so" {
+        OsFamily::NetBsd
+    } else if path == "/usr/libexec/ld.so" {
+        OsFamily::OpenBsd
+    } else if path.starts_with("/usr/lib/") && file == "ld.so.1" {
+        OsFamily::Solaris
+    } else {
+        return None;
+    };
+    Some(Vote::new(family, format!("interpreter: {path}")))
+}
+
+fn goos_vote(goos: &str) -> Vote {
+    let family = match goos {
+        "linux" => OsFamily::Linux,
+        "android" => OsFamily::Android,
+        "windows" => OsFamily::Windows,
+        "darwin" => OsFamily::MacOs,
+        "ios" => OsFamily::Ios,
+        "freebsd" => OsFamily::FreeBsd,
+        "openbsd" => OsFamily::OpenBsd,
+        "netbsd" => OsFamily::NetBsd,
+        "solaris" | "illumos" => OsFamily::Solaris,
+        "aix" => OsFamily::Aix,
+        "zos" => OsFamily::ZOs,
+        "wasip1" => OsFamily::Wasi,
+        "js" => OsFamily::Wasm,
+        _ => OsFamily::Unknown,
+    };
+    Vote::new(family, format!("Go GOOS: {goos}"))
+}
+
+fn format_vote(format: FileFormat) -> Option<Vote> {
+    let family = match format {
+        FileFormat::Pe => OsFamily::Windows,
+        FileFormat::MachO | FileFormat::MachOFat => OsFamily::MacOs,
+        FileFormat::Dex | FileFormat::Odex | FileFormat::Vdex | FileFormat::Art => {
+            OsFamily::Android
+        }
+        FileFormat::Xcoff => OsFamily::Aix,
+        FileFormat::Goff | FileFormat::MvsLoad => OsFamily::ZOs,
+        FileFormat::Wasm => OsFamily::Wasm,
+        FileFormat::Xbe => OsFamily::GameConsole("Xbox".to_string()),
+        FileFormat::Xex => OsFamily::GameConsole("Xbox 360".to_string()),
+        FileFormat::SelfPs3 => OsFamily::GameConsole("PlayStation 3".to_string()),
+        FileFormat::SelfPs4 => OsFamily::GameConsole("PlayStation 4".to_string()),
+        FileFormat::SelfPs5 => OsFamily::GameConsole("PlayStation 5".to_string()),
+        FileFormat::Nso | FileFormat::Nro => OsFamily::GameConsole("Nintendo Switch".to_string()),
+        FileFormat::Dol | FileFormat::Rel => OsFamily::GameConsole("GameCube/Wii".to_string()),
+        FileFormat::IpBin => OsFamily::GameConsole("Dreamcast".to_string()),
+        _ => return None,
+    };
+    Some(Vote::new(family, format!("format: {format}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elf(osabi: &str) -> OsSignals {
+        OsSignals {
+            format: Some(FileFormat::Elf),
+            target_env: Some(TargetEnv::OsHosted),
+            elf_osabi: Some(osabi.to_string()),
+            ..OsSignals::default()
+        }
+    }
+
+    #[test]
+    fn test_glibc_linux() {
+        let signals = OsSignals {
+            abi_tag: Some(("Linux".to_string(), "3.2.0".to_string())),
+            interpreter: Some("/lib64/ld-linux-x86-64.so.2".to_string()),
+            ..elf("UNIX System V")
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Linux);
+        assert_eq!(os.version_hint.as_deref(), Some("3.2.0"));
+        assert_eq!(
+            os.evidence,
+            [
+                "GNU ABI tag: Linux 3.2.0",
+                "interpreter: /lib64/ld-linux-x86-64.so.2"
+            ]
+        );
+        assert!(os.conflicts.is_empty());
+        assert_eq!(os.to_string(), "Linux 3.2.0");
+    }
+
+    #[test]
+    fn test_android_interpreter() {
+        let signals = OsSignals {
+            interpreter: Some("/system/bin/linker64".to_string()),
+            ..elf("UNIX System V")
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Android);
+        assert_eq!(os.version_hint, None);
+    }
+
+    #[test]
+    fn test_freebsd_osabi_beats_go_goos() {
+        let signals = OsSignals {
+            interpreter: Some("/libexec/ld-elf.so.1".to_string()),
+            go_goos: Some("linux".to_string()),
+            ..elf("FreeBSD")
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::FreeBsd);
+        assert_eq!(
+            os.evidence,
+            ["ELF OS ABI: FreeBSD", "interpreter: /libexec/ld-elf.so.1"]
+        );
+        assert_eq!(os.conflicts, ["Go GOOS: linux suggests Linux"]);
+    }
+
+    #[test]
+    fn test_efi_subsystem_beats_pe_format() {
+        let signals = OsSignals {
+            format: Some(FileFormat::Pe),
+            pe_subsystem: Some((pe::subsystem::EFI_APPLICATION, 0, 0)),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Uefi);
+        assert_eq!(os.evidence, ["PE subsystem: EFI application"]);
+        // PE's usual platform is no claim against the subsystem
+        assert!(os.conflicts.is_empty());
+
+        // Without a subsystem the format's default stands
+        let signals = OsSignals {
+            format: Some(FileFormat::Pe),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Windows);
+        assert_eq!(os.evidence, ["format: PE/COFF"]);
+    }
+
+    #[test]
+    fn test_windows_subsystem_version() {
+        let signals = OsSignals {
+            format: Some(FileFormat::Pe),
+            pe_subsystem: Some((pe::subsystem::WINDOWS_CUI, 6, 0)),
+            go_goos: Some("windows".to_string()),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Windows);
+        assert_eq!(os.version_hint.as_deref(), Some("6.0"));
+        assert_eq!(
+            os.evidence,
+            ["PE subsystem: Windows console", "Go GOOS: windows"]
+        );
+    }
+
+    #[test]
+    fn test_ios_platform() {
+        let signals = OsSignals {
+            format: Some(FileFormat::MachO),
+            macho_platform: Some((macho::platform::IOS, "16.4".to_string())),
+            go_goos: Some("ios".to_string()),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Ios);
+        assert_eq!(os.version_hint.as_deref(), Some("16.4"));
+        assert_eq!(os.evidence, ["Mach-O platform: iOS 16.4", "Go GOOS: ios"]);
+        // The container alone would say macOS, but only as a fallback
+        assert!(os.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_wasi_module() {
+        let mut signals = OsSignals {
+            format: Some(FileFormat::Wasm),
+            target_env: Some(TargetEnv::OsHosted),
+            ..OsSignals::default()
+        };
+        assert_eq!(resolve(&signals).unwrap().family, OsFamily::Wasm);
+
+        signals.wasi = true;
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Wasi);
+        assert_eq!(os.evidence, ["WASI imports"]);
+        assert!(os.conflicts.is_empty());
+
+        // GOOS=js names plain WebAssembly, which WASI refines
+        signals.go_goos = Some("js".to_string());
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Wasi);
+        assert_eq!(os.evidence, ["Go GOOS: js", "WASI imports"]);
+        assert!(os.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_console_and_bare_metal() {
+        let signals = OsSignals {
+            format: Some(FileFormat::SelfPs4),
+            target_env: Some(TargetEnv::OsHosted),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(
+            os.family,
+            OsFamily::GameConsole("PlayStation 4".to_string())
+        );
+        assert_eq!(os.to_string(), "PlayStation 4");
+
+        let signals = OsSignals {
+            format: Some(FileFormat::IntelHex),
+            target_env: Some(TargetEnv::BareMetal),
+            ..OsSignals::default()
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::BareMetal);
+        assert_eq!(os.evidence, ["target environment: bare-metal"]);
+    }
+
+    #[test]
+    fn test_unknown_os_and_no_signals() {
+        // HP-UX names an OS outside the list
+        let os = resolve(&elf("HP-UX")).unwrap();
+        assert_eq!(os.family, OsFamily::Unknown);
+        assert_eq!(os.evidence, ["ELF OS ABI: HP-UX"]);
+
+        // ...but yields to anything that does name one, without conflict
+        let signals = OsSignals {
+            go_goos: Some("linux".to_string()),
+            ..elf("HP-UX")
+        };
+        let os = resolve(&signals).unwrap();
+        assert_eq!(os.family, OsFamily::Linux);
+        assert!(os.conflicts.is_empty());
+
+        // A System V ELF with nothing else says nothing about its OS
+        assert_eq!(resolve(&elf("UNIX System V")), None);
+        assert_eq!(resolve(&OsSignals::default()), None);
+    }
+
+    #[test]
+    fn test_signals_from_payload() {
+        let mut data = b"\0asm\x01\0\0\0".to_vec();
+        data.extend_from_slice(b"\x02\x1Bwasi_snapshot_preview1");
+        data.extend_from_slice(b"\tbuild\tGOOS=wasip1\n");
+        let payload = crate::detect_payload(&data, &crate::ClassifierOptions::new()).unwrap();
+        let signals = signals(&data, &payload);
+        assert!(signals.wasi);
+        assert_eq!(signals.go_goos.as_deref(), Some("wasip1"));
+
+        let os = payload.os.unwrap();
+        assert_eq!(os.family, OsFamily::Wasi);
+        assert_eq!(os.evidence, ["Go GOOS: wasip1", "WASI imports"]);
+    }
+}
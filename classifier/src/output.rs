@@ -0,0 +1,220 @@
+//! Terminal output for the CLI.
+//!
+//! Color is decided once per stream: the `--color` choice is resolved
+//! against the `NO_COLOR` / `CLICOLOR` / `CLICOLOR_FORCE` conventions and
+//! whether the stream is a terminal, and the result is handed to the
+//! formatters. Only [`HumanFormatter`](crate::HumanFormatter) styles its
+//! output; machine-readable formats never carry escape codes.
+
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+
+/// When to emit ANSI color codes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Color when the stream is a terminal and the environment allows it
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolve `Auto` to `Always` or `Never`.
+    ///
+    /// An explicit `Always`/`Never` wins. Under `Auto`, a non-empty
+    /// `NO_COLOR` disables color, a `CLICOLOR_FORCE` other than `0` forces
+    /// it, and otherwise color follows `is_tty` unless `CLICOLOR=0` or
+    /// `TERM=dumb`. `env` looks up a variable by name.
+    pub fn resolve(self, env: impl Fn(&str) -> Option<String>, is_tty: bool) -> Self {
+        if self != ColorChoice::Auto {
+            return self;
+        }
+        let set = |name: &str| env(name).filter(|v| !v.is_empty());
+        if set("NO_COLOR").is_some() {
+            return ColorChoice::Never;
+        }
+        if set("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+            return ColorChoice::Always;
+        }
+        if !is_tty
+            || set("CLICOLOR").is_some_and(|v| v == "0")
+            || set("TERM").is_some_and(|v| v == "dumb")
+        {
+            return ColorChoice::Never;
+        }
+        ColorChoice::Always
+    }
+
+    /// Wrap `text` in the escape codes for `style` when this choice is
+    /// `Always`; `Auto` is treated as `Never`, so resolve it first.
+    pub fn paint(self, text: &str, style: Style) -> String {
+        if self == ColorChoice::Always {
+            format!("\x1b[{}m{}\x1b[0m", style.sgr(), text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Text styles used by the human formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Bold
+    Bold,
+    /// Red foreground
+    Red,
+    /// Yellow foreground
+    Yellow,
+    /// Green foreground
+    Green,
+}
+
+impl Style {
+    /// ANSI SGR parameter for this style.
+    pub fn sgr(self) -> &'static str {
+        match self {
+            Style::Bold => "1",
+            Style::Red => "31",
+            Style::Yellow => "33",
+            Style::Green => "32",
+        }
+    }
+
+    /// Style for a confidence value: green when solid, yellow when middling,
+    /// red when weak.
+    pub fn for_confidence(confidence: f64) -> Self {
+        if confidence >= 0.8 {
+            Style::Green
+        } else if confidence >= 0.5 {
+            Style::Yellow
+        } else {
+            Style::Red
+        }
+    }
+}
+
+/// The CLI's stdout/stderr, with color resolved per stream.
+///
+/// All CLI output goes through one `Output` so the terminal checks and
+/// environment lookups happen exactly once.
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    stdout_color: ColorChoice,
+    stderr_color: ColorChoice,
+}
+
+impl Output {
+    /// Resolve `choice` against the process environment and the terminal
+    /// status of stdout and stderr.
+    pub fn new(choice: ColorChoice) -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        Self {
+            stdout_color: choice.resolve(env, std::io::stdout().is_terminal()),
+            stderr_color: choice.resolve(env, std::io::stderr().is_terminal()),
+        }
+    }
+
+    /// Color decision for stdout (`Always` or `Never`).
+    pub fn stdout_color(&self) -> ColorChoice {
+        self.stdout_color
+    }
+
+    /// Color decision for stderr (`Always` or `Never`).
+    pub fn stderr_color(&self) -> ColorChoice {
+        self.stderr_color
+    }
+
+    /// Write `text` to stdout as-is.
+    pub fn print(&self, text: impl Display) {
+        // A closed pipe (`isa-classify ... | head`) is not worth a panic
+        let _ = write!(std::io::stdout().lock(), "{text}");
+    }
+
+    /// Write `text` and a newline to stdout.
+    pub fn println(&self, text: impl Display) {
+        let _ = writeln!(std::io::stdout().lock(), "{text}");
+    }
+
+    /// Write `text` and a newline to stderr.
+    pub fn eprintln(&self, text: impl Display) {
+        let _ = writeln!(std::io::stderr().lock(), "{text}");
+    }
+
+    /// Write one streaming record (e.g. an NDJSON line) to stdout and flush.
+    ///
+    /// The whole record is written under a single stdout lock, so records
+    /// written from several threads never interleave, and each one reaches
+    /// the consumer as soon as it is produced.
+    pub fn record(&self, line: &str) {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(line.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_of(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| (*v).to_string())
+        }
+    }
+
+    #[test]
+    fn test_color_choice_resolution() {
+        let auto = ColorChoice::Auto;
+        assert_eq!(auto.resolve(env_of(&[]), true), ColorChoice::Always);
+        assert_eq!(auto.resolve(env_of(&[]), false), ColorChoice::Never);
+
+        // NO_COLOR overrides auto, but not an explicit request
+        let no_color = env_of(&[("NO_COLOR", "1")]);
+        assert_eq!(auto.resolve(&no_color, true), ColorChoice::Never);
+        assert_eq!(
+            ColorChoice::Always.resolve(&no_color, false),
+            ColorChoice::Always
+        );
+        // Empty NO_COLOR is ignored
+        assert_eq!(
+            auto.resolve(env_of(&[("NO_COLOR", "")]), true),
+            ColorChoice::Always
+        );
+
+        assert_eq!(
+            auto.resolve(env_of(&[("CLICOLOR_FORCE", "1")]), false),
+            ColorChoice::Always
+        );
+        assert_eq!(
+            auto.resolve(env_of(&[("CLICOLOR_FORCE", "0")]), false),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            auto.resolve(env_of(&[("CLICOLOR", "0")]), true),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            auto.resolve(env_of(&[("TERM", "dumb")]), true),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            ColorChoice::Never.resolve(env_of(&[("CLICOLOR_FORCE", "1")]), true),
+            ColorChoice::Never
+        );
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(
+            ColorChoice::Always.paint("[warn]", Style::Yellow),
+            "\x1b[33m[warn]\x1b[0m"
+        );
+        assert_eq!(ColorChoice::Never.paint("[warn]", Style::Yellow), "[warn]");
+        assert_eq!(ColorChoice::Auto.paint("[warn]", Style::Yellow), "[warn]");
+    }
+}
@@ -0,0 +1,159 @@
+//! Re-running classification on bytes already in memory.
+//!
+//! Escalating analysis (a fast pass first, then a thorough one for
+//! inconclusive results) re-classifies the same buffer with different
+//! [`ClassifierOptions`]. [`Reclassifier`] holds the bytes and the options
+//! so each retry is one call, without re-reading the file or threading the
+//! buffer through the loop by hand.
+//!
+//! ```rust,no_run
+//! use isa_classifier::{ClassifierOptions, Reclassifier};
+//!
+//! let fast = Reclassifier::from_file("firmware.bin", ClassifierOptions::fast())?;
+//! let mut payload = fast.detect()?;
+//! if payload.primary.confidence < 0.5 {
+//!     payload = fast.with_options(ClassifierOptions::thorough()).detect()?;
+//! }
+//! # Ok::<(), isa_classifier::ClassifierError>(())
+//! ```
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::types::{ClassificationResult, ClassifierOptions, DetectionPayload};
+
+/// Input bytes paired with the options to classify them with.
+#[derive(Debug, Clone)]
+pub struct Reclassifier<'a> {
+    data: Cow<'a, [u8]>,
+    options: ClassifierOptions,
+}
+
+impl<'a> Reclassifier<'a> {
+    /// Hold `data`, borrowed or owned, for classification with `options`.
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, options: ClassifierOptions) -> Self {
+        Self {
+            data: data.into(),
+            options,
+        }
+    }
+
+    /// Read `path` once and hold its contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns the IO error when the file cannot be read.
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        options: ClassifierOptions,
+    ) -> Result<Reclassifier<'static>> {
+        Ok(Reclassifier::new(std::fs::read(path)?, options))
+    }
+
+    /// The same bytes with different options. The bytes are borrowed, not
+    /// copied, and `self` stays usable.
+    pub fn with_options(&self, options: ClassifierOptions) -> Reclassifier<'_> {
+        Reclassifier {
+            data: Cow::Borrowed(&self.data),
+            options,
+        }
+    }
+
+    /// The held bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The options classification runs with.
+    pub fn options(&self) -> &ClassifierOptions {
+        &self.options
+    }
+
+    /// Run [`classify_bytes_with_options`](crate::classify_bytes_with_options).
+    ///
+    /// # Errors
+    ///
+    /// Returns the classification error, as the wrapped function does.
+    pub fn classify(&self) -> Result<ClassificationResult> {
+        crate::classify_bytes_with_options(&self.data, &self.options)
+    }
+
+    /// Run [`detect_payload`](crate::detect_payload).
+    ///
+    /// # Errors
+    ///
+    /// Returns the detection error, as the wrapped function does.
+    pub fn detect(&self) -> Result<DetectionPayload> {
+        crate::detect_payload(&self.data, &self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Isa;
+
+    /// x86-64 executable: headers, one `PT_LOAD` over the whole file and
+    /// some code.
+    fn x86_64_elf() -> Vec<u8> {
+        let mut data = vec![0u8; 120];
+        data[0..4].copy_from_slice(b"\x7FELF");
+        data[4] = 2; // ELFCLASS64
+        data[5] = 1; // ELFDATA2LSB
+        data[6] = 1;
+        data[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        data[18..20].copy_from_slice(&62u16.to_le_bytes()); // EM_X86_64
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+        data[24..32].copy_from_slice(&0x40_0078u64.to_le_bytes());
+        data[32..40].copy_from_slice(&64u64.to_le_bytes()); // e_phoff
+        data[52..54].copy_from_slice(&64u16.to_le_bytes());
+        data[54..56].copy_from_slice(&56u16.to_le_bytes());
+        data[56..58].copy_from_slice(&1u16.to_le_bytes());
+        data[64..68].copy_from_slice(&1u32.to_le_bytes()); // PT_LOAD
+        data[68..72].copy_from_slice(&5u32.to_le_bytes()); // R+X
+        data[80..88].copy_from_slice(&0x40_0000u64.to_le_bytes());
+        while data.len() < 1024 {
+            // push rbp; mov rbp, rsp; vpaddd ymm0, ymm1, ymm2; pop rbp; ret
+            data.extend_from_slice(&[0x55, 0x48, 0x89, 0xE5, 0xC5, 0xF5, 0xFE, 0xC2, 0x5D, 0xC3]);
+        }
+        let size = (data.len() as u64).to_le_bytes();
+        data[96..104].copy_from_slice(&size);
+        data[104..112].copy_from_slice(&size);
+        data
+    }
+
+    #[test]
+    fn test_escalate_fast_to_thorough() {
+        let data = x86_64_elf();
+        let fast = Reclassifier::new(&data[..], ClassifierOptions::fast());
+        let first = fast.detect().unwrap();
+        assert_eq!(first.primary.isa, Isa::X86_64);
+        assert!(!first.extensions_analyzed);
+        assert!(first.hardening.is_none());
+
+        let thorough = fast.with_options(ClassifierOptions::thorough());
+        assert!(std::ptr::eq(thorough.data(), fast.data()));
+        let second = thorough.detect().unwrap();
+        assert_eq!(second.primary.isa, Isa::X86_64);
+        assert!(second.extensions_analyzed);
+        assert!(second.hardening.is_some());
+
+        // Same answers as calling the functions directly
+        let direct = crate::classify_bytes_with_options(&data, &ClassifierOptions::thorough());
+        assert_eq!(thorough.classify().unwrap().isa, direct.unwrap().isa);
+        assert!(fast.options().fast_mode);
+    }
+
+    #[test]
+    fn test_owned_bytes_from_file() {
+        let data = x86_64_elf();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &data).unwrap();
+
+        let owned = Reclassifier::from_file(file.path(), ClassifierOptions::new()).unwrap();
+        assert_eq!(owned.data(), &data[..]);
+        assert_eq!(owned.classify().unwrap().isa, Isa::X86_64);
+        assert!(Reclassifier::from_file("/nonexistent/input", ClassifierOptions::new()).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Bare-metal versus OS-hosted triage.
+//!
+//! Large mixed corpora hold both application binaries and device firmware,
+//! and the first question asked of an unknown file is usually which of the
+//! two it is. The answer is derived from detections that already exist:
+//!
+//! - **Format family**: boot and kernel images, flash dumps in hex-text
+//!   containers, automotive ECU containers and firmware blobs run without
+//!   an operating system underneath. Executable formats with a loader
+//!   (ELF, PE, Mach-O, the console and bytecode formats) are OS-hosted.
+//! - **ISA class**: microcontroller cores (AVR, MSP430, PIC, ...) run
+//!   firmware straight from flash, whatever container it was linked into.
+//! - **Vector table**: a headerless ARM image that opens with a Cortex-M
+//!   exception table is firmware.
+//!
+//! Headerless images with none of these signals are left unlabelled.
+
+use crate::architectures::arm;
+use crate::types::{FileFormat, FormatFamily, Isa, MetadataEntry, MetadataValue};
+
+/// The environment a binary is built to run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetEnv {
+    /// Firmware, boot loaders and kernels: no operating system underneath
+    BareMetal,
+    /// Programs started by an operating system's loader
+    OsHosted,
+}
+
+impl TargetEnv {
+    /// Label used in metadata ("bare-metal" or "os-hosted").
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TargetEnv::BareMetal => "bare-metal",
+            TargetEnv::OsHosted => "os-hosted",
+        }
+    }
+}
+
+/// Classify `data`, already identified as `format` for `isa`.
+///
+/// Returns `None` for headerless images that carry no firmware signal,
+/// since raw code alone does not tell a firmware dump from a code snippet.
+pub fn identify(data: &[u8], format: FileFormat, isa: Isa) -> Option<TargetEnv> {
+    if isa.is_microcontroller() {
+        return Some(TargetEnv::BareMetal);
+    }
+    match format.family() {
+        FormatFamily::Boot | FormatFamily::HexText | FormatFamily::Automotive => {
+            Some(TargetEnv::BareMetal)
+        }
+        FormatFamily::Embedded => match format {
+            // uClinux flat binaries are loaded by a kernel
+            FileFormat::Bflt => Some(TargetEnv::OsHosted),
+            _ => Some(TargetEnv::BareMetal),
+        },
+        FormatFamily::Raw => (isa == Isa::Arm && arm::has_cortex_m_vector_table(data))
+            .then_some(TargetEnv::BareMetal),
+        _ => Some(TargetEnv::OsHosted),
+    }
+}
+
+/// Build the `target_env` metadata entry.
+pub fn metadata_entry(env: TargetEnv) -> MetadataEntry {
+    MetadataEntry::custom(
+        "target_env",
+        MetadataValue::String(env.as_str().to_string()),
+        "Target Environment",
+    )
+    .with_origin("target_env")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{classify_bytes_with_options, detect_payload, ClassifierOptions};
+
+    /// A Cortex-M image: initial SP in SRAM, then Thumb handler addresses,
+    /// then Thumb code.
+    fn cortex_m_blob() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0x2000_8000u32.to_le_bytes());
+        for i in 0..47u32 {
+            data.extend_from_slice(&(0x0800_0101 + i * 0x10).to_le_bytes());
+        }
+        // push {r4, lr}; movs r0, #1; adds r0, r0, r1; bl; pop {r4, pc}; bx lr
+        let code: &[u8] = &[
+            0x10, 0xB5, 0x01, 0x20, 0x40, 0x18, 0x00, 0xF0, 0x10, 0xF8, 0x10, 0xBD, 0x70, 0x47,
+            0x00, 0xBF,
+        ];
+        while data.len() < 4096 {
+            data.extend_from_slice(code);
+        }
+        data
+    }
+
+    fn linux_elf() -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(b"\x7FELF");
+        data[4] = 2; // ELFCLASS64
+        data[5] = 1; // ELFDATA2LSB
+        data[6] = 1; // EV_CURRENT
+        data[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        data[18..20].copy_from_slice(&62u16.to_le_bytes()); // EM_X86_64
+        data[20..24].copy_from_slice(&1u32.to_le_bytes());
+        data[24..32].copy_from_slice(&0x40_1000u64.to_le_bytes());
+        data[52..54].copy_from_slice(&64u16.to_le_bytes());
+        data
+    }
+
+    fn target_env_value(entries: &[MetadataEntry]) -> Option<String> {
+        entries.iter().find_map(|e| match (&e.key, &e.value) {
+            (crate::types::MetadataKey::Custom(k), MetadataValue::String(v))
+                if k == "target_env" =>
+            {
+                Some(v.clone())
+            }
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_vector_table_blob_is_bare_metal() {
+        let data = cortex_m_blob();
+        assert!(arm::has_cortex_m_vector_table(&data));
+        assert_eq!(
+            identify(&data, FileFormat::Raw, Isa::Arm),
+            Some(TargetEnv::BareMetal)
+        );
+
+        let result = classify_bytes_with_options(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(result.isa, Isa::Arm);
+        assert_eq!(
+            target_env_value(&result.metadata.custom).as_deref(),
+            Some("bare-metal")
+        );
+    }
+
+    #[test]
+    fn test_linux_elf_is_os_hosted() {
+        let data = linux_elf();
+        let payload = detect_payload(&data, &ClassifierOptions::new()).unwrap();
+        assert_eq!(
+            target_env_value(&payload.metadata).as_deref(),
+            Some("os-hosted")
+        );
+    }
+
+    #[test]
+    fn test_format_and_isa_signals() {
+        assert_eq!(
+            identify(&[], FileFormat::UImage, Isa::Arm),
+            Some(TargetEnv::BareMetal)
+        );
+        assert_eq!(
+            identify(&[], FileFormat::Elf, Isa::Avr),
+            Some(TargetEnv::BareMetal)
+        );
+        assert_eq!(
+            identify(&[], FileFormat::Bflt, Isa::Arm),
+            Some(TargetEnv::OsHosted)
+        );
+        assert_eq!(identify(&[0x90; 256], FileFormat::Raw, Isa::X86_64), None);
+    }
+}
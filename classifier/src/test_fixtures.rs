@@ -0,0 +1,26 @@
+//! Code samples shared by unit tests.
+
+/// One little-endian AArch64 function: frame set-up, a load, a call, a
+/// branch and the matching tear-down.
+pub(crate) const AARCH64_FUNCTION: [u32; 10] = [
+    0xA9BF_7BFD, // stp x29, x30, [sp, #-16]!
+    0x9100_03FD, // mov x29, sp
+    0xF940_0400, // ldr x0, [x0, #8]
+    0x9400_0010, // bl +0x40
+    0xB400_0060, // cbz x0, +12
+    0x9100_0400, // add x0, x0, #1
+    0xD503_201F, // nop
+    0xA8C1_7BFD, // ldp x29, x30, [sp], #16
+    0xD65F_03C0, // ret
+    0xD503_201F, // nop
+];
+
+/// `words` instruction words of [`AARCH64_FUNCTION`], repeated as needed.
+pub(crate) fn aarch64_code(words: usize) -> Vec<u8> {
+    AARCH64_FUNCTION
+        .iter()
+        .cycle()
+        .take(words)
+        .flat_map(|w| w.to_le_bytes())
+        .collect()
+}
@@ -0,0 +1,475 @@
+//! Cross-compile target triple suggestions.
+//!
+//! Maps a classification onto the Rust/LLVM target triples most likely to
+//! build or process it. The mapping is a plain table keyed on ISA,
+//! endianness, container family and float ABI; hints then reorder the
+//! matching row:
+//!
+//! - an identified C library moves its triples (`-musl`, `-uclibc`, `-gnu`)
+//!   to the front;
+//! - bare-metal inputs (raw images, hex files) move the `-none` triples to
+//!   the front.
+//!
+//! Combinations without a row yield no suggestions rather than a guess.
+
+use crate::types::{Endianness, FileFormat, Isa, Variant};
+
+/// Container family a triple's OS/vendor fields follow from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TargetFamily {
+    /// ELF, raw and hex images: Linux first, bare metal last
+    Elf,
+    /// PE images
+    Windows,
+    /// Mach-O images
+    Apple,
+    /// WebAssembly modules
+    Wasm,
+}
+
+/// Float calling convention, from the ISA variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FloatAbi {
+    Hard,
+    Soft,
+}
+
+/// One row of [`TRIPLE_TABLE`]; `None` fields match anything.
+struct TripleRow {
+    isa: Isa,
+    endianness: Option<Endianness>,
+    family: TargetFamily,
+    float_abi: Option<FloatAbi>,
+    triples: &'static [&'static str],
+}
+
+const fn row(
+    isa: Isa,
+    endianness: Option<Endianness>,
+    family: TargetFamily,
+    triples: &'static [&'static str],
+) -> TripleRow {
+    TripleRow {
+        isa,
+        endianness,
+        family,
+        float_abi: None,
+        triples,
+    }
+}
+
+const LE: Option<Endianness> = Some(Endianness::Little);
+const BE: Option<Endianness> = Some(Endianness::Big);
+
+/// Triple table. The first matching row wins, so float-ABI-specific rows
+/// precede the catch-all row for the same ISA.
+const TRIPLE_TABLE: &[TripleRow] = {
+    use TargetFamily::{Apple, Elf, Wasm, Windows};
+    &[
+        row(
+            Isa::X86_64,
+            None,
+            Elf,
+            &[
+                "x86_64-unknown-linux-gnu",
+                "x86_64-unknown-linux-musl",
+                "x86_64-unknown-none",
+            ],
+        ),
+        row(
+            Isa::X86_64,
+            None,
+            Windows,
+            &["x86_64-pc-windows-msvc", "x86_64-pc-windows-gnu"],
+        ),
+        row(Isa::X86_64, None, Apple, &["x86_64-apple-darwin"]),
+        row(
+            Isa::X86,
+            None,
+            Elf,
+            &[
+                "i686-unknown-linux-gnu",
+                "i686-unknown-linux-musl",
+                "i586-unknown-linux-gnu",
+            ],
+        ),
+        row(
+            Isa::X86,
+            None,
+            Windows,
+            &["i686-pc-windows-msvc", "i686-pc-windows-gnu"],
+        ),
+        row(Isa::X86, None, Apple, &["i686-apple-darwin"]),
+        row(
+            Isa::AArch64,
+            LE,
+            Elf,
+            &[
+                "aarch64-unknown-linux-gnu",
+                "aarch64-unknown-linux-musl",
+                "aarch64-unknown-none",
+            ],
+        ),
+        row(
+            Isa::AArch64,
+            BE,
+            Elf,
+            &["aarch64_be-unknown-linux-gnu", "aarch64_be-unknown-none"],
+        ),
+        row(
+            Isa::AArch64,
+            None,
+            Windows,
+            &["aarch64-pc-windows-msvc", "aarch64-pc-windows-gnullvm"],
+        ),
+        row(Isa::AArch64, None, Apple, &["aarch64-apple-darwin"]),
+        TripleRow {
+            isa: Isa::Arm,
+            endianness: LE,
+            family: Elf,
+            float_abi: Some(FloatAbi::Hard),
+            triples: &[
+                "armv7-unknown-linux-gnueabihf",
+                "arm-unknown-linux-gnueabihf",
+                "armv7-unknown-linux-musleabihf",
+                "arm-unknown-linux-musleabihf",
+                "armv7a-none-eabihf",
+            ],
+        },
+        row(
+            Isa::Arm,
+            LE,
+            Elf,
+            &[
+                "arm-unknown-linux-gnueabi",
+                "armv7-unknown-linux-gnueabi",
+                "arm-unknown-linux-musleabi",
+                "armv5te-unknown-linux-uclibceabi",
+                "armv7a-none-eabi",
+                "thumbv7m-none-eabi",
+            ],
+        ),
+        row(
+            Isa::Arm,
+            BE,
+            Elf,
+            &["armeb-unknown-linux-gnueabi", "armebv7r-none-eabi"],
+        ),
+        row(Isa::Arm, LE, Windows, &["thumbv7a-pc-windows-msvc"]),
+        row(
+            Isa::RiscV64,
+            LE,
+            Elf,
+            &[
+                "riscv64gc-unknown-linux-gnu",
+                "riscv64gc-unknown-linux-musl",
+                "riscv64gc-unknown-none-elf",
+                "riscv64imac-unknown-none-elf",
+            ],
+        ),
+        row(
+            Isa::RiscV32,
+            LE,
+            Elf,
+            &[
+                "riscv32gc-unknown-linux-gnu",
+                "riscv32imac-unknown-none-elf",
+                "riscv32imc-unknown-none-elf",
+                "riscv32i-unknown-none-elf",
+            ],
+        ),
+        row(
+            Isa::Mips,
+            BE,
+            Elf,
+            &[
+                "mips-unknown-linux-gnu",
+                "mips-unknown-linux-musl",
+                "mips-unknown-linux-uclibc",
+            ],
+        ),
+        row(
+            Isa::Mips,
+            LE,
+            Elf,
+            &[
+                "mipsel-unknown-linux-gnu",
+                "mipsel-unknown-linux-musl",
+                "mipsel-unknown-linux-uclibc",
+                "mipsel-unknown-none",
+            ],
+        ),
+        row(
+            Isa::Mips64,
+            BE,
+            Elf,
+            &[
+                "mips64-unknown-linux-gnuabi64",
+                "mips64-unknown-linux-muslabi64",
+            ],
+        ),
+        row(
+            Isa::Mips64,
+            LE,
+            Elf,
+            &[
+                "mips64el-unknown-linux-gnuabi64",
+                "mips64el-unknown-linux-muslabi64",
+            ],
+        ),
+        row(
+            Isa::Ppc,
+            BE,
+            Elf,
+            &["powerpc-unknown-linux-gnu", "powerpc-unknown-linux-musl"],
+        ),
+        row(
+            Isa::Ppc64,
+            BE,
+            Elf,
+            &[
+                "powerpc64-unknown-linux-gnu",
+                "powerpc64-unknown-linux-musl",
+            ],
+        ),
+        row(
+            Isa::Ppc64,
+            LE,
+            Elf,
+            &[
+                "powerpc64le-unknown-linux-gnu",
+                "powerpc64le-unknown-linux-musl",
+            ],
+        ),
+        row(
+            Isa::S390x,
+            BE,
+            Elf,
+            &["s390x-unknown-linux-gnu", "s390x-unknown-linux-musl"],
+        ),
+        row(
+            Isa::LoongArch64,
+            LE,
+            Elf,
+            &[
+                "loongarch64-unknown-linux-gnu",
+                "loongarch64-unknown-linux-musl",
+                "loongarch64-unknown-none",
+            ],
+        ),
+        row(Isa::Sparc64, BE, Elf, &["sparc64-unknown-linux-gnu"]),
+        row(Isa::Sparc, BE, Elf, &["sparc-unknown-linux-gnu"]),
+        row(Isa::M68k, BE, Elf, &["m68k-unknown-linux-gnu"]),
+        row(Isa::Hexagon, LE, Elf, &["hexagon-unknown-linux-musl"]),
+        row(Isa::Msp430, LE, Elf, &["msp430-none-elf"]),
+        row(Isa::Avr, LE, Elf, &["avr-unknown-gnu-atmega328"]),
+        row(Isa::Bpf, LE, Elf, &["bpfel-unknown-none"]),
+        row(Isa::Bpf, BE, Elf, &["bpfeb-unknown-none"]),
+        row(
+            Isa::Wasm,
+            None,
+            Wasm,
+            &["wasm32-unknown-unknown", "wasm32-wasip1"],
+        ),
+    ]
+};
+
+/// Container family for `format`, and whether it implies bare metal.
+fn family_for(format: FileFormat) -> Option<(TargetFamily, bool)> {
+    match format {
+        FileFormat::Elf => Some((TargetFamily::Elf, false)),
+        FileFormat::Raw | FileFormat::IntelHex | FileFormat::Srec | FileFormat::TiTxt => {
+            Some((TargetFamily::Elf, true))
+        }
+        FileFormat::Pe => Some((TargetFamily::Windows, false)),
+        FileFormat::MachO | FileFormat::MachOFat => Some((TargetFamily::Apple, false)),
+        FileFormat::Wasm => Some((TargetFamily::Wasm, false)),
+        _ => None,
+    }
+}
+
+/// Float ABI named in the variant ("hard-float", "soft-float"), if any.
+fn float_abi_of(variant: Option<&Variant>) -> Option<FloatAbi> {
+    let variant = variant?;
+    let mentions = |needle: &str| {
+        variant.name.contains(needle) || variant.abi.as_deref().is_some_and(|a| a.contains(needle))
+    };
+    if mentions("hard-float") {
+        Some(FloatAbi::Hard)
+    } else if mentions("soft-float") {
+        Some(FloatAbi::Soft)
+    } else {
+        None
+    }
+}
+
+/// Triple environment fragment for a C library name as reported by
+/// [`crate::libc`] ("musl 1.2.3", "uClibc-ng", "glibc 2.31").
+fn libc_env(libc: &str) -> Option<&'static str> {
+    let libc = libc.to_ascii_lowercase();
+    if libc.starts_with("musl") {
+        Some("-musl")
+    } else if libc.starts_with("uclibc") {
+        Some("-uclibc")
+    } else if libc.starts_with("glibc") {
+        Some("-gnu")
+    } else {
+        None
+    }
+}
+
+/// Suggest target triples for a classification, most likely first.
+///
+/// `libc` is the C library name from the `libc` metadata entry, when
+/// identified. Returns an empty vector for combinations without a table
+/// row.
+pub fn suggest(
+    isa: Isa,
+    endianness: Endianness,
+    variant: Option<&Variant>,
+    format: FileFormat,
+    libc: Option<&str>,
+) -> Vec<String> {
+    let Some((family, bare_metal)) = family_for(format) else {
+        return Vec::new();
+    };
+    let float_abi = float_abi_of(variant);
+    let Some(row) = TRIPLE_TABLE.iter().find(|row| {
+        row.isa == isa
+            && row.family == family
+            && row.endianness.map_or(true, |e| e == endianness)
+            && row.float_abi.map_or(true, |abi| Some(abi) == float_abi)
+    }) else {
+        return Vec::new();
+    };
+
+    let mut triples: Vec<String> = row.triples.iter().map(|t| (*t).to_string()).collect();
+    // Both sorts are stable, so the table order survives within each group
+    if let Some(env) = libc.and_then(libc_env) {
+        triples.sort_by_key(|t| !t.contains(env));
+    }
+    if bare_metal {
+        triples.sort_by_key(|t| !t.contains("-none"));
+    }
+    triples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_for(family: TargetFamily) -> FileFormat {
+        match family {
+            TargetFamily::Elf => FileFormat::Elf,
+            TargetFamily::Windows => FileFormat::Pe,
+            TargetFamily::Apple => FileFormat::MachO,
+            TargetFamily::Wasm => FileFormat::Wasm,
+        }
+    }
+
+    #[test]
+    fn test_every_row_is_reachable() {
+        for row in TRIPLE_TABLE {
+            let variant = match row.float_abi {
+                Some(FloatAbi::Hard) => Some(Variant::new("EABI5, hard-float")),
+                Some(FloatAbi::Soft) => Some(Variant::new("EABI5, soft-float")),
+                None => None,
+            };
+            let endianness = row.endianness.unwrap_or(Endianness::Little);
+            let triples = suggest(
+                row.isa,
+                endianness,
+                variant.as_ref(),
+                format_for(row.family),
+                None,
+            );
+            assert_eq!(triples, row.triples, "{:?} {:?}", row.isa, row.family);
+        }
+    }
+
+    #[test]
+    fn test_libc_hint_reorders() {
+        let triples = suggest(
+            Isa::AArch64,
+            Endianness::Little,
+            None,
+            FileFormat::Elf,
+            Some("musl 1.2.4"),
+        );
+        assert_eq!(
+            triples,
+            [
+                "aarch64-unknown-linux-musl",
+                "aarch64-unknown-linux-gnu",
+                "aarch64-unknown-none"
+            ]
+        );
+
+        let uclibc = suggest(
+            Isa::Mips,
+            Endianness::Big,
+            None,
+            FileFormat::Elf,
+            Some("uClibc-ng 1.0.39"),
+        );
+        assert_eq!(uclibc[0], "mips-unknown-linux-uclibc");
+
+        // An unidentified libc leaves the table order alone
+        let unknown = suggest(
+            Isa::AArch64,
+            Endianness::Little,
+            None,
+            FileFormat::Elf,
+            Some("unknown"),
+        );
+        assert_eq!(unknown[0], "aarch64-unknown-linux-gnu");
+    }
+
+    #[test]
+    fn test_float_abi_and_bare_metal() {
+        let hard = Variant::new("EABI5, hard-float");
+        let triples = suggest(
+            Isa::Arm,
+            Endianness::Little,
+            Some(&hard),
+            FileFormat::Elf,
+            None,
+        );
+        assert_eq!(triples[0], "armv7-unknown-linux-gnueabihf");
+
+        let soft = Variant::new("EABI5, soft-float");
+        let triples = suggest(
+            Isa::Arm,
+            Endianness::Little,
+            Some(&soft),
+            FileFormat::Elf,
+            None,
+        );
+        assert_eq!(triples[0], "arm-unknown-linux-gnueabi");
+
+        // Raw firmware puts the bare-metal triples first
+        let triples = suggest(Isa::Arm, Endianness::Little, None, FileFormat::Raw, None);
+        assert_eq!(&triples[..2], ["armv7a-none-eabi", "thumbv7m-none-eabi"]);
+    }
+
+    #[test]
+    fn test_unknown_combinations_are_empty() {
+        assert!(suggest(Isa::Arm, Endianness::Little, None, FileFormat::MachO, None).is_empty());
+        assert!(suggest(
+            Isa::X86_64,
+            Endianness::Little,
+            None,
+            FileFormat::JavaClass,
+            None
+        )
+        .is_empty());
+        assert!(suggest(
+            Isa::Unknown(0),
+            Endianness::Little,
+            None,
+            FileFormat::Elf,
+            None
+        )
+        .is_empty());
+    }
+}
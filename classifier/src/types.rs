@@ -26,6 +26,8 @@ pub enum Isa {
     // RISC-V family
     RiscV32,
     RiscV64,
+    /// Only reported from ELF files whose arch attribute names RV128; no
+    /// heuristic scorer produces it
     RiscV128,
 
     // MIPS family
@@ -136,6 +138,8 @@ pub enum Isa {
     Avr32,
     Msp430,
     Pic,
+    // Microchip PIC24/dsPIC — 16-bit, 24-bit instruction words
+    Pic24,
     Stm8,
 
     // GPU/Accelerators
@@ -193,6 +197,8 @@ pub enum Isa {
     Clr,
     /// EFI Byte Code
     Ebc,
+    /// Inferno Dis virtual machine
+    Dis,
 
     // Cell BE / PlayStation 3
     /// Cell Broadband Engine SPU
@@ -261,6 +267,7 @@ impl Isa {
             Isa::Avr32 => "Atmel AVR32",
             Isa::Msp430 => "TI MSP430",
             Isa::Pic => "Microchip PIC",
+            Isa::Pic24 => "Microchip PIC24/dsPIC",
             Isa::Stm8 => "STMicro STM8",
             Isa::AmdGpu => "AMD GPU",
             Isa::Cuda => "NVIDIA CUDA",
@@ -289,11 +296,104 @@ impl Isa {
             Isa::Dalvik => "Dalvik/ART Bytecode",
             Isa::Clr => "CLR/.NET Bytecode",
             Isa::Ebc => "EFI Byte Code",
+            Isa::Dis => "Dis Bytecode",
             Isa::CellSpu => "Cell SPU",
             Isa::Unknown(_) => "Unknown",
         }
     }
 
+    /// Returns the decade this ISA was introduced, e.g. `"1970s"`.
+    ///
+    /// Dates refer to the instruction set named by the variant, not its
+    /// family: [`Isa::X86`] is the 32-bit i386 (1980s) and [`Isa::X86_64`]
+    /// the AMD64 extension (2000s). Decades sort chronologically as
+    /// strings, so results can be grouped by era directly.
+    pub fn era(&self) -> &'static str {
+        match self {
+            Isa::Pic | Isa::M68k | Isa::Vax | Isa::Pdp11 | Isa::Z80 | Isa::Mcs6502 => "1970s",
+            Isa::X86
+            | Isa::Arm
+            | Isa::Mips
+            | Isa::Sparc
+            | Isa::Parisc
+            | Isa::Hc11
+            | Isa::I860
+            | Isa::I960
+            | Isa::W65816 => "1980s",
+            Isa::Mips64
+            | Isa::Ppc
+            | Isa::Ppc64
+            | Isa::S390
+            | Isa::Sparc64
+            | Isa::ColdFire
+            | Isa::Sh
+            | Isa::Sh4
+            | Isa::Alpha
+            | Isa::Arc
+            | Isa::Xtensa
+            | Isa::V850
+            | Isa::TiC6000
+            | Isa::TiC2000
+            | Isa::Sharc
+            | Isa::Tricore
+            | Isa::Hcs12
+            | Isa::C166
+            | Isa::Avr
+            | Isa::Msp430
+            | Isa::Fr30
+            | Isa::Jvm
+            | Isa::Dis => "1990s",
+            Isa::X86_64
+            | Isa::S390x
+            | Isa::Ia64
+            | Isa::Hexagon
+            | Isa::ArcCompact
+            | Isa::MicroBlaze
+            | Isa::Nios2
+            | Isa::OpenRisc
+            | Isa::Csky
+            | Isa::K78k0r
+            | Isa::Rx
+            | Isa::TiC28x
+            | Isa::TiC5500
+            | Isa::TiPru
+            | Isa::Blackfin
+            | Isa::Avr32
+            | Isa::Pic24
+            | Isa::Stm8
+            | Isa::Cuda
+            | Isa::Elbrus
+            | Isa::McstElbrus
+            | Isa::Tile64
+            | Isa::TilePro
+            | Isa::VideoCore3
+            | Isa::Frv
+            | Isa::Fr80
+            | Isa::PpcVle
+            | Isa::Dalvik
+            | Isa::Clr
+            | Isa::Ebc
+            | Isa::CellSpu => "2000s",
+            Isa::AArch64
+            | Isa::RiscV32
+            | Isa::RiscV64
+            | Isa::RiscV128
+            | Isa::ArcCompact2
+            | Isa::Lanai
+            | Isa::Rh850
+            | Isa::S12z
+            | Isa::Rl78
+            | Isa::AmdGpu
+            | Isa::Bpf
+            | Isa::TileGx
+            | Isa::VideoCore5
+            | Isa::Kvx
+            | Isa::Wasm => "2010s",
+            Isa::LoongArch32 | Isa::LoongArch64 => "2020s",
+            Isa::Unknown(_) => "Unknown",
+        }
+    }
+
     /// Returns the default bitwidth for this ISA.
     pub fn default_bitwidth(&self) -> u8 {
         match self {
@@ -332,8 +432,7 @@ impl Isa {
             | Isa::Hexagon
             | Isa::Tricore
             | Isa::Tile64
-            | Isa::TilePro
-            | Isa::TileGx => 32,
+            | Isa::TilePro => 32,
 
             Isa::X86_64
             | Isa::AArch64
@@ -349,7 +448,8 @@ impl Isa {
             | Isa::Elbrus
             | Isa::McstElbrus
             | Isa::Bpf
-            | Isa::Ebc => 64,
+            | Isa::Ebc
+            | Isa::TileGx => 64,
 
             Isa::RiscV128 => 128,
 
@@ -357,6 +457,7 @@ impl Isa {
             | Isa::Avr32
             | Isa::Msp430
             | Isa::Pic
+            | Isa::Pic24
             | Isa::Stm8
             | Isa::Z80
             | Isa::Mcs6502
@@ -384,6 +485,7 @@ impl Isa {
             Isa::Jvm => 32,    // JVM operand stack width
             Isa::Dalvik => 32, // Register-based 32-bit
             Isa::Clr => 32,    // CIL stack width
+            Isa::Dis => 32,    // Dis word size
 
             Isa::Unknown(_) => 0,
         }
@@ -415,6 +517,397 @@ impl Isa {
                 | Isa::Rh850
         )
     }
+
+    /// Returns whether this ISA is a microcontroller-class architecture.
+    ///
+    /// These cores run firmware straight from flash with no operating
+    /// system, so an image for one is almost always bare-metal.
+    pub fn is_microcontroller(&self) -> bool {
+        matches!(
+            self,
+            Isa::Avr
+                | Isa::Msp430
+                | Isa::Pic
+                | Isa::Pic24
+                | Isa::Stm8
+                | Isa::Hc11
+                | Isa::Hcs12
+                | Isa::S12z
+                | Isa::Rl78
+                | Isa::K78k0r
+                | Isa::C166
+                | Isa::TiC2000
+                | Isa::TiC28x
+                | Isa::TiPru
+                | Isa::Tricore
+                | Isa::Rh850
+        )
+    }
+
+    /// Returns whether this ISA is discontinued by its vendor.
+    ///
+    /// Intended for portability audits; families that are merely legacy but
+    /// still shipping (e.g. MIPS, SPARC, 68k/ColdFire) are not included.
+    pub fn is_deprecated(&self) -> bool {
+        matches!(
+            self,
+            Isa::Ia64
+                | Isa::Alpha
+                | Isa::Parisc
+                | Isa::Vax
+                | Isa::Pdp11
+                | Isa::I860
+                | Isa::I960
+                | Isa::Avr32
+                | Isa::CellSpu
+                | Isa::Tile64
+                | Isa::TilePro
+                | Isa::TileGx
+                | Isa::Frv
+        )
+    }
+
+    /// Returns the architecture family this ISA belongs to.
+    ///
+    /// Families group ISAs that share a lineage and vendor naming, even when
+    /// their encodings differ (AArch64 and 32-bit ARM are both
+    /// [`IsaFamily::Arm`]).
+    pub fn family(&self) -> IsaFamily {
+        match self {
+            Isa::X86 | Isa::X86_64 => IsaFamily::X86,
+            Isa::Arm | Isa::AArch64 => IsaFamily::Arm,
+            Isa::RiscV32 | Isa::RiscV64 | Isa::RiscV128 => IsaFamily::RiscV,
+            Isa::Mips | Isa::Mips64 => IsaFamily::Mips,
+            Isa::Ppc | Isa::Ppc64 | Isa::PpcVle => IsaFamily::PowerPc,
+            Isa::S390 | Isa::S390x => IsaFamily::S390,
+            Isa::Sparc | Isa::Sparc64 => IsaFamily::Sparc,
+            Isa::M68k | Isa::ColdFire => IsaFamily::M68k,
+            Isa::Sh | Isa::Sh4 => IsaFamily::SuperH,
+            Isa::LoongArch32 | Isa::LoongArch64 => IsaFamily::LoongArch,
+            Isa::Arc | Isa::ArcCompact | Isa::ArcCompact2 => IsaFamily::Arc,
+            Isa::V850 | Isa::Rh850 => IsaFamily::V850,
+            Isa::TiC6000 | Isa::TiC2000 | Isa::TiC28x | Isa::TiC5500 => IsaFamily::TiDsp,
+            Isa::Hc11 | Isa::Hcs12 | Isa::S12z => IsaFamily::Mc68hc,
+            Isa::Avr | Isa::Avr32 => IsaFamily::Avr,
+            Isa::Pic | Isa::Pic24 => IsaFamily::Pic,
+            Isa::Mcs6502 | Isa::W65816 => IsaFamily::Mos6502,
+            Isa::Tile64 | Isa::TilePro | Isa::TileGx => IsaFamily::Tile,
+            Isa::VideoCore3 | Isa::VideoCore5 => IsaFamily::VideoCore,
+            Isa::Frv | Isa::Fr30 | Isa::Fr80 => IsaFamily::Fujitsu,
+            Isa::Elbrus | Isa::McstElbrus => IsaFamily::Elbrus,
+            _ => IsaFamily::Other,
+        }
+    }
+
+    /// Returns the general-purpose register file of the base architecture.
+    ///
+    /// Counts are the architecturally visible integer registers of the base
+    /// ISA, not optional extensions: 32-bit ARM reports no FP registers
+    /// since VFP is optional, while x86-64 reports vector registers since
+    /// SSE2 is part of the baseline. Windowed ISAs (SPARC, Xtensa) report
+    /// the registers visible at one time. Returns `None` for stack machines
+    /// and ISAs not in the table.
+    pub fn register_info(&self) -> Option<RegisterInfo> {
+        let info = |gpr_count, gpr_width, fp_registers, vector_registers| RegisterInfo {
+            gpr_count,
+            gpr_width,
+            separate_sp: false,
+            fp_registers,
+            vector_registers,
+        };
+        Some(match self {
+            Isa::X86 => info(8, 32, true, false),
+            Isa::X86_64 => info(16, 64, true, true),
+            Isa::AArch64 => RegisterInfo {
+                separate_sp: true,
+                ..info(31, 64, true, true)
+            },
+            Isa::Avr => RegisterInfo {
+                separate_sp: true,
+                ..info(32, 8, false, false)
+            },
+            Isa::Arm | Isa::M68k | Isa::Sh | Isa::Xtensa | Isa::Vax => info(16, 32, false, false),
+            Isa::RiscV32 | Isa::Mips | Isa::LoongArch32 | Isa::Hexagon => {
+                info(32, 32, false, false)
+            }
+            Isa::RiscV64 | Isa::Mips64 | Isa::LoongArch64 => info(32, 64, false, false),
+            Isa::RiscV128 => info(32, 128, false, false),
+            Isa::Ppc | Isa::Sparc | Isa::Parisc => info(32, 32, true, false),
+            Isa::Ppc64 | Isa::Sparc64 | Isa::Alpha => info(32, 64, true, false),
+            Isa::S390 | Isa::Sh4 => info(16, 32, true, false),
+            Isa::S390x => info(16, 64, true, false),
+            Isa::Ia64 => info(128, 64, true, false),
+            Isa::Bpf => info(11, 64, false, false),
+            Isa::Msp430 => info(16, 16, false, false),
+            Isa::Pdp11 => info(8, 16, false, false),
+            _ => return None,
+        })
+    }
+
+    /// Code reserved for [`Isa::Unknown`]; see [`Isa::to_code`].
+    pub const UNKNOWN_CODE: u16 = 0xFFFF;
+
+    /// Returns the stable numeric code of this ISA, for compact storage.
+    ///
+    /// Codes are assigned by this crate and are unrelated to format-native
+    /// machine numbers such as ELF `e_machine`. A code is never reused or
+    /// renumbered once released; new ISAs take the next free value. Code 0
+    /// is never assigned, so it can stand for "no ISA" in storage. Every
+    /// [`Isa::Unknown`] maps to [`Isa::UNKNOWN_CODE`]; store its raw value
+    /// separately to round-trip it.
+    pub fn to_code(&self) -> u16 {
+        match self {
+            Isa::X86 => 1,
+            Isa::X86_64 => 2,
+            Isa::Arm => 3,
+            Isa::AArch64 => 4,
+            Isa::RiscV32 => 5,
+            Isa::RiscV64 => 6,
+            Isa::RiscV128 => 7,
+            Isa::Mips => 8,
+            Isa::Mips64 => 9,
+            Isa::Ppc => 10,
+            Isa::Ppc64 => 11,
+            Isa::S390 => 12,
+            Isa::S390x => 13,
+            Isa::Sparc => 14,
+            Isa::Sparc64 => 15,
+            Isa::M68k => 16,
+            Isa::ColdFire => 17,
+            Isa::Sh => 18,
+            Isa::Sh4 => 19,
+            Isa::Ia64 => 20,
+            Isa::Alpha => 21,
+            Isa::Parisc => 22,
+            Isa::LoongArch32 => 23,
+            Isa::LoongArch64 => 24,
+            Isa::Hexagon => 25,
+            Isa::Arc => 26,
+            Isa::ArcCompact => 27,
+            Isa::ArcCompact2 => 28,
+            Isa::Xtensa => 29,
+            Isa::MicroBlaze => 30,
+            Isa::Nios2 => 31,
+            Isa::OpenRisc => 32,
+            Isa::Lanai => 33,
+            Isa::Csky => 34,
+            Isa::V850 => 35,
+            Isa::Rh850 => 36,
+            Isa::K78k0r => 37,
+            Isa::Rx => 38,
+            Isa::TiC6000 => 39,
+            Isa::TiC2000 => 40,
+            Isa::TiC28x => 41,
+            Isa::TiC5500 => 42,
+            Isa::TiPru => 43,
+            Isa::Blackfin => 44,
+            Isa::Sharc => 45,
+            Isa::Tricore => 46,
+            Isa::Hcs12 => 47,
+            Isa::S12z => 48,
+            Isa::Hc11 => 49,
+            Isa::C166 => 50,
+            Isa::Rl78 => 51,
+            Isa::Avr => 52,
+            Isa::Avr32 => 53,
+            Isa::Msp430 => 54,
+            Isa::Pic => 55,
+            Isa::Pic24 => 56,
+            Isa::Stm8 => 57,
+            Isa::AmdGpu => 58,
+            Isa::Cuda => 59,
+            Isa::Bpf => 60,
+            Isa::I860 => 61,
+            Isa::I960 => 62,
+            Isa::Vax => 63,
+            Isa::Pdp11 => 64,
+            Isa::Z80 => 65,
+            Isa::Mcs6502 => 66,
+            Isa::W65816 => 67,
+            Isa::Elbrus => 68,
+            Isa::Tile64 => 69,
+            Isa::TilePro => 70,
+            Isa::TileGx => 71,
+            Isa::VideoCore3 => 72,
+            Isa::VideoCore5 => 73,
+            Isa::Kvx => 74,
+            Isa::McstElbrus => 75,
+            Isa::Frv => 76,
+            Isa::Fr30 => 77,
+            Isa::Fr80 => 78,
+            Isa::PpcVle => 79,
+            Isa::Wasm => 80,
+            Isa::Jvm => 81,
+            Isa::Dalvik => 82,
+            Isa::Clr => 83,
+            Isa::Ebc => 84,
+            Isa::CellSpu => 85,
+            Isa::Dis => 86,
+            Isa::Unknown(_) => Self::UNKNOWN_CODE,
+        }
+    }
+
+    /// Returns the ISA with the given [`Isa::to_code`] code.
+    ///
+    /// Returns `None` for unassigned codes and for [`Isa::UNKNOWN_CODE`],
+    /// which needs the separately stored raw value to rebuild
+    /// [`Isa::Unknown`].
+    pub fn from_code(code: u16) -> Option<Isa> {
+        Some(match code {
+            1 => Isa::X86,
+            2 => Isa::X86_64,
+            3 => Isa::Arm,
+            4 => Isa::AArch64,
+            5 => Isa::RiscV32,
+            6 => Isa::RiscV64,
+            7 => Isa::RiscV128,
+            8 => Isa::Mips,
+            9 => Isa::Mips64,
+            10 => Isa::Ppc,
+            11 => Isa::Ppc64,
+            12 => Isa::S390,
+            13 => Isa::S390x,
+            14 => Isa::Sparc,
+            15 => Isa::Sparc64,
+            16 => Isa::M68k,
+            17 => Isa::ColdFire,
+            18 => Isa::Sh,
+            19 => Isa::Sh4,
+            20 => Isa::Ia64,
+            21 => Isa::Alpha,
+            22 => Isa::Parisc,
+            23 => Isa::LoongArch32,
+            24 => Isa::LoongArch64,
+            25 => Isa::Hexagon,
+            26 => Isa::Arc,
+            27 => Isa::ArcCompact,
+            28 => Isa::ArcCompact2,
+            29 => Isa::Xtensa,
+            30 => Isa::MicroBlaze,
+            31 => Isa::Nios2,
+            32 => Isa::OpenRisc,
+            33 => Isa::Lanai,
+            34 => Isa::Csky,
+            35 => Isa::V850,
+            36 => Isa::Rh850,
+            37 => Isa::K78k0r,
+            38 => Isa::Rx,
+            39 => Isa::TiC6000,
+            40 => Isa::TiC2000,
+            41 => Isa::TiC28x,
+            42 => Isa::TiC5500,
+            43 => Isa::TiPru,
+            44 => Isa::Blackfin,
+            45 => Isa::Sharc,
+            46 => Isa::Tricore,
+            47 => Isa::Hcs12,
+            48 => Isa::S12z,
+            49 => Isa::Hc11,
+            50 => Isa::C166,
+            51 => Isa::Rl78,
+            52 => Isa::Avr,
+            53 => Isa::Avr32,
+            54 => Isa::Msp430,
+            55 => Isa::Pic,
+            56 => Isa::Pic24,
+            57 => Isa::Stm8,
+            58 => Isa::AmdGpu,
+            59 => Isa::Cuda,
+            60 => Isa::Bpf,
+            61 => Isa::I860,
+            62 => Isa::I960,
+            63 => Isa::Vax,
+            64 => Isa::Pdp11,
+            65 => Isa::Z80,
+            66 => Isa::Mcs6502,
+            67 => Isa::W65816,
+            68 => Isa::Elbrus,
+            69 => Isa::Tile64,
+            70 => Isa::TilePro,
+            71 => Isa::TileGx,
+            72 => Isa::VideoCore3,
+            73 => Isa::VideoCore5,
+            74 => Isa::Kvx,
+            75 => Isa::McstElbrus,
+            76 => Isa::Frv,
+            77 => Isa::Fr30,
+            78 => Isa::Fr80,
+            79 => Isa::PpcVle,
+            80 => Isa::Wasm,
+            81 => Isa::Jvm,
+            82 => Isa::Dalvik,
+            83 => Isa::Clr,
+            84 => Isa::Ebc,
+            85 => Isa::CellSpu,
+            86 => Isa::Dis,
+            _ => return None,
+        })
+    }
+}
+
+/// General-purpose register file of an ISA; see [`Isa::register_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RegisterInfo {
+    /// Number of general-purpose registers, including any hardwired zero
+    /// register and any stack or program counter that lives in the file
+    pub gpr_count: u8,
+    /// Width of a general-purpose register in bits
+    pub gpr_width: u8,
+    /// Whether the stack pointer is a dedicated register outside the count
+    pub separate_sp: bool,
+    /// Whether the base architecture has floating-point registers
+    pub fp_registers: bool,
+    /// Whether the base architecture has vector registers
+    pub vector_registers: bool,
+}
+
+impl RegisterInfo {
+    /// Metadata entries describing the register file.
+    pub fn to_metadata(&self) -> Vec<MetadataEntry> {
+        vec![
+            MetadataEntry::custom(
+                "gpr_count",
+                MetadataValue::Integer(u64::from(self.gpr_count)),
+                "GPRs",
+            ),
+            MetadataEntry::custom(
+                "gpr_width",
+                MetadataValue::Integer(u64::from(self.gpr_width)),
+                "GPR Width",
+            ),
+            MetadataEntry::custom(
+                "separate_sp",
+                MetadataValue::Bool(self.separate_sp),
+                "Separate SP",
+            ),
+            MetadataEntry::custom(
+                "fp_registers",
+                MetadataValue::Bool(self.fp_registers),
+                "FP Registers",
+            ),
+            MetadataEntry::custom(
+                "vector_registers",
+                MetadataValue::Bool(self.vector_registers),
+                "Vector Registers",
+            ),
+        ]
+    }
+}
+
+impl fmt::Display for RegisterInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} x {}-bit GPRs", self.gpr_count, self.gpr_width)?;
+        if self.separate_sp {
+            write!(f, " + SP")?;
+        }
+        if self.fp_registers {
+            write!(f, ", FP")?;
+        }
+        if self.vector_registers {
+            write!(f, ", vector")?;
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for Isa {
@@ -426,6 +919,167 @@ impl fmt::Display for Isa {
     }
 }
 
+/// Architecture family grouping related [`Isa`] values.
+///
+/// See [`Isa::family`]. ISAs without close relatives map to
+/// [`IsaFamily::Other`], which never implies a relationship between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum IsaFamily {
+    /// x86 and x86-64
+    X86,
+    /// 32-bit ARM/Thumb and AArch64
+    Arm,
+    /// RISC-V of any register width
+    RiscV,
+    /// MIPS32 and MIPS64
+    Mips,
+    /// PowerPC, PowerPC64 and VLE
+    PowerPc,
+    /// S/390 and z/Architecture
+    S390,
+    /// SPARC V8 and V9
+    Sparc,
+    /// 68000 and ColdFire
+    M68k,
+    /// SuperH
+    SuperH,
+    /// LoongArch
+    LoongArch,
+    /// Synopsys ARC
+    Arc,
+    /// NEC V850 and Renesas RH850
+    V850,
+    /// TI TMS320 DSPs
+    TiDsp,
+    /// Motorola/Freescale 68HC11, HCS12 and S12Z
+    Mc68hc,
+    /// Atmel AVR and AVR32
+    Avr,
+    /// Microchip PIC and PIC24/dsPIC
+    Pic,
+    /// MOS 6502 and WDC 65816
+    Mos6502,
+    /// Tilera TILE
+    Tile,
+    /// Broadcom VideoCore
+    VideoCore,
+    /// Fujitsu FR and FR-V
+    Fujitsu,
+    /// Elbrus
+    Elbrus,
+    /// An ISA with no grouped relatives
+    Other,
+}
+
+impl IsaFamily {
+    /// Returns a human-readable name for this family.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IsaFamily::X86 => "x86",
+            IsaFamily::Arm => "ARM",
+            IsaFamily::RiscV => "RISC-V",
+            IsaFamily::Mips => "MIPS",
+            IsaFamily::PowerPc => "PowerPC",
+            IsaFamily::S390 => "S/390",
+            IsaFamily::Sparc => "SPARC",
+            IsaFamily::M68k => "68k",
+            IsaFamily::SuperH => "SuperH",
+            IsaFamily::LoongArch => "LoongArch",
+            IsaFamily::Arc => "ARC",
+            IsaFamily::V850 => "V850",
+            IsaFamily::TiDsp => "TI DSP",
+            IsaFamily::Mc68hc => "68HC",
+            IsaFamily::Avr => "AVR",
+            IsaFamily::Pic => "PIC",
+            IsaFamily::Mos6502 => "6502",
+            IsaFamily::Tile => "TILE",
+            IsaFamily::VideoCore => "VideoCore",
+            IsaFamily::Fujitsu => "Fujitsu FR",
+            IsaFamily::Elbrus => "Elbrus",
+            IsaFamily::Other => "Other",
+        }
+    }
+}
+
+impl fmt::Display for IsaFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Reporting family grouping related [`FileFormat`] values.
+///
+/// See [`FileFormat::family`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum FormatFamily {
+    /// ELF and FatELF
+    Elf,
+    /// Mach-O and universal (fat) Mach-O
+    MachO,
+    /// PE and standalone COFF objects
+    Pe,
+    /// MZ, NE, LE/LX, COM and the DOS-era OMF/TDS files
+    DosLegacy,
+    /// a.out variants, XCOFF, ECOFF and SOM
+    UnixLegacy,
+    /// DEX, ODEX, VDEX and ART images
+    Android,
+    /// WebAssembly, Java class files and LLVM bitcode
+    Bytecode,
+    /// ar archives and Windows import libraries
+    Archive,
+    /// Game console executables
+    Console,
+    /// Kernel images, boot containers and device trees
+    Boot,
+    /// Intel HEX, S-record and TI-TXT
+    HexText,
+    /// ECU flash and tuning containers
+    Automotive,
+    /// Embedded and no-MMU executables
+    Embedded,
+    /// z/Architecture object and load modules
+    Mainframe,
+    /// Historical platform containers
+    Legacy,
+    /// No recognized format
+    Raw,
+}
+
+impl FormatFamily {
+    /// Returns a human-readable name for this family.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FormatFamily::Elf => "ELF",
+            FormatFamily::MachO => "Mach-O",
+            FormatFamily::Pe => "PE/COFF",
+            FormatFamily::DosLegacy => "DOS/Windows-legacy",
+            FormatFamily::UnixLegacy => "Unix-legacy",
+            FormatFamily::Android => "Android",
+            FormatFamily::Bytecode => "Bytecode",
+            FormatFamily::Archive => "Archive",
+            FormatFamily::Console => "Console",
+            FormatFamily::Boot => "Kernel/boot",
+            FormatFamily::HexText => "Hex text",
+            FormatFamily::Automotive => "Automotive",
+            FormatFamily::Embedded => "Embedded",
+            FormatFamily::Mainframe => "Mainframe",
+            FormatFamily::Legacy => "Legacy",
+            FormatFamily::Raw => "Raw",
+        }
+    }
+}
+
+impl fmt::Display for FormatFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 /// Byte ordering (endianness).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -516,6 +1170,8 @@ pub enum FileFormat {
     Epoc,
     /// Espressif firmware image (ESP)
     EspFirmware,
+    /// Intel Management Engine / CSME firmware region
+    IntelMe,
     /// Palm database/resource container (PDB/PRC)
     PalmPdb,
     /// Amiga Hunk executable/object format
@@ -572,6 +1228,8 @@ pub enum FileFormat {
     Art,
     /// LLVM Bitcode
     LlvmBc,
+    /// Inferno Dis module
+    Dis,
 
     // Multi-Architecture Containers
     /// FatELF
@@ -580,6 +1238,8 @@ pub enum FileFormat {
     Archive,
     /// Windows .lib import library
     WindowsLib,
+    /// ZIP archive (also JAR, APK)
+    Zip,
 
     // Game Console Formats
     /// XBE (Original Xbox)
@@ -600,6 +1260,8 @@ pub enum FileFormat {
     Dol,
     /// REL (GameCube/Wii relocatable)
     Rel,
+    /// Dreamcast IP.BIN boot header
+    IpBin,
 
     // Kernel/Boot Formats
     /// Linux zImage/bzImage
@@ -612,6 +1274,206 @@ pub enum FileFormat {
     Dtb,
 }
 
+impl FileFormat {
+    /// Returns the reporting family this format belongs to.
+    ///
+    /// Families collapse container variants and close relatives
+    /// (Mach-O and fat Mach-O, the Android DEX derivatives) so corpus
+    /// summaries stay readable.
+    pub fn family(&self) -> FormatFamily {
+        match self {
+            FileFormat::Elf | FileFormat::FatElf => FormatFamily::Elf,
+            FileFormat::MachO | FileFormat::MachOFat => FormatFamily::MachO,
+            FileFormat::Pe | FileFormat::Coff => FormatFamily::Pe,
+            FileFormat::Mz
+            | FileFormat::Ne
+            | FileFormat::Le
+            | FileFormat::Lx
+            | FileFormat::Com
+            | FileFormat::Omf
+            | FileFormat::Tds => FormatFamily::DosLegacy,
+            FileFormat::Aout
+            | FileFormat::Plan9Aout
+            | FileFormat::MinixAout
+            | FileFormat::Xcoff
+            | FileFormat::Ecoff
+            | FileFormat::Som => FormatFamily::UnixLegacy,
+            FileFormat::Dex | FileFormat::Odex | FileFormat::Vdex | FileFormat::Art => {
+                FormatFamily::Android
+            }
+            FileFormat::Wasm | FileFormat::JavaClass | FileFormat::LlvmBc | FileFormat::Dis => {
+                FormatFamily::Bytecode
+            }
+            FileFormat::Archive | FileFormat::WindowsLib | FileFormat::Zip => FormatFamily::Archive,
+            FileFormat::Xbe
+            | FileFormat::Xex
+            | FileFormat::SelfPs3
+            | FileFormat::SelfPs4
+            | FileFormat::SelfPs5
+            | FileFormat::Nso
+            | FileFormat::Nro
+            | FileFormat::Dol
+            | FileFormat::Rel
+            | FileFormat::IpBin => FormatFamily::Console,
+            FileFormat::ZImage | FileFormat::UImage | FileFormat::Fit | FileFormat::Dtb => {
+                FormatFamily::Boot
+            }
+            FileFormat::IntelHex | FileFormat::Srec | FileFormat::TiTxt => FormatFamily::HexText,
+            FileFormat::Ols
+            | FileFormat::Epr
+            | FileFormat::Sgo
+            | FileFormat::Vbf
+            | FileFormat::Frf
+            | FileFormat::Bcf
+            | FileFormat::Sox => FormatFamily::Automotive,
+            FileFormat::Aof
+            | FileFormat::EspFirmware
+            | FileFormat::IntelMe
+            | FileFormat::Bflt
+            | FileFormat::Dxe => FormatFamily::Embedded,
+            FileFormat::Goff | FileFormat::MvsLoad => FormatFamily::Mainframe,
+            FileFormat::Epoc
+            | FileFormat::PalmPdb
+            | FileFormat::AmigaHunk
+            | FileFormat::Os9
+            | FileFormat::Pef
+            | FileFormat::Rsx11
+            | FileFormat::Vms
+            | FileFormat::Ieee695 => FormatFamily::Legacy,
+            FileFormat::Raw => FormatFamily::Raw,
+        }
+    }
+
+    /// Returns the byte order the format mandates for the code and data it
+    /// carries, or `None` when it varies with the target.
+    ///
+    /// Bytecode formats fix their own order (Java class files are
+    /// big-endian, DEX and WebAssembly little-endian), as do containers
+    /// tied to one CPU family (DOS on x86, GameCube on PowerPC). Formats
+    /// that record the order in their header (ELF, PE, Mach-O) or wrap
+    /// arbitrary targets return `None`.
+    pub fn endianness_hint(&self) -> Option<Endianness> {
+        match self {
+            FileFormat::JavaClass
+            | FileFormat::Dis
+            | FileFormat::Xcoff
+            | FileFormat::Goff
+            | FileFormat::MvsLoad
+            | FileFormat::Som
+            | FileFormat::Pef
+            | FileFormat::AmigaHunk
+            | FileFormat::PalmPdb
+            | FileFormat::Xex
+            | FileFormat::SelfPs3
+            | FileFormat::Dol
+            | FileFormat::Rel
+            | FileFormat::Dtb => Some(Endianness::Big),
+            FileFormat::Wasm
+            | FileFormat::Dex
+            | FileFormat::Odex
+            | FileFormat::Vdex
+            | FileFormat::Art
+            | FileFormat::Mz
+            | FileFormat::Ne
+            | FileFormat::Le
+            | FileFormat::Lx
+            | FileFormat::Com
+            | FileFormat::Omf
+            | FileFormat::Tds
+            | FileFormat::Epoc
+            | FileFormat::EspFirmware
+            | FileFormat::IntelMe
+            | FileFormat::Dxe
+            | FileFormat::Rsx11
+            | FileFormat::Vms
+            | FileFormat::Xbe
+            | FileFormat::SelfPs4
+            | FileFormat::SelfPs5
+            | FileFormat::Nso
+            | FileFormat::Nro
+            | FileFormat::IpBin => Some(Endianness::Little),
+            _ => None,
+        }
+    }
+
+    /// Returns common file extensions for this format, including the dot.
+    ///
+    /// The first entry is the most typical. An empty string means files of
+    /// this format are commonly extension-less (e.g., ELF executables).
+    /// Formats with no conventional extension return an empty slice.
+    pub fn typical_extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileFormat::Elf => &["", ".so", ".o", ".elf", ".ko"],
+            FileFormat::Pe => &[".exe", ".dll", ".sys", ".efi", ".ocx", ".scr"],
+            FileFormat::MachO => &["", ".dylib", ".o", ".bundle"],
+            FileFormat::MachOFat => &["", ".dylib"],
+            FileFormat::Coff => &[".obj", ".o"],
+            FileFormat::Xcoff => &["", ".o", ".a"],
+            FileFormat::Ecoff => &["", ".o"],
+            FileFormat::Ols => &[".ols"],
+            FileFormat::Epr => &[".epr"],
+            FileFormat::Sgo => &[".sgo"],
+            FileFormat::Vbf => &[".vbf"],
+            FileFormat::Frf => &[".frf"],
+            FileFormat::Bcf => &[".bcf"],
+            FileFormat::Sox => &[".sox"],
+            FileFormat::Raw => &[".bin", ".rom", ".img"],
+            FileFormat::Aout | FileFormat::MinixAout => &["", ".out", ".o"],
+            FileFormat::Plan9Aout => &["", ".out"],
+            FileFormat::Mz => &[".exe"],
+            FileFormat::Ne => &[".exe", ".dll", ".drv"],
+            FileFormat::Le => &[".exe", ".vxd", ".386"],
+            FileFormat::Lx => &[".exe", ".dll"],
+            FileFormat::Com => &[".com"],
+            FileFormat::Omf => &[".obj", ".lib"],
+            FileFormat::Tds => &[".tds"],
+            FileFormat::Aof => &[".o", ".axf", ".aof"],
+            FileFormat::Epoc => &[".app", ".exe", ".dll"],
+            FileFormat::EspFirmware => &[".bin"],
+            FileFormat::IntelMe => &[".bin", ".rgn"],
+            FileFormat::PalmPdb => &[".prc", ".pdb"],
+            FileFormat::AmigaHunk => &["", ".library", ".device"],
+            FileFormat::Os9 | FileFormat::Pef => &[""],
+            FileFormat::IntelHex => &[".hex", ".ihex", ".ihx"],
+            FileFormat::Srec => &[".srec", ".s19", ".s28", ".s37", ".mot"],
+            FileFormat::TiTxt => &[".txt"],
+            FileFormat::Bflt => &["", ".bflt"],
+            FileFormat::Dxe => &[".dxe"],
+            FileFormat::Goff => &[".o"],
+            FileFormat::MvsLoad => &[],
+            FileFormat::Som => &["", ".sl", ".o"],
+            FileFormat::Rsx11 => &[".tsk", ".obj"],
+            FileFormat::Vms => &[".exe", ".obj"],
+            FileFormat::Ieee695 => &[".x", ".abs"],
+            FileFormat::Wasm => &[".wasm"],
+            FileFormat::JavaClass => &[".class"],
+            FileFormat::Dex => &[".dex"],
+            FileFormat::Odex => &[".odex"],
+            FileFormat::Vdex => &[".vdex"],
+            FileFormat::Art => &[".art"],
+            FileFormat::LlvmBc => &[".bc"],
+            FileFormat::Dis => &[".dis"],
+            FileFormat::FatElf => &["", ".so"],
+            FileFormat::Archive => &[".a", ".lib", ".deb"],
+            FileFormat::WindowsLib => &[".lib"],
+            FileFormat::Zip => &[".zip", ".jar", ".apk"],
+            FileFormat::Xbe => &[".xbe"],
+            FileFormat::Xex => &[".xex"],
+            FileFormat::SelfPs3 => &[".self", ".sprx"],
+            FileFormat::SelfPs4 | FileFormat::SelfPs5 => &[".self", ".prx", ".sprx", ".elf"],
+            FileFormat::Nso => &[".nso", ""],
+            FileFormat::Nro => &[".nro"],
+            FileFormat::Dol => &[".dol"],
+            FileFormat::Rel => &[".rel"],
+            FileFormat::IpBin => &[".bin"],
+            FileFormat::ZImage => &["", ".zimage"],
+            FileFormat::UImage => &[".uimg", ".ub", ".img"],
+            FileFormat::Fit => &[".itb", ".fit"],
+            FileFormat::Dtb => &[".dtb", ".dtbo"],
+        }
+    }
+}
+
 impl fmt::Display for FileFormat {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -645,6 +1507,7 @@ impl fmt::Display for FileFormat {
             FileFormat::Aof => write!(f, "AOF/AXF"),
             FileFormat::Epoc => write!(f, "EPOC"),
             FileFormat::EspFirmware => write!(f, "ESP Firmware"),
+            FileFormat::IntelMe => write!(f, "Intel ME"),
             FileFormat::PalmPdb => write!(f, "Palm PDB/PRC"),
             FileFormat::AmigaHunk => write!(f, "Amiga Hunk"),
             FileFormat::Os9 => write!(f, "OS-9"),
@@ -673,10 +1536,12 @@ impl fmt::Display for FileFormat {
             FileFormat::Vdex => write!(f, "VDEX"),
             FileFormat::Art => write!(f, "ART"),
             FileFormat::LlvmBc => write!(f, "LLVM Bitcode"),
+            FileFormat::Dis => write!(f, "Dis"),
             // Multi-arch containers
             FileFormat::FatElf => write!(f, "FatELF"),
             FileFormat::Archive => write!(f, "ar Archive"),
             FileFormat::WindowsLib => write!(f, "Windows .lib"),
+            FileFormat::Zip => write!(f, "ZIP"),
             // Game consoles
             FileFormat::Xbe => write!(f, "XBE"),
             FileFormat::Xex => write!(f, "XEX"),
@@ -687,6 +1552,7 @@ impl fmt::Display for FileFormat {
             FileFormat::Nro => write!(f, "NRO"),
             FileFormat::Dol => write!(f, "DOL"),
             FileFormat::Rel => write!(f, "REL"),
+            FileFormat::IpBin => write!(f, "IP.BIN"),
             // Kernel/Boot
             FileFormat::ZImage => write!(f, "zImage"),
             FileFormat::UImage => write!(f, "uImage"),
@@ -736,7 +1602,7 @@ impl Extension {
         Self {
             name: name.into(),
             category,
-            confidence: confidence.clamp(0.0, 1.0),
+            confidence: round_confidence(confidence),
         }
     }
 }
@@ -777,6 +1643,25 @@ pub enum ExtensionCategory {
     Other,
 }
 
+impl fmt::Display for ExtensionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtensionCategory::Simd => write!(f, "SIMD"),
+            ExtensionCategory::Crypto => write!(f, "crypto"),
+            ExtensionCategory::Atomic => write!(f, "atomic"),
+            ExtensionCategory::FloatingPoint => write!(f, "floating-point"),
+            ExtensionCategory::BitManip => write!(f, "bit-manip"),
+            ExtensionCategory::Virtualization => write!(f, "virtualization"),
+            ExtensionCategory::Security => write!(f, "security"),
+            ExtensionCategory::Transactional => write!(f, "transactional"),
+            ExtensionCategory::MachineLearning => write!(f, "ML"),
+            ExtensionCategory::Compressed => write!(f, "compressed"),
+            ExtensionCategory::System => write!(f, "system"),
+            ExtensionCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
 /// Architecture variant or profile.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub struct Variant {
@@ -815,6 +1700,11 @@ impl Variant {
             abi: Some(abi.into()),
         }
     }
+
+    /// The variant, or `None` when no field is set.
+    pub fn non_empty(self) -> Option<Self> {
+        (self != Self::default()).then_some(self)
+    }
 }
 
 impl fmt::Display for Variant {
@@ -841,8 +1731,9 @@ pub struct ClassificationResult {
     pub endianness: Endianness,
     /// File format
     pub format: FileFormat,
-    /// Architecture variant
-    pub variant: Variant,
+    /// Architecture variant (`None` when the format or heuristics say
+    /// nothing beyond the ISA)
+    pub variant: Option<Variant>,
     /// Detected extensions
     pub extensions: Vec<Extension>,
     /// Overall confidence (0.0 - 1.0)
@@ -851,6 +1742,14 @@ pub struct ClassificationResult {
     pub source: ClassificationSource,
     /// Additional metadata
     pub metadata: ClassificationMetadata,
+    /// Ranked heuristic candidates; for fat Mach-O binaries, one entry per
+    /// slice. Empty for other format-based results
+    #[serde(default)]
+    pub candidates: Vec<IsaCandidate>,
+    /// Distinctive instruction idioms the winning scorer counted (`None` for
+    /// format-based results and for scorers that keep no such tally)
+    #[serde(default)]
+    pub match_count: Option<u64>,
 }
 
 impl ClassificationResult {
@@ -861,11 +1760,13 @@ impl ClassificationResult {
             bitwidth,
             endianness,
             format,
-            variant: Variant::default(),
+            variant: None,
             extensions: Vec::new(),
             confidence: 1.0,
             source: ClassificationSource::FileFormat,
             metadata: ClassificationMetadata::default(),
+            candidates: Vec::new(),
+            match_count: None,
         }
     }
 
@@ -881,11 +1782,13 @@ impl ClassificationResult {
             bitwidth,
             endianness,
             format: FileFormat::Raw,
-            variant: Variant::default(),
+            variant: None,
             extensions: Vec::new(),
-            confidence,
+            confidence: round_confidence(confidence),
             source: ClassificationSource::Heuristic,
             metadata: ClassificationMetadata::default(),
+            candidates: Vec::new(),
+            match_count: None,
         }
     }
 
@@ -897,10 +1800,15 @@ impl ClassificationResult {
 
     /// Set the variant.
     pub fn with_variant(mut self, variant: Variant) -> Self {
-        self.variant = variant;
+        self.variant = Some(variant);
         self
     }
 
+    /// Name of the variant, if any.
+    pub fn variant_name(&self) -> Option<&str> {
+        self.variant.as_ref().map(|v| v.name.as_str())
+    }
+
     /// Add metadata.
     pub fn with_metadata(mut self, metadata: ClassificationMetadata) -> Self {
         self.metadata = metadata;
@@ -916,6 +1824,32 @@ impl ClassificationResult {
     pub fn extension_names(&self) -> Vec<&str> {
         self.extensions.iter().map(|e| e.name.as_str()).collect()
     }
+
+    /// Candidate Rust/LLVM target triples for this result, most likely
+    /// first; empty when the combination is not covered.
+    ///
+    /// See [`crate::triple`] for the mapping and the hints that reorder it.
+    pub fn suggest_target_triples(&self) -> Vec<String> {
+        crate::triple::suggest(
+            self.isa,
+            self.endianness,
+            self.variant.as_ref(),
+            self.format,
+            libc_hint(&self.metadata.custom),
+        )
+    }
+}
+
+/// The identified C library from a `libc` metadata entry, if any.
+fn libc_hint(entries: &[MetadataEntry]) -> Option<&str> {
+    entries
+        .iter()
+        .find_map(|entry| match (&entry.key, &entry.value) {
+            (MetadataKey::Custom(key), MetadataValue::String(value)) if key == "libc" => {
+                Some(value.as_str())
+            }
+            _ => None,
+        })
 }
 
 impl fmt::Display for ClassificationResult {
@@ -930,8 +1864,8 @@ impl fmt::Display for ClassificationResult {
             self.confidence * 100.0
         )?;
 
-        if !self.variant.name.is_empty() {
-            write!(f, "\n  Variant: {}", self.variant)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "\n  Variant: {variant}")?;
         }
 
         if !self.extensions.is_empty() {
@@ -975,10 +1909,107 @@ pub struct ClassificationMetadata {
     pub code_size: Option<u64>,
     /// ELF e_flags or equivalent
     pub flags: Option<u32>,
-    /// Raw machine type value
+    /// Format-native machine identifier, kept even when the ISA is unknown.
+    ///
+    /// | Format | Field |
+    /// |--------|-------|
+    /// | ELF, fat ELF | `e_machine` (first record for fat ELF) |
+    /// | PE, COFF | `IMAGE_FILE_HEADER.Machine` |
+    /// | Mach-O, fat Mach-O | `cputype` (first slice for fat) |
+    /// | LLVM wrapped bitcode | wrapper `cputype` |
+    /// | XCOFF, ECOFF | file header `f_magic` |
+    /// | BSD a.out | `a_midmag` machine ID |
+    /// | Plan 9 a.out | header magic |
+    /// | Minix a.out | `a_cpu` |
+    /// | LE/LX | CPU type |
+    /// | PEF | `architecture` tag (e.g. `pwpc`) |
+    /// | SOM | `system_id` |
+    /// | uImage | `ih_arch` |
+    /// | ESP firmware | extended header chip ID |
+    ///
+    /// Formats whose headers carry no machine field leave this `None`.
     pub raw_machine: Option<u32>,
     /// Additional notes
     pub notes: Vec<String>,
+    /// Parser warnings, surfaced as warning-level notes in a payload
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Format-specific metadata entries (typically `MetadataKey::Custom`)
+    #[serde(default)]
+    pub custom: Vec<MetadataEntry>,
+    /// File ranges `(offset, length)` whose bytes are not analyzable code
+    /// (e.g. encrypted text); code-pattern extension detection skips them
+    #[serde(default)]
+    pub opaque_ranges: Vec<(u64, u64)>,
+    /// File ranges `(offset, length)` the format declares executable
+    /// (code sections, or executable segments when sections are stripped);
+    /// empty when the format does not say. See
+    /// [`ClassifierOptions::scan_executable_only`]
+    #[serde(default)]
+    pub code_ranges: Vec<(u64, u64)>,
+    /// Bytes of file header and header tables the parser read, from offset 0
+    #[serde(default)]
+    pub header_size: Option<u64>,
+    /// File size the headers describe (one past the last byte of any header,
+    /// section or segment), not clamped to the input
+    #[serde(default)]
+    pub described_size: Option<u64>,
+}
+
+/// Where heuristic scoring reads a raw input (see
+/// [`ClassifierOptions::sample_mode`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SampleMode {
+    /// The first `max_scan_bytes` informative bytes
+    #[default]
+    Prefix,
+    /// `windows` windows of `window_size` bytes each, evenly spaced from
+    /// the start of the input to its end
+    Strided {
+        /// Number of windows
+        windows: usize,
+        /// Bytes per window
+        window_size: usize,
+    },
+    /// `n_samples` windows evenly spaced through the input, sharing the
+    /// `max_scan_bytes` budget equally
+    Uniform {
+        /// Number of windows
+        n_samples: usize,
+    },
+}
+
+impl SampleMode {
+    /// The `(start, end)` windows to score in an input of `len` bytes.
+    ///
+    /// `None` means prefix scanning: the mode is [`SampleMode::Prefix`], the
+    /// input fits in `max_scan_bytes`, or the windows would cover it all.
+    pub fn windows(self, len: usize, max_scan_bytes: usize) -> Option<Vec<(usize, usize)>> {
+        let (count, size) = match self {
+            SampleMode::Prefix => return None,
+            SampleMode::Strided {
+                windows,
+                window_size,
+            } => (windows.max(1), window_size.max(1)),
+            SampleMode::Uniform { n_samples } => {
+                let count = n_samples.max(1);
+                (count, (max_scan_bytes / count).max(1))
+            }
+        };
+        if len <= max_scan_bytes || count.saturating_mul(size) >= len {
+            return None;
+        }
+        // The last window ends at the end of the input
+        let step = (len - size) / (count - 1).max(1);
+        Some(
+            (0..count)
+                .map(|k| {
+                    let start = if k + 1 == count { len - size } else { k * step };
+                    (start, start + size)
+                })
+                .collect(),
+        )
+    }
 }
 
 /// Options for classification behavior.
@@ -989,22 +2020,122 @@ pub struct ClassifierOptions {
     /// Enable deep heuristic scanning
     pub deep_scan: bool,
     /// Maximum bytes to scan for heuristics
+    ///
+    /// The window covers the first `max_scan_bytes` informative bytes (long
+    /// runs of one byte value are skipped). Scorers never read past it and
+    /// each trims it to whole instruction slots, so a pattern straddling the
+    /// cut-off never counts. Windows of at least
+    /// [`ClassifierOptions::MIN_STABLE_SCAN_BYTES`] pick the same winning
+    /// ISA for code inputs when the length changes by up to 16 bytes.
     pub max_scan_bytes: usize,
+    /// How heuristic scoring samples inputs larger than `max_scan_bytes`:
+    /// the prefix (the default), or windows spread through the whole input
+    /// whose scores are summed. Sampling finds code deep inside large disk
+    /// and flash images without scanning all of it.
+    pub sample_mode: SampleMode,
     /// Enable extension detection
     pub detect_extensions: bool,
     /// Prefer speed over accuracy
     pub fast_mode: bool,
+    /// Collect the section/segment inventory for formats that have one
+    pub collect_sections: bool,
+    /// Maximum sections (and segments) to collect; 0 uses
+    /// [`ClassifierOptions::DEFAULT_MAX_SECTIONS`]
+    pub max_sections: usize,
+    /// Identify the C library linked into static binaries
+    pub detect_libc: bool,
+    /// Add a warning note when the classified ISA is discontinued
+    pub warn_deprecated: bool,
+    /// Report plain-text inputs as text instead of running the heuristic scorers
+    pub screen_text: bool,
+    /// Enumerate further ELF images packed back-to-back after the first
+    pub scan_concatenated_elf: bool,
+    /// Check raw inputs for 16/32-bit byte swapping even when the unswapped
+    /// classification is confident (it is always checked when it is not)
+    pub detect_byte_swap: bool,
+    /// Add the ISA's register file ([`Isa::register_info`]) as metadata
+    pub report_registers: bool,
+    /// Summarize PE/ELF/Mach-O exploit mitigations into
+    /// [`DetectionPayload::hardening`]
+    pub report_hardening: bool,
+    /// Maximum ranked candidates reported with a heuristic result, or
+    /// slices listed as candidates for a fat Mach-O; 0 reports the primary
+    /// classification only and `usize::MAX` the full ranking
+    pub max_candidates: usize,
+    /// Infer whether raw code is position-independent or linked at a fixed
+    /// load address, and report the base (see [`crate::heuristics::addressing`])
+    pub infer_addressing: bool,
+    /// Accept only formats with an unambiguous magic number; inputs whose
+    /// format is decided heuristically (see
+    /// [`DetectedFormat::is_heuristic`](crate::formats::DetectedFormat::is_heuristic))
+    /// and headerless .COM images are analyzed as raw code instead
+    pub strict_magic: bool,
+    /// Assess raw inputs that fit in a 64 KiB segment as headerless CP/M or
+    /// DOS .COM images (see [`crate::formats::com`]). The assessment
+    /// replaces the heuristic result only when the x86 and Z80 scorers back
+    /// it, and the heuristic candidates are kept
+    pub detect_com: bool,
+    /// Restrict heuristic scoring to these ISAs; empty allows every ISA.
+    /// Narrows the search when the candidate architectures are known, e.g.
+    /// for a firmware image from a known SoC
+    pub allowed_isas: Vec<Isa>,
+    /// Maximum bytes of each contiguous multi-ISA region scanned for
+    /// extensions; 0 uses [`ClassifierOptions::DEFAULT_REGION_EXTENSION_BUDGET`]
+    pub region_extension_budget: usize,
+    /// Scan only the ranges a format declares executable
+    /// ([`ClassificationMetadata::code_ranges`]) for code-pattern
+    /// extensions. Read-only data and debug info in ELF, PE and Mach-O files
+    /// otherwise yield false positives; raw inputs declare no ranges and
+    /// are scanned in full either way
+    pub scan_executable_only: bool,
+    /// Length of the whole input the classified bytes were read from; `None`
+    /// when unknown. File-based APIs set it. A structure the format declares
+    /// past the bytes at hand is reported as beyond the scan limit when it
+    /// lies within this length, and as truncation when it runs past it
+    pub input_len: Option<u64>,
 }
 
 impl ClassifierOptions {
+    /// Default cap on collected sections/segments.
+    pub const DEFAULT_MAX_SECTIONS: usize = 128;
+
+    /// Default cap on reported candidates.
+    pub const DEFAULT_MAX_CANDIDATES: usize = 10;
+
+    /// Default cap on the bytes of one multi-ISA region scanned for
+    /// extensions.
+    pub const DEFAULT_REGION_EXTENSION_BUDGET: usize = 4 * 1024 * 1024;
+
+    /// Smallest `max_scan_bytes` whose winner is stable against small
+    /// changes in window length (see [`ClassifierOptions::max_scan_bytes`]).
+    pub const MIN_STABLE_SCAN_BYTES: usize = 256;
+
     /// Create options with default settings.
     pub fn new() -> Self {
         Self {
             min_confidence: 0.3,
             deep_scan: false,
             max_scan_bytes: 1024 * 1024, // 1MB
+            sample_mode: SampleMode::Prefix,
             detect_extensions: true,
             fast_mode: false,
+            collect_sections: false,
+            max_sections: Self::DEFAULT_MAX_SECTIONS,
+            detect_libc: false,
+            warn_deprecated: false,
+            screen_text: true,
+            scan_concatenated_elf: false,
+            detect_byte_swap: false,
+            report_registers: false,
+            report_hardening: false,
+            max_candidates: Self::DEFAULT_MAX_CANDIDATES,
+            infer_addressing: false,
+            strict_magic: false,
+            detect_com: false,
+            allowed_isas: Vec::new(),
+            region_extension_budget: Self::DEFAULT_REGION_EXTENSION_BUDGET,
+            scan_executable_only: true,
+            input_len: None,
         }
     }
 
@@ -1014,8 +2145,26 @@ impl ClassifierOptions {
             min_confidence: 0.2,
             deep_scan: true,
             max_scan_bytes: 10 * 1024 * 1024, // 10MB
+            sample_mode: SampleMode::Prefix,
             detect_extensions: true,
             fast_mode: false,
+            collect_sections: false,
+            max_sections: Self::DEFAULT_MAX_SECTIONS,
+            detect_libc: false,
+            warn_deprecated: false,
+            screen_text: true,
+            scan_concatenated_elf: false,
+            detect_byte_swap: false,
+            report_registers: false,
+            report_hardening: true,
+            max_candidates: Self::DEFAULT_MAX_CANDIDATES,
+            infer_addressing: false,
+            strict_magic: false,
+            detect_com: false,
+            allowed_isas: Vec::new(),
+            region_extension_budget: Self::DEFAULT_REGION_EXTENSION_BUDGET,
+            scan_executable_only: true,
+            input_len: None,
         }
     }
 
@@ -1025,8 +2174,26 @@ impl ClassifierOptions {
             min_confidence: 0.5,
             deep_scan: false,
             max_scan_bytes: 64 * 1024, // 64KB
+            sample_mode: SampleMode::Prefix,
             detect_extensions: false,
             fast_mode: true,
+            collect_sections: false,
+            max_sections: Self::DEFAULT_MAX_SECTIONS,
+            detect_libc: false,
+            warn_deprecated: false,
+            screen_text: true,
+            scan_concatenated_elf: false,
+            detect_byte_swap: false,
+            report_registers: false,
+            report_hardening: false,
+            max_candidates: Self::DEFAULT_MAX_CANDIDATES,
+            infer_addressing: false,
+            strict_magic: false,
+            detect_com: false,
+            allowed_isas: Vec::new(),
+            region_extension_budget: Self::DEFAULT_REGION_EXTENSION_BUDGET,
+            scan_executable_only: true,
+            input_len: None,
         }
     }
 }
@@ -1035,6 +2202,210 @@ impl ClassifierOptions {
 // Detection Payload Types - Structured output for formatters
 // =============================================================================
 
+/// A section from the format's section table (e.g., ELF section headers).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionInfo {
+    /// Section name (empty if the string table is unavailable)
+    pub name: String,
+    /// File offset of the section contents
+    pub offset: u64,
+    /// Size of the section in bytes
+    pub size: u64,
+    /// Section contains executable instructions
+    pub flags_execinstr: bool,
+    /// Section is writable at runtime
+    pub flags_write: bool,
+}
+
+/// A segment from the format's program header table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentInfo {
+    /// Segment type name (e.g., "LOAD", "INTERP")
+    pub kind: String,
+    /// File offset of the segment
+    pub offset: u64,
+    /// Virtual address of the segment
+    pub vaddr: u64,
+    /// Size of the segment in the file
+    pub file_size: u64,
+    /// Size of the segment in memory
+    pub mem_size: u64,
+    /// Segment is readable
+    pub flags_read: bool,
+    /// Segment is writable
+    pub flags_write: bool,
+    /// Segment is executable
+    pub flags_execute: bool,
+}
+
+/// Exploit-mitigation posture read from PE, ELF or Mach-O headers.
+///
+/// The common flags are set for every format; the `Option` flags are `None`
+/// for formats that have no such mechanism.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardeningSummary {
+    /// Image can be loaded at a randomized base: ELF PIE (`ET_DYN` with an
+    /// interpreter), PE `DYNAMIC_BASE`, Mach-O `MH_PIE`
+    pub pie: bool,
+    /// Stack and data are not executable: ELF `PT_GNU_STACK` without `PF_X`,
+    /// PE `NX_COMPAT`, Mach-O without `MH_ALLOW_STACK_EXECUTION`
+    pub nx: bool,
+    /// ELF `PT_GNU_RELRO` segment present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relro: Option<bool>,
+    /// ELF immediate binding (`DT_BIND_NOW`, `DF_BIND_NOW` or `DF_1_NOW`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_now: Option<bool>,
+    /// ELF dynamic string table references `__stack_chk_fail`; `None` when
+    /// there is no dynamic string table to check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_canary: Option<bool>,
+    /// PE `HIGH_ENTROPY_VA` (64-bit ASLR)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_entropy_va: Option<bool>,
+    /// PE `GUARD_CF` (Control Flow Guard)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_flow_guard: Option<bool>,
+    /// Mach-O `MH_NO_HEAP_EXECUTION`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_heap_execution: Option<bool>,
+    /// Mach-O `__RESTRICT` segment (ignores `DYLD_*` environment variables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restrict: Option<bool>,
+    /// One-line summary of the flags above, e.g. "PIE, NX, full RELRO, canary"
+    pub note: String,
+}
+
+impl HardeningSummary {
+    /// Whether both RELRO and immediate binding are present.
+    pub fn full_relro(&self) -> bool {
+        self.relro == Some(true) && self.bind_now == Some(true)
+    }
+
+    /// Fill in [`HardeningSummary::note`] from the flags.
+    pub fn with_note(mut self) -> Self {
+        let mut parts = vec![
+            if self.pie { "PIE" } else { "no PIE" },
+            if self.nx { "NX" } else { "no NX" },
+        ];
+        match self.relro {
+            Some(true) if self.full_relro() => parts.push("full RELRO"),
+            Some(true) => parts.push("partial RELRO"),
+            Some(false) => parts.push("no RELRO"),
+            None => {}
+        }
+        let optional = [
+            (self.stack_canary, "canary", "no canary"),
+            (
+                self.high_entropy_va,
+                "high-entropy VA",
+                "no high-entropy VA",
+            ),
+            (self.control_flow_guard, "CFG", "no CFG"),
+            (self.no_heap_execution, "heap NX", "executable heap"),
+            (self.restrict, "restricted", "unrestricted"),
+        ];
+        for (flag, yes, no) in optional {
+            if let Some(set) = flag {
+                parts.push(if set { yes } else { no });
+            }
+        }
+        self.note = parts.join(", ");
+        self
+    }
+}
+
+/// Operating system a binary targets, as resolved by [`crate::os`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsFamily {
+    /// Linux (GNU, musl and uClibc userlands)
+    Linux,
+    /// Android
+    Android,
+    /// Microsoft Windows
+    Windows,
+    /// macOS (including Mac Catalyst and DriverKit)
+    MacOs,
+    /// iOS and the other iPhone-derived Apple platforms
+    Ios,
+    /// FreeBSD
+    FreeBsd,
+    /// OpenBSD
+    OpenBsd,
+    /// NetBSD
+    NetBsd,
+    /// Solaris and illumos
+    Solaris,
+    /// IBM AIX
+    Aix,
+    /// IBM z/OS
+    ZOs,
+    /// No operating system: firmware, boot loaders, kernels
+    BareMetal,
+    /// UEFI applications and drivers
+    Uefi,
+    /// A game console, by name (e.g. "PlayStation 4")
+    GameConsole(String),
+    /// WebAssembly without a system interface
+    Wasm,
+    /// WebAssembly against WASI
+    Wasi,
+    /// An operating system outside this list
+    Unknown,
+}
+
+impl fmt::Display for OsFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OsFamily::Linux => "Linux",
+            OsFamily::Android => "Android",
+            OsFamily::Windows => "Windows",
+            OsFamily::MacOs => "macOS",
+            OsFamily::Ios => "iOS",
+            OsFamily::FreeBsd => "FreeBSD",
+            OsFamily::OpenBsd => "OpenBSD",
+            OsFamily::NetBsd => "NetBSD",
+            OsFamily::Solaris => "Solaris",
+            OsFamily::Aix => "AIX",
+            OsFamily::ZOs => "z/OS",
+            OsFamily::BareMetal => "bare metal",
+            OsFamily::Uefi => "UEFI",
+            OsFamily::GameConsole(name) => name,
+            OsFamily::Wasm => "WebAssembly",
+            OsFamily::Wasi => "WASI",
+            OsFamily::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The operating system a binary targets, combined from every OS signal
+/// the analysis found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsFingerprint {
+    /// Resolved OS family
+    pub family: OsFamily,
+    /// Minimum OS or kernel version, when a signal carries one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_hint: Option<String>,
+    /// The signals that agree with `family`, highest precedence first
+    pub evidence: Vec<String>,
+    /// Lower-precedence signals that named a different OS
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+impl fmt::Display for OsFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.family)?;
+        if let Some(version) = &self.version_hint {
+            write!(f, " {version}")?;
+        }
+        Ok(())
+    }
+}
+
 /// An architecture slice within a multi-architecture container (e.g., fat Mach-O).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainedArch {
@@ -1071,12 +2442,47 @@ pub struct DetectionPayload {
     /// Contained architecture slices (populated for multi-arch containers like fat Mach-O)
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub slices: Vec<ContainedArch>,
+    /// Section inventory (populated when `ClassifierOptions::collect_sections` is set)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<SectionInfo>,
+    /// Segment inventory (populated when `ClassifierOptions::collect_sections` is set)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<SegmentInfo>,
     /// Detected ISA extensions
     pub extensions: Vec<ExtensionDetection>,
+    /// Whether code-pattern extension detection ran for an ISA that has a
+    /// detector. When false, an empty `extensions` list means "not
+    /// analyzed" rather than "none present".
+    #[serde(default)]
+    pub extensions_analyzed: bool,
     /// Extracted metadata items
     pub metadata: Vec<MetadataEntry>,
     /// Analysis notes and warnings
     pub notes: Vec<Note>,
+    /// Size of the input in bytes
+    #[serde(default)]
+    pub input_size: u64,
+    /// Bytes the analysis examined: the headers for a format parse, the
+    /// capped window for heuristics, and the whole input (minus opaque
+    /// ranges) when code-pattern extension detection ran. `None` when the
+    /// parser does not report its header extent.
+    #[serde(default)]
+    pub scanned_bytes: Option<u64>,
+    /// File size the format headers describe (PE sections, ELF and Mach-O
+    /// segments); input beyond it is overlay data. `None` for raw inputs and
+    /// formats that do not report it.
+    #[serde(default)]
+    pub described_size: Option<u64>,
+    /// Exploit-mitigation flags (populated when
+    /// `ClassifierOptions::report_hardening` is set, for PE, ELF and Mach-O)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardening: Option<HardeningSummary>,
+    /// Observations behind the primary classification, strongest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    /// Target operating system resolved from all OS signals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<OsFingerprint>,
 }
 
 impl DetectionPayload {
@@ -1087,12 +2493,31 @@ impl DetectionPayload {
             primary,
             candidates: Vec::new(),
             slices: Vec::new(),
+            sections: Vec::new(),
+            segments: Vec::new(),
             extensions: Vec::new(),
+            extensions_analyzed: false,
             metadata: Vec::new(),
             notes: Vec::new(),
+            input_size: 0,
+            scanned_bytes: None,
+            described_size: None,
+            hardening: None,
+            evidence: Vec::new(),
+            os: None,
         }
     }
 
+    /// One line per [`Evidence`] entry explaining the primary
+    /// classification; empty when no evidence was collected.
+    pub fn explain(&self) -> String {
+        self.evidence
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Add an ISA candidate.
     pub fn with_candidate(mut self, candidate: IsaCandidate) -> Self {
         self.candidates.push(candidate);
@@ -1129,6 +2554,35 @@ impl DetectionPayload {
         self
     }
 
+    /// Extensions that are not part of the ISA or variant baseline.
+    pub fn optional_extensions(&self) -> impl Iterator<Item = &ExtensionDetection> {
+        self.extensions.iter().filter(|e| !e.baseline)
+    }
+
+    /// Structural fingerprint of `data`, the input this payload describes.
+    ///
+    /// Meant for recognising the same program across rebuilds; not a
+    /// security mechanism. See [`crate::fingerprint`] for the features and
+    /// the similarity property.
+    pub fn structural_fingerprint(&self, data: &[u8]) -> crate::fingerprint::Fingerprint {
+        crate::fingerprint::Fingerprint::compute(self, data)
+    }
+
+    /// Candidate Rust/LLVM target triples for the primary classification,
+    /// most likely first; empty when the combination is not covered.
+    ///
+    /// A `libc` metadata entry (see [`ClassifierOptions::detect_libc`])
+    /// moves the matching environment to the front.
+    pub fn suggest_target_triples(&self) -> Vec<String> {
+        crate::triple::suggest(
+            self.primary.isa,
+            self.primary.endianness,
+            self.primary.variant.as_ref(),
+            self.format.format,
+            libc_hint(&self.metadata),
+        )
+    }
+
     /// Convert to legacy ClassificationResult for backwards compatibility.
     pub fn to_classification_result(&self) -> ClassificationResult {
         ClassificationResult {
@@ -1136,11 +2590,13 @@ impl DetectionPayload {
             bitwidth: self.primary.bitwidth,
             endianness: self.primary.endianness,
             format: self.format.format,
-            variant: self.primary.variant.clone().unwrap_or_default(),
+            variant: self.primary.variant.clone(),
             extensions: self.extensions.iter().map(|e| e.to_extension()).collect(),
             confidence: self.primary.confidence,
             source: self.primary.source,
             metadata: self.to_classification_metadata(),
+            candidates: self.candidates.clone(),
+            match_count: self.primary.match_count,
         }
     }
 
@@ -1179,10 +2635,16 @@ impl DetectionPayload {
                         meta.raw_machine = Some(h);
                     }
                 }
-                MetadataKey::Custom(_) => {}
+                MetadataKey::Custom(_) => meta.custom.push(entry.clone()),
             }
         }
         meta.notes = self.notes.iter().map(|n| n.message.clone()).collect();
+        meta.warnings = self
+            .notes
+            .iter()
+            .filter(|n| n.level >= NoteLevel::Warning)
+            .map(|n| n.message.clone())
+            .collect();
         meta
     }
 }
@@ -1232,6 +2694,21 @@ impl FormatDetection {
     }
 }
 
+/// Steps per unit of stored confidence (a resolution of 1e-4).
+pub const CONFIDENCE_SCALE: f64 = 10_000.0;
+
+/// Clamp `confidence` to 0.0 - 1.0 and round it to 4 decimal places.
+///
+/// Applied where classifications and candidates are built, so serialized
+/// confidences read `0.3333` rather than `0.33333333333333331` and compare
+/// equal across machines. NaN maps to 0.0.
+pub fn round_confidence(confidence: f64) -> f64 {
+    if confidence.is_nan() {
+        return 0.0;
+    }
+    (confidence.clamp(0.0, 1.0) * CONFIDENCE_SCALE).round() / CONFIDENCE_SCALE
+}
+
 /// Primary ISA classification result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IsaClassification {
@@ -1247,6 +2724,10 @@ pub struct IsaClassification {
     pub source: ClassificationSource,
     /// Architecture variant/profile
     pub variant: Option<Variant>,
+    /// Distinctive instruction idioms behind a heuristic decision
+    /// (see [`IsaClassification::match_count`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) match_count: Option<u64>,
 }
 
 impl IsaClassification {
@@ -1259,6 +2740,7 @@ impl IsaClassification {
             confidence: 1.0,
             source: ClassificationSource::FileFormat,
             variant: None,
+            match_count: None,
         }
     }
 
@@ -1273,9 +2755,10 @@ impl IsaClassification {
             isa,
             bitwidth,
             endianness,
-            confidence,
+            confidence: round_confidence(confidence),
             source: ClassificationSource::Heuristic,
             variant: None,
+            match_count: None,
         }
     }
 
@@ -1284,10 +2767,26 @@ impl IsaClassification {
         self.variant = Some(variant);
         self
     }
+
+    /// Set the instruction-evidence count.
+    pub fn with_match_count(mut self, match_count: u64) -> Self {
+        self.match_count = Some(match_count);
+        self
+    }
+
+    /// Number of distinctive instruction idioms (returns, calls, prologues
+    /// and the like) the winning scorer counted.
+    ///
+    /// A handful of matches behind a confident-looking verdict means the
+    /// decision rests on little actual code. `None` for format-based results
+    /// and for architectures whose scorer keeps no idiom tally.
+    pub fn match_count(&self) -> Option<u64> {
+        self.match_count
+    }
 }
 
 /// ISA candidate from heuristic analysis.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IsaCandidate {
     /// The ISA
     pub isa: Isa,
@@ -1315,7 +2814,7 @@ impl IsaCandidate {
             bitwidth,
             endianness,
             raw_score,
-            confidence,
+            confidence: round_confidence(confidence),
         }
     }
 }
@@ -1331,6 +2830,10 @@ pub struct ExtensionDetection {
     pub confidence: f64,
     /// How it was detected
     pub source: ExtensionSource,
+    /// Guaranteed by the ISA or variant baseline (e.g. SSE2 on x86-64)
+    /// rather than an optional feature
+    #[serde(default)]
+    pub baseline: bool,
 }
 
 impl ExtensionDetection {
@@ -1343,8 +2846,9 @@ impl ExtensionDetection {
         Self {
             name: name.into(),
             category,
-            confidence,
+            confidence: round_confidence(confidence),
             source: ExtensionSource::CodePattern,
+            baseline: false,
         }
     }
 
@@ -1355,6 +2859,18 @@ impl ExtensionDetection {
             category,
             confidence: 1.0,
             source: ExtensionSource::FormatAttribute,
+            baseline: false,
+        }
+    }
+
+    /// Create as implied by the ISA variant.
+    pub fn from_variant(name: impl Into<String>, category: ExtensionCategory) -> Self {
+        Self {
+            name: name.into(),
+            category,
+            confidence: 1.0,
+            source: ExtensionSource::VariantImplied,
+            baseline: false,
         }
     }
 
@@ -1376,6 +2892,16 @@ pub enum ExtensionSource {
     VariantImplied,
 }
 
+impl fmt::Display for ExtensionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtensionSource::CodePattern => write!(f, "code"),
+            ExtensionSource::FormatAttribute => write!(f, "format"),
+            ExtensionSource::VariantImplied => write!(f, "variant"),
+        }
+    }
+}
+
 /// Metadata entry with typed key and value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetadataEntry {
@@ -1385,6 +2911,10 @@ pub struct MetadataEntry {
     pub value: MetadataValue,
     /// Human-readable label
     pub label: String,
+    /// Parser or analysis that produced the entry (e.g. "elf", "pe",
+    /// "heuristics")
+    #[serde(default, skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub origin: Option<&'static str>,
 }
 
 impl MetadataEntry {
@@ -1394,9 +2924,16 @@ impl MetadataEntry {
             key,
             value,
             label: label.into(),
+            origin: None,
         }
     }
 
+    /// Tag the entry with the parser or analysis that produced it.
+    pub fn with_origin(mut self, origin: &'static str) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
     /// Create entry point metadata.
     pub fn entry_point(addr: u64) -> Self {
         Self::new(
@@ -1428,6 +2965,11 @@ impl MetadataEntry {
             "Machine Type",
         )
     }
+
+    /// Create format-specific metadata under a custom key.
+    pub fn custom(key: impl Into<String>, value: MetadataValue, label: impl Into<String>) -> Self {
+        Self::new(MetadataKey::Custom(key.into()), value, label)
+    }
 }
 
 /// Metadata key types.
@@ -1462,6 +3004,22 @@ pub enum MetadataValue {
     String(String),
     /// Hex value (for flags, machine types)
     Hex(u32),
+    /// Boolean value
+    Bool(bool),
+}
+
+impl fmt::Display for MetadataKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataKey::EntryPoint => write!(f, "entry_point"),
+            MetadataKey::SectionCount => write!(f, "section_count"),
+            MetadataKey::SymbolCount => write!(f, "symbol_count"),
+            MetadataKey::CodeSize => write!(f, "code_size"),
+            MetadataKey::Flags => write!(f, "flags"),
+            MetadataKey::RawMachine => write!(f, "raw_machine"),
+            MetadataKey::Custom(key) => write!(f, "{key}"),
+        }
+    }
 }
 
 impl fmt::Display for MetadataValue {
@@ -1471,6 +3029,7 @@ impl fmt::Display for MetadataValue {
             MetadataValue::Integer(n) => write!(f, "{}", n),
             MetadataValue::String(s) => write!(f, "{}", s),
             MetadataValue::Hex(h) => write!(f, "0x{:08X}", h),
+            MetadataValue::Bool(b) => write!(f, "{b}"),
         }
     }
 }
@@ -1522,7 +3081,9 @@ impl Note {
 }
 
 /// Note severity level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Levels are ordered by severity, so `Info < Warning < Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum NoteLevel {
     /// Informational
@@ -1533,10 +3094,99 @@ pub enum NoteLevel {
     Error,
 }
 
+/// Kind of observation that supports a classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    /// Format magic bytes were recognized
+    MagicMatch,
+    /// A header field (machine type, CPU type, flags) names the ISA
+    HeaderField,
+    /// Instruction patterns scored for an ISA
+    InstructionPattern,
+    /// A section name characteristic of a toolchain or ISA
+    SectionName,
+    /// An analysis note that qualifies the result
+    Note,
+}
+
+impl fmt::Display for EvidenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EvidenceKind::MagicMatch => "magic",
+            EvidenceKind::HeaderField => "header",
+            EvidenceKind::InstructionPattern => "pattern",
+            EvidenceKind::SectionName => "section",
+            EvidenceKind::Note => "note",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One machine-readable reason behind a detection.
+///
+/// [`DetectionPayload::evidence`] lists these strongest first, so a UI can
+/// render "why" without parsing notes; [`DetectionPayload::explain`] joins
+/// them into a single explanation string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evidence {
+    /// What was observed
+    pub kind: EvidenceKind,
+    /// Human-readable description
+    pub description: String,
+    /// Contribution to the result (0.0 - 1.0)
+    pub weight: f64,
+    /// Input offset the observation refers to, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl Evidence {
+    /// Create an evidence entry without an offset.
+    pub fn new(kind: EvidenceKind, description: impl Into<String>, weight: f64) -> Self {
+        Self {
+            kind,
+            description: description.into(),
+            weight,
+            offset: None,
+        }
+    }
+
+    /// Attach the input offset the observation refers to.
+    pub fn at(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl fmt::Display for Evidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind, self.description)?;
+        if let Some(offset) = self.offset {
+            write!(f, " at 0x{offset:X}")?;
+        }
+        write!(f, " (weight {:.2})", self.weight)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_file_format_typical_extensions() {
+        assert_eq!(FileFormat::Elf.typical_extensions()[0], "");
+        assert!(FileFormat::Elf.typical_extensions().contains(&".so"));
+        assert_eq!(
+            FileFormat::Pe.typical_extensions(),
+            &[".exe", ".dll", ".sys", ".efi", ".ocx", ".scr"]
+        );
+        assert_eq!(FileFormat::Wasm.typical_extensions(), &[".wasm"]);
+        assert_eq!(FileFormat::JavaClass.typical_extensions(), &[".class"]);
+        assert!(FileFormat::Srec.typical_extensions().contains(&".s19"));
+        assert!(FileFormat::MvsLoad.typical_extensions().is_empty());
+    }
+
     #[test]
     fn test_isa_display() {
         assert_eq!(Isa::X86_64.to_string(), "x86_64");
@@ -1552,6 +3202,165 @@ mod tests {
         assert_eq!(Isa::RiscV128.default_bitwidth(), 128);
     }
 
+    #[test]
+    fn test_isa_family() {
+        assert_eq!(Isa::Arm.family(), IsaFamily::Arm);
+        assert_eq!(Isa::AArch64.family(), IsaFamily::Arm);
+        assert_eq!(Isa::RiscV64.family(), Isa::RiscV32.family());
+        assert_ne!(Isa::X86.family(), Isa::Arm.family());
+        assert_eq!(Isa::Unknown(0).family(), IsaFamily::Other);
+        assert_eq!(IsaFamily::Arm.to_string(), "ARM");
+    }
+
+    #[test]
+    fn test_format_family() {
+        assert_eq!(FileFormat::Dex.family(), FileFormat::Odex.family());
+        assert_eq!(FileFormat::Dex.family(), FormatFamily::Android);
+        assert_eq!(FileFormat::MachOFat.family(), FileFormat::MachO.family());
+        assert_eq!(FileFormat::FatElf.family(), FormatFamily::Elf);
+        assert_eq!(FileFormat::Ne.family(), FileFormat::Com.family());
+        assert_ne!(FileFormat::Elf.family(), FileFormat::Pe.family());
+        assert_eq!(FormatFamily::DosLegacy.to_string(), "DOS/Windows-legacy");
+    }
+
+    #[test]
+    fn test_format_endianness_hint() {
+        assert_eq!(
+            FileFormat::JavaClass.endianness_hint(),
+            Some(Endianness::Big)
+        );
+        assert_eq!(FileFormat::Wasm.endianness_hint(), Some(Endianness::Little));
+        assert_eq!(FileFormat::Dex.endianness_hint(), Some(Endianness::Little));
+        assert_eq!(FileFormat::Dol.endianness_hint(), Some(Endianness::Big));
+        for format in [
+            FileFormat::Elf,
+            FileFormat::Pe,
+            FileFormat::MachO,
+            FileFormat::Raw,
+        ] {
+            assert_eq!(format.endianness_hint(), None, "{format}");
+        }
+    }
+
+    #[test]
+    fn test_isa_codes_are_pinned() {
+        // Stored codes must never change; only append new entries.
+        let pinned = [
+            (Isa::X86, 1),
+            (Isa::X86_64, 2),
+            (Isa::Arm, 3),
+            (Isa::AArch64, 4),
+            (Isa::RiscV32, 5),
+            (Isa::RiscV64, 6),
+            (Isa::RiscV128, 7),
+            (Isa::Mips, 8),
+            (Isa::Mips64, 9),
+            (Isa::Ppc, 10),
+            (Isa::Ppc64, 11),
+            (Isa::S390, 12),
+            (Isa::S390x, 13),
+            (Isa::Sparc, 14),
+            (Isa::Sparc64, 15),
+            (Isa::M68k, 16),
+            (Isa::ColdFire, 17),
+            (Isa::Sh, 18),
+            (Isa::Sh4, 19),
+            (Isa::Ia64, 20),
+            (Isa::Alpha, 21),
+            (Isa::Parisc, 22),
+            (Isa::LoongArch32, 23),
+            (Isa::LoongArch64, 24),
+            (Isa::Hexagon, 25),
+            (Isa::Arc, 26),
+            (Isa::ArcCompact, 27),
+            (Isa::ArcCompact2, 28),
+            (Isa::Xtensa, 29),
+            (Isa::MicroBlaze, 30),
+            (Isa::Nios2, 31),
+            (Isa::OpenRisc, 32),
+            (Isa::Lanai, 33),
+            (Isa::Csky, 34),
+            (Isa::V850, 35),
+            (Isa::Rh850, 36),
+            (Isa::K78k0r, 37),
+            (Isa::Rx, 38),
+            (Isa::TiC6000, 39),
+            (Isa::TiC2000, 40),
+            (Isa::TiC28x, 41),
+            (Isa::TiC5500, 42),
+            (Isa::TiPru, 43),
+            (Isa::Blackfin, 44),
+            (Isa::Sharc, 45),
+            (Isa::Tricore, 46),
+            (Isa::Hcs12, 47),
+            (Isa::S12z, 48),
+            (Isa::Hc11, 49),
+            (Isa::C166, 50),
+            (Isa::Rl78, 51),
+            (Isa::Avr, 52),
+            (Isa::Avr32, 53),
+            (Isa::Msp430, 54),
+            (Isa::Pic, 55),
+            (Isa::Pic24, 56),
+            (Isa::Stm8, 57),
+            (Isa::AmdGpu, 58),
+            (Isa::Cuda, 59),
+            (Isa::Bpf, 60),
+            (Isa::I860, 61),
+            (Isa::I960, 62),
+            (Isa::Vax, 63),
+            (Isa::Pdp11, 64),
+            (Isa::Z80, 65),
+            (Isa::Mcs6502, 66),
+            (Isa::W65816, 67),
+            (Isa::Elbrus, 68),
+            (Isa::Tile64, 69),
+            (Isa::TilePro, 70),
+            (Isa::TileGx, 71),
+            (Isa::VideoCore3, 72),
+            (Isa::VideoCore5, 73),
+            (Isa::Kvx, 74),
+            (Isa::McstElbrus, 75),
+            (Isa::Frv, 76),
+            (Isa::Fr30, 77),
+            (Isa::Fr80, 78),
+            (Isa::PpcVle, 79),
+            (Isa::Wasm, 80),
+            (Isa::Jvm, 81),
+            (Isa::Dalvik, 82),
+            (Isa::Clr, 83),
+            (Isa::Ebc, 84),
+            (Isa::CellSpu, 85),
+            (Isa::Dis, 86),
+        ];
+        for (isa, code) in pinned {
+            assert_eq!(isa.to_code(), code, "{isa:?}");
+            assert_eq!(Isa::from_code(code), Some(isa));
+        }
+        assert_eq!(Isa::Unknown(0x1234).to_code(), Isa::UNKNOWN_CODE);
+        assert_eq!(Isa::from_code(Isa::UNKNOWN_CODE), None);
+        assert_eq!(Isa::from_code(0), None);
+        assert_eq!(Isa::from_code(pinned.len() as u16 + 1), None);
+    }
+
+    #[test]
+    fn test_isa_is_deprecated() {
+        assert!(Isa::Ia64.is_deprecated());
+        assert!(Isa::Alpha.is_deprecated());
+        assert!(!Isa::AArch64.is_deprecated());
+        assert!(!Isa::Unknown(0).is_deprecated());
+    }
+
+    #[test]
+    fn test_isa_era() {
+        assert_eq!(Isa::Mcs6502.era(), "1970s");
+        assert_eq!(Isa::X86.era(), "1980s");
+        assert_eq!(Isa::X86_64.era(), "2000s");
+        assert_eq!(Isa::RiscV64.era(), "2010s");
+        assert_eq!(Isa::LoongArch64.era(), "2020s");
+        assert!(Isa::Pdp11.era() < Isa::Wasm.era());
+    }
+
     #[test]
     fn test_extension_display() {
         let ext = Extension::new("AVX2", ExtensionCategory::Simd);
@@ -0,0 +1,197 @@
+//! Directory walking for file-based classification.
+//!
+//! [`classify_dir`] lazily walks a directory tree and classifies each regular
+//! file as it is reached, so callers can stream results without holding the
+//! whole listing in memory. Directory entries are visited in sorted order to
+//! keep output stable across runs. Symbolic links to files are classified;
+//! symbolic links to directories are not followed, which avoids cycles.
+//! FIFOs, sockets and device nodes are skipped without being opened, so a
+//! walk never blocks on a pipe or reads from a device.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::types::{ClassificationResult, ClassifierOptions};
+
+/// Iterator returned by [`classify_dir`].
+#[derive(Debug)]
+pub struct ClassifyDir {
+    options: ClassifierOptions,
+    recursive: bool,
+    /// Root that has not been listed yet.
+    root: Option<PathBuf>,
+    /// Pending entries of each open directory, deepest last.
+    stack: Vec<std::vec::IntoIter<PathBuf>>,
+}
+
+/// Walk `root` and classify every file found.
+///
+/// With `recursive` false only the files directly inside `root` are
+/// classified. Only regular files (and symbolic links to them) are yielded;
+/// directories and special files are not. A directory or file that cannot
+/// be read is yielded with its IO error rather than aborting the walk.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use isa_classifier::{classify_dir, ClassifierOptions};
+///
+/// for (path, result) in classify_dir("firmware/", &ClassifierOptions::new(), true) {
+///     match result {
+///         Ok(r) => println!("{}: {}", path.display(), r.isa.name()),
+///         Err(e) => eprintln!("{}: {}", path.display(), e),
+///     }
+/// }
+/// ```
+pub fn classify_dir<P: AsRef<Path>>(
+    root: P,
+    options: &ClassifierOptions,
+    recursive: bool,
+) -> ClassifyDir {
+    ClassifyDir {
+        options: options.clone(),
+        recursive,
+        root: Some(root.as_ref().to_path_buf()),
+        stack: Vec::new(),
+    }
+}
+
+impl ClassifyDir {
+    /// List `dir` and push its entries; errors are returned for the caller
+    /// to yield.
+    fn open(&mut self, dir: &Path) -> Result<()> {
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        self.stack.push(entries.into_iter());
+        Ok(())
+    }
+
+    fn classify(&self, path: &Path) -> Result<ClassificationResult> {
+        let data = fs::read(path)?;
+        let options = ClassifierOptions {
+            input_len: Some(data.len() as u64),
+            ..self.options.clone()
+        };
+        crate::classify_bytes_with_options(&data, &options)
+    }
+}
+
+impl Iterator for ClassifyDir {
+    type Item = (PathBuf, Result<ClassificationResult>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            if let Err(e) = self.open(&root) {
+                return Some((root, Err(e)));
+            }
+        }
+
+        loop {
+            let Some(path) = self.stack.last_mut()?.next() else {
+                self.stack.pop();
+                continue;
+            };
+
+            let file_type = match fs::symlink_metadata(&path) {
+                Ok(meta) => meta.file_type(),
+                Err(e) => return Some((path, Err(e.into()))),
+            };
+
+            if file_type.is_dir() {
+                if self.recursive {
+                    if let Err(e) = self.open(&path) {
+                        return Some((path, Err(e)));
+                    }
+                }
+                continue;
+            }
+
+            // Follow links to files, but never into directories.
+            if file_type.is_symlink() {
+                match fs::metadata(&path) {
+                    Ok(meta) if meta.is_file() => {}
+                    Ok(_) => continue,
+                    Err(e) => return Some((path, Err(e.into()))),
+                }
+            } else if !file_type.is_file() {
+                continue;
+            }
+
+            let result = self.classify(&path);
+            return Some((path, result));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Isa;
+
+    fn elf_header(e_machine: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+        data[4] = 2;
+        data[5] = 1;
+        data[6] = 1;
+        data[0x12..0x14].copy_from_slice(&e_machine.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_classify_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.elf"), elf_header(0x3E)).unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub").join("b.elf"), elf_header(0xB7)).unwrap();
+
+        let opts = ClassifierOptions::new();
+        let flat: Vec<_> = classify_dir(dir.path(), &opts, false).collect();
+        assert_eq!(flat.len(), 1);
+        assert_eq!(flat[0].0, dir.path().join("a.elf"));
+        assert_eq!(flat[0].1.as_ref().unwrap().isa, Isa::X86_64);
+
+        let all: Vec<_> = classify_dir(dir.path(), &opts, true)
+            .map(|(p, r)| (p, r.unwrap().isa))
+            .collect();
+        assert_eq!(
+            all,
+            vec![
+                (dir.path().join("a.elf"), Isa::X86_64),
+                (dir.path().join("sub").join("b.elf"), Isa::AArch64),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_classify_dir_skips_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.elf"), elf_header(0x3E)).unwrap();
+        let fifo = dir.path().join("pipe");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::os::unix::fs::symlink(&fifo, dir.path().join("pipe-link")).unwrap();
+
+        // Opening either would block forever with no writer
+        let results: Vec<_> = classify_dir(dir.path(), &ClassifierOptions::new(), true).collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, dir.path().join("a.elf"));
+    }
+
+    #[test]
+    fn test_classify_dir_missing_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("nope");
+        let results: Vec<_> = classify_dir(&missing, &ClassifierOptions::new(), true).collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, missing);
+        assert!(results[0].1.is_err());
+    }
+}
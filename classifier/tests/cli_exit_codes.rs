@@ -0,0 +1,161 @@
+//! Exit codes of the `--fail-*` gatekeeping flags, driven through the
+//! compiled `isa-classify` binary.
+
+#![cfg(feature = "cli")]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Minimal 64-bit little-endian ELF executable header for `e_machine`.
+fn elf_header(e_machine: u16) -> Vec<u8> {
+    let mut data = vec![0u8; 64];
+    data[0..4].copy_from_slice(&[0x7F, b'E', b'L', b'F']);
+    data[4] = 2; // ELFCLASS64
+    data[5] = 1; // ELFDATA2LSB
+    data[6] = 1; // EV_CURRENT
+    data[0x10..0x12].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    data[0x12..0x14].copy_from_slice(&e_machine.to_le_bytes());
+    data[0x14..0x18].copy_from_slice(&1u32.to_le_bytes());
+    data
+}
+
+/// Headerless x86-64 code that classifies with well under full confidence.
+fn raw_x86_64() -> Vec<u8> {
+    [
+        0x55, 0x48, 0x89, 0xE5, // push rbp; mov rbp, rsp
+        0x48, 0x83, 0xEC, 0x10, // sub rsp, 16
+        0x89, 0x7D, 0xFC, // mov [rbp-4], edi
+        0x8B, 0x45, 0xFC, // mov eax, [rbp-4]
+        0x01, 0xC0, // add eax, eax
+        0xC9, 0xC3, // leave; ret
+        0x90, 0x90,
+    ]
+    .repeat(40)
+}
+
+struct Fixtures {
+    _dir: tempfile::TempDir,
+    x86_64: PathBuf,
+    unknown: PathBuf,
+    em_none: PathBuf,
+    raw: PathBuf,
+}
+
+fn fixtures() -> Fixtures {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, data: Vec<u8>| {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        path
+    };
+    Fixtures {
+        x86_64: write("x86_64.elf", elf_header(0x3E)),
+        unknown: write("unknown.elf", elf_header(0x7777)),
+        // Produces an info note: "ELF declares no machine (EM_NONE)"
+        em_none: write("em_none.elf", elf_header(0)),
+        raw: write("code.bin", raw_x86_64()),
+        _dir: dir,
+    }
+}
+
+fn run(args: &[&str], files: &[&Path]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_isa-classify"))
+        .args(args)
+        .args(files)
+        .output()
+        .unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_no_policy_flags_exit_zero() {
+    let f = fixtures();
+    let output = run(&[], &[&f.x86_64, &f.unknown, &f.raw]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn test_fail_below_confidence_exits_6() {
+    let f = fixtures();
+    let output = run(&["--fail-below-confidence", "0.99"], &[&f.x86_64, &f.raw]);
+    assert_eq!(output.status.code(), Some(6));
+    let err = stderr(&output);
+    assert!(
+        err.contains("code.bin: confidence below threshold"),
+        "{err}"
+    );
+    assert!(!err.contains("x86_64.elf"), "{err}");
+
+    let output = run(&["--fail-below-confidence", "0.1"], &[&f.raw]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn test_fail_on_unknown_exits_7() {
+    let f = fixtures();
+    let output = run(&["--fail-on-unknown"], &[&f.x86_64, &f.unknown]);
+    assert_eq!(output.status.code(), Some(7));
+    assert!(stderr(&output).contains("unknown.elf: unknown ISA"));
+
+    let output = run(&["--fail-on-unknown"], &[&f.x86_64]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn test_fail_on_notes_exits_8() {
+    let f = fixtures();
+    let output = run(&["--fail-on-notes", "info"], &[&f.em_none]);
+    assert_eq!(output.status.code(), Some(8));
+    assert!(stderr(&output).contains("em_none.elf: notes at or above requested level"));
+
+    // The EM_NONE note is informational only
+    let output = run(&["--fail-on-notes", "warning"], &[&f.em_none]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn test_worst_failure_wins() {
+    let f = fixtures();
+    let output = run(
+        &[
+            "--fail-below-confidence",
+            "0.99",
+            "--fail-on-unknown",
+            "--fail-on-notes",
+            "info",
+        ],
+        &[&f.raw, &f.unknown, &f.em_none],
+    );
+    assert_eq!(output.status.code(), Some(8));
+    let err = stderr(&output);
+    // Every offender is listed, not only the one behind the exit code
+    assert!(
+        err.contains("code.bin: confidence below threshold"),
+        "{err}"
+    );
+    assert!(err.contains("unknown.elf: unknown ISA"), "{err}");
+    assert!(
+        err.contains("em_none.elf: notes at or above requested level"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_policy_applies_to_ndjson() {
+    let f = fixtures();
+    let output = run(&["--format", "ndjson", "--fail-on-unknown"], &[&f.unknown]);
+    assert_eq!(output.status.code(), Some(7));
+}
+
+#[test]
+fn test_invalid_policy_arguments_rejected() {
+    let f = fixtures();
+    let output = run(&["--fail-below-confidence", "1.5"], &[&f.x86_64]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = run(&["--multi-isa", "--fail-on-unknown"], &[&f.raw]);
+    assert_eq!(output.status.code(), Some(2));
+}